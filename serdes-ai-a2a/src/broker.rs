@@ -61,6 +61,11 @@ pub trait Broker: Send + Sync {
     /// Returns `None` if the task doesn't exist or has no token.
     async fn get_cancellation_token(&self, task_id: &TaskId) -> Option<CancellationToken>;
 
+    /// Release per-task state once a worker is done with a task.
+    ///
+    /// Called by the worker after every task, whatever its outcome.
+    async fn release_task(&self, _task_id: &TaskId) {}
+
     /// Get the number of pending tasks.
     async fn pending_count(&self) -> usize;
 
@@ -226,6 +231,10 @@ impl Broker for InMemoryBroker {
         tokens.get(task_id).cloned()
     }
 
+    async fn release_task(&self, task_id: &TaskId) {
+        self.cleanup_token(task_id).await;
+    }
+
     async fn pending_count(&self) -> usize {
         self.queue.lock().await.len()
     }
@@ -0,0 +1,175 @@
+//! Expiry of finished tasks.
+//!
+//! Finished tasks stay in storage so clients can fetch their results, but
+//! without cleanup they accumulate forever. The [`TaskJanitor`] periodically
+//! deletes completed, failed and cancelled tasks whose last update is older
+//! than a TTL, using [`Storage::delete_expired`] so it works with any backend.
+//!
+//! ```rust,ignore
+//! use serdes_ai_a2a::TaskJanitor;
+//! use std::time::Duration;
+//!
+//! let janitor = TaskJanitor::new(storage, Duration::from_secs(3600)).spawn();
+//! // ...
+//! janitor.shutdown().await;
+//! ```
+
+use crate::storage::{Storage, StorageError};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Handle for controlling a running janitor.
+#[derive(Debug)]
+pub struct JanitorHandle {
+    handle: JoinHandle<()>,
+    shutdown_tx: mpsc::Sender<()>,
+}
+
+impl JanitorHandle {
+    /// Check if the janitor is still running.
+    pub fn is_running(&self) -> bool {
+        !self.handle.is_finished()
+    }
+
+    /// Signal the janitor to stop.
+    pub async fn stop(&self) {
+        let _ = self.shutdown_tx.send(()).await;
+    }
+
+    /// Stop the janitor and wait for it to finish.
+    pub async fn shutdown(self) {
+        self.stop().await;
+        let _ = self.handle.await;
+    }
+}
+
+/// Deletes finished tasks once they are older than a TTL.
+pub struct TaskJanitor {
+    storage: Arc<dyn Storage>,
+    ttl: Duration,
+    interval: Duration,
+}
+
+impl TaskJanitor {
+    /// Create a janitor that expires finished tasks `ttl` after their last
+    /// update.
+    ///
+    /// By default it sweeps every `ttl / 10`, but at most once a second.
+    pub fn new(storage: Arc<dyn Storage>, ttl: Duration) -> Self {
+        Self {
+            storage,
+            ttl,
+            interval: (ttl / 10).max(Duration::from_secs(1)),
+        }
+    }
+
+    /// Set how often to sweep for expired tasks.
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Get the TTL of finished tasks.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Delete expired tasks once, returning how many were deleted.
+    pub async fn run_once(&self) -> Result<usize, StorageError> {
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or(chrono::Duration::MAX);
+        let before = Utc::now()
+            .checked_sub_signed(ttl)
+            .unwrap_or(chrono::DateTime::<Utc>::MIN_UTC);
+        self.storage.delete_expired(before).await
+    }
+
+    /// Start sweeping in a background task.
+    pub fn spawn(self) -> JanitorHandle {
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = interval.tick() => {
+                        // A failed sweep is retried on the next tick.
+                        let _ = self.run_once().await;
+                    }
+                }
+            }
+        });
+
+        JanitorHandle {
+            handle,
+            shutdown_tx,
+        }
+    }
+}
+
+impl std::fmt::Debug for TaskJanitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskJanitor")
+            .field("ttl", &self.ttl)
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Message;
+    use crate::task::Task;
+    use crate::InMemoryStorage;
+
+    async fn finished_task(storage: &InMemoryStorage) -> Task {
+        let mut task = Task::new("thread-1", Message::user("Hi"));
+        task.start().unwrap();
+        task.complete().unwrap();
+        storage.save_task(&task).await.unwrap();
+        task
+    }
+
+    #[tokio::test]
+    async fn test_run_once_respects_ttl() {
+        let storage = Arc::new(InMemoryStorage::new());
+        let task = finished_task(&storage).await;
+
+        let janitor = TaskJanitor::new(storage.clone(), Duration::from_secs(3600));
+        assert_eq!(janitor.run_once().await.unwrap(), 0);
+        assert!(storage.get_task(&task.id).await.unwrap().is_some());
+
+        let janitor = TaskJanitor::new(storage.clone(), Duration::ZERO);
+        assert_eq!(janitor.run_once().await.unwrap(), 1);
+        assert!(storage.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_janitor_sweeps() {
+        let storage = Arc::new(InMemoryStorage::new());
+        finished_task(&storage).await;
+        let running = Task::new("thread-1", Message::user("Still going"));
+        storage.save_task(&running).await.unwrap();
+
+        let handle = TaskJanitor::new(storage.clone(), Duration::ZERO)
+            .with_interval(Duration::from_millis(5))
+            .spawn();
+        assert!(handle.is_running());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while storage.len().await > 1 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        handle.shutdown().await;
+
+        assert!(storage.get_task(&running.id).await.unwrap().is_some());
+    }
+}
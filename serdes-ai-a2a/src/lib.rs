@@ -7,9 +7,14 @@
 //!
 //! ## Features
 //!
-//! - Agent cards for capability discovery
-//! - Task submission and tracking
+//! - Agent cards for capability discovery, with skills generated from the
+//!   agent's tools and output
+//! - Task submission and tracking, with cancellation of running tasks and
+//!   expiry of finished ones
 //! - Streaming results
+//! - Push notifications to client webhooks when tasks finish
+//! - W3C trace context propagation from callers into task runs (`otel`
+//!   feature)
 //! - Storage and broker abstractions
 //!
 //! ## Example
@@ -33,7 +38,10 @@
 //! ```
 
 pub mod broker;
+pub mod janitor;
+pub mod push;
 pub mod schema;
+pub mod skills;
 pub mod storage;
 pub mod task;
 pub mod worker;
@@ -42,7 +50,12 @@ pub mod worker;
 pub mod server;
 
 pub use broker::{Broker, BrokerError, InMemoryBroker};
+pub use janitor::{JanitorHandle, TaskJanitor};
+#[cfg(feature = "push")]
+pub use push::{sign_payload, verify_signature, WebhookNotifier};
+pub use push::{PushError, PushNotification, PushNotifier, SIGNATURE_HEADER, TOKEN_HEADER};
 pub use schema::*;
+pub use skills::{agent_skills, SkillOverrideFn, DEFAULT_AGENT_SKILL_ID};
 pub use storage::{InMemoryStorage, Storage, StorageError};
 pub use task::{Task, TaskError, TaskResult, TaskStatus};
 pub use worker::{AgentWorker, WorkerHandle};
@@ -93,6 +106,7 @@ pub struct A2AServer<Deps, Output> {
     config: A2AConfig,
     storage: Arc<dyn Storage>,
     broker: Arc<dyn Broker>,
+    skill_override: Option<Arc<SkillOverrideFn>>,
 }
 
 impl<Deps, Output> A2AServer<Deps, Output>
@@ -107,6 +121,7 @@ where
             config,
             storage: Arc::new(InMemoryStorage::new()),
             broker: Arc::new(InMemoryBroker::new()),
+            skill_override: None,
         }
     }
 
@@ -122,9 +137,36 @@ where
         self
     }
 
+    /// Adjust or drop skills generated from the agent.
+    ///
+    /// The hook sees each generated skill before it is added to the agent
+    /// card; returning `None` leaves it out.
+    pub fn with_skill_override<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Skill) -> Option<Skill> + Send + Sync + 'static,
+    {
+        self.skill_override = Some(Arc::new(f));
+        self
+    }
+
     /// Get the agent card describing this agent's capabilities.
+    ///
+    /// Unless [`A2AConfig::auto_skills`] is off, the card lists skills
+    /// generated from the agent's tools and output after the configured ones.
     pub fn agent_card(&self) -> AgentCard {
-        self.config.to_agent_card()
+        let mut card = self.config.to_agent_card();
+        if self.config.auto_skills {
+            let generated = agent_skills(&self.agent)
+                .into_iter()
+                .filter_map(|skill| match &self.skill_override {
+                    Some(f) => f(skill),
+                    None => Some(skill),
+                })
+                .filter(|skill| !card.skills.iter().any(|s| s.id == skill.id))
+                .collect::<Vec<_>>();
+            card.skills.extend(generated);
+        }
+        card
     }
 
     /// Get a reference to the underlying agent.
@@ -204,4 +246,64 @@ mod tests {
         assert_eq!(card.skills.len(), 1);
         assert_eq!(card.skills[0].id, "chat");
     }
+
+    struct EchoTool;
+
+    #[async_trait::async_trait]
+    impl serdes_ai_agent::ToolExecutor<()> for EchoTool {
+        async fn execute(
+            &self,
+            args: serde_json::Value,
+            _ctx: &serdes_ai_agent::RunContext<()>,
+        ) -> Result<serdes_ai_tools::ToolReturn, serdes_ai_tools::ToolError> {
+            Ok(serdes_ai_tools::ToolReturn::json(args))
+        }
+    }
+
+    #[test]
+    fn test_agent_card_generated_skills() {
+        let forecast =
+            serdes_ai_tools::ToolDefinition::new("get_forecast", "Get the weather forecast")
+                .with_parameters(serde_json::json!({
+                    "type": "object",
+                    "properties": {"city": {"type": "string"}},
+                    "required": ["city"]
+                }));
+        let debug = serdes_ai_tools::ToolDefinition::new("internal_debug", "Not for other agents");
+        let agent = serdes_ai_agent::agent(serdes_ai_models::MockModel::new("mock"))
+            .name("weather")
+            .tool_with_executor(forecast, EchoTool)
+            .tool_with_executor(debug, EchoTool)
+            .build();
+        let config = A2AConfig::new().skill(Skill {
+            id: "weather".to_string(),
+            name: "Weather".to_string(),
+            description: Some("Hand-written".to_string()),
+            tags: Vec::new(),
+        });
+        let server = agent_to_a2a(agent, config)
+            .with_skill_override(|skill| (skill.id != "internal_debug").then_some(skill));
+
+        let card = server.agent_card();
+        let ids: Vec<_> = card.skills.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["weather", "get_forecast"]);
+        assert_eq!(card.skills[0].description.as_deref(), Some("Hand-written"));
+        assert_eq!(card.skills[1].name, "Get forecast");
+        assert_eq!(
+            card.skills[1].description.as_deref(),
+            Some("Get the weather forecast\n\nParameters: city (string, required)")
+        );
+
+        let agent = serdes_ai_agent::agent(serdes_ai_models::MockModel::new("mock")).build();
+        let skills = agent_skills(&agent);
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].id, DEFAULT_AGENT_SKILL_ID);
+        assert_eq!(
+            skills[0].description.as_deref(),
+            Some("Responds with text.")
+        );
+
+        let server = agent_to_a2a(agent, A2AConfig::new().auto_skills(false));
+        assert!(server.agent_card().skills.is_empty());
+    }
 }
@@ -0,0 +1,347 @@
+//! Push notifications for finished tasks.
+//!
+//! Clients that can't poll or hold a streaming connection register a webhook
+//! ([`PushNotificationConfig`]) with a task, either when sending it or later
+//! through the `pushNotification` endpoint. When the task completes, fails or
+//! is cancelled, the [`AgentWorker`](crate::AgentWorker) POSTs a
+//! [`PushNotification`] to it through a [`PushNotifier`].
+//!
+//! [`WebhookNotifier`] (feature `push`) signs each body with HMAC-SHA256 and
+//! retries failed deliveries with exponential backoff. Receivers check the
+//! signature with [`verify_signature`]:
+//!
+//! ```rust,ignore
+//! use serdes_ai_a2a::{verify_signature, SIGNATURE_HEADER};
+//!
+//! let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+//! if !signature.is_some_and(|s| verify_signature(secret, &body, s)) {
+//!     return StatusCode::UNAUTHORIZED;
+//! }
+//! ```
+
+use crate::schema::{Artifact, Message, PushNotificationConfig};
+use crate::task::{Task, TaskId, TaskStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Header carrying the HMAC-SHA256 signature of the body (`sha256=<hex>`).
+pub const SIGNATURE_HEADER: &str = "X-A2A-Signature";
+
+/// Header carrying the token from the [`PushNotificationConfig`].
+pub const TOKEN_HEADER: &str = "X-A2A-Notification-Token";
+
+/// Errors delivering push notifications.
+#[derive(Debug, Error)]
+pub enum PushError {
+    /// The webhook URL is not an HTTP(S) URL.
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+    /// The notification could not be serialized.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// The webhook did not accept the notification.
+    #[error("Webhook delivery failed after {attempts} attempts: {message}")]
+    Delivery {
+        /// Number of attempts made.
+        attempts: u32,
+        /// Error of the last attempt.
+        message: String,
+    },
+}
+
+/// Notification sent to a task's webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushNotification {
+    /// The task this is about.
+    pub task_id: TaskId,
+    /// Thread the task belongs to.
+    pub thread_id: String,
+    /// Status of the task.
+    pub status: TaskStatus,
+    /// Messages of the task, including the agent's reply.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Artifacts produced by the task.
+    #[serde(default)]
+    pub artifacts: Vec<Artifact>,
+    /// Error message if the task failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// When the notification was created.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PushNotification {
+    /// Create a notification describing the current state of a task.
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            task_id: task.id.clone(),
+            thread_id: task.thread_id.clone(),
+            status: task.status,
+            messages: task.messages.clone(),
+            artifacts: task.artifacts.clone(),
+            error: task.error.clone(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Delivers push notifications to webhooks.
+#[async_trait]
+pub trait PushNotifier: Send + Sync {
+    /// Deliver a notification to the webhook in `config`.
+    async fn notify(
+        &self,
+        config: &PushNotificationConfig,
+        notification: &PushNotification,
+    ) -> Result<(), PushError>;
+}
+
+#[cfg(feature = "push")]
+pub use webhook::{sign_payload, verify_signature, WebhookNotifier};
+
+#[cfg(feature = "push")]
+mod webhook {
+    use super::*;
+    use ring::hmac;
+    use std::time::Duration;
+
+    /// Sign a body, returning the value of the [`SIGNATURE_HEADER`].
+    pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+        let tag = hmac::sign(&key, body);
+        let hex: String = tag.as_ref().iter().map(|b| format!("{b:02x}")).collect();
+        format!("sha256={hex}")
+    }
+
+    /// Check the [`SIGNATURE_HEADER`] of a received notification.
+    pub fn verify_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+        let Some(hex) = signature.strip_prefix("sha256=") else {
+            return false;
+        };
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return false;
+        }
+        let Ok(tag) = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+        else {
+            return false;
+        };
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+        hmac::verify(&key, body, &tag).is_ok()
+    }
+
+    /// Delivers notifications by POSTing signed JSON to webhooks.
+    ///
+    /// Connection errors, timeouts, `408`, `429` and `5xx` responses are
+    /// retried with exponential backoff; other error responses are not.
+    #[derive(Clone)]
+    pub struct WebhookNotifier {
+        client: reqwest::Client,
+        secret: Option<Vec<u8>>,
+        max_retries: u32,
+        backoff: Duration,
+        timeout: Duration,
+    }
+
+    impl WebhookNotifier {
+        /// Create a notifier that sends unsigned notifications.
+        pub fn new() -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                secret: None,
+                max_retries: 3,
+                backoff: Duration::from_millis(500),
+                timeout: Duration::from_secs(10),
+            }
+        }
+
+        /// Sign notifications with the given secret.
+        pub fn with_signing_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+            self.secret = Some(secret.as_ref().to_vec());
+            self
+        }
+
+        /// Set how often a failed delivery is retried (default: 3).
+        pub fn with_max_retries(mut self, retries: u32) -> Self {
+            self.max_retries = retries;
+            self
+        }
+
+        /// Set the delay before the first retry; it doubles with each retry
+        /// (default: 500ms).
+        pub fn with_backoff(mut self, backoff: Duration) -> Self {
+            self.backoff = backoff;
+            self
+        }
+
+        /// Set the timeout of each delivery attempt (default: 10s).
+        pub fn with_timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = timeout;
+            self
+        }
+
+        async fn send(
+            &self,
+            config: &PushNotificationConfig,
+            body: &[u8],
+        ) -> Result<(), (bool, String)> {
+            let mut request = self
+                .client
+                .post(&config.url)
+                .header("Content-Type", "application/json")
+                .timeout(self.timeout)
+                .body(body.to_vec());
+            if let Some(secret) = &self.secret {
+                request = request.header(SIGNATURE_HEADER, sign_payload(secret, body));
+            }
+            if let Some(token) = &config.token {
+                request = request.header(TOKEN_HEADER, token);
+            }
+
+            let response = request.send().await.map_err(|e| (true, e.to_string()))?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(());
+            }
+            let retryable = status.is_server_error()
+                || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+            Err((retryable, format!("webhook returned {status}")))
+        }
+    }
+
+    impl Default for WebhookNotifier {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl std::fmt::Debug for WebhookNotifier {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("WebhookNotifier")
+                .field("signed", &self.secret.is_some())
+                .field("max_retries", &self.max_retries)
+                .field("backoff", &self.backoff)
+                .field("timeout", &self.timeout)
+                .finish()
+        }
+    }
+
+    #[async_trait]
+    impl PushNotifier for WebhookNotifier {
+        async fn notify(
+            &self,
+            config: &PushNotificationConfig,
+            notification: &PushNotification,
+        ) -> Result<(), PushError> {
+            if !config.is_valid() {
+                return Err(PushError::InvalidUrl(config.url.clone()));
+            }
+            let body = serde_json::to_vec(notification)?;
+
+            let mut attempts = 0;
+            loop {
+                attempts += 1;
+                match self.send(config, &body).await {
+                    Ok(()) => return Ok(()),
+                    Err((retryable, message)) => {
+                        if !retryable || attempts > self.max_retries {
+                            return Err(PushError::Delivery { attempts, message });
+                        }
+                    }
+                }
+                tokio::time::sleep(self.backoff * 2u32.saturating_pow(attempts - 1)).await;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "push"))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use wiremock::matchers::{header, header_exists, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn notification() -> PushNotification {
+        let mut task = Task::new("thread-1", Message::user("Hi"));
+        task.start().unwrap();
+        task.add_message(Message::agent("Hello!"));
+        task.complete().unwrap();
+        PushNotification::from_task(&task)
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign_payload(b"secret", b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature(b"secret", b"{}", &signature));
+        assert!(!verify_signature(b"other", b"{}", &signature));
+        assert!(!verify_signature(b"secret", b"{ }", &signature));
+        assert!(!verify_signature(b"secret", b"{}", "sha256=zz"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_signed_delivery() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(TOKEN_HEADER, "client-token"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = PushNotificationConfig::new(server.uri()).with_token("client-token");
+        WebhookNotifier::new()
+            .with_signing_secret("secret")
+            .notify(&config, &notification())
+            .await
+            .unwrap();
+
+        let request = &server.received_requests().await.unwrap()[0];
+        let signature = request.headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify_signature(b"secret", &request.body, signature));
+        let sent: PushNotification = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(sent.status, TaskStatus::Completed);
+        assert_eq!(sent.messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let notifier = WebhookNotifier::new().with_backoff(Duration::from_millis(1));
+        let config = PushNotificationConfig::new(server.uri());
+        notifier.notify(&config, &notification()).await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+        // Client errors are not retried
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let config = PushNotificationConfig::new(server.uri());
+        let err = notifier.notify(&config, &notification()).await.unwrap_err();
+        assert!(matches!(err, PushError::Delivery { attempts: 1, .. }));
+
+        let config = PushNotificationConfig::new("ftp://example.com");
+        let err = notifier.notify(&config, &notification()).await.unwrap_err();
+        assert!(matches!(err, PushError::InvalidUrl(_)));
+    }
+}
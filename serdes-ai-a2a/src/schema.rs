@@ -60,6 +60,52 @@ pub struct TaskSendParams {
     pub thread_id: String,
     /// The message to send.
     pub message: Message,
+    /// Webhook to notify when the task finishes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_notification: Option<PushNotificationConfig>,
+}
+
+/// Webhook a client registers to be notified about a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushNotificationConfig {
+    /// URL the notification is POSTed to.
+    pub url: String,
+    /// Token sent back with each notification, so the client can check
+    /// that it comes from the task it registered for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl PushNotificationConfig {
+    /// Create a config for the given webhook URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            token: None,
+        }
+    }
+
+    /// Set the token sent back with each notification.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Check that the URL is an absolute HTTP(S) URL.
+    pub fn is_valid(&self) -> bool {
+        ["http://", "https://"]
+            .iter()
+            .any(|scheme| self.url.len() > scheme.len() && self.url.starts_with(scheme))
+    }
+}
+
+/// Push notification config of a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPushNotificationConfig {
+    /// The task the config belongs to.
+    pub task_id: String,
+    /// The webhook config.
+    pub push_notification_config: PushNotificationConfig,
 }
 
 /// Task ID parameters for querying task status.
@@ -228,6 +274,8 @@ pub struct A2AConfig {
     pub provider: Option<AgentProvider>,
     /// Skills this agent provides.
     pub skills: Vec<Skill>,
+    /// Whether to generate skills from the agent's tools and output.
+    pub auto_skills: bool,
 }
 
 impl Default for A2AConfig {
@@ -246,6 +294,7 @@ impl A2AConfig {
             description: None,
             provider: None,
             skills: Vec::new(),
+            auto_skills: true,
         }
     }
 
@@ -285,7 +334,20 @@ impl A2AConfig {
         self
     }
 
+    /// Set whether to generate skills from the agent (default: true).
+    ///
+    /// Skills added with [`skill`](Self::skill) are always included and
+    /// replace generated skills with the same id.
+    pub fn auto_skills(mut self, enabled: bool) -> Self {
+        self.auto_skills = enabled;
+        self
+    }
+
     /// Convert to an agent card.
+    ///
+    /// Only includes the configured skills; use
+    /// [`A2AServer::agent_card`](crate::A2AServer::agent_card) to include
+    /// skills generated from the agent.
     pub fn to_agent_card(&self) -> AgentCard {
         AgentCard {
             name: self.name.clone(),
@@ -2,20 +2,28 @@
 //!
 //! This module provides an Axum-based HTTP server for exposing A2A endpoints.
 //! It is only available when the `server` feature is enabled.
+//!
+//! A W3C `traceparent` header on a task submission is stored on the task.
+//! With the `otel` feature enabled, each request and each task run is traced
+//! in a span whose parent is the caller's, so the agent's model requests
+//! join the caller's trace.
 
 use crate::broker::Broker;
-use crate::schema::{AgentCard, Message, TaskSendParams};
+use crate::schema::{
+    AgentCard, Message, PushNotificationConfig, TaskPushNotificationConfig, TaskSendParams,
+};
 use crate::storage::Storage;
 use crate::task::{Task, TaskStatus};
 use crate::A2AServer;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use serdes_ai_core::TraceContext;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
@@ -81,14 +89,23 @@ where
             broker: self.broker_arc(),
         });
 
-        Router::new()
+        let router = Router::new()
             .route("/.well-known/agent.json", get(get_agent_card))
             .route("/agent/card", get(get_agent_card))
             .route("/tasks/send", post(submit_task))
             .route("/tasks/:task_id", get(get_task_status))
             .route("/tasks/:task_id/cancel", post(cancel_task))
+            .route(
+                "/tasks/:task_id/pushNotification",
+                get(get_push_notification).post(set_push_notification),
+            )
             .route("/health", get(health_check))
-            .with_state(state)
+            .with_state(state);
+
+        #[cfg(feature = "otel")]
+        let router = router.layer(axum::middleware::from_fn(trace_request));
+
+        router
     }
 
     /// Start serving on the given address.
@@ -119,6 +136,30 @@ pub enum ServerError {
     Serve(String),
 }
 
+/// Parse the caller's trace context from request headers.
+fn trace_context(headers: &HeaderMap) -> Option<TraceContext> {
+    TraceContext::from_headers(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+}
+
+/// Run a request in a span that continues the caller's trace.
+#[cfg(feature = "otel")]
+async fn trace_request(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use tracing::Instrument;
+
+    let span = tracing::info_span!(
+        "a2a.request",
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    if let Some(cx) = trace_context(request.headers()) {
+        cx.set_parent(&span);
+    }
+    next.run(request).instrument(span).await
+}
+
 // Handler implementations
 
 /// GET /.well-known/agent.json - Get agent card
@@ -129,10 +170,19 @@ async fn get_agent_card(State(state): State<Arc<A2AState>>) -> Json<AgentCard> {
 /// POST /tasks/send - Submit a new task
 async fn submit_task(
     State(state): State<Arc<A2AState>>,
+    headers: HeaderMap,
     Json(params): Json<TaskSendParams>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    if let Some(config) = &params.push_notification {
+        if !config.is_valid() {
+            return Err(invalid_webhook(config));
+        }
+    }
+
     // Create a new task
-    let task = Task::new(params.thread_id, params.message);
+    let mut task = Task::new(params.thread_id, params.message);
+    task.push_notification = params.push_notification;
+    task.trace_context = trace_context(&headers);
     let task_id = task.id.clone();
     let status = task.status;
 
@@ -258,6 +308,89 @@ async fn cancel_task(
     }
 }
 
+/// GET /tasks/:task_id/pushNotification - Get a task's webhook
+async fn get_push_notification(
+    State(state): State<Arc<A2AState>>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskPushNotificationConfig>, (StatusCode, Json<ErrorResponse>)> {
+    let task = find_task(&state, &task_id).await?;
+    match task.push_notification {
+        Some(config) => Ok(Json(TaskPushNotificationConfig {
+            task_id: task.id,
+            push_notification_config: config,
+        })),
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::with_code(
+                format!("No push notification config for task: {}", task_id),
+                "not_found",
+            )),
+        )),
+    }
+}
+
+/// POST /tasks/:task_id/pushNotification - Set a task's webhook
+async fn set_push_notification(
+    State(state): State<Arc<A2AState>>,
+    Path(task_id): Path<String>,
+    Json(config): Json<PushNotificationConfig>,
+) -> Result<Json<TaskPushNotificationConfig>, (StatusCode, Json<ErrorResponse>)> {
+    if !config.is_valid() {
+        return Err(invalid_webhook(&config));
+    }
+
+    let mut task = find_task(&state, &task_id).await?;
+    task.push_notification = Some(config.clone());
+    if let Err(e) = state.storage.update_task(&task).await {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::with_code(
+                format!("Failed to save push notification config: {}", e),
+                "storage_error",
+            )),
+        ));
+    }
+
+    Ok(Json(TaskPushNotificationConfig {
+        task_id: task.id,
+        push_notification_config: config,
+    }))
+}
+
+/// Load a task, mapping a missing task to 404.
+async fn find_task(
+    state: &A2AState,
+    task_id: &str,
+) -> Result<Task, (StatusCode, Json<ErrorResponse>)> {
+    match state.storage.get_task(task_id).await {
+        Ok(Some(task)) => Ok(task),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::with_code(
+                format!("Task not found: {}", task_id),
+                "not_found",
+            )),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::with_code(
+                format!("Failed to get task: {}", e),
+                "storage_error",
+            )),
+        )),
+    }
+}
+
+fn invalid_webhook(config: &PushNotificationConfig) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::with_code(
+            format!("Invalid webhook URL: {}", config.url),
+            "invalid_webhook",
+        )),
+    )
+}
+
 /// GET /health - Health check endpoint
 async fn health_check() -> impl IntoResponse {
     Json(serde_json::json!({
@@ -283,4 +416,48 @@ mod tests {
         assert_eq!(error.error, "Not found");
         assert_eq!(error.code, Some("not_found".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_submit_task_keeps_trace_context() {
+        use crate::broker::InMemoryBroker;
+        use crate::storage::InMemoryStorage;
+
+        let state = Arc::new(A2AState {
+            agent_card: AgentCard {
+                name: "test".into(),
+                url: "http://localhost".into(),
+                version: "1.0".into(),
+                description: None,
+                provider: None,
+                skills: Vec::new(),
+            },
+            storage: Arc::new(InMemoryStorage::new()),
+            broker: Arc::new(InMemoryBroker::new()),
+        });
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut headers = HeaderMap::new();
+        headers.insert("traceparent", traceparent.parse().unwrap());
+        let params = TaskSendParams {
+            thread_id: "thread-1".into(),
+            message: Message::user("Hello"),
+            push_notification: None,
+        };
+
+        let response = submit_task(State(state.clone()), headers, Json(params))
+            .await
+            .unwrap()
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let submitted: TaskSubmitResponse = serde_json::from_slice(&body).unwrap();
+
+        let task = state
+            .storage
+            .get_task(&submitted.task_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(task.trace_context.unwrap().traceparent(), traceparent);
+    }
 }
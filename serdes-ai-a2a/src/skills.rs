@@ -0,0 +1,143 @@
+//! Agent card skills generated from an agent.
+//!
+//! [`agent_skills`] describes what an agent can do from its configuration:
+//! one skill for the agent itself, summarizing its output, and one per
+//! registered tool, summarizing its parameters. [`A2AServer`](crate::A2AServer)
+//! adds these to its agent card, so the card stays in sync with the agent.
+
+use crate::schema::Skill;
+use serde_json::Value as JsonValue;
+use serdes_ai_agent::{Agent, OutputMode};
+
+/// Hook to adjust or drop generated skills.
+///
+/// Returning `None` leaves the skill out of the agent card.
+pub type SkillOverrideFn = dyn Fn(Skill) -> Option<Skill> + Send + Sync;
+
+/// Id of the agent's own skill when the agent has no name.
+pub const DEFAULT_AGENT_SKILL_ID: &str = "agent";
+
+/// Generate skills describing an agent.
+///
+/// The first skill describes the agent as a whole and its output; the rest
+/// describe its tools, in registration order.
+pub fn agent_skills<Deps, Output>(agent: &Agent<Deps, Output>) -> Vec<Skill>
+where
+    Deps: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    let id = agent.name().unwrap_or(DEFAULT_AGENT_SKILL_ID);
+    let (output, tag) = match (agent.output_mode(), agent.output_json_schema()) {
+        (OutputMode::Text, _) => ("Responds with text.".to_string(), "text"),
+        (_, Some(schema)) => match summarize_schema(&schema) {
+            Some(fields) => (format!("Responds with JSON: {fields}."), "json"),
+            None => ("Responds with JSON.".to_string(), "json"),
+        },
+        (_, None) => ("Responds with JSON.".to_string(), "json"),
+    };
+
+    let mut skills = vec![Skill {
+        id: id.to_string(),
+        name: humanize(id),
+        description: Some(output),
+        tags: vec!["agent".to_string(), tag.to_string()],
+    }];
+
+    skills.extend(agent.tools().into_iter().map(|tool| {
+        let mut description = tool.description.clone();
+        if let Some(params) = summarize_schema(&tool.parameters_json_schema) {
+            if !description.is_empty() {
+                description.push_str("\n\n");
+            }
+            description.push_str("Parameters: ");
+            description.push_str(&params);
+        }
+        Skill {
+            id: tool.name.clone(),
+            name: humanize(&tool.name),
+            description: Some(description).filter(|d| !d.is_empty()),
+            tags: vec!["tool".to_string()],
+        }
+    }));
+
+    skills
+}
+
+/// Summarize the properties of an object schema, e.g.
+/// `city (string, required): City name; units (string)`.
+fn summarize_schema(schema: &JsonValue) -> Option<String> {
+    let properties = schema.get("properties")?.as_object()?;
+    if properties.is_empty() {
+        return None;
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(JsonValue::as_array)
+        .map(|r| r.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_default();
+
+    let fields: Vec<String> = properties
+        .iter()
+        .map(|(name, prop)| {
+            let ty = match prop.get("type") {
+                Some(JsonValue::String(ty)) => ty.clone(),
+                Some(JsonValue::Array(types)) => types
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                _ => "any".to_string(),
+            };
+            let mut field = if required.contains(&name.as_str()) {
+                format!("{name} ({ty}, required)")
+            } else {
+                format!("{name} ({ty})")
+            };
+            if let Some(desc) = prop.get("description").and_then(JsonValue::as_str) {
+                field.push_str(": ");
+                field.push_str(desc);
+            }
+            field
+        })
+        .collect();
+    Some(fields.join("; "))
+}
+
+/// Turn an identifier like `get_weather` into `Get weather`.
+fn humanize(id: &str) -> String {
+    let words = id.replace(['_', '-'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_schema() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "city": {"type": "string", "description": "City name"},
+                "days": {"type": ["integer", "null"]}
+            },
+            "required": ["city"]
+        });
+        assert_eq!(
+            summarize_schema(&schema).unwrap(),
+            "city (string, required): City name; days (integer | null)"
+        );
+        assert!(summarize_schema(&serde_json::json!({"type": "object"})).is_none());
+    }
+
+    #[test]
+    fn test_humanize() {
+        assert_eq!(humanize("get_weather"), "Get weather");
+        assert_eq!(humanize("search-docs"), "Search docs");
+        assert_eq!(humanize(""), "");
+    }
+}
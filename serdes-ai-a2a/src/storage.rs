@@ -4,6 +4,7 @@
 
 use crate::task::{Task, TaskId};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
@@ -52,6 +53,26 @@ pub trait Storage: Send + Sync {
 
     /// List all tasks (with optional limit).
     async fn list_tasks(&self, limit: Option<usize>) -> Result<Vec<Task>, StorageError>;
+
+    /// Delete finished tasks last updated before `before`.
+    ///
+    /// Pending and running tasks are never deleted. Returns the number of
+    /// deleted tasks. The default implementation lists and deletes tasks one
+    /// by one; backends that can filter on the server should override it.
+    async fn delete_expired(&self, before: DateTime<Utc>) -> Result<usize, StorageError> {
+        let mut deleted = 0;
+        for task in self.list_tasks(None).await? {
+            if task.is_completed() && task.updated_at < before {
+                match self.delete_task(&task.id).await {
+                    Ok(()) => deleted += 1,
+                    // Deleted concurrently
+                    Err(StorageError::NotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(deleted)
+    }
 }
 
 /// In-memory storage implementation.
@@ -143,6 +164,13 @@ impl Storage for InMemoryStorage {
 
         Ok(all_tasks)
     }
+
+    async fn delete_expired(&self, before: DateTime<Utc>) -> Result<usize, StorageError> {
+        let mut tasks = self.tasks.write().await;
+        let len = tasks.len();
+        tasks.retain(|_, t| !(t.is_completed() && t.updated_at < before));
+        Ok(len - tasks.len())
+    }
 }
 
 #[cfg(test)]
@@ -245,6 +273,27 @@ mod tests {
         assert_eq!(unlimited.len(), 10);
     }
 
+    #[tokio::test]
+    async fn test_delete_expired() {
+        let storage = InMemoryStorage::new();
+
+        let pending = Task::new("thread-1", Message::user("Pending"));
+        let mut done = Task::new("thread-1", Message::user("Done"));
+        done.start().unwrap();
+        done.complete().unwrap();
+        storage.save_task(&pending).await.unwrap();
+        storage.save_task(&done).await.unwrap();
+
+        // Not expired yet
+        let cutoff = done.updated_at;
+        assert_eq!(storage.delete_expired(cutoff).await.unwrap(), 0);
+
+        let cutoff = Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(storage.delete_expired(cutoff).await.unwrap(), 1);
+        assert!(storage.get_task(&done.id).await.unwrap().is_none());
+        assert!(storage.get_task(&pending.id).await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_clear_storage() {
         let storage = InMemoryStorage::new();
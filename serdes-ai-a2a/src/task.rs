@@ -2,9 +2,10 @@
 //!
 //! Tasks represent units of work submitted to an agent.
 
-use crate::schema::{Artifact, Message};
+use crate::schema::{Artifact, Message, PushNotificationConfig};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serdes_ai_core::TraceContext;
 use thiserror::Error;
 
 /// Errors related to task operations.
@@ -48,6 +49,12 @@ pub struct Task {
     /// Optional metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+    /// Webhook to notify when the task finishes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_notification: Option<PushNotificationConfig>,
+    /// Trace context of the request that submitted the task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>,
 }
 
 impl Task {
@@ -67,6 +74,8 @@ impl Task {
             updated_at: now,
             error: None,
             metadata: None,
+            push_notification: None,
+            trace_context: None,
         }
     }
 
@@ -86,9 +95,17 @@ impl Task {
             updated_at: now,
             error: None,
             metadata: None,
+            push_notification: None,
+            trace_context: None,
         }
     }
 
+    /// Set the webhook to notify when the task finishes.
+    pub fn with_push_notification(mut self, config: PushNotificationConfig) -> Self {
+        self.push_notification = Some(config);
+        self
+    }
+
     /// Check if the task is pending.
     pub fn is_pending(&self) -> bool {
         self.status == TaskStatus::Pending
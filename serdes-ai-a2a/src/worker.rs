@@ -1,12 +1,14 @@
 //! Worker for processing A2A tasks.
 //!
 //! The worker polls the broker for tasks and processes them using the agent.
+//! Tasks with a webhook get a push notification once they finish.
 
 use crate::broker::Broker;
+use crate::push::{PushNotification, PushNotifier};
 use crate::schema::Message;
 use crate::storage::Storage;
 use crate::task::{Task, TaskResult, TaskStatus};
-use serdes_ai_agent::Agent;
+use serdes_ai_agent::{Agent, AgentRun, AgentRunError, RunOptions};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -57,6 +59,7 @@ pub struct AgentWorker<Deps, Output> {
     broker: Arc<dyn Broker>,
     storage: Arc<dyn Storage>,
     deps_factory: Arc<dyn Fn() -> Deps + Send + Sync>,
+    push_notifier: Option<Arc<dyn PushNotifier>>,
 }
 
 impl<Deps, Output> AgentWorker<Deps, Output>
@@ -86,9 +89,19 @@ where
             broker,
             storage,
             deps_factory: Arc::new(deps_factory),
+            push_notifier: None,
         }
     }
 
+    /// Send push notifications for tasks that registered a webhook.
+    ///
+    /// Notifications are delivered in the background once a task completes,
+    /// fails or is cancelled, so slow webhooks don't hold up the worker.
+    pub fn with_push_notifier(mut self, notifier: impl PushNotifier + 'static) -> Self {
+        self.push_notifier = Some(Arc::new(notifier));
+        self
+    }
+
     /// Start the worker in a background task.
     ///
     /// Returns a handle that can be used to control the worker.
@@ -127,7 +140,17 @@ where
     }
 
     /// Process a single task.
-    pub async fn process_task(&self, mut task: Task) -> TaskResult {
+    ///
+    /// Cancelling the task through the broker stops the agent run: between
+    /// steps and tool calls, and by abandoning an in-flight model request.
+    pub async fn process_task(&self, task: Task) -> TaskResult {
+        let task_id = task.id.clone();
+        let result = self.run_task(task).await;
+        self.broker.release_task(&task_id).await;
+        result
+    }
+
+    async fn run_task(&self, mut task: Task) -> TaskResult {
         let start = Instant::now();
 
         // Check if task was already cancelled before we start
@@ -163,49 +186,60 @@ where
         // Create dependencies for this task
         let deps = (self.deps_factory)();
 
-        // Run the agent with cancellation support
-        let result = tokio::select! {
-            result = self.agent.run(prompt, deps) => result,
-            _ = cancellation_token.cancelled() => {
-                // Task was cancelled during execution
-                task.force_status(TaskStatus::Cancelled);
-                if let Err(e) = self.storage.update_task(&task).await {
-                    return TaskResult::failure(&task.id, format!("Failed to save cancellation: {}", e));
-                }
-                return TaskResult {
-                    task_id: task.id,
-                    status: TaskStatus::Cancelled,
-                    messages: Vec::new(),
-                    artifacts: Vec::new(),
-                    error: None,
-                    duration_ms: Some(start.elapsed().as_millis() as u64),
-                };
+        // Run the agent with cancellation support. The run checks the token
+        // between steps; the select also abandons an in-flight model request.
+        let run = async {
+            AgentRun::new_with_cancel(
+                &self.agent,
+                prompt.into(),
+                deps,
+                RunOptions::default(),
+                cancellation_token.clone(),
+            )
+            .await?
+            .run_to_completion()
+            .await
+        };
+        // Continue the trace of the request that submitted the task
+        #[cfg(feature = "otel")]
+        let run = {
+            use tracing::Instrument;
+
+            let span = tracing::info_span!("a2a.task", task_id = %task.id);
+            if let Some(cx) = &task.trace_context {
+                cx.set_parent(&span);
             }
+            run.instrument(span)
+        };
+        let result = tokio::select! {
+            result = run => result,
+            _ = cancellation_token.cancelled() => Err(AgentRunError::Cancelled),
         };
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
+        // Check for cancellation one more time before completing
+        if cancellation_token.is_cancelled() || matches!(result, Err(AgentRunError::Cancelled)) {
+            task.force_status(TaskStatus::Cancelled);
+            if let Err(e) = self.storage.update_task(&task).await {
+                return TaskResult::failure(
+                    &task.id,
+                    format!("Failed to save cancellation: {}", e),
+                );
+            }
+            self.push(&task);
+            return TaskResult {
+                task_id: task.id,
+                status: TaskStatus::Cancelled,
+                messages: Vec::new(),
+                artifacts: Vec::new(),
+                error: None,
+                duration_ms: Some(duration_ms),
+            };
+        }
+
         match result {
             Ok(agent_result) => {
-                // Check for cancellation one more time before completing
-                if cancellation_token.is_cancelled() {
-                    task.force_status(TaskStatus::Cancelled);
-                    if let Err(e) = self.storage.update_task(&task).await {
-                        return TaskResult::failure(
-                            &task.id,
-                            format!("Failed to save cancellation: {}", e),
-                        );
-                    }
-                    return TaskResult {
-                        task_id: task.id,
-                        status: TaskStatus::Cancelled,
-                        messages: Vec::new(),
-                        artifacts: Vec::new(),
-                        error: None,
-                        duration_ms: Some(duration_ms),
-                    };
-                }
-
                 // Create response message
                 let output_text = agent_result.output.to_string();
                 let response_message = Message::agent(output_text);
@@ -221,6 +255,7 @@ where
                 if let Err(e) = self.storage.update_task(&task).await {
                     return TaskResult::failure(&task.id, format!("Failed to save result: {}", e));
                 }
+                self.push(&task);
 
                 TaskResult::success(&task.id, vec![response_message])
                     .with_artifacts(task.artifacts)
@@ -239,6 +274,7 @@ where
                         format!("Agent failed: {}. Also failed to save: {}", e, storage_err),
                     );
                 }
+                self.push(&task);
 
                 TaskResult::failure(&task.id, error_msg).with_duration(duration_ms)
             }
@@ -246,6 +282,22 @@ where
     }
 }
 
+impl<Deps, Output> AgentWorker<Deps, Output> {
+    /// Notify the task's webhook, if it has one, in the background.
+    fn push(&self, task: &Task) {
+        let (Some(notifier), Some(config)) = (&self.push_notifier, &task.push_notification) else {
+            return;
+        };
+        let notifier = Arc::clone(notifier);
+        let config = config.clone();
+        let notification = PushNotification::from_task(task);
+        tokio::spawn(async move {
+            // Delivery already retries; a webhook that stays down misses the update.
+            let _ = notifier.notify(&config, &notification).await;
+        });
+    }
+}
+
 /// Builder for creating an AgentWorker with unit dependencies.
 impl<Output> AgentWorker<(), Output>
 where
@@ -282,6 +334,87 @@ mod tests {
         assert!(handle.is_running());
     }
 
+    struct RecordingNotifier(mpsc::UnboundedSender<(String, PushNotification)>);
+
+    #[async_trait::async_trait]
+    impl PushNotifier for RecordingNotifier {
+        async fn notify(
+            &self,
+            config: &crate::PushNotificationConfig,
+            notification: &PushNotification,
+        ) -> Result<(), crate::PushError> {
+            let _ = self.0.send((config.url.clone(), notification.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_notification_on_completion() {
+        use crate::{InMemoryBroker, InMemoryStorage, PushNotificationConfig};
+
+        let model = serdes_ai_models::MockModel::new("mock").with_text_response("Done!");
+        let agent = Arc::new(serdes_ai_agent::agent(model).build());
+        let storage = Arc::new(InMemoryStorage::new());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let worker = AgentWorker::without_deps(
+            agent,
+            Arc::new(InMemoryBroker::new()),
+            Arc::clone(&storage) as Arc<dyn Storage>,
+        )
+        .with_push_notifier(RecordingNotifier(tx));
+
+        let task = Task::new("thread-1", Message::user("Hi"))
+            .with_push_notification(PushNotificationConfig::new("https://client.example/hook"));
+        storage.save_task(&task).await.unwrap();
+        let result = worker.process_task(task.clone()).await;
+        assert!(result.is_success());
+
+        let (url, notification) = rx.recv().await.unwrap();
+        assert_eq!(url, "https://client.example/hook");
+        assert_eq!(notification.task_id, task.id);
+        assert_eq!(notification.status, TaskStatus::Completed);
+        assert_eq!(
+            notification.messages.last().unwrap().text_content(),
+            "Done!"
+        );
+
+        // Tasks without a webhook are not notified
+        let task = Task::new("thread-1", Message::user("Hi"));
+        storage.save_task(&task).await.unwrap();
+        assert!(worker.process_task(task).await.is_success());
+        tokio::task::yield_now().await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_stops_run() {
+        use crate::{InMemoryBroker, InMemoryStorage};
+
+        let model = serdes_ai_models::MockModel::new("mock").with_text_response("Done!");
+        let agent = Arc::new(serdes_ai_agent::agent(model).build());
+        let broker = Arc::new(InMemoryBroker::new());
+        let storage = Arc::new(InMemoryStorage::new());
+        let worker = AgentWorker::without_deps(
+            agent,
+            Arc::clone(&broker) as Arc<dyn Broker>,
+            Arc::clone(&storage) as Arc<dyn Storage>,
+        );
+
+        let task = Task::new("thread-1", Message::user("Hi"));
+        storage.save_task(&task).await.unwrap();
+        broker.submit_task(task.clone()).await.unwrap();
+        assert!(broker.cancel_task(&task.id).await);
+
+        let result = worker.process_task(task.clone()).await;
+        assert_eq!(result.status, TaskStatus::Cancelled);
+        let stored = storage.get_task(&task.id).await.unwrap().unwrap();
+        assert_eq!(stored.status, TaskStatus::Cancelled);
+        assert_eq!(stored.messages.len(), 1);
+
+        // The cancellation token is released once the worker is done
+        assert!(broker.get_cancellation_token(&task.id).await.is_none());
+    }
+
     #[tokio::test]
     async fn test_task_result_builder() {
         let result = TaskResult::success("task-1", vec![Message::agent("Done!")]);
@@ -3,19 +3,33 @@
 //! The Agent is the core type of serdes-ai. It orchestrates model calls,
 //! tool execution, and output validation.
 
+use crate::audit::AuditLog;
+use crate::confidence::ConfidenceEstimator;
 use crate::context::{RunContext, UsageLimits};
 use crate::errors::AgentRunError;
-use crate::history::HistoryProcessor;
+use crate::health::{HealthCheckOptions, HealthReport};
+use crate::history::{HistoryProcessor, HistoryTransform};
 use crate::instructions::{InstructionFn, SystemPromptFn};
+use crate::language::{prompt_text, Language, LanguageDetector};
 use crate::output::{OutputMode, OutputSchema, OutputValidator};
+use crate::review::Reviewer;
 use crate::run::{AgentRun, AgentRunResult, RunOptions};
 use crate::stream::AgentStream;
-use serdes_ai_core::messages::UserContent;
-use serdes_ai_core::ModelSettings;
-use serdes_ai_models::Model;
-use serdes_ai_tools::ToolDefinition;
+use crate::tool_return_limit::ToolReturnLimit;
+use crate::tool_selection::ToolSelection;
+use crate::tool_usage::ToolRouting;
+use crate::usage_meter::UsageMeter;
+use serdes_ai_core::identifier::generate_run_id;
+use serdes_ai_core::messages::{JsonRepairer, UserContent};
+use serdes_ai_core::{ApiKey, IdGenerator, IdKind, ModelSettings};
+use serdes_ai_models::{
+    Model, ModelRequestParameters, ModelWithMetadata, OutputMode as ProfileOutputMode,
+};
+use serdes_ai_tools::{ToolCallStats, ToolDefinition, ToolStats};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Strategy for handling tool calls when output is ready.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -38,6 +52,19 @@ pub struct InstrumentationSettings {
     pub span_name: Option<String>,
 }
 
+/// Hook returning per-request model setting overrides.
+///
+/// Called before every model request. The returned settings are merged over
+/// the run's settings, see [`ModelSettings`] for the precedence order.
+pub type RequestSettingsFn<Deps> =
+    Arc<dyn Fn(&RunContext<Deps>) -> Option<ModelSettings> + Send + Sync>;
+
+/// Resolves the API key for a run from its dependencies.
+///
+/// Lets one agent bill model usage to per-tenant keys. Returning `None`
+/// keeps the model's configured key.
+pub type KeyResolverFn<Deps> = Arc<dyn Fn(&Deps) -> Option<ApiKey> + Send + Sync>;
+
 /// The main agent type.
 ///
 /// An agent wraps a model and provides:
@@ -47,6 +74,14 @@ pub struct InstrumentationSettings {
 /// - Retry logic for failures
 /// - Usage tracking and limits
 ///
+/// # Sharing
+///
+/// An agent holds only immutable configuration behind [`Arc`]s; all per-run
+/// state lives in the [`AgentRun`]. Cloning is cheap, and the agent is
+/// `Send + Sync` whenever `Deps` and `Output` are, so a single agent built at
+/// startup can serve concurrent requests (e.g. stored in web server state)
+/// without rebuilding it per request.
+///
 /// # Type Parameters
 ///
 /// - `Deps`: Dependencies injected into tools and instruction functions.
@@ -58,21 +93,27 @@ pub struct Agent<Deps = (), Output = String> {
     pub(crate) name: Option<String>,
     /// Default model settings.
     pub(crate) model_settings: ModelSettings,
-    /// Pre-joined static system prompt (static instructions + static prompts).
+    /// Per-request model settings hooks.
+    pub(crate) request_settings_fns: Vec<RequestSettingsFn<Deps>>,
+    /// Per-run API key resolver.
+    pub(crate) key_resolver: Option<KeyResolverFn<Deps>>,
+    /// Pre-joined static system prompt.
     /// This avoids cloning on every run.
     pub(crate) static_system_prompt: Arc<str>,
+    /// Pre-joined static instructions.
+    pub(crate) static_instructions: Arc<str>,
     /// Dynamic instruction functions.
-    pub(crate) instruction_fns: Vec<Box<dyn InstructionFn<Deps>>>,
+    pub(crate) instruction_fns: Arc<[Box<dyn InstructionFn<Deps>>]>,
     /// Dynamic system prompt functions.
-    pub(crate) system_prompt_fns: Vec<Box<dyn SystemPromptFn<Deps>>>,
+    pub(crate) system_prompt_fns: Arc<[Box<dyn SystemPromptFn<Deps>>]>,
     /// Registered tool definitions.
     pub(crate) tools: Vec<RegisteredTool<Deps>>,
     /// Cached tool definitions - pre-computed to avoid cloning on every step.
     pub(crate) cached_tool_defs: Arc<Vec<ToolDefinition>>,
     /// Output schema.
-    pub(crate) output_schema: Box<dyn OutputSchema<Output>>,
+    pub(crate) output_schema: Arc<dyn OutputSchema<Output>>,
     /// Output validators.
-    pub(crate) output_validators: Vec<Box<dyn OutputValidator<Output, Deps>>>,
+    pub(crate) output_validators: Vec<Arc<dyn OutputValidator<Output, Deps>>>,
     /// End strategy for tool calls.
     pub(crate) end_strategy: EndStrategy,
     /// Maximum retries for output validation.
@@ -83,7 +124,7 @@ pub struct Agent<Deps = (), Output = String> {
     /// Usage limits.
     pub(crate) usage_limits: Option<UsageLimits>,
     /// History processors.
-    pub(crate) history_processors: Vec<Box<dyn HistoryProcessor<Deps>>>,
+    pub(crate) history_processors: Arc<[Box<dyn HistoryProcessor<Deps>>]>,
     /// Instrumentation settings.
     #[allow(dead_code)]
     pub(crate) instrument: Option<InstrumentationSettings>,
@@ -91,6 +132,30 @@ pub struct Agent<Deps = (), Output = String> {
     pub(crate) parallel_tool_calls: bool,
     /// Maximum number of concurrent tool calls (None = unlimited).
     pub(crate) max_concurrent_tools: Option<usize>,
+    /// Tool calls slower than this are logged as warnings.
+    pub(crate) slow_tool_threshold: Option<Duration>,
+    /// Repairer for malformed tool call arguments.
+    pub(crate) json_repair: JsonRepairer,
+    /// Meter this agent reports usage into (falls back to the global meter).
+    pub(crate) usage_meter: Option<Arc<UsageMeter>>,
+    /// Audit log of tool calls and model requests.
+    pub(crate) audit: Option<AuditLog<Deps>>,
+    /// Reviewer pass over run outputs.
+    pub(crate) reviewer: Option<Reviewer>,
+    /// Estimator of run output confidence.
+    pub(crate) confidence_estimator: Option<Arc<dyn ConfidenceEstimator>>,
+    /// Whether JSON output is requested in JSON mode.
+    pub(crate) json_object_output: bool,
+    /// Detector for the language of run prompts.
+    pub(crate) language_detector: Option<Arc<dyn LanguageDetector>>,
+    /// Transform applied to history returned from runs.
+    pub(crate) history_transform: Option<Arc<dyn HistoryTransform>>,
+    /// Relevance-based selection of the tools sent to the model.
+    pub(crate) tool_selection: Option<ToolSelection>,
+    /// Usage-driven ordering and pruning of the tools sent to the model.
+    pub(crate) tool_routing: Option<ToolRouting>,
+    /// Generator of run IDs, overriding the current one.
+    pub(crate) id_generator: Option<Arc<dyn IdGenerator>>,
     pub(crate) _phantom: PhantomData<(Deps, Output)>,
 }
 
@@ -102,6 +167,10 @@ pub struct RegisteredTool<Deps> {
     pub executor: Arc<dyn ToolExecutor<Deps>>,
     /// Max retries for this tool.
     pub max_retries: u32,
+    /// Size limit for this tool's return content.
+    pub return_limit: Option<ToolReturnLimit>,
+    /// What to do when the tool is unavailable.
+    pub failure_policy: ToolFailurePolicy,
 }
 
 impl<Deps> Clone for RegisteredTool<Deps> {
@@ -110,10 +179,44 @@ impl<Deps> Clone for RegisteredTool<Deps> {
             definition: self.definition.clone(),
             executor: Arc::clone(&self.executor),
             max_retries: self.max_retries,
+            return_limit: self.return_limit.clone(),
+            failure_policy: self.failure_policy,
         }
     }
 }
 
+/// What happens when a tool call fails because the tool is unavailable.
+///
+/// Applies to errors for which [`ToolError::is_unavailable`] holds (timeouts
+/// and transport failures of remote tools), once in-place retries are
+/// exhausted. Other errors are always reported to the model.
+///
+/// [`ToolError::is_unavailable`]: serdes_ai_tools::ToolError::is_unavailable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolFailurePolicy {
+    /// Report the error to the model, which may call the tool again.
+    #[default]
+    Report,
+    /// Tell the model the tool is unavailable and to continue without it,
+    /// so a flaky tool server doesn't take down the run.
+    Degrade,
+    /// Fail the run.
+    Fail,
+}
+
+impl ToolFailurePolicy {
+    /// Message telling the model a tool is unavailable.
+    pub(crate) fn unavailable_message(
+        tool_name: &str,
+        error: &serdes_ai_tools::ToolError,
+    ) -> String {
+        format!(
+            "Tool '{}' is currently unavailable ({}). Continue without it.",
+            tool_name, error
+        )
+    }
+}
+
 /// Trait for executing tools.
 #[async_trait::async_trait]
 pub trait ToolExecutor<Deps>: Send + Sync {
@@ -123,6 +226,26 @@ pub trait ToolExecutor<Deps>: Send + Sync {
         args: serde_json::Value,
         ctx: &RunContext<Deps>,
     ) -> Result<serdes_ai_tools::ToolReturn, serdes_ai_tools::ToolError>;
+
+    /// Whether a call that already succeeded in this run is skipped when it
+    /// is made again with the same arguments.
+    ///
+    /// Enable this for tools that are not idempotent (sending emails,
+    /// creating records), so a retried step reuses the first result instead
+    /// of writing twice. Calls are matched by
+    /// [`RunContext::idempotency_key`].
+    fn skip_repeated_calls(&self) -> bool {
+        false
+    }
+
+    /// Check that the tool is ready to be called.
+    ///
+    /// Called by [`Agent::health_check`]. Override for tools backed by a
+    /// remote service, e.g. to initialize an MCP session; the default
+    /// reports the tool as ready.
+    async fn health_check(&self) -> Result<(), serdes_ai_tools::ToolError> {
+        Ok(())
+    }
 }
 
 impl<Deps, Output> Agent<Deps, Output>
@@ -135,6 +258,11 @@ where
         self.model.as_ref()
     }
 
+    /// Get metadata about the model (context window, pricing, modalities).
+    pub fn model_metadata(&self) -> ModelWithMetadata {
+        ModelWithMetadata::new(self.model.clone())
+    }
+
     /// Get the model as an Arc (for cloning into spawned tasks).
     pub fn model_arc(&self) -> Arc<dyn Model> {
         Arc::clone(&self.model)
@@ -160,6 +288,16 @@ where
         self.output_schema.mode()
     }
 
+    /// Get the JSON schema of the structured output, if it has one.
+    pub fn output_json_schema(&self) -> Option<serde_json::Value> {
+        self.output_schema.json_schema()
+    }
+
+    /// Check if JSON output is requested in JSON mode.
+    pub fn json_object_output(&self) -> bool {
+        self.json_object_output
+    }
+
     /// Check if the agent has tools.
     pub fn has_tools(&self) -> bool {
         !self.tools.is_empty()
@@ -180,6 +318,42 @@ where
         self.max_concurrent_tools
     }
 
+    /// Get the threshold above which tool calls are logged as slow.
+    pub fn slow_tool_threshold(&self) -> Option<Duration> {
+        self.slow_tool_threshold
+    }
+
+    /// Create the tool call statistics collector for a run.
+    pub(crate) fn new_tool_stats(&self) -> Arc<ToolStats> {
+        let stats = ToolStats::new();
+        Arc::new(match self.slow_tool_threshold {
+            Some(threshold) => stats.with_slow_threshold(threshold),
+            None => stats,
+        })
+    }
+
+    /// Get the repairer used for malformed tool call arguments.
+    pub fn json_repair(&self) -> &JsonRepairer {
+        &self.json_repair
+    }
+
+    /// Get the usage meter this agent reports into.
+    ///
+    /// Returns the agent's own meter, or the process-wide meter if none was set.
+    pub fn usage_meter(&self) -> Option<Arc<UsageMeter>> {
+        self.usage_meter.clone().or_else(UsageMeter::global)
+    }
+
+    /// Get the audit log, if any.
+    pub fn audit(&self) -> Option<&AuditLog<Deps>> {
+        self.audit.as_ref()
+    }
+
+    /// Get the reviewer, if any.
+    pub fn reviewer(&self) -> Option<&Reviewer> {
+        self.reviewer.as_ref()
+    }
+
     /// Run the agent with a prompt.
     ///
     /// # Arguments
@@ -210,6 +384,20 @@ where
         run.run_to_completion().await
     }
 
+    /// Run with extra tools for this run only.
+    ///
+    /// Tools whose names clash with the agent's own tools are skipped.
+    pub async fn run_with_tools(
+        &self,
+        prompt: impl Into<UserContent>,
+        deps: Deps,
+        options: RunOptions,
+        tools: impl IntoIterator<Item = RegisteredTool<Deps>>,
+    ) -> Result<AgentRunResult<Output>, AgentRunError> {
+        let run = self.start_run(prompt, deps, options).await?;
+        run.with_tools(tools).run_to_completion().await
+    }
+
     /// Run synchronously (blocking).
     ///
     /// Note: This requires a Tokio runtime to be available.
@@ -258,11 +446,8 @@ where
     /// Static prompts are pre-joined at build time for efficiency.
     /// Only dynamic prompts need to be evaluated per-run.
     pub(crate) async fn build_system_prompt(&self, ctx: &RunContext<Deps>) -> String {
-        // Check if we have any dynamic prompts
-        let has_dynamic = !self.system_prompt_fns.is_empty() || !self.instruction_fns.is_empty();
-
-        if !has_dynamic {
-            // Fast path: just return the pre-joined static prompt (no allocation needed)
+        if self.system_prompt_fns.is_empty() {
+            // Fast path: just return the pre-joined static prompt
             return self.static_system_prompt.to_string();
         }
 
@@ -275,7 +460,7 @@ where
         }
 
         // Dynamic system prompts
-        for prompt_fn in &self.system_prompt_fns {
+        for prompt_fn in self.system_prompt_fns.iter() {
             if let Some(prompt) = prompt_fn.generate(ctx).await {
                 if !prompt.is_empty() {
                     parts.push(prompt);
@@ -283,8 +468,21 @@ where
             }
         }
 
-        // Dynamic instructions
-        for instruction_fn in &self.instruction_fns {
+        parts.join("\n\n")
+    }
+
+    /// Build the instructions for the next request.
+    ///
+    /// Instructions are sent with the latest request only and never stored
+    /// in the message history, so they are re-evaluated on every step.
+    pub(crate) async fn build_instructions(&self, ctx: &RunContext<Deps>) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if !self.static_instructions.is_empty() {
+            parts.push(self.static_instructions.to_string());
+        }
+
+        for instruction_fn in self.instruction_fns.iter() {
             if let Some(instruction) = instruction_fn.generate(ctx).await {
                 if !instruction.is_empty() {
                     parts.push(instruction);
@@ -292,7 +490,11 @@ where
             }
         }
 
-        parts.join("\n\n")
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("\n\n"))
+        }
     }
 
     /// Get the cached tool definitions.
@@ -302,11 +504,74 @@ where
         Arc::clone(&self.cached_tool_defs)
     }
 
+    /// Get the tool usage category of a run's prompt, if routing is configured.
+    pub(crate) fn tool_usage_category(&self, prompt: &UserContent) -> Option<String> {
+        let routing = self.tool_routing.as_ref()?;
+        Some(routing.category(&prompt_text(prompt)))
+    }
+
+    /// Get the tool definitions to send for a run's prompt.
+    ///
+    /// All tools, unless a [`ToolRouting`] or [`ToolSelection`] is configured.
+    pub(crate) async fn run_tool_definitions(
+        &self,
+        prompt: &UserContent,
+        usage_category: Option<&str>,
+    ) -> Arc<Vec<ToolDefinition>> {
+        let tools = match (&self.tool_routing, usage_category) {
+            (Some(routing), Some(category)) => {
+                Arc::new(routing.route(category, &self.cached_tool_defs))
+            }
+            _ => self.tool_definitions(),
+        };
+        match &self.tool_selection {
+            Some(selection) => Arc::new(selection.select(&prompt_text(prompt), &tools).await),
+            None => tools,
+        }
+    }
+
+    /// Record a finished run's tool calls for usage-driven routing.
+    pub(crate) fn record_tool_usage(
+        &self,
+        usage_category: Option<&str>,
+        stats: &HashMap<String, ToolCallStats>,
+    ) {
+        if let (Some(routing), Some(category)) = (&self.tool_routing, usage_category) {
+            routing.record(category, stats);
+        }
+    }
+
+    /// Build the parameters for a model request with the given tools.
+    pub(crate) fn request_params(&self, tools: Arc<Vec<ToolDefinition>>) -> ModelRequestParameters {
+        let params = ModelRequestParameters::new()
+            .with_tools_arc(tools)
+            .with_allow_text(true);
+        if self.json_object_output {
+            params.with_profile_output_mode(self.model.profile(), ProfileOutputMode::JsonObject)
+        } else {
+            params
+        }
+    }
+
     /// Find a tool by name.
     pub(crate) fn find_tool(&self, name: &str) -> Option<&RegisteredTool<Deps>> {
         self.tools.iter().find(|t| t.definition.name == name)
     }
 
+    /// Generate the ID of a new run.
+    pub(crate) fn new_run_id(&self) -> String {
+        match &self.id_generator {
+            Some(generator) => generator.generate(IdKind::Run),
+            None => generate_run_id(),
+        }
+    }
+
+    /// Detect the language of a run's prompt, if a detector is configured.
+    pub(crate) async fn detect_language(&self, prompt: &UserContent) -> Option<Language> {
+        let detector = self.language_detector.as_ref()?;
+        detector.detect(&prompt_text(prompt)).await
+    }
+
     /// Check if this is the output tool.
     pub(crate) fn is_output_tool(&self, name: &str) -> bool {
         self.output_schema
@@ -321,10 +586,78 @@ where
         self.output_schema.tool_name().map(|s| s.to_string())
     }
 
+    /// Check the model endpoint, tools and output schema.
+    ///
+    /// Sends a one-token request to the model, so it costs a little. See
+    /// [`health_check_with`](Self::health_check_with) to skip it.
+    pub async fn health_check(&self) -> HealthReport {
+        self.health_check_with(HealthCheckOptions::new()).await
+    }
+
+    /// Check the model endpoint, tools and output schema with options.
+    pub async fn health_check_with(&self, options: HealthCheckOptions) -> HealthReport {
+        crate::health::check_agent(self, &options).await
+    }
+
     /// Get the static system prompt.
     pub fn static_system_prompt(&self) -> &str {
         &self.static_system_prompt
     }
+
+    /// Get the static instructions.
+    pub fn static_instructions(&self) -> &str {
+        &self.static_instructions
+    }
+
+    /// Get a copy of this agent with a different static system prompt.
+    ///
+    /// Dynamic system prompt functions, tools and settings are kept.
+    pub fn with_system_prompt(&self, prompt: impl Into<String>) -> Self {
+        let mut agent = self.clone();
+        agent.static_system_prompt = Arc::from(prompt.into());
+        agent
+    }
+}
+
+impl<Deps, Output> Clone for Agent<Deps, Output> {
+    fn clone(&self) -> Self {
+        Self {
+            model: Arc::clone(&self.model),
+            name: self.name.clone(),
+            model_settings: self.model_settings.clone(),
+            request_settings_fns: self.request_settings_fns.clone(),
+            key_resolver: self.key_resolver.clone(),
+            static_system_prompt: Arc::clone(&self.static_system_prompt),
+            static_instructions: Arc::clone(&self.static_instructions),
+            instruction_fns: Arc::clone(&self.instruction_fns),
+            system_prompt_fns: Arc::clone(&self.system_prompt_fns),
+            tools: self.tools.clone(),
+            cached_tool_defs: Arc::clone(&self.cached_tool_defs),
+            output_schema: Arc::clone(&self.output_schema),
+            output_validators: self.output_validators.clone(),
+            end_strategy: self.end_strategy,
+            max_output_retries: self.max_output_retries,
+            max_tool_retries: self.max_tool_retries,
+            usage_limits: self.usage_limits.clone(),
+            history_processors: Arc::clone(&self.history_processors),
+            instrument: self.instrument.clone(),
+            parallel_tool_calls: self.parallel_tool_calls,
+            max_concurrent_tools: self.max_concurrent_tools,
+            slow_tool_threshold: self.slow_tool_threshold,
+            json_repair: self.json_repair.clone(),
+            usage_meter: self.usage_meter.clone(),
+            audit: self.audit.clone(),
+            reviewer: self.reviewer.clone(),
+            confidence_estimator: self.confidence_estimator.clone(),
+            json_object_output: self.json_object_output,
+            language_detector: self.language_detector.clone(),
+            id_generator: self.id_generator.clone(),
+            history_transform: self.history_transform.clone(),
+            tool_selection: self.tool_selection.clone(),
+            tool_routing: self.tool_routing.clone(),
+            _phantom: PhantomData,
+        }
+    }
 }
 
 // Default for String output
@@ -361,4 +694,67 @@ mod tests {
         assert!(!settings.enable_tracing);
         assert!(settings.log_level.is_none());
     }
+
+    #[test]
+    fn test_model_metadata() {
+        let agent = crate::AgentBuilder::<(), String>::new(serdes_ai_models::MockModel::new(
+            "claude-3-5-sonnet-20241022",
+        ))
+        .build();
+        let meta = agent.model_metadata();
+        assert_eq!(meta.context_window, Some(200_000));
+        assert_eq!(meta.input_price, Some(3.0));
+    }
+
+    #[test]
+    fn test_agent_is_send_sync_clone() {
+        fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+        assert_shareable::<Agent<(), String>>();
+        assert_shareable::<Agent<Arc<String>, serde_json::Value>>();
+    }
+
+    #[tokio::test]
+    async fn test_clones_serve_concurrent_runs() {
+        use serdes_ai_core::{ModelRequest, ModelResponse};
+
+        let agent = crate::agent(serdes_ai_models::FunctionModel::new(
+            |messages: &[ModelRequest], _| {
+                let prompt = messages
+                    .iter()
+                    .flat_map(|m| m.user_prompts())
+                    .last()
+                    .map(|p| format!("{:?}", p.content))
+                    .unwrap_or_default();
+                ModelResponse::text(prompt)
+            },
+        ))
+        .tool_fn(
+            "noop",
+            "Does nothing",
+            |_ctx: &RunContext<()>, _args: serde_json::Value| {
+                Ok(serdes_ai_tools::ToolReturn::text("ok"))
+            },
+        )
+        .build();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let agent = agent.clone();
+                tokio::spawn(async move {
+                    let result = agent.run(format!("request {i}"), ()).await.unwrap();
+                    (i, result.output().clone())
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let (i, output) = handle.await.unwrap();
+            assert!(output.contains(&format!("request {i}")));
+        }
+        // Clones share configuration rather than copying it
+        assert!(Arc::ptr_eq(
+            &agent.clone().cached_tool_defs,
+            &agent.cached_tool_defs
+        ));
+    }
 }
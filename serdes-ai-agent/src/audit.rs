@@ -0,0 +1,640 @@
+//! Audit log of tool calls and model requests.
+//!
+//! An [`AuditLog`] sends an immutable [`AuditRecord`] to an [`AuditSink`] for
+//! every tool call and model request an agent makes: who made it, when, what
+//! was called, a SHA-256 hash of the arguments, and how it ended. Arguments
+//! themselves are never logged, so records can be kept for compliance
+//! without retaining user data.
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent_with_deps, AuditLog, JsonlAuditSink};
+//! use std::sync::Arc;
+//!
+//! let sink = Arc::new(JsonlAuditSink::open("audit.jsonl").await?);
+//! let agent = agent_with_deps::<Session, _>(model)
+//!     .audit(AuditLog::new(sink).with_actor(|session: &Session| session.user_id.clone()))
+//!     .build();
+//! ```
+//!
+//! [`JsonlAuditSink`] appends one JSON record per line to a file. With the
+//! `audit-http` feature, [`HttpAuditSink`] POSTs records to a collector in
+//! batches. A sink that fails never fails the run; the error is logged.
+
+use crate::agent::ToolExecutor;
+use crate::context::RunContext;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serdes_ai_core::RequestUsage;
+use serdes_ai_tools::{ToolError, ToolReturn};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "audit-http")]
+pub use http::HttpAuditSink;
+
+/// What an audit record is about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A tool was called.
+    ToolCall {
+        /// Tool name.
+        tool_name: String,
+        /// Tool call ID from the model, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_call_id: Option<String>,
+        /// Hex SHA-256 hash of the JSON arguments.
+        args_sha256: String,
+        /// Wall-clock duration in milliseconds.
+        duration_ms: u64,
+    },
+    /// A model request was made.
+    ModelRequest {
+        /// Model name.
+        model_name: String,
+        /// Token usage, if the model reported it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<RequestUsage>,
+    },
+}
+
+/// How an audited call ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// The call succeeded.
+    Success,
+    /// The call failed.
+    Error {
+        /// Error message.
+        message: String,
+    },
+}
+
+impl AuditStatus {
+    /// Status of a tool call result; returns marked as errors count as failed.
+    #[must_use]
+    pub fn from_tool_result(result: &Result<ToolReturn, ToolError>) -> Self {
+        match result {
+            Ok(ret) if ret.is_error() => Self::Error {
+                message: ret.content.to_string_content(),
+            },
+            Ok(_) => Self::Success,
+            Err(e) => Self::Error {
+                message: e.to_string(),
+            },
+        }
+    }
+
+    /// Check if the call succeeded.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success)
+    }
+}
+
+/// One entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Unique record ID.
+    pub id: String,
+    /// When the call finished.
+    pub timestamp: DateTime<Utc>,
+    /// Run the call belongs to.
+    pub run_id: String,
+    /// Who the run was made for, if the log has an actor function.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// What was called.
+    #[serde(flatten)]
+    pub event: AuditEvent,
+    /// How the call ended.
+    #[serde(flatten)]
+    pub status: AuditStatus,
+}
+
+impl AuditRecord {
+    /// Create a record timestamped now.
+    #[must_use]
+    pub fn new(
+        run_id: impl Into<String>,
+        actor: Option<String>,
+        event: AuditEvent,
+        status: AuditStatus,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: serdes_ai_core::clock::now(),
+            run_id: run_id.into(),
+            actor,
+            event,
+            status,
+        }
+    }
+}
+
+/// Hex SHA-256 hash of a tool call's JSON arguments.
+#[must_use]
+pub fn hash_args(args: &JsonValue) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, args.to_string().as_bytes());
+    digest.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Destination for audit records.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Store a record.
+    async fn record(&self, record: &AuditRecord) -> io::Result<()>;
+
+    /// Write out buffered records.
+    async fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// In-memory [`AuditSink`], mostly useful in tests.
+#[derive(Debug, Default)]
+pub struct InMemoryAuditSink {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl InMemoryAuditSink {
+    /// Create an empty sink.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the records stored so far.
+    #[must_use]
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl AuditSink for InMemoryAuditSink {
+    async fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        if let Ok(mut records) = self.records.lock() {
+            records.push(record.clone());
+        }
+        Ok(())
+    }
+}
+
+/// [`AuditSink`] appending one JSON record per line to a file.
+///
+/// Each record is written with a single append, so concurrent runs never
+/// interleave lines.
+#[derive(Debug)]
+pub struct JsonlAuditSink {
+    path: PathBuf,
+    file: tokio::sync::Mutex<tokio::fs::File>,
+}
+
+impl JsonlAuditSink {
+    /// Open a log file for appending, creating it if needed.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        Ok(Self {
+            path,
+            file: tokio::sync::Mutex::new(file),
+        })
+    }
+
+    /// Get the file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonlAuditSink {
+    async fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.flush().await
+    }
+
+    async fn flush(&self) -> io::Result<()> {
+        self.file.lock().await.sync_data().await
+    }
+}
+
+/// Function naming who a run was made for.
+pub type ActorFn<Deps> = Arc<dyn Fn(&Deps) -> Option<String> + Send + Sync>;
+
+/// Sends an [`AuditRecord`] for every tool call and model request of an
+/// agent.
+pub struct AuditLog<Deps> {
+    sink: Arc<dyn AuditSink>,
+    actor: Option<ActorFn<Deps>>,
+}
+
+impl<Deps> AuditLog<Deps> {
+    /// Log to `sink`.
+    #[must_use]
+    pub fn new(sink: Arc<dyn AuditSink>) -> Self {
+        Self { sink, actor: None }
+    }
+
+    /// Name the actor of each record from the run's deps.
+    #[must_use]
+    pub fn with_actor<F>(mut self, actor: F) -> Self
+    where
+        F: Fn(&Deps) -> Option<String> + Send + Sync + 'static,
+    {
+        self.actor = Some(Arc::new(actor));
+        self
+    }
+
+    /// Get the sink.
+    #[must_use]
+    pub fn sink(&self) -> &Arc<dyn AuditSink> {
+        &self.sink
+    }
+
+    /// Get the actor of a run.
+    #[must_use]
+    pub fn actor(&self, deps: &Deps) -> Option<String> {
+        self.actor.as_ref().and_then(|actor| actor(deps))
+    }
+
+    /// Record a model request.
+    pub(crate) async fn model_request(
+        &self,
+        deps: &Deps,
+        run_id: &str,
+        model_name: &str,
+        usage: Option<&RequestUsage>,
+        status: AuditStatus,
+    ) {
+        let event = AuditEvent::ModelRequest {
+            model_name: model_name.to_string(),
+            usage: usage.cloned(),
+        };
+        self.send(AuditRecord::new(run_id, self.actor(deps), event, status))
+            .await;
+    }
+
+    async fn send(&self, record: AuditRecord) {
+        if let Err(_e) = self.sink.record(&record).await {
+            warn!(record_id = %record.id, error = %_e, "Failed to write audit record");
+        }
+    }
+}
+
+impl<Deps> Clone for AuditLog<Deps> {
+    fn clone(&self) -> Self {
+        Self {
+            sink: Arc::clone(&self.sink),
+            actor: self.actor.clone(),
+        }
+    }
+}
+
+impl<Deps> fmt::Debug for AuditLog<Deps> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("actor", &self.actor.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Executor wrapper that records every call in the audit log.
+pub(crate) struct AuditedExecutor<Deps> {
+    pub(crate) inner: Arc<dyn ToolExecutor<Deps>>,
+    pub(crate) log: AuditLog<Deps>,
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for AuditedExecutor<Deps> {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        let args_sha256 = hash_args(&args);
+        let started = Instant::now();
+        let result = self.inner.execute(args, ctx).await;
+        let event = AuditEvent::ToolCall {
+            tool_name: ctx.tool_name.clone().unwrap_or_default(),
+            tool_call_id: ctx.tool_call_id.clone(),
+            args_sha256,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        let status = AuditStatus::from_tool_result(&result);
+        self.log
+            .send(AuditRecord::new(
+                ctx.run_id.clone(),
+                self.log.actor(&ctx.deps),
+                event,
+                status,
+            ))
+            .await;
+        result
+    }
+
+    fn skip_repeated_calls(&self) -> bool {
+        self.inner.skip_repeated_calls()
+    }
+
+    async fn health_check(&self) -> Result<(), ToolError> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(feature = "audit-http")]
+mod http {
+    use super::*;
+    use std::sync::OnceLock;
+    use std::time::Duration;
+    use tokio::sync::{mpsc, oneshot};
+
+    enum Command {
+        Record(Box<AuditRecord>),
+        Flush(oneshot::Sender<io::Result<()>>),
+    }
+
+    /// [`AuditSink`] POSTing records to a collector as JSON arrays.
+    ///
+    /// Records are batched in a background task and sent once the batch is
+    /// full or the flush interval passes. Records of a failed POST are kept
+    /// and sent again with the next batch; past the buffer limit the oldest
+    /// are dropped with a warning. Dropping the sink sends what is left.
+    ///
+    /// The background task is spawned on the first record, so the sink must
+    /// be used inside a Tokio runtime.
+    pub struct HttpAuditSink {
+        client: reqwest::Client,
+        url: String,
+        bearer_token: Option<String>,
+        batch_size: usize,
+        flush_interval: Duration,
+        max_buffered: usize,
+        sender: OnceLock<mpsc::Sender<Command>>,
+    }
+
+    impl HttpAuditSink {
+        /// Send records to `url`.
+        #[must_use]
+        pub fn new(url: impl Into<String>) -> Self {
+            Self {
+                client: reqwest::Client::new(),
+                url: url.into(),
+                bearer_token: None,
+                batch_size: 100,
+                flush_interval: Duration::from_secs(5),
+                max_buffered: 10_000,
+                sender: OnceLock::new(),
+            }
+        }
+
+        /// Use a custom HTTP client.
+        #[must_use]
+        pub fn with_client(mut self, client: reqwest::Client) -> Self {
+            self.client = client;
+            self
+        }
+
+        /// Authenticate with a bearer token.
+        #[must_use]
+        pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+            self.bearer_token = Some(token.into());
+            self
+        }
+
+        /// Set how many records are sent per request (default: 100).
+        #[must_use]
+        pub fn with_batch_size(mut self, size: usize) -> Self {
+            self.batch_size = size.max(1);
+            self
+        }
+
+        /// Set how often partial batches are sent (default: 5s).
+        #[must_use]
+        pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+            self.flush_interval = interval;
+            self
+        }
+
+        /// Set how many unsent records are kept while the collector is
+        /// unreachable (default: 10,000).
+        #[must_use]
+        pub fn with_max_buffered(mut self, max: usize) -> Self {
+            self.max_buffered = max;
+            self
+        }
+
+        fn sender(&self) -> &mpsc::Sender<Command> {
+            self.sender.get_or_init(|| {
+                let (tx, rx) = mpsc::channel(self.batch_size * 4);
+                let batcher = Batcher {
+                    client: self.client.clone(),
+                    url: self.url.clone(),
+                    bearer_token: self.bearer_token.clone(),
+                    batch_size: self.batch_size,
+                    max_buffered: self.max_buffered,
+                    buffer: Vec::new(),
+                };
+                tokio::spawn(batcher.run(rx, self.flush_interval));
+                tx
+            })
+        }
+    }
+
+    impl fmt::Debug for HttpAuditSink {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("HttpAuditSink")
+                .field("url", &self.url)
+                .field("batch_size", &self.batch_size)
+                .field("flush_interval", &self.flush_interval)
+                .finish_non_exhaustive()
+        }
+    }
+
+    #[async_trait]
+    impl AuditSink for HttpAuditSink {
+        async fn record(&self, record: &AuditRecord) -> io::Result<()> {
+            self.sender()
+                .send(Command::Record(Box::new(record.clone())))
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "audit sink stopped"))
+        }
+
+        async fn flush(&self) -> io::Result<()> {
+            let (tx, rx) = oneshot::channel();
+            self.sender()
+                .send(Command::Flush(tx))
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "audit sink stopped"))?;
+            rx.await
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "audit sink stopped"))?
+        }
+    }
+
+    struct Batcher {
+        client: reqwest::Client,
+        url: String,
+        bearer_token: Option<String>,
+        batch_size: usize,
+        max_buffered: usize,
+        buffer: Vec<AuditRecord>,
+    }
+
+    impl Batcher {
+        async fn run(mut self, mut rx: mpsc::Receiver<Command>, flush_interval: Duration) {
+            let mut ticker = tokio::time::interval(flush_interval);
+            loop {
+                tokio::select! {
+                    command = rx.recv() => match command {
+                        Some(Command::Record(record)) => {
+                            self.push(*record);
+                            if self.buffer.len() >= self.batch_size {
+                                let _ = self.send().await;
+                            }
+                        }
+                        Some(Command::Flush(done)) => {
+                            let _ = done.send(self.send().await);
+                        }
+                        None => {
+                            let _ = self.send().await;
+                            return;
+                        }
+                    },
+                    _ = ticker.tick() => {
+                        let _ = self.send().await;
+                    }
+                }
+            }
+        }
+
+        fn push(&mut self, record: AuditRecord) {
+            if self.buffer.len() >= self.max_buffered {
+                warn!(
+                    max_buffered = self.max_buffered,
+                    "Audit buffer full, dropping oldest record"
+                );
+                self.buffer.remove(0);
+            }
+            self.buffer.push(record);
+        }
+
+        async fn send(&mut self) -> io::Result<()> {
+            if self.buffer.is_empty() {
+                return Ok(());
+            }
+            let mut request = self.client.post(&self.url).json(&self.buffer);
+            if let Some(token) = &self.bearer_token {
+                request = request.bearer_auth(token);
+            }
+            let result = match request.send().await {
+                Ok(response) if response.status().is_success() => Ok(()),
+                Ok(response) => Err(format!("collector returned {}", response.status())),
+                Err(e) => Err(e.to_string()),
+            };
+            match result {
+                Ok(()) => {
+                    self.buffer.clear();
+                    Ok(())
+                }
+                Err(message) => {
+                    warn!(
+                        records = self.buffer.len(),
+                        error = %message,
+                        "Failed to send audit records"
+                    );
+                    Err(io::Error::other(message))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> AuditRecord {
+        AuditRecord::new(
+            "run-1",
+            Some("alice".into()),
+            AuditEvent::ToolCall {
+                tool_name: "lookup".into(),
+                tool_call_id: Some("call-1".into()),
+                args_sha256: hash_args(&serde_json::json!({"id": 1})),
+                duration_ms: 3,
+            },
+            AuditStatus::Success,
+        )
+    }
+
+    #[test]
+    fn test_hash_args() {
+        let hash = hash_args(&serde_json::json!({}));
+        assert_eq!(
+            hash,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+    }
+
+    #[test]
+    fn test_record_serializes_flat() {
+        let record = record();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["kind"], "tool_call");
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["actor"], "alice");
+        assert!(json.get("args").is_none());
+
+        let back: AuditRecord = serde_json::from_value(json).unwrap();
+        assert_eq!(back, record);
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_sink_appends() {
+        let dir = std::env::temp_dir().join(format!("serdes-ai-audit-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+
+        let sink = JsonlAuditSink::open(&path).await.unwrap();
+        sink.record(&record()).await.unwrap();
+        sink.record(&record()).await.unwrap();
+        drop(sink);
+        let sink = JsonlAuditSink::open(&path).await.unwrap();
+        sink.record(&record()).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<AuditRecord> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -42,23 +42,38 @@
 //!     .build();
 //! ```
 
-use crate::agent::{Agent, EndStrategy, InstrumentationSettings, RegisteredTool, ToolExecutor};
+use crate::agent::{
+    Agent, EndStrategy, InstrumentationSettings, KeyResolverFn, RegisteredTool, RequestSettingsFn,
+    ToolExecutor, ToolFailurePolicy,
+};
+use crate::audit::{AuditLog, AuditedExecutor};
+use crate::confidence::ConfidenceEstimator;
 use crate::context::{RunContext, UsageLimits};
-use crate::errors::OutputValidationError;
-use crate::history::HistoryProcessor;
+use crate::errors::{AgentBuildError, OutputValidationError};
+use crate::history::{HistoryProcessor, HistoryTransform};
 use crate::instructions::{
     AsyncInstructionFn, AsyncSystemPromptFn, InstructionFn, SyncInstructionFn, SyncSystemPromptFn,
     SystemPromptFn,
 };
+use crate::language::LanguageDetector;
 use crate::output::{
-    DefaultOutputSchema, JsonOutputSchema, OutputSchema, OutputValidator, SyncValidator,
-    ToolOutputSchema,
+    envelope_example, CodeEditsValidator, DefaultOutputSchema, JsonOutputSchema, OutputMode,
+    OutputSchema, OutputValidator, SyncValidator, ToolOutputSchema,
 };
+use crate::review::Reviewer;
+use crate::tool_return_limit::ToolReturnLimit;
+use crate::tool_selection::ToolSelection;
+use crate::tool_usage::ToolRouting;
+use crate::usage_meter::UsageMeter;
 use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
-use serdes_ai_core::ModelSettings;
-use serdes_ai_models::{Model, ModelError};
-use serdes_ai_tools::{ToolDefinition, ToolError, ToolReturn};
+use serdes_ai_core::code_edits::{CodeEdits, CODE_EDITS_INSTRUCTIONS};
+use serdes_ai_core::messages::{JsonRepairPass, JsonRepairPolicy, JsonRepairer};
+use serdes_ai_core::{ApiKey, IdGenerator, ModelSettings};
+use serdes_ai_models::{Model, ModelError, PromptedOutputTemplate};
+use serdes_ai_tools::permissions::authorize;
+use serdes_ai_tools::{HasPermissionPolicy, Permissions, ToolDefinition, ToolError, ToolReturn};
+use std::collections::HashSet;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -207,13 +222,17 @@ pub struct AgentBuilder<Deps = (), Output = String> {
     model: Arc<dyn Model>,
     name: Option<String>,
     model_settings: ModelSettings,
+    request_settings_fns: Vec<RequestSettingsFn<Deps>>,
+    key_resolver: Option<KeyResolverFn<Deps>>,
     instructions: Vec<String>,
     instruction_fns: Vec<Box<dyn InstructionFn<Deps>>>,
     system_prompts: Vec<String>,
     system_prompt_fns: Vec<Box<dyn SystemPromptFn<Deps>>>,
     tools: Vec<RegisteredTool<Deps>>,
     output_schema: Option<Box<dyn OutputSchema<Output>>>,
-    output_validators: Vec<Box<dyn OutputValidator<Output, Deps>>>,
+    output_validators: Vec<Arc<dyn OutputValidator<Output, Deps>>>,
+    prompted_output_template: Option<PromptedOutputTemplate>,
+    output_examples: Vec<JsonValue>,
     end_strategy: EndStrategy,
     max_output_retries: u32,
     max_tool_retries: u32,
@@ -222,6 +241,18 @@ pub struct AgentBuilder<Deps = (), Output = String> {
     instrument: Option<InstrumentationSettings>,
     parallel_tool_calls: bool,
     max_concurrent_tools: Option<usize>,
+    slow_tool_threshold: Option<Duration>,
+    json_repair: JsonRepairer,
+    usage_meter: Option<Arc<UsageMeter>>,
+    audit: Option<AuditLog<Deps>>,
+    reviewer: Option<Reviewer>,
+    confidence_estimator: Option<Arc<dyn ConfidenceEstimator>>,
+    json_object_output: bool,
+    language_detector: Option<Arc<dyn LanguageDetector>>,
+    history_transform: Option<Arc<dyn HistoryTransform>>,
+    tool_selection: Option<ToolSelection>,
+    tool_routing: Option<ToolRouting>,
+    id_generator: Option<Arc<dyn IdGenerator>>,
     _phantom: PhantomData<(Deps, Output)>,
 }
 
@@ -271,6 +302,8 @@ where
             model,
             name: None,
             model_settings: ModelSettings::default(),
+            request_settings_fns: Vec::new(),
+            key_resolver: None,
             instructions: Vec::new(),
             instruction_fns: Vec::new(),
             system_prompts: Vec::new(),
@@ -278,6 +311,8 @@ where
             tools: Vec::new(),
             output_schema: None,
             output_validators: Vec::new(),
+            prompted_output_template: None,
+            output_examples: Vec::new(),
             end_strategy: EndStrategy::Early,
             max_output_retries: 3,
             max_tool_retries: 3,
@@ -286,6 +321,18 @@ where
             instrument: None,
             parallel_tool_calls: true,
             max_concurrent_tools: None,
+            slow_tool_threshold: None,
+            json_repair: JsonRepairer::default(),
+            usage_meter: None,
+            audit: None,
+            reviewer: None,
+            confidence_estimator: None,
+            json_object_output: false,
+            language_detector: None,
+            history_transform: None,
+            tool_selection: None,
+            tool_routing: None,
+            id_generator: None,
             _phantom: PhantomData,
         }
     }
@@ -359,12 +406,43 @@ where
     }
 
     /// Set model settings.
+    ///
+    /// These are the agent defaults, the lowest layer of the settings
+    /// precedence: run options and per-request hooks override them.
     #[must_use]
     pub fn model_settings(mut self, settings: ModelSettings) -> Self {
         self.model_settings = settings;
         self
     }
 
+    /// Add a hook returning per-request model setting overrides.
+    ///
+    /// Called before every model request with the run context. The returned
+    /// settings take precedence over both agent defaults and run options;
+    /// hooks are applied in the order they were added.
+    #[must_use]
+    pub fn request_settings_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RunContext<Deps>) -> Option<ModelSettings> + Send + Sync + 'static,
+    {
+        self.request_settings_fns.push(Arc::new(f));
+        self
+    }
+
+    /// Resolve the API key of each run from its dependencies.
+    ///
+    /// Lets a multi-tenant application bill model usage to each customer's
+    /// own key without building a model per tenant. A key set with
+    /// [`RunOptions::api_key`](crate::RunOptions::api_key) takes precedence.
+    #[must_use]
+    pub fn key_resolver<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Deps) -> Option<ApiKey> + Send + Sync + 'static,
+    {
+        self.key_resolver = Some(Arc::new(f));
+        self
+    }
+
     /// Set temperature.
     #[must_use]
     pub fn temperature(mut self, temp: f64) -> Self {
@@ -387,6 +465,9 @@ where
     }
 
     /// Add static instructions.
+    ///
+    /// Unlike system prompts, instructions are sent with the latest request
+    /// only and are not stored in the message history.
     #[must_use]
     pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
         self.instructions.push(instructions.into());
@@ -416,6 +497,15 @@ where
         self
     }
 
+    /// Add a custom instruction, such as a
+    /// [`CombinedInstruction`](crate::instructions::CombinedInstruction) or a
+    /// [`LanguageInstruction`](crate::LanguageInstruction).
+    #[must_use]
+    pub fn instruction<I: InstructionFn<Deps> + 'static>(mut self, instruction: I) -> Self {
+        self.instruction_fns.push(Box::new(instruction));
+        self
+    }
+
     /// Add system prompt.
     #[must_use]
     pub fn system_prompt(mut self, prompt: impl Into<String>) -> Self {
@@ -456,6 +546,8 @@ where
             definition,
             executor: Arc::new(executor),
             max_retries: self.max_tool_retries,
+            return_limit: None,
+            failure_policy: ToolFailurePolicy::default(),
         });
         self
     }
@@ -488,6 +580,8 @@ where
             definition,
             executor: Arc::new(executor),
             max_retries: self.max_tool_retries,
+            return_limit: None,
+            failure_policy: ToolFailurePolicy::default(),
         });
         self
     }
@@ -518,6 +612,8 @@ where
             definition,
             executor: Arc::new(executor),
             max_retries: self.max_tool_retries,
+            return_limit: None,
+            failure_policy: ToolFailurePolicy::default(),
         });
         self
     }
@@ -535,7 +631,7 @@ where
         mut self,
         validator: V,
     ) -> Self {
-        self.output_validators.push(Box::new(validator));
+        self.output_validators.push(Arc::new(validator));
         self
     }
 
@@ -548,7 +644,56 @@ where
             + Sync
             + 'static,
     {
-        self.output_validators.push(Box::new(SyncValidator::new(f)));
+        self.output_validators.push(Arc::new(SyncValidator::new(f)));
+        self
+    }
+
+    /// Set the template for prompted JSON output instructions.
+    ///
+    /// When the output is parsed from JSON text and has a schema, the agent
+    /// adds instructions rendered from this template. Defaults to the model
+    /// profile's template; see [`PromptedOutputTemplate::localized`] for
+    /// translated variants.
+    #[must_use]
+    pub fn prompted_output_template(mut self, template: impl Into<PromptedOutputTemplate>) -> Self {
+        self.prompted_output_template = Some(template.into());
+        self
+    }
+
+    /// Add an example output shown in prompted output instructions.
+    #[must_use]
+    pub fn output_example(mut self, example: JsonValue) -> Self {
+        self.output_examples.push(example);
+        self
+    }
+
+    /// Request JSON output in JSON mode (`response_format: json_object`).
+    ///
+    /// The schema is still given in the prompt and the output validated on
+    /// parse, but the model can only answer with a JSON object. More reliable
+    /// than prompting alone on models with JSON mode but no JSON schema
+    /// support, such as Groq and Mistral models or older OpenAI models.
+    #[must_use]
+    pub fn json_object_output(mut self, enabled: bool) -> Self {
+        self.json_object_output = enabled;
+        self
+    }
+
+    /// Detect the language of each run's prompt.
+    ///
+    /// The language is available as [`RunContext::language`] to instructions,
+    /// tools and validators.
+    #[must_use]
+    pub fn language_detector(mut self, detector: impl LanguageDetector + 'static) -> Self {
+        self.language_detector = Some(Arc::new(detector));
+        self
+    }
+
+    /// Generate this agent's run IDs with `generator` instead of the
+    /// current [`IdGenerator`].
+    #[must_use]
+    pub fn id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.id_generator = Some(Arc::new(generator));
         self
     }
 
@@ -567,12 +712,97 @@ where
     }
 
     /// Set max tool retries.
+    ///
+    /// Applies to tools added after this call.
     #[must_use]
     pub fn max_tool_retries(mut self, retries: u32) -> Self {
         self.max_tool_retries = retries;
         self
     }
 
+    /// Set max retries for an already added tool.
+    ///
+    /// This bounds both in-place retries of retryable failures and how often
+    /// the model may retry the tool after it returns
+    /// [`ToolError::ModelRetry`].
+    #[must_use]
+    pub fn tool_max_retries(mut self, tool_name: &str, retries: u32) -> Self {
+        for tool in &mut self.tools {
+            if tool.definition.name == tool_name {
+                tool.max_retries = retries;
+            }
+        }
+        self
+    }
+
+    /// Limit the size of an already added tool's return content.
+    ///
+    /// Returns over the limit are truncated, stored as an artifact or
+    /// summarized, depending on the limit's strategy.
+    #[must_use]
+    pub fn tool_return_limit(mut self, tool_name: &str, limit: ToolReturnLimit) -> Self {
+        for tool in &mut self.tools {
+            if tool.definition.name == tool_name {
+                tool.return_limit = Some(limit.clone());
+            }
+        }
+        self
+    }
+
+    /// Set what happens when an already added tool is unavailable.
+    ///
+    /// Use [`ToolFailurePolicy::Degrade`] for tools served by a remote
+    /// server, so the run goes on without them when the server is down.
+    #[must_use]
+    pub fn tool_failure_policy(mut self, tool_name: &str, policy: ToolFailurePolicy) -> Self {
+        for tool in &mut self.tools {
+            if tool.definition.name == tool_name {
+                tool.failure_policy = policy;
+            }
+        }
+        self
+    }
+
+    /// Skip repeated calls to an already added tool.
+    ///
+    /// A call with the same arguments as one that already succeeded in the
+    /// run reuses the first result instead of running the tool again. Use
+    /// this for tools that are not idempotent, so a retried step can't write
+    /// twice. See [`ToolExecutor::skip_repeated_calls`].
+    #[must_use]
+    pub fn skip_repeated_tool_calls(mut self, tool_name: &str) -> Self {
+        for tool in &mut self.tools {
+            if tool.definition.name == tool_name {
+                tool.executor = Arc::new(SkipRepeatedExecutor(Arc::clone(&tool.executor)));
+            }
+        }
+        self
+    }
+
+    /// Require scopes for calls to an already added tool.
+    ///
+    /// Each call is checked against the permission policy of the run's
+    /// deps. Calls whose scopes the principal lacks are refused, or answered
+    /// with [`ToolError::ApprovalRequired`] if the policy routes them to
+    /// approval; either way the model is told the tool didn't run. See
+    /// [`serdes_ai_tools::permissions`].
+    #[must_use]
+    pub fn tool_permissions(mut self, tool_name: &str, permissions: Permissions) -> Self
+    where
+        Deps: HasPermissionPolicy,
+    {
+        for tool in &mut self.tools {
+            if tool.definition.name == tool_name {
+                tool.executor = Arc::new(PermissionedExecutor {
+                    inner: Arc::clone(&tool.executor),
+                    tool_name: tool_name.to_string(),
+                    permissions: permissions.clone(),
+                });
+            }
+        }
+        self
+    }
+
     /// Set usage limits.
     #[must_use]
     pub fn usage_limits(mut self, limits: UsageLimits) -> Self {
@@ -587,6 +817,30 @@ where
         self
     }
 
+    /// Only send the tools most relevant to each run's prompt.
+    #[must_use]
+    pub fn tool_selection(mut self, selection: ToolSelection) -> Self {
+        self.tool_selection = Some(selection);
+        self
+    }
+
+    /// Order and prune the tools sent to the model by how often they were
+    /// called in earlier runs.
+    #[must_use]
+    pub fn tool_routing(mut self, routing: ToolRouting) -> Self {
+        self.tool_routing = Some(routing);
+        self
+    }
+
+    /// Transform history before it is returned from runs, e.g. with
+    /// [`RedactHistory`](crate::RedactHistory) to keep raw images and secrets
+    /// out of persisted conversations.
+    #[must_use]
+    pub fn history_transform(mut self, transform: impl HistoryTransform + 'static) -> Self {
+        self.history_transform = Some(Arc::new(transform));
+        self
+    }
+
     /// Enable instrumentation.
     #[must_use]
     pub fn instrument(mut self, settings: InstrumentationSettings) -> Self {
@@ -618,80 +872,359 @@ where
         self
     }
 
+    /// Log a warning for tool calls slower than `threshold`.
+    ///
+    /// Tool call statistics are collected either way and available from
+    /// [`AgentRunResult::tool_stats`](crate::AgentRunResult::tool_stats).
+    #[must_use]
+    pub fn slow_tool_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_tool_threshold = Some(threshold);
+        self
+    }
+
+    /// Set how malformed tool call arguments are repaired.
+    ///
+    /// Defaults to [`JsonRepairPolicy::Aggressive`]. With
+    /// [`JsonRepairPolicy::Off`], unparseable arguments reach tools as
+    /// `{"_raw": ..., "_error": "parse_failed"}`.
+    #[must_use]
+    pub fn json_repair_policy(mut self, policy: JsonRepairPolicy) -> Self {
+        self.json_repair = self.json_repair.with_policy(policy);
+        self
+    }
+
+    /// Add a custom repair pass for tool call arguments.
+    ///
+    /// Custom passes run after the built-in repairs when the arguments still
+    /// fail to parse.
+    #[must_use]
+    pub fn json_repair_pass(mut self, pass: impl JsonRepairPass + 'static) -> Self {
+        self.json_repair = self.json_repair.with_pass(pass);
+        self
+    }
+
+    /// Report usage into a shared meter.
+    ///
+    /// Requests fail with [`UsageLimitError::BudgetExhausted`](crate::UsageLimitError::BudgetExhausted)
+    /// once the meter's budget is used up. Without a meter, the agent reports
+    /// into the process-wide meter, if one is installed.
+    #[must_use]
+    pub fn usage_meter(mut self, meter: Arc<UsageMeter>) -> Self {
+        self.usage_meter = Some(meter);
+        self
+    }
+
+    /// Send an audit record for every tool call and model request.
+    ///
+    /// Applies to all tools, including ones added after this call.
+    #[must_use]
+    pub fn audit(mut self, log: AuditLog<Deps>) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Review the output of each run before returning it.
+    ///
+    /// Takes a model, an agent, or a configured [`Reviewer`]. By default the
+    /// reviewer checks every run and its critique is sent back to the agent
+    /// for one revision. Streamed runs are not reviewed.
+    #[must_use]
+    pub fn with_reviewer(mut self, reviewer: impl Into<Reviewer>) -> Self {
+        self.reviewer = Some(reviewer.into());
+        self
+    }
+
+    /// Set the estimator of [`AgentRunResult::confidence`](crate::AgentRunResult::confidence).
+    ///
+    /// Defaults to [`HeuristicEstimator`](crate::HeuristicEstimator).
+    #[must_use]
+    pub fn confidence_estimator<E: ConfidenceEstimator + 'static>(mut self, estimator: E) -> Self {
+        self.confidence_estimator = Some(Arc::new(estimator));
+        self
+    }
+
     /// Build the agent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is invalid. Use [`try_build`](Self::try_build)
+    /// to handle the error instead.
     pub fn build(self) -> Agent<Deps, Output>
+    where
+        Output: serde::de::DeserializeOwned,
+    {
+        match self.try_build() {
+            Ok(agent) => agent,
+            Err(e) => panic!("Invalid agent configuration: {}", e),
+        }
+    }
+
+    /// Try to build the agent, returning an error if the configuration is invalid.
+    ///
+    /// Checks the whole configuration against the model's profile up front:
+    /// the output mode, tool support and name collisions, model setting ranges,
+    /// and settings that contradict each other.
+    pub fn try_build(self) -> Result<Agent<Deps, Output>, AgentBuildError>
     where
         Output: serde::de::DeserializeOwned,
     {
         let output_schema = self
             .output_schema
             .unwrap_or_else(|| Box::new(DefaultOutputSchema::<Output>::new()));
+        validate_tools(self.model.as_ref(), &self.tools, output_schema.as_ref())?;
+        if self.json_object_output {
+            validate_json_object_output(self.model.as_ref(), output_schema.as_ref())?;
+        }
+        validate_settings(
+            self.model.as_ref(),
+            &self.model_settings,
+            self.max_concurrent_tools,
+        )?;
 
         // Pre-join static system prompts and instructions at build time.
         // This avoids cloning these strings on every run.
-        let static_system_prompt = {
-            let mut parts = Vec::new();
-
-            // Static system prompts first
-            for prompt in &self.system_prompts {
-                if !prompt.is_empty() {
-                    parts.push(prompt.as_str());
-                }
-            }
-
-            // Then static instructions
-            for instruction in &self.instructions {
-                if !instruction.is_empty() {
-                    parts.push(instruction.as_str());
-                }
-            }
-
+        let join = |items: &[String]| -> Arc<str> {
+            let parts: Vec<&str> = items
+                .iter()
+                .map(String::as_str)
+                .filter(|s| !s.is_empty())
+                .collect();
             Arc::from(parts.join("\n\n"))
         };
+        let mut instructions = self.instructions;
+        instructions.extend(prompted_output_instructions(
+            self.model.as_ref(),
+            output_schema.as_ref(),
+            self.prompted_output_template,
+            &self.output_examples,
+        ));
+        let static_system_prompt = join(&self.system_prompts);
+        let static_instructions = join(&instructions);
+
+        let mut tools = self.tools;
+        if let Some(log) = &self.audit {
+            for tool in &mut tools {
+                tool.executor = Arc::new(AuditedExecutor {
+                    inner: Arc::clone(&tool.executor),
+                    log: log.clone(),
+                });
+            }
+        }
 
         // Pre-compute tool definitions at build time.
         // This avoids cloning tool definitions on every agent step.
         let cached_tool_defs = Arc::new(
-            self.tools
+            tools
                 .iter()
                 .map(|t| t.definition.clone())
                 .collect::<Vec<_>>(),
         );
 
-        Agent {
+        Ok(Agent {
             model: self.model,
             name: self.name,
             model_settings: self.model_settings,
+            request_settings_fns: self.request_settings_fns,
+            key_resolver: self.key_resolver,
             static_system_prompt,
-            instruction_fns: self.instruction_fns,
-            system_prompt_fns: self.system_prompt_fns,
-            tools: self.tools,
+            static_instructions,
+            instruction_fns: self.instruction_fns.into(),
+            system_prompt_fns: self.system_prompt_fns.into(),
+            tools,
             cached_tool_defs,
-            output_schema,
+            output_schema: Arc::from(output_schema),
             output_validators: self.output_validators,
             end_strategy: self.end_strategy,
             max_output_retries: self.max_output_retries,
             max_tool_retries: self.max_tool_retries,
             usage_limits: self.usage_limits,
-            history_processors: self.history_processors,
+            history_processors: self.history_processors.into(),
             instrument: self.instrument,
             parallel_tool_calls: self.parallel_tool_calls,
             max_concurrent_tools: self.max_concurrent_tools,
+            slow_tool_threshold: self.slow_tool_threshold,
+            json_repair: self.json_repair,
+            usage_meter: self.usage_meter,
+            audit: self.audit,
+            reviewer: self.reviewer,
+            confidence_estimator: self.confidence_estimator,
+            json_object_output: self.json_object_output,
+            language_detector: self.language_detector,
+            history_transform: self.history_transform,
+            tool_selection: self.tool_selection,
+            tool_routing: self.tool_routing,
+            id_generator: self.id_generator,
             _phantom: PhantomData,
+        })
+    }
+}
+
+/// Check tool names and that the model can serve the tools and output mode.
+/// Render instructions describing the expected JSON output, if it has a schema.
+fn prompted_output_instructions<Output>(
+    model: &dyn Model,
+    output_schema: &dyn OutputSchema<Output>,
+    template: Option<PromptedOutputTemplate>,
+    examples: &[JsonValue],
+) -> Option<String> {
+    if output_schema.mode() != OutputMode::Json {
+        return None;
+    }
+    let schema = serde_json::to_string_pretty(&output_schema.json_schema()?).ok()?;
+    let template = template.unwrap_or_else(|| {
+        PromptedOutputTemplate::new(model.profile().prompted_output_template.as_str())
+    });
+    let examples: Vec<String> = examples
+        .iter()
+        .map(|e| envelope_example(e.clone()).to_string())
+        .collect();
+    Some(template.render(&schema, &examples))
+}
+
+/// Check that JSON mode has JSON output to constrain, on a model that supports it.
+fn validate_json_object_output<Output>(
+    model: &dyn Model,
+    output_schema: &dyn OutputSchema<Output>,
+) -> Result<(), AgentBuildError> {
+    if output_schema.mode() != OutputMode::Json {
+        return Err(AgentBuildError::InvalidConfig(
+            "`json_object_output` needs JSON output; use `output_type` or \
+             `output_type_with_schema`"
+                .to_string(),
+        ));
+    }
+    if !model.profile().supports_json_object_output {
+        return Err(AgentBuildError::UnsupportedOutputMode {
+            mode: OutputMode::Json,
+            model: model.name().to_string(),
+            suggestion: "drop `json_object_output` to rely on the prompted schema alone",
+        });
+    }
+    Ok(())
+}
+
+fn validate_tools<Deps, Output>(
+    model: &dyn Model,
+    tools: &[RegisteredTool<Deps>],
+    output_schema: &dyn OutputSchema<Output>,
+) -> Result<(), AgentBuildError> {
+    let mut names = HashSet::new();
+    for name in tools
+        .iter()
+        .map(|t| t.definition.name.as_str())
+        .chain(output_schema.tool_name())
+    {
+        if !names.insert(name) {
+            return Err(AgentBuildError::DuplicateToolName {
+                name: name.to_string(),
+            });
+        }
+    }
+
+    let profile = model.profile();
+    if !profile.supports_tools {
+        if output_schema.mode() == OutputMode::ToolCall {
+            return Err(AgentBuildError::UnsupportedOutputMode {
+                mode: OutputMode::ToolCall,
+                model: model.name().to_string(),
+                suggestion: "use `output_type_with_schema` to get JSON output as text",
+            });
+        }
+        if !tools.is_empty() {
+            return Err(AgentBuildError::ToolsNotSupported {
+                model: model.name().to_string(),
+                count: tools.len(),
+            });
         }
     }
+    Ok(())
+}
+
+/// Check model setting ranges and contradicting settings.
+fn validate_settings(
+    model: &dyn Model,
+    settings: &ModelSettings,
+    max_concurrent_tools: Option<usize>,
+) -> Result<(), AgentBuildError> {
+    let check_range =
+        |setting: &'static str, value: Option<f64>, min: f64, max: f64, expected: &'static str| {
+            match value {
+                Some(v) if !(min..=max).contains(&v) => Err(AgentBuildError::InvalidSetting {
+                    setting,
+                    value: v.to_string(),
+                    expected,
+                }),
+                _ => Ok(()),
+            }
+        };
+    check_range(
+        "temperature",
+        settings.temperature,
+        0.0,
+        2.0,
+        "a value between 0.0 and 2.0",
+    )?;
+    check_range(
+        "top_p",
+        settings.top_p,
+        0.0,
+        1.0,
+        "a value between 0.0 and 1.0",
+    )?;
+    check_range(
+        "frequency_penalty",
+        settings.frequency_penalty,
+        -2.0,
+        2.0,
+        "a value between -2.0 and 2.0",
+    )?;
+    check_range(
+        "presence_penalty",
+        settings.presence_penalty,
+        -2.0,
+        2.0,
+        "a value between -2.0 and 2.0",
+    )?;
+    if settings.max_tokens == Some(0) {
+        return Err(AgentBuildError::InvalidSetting {
+            setting: "max_tokens",
+            value: "0".to_string(),
+            expected: "at least 1",
+        });
+    }
+    if max_concurrent_tools == Some(0) {
+        return Err(AgentBuildError::InvalidSetting {
+            setting: "max_concurrent_tools",
+            value: "0".to_string(),
+            expected: "at least 1",
+        });
+    }
+
+    if settings.parallel_tool_calls == Some(true) && !model.profile().supports_parallel_tools {
+        return Err(AgentBuildError::ConflictingSettings {
+            first: "ModelSettings::parallel_tool_calls",
+            second: "the model profile",
+            suggestion: "the model can't make parallel tool calls; unset the setting",
+        });
+    }
+    Ok(())
 }
 
 // Specialized builders for output types
 
 impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
     /// Change output type to a JSON-parsed type.
+    ///
+    /// Lists and primitives such as `Vec<T>` or `u32` work too; outputs
+    /// wrapped as `{"items": [...]}` or `{"value": ...}` are unwrapped.
     #[must_use]
     pub fn output_type<T: DeserializeOwned + Send + Sync + 'static>(self) -> AgentBuilder<Deps, T> {
         AgentBuilder {
             model: self.model,
             name: self.name,
             model_settings: self.model_settings,
+            request_settings_fns: self.request_settings_fns,
+            key_resolver: self.key_resolver,
             instructions: self.instructions,
             instruction_fns: self.instruction_fns,
             system_prompts: self.system_prompts,
@@ -699,6 +1232,8 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             tools: self.tools,
             output_schema: Some(Box::new(JsonOutputSchema::<T>::new())),
             output_validators: Vec::new(),
+            prompted_output_template: self.prompted_output_template,
+            output_examples: self.output_examples,
             end_strategy: self.end_strategy,
             max_output_retries: self.max_output_retries,
             max_tool_retries: self.max_tool_retries,
@@ -707,6 +1242,18 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             instrument: self.instrument,
             parallel_tool_calls: self.parallel_tool_calls,
             max_concurrent_tools: self.max_concurrent_tools,
+            slow_tool_threshold: self.slow_tool_threshold,
+            json_repair: self.json_repair,
+            usage_meter: self.usage_meter,
+            audit: self.audit,
+            reviewer: self.reviewer,
+            confidence_estimator: self.confidence_estimator,
+            json_object_output: self.json_object_output,
+            language_detector: self.language_detector,
+            history_transform: self.history_transform,
+            tool_selection: self.tool_selection,
+            tool_routing: self.tool_routing,
+            id_generator: self.id_generator,
             _phantom: PhantomData,
         }
     }
@@ -721,6 +1268,8 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             model: self.model,
             name: self.name,
             model_settings: self.model_settings,
+            request_settings_fns: self.request_settings_fns,
+            key_resolver: self.key_resolver,
             instructions: self.instructions,
             instruction_fns: self.instruction_fns,
             system_prompts: self.system_prompts,
@@ -728,6 +1277,8 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             tools: self.tools,
             output_schema: Some(Box::new(JsonOutputSchema::<T>::new().with_schema(schema))),
             output_validators: Vec::new(),
+            prompted_output_template: self.prompted_output_template,
+            output_examples: self.output_examples,
             end_strategy: self.end_strategy,
             max_output_retries: self.max_output_retries,
             max_tool_retries: self.max_tool_retries,
@@ -736,6 +1287,18 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             instrument: self.instrument,
             parallel_tool_calls: self.parallel_tool_calls,
             max_concurrent_tools: self.max_concurrent_tools,
+            slow_tool_threshold: self.slow_tool_threshold,
+            json_repair: self.json_repair,
+            usage_meter: self.usage_meter,
+            audit: self.audit,
+            reviewer: self.reviewer,
+            confidence_estimator: self.confidence_estimator,
+            json_object_output: self.json_object_output,
+            language_detector: self.language_detector,
+            history_transform: self.history_transform,
+            tool_selection: self.tool_selection,
+            tool_routing: self.tool_routing,
+            id_generator: self.id_generator,
             _phantom: PhantomData,
         }
     }
@@ -751,6 +1314,8 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             model: self.model,
             name: self.name,
             model_settings: self.model_settings,
+            request_settings_fns: self.request_settings_fns,
+            key_resolver: self.key_resolver,
             instructions: self.instructions,
             instruction_fns: self.instruction_fns,
             system_prompts: self.system_prompts,
@@ -760,6 +1325,8 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
                 ToolOutputSchema::<T>::new(tool_name).with_schema(schema),
             )),
             output_validators: Vec::new(),
+            prompted_output_template: self.prompted_output_template,
+            output_examples: self.output_examples,
             end_strategy: self.end_strategy,
             max_output_retries: self.max_output_retries,
             max_tool_retries: self.max_tool_retries,
@@ -768,9 +1335,34 @@ impl<Deps: Send + Sync + 'static> AgentBuilder<Deps, String> {
             instrument: self.instrument,
             parallel_tool_calls: self.parallel_tool_calls,
             max_concurrent_tools: self.max_concurrent_tools,
+            slow_tool_threshold: self.slow_tool_threshold,
+            json_repair: self.json_repair,
+            usage_meter: self.usage_meter,
+            audit: self.audit,
+            reviewer: self.reviewer,
+            confidence_estimator: self.confidence_estimator,
+            json_object_output: self.json_object_output,
+            language_detector: self.language_detector,
+            history_transform: self.history_transform,
+            tool_selection: self.tool_selection,
+            tool_routing: self.tool_routing,
+            id_generator: self.id_generator,
             _phantom: PhantomData,
         }
     }
+
+    /// Output [`CodeEdits`] for coding agents.
+    ///
+    /// Adds instructions describing the edit format and a
+    /// [`CodeEditsValidator`] checking paths. Add
+    /// [`CodeEditsValidator::with_snapshot`] to also check that the edits
+    /// apply to the workspace.
+    #[must_use]
+    pub fn output_code_edits(self) -> AgentBuilder<Deps, CodeEdits> {
+        self.instructions(CODE_EDITS_INSTRUCTIONS)
+            .output_type_with_schema::<CodeEdits>(CodeEdits::json_schema())
+            .output_validator(CodeEditsValidator::new())
+    }
 }
 
 // ============================================================================
@@ -795,6 +1387,60 @@ impl<Deps: Send + Sync> ToolExecutor<Deps> for SyncFnExecutor<Deps> {
     }
 }
 
+/// Executor wrapper that opts a tool into skipping repeated calls.
+struct SkipRepeatedExecutor<Deps>(Arc<dyn ToolExecutor<Deps>>);
+
+#[async_trait::async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for SkipRepeatedExecutor<Deps> {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        self.0.execute(args, ctx).await
+    }
+
+    fn skip_repeated_calls(&self) -> bool {
+        true
+    }
+
+    async fn health_check(&self) -> Result<(), ToolError> {
+        self.0.health_check().await
+    }
+}
+
+/// Executor wrapper that checks the principal's permissions first.
+struct PermissionedExecutor<Deps> {
+    inner: Arc<dyn ToolExecutor<Deps>>,
+    tool_name: String,
+    permissions: Permissions,
+}
+
+#[async_trait::async_trait]
+impl<Deps: HasPermissionPolicy + Send + Sync> ToolExecutor<Deps> for PermissionedExecutor<Deps> {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        authorize(
+            ctx.deps.permission_policy(),
+            &self.tool_name,
+            &self.permissions,
+            &args,
+        )?;
+        self.inner.execute(args, ctx).await
+    }
+
+    fn skip_repeated_calls(&self) -> bool {
+        self.inner.skip_repeated_calls()
+    }
+
+    async fn health_check(&self) -> Result<(), ToolError> {
+        self.inner.health_check().await
+    }
+}
+
 /// Async function executor.
 struct AsyncFnExecutor<F, Deps, Args, Fut>
 where
@@ -841,7 +1487,7 @@ pub fn agent_with_deps<Deps: Send + Sync + 'static, M: Model + 'static>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serdes_ai_models::MockModel;
+    use serdes_ai_models::{MockModel, ModelProfile};
 
     fn create_mock_model() -> MockModel {
         MockModel::new("test-model")
@@ -867,9 +1513,38 @@ mod tests {
             .instructions("Be concise.")
             .build();
 
-        // Static prompts are now pre-joined at build time
-        assert!(agent.static_system_prompt.contains("You are helpful."));
-        assert!(agent.static_system_prompt.contains("Be concise."));
+        // Instructions are kept apart from the system prompt
+        assert_eq!(agent.static_system_prompt(), "You are helpful.");
+        assert_eq!(agent.static_instructions(), "Be concise.");
+    }
+
+    #[test]
+    fn test_prompted_output_instructions() {
+        let schema = serde_json::json!({"type": "array", "items": {"type": "string"}});
+
+        let agent = AgentBuilder::<(), String>::new(create_mock_model())
+            .instructions("Be concise.")
+            .output_example(serde_json::json!(["a", "b"]))
+            .output_type_with_schema::<Vec<String>>(schema.clone())
+            .build();
+        let instructions = agent.static_instructions();
+        assert!(instructions.starts_with("Be concise.\n\nOutput your response as JSON"));
+        assert!(instructions.contains(r#"{"items":["a","b"]}"#));
+
+        let agent = AgentBuilder::<(), String>::new(create_mock_model())
+            .prompted_output_template(PromptedOutputTemplate::localized("de").unwrap())
+            .output_type_with_schema::<Vec<String>>(schema)
+            .build();
+        assert!(agent
+            .static_instructions()
+            .starts_with("Gib deine Antwort als JSON"));
+        assert!(!agent.static_instructions().contains("{examples}"));
+
+        // Text output gets no schema instructions
+        let agent = AgentBuilder::<(), String>::new(create_mock_model())
+            .prompted_output_template("{schema}")
+            .build();
+        assert_eq!(agent.static_instructions(), "");
     }
 
     #[test]
@@ -965,6 +1640,127 @@ mod tests {
         assert_eq!(agent.max_concurrent_tools(), Some(2));
     }
 
+    #[test]
+    fn test_try_build_duplicate_tool_name() {
+        let builder = AgentBuilder::<(), String>::new(create_mock_model())
+            .tool_fn("greet", "Greet", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("hi"))
+            })
+            .tool_fn("greet", "Greet again", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("hi"))
+            });
+
+        assert!(matches!(
+            builder.try_build(),
+            Err(AgentBuildError::DuplicateToolName { name }) if name == "greet"
+        ));
+    }
+
+    #[test]
+    fn test_try_build_output_tool_collision() {
+        let builder = AgentBuilder::<(), String>::new(create_mock_model())
+            .tool_fn("final_result", "Clashes", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("hi"))
+            })
+            .output_tool::<JsonValue>("final_result", serde_json::json!({"type": "object"}));
+
+        assert!(matches!(
+            builder.try_build(),
+            Err(AgentBuildError::DuplicateToolName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_try_build_unsupported_output_mode() {
+        let model = create_mock_model().with_profile(ModelProfile {
+            supports_tools: false,
+            ..Default::default()
+        });
+        let err = AgentBuilder::<(), String>::new(model)
+            .output_tool::<JsonValue>("final_result", serde_json::json!({"type": "object"}))
+            .try_build()
+            .err()
+            .unwrap();
+
+        assert!(matches!(
+            err,
+            AgentBuildError::UnsupportedOutputMode {
+                mode: OutputMode::ToolCall,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("output_type_with_schema"));
+    }
+
+    #[tokio::test]
+    async fn test_json_object_output() {
+        let model =
+            create_mock_model().with_profile(ModelProfile::new().with_json_object_output(true));
+        let agent = AgentBuilder::<(), String>::new(model)
+            .output_type_with_schema::<JsonValue>(serde_json::json!({"type": "object"}))
+            .json_object_output(true)
+            .build();
+        assert!(agent.static_instructions().contains("JSON"));
+
+        let mut run = agent
+            .start_run("hi", (), crate::RunOptions::new())
+            .await
+            .unwrap();
+        let request = run.prepare_request().await.unwrap();
+        assert_eq!(request.params.output_mode.to_string(), "json_object");
+
+        // Needs JSON output and a model with JSON mode
+        let err = AgentBuilder::<(), String>::new(create_mock_model())
+            .json_object_output(true)
+            .try_build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, AgentBuildError::InvalidConfig(_)));
+
+        let err = AgentBuilder::<(), String>::new(create_mock_model())
+            .output_type::<JsonValue>()
+            .json_object_output(true)
+            .try_build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, AgentBuildError::UnsupportedOutputMode { .. }));
+    }
+
+    #[test]
+    fn test_try_build_invalid_settings() {
+        let err = AgentBuilder::<(), String>::new(create_mock_model())
+            .temperature(3.0)
+            .try_build()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            AgentBuildError::InvalidSetting {
+                setting: "temperature",
+                ..
+            }
+        ));
+
+        let model = create_mock_model().with_profile(ModelProfile {
+            supports_parallel_tools: false,
+            ..Default::default()
+        });
+        let err = AgentBuilder::<(), String>::new(model)
+            .model_settings(ModelSettings::new().parallel_tool_calls(true))
+            .try_build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, AgentBuildError::ConflictingSettings { .. }));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid agent configuration")]
+    fn test_build_panics_on_invalid_config() {
+        let _ = AgentBuilder::<(), String>::new(create_mock_model())
+            .top_p(1.5)
+            .build();
+    }
+
     #[test]
     fn test_builder_from_arc() {
         let model = create_mock_model();
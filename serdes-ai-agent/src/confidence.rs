@@ -0,0 +1,201 @@
+//! Confidence estimation for run outputs.
+//!
+//! Every [`AgentRunResult`](crate::AgentRunResult) carries a confidence score
+//! between 0 and 1, so applications can route uncertain answers to a human.
+//! The score is computed by a [`ConfidenceEstimator`] from the
+//! [`ConfidenceSignals`] of the run:
+//!
+//! - the log probabilities of the final response, when the model returned
+//!   them (see [`ModelSettings::logprobs`](serdes_ai_core::ModelSettings::logprobs))
+//! - how often the output failed parsing or validation
+//! - how many tool calls had malformed arguments that were repaired
+//! - the outcome of the reviewer pass, if any
+//!
+//! Without a configured estimator, [`HeuristicEstimator`] combines these
+//! signals. [`JudgeEstimator`] asks a model to score the answer instead.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, JudgeEstimator};
+//!
+//! let agent = agent(model)
+//!     .confidence_estimator(JudgeEstimator::new(judge_model))
+//!     .build();
+//!
+//! let result = agent.run("Is this contract clause enforceable?", ()).await?;
+//! if result.confidence() < 0.7 {
+//!     escalate_to_human(&result.output).await;
+//! }
+//! ```
+
+use crate::agent::Agent;
+use crate::review::Review;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serdes_ai_models::Model;
+use std::fmt;
+
+/// Factor applied per failed output parse or validation.
+pub const RETRY_PENALTY: f64 = 0.8;
+
+/// Factor applied per repaired tool call.
+pub const REPAIR_PENALTY: f64 = 0.9;
+
+const JUDGE_SYSTEM_PROMPT: &str = "You rate how likely an AI assistant's answer is to be \
+    correct and complete. Reply with a single number from 0 to 100 and nothing else.";
+
+/// What an estimator knows about a finished run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceSignals {
+    /// The user prompt of the run.
+    pub prompt: String,
+    /// The output, as text or JSON.
+    pub output: String,
+    /// Mean log probability of the final response's tokens, if returned.
+    pub mean_logprob: Option<f64>,
+    /// Number of times the output failed parsing or validation.
+    pub output_retries: u32,
+    /// Number of tool calls whose arguments had to be repaired.
+    pub repairs: u32,
+    /// Outcome of the reviewer pass, if the run was reviewed.
+    pub review: Option<Review>,
+}
+
+/// Scores the confidence of a run's output.
+#[async_trait]
+pub trait ConfidenceEstimator: Send + Sync {
+    /// Estimate a confidence between 0 and 1.
+    ///
+    /// Returning `None` falls back to [`HeuristicEstimator`].
+    async fn estimate(&self, signals: &ConfidenceSignals) -> Option<f64>;
+}
+
+#[async_trait]
+impl<F> ConfidenceEstimator for F
+where
+    F: Fn(&ConfidenceSignals) -> f64 + Send + Sync,
+{
+    async fn estimate(&self, signals: &ConfidenceSignals) -> Option<f64> {
+        Some(self(signals))
+    }
+}
+
+/// Estimator combining the run's signals without extra requests.
+///
+/// Starts from the mean token probability (1 without log probabilities),
+/// applies [`RETRY_PENALTY`] per output retry and [`REPAIR_PENALTY`] per
+/// repaired tool call, and lowers the score when a reviewer found problems:
+/// to 80% if the answer was revised, to 50% if it was not.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicEstimator;
+
+impl HeuristicEstimator {
+    /// Score a run's signals.
+    pub fn score(&self, signals: &ConfidenceSignals) -> f64 {
+        let mut score = signals.mean_logprob.map_or(1.0, f64::exp);
+        score *= RETRY_PENALTY.powi(signals.output_retries as i32);
+        score *= REPAIR_PENALTY.powi(signals.repairs as i32);
+        match &signals.review {
+            Some(review) if !review.approved && review.revised => score *= 0.8,
+            Some(review) if !review.approved => score *= 0.5,
+            _ => {}
+        }
+        score.clamp(0.0, 1.0)
+    }
+}
+
+#[async_trait]
+impl ConfidenceEstimator for HeuristicEstimator {
+    async fn estimate(&self, signals: &ConfidenceSignals) -> Option<f64> {
+        Some(self.score(signals))
+    }
+}
+
+/// Estimator asking a judge model to score the answer.
+///
+/// The judge's usage is not added to the run's usage. A reply without a
+/// number falls back to [`HeuristicEstimator`].
+#[derive(Clone)]
+pub struct JudgeEstimator {
+    agent: Agent<(), String>,
+}
+
+impl JudgeEstimator {
+    /// Create an estimator backed by a judge model.
+    pub fn new<M: Model + 'static>(model: M) -> Self {
+        Self {
+            agent: crate::builder::agent(model)
+                .system_prompt(JUDGE_SYSTEM_PROMPT)
+                .build(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConfidenceEstimator for JudgeEstimator {
+    async fn estimate(&self, signals: &ConfidenceSignals) -> Option<f64> {
+        let request = format!(
+            "<request>\n{}\n</request>\n\n<answer>\n{}\n</answer>",
+            signals.prompt, signals.output
+        );
+        let reply = Box::pin(self.agent.run(request, ())).await.ok()?.output;
+        parse_score(&reply)
+    }
+}
+
+impl fmt::Debug for JudgeEstimator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JudgeEstimator")
+            .field("model", &self.agent.model().name())
+            .finish()
+    }
+}
+
+/// Parse a 0-100 score from the start of a judge's reply.
+fn parse_score(reply: &str) -> Option<f64> {
+    let number: String = reply
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let score = number.parse::<f64>().ok()?;
+    Some((score / 100.0).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_penalties() {
+        let clean = ConfidenceSignals::default();
+        assert_eq!(HeuristicEstimator.score(&clean), 1.0);
+
+        let shaky = ConfidenceSignals {
+            mean_logprob: Some(-0.1),
+            output_retries: 1,
+            repairs: 1,
+            ..Default::default()
+        };
+        let expected = (-0.1f64).exp() * RETRY_PENALTY * REPAIR_PENALTY;
+        assert!((HeuristicEstimator.score(&shaky) - expected).abs() < 1e-9);
+
+        let rejected = ConfidenceSignals {
+            review: Some(Review {
+                critique: "Wrong year.".into(),
+                approved: false,
+                revised: false,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(HeuristicEstimator.score(&rejected), 0.5);
+    }
+
+    #[test]
+    fn test_parse_score() {
+        assert_eq!(parse_score("85"), Some(0.85));
+        assert_eq!(parse_score(" 100.0\n"), Some(1.0));
+        assert_eq!(parse_score("high"), None);
+    }
+}
@@ -3,9 +3,12 @@
 //! The context contains all information about the current agent run,
 //! including dependencies, settings, and execution state.
 
-use chrono::{DateTime, Utc};
+use crate::language::Language;
+use chrono::{DateTime, FixedOffset, Offset, Utc};
 use serde_json::Value as JsonValue;
+use serdes_ai_core::now_utc;
 use serdes_ai_core::ModelSettings;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 /// Context for an agent run.
@@ -28,8 +31,24 @@ pub struct RunContext<Deps> {
     pub tool_name: Option<String>,
     /// Current tool call ID (if any).
     pub tool_call_id: Option<String>,
+    /// Idempotency key of the current tool call (if any).
+    ///
+    /// Derived from the run, the tool and its arguments, so the same call
+    /// made again when a step is retried gets the same key. Tools with side
+    /// effects can pass it on to downstream services to avoid double writes.
+    pub idempotency_key: Option<String>,
     /// Current retry count.
+    ///
+    /// For tool contexts, the number of times the model has retried this tool
+    /// after it returned [`ToolError::ModelRetry`](serdes_ai_tools::ToolError::ModelRetry).
     pub retry_count: u32,
+    /// Maximum retries allowed for the current tool (0 outside of tools).
+    pub max_retries: u32,
+    /// Language of the run's prompt, if the agent has a
+    /// [`LanguageDetector`](crate::LanguageDetector) and it found one.
+    pub language: Option<Language>,
+    /// Timezone of the run's user (UTC unless set in the run options).
+    pub timezone: FixedOffset,
     /// Custom metadata.
     pub metadata: Option<JsonValue>,
 }
@@ -40,12 +59,16 @@ impl<Deps> RunContext<Deps> {
         Self {
             deps: Arc::new(deps),
             run_id: generate_run_id(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             model_name: model_name.into(),
             model_settings: ModelSettings::default(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: None,
+            timezone: Utc.fix(),
             metadata: None,
         }
     }
@@ -55,12 +78,16 @@ impl<Deps> RunContext<Deps> {
         Self {
             deps,
             run_id: generate_run_id(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             model_name: model_name.into(),
             model_settings: ModelSettings::default(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: None,
+            timezone: Utc.fix(),
             metadata: None,
         }
     }
@@ -72,7 +99,7 @@ impl<Deps> RunContext<Deps> {
 
     /// Get elapsed time since run started.
     pub fn elapsed(&self) -> chrono::Duration {
-        Utc::now() - self.start_time
+        now_utc() - self.start_time
     }
 
     /// Get elapsed time in seconds.
@@ -85,6 +112,45 @@ impl<Deps> RunContext<Deps> {
         self.retry_count > 0
     }
 
+    /// Check if this is the last retry allowed for the current tool.
+    pub fn is_last_retry(&self) -> bool {
+        self.retry_count >= self.max_retries
+    }
+
+    /// Set the retry count and limit.
+    #[must_use]
+    pub fn with_retries(mut self, retry_count: u32, max_retries: u32) -> Self {
+        self.retry_count = retry_count;
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the idempotency key of the current tool call.
+    #[must_use]
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Set the detected language.
+    #[must_use]
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set the timezone of the run's user.
+    #[must_use]
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Get the run's start time in the run's timezone.
+    pub fn local_start_time(&self) -> DateTime<FixedOffset> {
+        self.start_time.with_timezone(&self.timezone)
+    }
+
     /// Check if we're currently in a tool execution.
     pub fn in_tool(&self) -> bool {
         self.tool_name.is_some()
@@ -120,7 +186,11 @@ impl<Deps> RunContext<Deps> {
             model_settings: self.model_settings.clone(),
             tool_name: Some(tool_name.into()),
             tool_call_id,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: self.language.clone(),
+            timezone: self.timezone,
             metadata: self.metadata.clone(),
         }
     }
@@ -135,7 +205,11 @@ impl<Deps> RunContext<Deps> {
             model_settings: self.model_settings.clone(),
             tool_name: self.tool_name.clone(),
             tool_call_id: self.tool_call_id.clone(),
+            idempotency_key: self.idempotency_key.clone(),
             retry_count: self.retry_count + 1,
+            max_retries: self.max_retries,
+            language: self.language.clone(),
+            timezone: self.timezone,
             metadata: self.metadata.clone(),
         }
     }
@@ -157,15 +231,36 @@ impl<Deps> Clone for RunContext<Deps> {
             model_settings: self.model_settings.clone(),
             tool_name: self.tool_name.clone(),
             tool_call_id: self.tool_call_id.clone(),
+            idempotency_key: self.idempotency_key.clone(),
             retry_count: self.retry_count,
+            max_retries: self.max_retries,
+            language: self.language.clone(),
+            timezone: self.timezone,
             metadata: self.metadata.clone(),
         }
     }
 }
 
-/// Generate a unique run ID.
+/// Generate a unique run ID with the current
+/// [`IdGenerator`](serdes_ai_core::IdGenerator).
 pub fn generate_run_id() -> String {
-    uuid::Uuid::new_v4().to_string()
+    serdes_ai_core::identifier::generate_run_id()
+}
+
+/// Derive the idempotency key of a tool call.
+///
+/// The key only depends on the run, the tool name and the arguments, so a
+/// call repeated when a step is retried gets the same key even though its
+/// tool call ID differs.
+pub fn idempotency_key(run_id: &str, tool_name: &str, args: &JsonValue) -> String {
+    // FNV-1a, so keys are stable across processes and Rust versions.
+    let hash = [run_id, tool_name, &args.to_string()]
+        .iter()
+        .flat_map(|s| s.bytes().chain(std::iter::once(0)))
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{tool_name}-{hash:016x}")
 }
 
 /// Usage tracking for a run.
@@ -185,6 +280,68 @@ pub struct RunUsage {
     pub cache_creation_tokens: Option<u64>,
     /// Cache read tokens.
     pub cache_read_tokens: Option<u64>,
+    /// Usage of each model request, in order.
+    pub entries: Vec<UsageEntry>,
+}
+
+/// Usage of a single model request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageEntry {
+    /// Step of the run the request was made in (1-based). Each agent step
+    /// makes one model request.
+    pub step: u32,
+    /// Model that served the request, if known.
+    pub model_name: Option<String>,
+    /// Token counts of the request.
+    pub tokens: UsageTotals,
+}
+
+/// Token counts summed over one or more model requests.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageTotals {
+    /// Number of model requests.
+    pub requests: u32,
+    /// Request (prompt) tokens.
+    pub request_tokens: u64,
+    /// Response (completion) tokens.
+    pub response_tokens: u64,
+    /// Total tokens.
+    pub total_tokens: u64,
+    /// Tokens written to the prompt cache.
+    pub cache_write_tokens: u64,
+    /// Tokens read from the prompt cache.
+    pub cache_read_tokens: u64,
+    /// Reasoning tokens, when the provider reports them.
+    pub reasoning_tokens: u64,
+}
+
+impl UsageTotals {
+    pub(crate) fn from_request(usage: &serdes_ai_core::RequestUsage) -> Self {
+        Self {
+            requests: 1,
+            request_tokens: usage.request_tokens.unwrap_or(0),
+            response_tokens: usage.response_tokens.unwrap_or(0),
+            total_tokens: usage.total(),
+            cache_write_tokens: usage.cache_creation_tokens.unwrap_or(0),
+            cache_read_tokens: usage.cache_read_tokens.unwrap_or(0),
+            reasoning_tokens: usage
+                .details
+                .as_ref()
+                .and_then(|d| d.get("reasoning_tokens"))
+                .and_then(JsonValue::as_u64)
+                .unwrap_or(0),
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.request_tokens += other.request_tokens;
+        self.response_tokens += other.response_tokens;
+        self.total_tokens += other.total_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+    }
 }
 
 impl RunUsage {
@@ -193,6 +350,66 @@ impl RunUsage {
         Self::default()
     }
 
+    /// Add usage from a request to the named model.
+    pub fn add_model_request(
+        &mut self,
+        model_name: impl Into<String>,
+        usage: serdes_ai_core::RequestUsage,
+    ) {
+        self.add_request(usage);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.model_name = Some(model_name.into());
+        }
+    }
+
+    /// Add usage from another run, e.g. a delegated agent.
+    ///
+    /// Its entries keep their model names and are renumbered to follow
+    /// this run's steps.
+    pub fn add_run(&mut self, other: &RunUsage) {
+        self.request_tokens += other.request_tokens;
+        self.response_tokens += other.response_tokens;
+        self.total_tokens += other.total_tokens;
+        self.request_count += other.request_count;
+        self.tool_call_count += other.tool_call_count;
+        if let Some(cache) = other.cache_creation_tokens {
+            *self.cache_creation_tokens.get_or_insert(0) += cache;
+        }
+        if let Some(cache) = other.cache_read_tokens {
+            *self.cache_read_tokens.get_or_insert(0) += cache;
+        }
+        let offset = self.entries.last().map_or(0, |e| e.step);
+        self.entries
+            .extend(other.entries.iter().map(|e| UsageEntry {
+                step: e.step + offset,
+                ..e.clone()
+            }));
+    }
+
+    /// Usage totals per model name.
+    ///
+    /// Requests without a known model are listed under `"unknown"`.
+    pub fn by_model(&self) -> BTreeMap<String, UsageTotals> {
+        let mut totals: BTreeMap<String, UsageTotals> = BTreeMap::new();
+        for entry in &self.entries {
+            let name = entry.model_name.as_deref().unwrap_or("unknown");
+            totals
+                .entry(name.to_string())
+                .or_default()
+                .add(&entry.tokens);
+        }
+        totals
+    }
+
+    /// Usage totals per step.
+    pub fn by_step(&self) -> BTreeMap<u32, UsageTotals> {
+        let mut totals: BTreeMap<u32, UsageTotals> = BTreeMap::new();
+        for entry in &self.entries {
+            totals.entry(entry.step).or_default().add(&entry.tokens);
+        }
+        totals
+    }
+
     /// Add usage from a model request.
     pub fn add_request(&mut self, usage: serdes_ai_core::RequestUsage) {
         if let Some(req) = usage.request_tokens {
@@ -213,6 +430,11 @@ impl RunUsage {
             *self.cache_read_tokens.get_or_insert(0) += cache;
         }
         self.request_count += 1;
+        self.entries.push(UsageEntry {
+            step: self.request_count,
+            model_name: None,
+            tokens: UsageTotals::from_request(&usage),
+        });
     }
 
     /// Record a tool call.
@@ -236,6 +458,11 @@ pub struct UsageLimits {
     pub max_tool_calls: Option<u32>,
     /// Maximum run time in seconds.
     pub max_time_seconds: Option<u64>,
+    /// Estimate each request's prompt tokens before sending it.
+    ///
+    /// Requests that would exceed `max_total_tokens` or the model's context
+    /// window are rejected without calling the provider.
+    pub preflight: bool,
 }
 
 impl UsageLimits {
@@ -280,6 +507,51 @@ impl UsageLimits {
         self
     }
 
+    /// Enable pre-flight token estimation for each request.
+    pub fn preflight(mut self, enabled: bool) -> Self {
+        self.preflight = enabled;
+        self
+    }
+
+    /// Check an estimated request against the limits before it is sent.
+    ///
+    /// Fails when the estimate plus the tokens already used would exceed
+    /// `max_total_tokens`, or when the estimate alone exceeds the context
+    /// window. Always passes unless [`preflight`](Self::preflight) is enabled.
+    pub fn check_preflight(
+        &self,
+        usage: &RunUsage,
+        estimated_tokens: u64,
+        context_window: Option<u64>,
+    ) -> Result<(), crate::errors::UsageLimitError> {
+        use crate::errors::UsageLimitError;
+
+        if !self.preflight {
+            return Ok(());
+        }
+
+        if let Some(limit) = self.max_total_tokens {
+            if usage.total_tokens + estimated_tokens > limit {
+                return Err(UsageLimitError::EstimatedTotalTokens {
+                    used: usage.total_tokens,
+                    estimated: estimated_tokens,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = context_window {
+            if estimated_tokens > limit {
+                return Err(UsageLimitError::ContextWindow {
+                    estimated: estimated_tokens,
+                    limit,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check usage against limits.
     pub fn check(&self, usage: &RunUsage) -> Result<(), crate::errors::UsageLimitError> {
         use crate::errors::UsageLimitError;
@@ -376,6 +648,20 @@ mod tests {
         assert!(tool_ctx.in_tool());
     }
 
+    #[test]
+    fn test_idempotency_key() {
+        let args = serde_json::json!({"to": "a@example.com", "body": "hi"});
+        let key = idempotency_key("run-1", "send_email", &args);
+
+        assert_eq!(key, idempotency_key("run-1", "send_email", &args.clone()));
+        assert!(key.starts_with("send_email-"));
+        assert_ne!(key, idempotency_key("run-2", "send_email", &args));
+        assert_ne!(
+            key,
+            idempotency_key("run-1", "send_email", &serde_json::json!({"to": "b"}))
+        );
+    }
+
     #[test]
     fn test_run_usage() {
         let mut usage = RunUsage::new();
@@ -393,6 +679,36 @@ mod tests {
         assert_eq!(usage.request_count, 1);
     }
 
+    #[test]
+    fn test_run_usage_breakdown() {
+        use serdes_ai_core::RequestUsage;
+
+        let mut usage = RunUsage::new();
+        usage.add_model_request(
+            "gpt-4o",
+            RequestUsage::with_tokens(100, 20)
+                .cache_read_tokens(80)
+                .details(serde_json::json!({"reasoning_tokens": 5})),
+        );
+        usage.add_model_request("claude", RequestUsage::with_tokens(50, 10));
+
+        let mut delegated = RunUsage::new();
+        delegated.add_model_request("gpt-4o", RequestUsage::with_tokens(10, 5));
+        usage.add_run(&delegated);
+
+        let by_model = usage.by_model();
+        assert_eq!(by_model["gpt-4o"].requests, 2);
+        assert_eq!(by_model["gpt-4o"].request_tokens, 110);
+        assert_eq!(by_model["gpt-4o"].cache_read_tokens, 80);
+        assert_eq!(by_model["gpt-4o"].reasoning_tokens, 5);
+        assert_eq!(by_model["claude"].total_tokens, 60);
+
+        let by_step = usage.by_step();
+        assert_eq!(by_step.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(by_step[&3].total_tokens, 15);
+        assert_eq!(usage.total_tokens, 195);
+    }
+
     #[test]
     fn test_usage_limits() {
         let limits = UsageLimits::new().total_tokens(1000).requests(10);
@@ -406,4 +722,32 @@ mod tests {
         usage.total_tokens = 1500;
         assert!(limits.check(&usage).is_err());
     }
+
+    #[test]
+    fn test_preflight_limits() {
+        let mut usage = RunUsage::new();
+        usage.total_tokens = 800;
+
+        // Disabled by default
+        let limits = UsageLimits::new().total_tokens(1000);
+        assert!(limits.check_preflight(&usage, 500, Some(100)).is_ok());
+
+        let limits = limits.preflight(true);
+        assert!(limits.check_preflight(&usage, 100, None).is_ok());
+        assert!(matches!(
+            limits.check_preflight(&usage, 300, None),
+            Err(crate::errors::UsageLimitError::EstimatedTotalTokens {
+                used: 800,
+                estimated: 300,
+                limit: 1000
+            })
+        ));
+        assert!(matches!(
+            limits.check_preflight(&RunUsage::new(), 150, Some(100)),
+            Err(crate::errors::UsageLimitError::ContextWindow {
+                estimated: 150,
+                limit: 100
+            })
+        ));
+    }
 }
@@ -0,0 +1,395 @@
+//! Persisting conversations.
+//!
+//! A [`ConversationStore`] keeps [`ConversationBranch`]es by
+//! [`ConversationId`], so a conversation can be picked up again after the
+//! process restarts, or inspected after an unattended run (see
+//! [`Scheduler`](crate::Scheduler)).
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{ConversationStore, FileConversationStore};
+//!
+//! let store = FileConversationStore::open("conversations")?;
+//! let result = agent.run("Plan a trip to Rome", ()).await?;
+//! let conversation = result.fork();
+//! store.save(&conversation)?;
+//!
+//! let restored = store.load(conversation.id())?.unwrap();
+//! ```
+//!
+//! With the `encryption` feature, [`FileConversationStore`] can encrypt
+//! conversations at rest with AES-256-GCM:
+//!
+//! ```rust,ignore
+//! use serdes_ai_core::encryption::EnvKeyProvider;
+//!
+//! let store = FileConversationStore::open("conversations")?
+//!     .with_encryption(&EnvKeyProvider::default())?;
+//! ```
+
+use crate::fork::ConversationBranch;
+use serdes_ai_core::ConversationId;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Storage for conversation histories.
+pub trait ConversationStore: Send + Sync {
+    /// Save a conversation, replacing any earlier version with the same ID.
+    fn save(&self, conversation: &ConversationBranch) -> io::Result<()>;
+
+    /// Load a conversation by ID.
+    fn load(&self, id: &ConversationId) -> io::Result<Option<ConversationBranch>>;
+
+    /// List the IDs of all stored conversations.
+    fn list(&self) -> io::Result<Vec<ConversationId>>;
+
+    /// Delete a conversation, returning whether it existed.
+    fn delete(&self, id: &ConversationId) -> io::Result<bool>;
+}
+
+impl<S: ConversationStore + ?Sized> ConversationStore for Arc<S> {
+    fn save(&self, conversation: &ConversationBranch) -> io::Result<()> {
+        (**self).save(conversation)
+    }
+
+    fn load(&self, id: &ConversationId) -> io::Result<Option<ConversationBranch>> {
+        (**self).load(id)
+    }
+
+    fn list(&self) -> io::Result<Vec<ConversationId>> {
+        (**self).list()
+    }
+
+    fn delete(&self, id: &ConversationId) -> io::Result<bool> {
+        (**self).delete(id)
+    }
+}
+
+/// In-memory [`ConversationStore`].
+#[derive(Default)]
+pub struct InMemoryConversationStore {
+    conversations: RwLock<HashMap<ConversationId, ConversationBranch>>,
+}
+
+impl InMemoryConversationStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored conversations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.conversations.read().map_or(0, |c| c.len())
+    }
+
+    /// Check if the store is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn poisoned() -> io::Error {
+    io::Error::other("conversation store lock poisoned")
+}
+
+impl ConversationStore for InMemoryConversationStore {
+    fn save(&self, conversation: &ConversationBranch) -> io::Result<()> {
+        self.conversations
+            .write()
+            .map_err(|_| poisoned())?
+            .insert(conversation.id().clone(), conversation.clone());
+        Ok(())
+    }
+
+    fn load(&self, id: &ConversationId) -> io::Result<Option<ConversationBranch>> {
+        Ok(self
+            .conversations
+            .read()
+            .map_err(|_| poisoned())?
+            .get(id)
+            .cloned())
+    }
+
+    fn list(&self) -> io::Result<Vec<ConversationId>> {
+        let mut ids: Vec<_> = self
+            .conversations
+            .read()
+            .map_err(|_| poisoned())?
+            .keys()
+            .cloned()
+            .collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        Ok(ids)
+    }
+
+    fn delete(&self, id: &ConversationId) -> io::Result<bool> {
+        Ok(self
+            .conversations
+            .write()
+            .map_err(|_| poisoned())?
+            .remove(id)
+            .is_some())
+    }
+}
+
+impl fmt::Debug for InMemoryConversationStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryConversationStore")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// [`ConversationStore`] keeping each conversation in a `<id>.json` file.
+///
+/// With the `encryption` feature, files can be encrypted at rest with
+/// [`with_encryption`](Self::with_encryption).
+#[derive(Debug)]
+pub struct FileConversationStore {
+    dir: PathBuf,
+    #[cfg(feature = "encryption")]
+    cipher: Option<Arc<serdes_ai_core::encryption::FileCipher>>,
+    #[cfg(feature = "encryption")]
+    plaintext_migration: bool,
+}
+
+impl FileConversationStore {
+    /// Open a store in `dir`, creating the directory if needed.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            #[cfg(feature = "encryption")]
+            cipher: None,
+            #[cfg(feature = "encryption")]
+            plaintext_migration: false,
+        })
+    }
+
+    /// Encrypt files with AES-256-GCM using the provider's key.
+    ///
+    /// Once a key is set, plaintext files are rejected, since anyone able to
+    /// write to the directory could use them to inject conversations. See
+    /// [`with_plaintext_migration`](Self::with_plaintext_migration).
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(
+        mut self,
+        provider: &dyn serdes_ai_core::encryption::KeyProvider,
+    ) -> io::Result<Self> {
+        let cipher = serdes_ai_core::encryption::FileCipher::new(provider)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.cipher = Some(Arc::new(cipher));
+        Ok(self)
+    }
+
+    /// Also read plaintext files when encryption is enabled.
+    ///
+    /// Meant for migrating files written before encryption was turned on;
+    /// they are encrypted the next time they are saved. Off by default.
+    #[cfg(feature = "encryption")]
+    #[must_use]
+    pub fn with_plaintext_migration(mut self, allow: bool) -> Self {
+        self.plaintext_migration = allow;
+        self
+    }
+
+    /// Get the directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the path of a conversation's file.
+    ///
+    /// Returns `None` for IDs that are not safe to use as a file name.
+    #[must_use]
+    pub fn path(&self, id: &ConversationId) -> Option<PathBuf> {
+        let id = id.as_str();
+        let valid = !id.is_empty()
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'));
+        valid.then(|| self.dir.join(format!("{id}.json")))
+    }
+
+    /// Encrypt a file's content, if a key is set.
+    #[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+    fn seal(&self, path: &Path, content: Vec<u8>) -> io::Result<Vec<u8>> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = &self.cipher {
+            return cipher
+                .encrypt(&content, file_name(path).as_bytes())
+                .map_err(io::Error::other);
+        }
+        Ok(content)
+    }
+
+    /// Decrypt a file's content, rejecting plaintext once a key is set.
+    #[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+    fn unseal(&self, path: &Path, data: Vec<u8>) -> io::Result<Vec<u8>> {
+        #[cfg(feature = "encryption")]
+        {
+            let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+            if serdes_ai_core::encryption::FileCipher::is_encrypted(&data) {
+                let cipher = self.cipher.as_ref().ok_or_else(|| {
+                    invalid(format!(
+                        "{} is encrypted but no key is configured",
+                        path.display()
+                    ))
+                })?;
+                return cipher
+                    .decrypt(&data, file_name(path).as_bytes())
+                    .map_err(|e| invalid(format!("{}: {}", path.display(), e)));
+            }
+            if self.cipher.is_some() && !self.plaintext_migration {
+                return Err(invalid(format!(
+                    "{} is not encrypted; enable plaintext migration to read it",
+                    path.display()
+                )));
+            }
+        }
+        Ok(data)
+    }
+
+    fn path_or_err(&self, id: &ConversationId) -> io::Result<PathBuf> {
+        self.path(id).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid conversation id '{id}'"),
+            )
+        })
+    }
+}
+
+impl ConversationStore for FileConversationStore {
+    fn save(&self, conversation: &ConversationBranch) -> io::Result<()> {
+        let path = self.path_or_err(conversation.id())?;
+        let json = self.seal(&path, serde_json::to_vec_pretty(conversation)?)?;
+        // Write then rename, so readers never see a partial file
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &path)
+    }
+
+    fn load(&self, id: &ConversationId) -> io::Result<Option<ConversationBranch>> {
+        let Some(path) = self.path(id) else {
+            return Ok(None);
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&self.unseal(&path, bytes)?)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn list(&self) -> io::Result<Vec<ConversationId>> {
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    ids.push(ConversationId::from_string(stem));
+                }
+            }
+        }
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        Ok(ids)
+    }
+
+    fn delete(&self, id: &ConversationId) -> io::Result<bool> {
+        let Some(path) = self.path(id) else {
+            return Ok(false);
+        };
+        match std::fs::remove_file(path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::ModelRequest;
+
+    fn conversation(prompt: &str) -> ConversationBranch {
+        let mut request = ModelRequest::new();
+        request.add_user_prompt(prompt);
+        ConversationBranch::new(vec![request])
+    }
+
+    #[test]
+    fn test_file_store_round_trip() {
+        let dir =
+            std::env::temp_dir().join(format!("serdes-conversations-{}", uuid::Uuid::new_v4()));
+        let store = FileConversationStore::open(&dir).unwrap();
+        let first = conversation("hello");
+        store.save(&first).unwrap();
+        store.save(&conversation("bye")).unwrap();
+
+        let loaded = store.load(first.id()).unwrap().unwrap();
+        assert_eq!(loaded.id(), first.id());
+        assert_eq!(loaded.user_prompts().len(), 1);
+        assert_eq!(store.list().unwrap().len(), 2);
+
+        assert!(store.delete(first.id()).unwrap());
+        assert!(store.load(first.id()).unwrap().is_none());
+        assert!(store
+            .load(&ConversationId::from_string("../escape"))
+            .unwrap()
+            .is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_file_store_encrypted_round_trip() {
+        use serdes_ai_core::encryption::EncryptionKey;
+
+        let dir =
+            std::env::temp_dir().join(format!("serdes-conversations-{}", uuid::Uuid::new_v4()));
+        let key = EncryptionKey::generate().unwrap();
+        let store = FileConversationStore::open(&dir)
+            .unwrap()
+            .with_encryption(&key)
+            .unwrap();
+        let first = conversation("secret plans");
+        store.save(&first).unwrap();
+
+        let raw = std::fs::read(store.path(first.id()).unwrap()).unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("secret plans"));
+        let loaded = store.load(first.id()).unwrap().unwrap();
+        assert_eq!(loaded.id(), first.id());
+        assert_eq!(store.list().unwrap(), vec![first.id().clone()]);
+
+        // Encrypted files need the key; plaintext files need migration
+        let plain = FileConversationStore::open(&dir).unwrap();
+        assert!(plain.load(first.id()).is_err());
+        let second = conversation("hello");
+        plain.save(&second).unwrap();
+        assert!(store.load(second.id()).is_err());
+        let migrating = FileConversationStore::open(&dir)
+            .unwrap()
+            .with_encryption(&key)
+            .unwrap()
+            .with_plaintext_migration(true);
+        assert!(migrating.load(second.id()).unwrap().is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,285 @@
+//! Resolving relative dates.
+//!
+//! Models are unreliable at calendar arithmetic. [`RelativeDateResolver`] is
+//! a tool that turns phrases like "next Tuesday" or "in 3 weeks" into ISO
+//! dates, counted from today in the run's timezone (see
+//! [`RunOptions::timezone`](crate::RunOptions::timezone)).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, DateTimeInstruction, RelativeDateResolver, RunOptions};
+//!
+//! let agent = agent(model)
+//!     .instruction(DateTimeInstruction::new())
+//!     .tool_with_executor(RelativeDateResolver::definition(), RelativeDateResolver::new())
+//!     .build();
+//!
+//! let tz = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+//! let options = RunOptions::new().timezone(tz);
+//! agent.run_with_options("Book the dentist for next Tuesday", (), options).await?;
+//! ```
+//!
+//! Supported phrases:
+//!
+//! - `today`, `tomorrow`, `yesterday`, `the day after tomorrow`,
+//!   `the day before yesterday`
+//! - `in 3 days`, `in a week`, `two months from now`, `5 days ago`
+//! - `monday`, `this friday` (today or the next one), `next friday` (the
+//!   next one after today), `last friday`
+//! - `next week`, `last month`, `next year`
+//! - `end of the month`, `end of the week`
+//! - ISO dates such as `2025-06-03`, returned unchanged
+
+use crate::agent::ToolExecutor;
+use crate::context::RunContext;
+use async_trait::async_trait;
+use chrono::{Datelike, Days, FixedOffset, Months, NaiveDate, Weekday};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serdes_ai_core::now_utc;
+use serdes_ai_tools::{SchemaBuilder, ToolDefinition, ToolError, ToolReturn};
+
+/// Name of the [`RelativeDateResolver`] tool.
+pub const RESOLVE_DATE_TOOL: &str = "resolve_date";
+
+/// Tool converting relative date phrases to ISO dates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelativeDateResolver {
+    timezone: Option<FixedOffset>,
+}
+
+impl RelativeDateResolver {
+    /// Create a resolver counting from today in the run's timezone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count from today in `timezone` instead of the run's timezone.
+    #[must_use]
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Get the tool definition.
+    pub fn definition() -> ToolDefinition {
+        ToolDefinition::new(
+            RESOLVE_DATE_TOOL,
+            "Convert a relative date such as 'next Tuesday' or 'in 3 days' to an ISO date",
+        )
+        .with_parameters(
+            SchemaBuilder::new()
+                .string("expression", "The relative date, e.g. 'next Tuesday'", true)
+                .build()
+                .expect("SchemaBuilder JSON serialization failed"),
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ResolveArgs {
+    expression: String,
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for RelativeDateResolver {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        let args: ResolveArgs = serde_json::from_value(args)
+            .map_err(|e| ToolError::invalid_arguments(RESOLVE_DATE_TOOL, e.to_string()))?;
+        let timezone = self.timezone.unwrap_or(ctx.timezone);
+        let today = now_utc().with_timezone(&timezone).date_naive();
+        let date = resolve_relative_date(&args.expression, today).ok_or_else(|| {
+            ToolError::model_retry(format!(
+                "Could not resolve '{}'. Use a phrase like 'next Tuesday', 'in 3 days' or \
+                 'end of the month'.",
+                args.expression
+            ))
+        })?;
+        Ok(ToolReturn::json(serde_json::json!({
+            "expression": args.expression,
+            "date": date.format("%Y-%m-%d").to_string(),
+            "weekday": date.format("%A").to_string(),
+        })))
+    }
+}
+
+/// Resolve a relative date phrase against `today`.
+///
+/// Returns `None` for phrases that are not understood.
+pub fn resolve_relative_date(expression: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let normalized = expression
+        .trim()
+        .trim_end_matches(['.', '?', '!'])
+        .to_lowercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&normalized, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let words: Vec<&str> = normalized
+        .split_whitespace()
+        .filter(|w| !matches!(*w, "the" | "on"))
+        .collect();
+
+    match words.as_slice() {
+        ["today" | "now"] => Some(today),
+        ["tomorrow"] => today.checked_add_days(Days::new(1)),
+        ["yesterday"] => today.checked_sub_days(Days::new(1)),
+        ["day", "after", "tomorrow"] => today.checked_add_days(Days::new(2)),
+        ["day", "before", "yesterday"] => today.checked_sub_days(Days::new(2)),
+        ["end", "of", "month"] | ["end", "of", "this", "month"] => {
+            let first = today.with_day(1)?;
+            first
+                .checked_add_months(Months::new(1))?
+                .checked_sub_days(Days::new(1))
+        }
+        ["end", "of", "week"] | ["end", "of", "this", "week"] => {
+            let until_sunday = 6 - today.weekday().num_days_from_monday();
+            today.checked_add_days(Days::new(u64::from(until_sunday)))
+        }
+        [day] | ["this", day] => {
+            let weekday = day.parse::<Weekday>().ok()?;
+            today.checked_add_days(Days::new(days_until(today.weekday(), weekday, 0)))
+        }
+        ["next", unit] | ["last", unit] => {
+            let sign = if words[0] == "next" { 1 } else { -1 };
+            if let Ok(weekday) = unit.parse::<Weekday>() {
+                return if sign > 0 {
+                    today.checked_add_days(Days::new(days_until(today.weekday(), weekday, 1)))
+                } else {
+                    let back = days_until(weekday, today.weekday(), 1);
+                    today.checked_sub_days(Days::new(back))
+                };
+            }
+            shift(today, sign, unit_of(unit)?)
+        }
+        ["in", count, unit] => shift(today, count_of(count)?, unit_of(unit)?),
+        [count, unit, "from", "now"] => shift(today, count_of(count)?, unit_of(unit)?),
+        [count, unit, "ago"] => shift(today, -count_of(count)?, unit_of(unit)?),
+        _ => None,
+    }
+}
+
+/// Days from `from` to the next `to`, at least `min` days ahead.
+fn days_until(from: Weekday, to: Weekday, min: u64) -> u64 {
+    let days = u64::from((7 + to.num_days_from_monday() - from.num_days_from_monday()) % 7);
+    if days < min {
+        days + 7
+    } else {
+        days
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+fn unit_of(word: &str) -> Option<Unit> {
+    match word.trim_end_matches('s') {
+        "day" => Some(Unit::Day),
+        "week" => Some(Unit::Week),
+        "month" => Some(Unit::Month),
+        "year" => Some(Unit::Year),
+        _ => None,
+    }
+}
+
+fn count_of(word: &str) -> Option<i64> {
+    const WORDS: [&str; 12] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven",
+        "twelve",
+    ];
+    match word {
+        "a" | "an" => Some(1),
+        _ => word
+            .parse()
+            .ok()
+            .or_else(|| WORDS.iter().position(|w| *w == word).map(|i| i as i64 + 1)),
+    }
+}
+
+fn shift(date: NaiveDate, count: i64, unit: Unit) -> Option<NaiveDate> {
+    let magnitude = count.unsigned_abs();
+    let (days, months) = match unit {
+        Unit::Day => (magnitude, 0),
+        Unit::Week => (magnitude.checked_mul(7)?, 0),
+        Unit::Month => (0, u32::try_from(magnitude).ok()?),
+        Unit::Year => (0, u32::try_from(magnitude).ok()?.checked_mul(12)?),
+    };
+    if count >= 0 {
+        date.checked_add_days(Days::new(days))?
+            .checked_add_months(Months::new(months))
+    } else {
+        date.checked_sub_days(Days::new(days))?
+            .checked_sub_months(Months::new(months))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(expression: &str) -> Option<String> {
+        // A Saturday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        resolve_relative_date(expression, today).map(|d| d.to_string())
+    }
+
+    #[test]
+    fn test_resolve_relative_dates() {
+        let cases = [
+            ("today", "2026-10-17"),
+            ("Tomorrow", "2026-10-18"),
+            ("the day after tomorrow", "2026-10-19"),
+            ("yesterday", "2026-10-16"),
+            ("next Tuesday", "2026-10-20"),
+            ("on Tuesday", "2026-10-20"),
+            ("this saturday", "2026-10-17"),
+            ("next saturday", "2026-10-24"),
+            ("last friday", "2026-10-16"),
+            ("last saturday", "2026-10-10"),
+            ("in 2 weeks", "2026-10-31"),
+            ("in a month", "2026-11-17"),
+            ("three days from now", "2026-10-20"),
+            ("5 days ago", "2026-10-12"),
+            ("next year", "2027-10-17"),
+            ("end of the month", "2026-10-31"),
+            ("end of week", "2026-10-18"),
+            ("2025-06-03", "2025-06-03"),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(
+                resolve(expression).as_deref(),
+                Some(expected),
+                "{expression}"
+            );
+        }
+        assert_eq!(resolve("sometime soon"), None);
+        assert_eq!(resolve("in many days"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolver_tool() {
+        let ctx = RunContext::new((), "test");
+        let result = RelativeDateResolver::new()
+            .execute(serde_json::json!({"expression": "gibberish"}), &ctx)
+            .await;
+        assert!(result.unwrap_err().is_model_retry());
+
+        let result = RelativeDateResolver::new()
+            .execute(serde_json::json!({"expression": "2025-06-03"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.as_json().unwrap()["weekday"],
+            serde_json::json!("Tuesday")
+        );
+    }
+}
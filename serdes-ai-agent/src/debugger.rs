@@ -0,0 +1,248 @@
+//! Step-through debugging for agent runs.
+//!
+//! An [`AgentDebugger`] drives a run one model request at a time. Before each
+//! request is sent, the exact messages, settings and parameters are exposed
+//! and can be edited. Every completed step is recorded in a timeline of
+//! [`DebugFrame`]s, and the run can be rewound to any earlier frame to try a
+//! different request from there.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{AgentDebugger, RunOptions};
+//!
+//! let mut debugger = AgentDebugger::new(&agent, "Plan my trip", deps, RunOptions::new()).await?;
+//!
+//! while !debugger.is_finished() {
+//!     let request = debugger.next_request().await?;
+//!     request.settings = request.settings.clone().temperature(0.0);
+//!     debugger.step().await?;
+//! }
+//!
+//! // Go back to before the second request and try again
+//! debugger.rewind(1)?;
+//! debugger.next_request().await?.messages.pop();
+//! debugger.step().await?;
+//! ```
+
+use crate::agent::Agent;
+use crate::errors::AgentRunError;
+use crate::run::{AgentRun, AgentRunResult, PreparedRequest, RunOptions, RunSnapshot, StepResult};
+use serdes_ai_core::messages::UserContent;
+use serdes_ai_core::{ModelRequest, ModelResponse};
+
+/// One completed step of a debugged run.
+#[derive(Debug, Clone)]
+pub struct DebugFrame {
+    /// Step number, starting at 1.
+    pub step: u32,
+    /// The request as it was sent, including any edits.
+    pub request: PreparedRequest,
+    /// The model's response.
+    pub response: ModelResponse,
+    /// What the step did.
+    pub result: StepResult,
+    /// Message history after the step.
+    pub messages: Vec<ModelRequest>,
+}
+
+/// Runs an agent one model request at a time, recording a timeline.
+pub struct AgentDebugger<'a, Deps, Output> {
+    run: AgentRun<'a, Deps, Output>,
+    pending: Option<(RunSnapshot, PreparedRequest)>,
+    timeline: Vec<DebugFrame>,
+    /// Run state before each frame, parallel to `timeline`.
+    snapshots: Vec<RunSnapshot>,
+}
+
+impl<'a, Deps, Output> AgentDebugger<'a, Deps, Output>
+where
+    Deps: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    /// Start a debugged run.
+    pub async fn new(
+        agent: &'a Agent<Deps, Output>,
+        prompt: impl Into<UserContent>,
+        deps: Deps,
+        options: RunOptions,
+    ) -> Result<Self, AgentRunError> {
+        let run = agent.start_run(prompt, deps, options).await?;
+        Ok(Self {
+            run,
+            pending: None,
+            timeline: Vec::new(),
+            snapshots: Vec::new(),
+        })
+    }
+
+    /// Get the request the next step will send.
+    ///
+    /// The request is built on the first call and kept until it is sent, so
+    /// edits made through the returned reference are what [`step`](Self::step)
+    /// dispatches.
+    pub async fn next_request(&mut self) -> Result<&mut PreparedRequest, AgentRunError> {
+        if self.pending.is_none() {
+            let snapshot = self.run.snapshot();
+            match self.run.prepare_request().await {
+                Ok(request) => self.pending = Some((snapshot, request)),
+                Err(e) => {
+                    self.run.restore(snapshot);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(&mut self.pending.as_mut().expect("request prepared above").1)
+    }
+
+    /// Send the next request and record the step in the timeline.
+    ///
+    /// If the step fails, the run is rolled back to before it, so the request
+    /// can be rebuilt and edited again.
+    pub async fn step(&mut self) -> Result<StepResult, AgentRunError> {
+        if self.run.is_finished() {
+            return Ok(StepResult::Finished);
+        }
+        self.next_request().await?;
+        let (snapshot, request) = self.pending.take().expect("request prepared above");
+
+        match self.run.dispatch(request.clone()).await {
+            Ok(result) => {
+                let response = self
+                    .run
+                    .responses()
+                    .last()
+                    .cloned()
+                    .expect("dispatch stores the response");
+                self.timeline.push(DebugFrame {
+                    step: self.run.step_number(),
+                    request,
+                    response,
+                    result: result.clone(),
+                    messages: self.run.messages().to_vec(),
+                });
+                self.snapshots.push(snapshot);
+                Ok(result)
+            }
+            Err(e) => {
+                self.run.restore(snapshot);
+                Err(e)
+            }
+        }
+    }
+
+    /// Rewind the run to just before the frame at `index`.
+    ///
+    /// That frame and all later ones are dropped from the timeline. Token
+    /// usage is not rolled back.
+    pub fn rewind(&mut self, index: usize) -> Result<(), AgentRunError> {
+        if index >= self.snapshots.len() {
+            return Err(AgentRunError::Other(anyhow::anyhow!(
+                "no frame {index} to rewind to ({} recorded)",
+                self.snapshots.len()
+            )));
+        }
+        let snapshot = self
+            .snapshots
+            .drain(index..)
+            .next()
+            .expect("index checked above");
+        self.timeline.truncate(index);
+        self.pending = None;
+        self.run.restore(snapshot);
+        Ok(())
+    }
+
+    /// Get the recorded steps.
+    pub fn timeline(&self) -> &[DebugFrame] {
+        &self.timeline
+    }
+
+    /// Get a recorded step.
+    pub fn frame(&self, index: usize) -> Option<&DebugFrame> {
+        self.timeline.get(index)
+    }
+
+    /// Get the underlying run.
+    pub fn run(&self) -> &AgentRun<'a, Deps, Output> {
+        &self.run
+    }
+
+    /// Check if the run has produced its output.
+    pub fn is_finished(&self) -> bool {
+        self.run.is_finished()
+    }
+
+    /// Finish debugging and return the run's result.
+    pub fn finish(self) -> Result<AgentRunResult<Output>, AgentRunError> {
+        self.run.finalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent;
+    use serdes_ai_core::FinishReason;
+    use serdes_ai_models::FunctionModel;
+
+    /// Replies with the last user prompt it was sent.
+    fn echo_model() -> FunctionModel {
+        FunctionModel::new(|messages: &[ModelRequest], _| {
+            let prompt = messages
+                .iter()
+                .flat_map(|m| m.user_prompts())
+                .last()
+                .map(|p| format!("{:?}", p.content))
+                .unwrap_or_default();
+            ModelResponse::text(prompt).with_finish_reason(FinishReason::Stop)
+        })
+    }
+
+    fn prompt(text: &str) -> Vec<ModelRequest> {
+        let mut request = ModelRequest::new();
+        request.add_user_prompt(text);
+        vec![request]
+    }
+
+    #[tokio::test]
+    async fn test_edit_request_before_dispatch() {
+        let agent = agent(echo_model()).build();
+        let mut debugger = AgentDebugger::new(&agent, "original", (), RunOptions::new())
+            .await
+            .unwrap();
+
+        let request = debugger.next_request().await.unwrap();
+        request.messages = prompt("edited");
+        assert_eq!(debugger.step().await.unwrap(), StepResult::OutputReady);
+
+        assert!(debugger.is_finished());
+        let frame = debugger.frame(0).unwrap();
+        assert_eq!(frame.step, 1);
+        assert!(frame.response.text_content().contains("edited"));
+
+        let result = debugger.finish().unwrap();
+        assert!(result.output().contains("edited"));
+    }
+
+    #[tokio::test]
+    async fn test_rewind_restores_state() {
+        let agent = agent(echo_model()).build();
+        let mut debugger = AgentDebugger::new(&agent, "first", (), RunOptions::new())
+            .await
+            .unwrap();
+        debugger.step().await.unwrap();
+        assert_eq!(debugger.timeline().len(), 1);
+
+        debugger.rewind(0).unwrap();
+        assert!(debugger.timeline().is_empty());
+        assert!(!debugger.is_finished());
+        assert_eq!(debugger.run().step_number(), 0);
+        assert!(debugger.rewind(0).is_err());
+
+        let request = debugger.next_request().await.unwrap();
+        request.messages = prompt("second");
+        debugger.step().await.unwrap();
+        assert!(debugger.finish().unwrap().output().contains("second"));
+    }
+}
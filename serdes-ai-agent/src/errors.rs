@@ -2,8 +2,10 @@
 //!
 //! This module defines all errors that can occur during agent execution.
 
+use crate::output::OutputMode;
 use serdes_ai_models::ModelError;
 use serdes_ai_tools::ToolError;
+use std::fmt;
 use thiserror::Error;
 
 /// Errors that can occur during agent run execution.
@@ -67,6 +69,10 @@ pub enum AgentRunError {
     #[error("Provider error: {0}")]
     Provider(String),
 
+    /// Writing streamed output to a sink failed.
+    #[error("Stream sink error: {0}")]
+    Sink(#[source] std::io::Error),
+
     /// Other error.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -99,11 +105,88 @@ impl AgentRunError {
             Self::Cancelled => false,
             Self::Timeout { .. } => false,
             Self::MaxRetriesExceeded { .. } => false,
+            Self::Sink(_) => false,
             _ => true,
         }
     }
 }
 
+/// A field of the output that failed parsing or validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// JSON Pointer to the field, e.g. `/items/0/age`; empty for the root.
+    pub path: String,
+    /// What the field should be.
+    pub expected: Option<String>,
+    /// What the model produced.
+    pub found: Option<String>,
+    /// The violated constraint, e.g. `required` or `minimum: 0`.
+    pub constraint: Option<String>,
+    /// Error message.
+    pub message: String,
+}
+
+impl FieldError {
+    /// Create a field error.
+    ///
+    /// `path` is a JSON Pointer; a bare field name such as `age` is turned
+    /// into `/age`.
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            path: if path.is_empty() || path.starts_with('/') {
+                path
+            } else {
+                format!("/{}", path)
+            },
+            expected: None,
+            found: None,
+            constraint: None,
+            message: message.into(),
+        }
+    }
+
+    /// Set what the field should be.
+    #[must_use]
+    pub fn with_expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    /// Set what the model produced.
+    #[must_use]
+    pub fn with_found(mut self, found: impl Into<String>) -> Self {
+        self.found = Some(found.into());
+        self
+    }
+
+    /// Set the violated constraint.
+    #[must_use]
+    pub fn with_constraint(mut self, constraint: impl Into<String>) -> Self {
+        self.constraint = Some(constraint.into());
+        self
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+fn join_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Output validation error.
 #[derive(Debug, Error)]
 pub enum OutputValidationError {
@@ -132,6 +215,10 @@ pub enum OutputValidationError {
     /// Missing required field.
     #[error("Missing required field: {0}")]
     MissingField(String),
+
+    /// Individual fields are invalid.
+    #[error("Invalid output fields: {}", join_field_errors(.0))]
+    Fields(Vec<FieldError>),
 }
 
 impl OutputValidationError {
@@ -156,6 +243,26 @@ impl OutputValidationError {
         Self::Custom(message.into())
     }
 
+    /// Create an error listing the invalid fields.
+    pub fn fields(errors: Vec<FieldError>) -> Self {
+        Self::Fields(errors)
+    }
+
+    /// Get the fields that failed, if known.
+    pub fn field_errors(&self) -> Vec<FieldError> {
+        match self {
+            Self::ValidationFailed {
+                message,
+                field: Some(field),
+            } => vec![FieldError::new(field.as_str(), message.as_str())],
+            Self::MissingField(field) => {
+                vec![FieldError::new(field.as_str(), "missing field").with_constraint("required")]
+            }
+            Self::Fields(errors) => errors.clone(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Get the error message for retry prompts.
     pub fn retry_message(&self) -> String {
         match self {
@@ -173,6 +280,17 @@ impl OutputValidationError {
             Self::MissingField(field) => {
                 format!("Missing required field: {}", field)
             }
+            Self::Fields(errors) => {
+                let mut message = String::from(
+                    "The output has invalid fields. Fix only these fields and keep \
+                     everything else unchanged:",
+                );
+                for error in errors {
+                    message.push_str("\n- ");
+                    message.push_str(&error.to_string());
+                }
+                message
+            }
         }
     }
 }
@@ -199,6 +317,10 @@ pub enum OutputParseError {
     /// Schema mismatch.
     #[error("Output does not match schema: {0}")]
     SchemaMismatch(String),
+
+    /// Valid JSON with fields that don't match the output type.
+    #[error("Invalid output fields: {}", join_field_errors(.0))]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl OutputParseError {
@@ -243,6 +365,37 @@ pub enum UsageLimitError {
         limit: u64,
     },
 
+    /// A request would exceed the total token limit, judging by its estimate.
+    #[error(
+        "Request would exceed total token limit: {used} used + ~{estimated} estimated > {limit}"
+    )]
+    EstimatedTotalTokens {
+        /// Tokens used so far.
+        used: u64,
+        /// Estimated prompt tokens of the request.
+        estimated: u64,
+        /// Token limit.
+        limit: u64,
+    },
+
+    /// A request would not fit the model's context window.
+    #[error("Request exceeds the model context window: ~{estimated} > {limit} tokens")]
+    ContextWindow {
+        /// Estimated prompt tokens of the request.
+        estimated: u64,
+        /// Context window size.
+        limit: u64,
+    },
+
+    /// The shared token budget of a [`UsageMeter`](crate::UsageMeter) is used up.
+    #[error("Token budget exhausted: {used} >= {budget}")]
+    BudgetExhausted {
+        /// Tokens used.
+        used: u64,
+        /// Token budget.
+        budget: u64,
+    },
+
     /// Request count limit exceeded.
     #[error("Request count limit exceeded: {count} > {limit}")]
     RequestCount {
@@ -285,6 +438,58 @@ pub enum AgentBuildError {
     /// Tool registration error.
     #[error("Tool registration error: {0}")]
     ToolRegistration(String),
+
+    /// Two tools (or a tool and the output tool) share a name.
+    #[error("Tool name '{name}' is used more than once; give each tool a unique name")]
+    DuplicateToolName {
+        /// The duplicated name.
+        name: String,
+    },
+
+    /// Tools are registered but the model can't call them.
+    #[error(
+        "Model '{model}' does not support tool calling but {count} tool(s) are registered; \
+         remove the tools or pick a model with tool support"
+    )]
+    ToolsNotSupported {
+        /// Model name.
+        model: String,
+        /// Number of registered tools.
+        count: usize,
+    },
+
+    /// The output mode isn't supported by the model.
+    #[error("Output mode {mode:?} is not supported by model '{model}'; {suggestion}")]
+    UnsupportedOutputMode {
+        /// The requested output mode.
+        mode: OutputMode,
+        /// Model name.
+        model: String,
+        /// How to fix it.
+        suggestion: &'static str,
+    },
+
+    /// A setting is out of range.
+    #[error("Invalid value {value} for `{setting}`; expected {expected}")]
+    InvalidSetting {
+        /// Setting name.
+        setting: &'static str,
+        /// The rejected value.
+        value: String,
+        /// The accepted range.
+        expected: &'static str,
+    },
+
+    /// Two settings contradict each other.
+    #[error("`{first}` conflicts with `{second}`; {suggestion}")]
+    ConflictingSettings {
+        /// First setting.
+        first: &'static str,
+        /// Second setting.
+        second: &'static str,
+        /// How to fix it.
+        suggestion: &'static str,
+    },
 }
 
 #[cfg(test)]
@@ -308,6 +513,23 @@ mod tests {
         let err = OutputValidationError::field_failed("name", "too short");
         assert!(err.retry_message().contains("name"));
         assert!(err.retry_message().contains("too short"));
+        assert_eq!(err.field_errors()[0].path, "/name");
+    }
+
+    #[test]
+    fn test_field_errors_retry_message() {
+        let err = OutputValidationError::fields(vec![
+            FieldError::new("/items/0/age", "must be positive")
+                .with_constraint("minimum: 0")
+                .with_found("-1"),
+            FieldError::new("/items/1/name", "missing field").with_constraint("required"),
+        ]);
+
+        let message = err.retry_message();
+        assert!(message.contains("Fix only these fields"));
+        assert!(message.contains("\n- /items/0/age: must be positive"));
+        assert!(message.contains("\n- /items/1/name: missing field"));
+        assert_eq!(err.field_errors().len(), 2);
     }
 
     #[test]
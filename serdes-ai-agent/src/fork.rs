@@ -0,0 +1,312 @@
+//! Conversation branching.
+//!
+//! [`AgentRunResult::fork`] turns a finished run into a [`ConversationBranch`],
+//! a history handle that can be branched to edit a past user message and
+//! regenerate, or to continue from an earlier step. Every branch has its own
+//! [`ConversationId`] and remembers the one it was forked from.
+//!
+//! ```rust,ignore
+//! let result = agent.run("Plan a trip to Rome", ()).await?;
+//! let conversation = result.fork();
+//!
+//! // Edit the first user message and regenerate
+//! let mut edited = conversation.edit_user_message(0).unwrap();
+//! let result = edited.run(&agent, "Plan a trip to Florence", ()).await?;
+//! assert_eq!(edited.parent_id(), Some(conversation.id()));
+//! ```
+
+use crate::agent::Agent;
+use crate::errors::AgentRunError;
+use crate::run::{AgentRunResult, RunOptions};
+use serde::{Deserialize, Serialize};
+use serdes_ai_core::messages::{ModelResponse, UserContent, UserPromptPart};
+use serdes_ai_core::{ConversationId, ModelRequest, ModelRequestPart};
+
+/// A conversation history that can be branched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationBranch {
+    id: ConversationId,
+    parent_id: Option<ConversationId>,
+    messages: Vec<ModelRequest>,
+}
+
+impl ConversationBranch {
+    /// Start a new conversation from a message history.
+    #[must_use]
+    pub fn new(messages: Vec<ModelRequest>) -> Self {
+        Self {
+            id: ConversationId::new(),
+            parent_id: None,
+            messages,
+        }
+    }
+
+    /// Get the ID of this branch.
+    pub fn id(&self) -> &ConversationId {
+        &self.id
+    }
+
+    /// Get the ID of the branch this one was forked from.
+    pub fn parent_id(&self) -> Option<&ConversationId> {
+        self.parent_id.as_ref()
+    }
+
+    /// Get the message history.
+    pub fn messages(&self) -> &[ModelRequest] {
+        &self.messages
+    }
+
+    /// Consume and return the message history.
+    pub fn into_messages(self) -> Vec<ModelRequest> {
+        self.messages
+    }
+
+    /// Get the user prompts, in order.
+    ///
+    /// The position of a prompt is the index to pass to
+    /// [`edit_user_message`](Self::edit_user_message).
+    pub fn user_prompts(&self) -> Vec<&UserPromptPart> {
+        self.messages
+            .iter()
+            .flat_map(|m| m.user_prompts())
+            .collect()
+    }
+
+    /// Get the number of model responses.
+    pub fn steps(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|m| response_of(m).is_some())
+            .count()
+    }
+
+    /// Fork a branch with the same history.
+    #[must_use]
+    pub fn branch(&self) -> Self {
+        self.child(self.messages.clone())
+    }
+
+    /// Fork a branch ending just before the `index`-th user prompt.
+    ///
+    /// Run the branch with the edited prompt to regenerate from there.
+    /// Returns `None` if there are not that many user prompts.
+    pub fn edit_user_message(&self, index: usize) -> Option<Self> {
+        let mut seen = 0;
+        let position = self.messages.iter().position(|m| {
+            seen += m.user_prompts().count();
+            seen > index
+        })?;
+        Some(self.child(self.messages[..position].to_vec()))
+    }
+
+    /// Fork a branch keeping the first `step` model responses.
+    ///
+    /// Tool returns and retry prompts answering the last kept response are
+    /// kept as well. Returns `None` if there are not that many responses.
+    pub fn continue_from_step(&self, step: usize) -> Option<Self> {
+        if step > self.steps() {
+            return None;
+        }
+        let mut end = 0;
+        let mut responses = 0;
+        for (i, message) in self.messages.iter().enumerate() {
+            if response_of(message).is_some() {
+                if responses == step {
+                    break;
+                }
+                responses += 1;
+            } else if responses == step && step > 0 && !answers_response(message) {
+                break;
+            }
+            end = i + 1;
+        }
+        Some(self.child(self.messages[..end].to_vec()))
+    }
+
+    /// Run the agent on this branch and append the run to its history.
+    pub async fn run<Deps, Output>(
+        &mut self,
+        agent: &Agent<Deps, Output>,
+        prompt: impl Into<UserContent>,
+        deps: Deps,
+    ) -> Result<AgentRunResult<Output>, AgentRunError>
+    where
+        Deps: Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+    {
+        self.run_with_options(agent, prompt, deps, RunOptions::default())
+            .await
+    }
+
+    /// Run with options; the message history of `options` is replaced by
+    /// this branch's history.
+    pub async fn run_with_options<Deps, Output>(
+        &mut self,
+        agent: &Agent<Deps, Output>,
+        prompt: impl Into<UserContent>,
+        deps: Deps,
+        options: RunOptions,
+    ) -> Result<AgentRunResult<Output>, AgentRunError>
+    where
+        Deps: Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+    {
+        let options = options.message_history(self.messages.clone());
+        let result = agent.run_with_options(prompt, deps, options).await?;
+        self.messages = full_history(&result.messages, &result.responses);
+        Ok(result)
+    }
+
+    fn child(&self, messages: Vec<ModelRequest>) -> Self {
+        Self {
+            id: ConversationId::new(),
+            parent_id: Some(self.id.clone()),
+            messages,
+        }
+    }
+}
+
+/// History of a run including its final response.
+pub(crate) fn full_history(
+    messages: &[ModelRequest],
+    responses: &[ModelResponse],
+) -> Vec<ModelRequest> {
+    let mut history = messages.to_vec();
+    let answered = history.last().is_some_and(|m| response_of(m).is_some());
+    if let (false, Some(last)) = (answered, responses.last()) {
+        history.push(ModelRequest::with_parts(vec![
+            ModelRequestPart::ModelResponse(Box::new(last.clone())),
+        ]));
+    }
+    history
+}
+
+fn response_of(message: &ModelRequest) -> Option<&ModelResponse> {
+    message.parts.iter().find_map(|p| match p {
+        ModelRequestPart::ModelResponse(r) => Some(r.as_ref()),
+        _ => None,
+    })
+}
+
+/// Check if a message only answers the previous response.
+fn answers_response(message: &ModelRequest) -> bool {
+    !message.parts.is_empty()
+        && message.parts.iter().all(|p| {
+            matches!(
+                p,
+                ModelRequestPart::ToolReturn(_)
+                    | ModelRequestPart::BuiltinToolReturn(_)
+                    | ModelRequestPart::RetryPrompt(_)
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::agent;
+    use serdes_ai_core::messages::{ModelResponsePart, ToolReturnPart};
+    use serdes_ai_core::FinishReason;
+    use serdes_ai_models::FunctionModel;
+
+    fn user(text: &str) -> ModelRequest {
+        let mut req = ModelRequest::new();
+        req.add_user_prompt(text);
+        req
+    }
+
+    fn response(text: &str) -> ModelRequest {
+        ModelRequest::with_parts(vec![ModelRequestPart::ModelResponse(Box::new(
+            ModelResponse::text(text),
+        ))])
+    }
+
+    fn texts(branch: &ConversationBranch) -> Vec<String> {
+        branch
+            .messages()
+            .iter()
+            .map(|m| match response_of(m) {
+                Some(r) => r.text_content(),
+                None => m
+                    .user_prompts()
+                    .filter_map(|u| u.as_text())
+                    .chain(m.tool_returns().map(|_| "<tool>"))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_edit_user_message() {
+        let root =
+            ConversationBranch::new(vec![user("one"), response("1"), user("two"), response("2")]);
+        assert_eq!(root.user_prompts().len(), 2);
+
+        let edited = root.edit_user_message(1).unwrap();
+        assert_eq!(texts(&edited), ["one", "1"]);
+        assert_eq!(edited.parent_id(), Some(root.id()));
+        assert_ne!(edited.id(), root.id());
+
+        assert!(root.edit_user_message(0).unwrap().messages().is_empty());
+        assert!(root.edit_user_message(2).is_none());
+    }
+
+    #[test]
+    fn test_continue_from_step() {
+        let mut tool_return = ModelRequest::new();
+        tool_return
+            .parts
+            .push(ModelRequestPart::ToolReturn(ToolReturnPart::new(
+                "lookup", "found",
+            )));
+        let root = ConversationBranch::new(vec![
+            user("find it"),
+            response("calling"),
+            tool_return,
+            response("found it"),
+            user("thanks"),
+            response("welcome"),
+        ]);
+        assert_eq!(root.steps(), 3);
+
+        assert_eq!(texts(&root.continue_from_step(0).unwrap()), ["find it"]);
+        assert_eq!(
+            texts(&root.continue_from_step(1).unwrap()),
+            ["find it", "calling", "<tool>"]
+        );
+        assert_eq!(
+            texts(&root.continue_from_step(2).unwrap()),
+            ["find it", "calling", "<tool>", "found it"]
+        );
+        assert_eq!(root.continue_from_step(3).unwrap().messages().len(), 6);
+        assert!(root.continue_from_step(4).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fork_and_regenerate() {
+        let model = FunctionModel::new(|messages, _| {
+            let prompts: Vec<String> = messages
+                .iter()
+                .flat_map(|m| m.user_prompts())
+                .filter_map(|u| u.as_text().map(str::to_string))
+                .collect();
+            ModelResponse::with_parts(vec![ModelResponsePart::text(prompts.join("+"))])
+                .with_finish_reason(FinishReason::Stop)
+        });
+        let agent = agent(model).build();
+
+        let first = agent.run("a", ()).await.unwrap();
+        let mut conversation = first.fork();
+        assert_eq!(conversation.steps(), 1);
+        let second = conversation.run(&agent, "b", ()).await.unwrap();
+        assert_eq!(second.output, "a+b");
+        assert_eq!(conversation.steps(), 2);
+
+        let mut edited = conversation.edit_user_message(1).unwrap();
+        let regenerated = edited.run(&agent, "c", ()).await.unwrap();
+        assert_eq!(regenerated.output, "a+c");
+        assert_eq!(edited.parent_id(), Some(conversation.id()));
+        // The original branch is untouched
+        assert_eq!(conversation.user_prompts()[1].as_text(), Some("b"));
+    }
+}
@@ -0,0 +1,399 @@
+//! Agent health checks.
+//!
+//! [`Agent::health_check`](crate::Agent::health_check) verifies an agent's
+//! configuration against the outside world before it serves traffic: the
+//! model endpoint answers a one-token request, every tool executor reports
+//! itself ready (executors backed by MCP servers initialize their session
+//! here), and the output schema is usable. Call it at service startup to fail
+//! fast on a wrong API key, an unreachable server or a broken schema:
+//!
+//! ```rust,ignore
+//! let report = agent.health_check().await;
+//! if !report.is_healthy() {
+//!     eprintln!("{report}");
+//!     std::process::exit(1);
+//! }
+//! ```
+
+use crate::agent::{Agent, RegisteredTool};
+use crate::output::{OutputMode, OutputSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serdes_ai_core::ModelRequest;
+use serdes_ai_models::ModelRequestParameters;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// Default time allowed for each check.
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What a health check covers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum HealthComponent {
+    /// The model endpoint.
+    Model,
+    /// A registered tool.
+    Tool(String),
+    /// The output schema.
+    Output,
+}
+
+impl fmt::Display for HealthComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthComponent::Model => write!(f, "model"),
+            HealthComponent::Tool(name) => write!(f, "tool `{}`", name),
+            HealthComponent::Output => write!(f, "output"),
+        }
+    }
+}
+
+/// Outcome of a health check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// The component works.
+    Pass,
+    /// The component works but is likely misconfigured.
+    Warn,
+    /// The component doesn't work.
+    Fail,
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthStatus::Pass => write!(f, "pass"),
+            HealthStatus::Warn => write!(f, "warn"),
+            HealthStatus::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// Result of checking one component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    /// Checked component.
+    pub component: HealthComponent,
+    /// Outcome.
+    pub status: HealthStatus,
+    /// Why the check warned or failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Time taken by the check, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Results of [`Agent::health_check`](crate::Agent::health_check).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Individual checks, model first.
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    /// Check if no check failed; warnings are healthy.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Get the failed checks.
+    pub fn failures(&self) -> impl Iterator<Item = &HealthCheck> {
+        self.checks
+            .iter()
+            .filter(|c| c.status == HealthStatus::Fail)
+    }
+
+    /// Get the check of a component.
+    pub fn get(&self, component: &HealthComponent) -> Option<&HealthCheck> {
+        self.checks.iter().find(|c| &c.component == component)
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            write!(
+                f,
+                "[{}] {} ({}ms)",
+                check.status, check.component, check.duration_ms
+            )?;
+            if let Some(message) = &check.message {
+                write!(f, ": {}", message)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`Agent::health_check_with`](crate::Agent::health_check_with).
+#[derive(Debug, Clone)]
+pub struct HealthCheckOptions {
+    ping_model: bool,
+    timeout: Duration,
+}
+
+impl HealthCheckOptions {
+    /// Create default options: ping the model, 30 second timeout.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            ping_model: true,
+            timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
+        }
+    }
+
+    /// Skip the model request, e.g. to avoid its cost on every startup.
+    #[must_use]
+    pub fn skip_model(mut self) -> Self {
+        self.ping_model = false;
+        self
+    }
+
+    /// Set the time allowed for each check.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for HealthCheckOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run all checks for an agent.
+pub(crate) async fn check_agent<Deps, Output>(
+    agent: &Agent<Deps, Output>,
+    options: &HealthCheckOptions,
+) -> HealthReport
+where
+    Deps: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    let mut checks = Vec::with_capacity(agent.tools.len() + 2);
+    if options.ping_model {
+        checks.push(
+            timed(HealthComponent::Model, options.timeout, async {
+                let mut request = ModelRequest::new();
+                request.add_user_prompt("ping");
+                let settings = agent.model_settings.clone().max_tokens(1);
+                agent
+                    .model()
+                    .request(&[request], &settings, &ModelRequestParameters::new())
+                    .await
+                    .map(|_| None)
+                    .map_err(|e| e.to_string())
+            })
+            .await,
+        );
+    }
+    for tool in &agent.tools {
+        checks.push(check_tool(tool, options.timeout).await);
+    }
+    let started = Instant::now();
+    checks.push(finish(
+        HealthComponent::Output,
+        started,
+        check_output(agent.output_schema.as_ref()),
+    ));
+    HealthReport { checks }
+}
+
+async fn check_tool<Deps>(tool: &RegisteredTool<Deps>, timeout: Duration) -> HealthCheck {
+    let component = HealthComponent::Tool(tool.definition.name.clone());
+    let started = Instant::now();
+    if let Err(message) = check_parameters_schema(&tool.definition.parameters_json_schema) {
+        return finish(component, started, Err(message));
+    }
+    timed(component, timeout, async {
+        tool.executor
+            .health_check()
+            .await
+            .map(|()| None)
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Providers only accept object schemas for tool parameters.
+fn check_parameters_schema(schema: &JsonValue) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        return Err("parameters schema is not a JSON object".to_string());
+    };
+    match schema.get("type") {
+        None => Ok(()),
+        Some(JsonValue::String(t)) if t == "object" => Ok(()),
+        Some(t) => Err(format!(
+            "parameters schema has type {}, expected \"object\"",
+            t
+        )),
+    }
+}
+
+/// `Ok(Some(_))` is a warning.
+fn check_output<Output>(schema: &dyn OutputSchema<Output>) -> Result<Option<String>, String> {
+    let mode = schema.mode();
+    if mode == OutputMode::ToolCall && schema.tool_name().is_none() {
+        return Err("tool call output has no tool name".to_string());
+    }
+    if mode == OutputMode::Text {
+        return Ok(None);
+    }
+    match schema.json_schema() {
+        Some(JsonValue::Object(_)) => Ok(None),
+        Some(other) => Err(format!("output schema is not a JSON object: {}", other)),
+        None => Ok(Some(
+            "structured output has no JSON schema; the model isn't told its shape".to_string(),
+        )),
+    }
+}
+
+async fn timed(
+    component: HealthComponent,
+    timeout: Duration,
+    check: impl Future<Output = Result<Option<String>, String>>,
+) -> HealthCheck {
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(timeout, check).await {
+        Ok(outcome) => outcome,
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+    finish(component, started, outcome)
+}
+
+fn finish(
+    component: HealthComponent,
+    started: Instant,
+    outcome: Result<Option<String>, String>,
+) -> HealthCheck {
+    let (status, message) = match outcome {
+        Ok(None) => (HealthStatus::Pass, None),
+        Ok(Some(warning)) => (HealthStatus::Warn, Some(warning)),
+        Err(error) => (HealthStatus::Fail, Some(error)),
+    };
+    HealthCheck {
+        component,
+        status,
+        message,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::agent;
+    use crate::context::RunContext;
+    use crate::ToolExecutor;
+    use serdes_ai_models::FunctionModel;
+    use serdes_ai_tools::{ToolDefinition, ToolError, ToolReturn};
+
+    struct Unreachable;
+
+    #[async_trait::async_trait]
+    impl ToolExecutor<()> for Unreachable {
+        async fn execute(
+            &self,
+            _args: JsonValue,
+            _ctx: &RunContext<()>,
+        ) -> Result<ToolReturn, ToolError> {
+            Ok(ToolReturn::empty())
+        }
+
+        async fn health_check(&self) -> Result<(), ToolError> {
+            Err(ToolError::execution_failed("connection refused"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_healthy_agent() {
+        let agent = agent(FunctionModel::constant_text("pong"))
+            .tool_fn(
+                "noop",
+                "Do nothing",
+                |_ctx: &RunContext<()>, _args: JsonValue| Ok(ToolReturn::empty()),
+            )
+            .build();
+
+        let report = agent.health_check().await;
+        assert!(report.is_healthy(), "{report}");
+        let components: Vec<_> = report.checks.iter().map(|c| &c.component).collect();
+        assert_eq!(
+            components,
+            [
+                &HealthComponent::Model,
+                &HealthComponent::Tool("noop".to_string()),
+                &HealthComponent::Output,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_failures() {
+        let bad_schema = ToolDefinition::new("bad", "Bad schema")
+            .with_parameters(serde_json::json!({"type": "string"}));
+        let agent = agent(FunctionModel::constant_text("pong"))
+            .tool_with_executor(ToolDefinition::new("remote", "Remote tool"), Unreachable)
+            .tool_with_executor(bad_schema, Unreachable)
+            .build();
+
+        let report = agent
+            .health_check_with(HealthCheckOptions::new().skip_model())
+            .await;
+        assert!(!report.is_healthy());
+        assert!(report.get(&HealthComponent::Model).is_none());
+        let remote = report
+            .get(&HealthComponent::Tool("remote".to_string()))
+            .unwrap();
+        assert_eq!(remote.status, HealthStatus::Fail);
+        assert!(remote
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("connection refused"));
+        let bad = report
+            .get(&HealthComponent::Tool("bad".to_string()))
+            .unwrap();
+        assert!(bad
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("expected \"object\""));
+        assert_eq!(report.failures().count(), 2);
+    }
+
+    /// Custom schema with a boolean root.
+    struct BooleanSchema;
+
+    impl OutputSchema<JsonValue> for BooleanSchema {
+        fn json_schema(&self) -> Option<JsonValue> {
+            Some(JsonValue::Bool(true))
+        }
+
+        fn mode(&self) -> OutputMode {
+            OutputMode::Json
+        }
+
+        fn parse_text(&self, text: &str) -> Result<JsonValue, crate::OutputParseError> {
+            serde_json::from_str(text).map_err(crate::OutputParseError::Json)
+        }
+    }
+
+    #[test]
+    fn test_check_output() {
+        assert_eq!(check_output(&crate::output::TextOutputSchema), Ok(None));
+        let schema = crate::output::ToolOutputSchema::<JsonValue>::new("final_result")
+            .with_schema(serde_json::json!({"type": "object"}));
+        assert_eq!(check_output(&schema), Ok(None));
+        let schema = crate::output::JsonOutputSchema::<JsonValue>::new();
+        assert!(check_output(&schema).unwrap().is_some());
+        assert!(check_output(&BooleanSchema).is_err());
+    }
+}
@@ -5,7 +5,11 @@
 
 use crate::context::RunContext;
 use async_trait::async_trait;
-use serdes_ai_core::{ModelRequest, ModelRequestPart, ModelResponsePart};
+use serdes_ai_core::messages::{
+    AudioContent, DocumentContent, FileContent, ImageContent, ToolCallArgs, ToolReturnContent,
+    ToolReturnItem, UserContent, UserContentPart, VideoContent,
+};
+use serdes_ai_core::{ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart};
 use std::collections::HashSet;
 use std::marker::PhantomData;
 
@@ -727,10 +731,245 @@ where
     }
 }
 
+// ============================================================================
+// Persistence Transforms
+// ============================================================================
+
+/// Transform applied to history before it leaves the agent.
+///
+/// Unlike a [`HistoryProcessor`], which shapes what the model sees, a
+/// transform shapes the history returned in
+/// [`AgentRunResult`](crate::AgentRunResult), reported on stream completion
+/// and kept by [`ConversationBranch`](crate::ConversationBranch), i.e. what
+/// applications persist.
+pub trait HistoryTransform: Send + Sync {
+    /// Transform the message history.
+    fn transform(&self, messages: Vec<ModelRequest>) -> Vec<ModelRequest>;
+}
+
+impl<F> HistoryTransform for F
+where
+    F: Fn(Vec<ModelRequest>) -> Vec<ModelRequest> + Send + Sync,
+{
+    fn transform(&self, messages: Vec<ModelRequest>) -> Vec<ModelRequest> {
+        self(messages)
+    }
+}
+
+/// Apply a transform to model responses.
+pub(crate) fn transform_responses(
+    transform: &dyn HistoryTransform,
+    responses: Vec<ModelResponse>,
+) -> Vec<ModelResponse> {
+    let wrapped = responses
+        .into_iter()
+        .map(|r| ModelRequest::with_parts(vec![ModelRequestPart::ModelResponse(Box::new(r))]))
+        .collect();
+    transform
+        .transform(wrapped)
+        .into_iter()
+        .flat_map(|m| m.parts)
+        .filter_map(|p| match p {
+            ModelRequestPart::ModelResponse(r) => Some(*r),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Redact history before persistence.
+///
+/// By default binary content (images, audio, documents, generated files) is
+/// replaced by a placeholder and the values of credential-like keys in tool
+/// arguments and JSON tool returns are redacted. Thinking parts are kept
+/// unless [`strip_thinking`](Self::strip_thinking) is set.
+#[derive(Debug, Clone)]
+pub struct RedactHistory {
+    strip_thinking: bool,
+    drop_binary: bool,
+    redact_secrets: bool,
+    redact_keys: HashSet<String>,
+}
+
+impl RedactHistory {
+    /// Create a transform with the default redactions.
+    pub fn new() -> Self {
+        Self {
+            strip_thinking: false,
+            drop_binary: true,
+            redact_secrets: true,
+            redact_keys: HashSet::new(),
+        }
+    }
+
+    /// Remove thinking parts.
+    pub fn strip_thinking(mut self, strip: bool) -> Self {
+        self.strip_thinking = strip;
+        self
+    }
+
+    /// Replace binary content with a placeholder.
+    pub fn drop_binary(mut self, drop: bool) -> Self {
+        self.drop_binary = drop;
+        self
+    }
+
+    /// Redact credential-like keys in tool arguments and JSON tool returns.
+    pub fn redact_secrets(mut self, redact: bool) -> Self {
+        self.redact_secrets = redact;
+        self
+    }
+
+    /// Also redact this key (compared case-insensitively).
+    pub fn redact_key(mut self, key: impl Into<String>) -> Self {
+        self.redact_keys.insert(key.into().to_ascii_lowercase());
+        self
+    }
+
+    fn redact_value(&self, value: &mut serde_json::Value) {
+        if self.redact_secrets {
+            serdes_ai_models::http_capture::redact_json(value);
+        }
+        if !self.redact_keys.is_empty() {
+            redact_keys(value, &self.redact_keys);
+        }
+    }
+
+    fn redact_args(&self, args: &mut ToolCallArgs) {
+        if self.redact_secrets || !self.redact_keys.is_empty() {
+            let mut value = args.to_json();
+            self.redact_value(&mut value);
+            *args = ToolCallArgs::Json(value);
+        }
+    }
+
+    fn redact_request_part(&self, part: &mut ModelRequestPart) {
+        match part {
+            ModelRequestPart::UserPrompt(prompt) if self.drop_binary => {
+                if let UserContent::Parts(parts) = &mut prompt.content {
+                    for part in parts.iter_mut() {
+                        if let Some(kind) = binary_kind(part) {
+                            *part = UserContentPart::text(placeholder(kind));
+                        }
+                    }
+                }
+            }
+            ModelRequestPart::ToolReturn(ret) => self.redact_tool_return(&mut ret.content),
+            ModelRequestPart::ModelResponse(response) => self.redact_response(response),
+            _ => {}
+        }
+    }
+
+    fn redact_tool_return(&self, content: &mut ToolReturnContent) {
+        match content {
+            ToolReturnContent::Json { content } => self.redact_value(content),
+            ToolReturnContent::Image {
+                image: ImageContent::Binary(_),
+            } if self.drop_binary => {
+                *content = ToolReturnContent::text(placeholder("image"));
+            }
+            ToolReturnContent::Multiple { items } => {
+                for item in items.iter_mut() {
+                    match item {
+                        ToolReturnItem::Json { value } => self.redact_value(value),
+                        ToolReturnItem::Image {
+                            image: ImageContent::Binary(_),
+                        } if self.drop_binary => {
+                            *item = ToolReturnItem::Text {
+                                content: placeholder("image"),
+                            };
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn redact_response(&self, response: &mut ModelResponse) {
+        if self.strip_thinking {
+            response
+                .parts
+                .retain(|p| !matches!(p, ModelResponsePart::Thinking(_)));
+        }
+        for part in &mut response.parts {
+            match part {
+                ModelResponsePart::ToolCall(call) => self.redact_args(&mut call.args),
+                ModelResponsePart::BuiltinToolCall(call) => self.redact_args(&mut call.args),
+                ModelResponsePart::File(file) if self.drop_binary => {
+                    *part = ModelResponsePart::text(placeholder(&file.content.media_type));
+                }
+                _ => {}
+            }
+        }
+        for alternative in &mut response.alternatives {
+            self.redact_response(alternative);
+        }
+    }
+}
+
+impl Default for RedactHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryTransform for RedactHistory {
+    fn transform(&self, mut messages: Vec<ModelRequest>) -> Vec<ModelRequest> {
+        for message in &mut messages {
+            for part in &mut message.parts {
+                self.redact_request_part(part);
+            }
+        }
+        messages
+    }
+}
+
+fn binary_kind(part: &UserContentPart) -> Option<&'static str> {
+    match part {
+        UserContentPart::Image {
+            image: ImageContent::Binary(_),
+        } => Some("image"),
+        UserContentPart::Audio {
+            audio: AudioContent::Binary(_),
+        } => Some("audio"),
+        UserContentPart::Video {
+            video: VideoContent::Binary(_),
+        } => Some("video"),
+        UserContentPart::Document {
+            document: DocumentContent::Binary(_),
+        } => Some("document"),
+        UserContentPart::File {
+            file: FileContent::Binary(_),
+        } => Some("file"),
+        _ => None,
+    }
+}
+
+fn placeholder(kind: &str) -> String {
+    format!("[{kind} removed]")
+}
+
+fn redact_keys(value: &mut serde_json::Value, keys: &HashSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if keys.contains(&key.to_ascii_lowercase()) {
+                    *value = serde_json::Value::String("[REDACTED]".to_string());
+                } else {
+                    redact_keys(value, keys);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| redact_keys(v, keys)),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use chrono::{Offset, Utc};
     use std::sync::Arc;
 
     fn make_test_context() -> RunContext<()> {
@@ -742,7 +981,11 @@ mod tests {
             model_settings: Default::default(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: None,
+            timezone: Utc.fix(),
             metadata: None,
         }
     }
@@ -1367,4 +1610,90 @@ mod tests {
             );
         }
     }
+
+    fn make_redaction_history() -> Vec<ModelRequest> {
+        use serdes_ai_core::messages::{ImageMediaType, ToolReturnPart};
+
+        let mut prompt = ModelRequest::new();
+        prompt.add_user_prompt(UserContent::parts(vec![
+            UserContentPart::text("What is this?"),
+            UserContentPart::image_binary(vec![1, 2, 3], ImageMediaType::Png),
+        ]));
+        let response = ModelResponse::with_parts(vec![
+            ModelResponsePart::thinking("Let me look it up"),
+            ModelResponsePart::tool_call(
+                "lookup",
+                serde_json::json!({"query": "cat", "api_key": "sk-1"}),
+            ),
+            ModelResponsePart::file(vec![4, 5, 6], "image/png"),
+        ]);
+        let mut response_req = ModelRequest::new();
+        response_req
+            .parts
+            .push(ModelRequestPart::ModelResponse(Box::new(response)));
+        let mut returns = ModelRequest::new();
+        returns
+            .parts
+            .push(ModelRequestPart::ToolReturn(ToolReturnPart::new(
+                "lookup",
+                ToolReturnContent::json(serde_json::json!({"name": "cat", "token": "t-1"})),
+            )));
+        vec![prompt, response_req, returns]
+    }
+
+    fn response_in(messages: &[ModelRequest]) -> &ModelResponse {
+        messages
+            .iter()
+            .flat_map(|m| &m.parts)
+            .find_map(|p| match p {
+                ModelRequestPart::ModelResponse(r) => Some(r.as_ref()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_redact_history_defaults() {
+        let messages = RedactHistory::default().transform(make_redaction_history());
+
+        let prompt = messages[0].user_prompts().next().unwrap();
+        let UserContent::Parts(parts) = &prompt.content else {
+            panic!("expected parts");
+        };
+        assert_eq!(parts[1], UserContentPart::text("[image removed]"));
+
+        let response = response_in(&messages);
+        assert_eq!(response.thinking_parts().count(), 1);
+        let args = response.tool_call_parts().next().unwrap().args.to_json();
+        assert_eq!(args["query"], "cat");
+        assert_eq!(args["api_key"], "[REDACTED]");
+        assert_eq!(response.file_parts().count(), 0);
+        assert!(response.text_content().contains("[image/png removed]"));
+
+        let ret = messages[2].tool_returns().next().unwrap();
+        assert_eq!(
+            ret.content,
+            ToolReturnContent::json(serde_json::json!({"name": "cat", "token": "[REDACTED]"}))
+        );
+    }
+
+    #[test]
+    fn test_redact_history_options() {
+        let transform = RedactHistory::new()
+            .strip_thinking(true)
+            .drop_binary(false)
+            .redact_key("Query");
+        let messages = transform.transform(make_redaction_history());
+
+        let response = response_in(&messages);
+        assert_eq!(response.thinking_parts().count(), 0);
+        assert_eq!(response.file_parts().count(), 1);
+        let args = response.tool_call_parts().next().unwrap().args.to_json();
+        assert_eq!(args["query"], "[REDACTED]");
+        assert_eq!(args["api_key"], "[REDACTED]");
+
+        // Closures are transforms too
+        let drop_all = |_: Vec<ModelRequest>| Vec::new();
+        assert!(drop_all.transform(make_redaction_history()).is_empty());
+    }
 }
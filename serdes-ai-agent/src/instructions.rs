@@ -4,7 +4,10 @@
 //! system prompts and instructions dynamically based on context.
 
 use crate::context::RunContext;
+use crate::language::LanguageInstruction;
 use async_trait::async_trait;
+use chrono::FixedOffset;
+use serdes_ai_core::now_utc;
 use std::future::Future;
 use std::marker::PhantomData;
 
@@ -261,6 +264,15 @@ impl<Deps: Send + Sync + 'static> InstructionBuilder<Deps> {
         self
     }
 
+    /// Ask the model to respond in the run's detected language.
+    ///
+    /// Adds "Respond in {language}." when the agent has a
+    /// [`LanguageDetector`](crate::LanguageDetector) and it found a language.
+    pub fn respond_in_detected_language(mut self) -> Self {
+        self.parts.push(Box::new(LanguageInstruction::new()));
+        self
+    }
+
     /// Add a custom instruction.
     pub fn add_instruction(mut self, instruction: Box<dyn InstructionFn<Deps>>) -> Self {
         self.parts.push(instruction);
@@ -313,18 +325,31 @@ impl<Deps: Send + Sync> InstructionFn<Deps> for CombinedInstruction<Deps> {
 // Common Instruction Functions
 // ============================================================================
 
+/// Default format of [`DateTimeInstruction`], e.g. `Tuesday, 2025-06-03 14:05:09 +02:00`.
+pub const DEFAULT_DATETIME_FORMAT: &str = "%A, %Y-%m-%d %H:%M:%S %:z";
+
 /// Instruction that includes the current date/time.
+///
+/// The time is shown in the run's timezone (see
+/// [`RunOptions::timezone`](crate::RunOptions::timezone)) unless the
+/// instruction has its own. By default it is the run's start time; with
+/// [`per_step`](Self::per_step) it is refreshed on every model request, for
+/// long-running agents.
 pub struct DateTimeInstruction {
     format: String,
     prefix: String,
+    timezone: Option<FixedOffset>,
+    per_step: bool,
 }
 
 impl DateTimeInstruction {
     /// Create with default format.
     pub fn new() -> Self {
         Self {
-            format: "%Y-%m-%d %H:%M:%S UTC".to_string(),
+            format: DEFAULT_DATETIME_FORMAT.to_string(),
             prefix: "Current date and time:".to_string(),
+            timezone: None,
+            per_step: false,
         }
     }
 
@@ -334,11 +359,33 @@ impl DateTimeInstruction {
         self
     }
 
+    /// Show only the date, e.g. `Tuesday, 2025-06-03`.
+    pub fn date_only(self) -> Self {
+        self.format("%A, %Y-%m-%d")
+    }
+
+    /// Show the time in RFC 3339 format.
+    pub fn rfc3339(self) -> Self {
+        self.format("%Y-%m-%dT%H:%M:%S%:z")
+    }
+
     /// Set prefix text.
     pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
         self.prefix = prefix.into();
         self
     }
+
+    /// Show the time in `timezone` instead of the run's timezone.
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Show the current time on every step instead of the run's start time.
+    pub fn per_step(mut self, per_step: bool) -> Self {
+        self.per_step = per_step;
+        self
+    }
 }
 
 impl Default for DateTimeInstruction {
@@ -350,7 +397,13 @@ impl Default for DateTimeInstruction {
 #[async_trait]
 impl<Deps: Send + Sync> InstructionFn<Deps> for DateTimeInstruction {
     async fn generate(&self, ctx: &RunContext<Deps>) -> Option<String> {
-        let formatted = ctx.start_time.format(&self.format).to_string();
+        let time = if self.per_step {
+            now_utc()
+        } else {
+            ctx.start_time
+        };
+        let timezone = self.timezone.unwrap_or(ctx.timezone);
+        let formatted = time.with_timezone(&timezone).format(&self.format);
         Some(format!("{} {}", self.prefix, formatted))
     }
 }
@@ -391,7 +444,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use chrono::{Offset, Utc};
     use std::sync::Arc;
 
     fn make_test_context() -> RunContext<()> {
@@ -403,7 +456,11 @@ mod tests {
             model_settings: Default::default(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: None,
+            timezone: Utc.fix(),
             metadata: None,
         }
     }
@@ -448,6 +505,28 @@ mod tests {
         assert!(result.contains("Current date and time:"));
     }
 
+    #[tokio::test]
+    async fn test_datetime_instruction_timezone() {
+        let mut ctx = make_test_context();
+        ctx.start_time = "2025-06-02T23:30:00Z".parse().unwrap();
+        let ctx = ctx.with_timezone(FixedOffset::east_opt(2 * 3600).unwrap());
+
+        let instruction = DateTimeInstruction::new();
+        assert_eq!(
+            instruction.generate(&ctx).await.unwrap(),
+            "Current date and time: Tuesday, 2025-06-03 01:30:00 +02:00"
+        );
+
+        let utc = DateTimeInstruction::new()
+            .date_only()
+            .timezone(Utc.fix())
+            .prefix("Today is");
+        assert_eq!(
+            utc.generate(&ctx).await.unwrap(),
+            "Today is Monday, 2025-06-02"
+        );
+    }
+
     #[tokio::test]
     async fn test_combined_instruction_skips_empty() {
         let instruction = InstructionBuilder::<()>::new()
@@ -0,0 +1,512 @@
+//! Language detection for user prompts.
+//!
+//! An agent configured with a [`LanguageDetector`] detects the language of
+//! each run's prompt before the first request and exposes it as
+//! [`RunContext::language`]. Instructions can then ask the model to answer in
+//! that language, e.g. with [`LanguageInstruction`] or
+//! [`InstructionBuilder::respond_in_detected_language`](crate::InstructionBuilder::respond_in_detected_language).
+//!
+//! Two detectors are provided:
+//!
+//! - [`HeuristicLanguageDetector`] is instant and free: it looks at the
+//!   script of the text and, for Latin-script text, at common words.
+//! - [`ModelLanguageDetector`] asks a (small, cheap) model.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, HeuristicLanguageDetector, LanguageInstruction};
+//!
+//! let agent = agent(model)
+//!     .language_detector(HeuristicLanguageDetector::new())
+//!     .instruction(LanguageInstruction::new())
+//!     .build();
+//!
+//! // Instructions include "Respond in German."
+//! agent.run("Wie ist das Wetter heute?", ()).await?;
+//! ```
+
+use crate::context::RunContext;
+use crate::instructions::InstructionFn;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serdes_ai_core::messages::{UserContent, UserContentPart};
+use serdes_ai_core::{ModelRequest, ModelSettings};
+use serdes_ai_models::{Model, ModelRequestParameters};
+use std::fmt;
+use std::sync::Arc;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Languages known by code, as `(ISO 639-1 code, English name)`.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("zh", "Chinese"),
+];
+
+/// A detected language.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Language {
+    /// ISO 639-1 code, e.g. `"de"`.
+    pub code: String,
+    /// English name, e.g. `"German"`.
+    pub name: String,
+}
+
+impl Language {
+    /// Create a language.
+    pub fn new(code: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Look up a language by its ISO 639-1 code.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_lowercase();
+        LANGUAGES
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(code, name)| Self::new(*code, *name))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Detects the language of a user prompt.
+#[async_trait]
+pub trait LanguageDetector: Send + Sync {
+    /// Detect the language of `text`.
+    ///
+    /// Returns `None` if the language can't be determined with confidence.
+    async fn detect(&self, text: &str) -> Option<Language>;
+}
+
+/// Get the text of a user prompt, ignoring non-text parts.
+pub(crate) fn prompt_text(prompt: &UserContent) -> String {
+    match prompt {
+        UserContent::Text(text) => text.clone(),
+        UserContent::Parts(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                UserContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+// ============================================================================
+// Heuristic Detector
+// ============================================================================
+
+/// Common words of Latin-script languages, used to tell them apart.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "what", "how", "to", "of", "in", "it", "this",
+            "that", "with", "for", "my", "can", "please", "do", "have", "not", "i",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "que", "y", "es", "en", "un", "una", "por", "para", "con",
+            "cómo", "qué", "está", "puedes", "hola", "gracias", "mi", "del", "necesito",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "des", "et", "est", "un", "une", "je", "vous", "que", "pour",
+            "dans", "pas", "avec", "ce", "comment", "bonjour", "merci", "mon", "il", "du",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "ich", "nicht", "ein", "eine", "zu", "mit", "sie",
+            "wie", "was", "für", "auf", "den", "dem", "mein", "bitte", "kannst", "hallo",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "di", "che", "è", "un", "una", "per", "non", "sono", "come", "mi", "ciao",
+            "grazie", "con", "del", "della", "questo", "puoi", "gli",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "que", "é", "um", "uma", "para", "com", "não", "como", "você",
+            "obrigado", "olá", "meu", "do", "da", "está", "preciso", "em",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "van", "ik", "je", "niet", "dat", "met", "voor", "op",
+            "hoe", "wat", "mijn", "hallo", "bedankt", "zijn", "kun",
+        ],
+    ),
+];
+
+/// Writing systems that identify a language on their own (or nearly so).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Kana,
+    Hangul,
+    Han,
+}
+
+impl Script {
+    fn of(c: char) -> Option<Self> {
+        Some(match c {
+            'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}' => Self::Latin,
+            '\u{0400}'..='\u{04FF}' => Self::Cyrillic,
+            '\u{0370}'..='\u{03FF}' => Self::Greek,
+            '\u{0600}'..='\u{06FF}' => Self::Arabic,
+            '\u{0590}'..='\u{05FF}' => Self::Hebrew,
+            '\u{0900}'..='\u{097F}' => Self::Devanagari,
+            '\u{0E00}'..='\u{0E7F}' => Self::Thai,
+            '\u{3040}'..='\u{30FF}' => Self::Kana,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => Self::Hangul,
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Self::Han,
+            _ => return None,
+        })
+    }
+}
+
+/// Fast, offline language detection.
+///
+/// Non-Latin scripts are identified by their characters (Japanese wins over
+/// Chinese as soon as kana appear). Latin-script text is scored against lists
+/// of common words for English, Spanish, French, German, Italian, Portuguese
+/// and Dutch; short or ambiguous text yields no language.
+#[derive(Debug, Clone)]
+pub struct HeuristicLanguageDetector {
+    min_matches: usize,
+}
+
+impl HeuristicLanguageDetector {
+    /// Create a detector.
+    pub fn new() -> Self {
+        Self { min_matches: 2 }
+    }
+
+    /// Set how many common words Latin-script text needs to match (default: 2).
+    #[must_use]
+    pub fn min_matches(mut self, min: usize) -> Self {
+        self.min_matches = min.max(1);
+        self
+    }
+
+    /// Detect the language of `text` without going through the async trait.
+    pub fn detect_sync(&self, text: &str) -> Option<Language> {
+        let mut counts: Vec<(Script, usize)> = Vec::new();
+        for script in text.chars().filter_map(Script::of) {
+            match counts.iter_mut().find(|(s, _)| *s == script) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((script, 1)),
+            }
+        }
+        let has_kana = counts.iter().any(|(s, _)| *s == Script::Kana);
+        let (script, _) = counts.into_iter().max_by_key(|(_, n)| *n)?;
+
+        let code = match script {
+            Script::Latin => return self.detect_latin(text),
+            Script::Han | Script::Kana if has_kana => "ja",
+            Script::Han => "zh",
+            Script::Kana => "ja",
+            Script::Hangul => "ko",
+            Script::Cyrillic if text.contains(['і', 'ї', 'є', 'ґ']) => "uk",
+            Script::Cyrillic => "ru",
+            Script::Greek => "el",
+            Script::Arabic => "ar",
+            Script::Hebrew => "he",
+            Script::Devanagari => "hi",
+            Script::Thai => "th",
+        };
+        Language::from_code(code)
+    }
+
+    fn detect_latin(&self, text: &str) -> Option<Language> {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let mut scores: Vec<(&str, usize)> = STOPWORDS
+            .iter()
+            .map(|(code, stopwords)| {
+                let hits = words.iter().filter(|w| stopwords.contains(w)).count();
+                (*code, hits)
+            })
+            .collect();
+        scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+        let (code, best) = scores[0];
+        let runner_up = scores.get(1).map_or(0, |(_, n)| *n);
+        if best < self.min_matches || best == runner_up {
+            return None;
+        }
+        Language::from_code(code)
+    }
+}
+
+impl Default for HeuristicLanguageDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LanguageDetector for HeuristicLanguageDetector {
+    async fn detect(&self, text: &str) -> Option<Language> {
+        self.detect_sync(text)
+    }
+}
+
+// ============================================================================
+// Model Detector
+// ============================================================================
+
+/// Prompt sent to the model by [`ModelLanguageDetector`].
+const DETECTION_PROMPT: &str = "Identify the language of the user's message. \
+Reply with its two-letter ISO 639-1 code only, e.g. \"en\".";
+
+/// Maximum prompt characters sent for detection.
+const MAX_DETECTION_CHARS: usize = 1000;
+
+/// Language detection by asking a model.
+///
+/// More accurate than [`HeuristicLanguageDetector`] for short or mixed text,
+/// at the cost of an extra request per run. Failed requests and unparseable
+/// replies yield no language.
+pub struct ModelLanguageDetector {
+    model: Arc<dyn Model>,
+}
+
+impl ModelLanguageDetector {
+    /// Create a detector using the given model.
+    pub fn new<M: Model + 'static>(model: M) -> Self {
+        Self::from_arc(Arc::new(model))
+    }
+
+    /// Create a detector from a shared model.
+    pub fn from_arc(model: Arc<dyn Model>) -> Self {
+        Self { model }
+    }
+}
+
+impl fmt::Debug for ModelLanguageDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModelLanguageDetector")
+            .field("model", &self.model.name())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl LanguageDetector for ModelLanguageDetector {
+    async fn detect(&self, text: &str) -> Option<Language> {
+        let text: String = text.chars().take(MAX_DETECTION_CHARS).collect();
+        let mut request = ModelRequest::new();
+        request.add_system_prompt(DETECTION_PROMPT);
+        request.add_user_prompt(text);
+
+        let settings = ModelSettings::new().temperature(0.0).max_tokens(8);
+        let response = self
+            .model
+            .request(&[request], &settings, &ModelRequestParameters::new())
+            .await
+            .map_err(|_e| {
+                warn!(error = %_e, "Language detection request failed");
+            })
+            .ok()?;
+
+        let reply = response.text_content();
+        let code: String = reply
+            .trim()
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect();
+        match code.len() {
+            2 => Language::from_code(&code).or_else(|| Some(Language::new(&code, &code))),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// Instruction
+// ============================================================================
+
+/// Instruction asking the model to respond in the detected language.
+///
+/// Adds nothing when no language was detected.
+#[derive(Debug, Clone)]
+pub struct LanguageInstruction {
+    template: String,
+}
+
+impl LanguageInstruction {
+    /// Create with the default text, "Respond in {language}."
+    pub fn new() -> Self {
+        Self {
+            template: "Respond in {language}.".to_string(),
+        }
+    }
+
+    /// Set the instruction text; `{language}` is replaced by the language name.
+    #[must_use]
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = template.into();
+        self
+    }
+}
+
+impl Default for LanguageInstruction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> InstructionFn<Deps> for LanguageInstruction {
+    async fn generate(&self, ctx: &RunContext<Deps>) -> Option<String> {
+        let language = ctx.language.as_ref()?;
+        Some(self.template.replace("{language}", &language.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::ModelResponse;
+    use serdes_ai_models::FunctionModel;
+
+    fn detect(text: &str) -> Option<String> {
+        HeuristicLanguageDetector::new()
+            .detect_sync(text)
+            .map(|l| l.code)
+    }
+
+    #[test]
+    fn test_heuristic_detection() {
+        assert_eq!(
+            detect("What is the weather like in Paris today?").as_deref(),
+            Some("en")
+        );
+        assert_eq!(
+            detect("¿Cómo está el tiempo en Madrid hoy?").as_deref(),
+            Some("es")
+        );
+        assert_eq!(
+            detect("Bonjour, je voudrais savoir le temps qu'il fait").as_deref(),
+            Some("fr")
+        );
+        assert_eq!(
+            detect("Wie ist das Wetter heute in Berlin?").as_deref(),
+            Some("de")
+        );
+        assert_eq!(detect("今日の天気はどうですか").as_deref(), Some("ja"));
+        assert_eq!(detect("今天天气怎么样").as_deref(), Some("zh"));
+        assert_eq!(detect("Какая сегодня погода?").as_deref(), Some("ru"));
+        assert_eq!(detect("Яка сьогодні погода? Є дощ?").as_deref(), Some("uk"));
+
+        // Too little to go on
+        assert_eq!(detect("Paris"), None);
+        assert_eq!(detect("42"), None);
+    }
+
+    #[tokio::test]
+    async fn test_model_detection() {
+        let detector =
+            ModelLanguageDetector::new(FunctionModel::new(|_, _| ModelResponse::text(" DE\n")));
+        let language = detector.detect("Hallo").await.unwrap();
+        assert_eq!(language, Language::new("de", "German"));
+
+        let detector =
+            ModelLanguageDetector::new(FunctionModel::new(|_, _| ModelResponse::text("unknown")));
+        assert!(detector.detect("Hallo").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_agent_responds_in_detected_language() {
+        let model = FunctionModel::new(|messages: &[ModelRequest], _| {
+            let instructions = messages.last().and_then(|m| m.instructions.clone());
+            ModelResponse::text(format!("[{}]", instructions.unwrap_or_default()))
+        });
+        let agent = crate::agent(model)
+            .language_detector(HeuristicLanguageDetector::new())
+            .instruction(
+                crate::InstructionBuilder::new()
+                    .respond_in_detected_language()
+                    .build(),
+            )
+            .instructions_fn_sync(|ctx| ctx.language.as_ref().map(|l| l.code.clone()))
+            .build();
+
+        let result = agent.run("Wie ist das Wetter heute?", ()).await.unwrap();
+        assert_eq!(result.output(), "[Respond in German.\n\nde]");
+
+        // Nothing is added when no language is detected
+        let result = agent.run("42", ()).await.unwrap();
+        assert_eq!(result.output(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_language_instruction() {
+        let mut ctx = RunContext::new((), "test-model");
+        let instruction = LanguageInstruction::new();
+        assert_eq!(instruction.generate(&ctx).await, None);
+
+        ctx.language = Language::from_code("fr");
+        assert_eq!(
+            instruction.generate(&ctx).await.as_deref(),
+            Some("Respond in French.")
+        );
+    }
+}
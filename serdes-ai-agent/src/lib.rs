@@ -72,40 +72,103 @@
 //! ```
 
 pub mod agent;
+pub mod audit;
 pub mod builder;
+pub mod confidence;
 pub mod context;
+pub mod conversations;
+pub mod dates;
+pub mod debugger;
 pub mod errors;
+pub mod fork;
+pub mod health;
 pub mod history;
 pub mod instructions;
+pub mod language;
 pub mod output;
+pub mod presets;
+pub mod prompt_diff;
+pub mod review;
 pub mod run;
+pub mod scheduler;
+pub mod sink;
 pub mod stream;
+pub mod tool_return_limit;
+pub mod tool_selection;
+pub mod tool_usage;
+pub mod triggers;
+pub mod usage_meter;
+pub mod worker;
 
 // Re-exports
-pub use agent::{Agent, EndStrategy, InstrumentationSettings, RegisteredTool, ToolExecutor};
+pub use agent::{
+    Agent, EndStrategy, InstrumentationSettings, KeyResolverFn, RegisteredTool, RequestSettingsFn,
+    ToolExecutor, ToolFailurePolicy,
+};
+#[cfg(feature = "audit-http")]
+pub use audit::HttpAuditSink;
+pub use audit::{
+    hash_args, ActorFn, AuditEvent, AuditLog, AuditRecord, AuditSink, AuditStatus,
+    InMemoryAuditSink, JsonlAuditSink,
+};
 pub use builder::{agent, agent_with_deps, AgentBuilder, ModelConfig};
-pub use context::{generate_run_id, RunContext, RunUsage, UsageLimits};
+pub use confidence::{ConfidenceEstimator, ConfidenceSignals, HeuristicEstimator, JudgeEstimator};
+pub use context::{
+    generate_run_id, idempotency_key, RunContext, RunUsage, UsageEntry, UsageLimits, UsageTotals,
+};
+pub use conversations::{ConversationStore, FileConversationStore, InMemoryConversationStore};
+pub use dates::{resolve_relative_date, RelativeDateResolver};
+pub use debugger::{AgentDebugger, DebugFrame};
 pub use errors::{
-    AgentBuildError, AgentRunError, OutputParseError, OutputValidationError, UsageLimitError,
+    AgentBuildError, AgentRunError, FieldError, OutputParseError, OutputValidationError,
+    UsageLimitError,
+};
+pub use fork::ConversationBranch;
+pub use health::{
+    HealthCheck, HealthCheckOptions, HealthComponent, HealthReport, HealthStatus,
+    DEFAULT_HEALTH_CHECK_TIMEOUT,
 };
 pub use history::{
-    ChainedProcessor, FilterHistory, FnProcessor, HistoryProcessor, SummarizeHistory,
-    TruncateByTokens, TruncateHistory,
+    ChainedProcessor, FilterHistory, FnProcessor, HistoryProcessor, HistoryTransform,
+    RedactHistory, SummarizeHistory, TruncateByTokens, TruncateHistory,
 };
 pub use instructions::{
     AsyncInstructionFn, AsyncSystemPromptFn, DateTimeInstruction, InstructionBuilder,
     InstructionFn, StaticInstruction, StaticSystemPrompt, SyncInstructionFn, SyncSystemPromptFn,
-    SystemPromptFn,
+    SystemPromptFn, DEFAULT_DATETIME_FORMAT,
+};
+pub use language::{
+    HeuristicLanguageDetector, Language, LanguageDetector, LanguageInstruction,
+    ModelLanguageDetector,
 };
 pub use output::{
-    AsyncValidator, ChainedValidator, DefaultOutputSchema, JsonOutputSchema, LengthValidator,
-    NonEmptyValidator, OutputMode, OutputSchema, OutputValidator, SyncValidator, TextOutputSchema,
-    ToolOutputSchema,
+    from_json_with_paths, AsyncValidator, ChainedValidator, CodeEditsValidator,
+    DefaultOutputSchema, JsonOutputSchema, LengthValidator, NonEmptyValidator, OutputMode,
+    OutputSchema, OutputValidator, SyncValidator, TextOutputSchema, ToolOutputSchema,
+    WorkspaceSnapshotFn,
 };
+pub use presets::{Document, Route, RouteDecision, SqlPool, VectorStore};
+pub use prompt_diff::{PromptChange, PromptDiff};
+pub use review::{Review, ReviewContext, ReviewMode, ReviewTrigger, Reviewer};
 pub use run::{
-    AgentRun, AgentRunResult, CompressionStrategy, ContextCompression, RunOptions, StepResult,
+    AgentRun, AgentRunResult, CompressionStrategy, ContextCompression, PreparedRequest, RunOptions,
+    StepResult,
 };
+pub use scheduler::{CronSchedule, Job, JobHook, JobRecord, Schedule, ScheduleError, Scheduler};
+pub use sink::{FileSink, PipedRun, StreamSink, WriterSink};
 pub use stream::{AgentStream, AgentStreamEvent};
+pub use tool_return_limit::{OversizedReturn, ToolReturnLimit};
+pub use tool_selection::{KeywordRanker, ToolRanker, ToolSelection};
+pub use tool_usage::{
+    CategoryFn, FileToolUsageStore, InMemoryToolUsageStore, ToolRouting, ToolUsage, ToolUsageStore,
+    DEFAULT_TOOL_USAGE_CATEGORY,
+};
+pub use triggers::{
+    ChannelTrigger, FilePromptFn, FileWatchTrigger, Trigger, TriggerEvent, TriggerSink,
+    WebhookTrigger,
+};
+pub use usage_meter::{BudgetCallback, BudgetEvent, UsageMeter, DEFAULT_BUDGET_WARN_RATIO};
+pub use worker::{AgentWorker, CompletionHook, WorkerStats};
 
 // Re-export CancellationToken for convenience
 pub use tokio_util::sync::CancellationToken;
@@ -2,14 +2,21 @@
 //!
 //! This module provides traits and implementations for validating
 //! and transforming agent outputs.
+//!
+//! Lists and primitives (`Vec<T>`, `u32`, ...) are supported as output types:
+//! schemas with a non-object root are wrapped in an `{"items": [...]}` or
+//! `{"value": ...}` envelope, and the envelope is removed again when parsing.
 
 use crate::context::RunContext;
-use crate::errors::{OutputParseError, OutputValidationError};
+use crate::errors::{FieldError, OutputParseError, OutputValidationError};
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use serde_json::error::Category;
 use serde_json::Value as JsonValue;
+use serdes_ai_core::code_edits::{CodeEdits, WorkspaceSnapshot};
 use std::any::TypeId;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 /// Trait for validating agent outputs.
 #[async_trait]
@@ -22,6 +29,22 @@ pub trait OutputValidator<Output, Deps>: Send + Sync {
         output: Output,
         ctx: &RunContext<Deps>,
     ) -> Result<Output, OutputValidationError>;
+
+    /// Check text output while it streams.
+    ///
+    /// `run_stream` calls this with the text received so far. Return an
+    /// error once the output can no longer become valid, e.g. when a maximum
+    /// length is exceeded, and generation is aborted early. Constraints that
+    /// need the complete text belong in
+    /// [`check_final_text`](Self::check_final_text).
+    fn check_partial_text(&self, _text: &str) -> Result<(), OutputValidationError> {
+        Ok(())
+    }
+
+    /// Check the complete text output of a streamed run.
+    fn check_final_text(&self, _text: &str) -> Result<(), OutputValidationError> {
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -121,10 +144,21 @@ impl<Deps: Send + Sync> OutputValidator<String, Deps> for NonEmptyValidator {
         output: String,
         _ctx: &RunContext<Deps>,
     ) -> Result<String, OutputValidationError> {
-        if output.trim().is_empty() {
+        self.check(&output)?;
+        Ok(output)
+    }
+
+    fn check_final_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.check(text)
+    }
+}
+
+impl NonEmptyValidator {
+    fn check(&self, text: &str) -> Result<(), OutputValidationError> {
+        if text.trim().is_empty() {
             Err(OutputValidationError::failed("Output cannot be empty"))
         } else {
-            Ok(output)
+            Ok(())
         }
     }
 }
@@ -170,8 +204,23 @@ impl<Deps: Send + Sync> OutputValidator<String, Deps> for LengthValidator {
         output: String,
         _ctx: &RunContext<Deps>,
     ) -> Result<String, OutputValidationError> {
-        let len = output.len();
+        self.check(&output)?;
+        Ok(output)
+    }
 
+    /// Fails as soon as the maximum length is exceeded.
+    fn check_partial_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.check_max(text.len())
+    }
+
+    fn check_final_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.check(text)
+    }
+}
+
+impl LengthValidator {
+    fn check(&self, text: &str) -> Result<(), OutputValidationError> {
+        let len = text.len();
         if let Some(min) = self.min {
             if len < min {
                 return Err(OutputValidationError::failed(format!(
@@ -180,17 +229,17 @@ impl<Deps: Send + Sync> OutputValidator<String, Deps> for LengthValidator {
                 )));
             }
         }
+        self.check_max(len)
+    }
 
-        if let Some(max) = self.max {
-            if len > max {
-                return Err(OutputValidationError::failed(format!(
-                    "Output too long: {} > {}",
-                    len, max
-                )));
-            }
+    fn check_max(&self, len: usize) -> Result<(), OutputValidationError> {
+        match self.max {
+            Some(max) if len > max => Err(OutputValidationError::failed(format!(
+                "Output too long: {} > {}",
+                len, max
+            ))),
+            _ => Ok(()),
         }
-
-        Ok(output)
     }
 }
 
@@ -220,14 +269,86 @@ impl<Deps: Send + Sync> OutputValidator<String, Deps> for RegexValidator {
         output: String,
         _ctx: &RunContext<Deps>,
     ) -> Result<String, OutputValidationError> {
-        if self.pattern.is_match(&output) {
-            Ok(output)
+        self.check(&output)?;
+        Ok(output)
+    }
+
+    /// Deferred while streaming: a prefix that doesn't match yet may still
+    /// match once complete.
+    fn check_final_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.check(text)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl RegexValidator {
+    fn check(&self, text: &str) -> Result<(), OutputValidationError> {
+        if self.pattern.is_match(text) {
+            Ok(())
         } else {
             Err(OutputValidationError::failed(&self.message))
         }
     }
 }
 
+/// Function returning the workspace a run's code edits apply to.
+pub type WorkspaceSnapshotFn<Deps> = Arc<dyn Fn(&Deps) -> WorkspaceSnapshot + Send + Sync>;
+
+/// Validator checking that [`CodeEdits`] apply cleanly.
+///
+/// Paths are always checked to be relative and inside the workspace. With a
+/// snapshot, every edit is also checked to apply to it; all problems are
+/// reported at once so the model can fix them in one retry.
+pub struct CodeEditsValidator<Deps> {
+    snapshot: Option<WorkspaceSnapshotFn<Deps>>,
+}
+
+impl<Deps> CodeEditsValidator<Deps> {
+    /// Create a validator checking paths only.
+    pub fn new() -> Self {
+        Self { snapshot: None }
+    }
+
+    /// Also check that the edits apply to the snapshot returned by `f`.
+    pub fn with_snapshot<F>(f: F) -> Self
+    where
+        F: Fn(&Deps) -> WorkspaceSnapshot + Send + Sync + 'static,
+    {
+        Self {
+            snapshot: Some(Arc::new(f)),
+        }
+    }
+}
+
+impl<Deps> Default for CodeEditsValidator<Deps> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> OutputValidator<CodeEdits, Deps> for CodeEditsValidator<Deps> {
+    async fn validate(
+        &self,
+        output: CodeEdits,
+        ctx: &RunContext<Deps>,
+    ) -> Result<CodeEdits, OutputValidationError> {
+        let errors = match &self.snapshot {
+            Some(snapshot) => output.check(&snapshot(&ctx.deps)),
+            None => output.validate_paths().err().into_iter().collect(),
+        };
+        if errors.is_empty() {
+            return Ok(output);
+        }
+        Err(OutputValidationError::fields(
+            errors
+                .into_iter()
+                .map(|e| FieldError::new(format!("/edits/{}", e.index), e.message))
+                .collect(),
+        ))
+    }
+}
+
 // ============================================================================
 // Chained Validators
 // ============================================================================
@@ -275,6 +396,18 @@ impl<Output: Send + Sync, Deps: Send + Sync> OutputValidator<Output, Deps>
         }
         Ok(output)
     }
+
+    fn check_partial_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.validators
+            .iter()
+            .try_for_each(|v| v.check_partial_text(text))
+    }
+
+    fn check_final_text(&self, text: &str) -> Result<(), OutputValidationError> {
+        self.validators
+            .iter()
+            .try_for_each(|v| v.check_final_text(text))
+    }
 }
 
 // ============================================================================
@@ -362,9 +495,211 @@ impl<Output: DeserializeOwned + Send + Sync + 'static> OutputSchema<Output>
                 .map_err(OutputParseError::Json)
         } else {
             let json_str = extract_json(text).unwrap_or(text);
-            serde_json::from_str(json_str).map_err(OutputParseError::Json)
+            let value = serde_json::from_str(json_str).map_err(OutputParseError::Json)?;
+            from_json_with_paths(&value)
+        }
+    }
+}
+
+/// Envelope property wrapping list outputs.
+const LIST_ENVELOPE_KEY: &str = "items";
+
+/// Envelope property wrapping primitive outputs.
+const VALUE_ENVELOPE_KEY: &str = "value";
+
+/// Wrap a schema with a non-object root in an envelope object.
+fn envelope_schema(schema: JsonValue) -> JsonValue {
+    let key = match schema.get("type").and_then(JsonValue::as_str) {
+        Some("object") => return schema,
+        Some("array") => LIST_ENVELOPE_KEY,
+        _ => VALUE_ENVELOPE_KEY,
+    };
+    serde_json::json!({
+        "type": "object",
+        "properties": { key: schema },
+        "required": [key],
+    })
+}
+
+/// Wrap an example output the same way [`envelope_schema`] wraps its schema.
+pub(crate) fn envelope_example(value: JsonValue) -> JsonValue {
+    let key = match value {
+        JsonValue::Object(_) => return value,
+        JsonValue::Array(_) => LIST_ENVELOPE_KEY,
+        _ => VALUE_ENVELOPE_KEY,
+    };
+    serde_json::json!({ key: value })
+}
+
+/// Deserialize output, removing an envelope if the type needs it.
+fn from_enveloped<T: DeserializeOwned>(value: JsonValue) -> Result<T, OutputParseError> {
+    match serde_json::from_value(value.clone()) {
+        Ok(output) => Ok(output),
+        Err(e) => match value {
+            JsonValue::Object(map)
+                if map.len() == 1
+                    && (map.contains_key(LIST_ENVELOPE_KEY)
+                        || map.contains_key(VALUE_ENVELOPE_KEY)) =>
+            {
+                let (key, inner) = map.into_iter().next().expect("map has one entry");
+                from_json_with_paths(&inner).map_err(|e| match e {
+                    OutputParseError::InvalidFields(errors) => OutputParseError::InvalidFields(
+                        errors
+                            .into_iter()
+                            .map(|mut error| {
+                                error.path = format!("/{}{}", key, error.path);
+                                error
+                            })
+                            .collect(),
+                    ),
+                    e => e,
+                })
+            }
+            value => Err(locate_error::<T>(&value, e)),
+        },
+    }
+}
+
+/// Deserialize a value, reporting which field failed.
+///
+/// Type errors become [`OutputParseError::InvalidFields`] with the JSON
+/// Pointer of the offending field.
+pub fn from_json_with_paths<T: DeserializeOwned>(value: &JsonValue) -> Result<T, OutputParseError> {
+    serde_json::from_value(value.clone()).map_err(|e| locate_error::<T>(value, e))
+}
+
+fn locate_error<T: DeserializeOwned>(
+    value: &JsonValue,
+    error: serde_json::Error,
+) -> OutputParseError {
+    if error.classify() != Category::Data {
+        return OutputParseError::Json(error);
+    }
+
+    // Values carry no positions; deserializing the serialized text gives the
+    // error a column that maps back to a field.
+    let text = value.to_string();
+    match serde_json::from_str::<T>(&text) {
+        Err(positioned) if positioned.classify() == Category::Data => {
+            OutputParseError::InvalidFields(vec![field_error(&text, &positioned)])
         }
+        _ => OutputParseError::InvalidFields(vec![FieldError::new("", serde_message(&error))]),
+    }
+}
+
+/// Error message without the position suffix.
+fn serde_message(error: &serde_json::Error) -> String {
+    let mut message = error.to_string();
+    if let Some(at) = message.rfind(" at line ") {
+        message.truncate(at);
     }
+    message
+}
+
+/// Build a [`FieldError`] for a serde error raised while reading `text`.
+fn field_error(text: &str, error: &serde_json::Error) -> FieldError {
+    let message = serde_message(error);
+    let mut end = error.column().min(text.len());
+
+    if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        // Reported after the closing brace of the object missing the field
+        end = end.saturating_sub(1);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut path = json_path_at(&text[..end]);
+        if let Some(PathSegment::Key(key)) = path.last_mut() {
+            *key = Some(field.to_string());
+        }
+        return FieldError::new(json_pointer(&path), message.clone()).with_constraint("required");
+    }
+
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let field = FieldError::new(json_pointer(&json_path_at(&text[..end])), message.clone());
+    if let Some(rest) = message
+        .strip_prefix("invalid type: ")
+        .or_else(|| message.strip_prefix("invalid value: "))
+        .or_else(|| message.strip_prefix("unknown variant "))
+    {
+        if let Some((found, expected)) = rest.split_once(", expected ") {
+            return field.with_found(found).with_expected(expected);
+        }
+    }
+    if message.starts_with("unknown field ") {
+        return field.with_constraint("unknown field");
+    }
+    field
+}
+
+/// Position inside a JSON document.
+enum PathSegment {
+    /// Inside an object, at the last key read.
+    Key(Option<String>),
+    /// Inside an array, at an index.
+    Index(usize),
+}
+
+/// Path to the innermost value at the end of a JSON prefix.
+fn json_path_at(prefix: &str) -> Vec<PathSegment> {
+    let mut path = Vec::new();
+    let mut expecting_key = false;
+    let mut chars = prefix.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => string.extend(chars.next()),
+                        '"' => break,
+                        c => string.push(c),
+                    }
+                }
+                if expecting_key {
+                    if let Some(PathSegment::Key(key)) = path.last_mut() {
+                        *key = Some(string);
+                    }
+                    expecting_key = false;
+                }
+            }
+            '{' => {
+                path.push(PathSegment::Key(None));
+                expecting_key = true;
+            }
+            '[' => path.push(PathSegment::Index(0)),
+            '}' | ']' => {
+                path.pop();
+            }
+            ',' => match path.last_mut() {
+                Some(PathSegment::Index(i)) => *i += 1,
+                Some(PathSegment::Key(_)) => expecting_key = true,
+                None => {}
+            },
+            _ => {}
+        }
+    }
+    path
+}
+
+/// Render a path as a JSON Pointer.
+fn json_pointer(path: &[PathSegment]) -> String {
+    path.iter()
+        .filter_map(|segment| match segment {
+            PathSegment::Key(key) => key
+                .as_ref()
+                .map(|k| k.replace('~', "~0").replace('/', "~1")),
+            PathSegment::Index(i) => Some(i.to_string()),
+        })
+        .fold(String::new(), |mut pointer, segment| {
+            pointer.push('/');
+            pointer.push_str(&segment);
+            pointer
+        })
 }
 
 /// JSON output schema (parses JSON to type).
@@ -383,8 +718,10 @@ impl<T: DeserializeOwned> JsonOutputSchema<T> {
     }
 
     /// Set the JSON schema.
+    ///
+    /// Non-object roots are wrapped in an envelope object.
     pub fn with_schema(mut self, schema: JsonValue) -> Self {
-        self.schema = Some(schema);
+        self.schema = Some(envelope_schema(schema));
         self
     }
 }
@@ -407,7 +744,8 @@ impl<T: DeserializeOwned + Send + Sync> OutputSchema<T> for JsonOutputSchema<T>
     fn parse_text(&self, text: &str) -> Result<T, OutputParseError> {
         // Try to extract JSON from the text
         let json_str = extract_json(text).unwrap_or(text);
-        serde_json::from_str(json_str).map_err(OutputParseError::Json)
+        let value = serde_json::from_str(json_str.trim()).map_err(OutputParseError::Json)?;
+        from_enveloped(value)
     }
 }
 
@@ -429,8 +767,11 @@ impl<T: DeserializeOwned> ToolOutputSchema<T> {
     }
 
     /// Set the JSON schema.
+    ///
+    /// Tool arguments must be objects, so non-object roots are wrapped in an
+    /// envelope object.
     pub fn with_schema(mut self, schema: JsonValue) -> Self {
-        self.schema = Some(schema);
+        self.schema = Some(envelope_schema(schema));
         self
     }
 }
@@ -456,7 +797,7 @@ impl<T: DeserializeOwned + Send + Sync> OutputSchema<T> for ToolOutputSchema<T>
         if name != self.tool_name {
             return Err(OutputParseError::ToolNotCalled);
         }
-        serde_json::from_value(args.clone()).map_err(OutputParseError::Json)
+        from_enveloped(args.clone())
     }
 }
 
@@ -487,6 +828,20 @@ fn extract_json(text: &str) -> Option<&str> {
     // Try to find raw JSON
     if let Some(start) = text.find('{') {
         if let Some(end) = text.rfind('}') {
+            if end > start {
+                let candidate = &text[start..=end];
+                // `[{...}, {...}]` is a list, not an object
+                let is_list = text.trim_start().starts_with('[');
+                if !is_list || serde_json::from_str::<JsonValue>(candidate).is_ok() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    // Try to find a raw JSON array
+    if let Some(start) = text.find('[') {
+        if let Some(end) = text.rfind(']') {
             if end > start {
                 return Some(&text[start..=end]);
             }
@@ -499,7 +854,8 @@ fn extract_json(text: &str) -> Option<&str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use chrono::{Offset, Utc};
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     fn make_context() -> RunContext<()> {
@@ -511,11 +867,41 @@ mod tests {
             model_settings: Default::default(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: None,
+            timezone: Utc.fix(),
             metadata: None,
         }
     }
 
+    #[tokio::test]
+    async fn test_code_edits_validator() {
+        use serdes_ai_core::code_edits::FileEdit;
+
+        let ctx = make_context();
+        let edits = CodeEdits::new(vec![
+            FileEdit::replace("src/main.rs", "println!(\"hi\")", "println!(\"hello\")"),
+            FileEdit::replace("../escape.rs", "", "x"),
+            FileEdit::replace("src/lib.rs", "missing", "x"),
+        ]);
+
+        let paths_only = CodeEditsValidator::new();
+        let err = paths_only.validate(edits.clone(), &ctx).await.unwrap_err();
+        assert_eq!(err.field_errors().len(), 1);
+        assert_eq!(err.field_errors()[0].path, "/edits/1");
+
+        let validator = CodeEditsValidator::with_snapshot(|_: &()| {
+            WorkspaceSnapshot::new()
+                .with_file("src/main.rs", "fn main() { println!(\"hi\") }")
+                .with_file("src/lib.rs", "")
+        });
+        let err = validator.validate(edits, &ctx).await.unwrap_err();
+        let paths: Vec<_> = err.field_errors().into_iter().map(|e| e.path).collect();
+        assert_eq!(paths, ["/edits/1", "/edits/2"]);
+    }
+
     #[tokio::test]
     async fn test_non_empty_validator() {
         let validator = NonEmptyValidator;
@@ -546,6 +932,19 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_length_validator_streaming_checks() {
+        let validator = LengthValidator::new().min(5).max(10);
+        let partial = |text| OutputValidator::<String, ()>::check_partial_text(&validator, text);
+        let last = |text| OutputValidator::<String, ()>::check_final_text(&validator, text);
+
+        // Too short is fine while streaming, too long fails fast
+        assert!(partial("hi").is_ok());
+        assert!(partial("hello world!").is_err());
+        assert!(last("hi").is_err());
+        assert!(last("hello").is_ok());
+    }
+
     #[tokio::test]
     async fn test_chained_validator() {
         let validator = ChainedValidator::<String, ()>::new()
@@ -612,5 +1011,77 @@ mod tests {
 
         let text = "```json\n{\"a\": 1}\n```";
         assert!(extract_json(text).is_some());
+
+        let text = "[{\"a\": 1}, {\"a\": 2}]";
+        assert_eq!(extract_json(text), Some(text));
+    }
+
+    #[test]
+    fn test_field_error_paths() {
+        use serde::Deserialize;
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Person {
+            name: String,
+            age: u32,
+            tags: Vec<String>,
+        }
+
+        let value = serde_json::json!({"people": [{"name": "a", "age": 1, "tags": ["x", 3]}]});
+        let err = from_json_with_paths::<HashMap<String, Vec<Person>>>(&value).unwrap_err();
+        let OutputParseError::InvalidFields(errors) = err else {
+            panic!("expected field errors");
+        };
+        assert_eq!(errors[0].path, "/people/0/tags/1");
+        assert_eq!(errors[0].found.as_deref(), Some("integer `3`"));
+        assert_eq!(errors[0].expected.as_deref(), Some("a string"));
+
+        let value = serde_json::json!([{"name": "a", "tags": []}]);
+        let Err(OutputParseError::InvalidFields(errors)) =
+            from_json_with_paths::<Vec<Person>>(&value)
+        else {
+            panic!("expected field errors");
+        };
+        assert_eq!(errors[0].path, "/0/age");
+        assert_eq!(errors[0].constraint.as_deref(), Some("required"));
+
+        // Paths include the envelope the model filled in
+        let schema = ToolOutputSchema::<Vec<Person>>::new("final_result");
+        let args = serde_json::json!({"items": [{"name": "a", "age": -1, "tags": []}]});
+        let Err(OutputParseError::InvalidFields(errors)) =
+            schema.parse_tool_call("final_result", &args)
+        else {
+            panic!("expected field errors");
+        };
+        assert_eq!(errors[0].path, "/items/0/age");
+    }
+
+    #[test]
+    fn test_list_and_primitive_outputs() {
+        let schema = ToolOutputSchema::<Vec<String>>::new("final_result")
+            .with_schema(serde_json::json!({"type": "array", "items": {"type": "string"}}));
+        let wrapped = schema.json_schema().unwrap();
+        assert_eq!(wrapped["type"], "object");
+        assert_eq!(wrapped["properties"]["items"]["type"], "array");
+
+        let args = serde_json::json!({"items": ["a", "b"]});
+        assert_eq!(
+            schema.parse_tool_call("final_result", &args).unwrap(),
+            ["a", "b"]
+        );
+
+        let schema = JsonOutputSchema::<Vec<u32>>::new();
+        assert_eq!(
+            schema.parse_text("Here you go: [1, 2, 3]").unwrap(),
+            [1, 2, 3]
+        );
+        assert_eq!(schema.parse_text(r#"{"items": [4]}"#).unwrap(), [4]);
+
+        let schema =
+            JsonOutputSchema::<u32>::new().with_schema(serde_json::json!({"type": "integer"}));
+        assert_eq!(schema.json_schema().unwrap()["required"][0], "value");
+        assert_eq!(schema.parse_text(r#"{"value": 42}"#).unwrap(), 42);
+        assert_eq!(schema.parse_text("42").unwrap(), 42);
     }
 }
@@ -0,0 +1,495 @@
+//! Prebuilt agents for common patterns.
+//!
+//! Each preset returns an [`AgentBuilder`] with the tools, prompt and output
+//! schema of the pattern already wired together, so it can be customized
+//! further before building:
+//!
+//! - [`Agent::rag_agent`] answers from documents found in a [`VectorStore`]
+//! - [`Agent::sql_agent`] answers by querying a database through a [`SqlPool`]
+//! - [`Agent::router_agent`] picks one of several [`Route`]s for a request
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::Agent;
+//! use serdes_ai_agent::presets::Route;
+//!
+//! let router = Agent::router_agent(
+//!     model,
+//!     vec![
+//!         Route::new("billing", "Invoices, payments and refunds"),
+//!         Route::new("support", "Technical problems with the product"),
+//!     ],
+//! )
+//! .build();
+//!
+//! let decision = router.run("I was charged twice", ()).await?.output;
+//! assert_eq!(decision.route, "billing");
+//! ```
+
+use crate::agent::{Agent, ToolExecutor};
+use crate::builder::AgentBuilder;
+use crate::context::RunContext;
+use crate::errors::OutputValidationError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use serdes_ai_models::Model;
+use serdes_ai_tools::{SchemaBuilder, ToolDefinition, ToolError, ToolReturn};
+use std::sync::Arc;
+
+/// Number of documents the RAG agent retrieves when the model doesn't say.
+pub const DEFAULT_TOP_K: usize = 5;
+
+/// Maximum number of rows the SQL agent passes to the model.
+pub const MAX_SQL_ROWS: usize = 100;
+
+const RAG_INSTRUCTIONS: &str = "Answer questions using the documents returned by the \
+    `search_documents` tool. Search before answering, cite the IDs of the documents you \
+    used, and say so if the documents don't contain the answer.";
+
+const SQL_INSTRUCTIONS: &str = "Answer questions by querying the database. Call \
+    `describe_schema` first to learn the tables, then use `run_sql` with read-only \
+    SELECT statements. Base your answer only on the query results.";
+
+// ============================================================================
+// RAG
+// ============================================================================
+
+/// A document returned by a [`VectorStore`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// Document ID.
+    pub id: String,
+    /// Document text.
+    pub content: String,
+    /// Similarity to the query.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// Extra metadata, such as title or source.
+    #[serde(default, skip_serializing_if = "JsonValue::is_null")]
+    pub metadata: JsonValue,
+}
+
+impl Document {
+    /// Create a document.
+    #[must_use]
+    pub fn new(id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            content: content.into(),
+            score: None,
+            metadata: JsonValue::Null,
+        }
+    }
+
+    /// Set the similarity score.
+    #[must_use]
+    pub fn with_score(mut self, score: f32) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Set the metadata.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: JsonValue) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+/// Semantic search over documents.
+///
+/// Implementations embed the query themselves, so any embedding model and
+/// index can back the RAG agent.
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    /// Find the `top_k` documents most similar to `query`.
+    async fn search(&self, query: &str, top_k: usize) -> Result<Vec<Document>, ToolError>;
+}
+
+#[derive(Deserialize)]
+struct SearchArgs {
+    query: String,
+    #[serde(default)]
+    top_k: Option<usize>,
+}
+
+struct SearchDocumentsExecutor {
+    store: Arc<dyn VectorStore>,
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for SearchDocumentsExecutor {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        _ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        let args: SearchArgs = serde_json::from_value(args)
+            .map_err(|e| ToolError::invalid_arguments("search_documents", e.to_string()))?;
+        let documents = self
+            .store
+            .search(&args.query, args.top_k.unwrap_or(DEFAULT_TOP_K))
+            .await?;
+        Ok(ToolReturn::json(
+            serde_json::to_value(documents).unwrap_or_default(),
+        ))
+    }
+}
+
+// ============================================================================
+// SQL
+// ============================================================================
+
+/// Database access for the SQL agent.
+#[async_trait]
+pub trait SqlPool: Send + Sync {
+    /// Describe the tables and columns, e.g. as `CREATE TABLE` statements.
+    async fn schema(&self) -> Result<String, ToolError>;
+
+    /// Run a query, returning rows as column-to-value maps.
+    async fn query(&self, sql: &str) -> Result<Vec<Map<String, JsonValue>>, ToolError>;
+}
+
+/// Check that a statement only reads data.
+///
+/// This is a guard against the model trying to write, not a sandbox; give
+/// the pool a read-only connection as well.
+fn is_read_only(sql: &str) -> bool {
+    let statement = sql.trim().trim_end_matches(';');
+    if statement.contains(';') {
+        return false;
+    }
+    let first = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    matches!(first.as_str(), "SELECT" | "WITH" | "EXPLAIN")
+}
+
+struct DescribeSchemaExecutor {
+    pool: Arc<dyn SqlPool>,
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for DescribeSchemaExecutor {
+    async fn execute(
+        &self,
+        _args: JsonValue,
+        _ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        Ok(ToolReturn::text(self.pool.schema().await?))
+    }
+}
+
+#[derive(Deserialize)]
+struct RunSqlArgs {
+    sql: String,
+}
+
+struct RunSqlExecutor {
+    pool: Arc<dyn SqlPool>,
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> ToolExecutor<Deps> for RunSqlExecutor {
+    async fn execute(
+        &self,
+        args: JsonValue,
+        _ctx: &RunContext<Deps>,
+    ) -> Result<ToolReturn, ToolError> {
+        let args: RunSqlArgs = serde_json::from_value(args)
+            .map_err(|e| ToolError::invalid_arguments("run_sql", e.to_string()))?;
+        if !is_read_only(&args.sql) {
+            return Err(ToolError::model_retry(
+                "Only single read-only statements (SELECT, WITH, EXPLAIN) are allowed",
+            ));
+        }
+
+        let mut rows = self.pool.query(&args.sql).await?;
+        let row_count = rows.len();
+        rows.truncate(MAX_SQL_ROWS);
+        Ok(ToolReturn::json(serde_json::json!({
+            "rows": rows,
+            "row_count": row_count,
+            "truncated": row_count > MAX_SQL_ROWS,
+        })))
+    }
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+/// A destination the router agent can pick.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    /// Route name returned in [`RouteDecision::route`].
+    pub name: String,
+    /// When to pick this route.
+    pub description: String,
+}
+
+impl Route {
+    /// Create a route.
+    #[must_use]
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Output of the router agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteDecision {
+    /// Name of the chosen route.
+    pub route: String,
+    /// Why the route was chosen.
+    #[serde(default)]
+    pub reason: String,
+}
+
+fn router_instructions(routes: &[Route]) -> String {
+    let mut instructions = String::from(
+        "Pick the route that should handle the user's request and explain why in one \
+         sentence. Routes:\n",
+    );
+    for route in routes {
+        instructions.push_str(&format!("- {}: {}\n", route.name, route.description));
+    }
+    instructions
+}
+
+// ============================================================================
+// Constructors
+// ============================================================================
+
+impl<Deps: Send + Sync + 'static> Agent<Deps, String> {
+    /// Start an agent answering from documents in a vector store.
+    ///
+    /// The agent gets a `search_documents` tool backed by `store` and
+    /// instructions to search before answering and cite document IDs.
+    #[must_use]
+    pub fn rag_agent<M: Model + 'static>(
+        model: M,
+        store: Arc<dyn VectorStore>,
+    ) -> AgentBuilder<Deps, String> {
+        let definition = ToolDefinition::new(
+            "search_documents",
+            "Search the knowledge base for documents relevant to a query",
+        )
+        .with_parameters(
+            SchemaBuilder::new()
+                .string("query", "What to search for", true)
+                .integer("top_k", "Number of documents to return", false)
+                .build()
+                .expect("SchemaBuilder JSON serialization failed"),
+        );
+
+        AgentBuilder::new(model)
+            .instructions(RAG_INSTRUCTIONS)
+            .tool_with_executor(definition, SearchDocumentsExecutor { store })
+    }
+
+    /// Start an agent answering questions about a database.
+    ///
+    /// The agent gets `describe_schema` and `run_sql` tools backed by `pool`.
+    /// `run_sql` rejects statements other than a single `SELECT`, `WITH` or
+    /// `EXPLAIN` and passes at most [`MAX_SQL_ROWS`] rows to the model.
+    #[must_use]
+    pub fn sql_agent<M: Model + 'static>(
+        model: M,
+        pool: Arc<dyn SqlPool>,
+    ) -> AgentBuilder<Deps, String> {
+        let describe = ToolDefinition::new(
+            "describe_schema",
+            "Describe the tables and columns of the database",
+        );
+        let run = ToolDefinition::new("run_sql", "Run a read-only SQL query").with_parameters(
+            SchemaBuilder::new()
+                .string("sql", "A single SELECT statement", true)
+                .build()
+                .expect("SchemaBuilder JSON serialization failed"),
+        );
+
+        AgentBuilder::new(model)
+            .instructions(SQL_INSTRUCTIONS)
+            .tool_with_executor(
+                describe,
+                DescribeSchemaExecutor {
+                    pool: Arc::clone(&pool),
+                },
+            )
+            .tool_with_executor(run, RunSqlExecutor { pool })
+    }
+
+    /// Start an agent that picks one of `routes` for each request.
+    ///
+    /// The output is a [`RouteDecision`] whose route is constrained to the
+    /// route names; other names are sent back to the model for a retry.
+    #[must_use]
+    pub fn router_agent<M: Model + 'static>(
+        model: M,
+        routes: Vec<Route>,
+    ) -> AgentBuilder<Deps, RouteDecision> {
+        let names: Vec<String> = routes.iter().map(|r| r.name.clone()).collect();
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "route": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "Name of the chosen route"
+                },
+                "reason": {
+                    "type": "string",
+                    "description": "Why the route was chosen"
+                }
+            },
+            "required": ["route", "reason"]
+        });
+
+        AgentBuilder::new(model)
+            .instructions(router_instructions(&routes))
+            .output_type_with_schema::<RouteDecision>(schema)
+            .output_validator_fn(move |decision: RouteDecision, _ctx| {
+                if names.contains(&decision.route) {
+                    Ok(decision)
+                } else {
+                    Err(OutputValidationError::failed(format!(
+                        "Unknown route '{}'; pick one of: {}",
+                        decision.route,
+                        names.join(", ")
+                    )))
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::messages::ModelRequestPart;
+    use serdes_ai_core::{FinishReason, ModelResponse, ModelResponsePart};
+    use serdes_ai_models::FunctionModel;
+
+    struct StaticStore;
+
+    #[async_trait]
+    impl VectorStore for StaticStore {
+        async fn search(&self, query: &str, top_k: usize) -> Result<Vec<Document>, ToolError> {
+            Ok(vec![
+                Document::new("doc-1", format!("{query} x{top_k}")).with_score(0.9)
+            ])
+        }
+    }
+
+    struct StaticPool;
+
+    #[async_trait]
+    impl SqlPool for StaticPool {
+        async fn schema(&self) -> Result<String, ToolError> {
+            Ok("CREATE TABLE users (id INTEGER)".to_string())
+        }
+
+        async fn query(&self, _sql: &str) -> Result<Vec<Map<String, JsonValue>>, ToolError> {
+            Ok((0..150)
+                .map(|id| Map::from_iter([("id".to_string(), JsonValue::from(id))]))
+                .collect())
+        }
+    }
+
+    /// Model calling one tool, then answering with the tool's return.
+    fn tool_calling_model(tool: &'static str, args: JsonValue) -> FunctionModel {
+        FunctionModel::new(move |messages, _| {
+            let returned = messages.iter().rev().find_map(|m| {
+                m.parts.iter().find_map(|p| match p {
+                    ModelRequestPart::ToolReturn(r) => Some(r.content.to_string_content()),
+                    _ => None,
+                })
+            });
+            match returned {
+                Some(content) => {
+                    ModelResponse::text(content).with_finish_reason(FinishReason::Stop)
+                }
+                None => ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    tool,
+                    args.clone(),
+                )])
+                .with_finish_reason(FinishReason::ToolCall),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rag_agent_searches_store() {
+        let model = tool_calling_model("search_documents", serde_json::json!({"query": "rust"}));
+        let agent = Agent::rag_agent(model, Arc::new(StaticStore)).build();
+
+        assert_eq!(agent.tools()[0].name, "search_documents");
+        let result = agent.run("What is Rust?", ()).await.unwrap();
+        assert!(result.output().contains("doc-1"));
+        assert!(result.output().contains("rust x5"));
+    }
+
+    #[tokio::test]
+    async fn test_sql_agent_truncates_rows() {
+        let model = tool_calling_model(
+            "run_sql",
+            serde_json::json!({"sql": "SELECT id FROM users"}),
+        );
+        let agent = Agent::sql_agent(model, Arc::new(StaticPool)).build();
+
+        let result = agent.run("How many users?", ()).await.unwrap();
+        let output: JsonValue = serde_json::from_str(result.output()).unwrap();
+        assert_eq!(output["row_count"], 150);
+        assert_eq!(output["truncated"], true);
+        assert_eq!(output["rows"].as_array().unwrap().len(), MAX_SQL_ROWS);
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("SELECT * FROM users;"));
+        assert!(is_read_only("  with t as (select 1) select * from t"));
+        assert!(!is_read_only("DELETE FROM users"));
+        assert!(!is_read_only("SELECT 1; DROP TABLE users"));
+    }
+
+    #[tokio::test]
+    async fn test_router_agent_retries_unknown_route() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let model = {
+            let calls = Arc::clone(&calls);
+            FunctionModel::new(move |_, _| {
+                let route = match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => "sales",
+                    _ => "billing",
+                };
+                ModelResponse::text(format!(r#"{{"route": "{route}", "reason": "charges"}}"#))
+                    .with_finish_reason(FinishReason::Stop)
+            })
+        };
+        let agent = Agent::router_agent(
+            model,
+            vec![
+                Route::new("billing", "Invoices and payments"),
+                Route::new("support", "Technical problems"),
+            ],
+        )
+        .build();
+
+        let schema = agent.output_json_schema().unwrap();
+        assert_eq!(
+            schema["properties"]["route"]["enum"],
+            serde_json::json!(["billing", "support"])
+        );
+
+        let result = agent.run("I was charged twice", ()).await.unwrap();
+        assert_eq!(result.output.route, "billing");
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+}
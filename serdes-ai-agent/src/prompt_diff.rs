@@ -0,0 +1,387 @@
+//! Diffing the prompts sent by two runs.
+//!
+//! When an agent's output changes between two runs, the cause is usually in
+//! what was sent to the model: a reworded system prompt, a changed tool
+//! schema, or a history processor dropping different messages. A
+//! [`PromptDiff`] compares two sets of messages part by part and reports
+//! those differences.
+//!
+//! Fields that change on every run (timestamps, usage, vendor ids) are
+//! ignored.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::PromptDiff;
+//!
+//! let before = debugger_a.frame(0).unwrap();
+//! let after = debugger_b.frame(0).unwrap();
+//! let diff = PromptDiff::requests(&before.request, &after.request);
+//! if !diff.is_empty() {
+//!     println!("{diff}");
+//! }
+//! ```
+
+use crate::run::PreparedRequest;
+use serde_json::Value as JsonValue;
+use serdes_ai_core::messages::latest_instructions;
+use serdes_ai_core::{ModelRequest, ModelRequestPart};
+use serdes_ai_tools::ToolDefinition;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Serialized fields that differ between otherwise identical runs.
+const VOLATILE_KEYS: &[&str] = &["timestamp", "usage", "vendor_id", "vendor_details"];
+
+/// A single difference between two prompts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptChange {
+    /// The combined system prompt changed.
+    SystemPrompt {
+        /// System prompt before.
+        before: String,
+        /// System prompt after.
+        after: String,
+    },
+    /// The latest instructions changed.
+    Instructions {
+        /// Instructions before.
+        before: Option<String>,
+        /// Instructions after.
+        after: Option<String>,
+    },
+    /// A tool is only offered in the second prompt.
+    ToolAdded(ToolDefinition),
+    /// A tool is only offered in the first prompt.
+    ToolRemoved(ToolDefinition),
+    /// A tool's description or schema changed.
+    ToolChanged {
+        /// Tool definition before.
+        before: ToolDefinition,
+        /// Tool definition after.
+        after: ToolDefinition,
+    },
+    /// Leading history parts were dropped, and possibly replaced (e.g. by a
+    /// summary), while the rest of the conversation is unchanged.
+    HistoryTruncated {
+        /// Number of parts dropped from the start of the history.
+        dropped: usize,
+        /// Parts inserted in their place.
+        replaced_with: Vec<JsonValue>,
+    },
+    /// A conversation part changed.
+    PartChanged {
+        /// Index of the part in the conversation.
+        index: usize,
+        /// Part before.
+        before: JsonValue,
+        /// Part after.
+        after: JsonValue,
+    },
+    /// A conversation part is only in the second prompt.
+    PartAdded {
+        /// Index of the part in the second conversation.
+        index: usize,
+        /// The part.
+        part: JsonValue,
+    },
+    /// A conversation part is only in the first prompt.
+    PartRemoved {
+        /// Index of the part in the first conversation.
+        index: usize,
+        /// The part.
+        part: JsonValue,
+    },
+}
+
+/// Part-level differences between two prompts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptDiff {
+    changes: Vec<PromptChange>,
+}
+
+impl PromptDiff {
+    /// Diff two message histories.
+    pub fn messages(before: &[ModelRequest], after: &[ModelRequest]) -> Self {
+        let mut changes = Vec::new();
+
+        let (system_before, system_after) = (system_prompt(before), system_prompt(after));
+        if system_before != system_after {
+            changes.push(PromptChange::SystemPrompt {
+                before: system_before,
+                after: system_after,
+            });
+        }
+
+        let (instructions_before, instructions_after) =
+            (latest_instructions(before), latest_instructions(after));
+        if instructions_before != instructions_after {
+            changes.push(PromptChange::Instructions {
+                before: instructions_before.map(str::to_string),
+                after: instructions_after.map(str::to_string),
+            });
+        }
+
+        diff_conversation(&conversation(before), &conversation(after), &mut changes);
+        Self { changes }
+    }
+
+    /// Diff two prepared requests, including their tool definitions.
+    pub fn requests(before: &PreparedRequest, after: &PreparedRequest) -> Self {
+        Self::messages(&before.messages, &after.messages)
+            .with_tools(&before.params.tools, &after.params.tools)
+    }
+
+    /// Add the differences between two sets of tool definitions.
+    #[must_use]
+    pub fn with_tools(mut self, before: &[ToolDefinition], after: &[ToolDefinition]) -> Self {
+        let before: BTreeMap<_, _> = before.iter().map(|t| (t.name.as_str(), t)).collect();
+        let after: BTreeMap<_, _> = after.iter().map(|t| (t.name.as_str(), t)).collect();
+
+        for (name, tool) in &before {
+            match after.get(name) {
+                None => self
+                    .changes
+                    .push(PromptChange::ToolRemoved((*tool).clone())),
+                Some(new) if new != tool => self.changes.push(PromptChange::ToolChanged {
+                    before: (*tool).clone(),
+                    after: (*new).clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (name, tool) in &after {
+            if !before.contains_key(name) {
+                self.changes.push(PromptChange::ToolAdded((*tool).clone()));
+            }
+        }
+        self
+    }
+
+    /// Get the changes.
+    pub fn changes(&self) -> &[PromptChange] {
+        &self.changes
+    }
+
+    /// Check if the prompts are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for PromptDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "prompts are identical");
+        }
+        for change in &self.changes {
+            match change {
+                PromptChange::SystemPrompt { before, after } => {
+                    writeln!(f, "~ system prompt")?;
+                    writeln!(f, "  - {before}")?;
+                    writeln!(f, "  + {after}")?;
+                }
+                PromptChange::Instructions { before, after } => {
+                    writeln!(f, "~ instructions")?;
+                    writeln!(f, "  - {}", before.as_deref().unwrap_or("<none>"))?;
+                    writeln!(f, "  + {}", after.as_deref().unwrap_or("<none>"))?;
+                }
+                PromptChange::ToolAdded(tool) => writeln!(f, "+ tool {}", tool.name)?,
+                PromptChange::ToolRemoved(tool) => writeln!(f, "- tool {}", tool.name)?,
+                PromptChange::ToolChanged { before, after } => {
+                    writeln!(f, "~ tool {}", before.name)?;
+                    if before.description != after.description {
+                        writeln!(f, "  - {}", before.description)?;
+                        writeln!(f, "  + {}", after.description)?;
+                    }
+                    if before.parameters_json_schema != after.parameters_json_schema {
+                        writeln!(f, "  - {}", before.parameters_json_schema)?;
+                        writeln!(f, "  + {}", after.parameters_json_schema)?;
+                    }
+                }
+                PromptChange::HistoryTruncated {
+                    dropped,
+                    replaced_with,
+                } => {
+                    writeln!(f, "~ history: dropped {dropped} leading part(s)")?;
+                    for part in replaced_with {
+                        writeln!(f, "  + {part}")?;
+                    }
+                }
+                PromptChange::PartChanged {
+                    index,
+                    before,
+                    after,
+                } => {
+                    writeln!(f, "~ part {index}")?;
+                    writeln!(f, "  - {before}")?;
+                    writeln!(f, "  + {after}")?;
+                }
+                PromptChange::PartAdded { index, part } => writeln!(f, "+ part {index}: {part}")?,
+                PromptChange::PartRemoved { index, part } => {
+                    writeln!(f, "- part {index}: {part}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// All system prompt parts, joined.
+fn system_prompt(messages: &[ModelRequest]) -> String {
+    messages
+        .iter()
+        .flat_map(|m| m.system_prompts())
+        .map(|p| p.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Every non-system part, serialized without volatile fields.
+fn conversation(messages: &[ModelRequest]) -> Vec<JsonValue> {
+    messages
+        .iter()
+        .flat_map(|m| &m.parts)
+        .filter(|p| !matches!(p, ModelRequestPart::SystemPrompt(_)))
+        .map(|p| {
+            let mut value = serde_json::to_value(p).unwrap_or(JsonValue::Null);
+            strip_volatile(&mut value);
+            value
+        })
+        .collect()
+}
+
+fn strip_volatile(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            map.retain(|k, _| !VOLATILE_KEYS.contains(&k.as_str()));
+            map.values_mut().for_each(strip_volatile);
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(strip_volatile),
+        _ => {}
+    }
+}
+
+fn diff_conversation(before: &[JsonValue], after: &[JsonValue], changes: &mut Vec<PromptChange>) {
+    let prefix = before.iter().zip(after).take_while(|(a, b)| a == b).count();
+    let suffix = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let removed = &before[prefix..before.len() - suffix];
+    let added = &after[prefix..after.len() - suffix];
+
+    // The conversation is intact except for its start: a history processor
+    // dropped (or summarized) old parts.
+    if prefix == 0 && suffix > 0 && removed.len() > added.len() {
+        changes.push(PromptChange::HistoryTruncated {
+            dropped: removed.len(),
+            replaced_with: added.to_vec(),
+        });
+        return;
+    }
+
+    for (i, (old, new)) in removed.iter().zip(added).enumerate() {
+        changes.push(PromptChange::PartChanged {
+            index: prefix + i,
+            before: old.clone(),
+            after: new.clone(),
+        });
+    }
+    let common = removed.len().min(added.len());
+    for (i, part) in removed.iter().enumerate().skip(common) {
+        changes.push(PromptChange::PartRemoved {
+            index: prefix + i,
+            part: part.clone(),
+        });
+    }
+    for (i, part) in added.iter().enumerate().skip(common) {
+        changes.push(PromptChange::PartAdded {
+            index: prefix + i,
+            part: part.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn history(system: &str, prompts: &[&str]) -> Vec<ModelRequest> {
+        let mut request = ModelRequest::new();
+        request.add_system_prompt(system);
+        for prompt in prompts {
+            request.add_user_prompt(*prompt);
+        }
+        vec![request]
+    }
+
+    #[test]
+    fn test_identical_prompts() {
+        let a = history("Be brief.", &["hi"]);
+        // Rebuilt later, so every timestamp differs
+        let b = history("Be brief.", &["hi"]);
+        let diff = PromptDiff::messages(&a, &b);
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "prompts are identical\n");
+    }
+
+    #[test]
+    fn test_system_prompt_and_parts() {
+        let a = history("Be brief.", &["hi", "bye"]);
+        let b = history("Be verbose.", &["hi", "ciao", "later"]);
+        let diff = PromptDiff::messages(&a, &b);
+
+        assert!(matches!(
+            &diff.changes()[0],
+            PromptChange::SystemPrompt { before, after } if before == "Be brief." && after == "Be verbose."
+        ));
+        assert!(matches!(
+            diff.changes()[1],
+            PromptChange::PartChanged { index: 1, .. }
+        ));
+        assert!(matches!(
+            diff.changes()[2],
+            PromptChange::PartAdded { index: 2, .. }
+        ));
+        assert_eq!(diff.changes().len(), 3);
+    }
+
+    #[test]
+    fn test_history_truncation() {
+        let a = history("sys", &["one", "two", "three", "four"]);
+        let b = history("sys", &["summary", "four"]);
+        let diff = PromptDiff::messages(&a, &b);
+
+        match diff.changes() {
+            [PromptChange::HistoryTruncated {
+                dropped,
+                replaced_with,
+            }] => {
+                assert_eq!(*dropped, 3);
+                assert_eq!(replaced_with.len(), 1);
+            }
+            other => panic!("unexpected changes: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_tool_changes() {
+        let search = ToolDefinition::new("search", "Search the web");
+        let lookup = ToolDefinition::new("lookup", "Look up a record");
+        let search_v2 = ToolDefinition::new("search", "Search the web")
+            .with_parameters(json!({"type": "object", "properties": {"q": {"type": "string"}}}));
+
+        let diff = PromptDiff::default().with_tools(&[search, lookup.clone()], &[search_v2]);
+        assert_eq!(diff.changes().len(), 2);
+        assert_eq!(diff.changes()[0], PromptChange::ToolRemoved(lookup));
+        assert!(matches!(
+            diff.changes()[1],
+            PromptChange::ToolChanged { .. }
+        ));
+        assert!(diff.to_string().contains("~ tool search"));
+    }
+}
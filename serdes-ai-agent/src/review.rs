@@ -0,0 +1,257 @@
+//! Reviewer pass over draft outputs.
+//!
+//! A [`Reviewer`] checks the output of a run with a second model (or agent)
+//! before it is returned. The reviewer replies either with an approval or a
+//! critique. In [`ReviewMode::Revise`] a critique is sent back to the agent,
+//! which revises its answer once; in [`ReviewMode::Critique`] the draft is
+//! kept and the critique is attached to the result as [`Review`].
+//!
+//! The [`ReviewTrigger`] decides which runs are reviewed, so the extra
+//! request can be limited to runs whose output failed validation first or
+//! whose confidence is low.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, Reviewer, ReviewTrigger};
+//!
+//! let agent = agent(model)
+//!     .with_reviewer(
+//!         Reviewer::new(judge_model)
+//!             .instructions("Check that every claim cites a source.")
+//!             .trigger(ReviewTrigger::ValidationFailed),
+//!     )
+//!     .build();
+//!
+//! let result = agent.run("Summarize the report", ()).await?;
+//! if let Some(review) = result.review() {
+//!     println!("approved: {}, revised: {}", review.approved, review.revised);
+//! }
+//! ```
+
+use crate::agent::Agent;
+use crate::context::RunUsage;
+use crate::errors::AgentRunError;
+use serde::{Deserialize, Serialize};
+use serdes_ai_core::ModelResponse;
+use serdes_ai_models::Model;
+use std::fmt;
+use std::sync::Arc;
+
+/// Reply of a reviewer that has no changes to request.
+pub const APPROVED: &str = "APPROVED";
+
+const REVIEWER_SYSTEM_PROMPT: &str = "You review draft answers written by an AI assistant \
+    before they are shown to the user. Be specific and brief.";
+
+const DEFAULT_REVIEW_INSTRUCTIONS: &str = "Check the draft for factual errors, parts of the \
+    request it does not address, and unclear wording.";
+
+/// Whether a reviewed draft is revised or only annotated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReviewMode {
+    /// Send the critique back to the agent and return its revised answer.
+    #[default]
+    Revise,
+    /// Keep the draft and attach the critique to the result.
+    Critique,
+}
+
+/// What a [`ReviewTrigger::When`] predicate sees of a finished run.
+#[derive(Debug, Clone, Copy)]
+pub struct ReviewContext<'a> {
+    /// The user prompt of the run.
+    pub prompt: &'a str,
+    /// The draft output, as text or JSON.
+    pub draft: &'a str,
+    /// Number of times the output failed parsing or validation.
+    pub output_retries: u32,
+    /// Heuristic confidence in the draft, see
+    /// [`HeuristicEstimator`](crate::HeuristicEstimator).
+    pub confidence: f64,
+}
+
+/// Predicate deciding whether a run is reviewed.
+pub type ReviewPredicate = Arc<dyn Fn(&ReviewContext<'_>) -> bool + Send + Sync>;
+
+/// Which runs are reviewed.
+#[derive(Clone, Default)]
+pub enum ReviewTrigger {
+    /// Review every run.
+    #[default]
+    Always,
+    /// Review runs whose output failed parsing or validation at least once.
+    ValidationFailed,
+    /// Review runs whose heuristic confidence is below a threshold.
+    LowConfidence(f64),
+    /// Review runs matching a predicate.
+    When(ReviewPredicate),
+}
+
+impl ReviewTrigger {
+    /// Review runs matching `f`.
+    pub fn when<F>(f: F) -> Self
+    where
+        F: Fn(&ReviewContext<'_>) -> bool + Send + Sync + 'static,
+    {
+        Self::When(Arc::new(f))
+    }
+
+    fn matches(&self, ctx: &ReviewContext<'_>) -> bool {
+        match self {
+            Self::Always => true,
+            Self::ValidationFailed => ctx.output_retries > 0,
+            Self::LowConfidence(threshold) => ctx.confidence < *threshold,
+            Self::When(f) => f(ctx),
+        }
+    }
+}
+
+impl fmt::Debug for ReviewTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => f.write_str("Always"),
+            Self::ValidationFailed => f.write_str("ValidationFailed"),
+            Self::LowConfidence(threshold) => {
+                f.debug_tuple("LowConfidence").field(threshold).finish()
+            }
+            Self::When(_) => f.write_str("When(<fn>)"),
+        }
+    }
+}
+
+/// Outcome of a reviewer pass.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    /// The reviewer's reply.
+    pub critique: String,
+    /// Whether the reviewer approved the draft.
+    pub approved: bool,
+    /// Whether the agent revised its answer after the critique.
+    pub revised: bool,
+}
+
+/// Second pass checking the output of a run.
+#[derive(Clone)]
+pub struct Reviewer {
+    agent: Arc<Agent<(), String>>,
+    mode: ReviewMode,
+    trigger: ReviewTrigger,
+    instructions: String,
+}
+
+impl Reviewer {
+    /// Create a reviewer backed by a model.
+    pub fn new<M: Model + 'static>(model: M) -> Self {
+        Self::from_agent(
+            crate::builder::agent(model)
+                .system_prompt(REVIEWER_SYSTEM_PROMPT)
+                .build(),
+        )
+    }
+
+    /// Create a reviewer backed by an agent, e.g. one with its own tools.
+    pub fn from_agent(agent: Agent<(), String>) -> Self {
+        Self {
+            agent: Arc::new(agent),
+            mode: ReviewMode::default(),
+            trigger: ReviewTrigger::default(),
+            instructions: DEFAULT_REVIEW_INSTRUCTIONS.to_string(),
+        }
+    }
+
+    /// Set whether drafts are revised or only annotated.
+    #[must_use]
+    pub fn mode(mut self, mode: ReviewMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set which runs are reviewed.
+    #[must_use]
+    pub fn trigger(mut self, trigger: ReviewTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Set what the reviewer checks the draft for.
+    #[must_use]
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+
+    /// Get the review mode.
+    pub fn review_mode(&self) -> ReviewMode {
+        self.mode
+    }
+
+    pub(crate) fn should_review(&self, ctx: &ReviewContext<'_>) -> bool {
+        self.trigger.matches(ctx)
+    }
+
+    /// Review a draft, returning the review and the reviewer's usage.
+    pub(crate) async fn review(
+        &self,
+        prompt: &str,
+        draft: &str,
+    ) -> Result<(Review, RunUsage), AgentRunError> {
+        let request = format!(
+            "{}\n\nIf the draft needs no changes, reply with {} only. Otherwise list the \
+             problems to fix.\n\n<request>\n{}\n</request>\n\n<draft>\n{}\n</draft>",
+            self.instructions, APPROVED, prompt, draft
+        );
+        // Boxed, since the reviewer's own run may have a reviewer
+        let result = Box::pin(self.agent.run(request, ())).await?;
+        let critique = result.output.trim().to_string();
+        let review = Review {
+            approved: critique.starts_with(APPROVED),
+            critique,
+            revised: false,
+        };
+        Ok((review, result.usage))
+    }
+}
+
+impl<M: Model + 'static> From<M> for Reviewer {
+    fn from(model: M) -> Self {
+        Self::new(model)
+    }
+}
+
+impl From<Agent<(), String>> for Reviewer {
+    fn from(agent: Agent<(), String>) -> Self {
+        Self::from_agent(agent)
+    }
+}
+
+impl fmt::Debug for Reviewer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reviewer")
+            .field("model", &self.agent.model().name())
+            .field("mode", &self.mode)
+            .field("trigger", &self.trigger)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Message asking the agent to revise its answer.
+pub(crate) fn revision_prompt(critique: &str) -> String {
+    format!(
+        "A reviewer found problems with your answer:\n\n{}\n\nRevise your answer to fix them.",
+        critique
+    )
+}
+
+/// Text of a draft response: its text, or the arguments of its tool calls.
+pub(crate) fn draft_text(response: &ModelResponse) -> String {
+    let text = response.text_content();
+    if !text.is_empty() {
+        return text;
+    }
+    response
+        .tool_call_parts()
+        .map(|call| call.args.to_json().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
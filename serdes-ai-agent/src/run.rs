@@ -2,20 +2,43 @@
 //!
 //! This module contains the core execution logic for agent runs.
 
-use crate::agent::{Agent, EndStrategy};
-use crate::context::{generate_run_id, RunContext, RunUsage, UsageLimits};
-use crate::errors::{AgentRunError, OutputParseError, OutputValidationError};
-use chrono::Utc;
+use crate::agent::{Agent, EndStrategy, RegisteredTool, RequestSettingsFn, ToolFailurePolicy};
+use crate::audit::AuditStatus;
+use crate::confidence::{ConfidenceSignals, HeuristicEstimator};
+use crate::context::{idempotency_key, RunContext, RunUsage, UsageLimits};
+use crate::errors::{AgentRunError, OutputParseError, OutputValidationError, UsageLimitError};
+use crate::fork::{full_history, ConversationBranch};
+use crate::history::transform_responses;
+use crate::language::prompt_text;
+use crate::review::{draft_text, revision_prompt, Review, ReviewContext, ReviewMode};
+use chrono::{FixedOffset, Offset, Utc};
 use serde_json::Value as JsonValue;
-use serdes_ai_core::messages::{RetryPromptPart, ToolCallArgs, ToolReturnPart, UserContent};
+use serdes_ai_core::messages::{
+    JsonRepairer, RetryPromptPart, ToolCallArgs, ToolReturnPart, UserContent,
+};
+use serdes_ai_core::now_utc;
 use serdes_ai_core::{
-    FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
+    ApiKey, FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart,
+    ModelSettings,
+};
+use serdes_ai_models::{Model, ModelRequestParameters};
+use serdes_ai_tools::{
+    ToolCallRecord, ToolCallStats, ToolDefinition, ToolError, ToolReturn, ToolStats,
 };
-use serdes_ai_models::ModelRequestParameters;
-use serdes_ai_tools::{ToolError, ToolReturn};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio_util::sync::CancellationToken;
 
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
 /// Context compression strategy.
 #[derive(Debug, Clone, Default)]
 pub enum CompressionStrategy {
@@ -50,7 +73,7 @@ impl Default for ContextCompression {
 /// Options for a run.
 #[derive(Debug, Clone, Default)]
 pub struct RunOptions {
-    /// Override model settings.
+    /// Model settings for this run, merged over the agent defaults.
     pub model_settings: Option<ModelSettings>,
     /// Message history to continue from.
     pub message_history: Option<Vec<ModelRequest>>,
@@ -60,6 +83,14 @@ pub struct RunOptions {
     pub metadata: Option<JsonValue>,
     /// Context compression configuration.
     pub compression: Option<ContextCompression>,
+    /// API key for this run's model requests.
+    pub api_key: Option<ApiKey>,
+    /// Text the model's reply is made to start with.
+    pub assistant_prefill: Option<String>,
+    /// Session key keeping this conversation on the same backend.
+    pub session_affinity: Option<String>,
+    /// Timezone of the user, for dates in instructions and tools.
+    pub timezone: Option<FixedOffset>,
 }
 
 impl RunOptions {
@@ -68,7 +99,9 @@ impl RunOptions {
         Self::default()
     }
 
-    /// Set model settings override.
+    /// Set model settings for this run.
+    ///
+    /// Fields set here override the agent defaults; unset fields keep them.
     pub fn model_settings(mut self, settings: ModelSettings) -> Self {
         self.model_settings = Some(settings);
         self
@@ -91,6 +124,48 @@ impl RunOptions {
         self.compression = Some(config);
         self
     }
+
+    /// Set the API key for this run's model requests.
+    ///
+    /// Overrides both the model's configured key and the agent's key
+    /// resolver.
+    pub fn api_key(mut self, key: impl Into<ApiKey>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Keep this run's requests on the same backend as earlier runs with
+    /// the same key.
+    ///
+    /// Use the conversation ID, so every turn of a conversation is routed
+    /// where its prompt is already cached. Honored by the fallback model and
+    /// sent to OpenAI as `prompt_cache_key`; see
+    /// [`ModelSettings::session_affinity`].
+    pub fn session_affinity(mut self, key: impl Into<String>) -> Self {
+        self.session_affinity = Some(key.into());
+        self
+    }
+
+    /// Set the user's timezone.
+    ///
+    /// Available to instructions and tools as
+    /// [`RunContext::timezone`]; UTC by default.
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Prefill the start of the model's reply.
+    ///
+    /// Each request ends with a partial assistant message holding `text`, so
+    /// the model continues from it, e.g. `"{"` to force a JSON object. The
+    /// prefill is prepended to the reply's text before the output is parsed.
+    /// Providers without assistant prefill support see it as a regular
+    /// assistant message.
+    pub fn assistant_prefill(mut self, text: impl Into<String>) -> Self {
+        self.assistant_prefill = Some(text.into());
+        self
+    }
 }
 
 /// Result of an agent run.
@@ -110,6 +185,12 @@ pub struct AgentRunResult<Output> {
     pub finish_reason: FinishReason,
     /// Metadata.
     pub metadata: Option<JsonValue>,
+    /// Tool call statistics for this run, by tool name.
+    pub tool_stats: HashMap<String, ToolCallStats>,
+    /// Outcome of the reviewer pass, if the run was reviewed.
+    pub review: Option<Review>,
+    /// Confidence in the output, between 0 and 1.
+    pub confidence: f64,
 }
 
 impl<Output> AgentRunResult<Output> {
@@ -118,10 +199,37 @@ impl<Output> AgentRunResult<Output> {
         &self.output
     }
 
+    /// Get tool call statistics for this run, by tool name.
+    pub fn tool_stats(&self) -> &HashMap<String, ToolCallStats> {
+        &self.tool_stats
+    }
+
+    /// Get the outcome of the reviewer pass, if the run was reviewed.
+    pub fn review(&self) -> Option<&Review> {
+        self.review.as_ref()
+    }
+
+    /// Get the confidence in the output, between 0 and 1.
+    ///
+    /// Computed by the agent's
+    /// [`ConfidenceEstimator`](crate::ConfidenceEstimator), e.g. to route
+    /// low-confidence answers to a human.
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
     /// Consume and return output.
     pub fn into_output(self) -> Output {
         self.output
     }
+
+    /// Start a branchable conversation from this run's history.
+    ///
+    /// The history includes the final response, so the conversation can be
+    /// continued, or branched to edit an earlier message and regenerate.
+    pub fn fork(&self) -> ConversationBranch {
+        ConversationBranch::new(full_history(&self.messages, &self.responses))
+    }
 }
 
 /// Active agent run that can be iterated.
@@ -140,6 +248,36 @@ pub struct AgentRun<'a, Deps, Output> {
     run_usage_limits: Option<UsageLimits>,
     /// Cancellation token for this run (if cancellation is enabled).
     cancel_token: Option<CancellationToken>,
+    /// Tools added for this run only.
+    extra_tools: Vec<RegisteredTool<Deps>>,
+    /// Definitions of the agent's tools and the run's extra tools.
+    tool_defs: Arc<Vec<ToolDefinition>>,
+    /// Category the run's tool calls are recorded under for tool routing.
+    tool_usage_category: Option<String>,
+    /// Start of every model reply.
+    assistant_prefill: Option<String>,
+}
+
+/// A model request built for the next step but not yet sent.
+#[derive(Debug, Clone)]
+pub struct PreparedRequest {
+    /// Messages to send, after history processing and with instructions.
+    pub messages: Vec<ModelRequest>,
+    /// Settings for the request.
+    pub settings: ModelSettings,
+    /// Tools and output parameters for the request.
+    pub params: ModelRequestParameters,
+}
+
+/// Run state saved before a step, used to rewind the run.
+#[derive(Debug, Clone)]
+pub(crate) struct RunSnapshot {
+    messages: Vec<ModelRequest>,
+    responses: Vec<ModelResponse>,
+    step: u32,
+    output_retries: u32,
+    tool_retries: HashMap<String, u32>,
+    finish_reason: Option<FinishReason>,
 }
 
 struct AgentRunState<Output> {
@@ -149,23 +287,205 @@ struct AgentRunState<Output> {
     run_id: String,
     step: u32,
     output_retries: u32,
+    /// Retries requested through `ToolError::ModelRetry`, by tool name.
+    tool_retries: HashMap<String, u32>,
+    /// Results of non-idempotent tool calls, by idempotency key.
+    completed_calls: HashMap<String, ToolReturn>,
+    tool_stats: Arc<ToolStats>,
     final_output: Option<Output>,
     finished: bool,
     finish_reason: Option<FinishReason>,
+    review: Option<Review>,
+    /// Number of tool calls whose arguments were repaired.
+    repairs: u32,
+    confidence: Option<f64>,
 }
 
 /// Canonicalize tool-call arguments in a model response before persisting it.
 ///
-/// Models can emit malformed JSON-ish strings in tool call args. We execute tools
-/// with `to_json()` (which repairs/falls back), and we must persist that canonical
-/// form into history so subsequent provider requests don't carry raw malformed args.
-fn canonicalize_tool_call_args_in_response(response: &mut ModelResponse) {
+/// Models can emit malformed JSON-ish strings in tool call args. We repair them
+/// with the agent's [`JsonRepairer`] (falling back to a `_raw` wrapper), and we
+/// must persist that canonical form into history so subsequent provider
+/// requests don't carry raw malformed args.
+///
+/// Returns the number of calls whose arguments were repaired.
+fn canonicalize_tool_call_args_in_response(
+    response: &mut ModelResponse,
+    repairer: &JsonRepairer,
+) -> u32 {
+    let mut repairs = 0;
     for part in &mut response.parts {
         if let ModelResponsePart::ToolCall(tc) = part {
-            let repaired = tc.args.to_json();
+            let (repaired, passes) = tc.args.to_json_with(repairer);
+            if !passes.is_empty() {
+                repairs += 1;
+                warn!(
+                    tool_name = %tc.tool_name,
+                    passes = ?passes,
+                    "Repaired malformed tool call arguments"
+                );
+            }
             tc.args = ToolCallArgs::Json(repaired);
         }
     }
+    repairs
+}
+
+/// Record a tool's [`ToolError::ModelRetry`] and build the retry prompt
+/// carrying the tool's guidance.
+///
+/// Fails once the tool has asked for more than `max_retries` retries.
+pub(crate) fn tool_retry_prompt(
+    tool_retries: &mut HashMap<String, u32>,
+    tool_name: &str,
+    tool_call_id: Option<String>,
+    message: String,
+    max_retries: u32,
+) -> Result<RetryPromptPart, AgentRunError> {
+    let retries = tool_retries.entry(tool_name.to_string()).or_insert(0);
+    *retries += 1;
+    if *retries > max_retries {
+        return Err(AgentRunError::MaxRetriesExceeded {
+            message: format!(
+                "Tool '{}' exceeded max retries count of {}",
+                tool_name, max_retries
+            ),
+        });
+    }
+
+    let mut part = RetryPromptPart::new(message).with_tool_name(tool_name);
+    if let Some(id) = tool_call_id {
+        part = part.with_tool_call_id(id);
+    }
+    Ok(part)
+}
+
+/// Put instructions on the latest request and clear them everywhere else.
+///
+/// Requests replayed from history may carry instructions of an earlier run;
+/// only the ones for the current step should reach the model.
+pub(crate) fn set_latest_instructions(messages: &mut [ModelRequest], instructions: Option<String>) {
+    for message in messages.iter_mut() {
+        message.instructions = None;
+    }
+    if let Some(last) = messages.last_mut() {
+        last.instructions = instructions;
+    }
+}
+
+/// Partial assistant message the model continues from.
+pub(crate) fn prefill_request(prefill: &str) -> ModelRequest {
+    ModelRequest::with_parts(vec![ModelRequestPart::ModelResponse(Box::new(
+        ModelResponse::text(prefill),
+    ))])
+}
+
+/// Make a reply continued from `prefill` start with it.
+///
+/// Models return only the continuation, so the prefill is prepended to the
+/// first text part unless the model repeated it. A reply without text or tool
+/// calls becomes the prefill alone.
+pub(crate) fn apply_prefill(response: &mut ModelResponse, prefill: &str) {
+    let first_text = response.parts.iter_mut().find_map(|part| match part {
+        ModelResponsePart::Text(text) => Some(text),
+        _ => None,
+    });
+    match first_text {
+        Some(text) => {
+            if !text.content.starts_with(prefill) {
+                text.content.insert_str(0, prefill);
+            }
+        }
+        None => {
+            let has_tool_calls = response
+                .parts
+                .iter()
+                .any(|part| matches!(part, ModelResponsePart::ToolCall(_)));
+            if !has_tool_calls && !prefill.is_empty() {
+                response.parts.insert(0, ModelResponsePart::text(prefill));
+            }
+        }
+    }
+}
+
+/// Layer run overrides over the agent's default settings.
+///
+/// The API key comes from the run options, falling back to the agent's key
+/// resolver.
+pub(crate) fn resolve_run_settings<Deps, Output>(
+    agent: &Agent<Deps, Output>,
+    options: &RunOptions,
+    deps: &Deps,
+) -> ModelSettings {
+    let mut settings = match &options.model_settings {
+        Some(overrides) => agent.model_settings.merge(overrides),
+        None => agent.model_settings.clone(),
+    };
+    if let Some(key) = options.api_key.clone().or_else(|| {
+        agent
+            .key_resolver
+            .as_ref()
+            .and_then(|resolve| resolve(deps))
+    }) {
+        settings.api_key = Some(key);
+    }
+    if let Some(session) = &options.session_affinity {
+        settings.session_affinity = Some(session.clone());
+    }
+    settings
+}
+
+/// Settings for the next request: the run's settings with hook overrides
+/// applied in order.
+pub(crate) fn request_settings<Deps>(
+    ctx: &RunContext<Deps>,
+    hooks: &[RequestSettingsFn<Deps>],
+) -> ModelSettings {
+    hooks
+        .iter()
+        .filter_map(|hook| hook(ctx))
+        .fold(ctx.model_settings.clone(), |acc, layer| acc.merge(&layer))
+}
+
+/// Estimate the prompt tokens of a request.
+///
+/// Uses the model's tokenizer when it has one, else about four bytes of
+/// serialized messages and tools per token.
+pub(crate) async fn estimate_request_tokens(
+    model: &dyn Model,
+    messages: &[ModelRequest],
+    params: &ModelRequestParameters,
+) -> u64 {
+    if let Ok(tokens) = model.count_tokens(messages).await {
+        return tokens;
+    }
+    let bytes = serde_json::to_string(messages).map_or(0, |s| s.len())
+        + serde_json::to_string(&*params.tools).map_or(0, |s| s.len());
+    (bytes / 4) as u64
+}
+
+/// Reject a request up front if its estimate can't fit the usage limits or
+/// the model's context window.
+pub(crate) async fn check_preflight(
+    model: &dyn Model,
+    messages: &[ModelRequest],
+    params: &ModelRequestParameters,
+    limits: [Option<&UsageLimits>; 2],
+    usage: &RunUsage,
+) -> Result<(), UsageLimitError> {
+    let limits: Vec<_> = limits
+        .into_iter()
+        .flatten()
+        .filter(|l| l.preflight)
+        .collect();
+    if limits.is_empty() {
+        return Ok(());
+    }
+    let estimated = estimate_request_tokens(model, messages, params).await;
+    let context_window = model.profile().context_window;
+    limits
+        .iter()
+        .try_for_each(|l| l.check_preflight(usage, estimated, context_window))
 }
 
 /// Result of a single step.
@@ -195,27 +515,37 @@ where
         deps: Deps,
         options: RunOptions,
     ) -> Result<Self, AgentRunError> {
-        let run_id = generate_run_id();
+        let run_id = agent.new_run_id();
         let deps = Arc::new(deps);
 
-        let model_settings = options
-            .model_settings
-            .unwrap_or_else(|| agent.model_settings.clone());
+        // Run overrides are layered over the agent defaults.
+        let model_settings = resolve_run_settings(agent, &options, &deps);
+        let language = agent.detect_language(&prompt).await;
+        let tool_usage_category = agent.tool_usage_category(&prompt);
+        let tool_defs = agent
+            .run_tool_definitions(&prompt, tool_usage_category.as_deref())
+            .await;
 
         let ctx = RunContext {
             deps: deps.clone(),
             run_id: run_id.clone(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             model_name: agent.model().name().to_string(),
             model_settings: model_settings.clone(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: language.clone(),
+            timezone: options.timezone.unwrap_or_else(|| Utc.fix()),
             metadata: options.metadata.clone(),
         };
 
         // Build initial messages
         let mut messages = options.message_history.unwrap_or_default();
+        // Instructions of earlier runs are not part of the history
+        set_latest_instructions(&mut messages, None);
 
         // Build system prompt
         let system_prompt = agent.build_system_prompt(&ctx).await;
@@ -240,13 +570,23 @@ where
                 run_id,
                 step: 0,
                 output_retries: 0,
+                tool_retries: HashMap::new(),
+                completed_calls: HashMap::new(),
+                tool_stats: agent.new_tool_stats(),
                 final_output: None,
                 finished: false,
                 finish_reason: None,
+                review: None,
+                repairs: 0,
+                confidence: None,
             },
             ctx,
             run_usage_limits: options.usage_limits,
             cancel_token: None,
+            extra_tools: Vec::new(),
+            tool_defs,
+            tool_usage_category,
+            assistant_prefill: options.assistant_prefill,
         })
     }
 
@@ -281,27 +621,37 @@ where
         options: RunOptions,
         cancel_token: CancellationToken,
     ) -> Result<Self, AgentRunError> {
-        let run_id = generate_run_id();
+        let run_id = agent.new_run_id();
         let deps = Arc::new(deps);
 
-        let model_settings = options
-            .model_settings
-            .unwrap_or_else(|| agent.model_settings.clone());
+        // Run overrides are layered over the agent defaults.
+        let model_settings = resolve_run_settings(agent, &options, &deps);
+        let language = agent.detect_language(&prompt).await;
+        let tool_usage_category = agent.tool_usage_category(&prompt);
+        let tool_defs = agent
+            .run_tool_definitions(&prompt, tool_usage_category.as_deref())
+            .await;
 
         let ctx = RunContext {
             deps: deps.clone(),
             run_id: run_id.clone(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             model_name: agent.model().name().to_string(),
             model_settings: model_settings.clone(),
             tool_name: None,
             tool_call_id: None,
+            idempotency_key: None,
             retry_count: 0,
+            max_retries: 0,
+            language: language.clone(),
+            timezone: options.timezone.unwrap_or_else(|| Utc.fix()),
             metadata: options.metadata.clone(),
         };
 
         // Build initial messages
         let mut messages = options.message_history.unwrap_or_default();
+        // Instructions of earlier runs are not part of the history
+        set_latest_instructions(&mut messages, None);
 
         // Build system prompt
         let system_prompt = agent.build_system_prompt(&ctx).await;
@@ -326,24 +676,136 @@ where
                 run_id,
                 step: 0,
                 output_retries: 0,
+                tool_retries: HashMap::new(),
+                completed_calls: HashMap::new(),
+                tool_stats: agent.new_tool_stats(),
                 final_output: None,
                 finished: false,
                 finish_reason: None,
+                review: None,
+                repairs: 0,
+                confidence: None,
             },
             ctx,
             run_usage_limits: options.usage_limits,
             cancel_token: Some(cancel_token),
+            extra_tools: Vec::new(),
+            tool_defs,
+            tool_usage_category,
+            assistant_prefill: options.assistant_prefill,
         })
     }
 
+    /// Add tools for this run only.
+    ///
+    /// Tools whose names clash with the agent's own tools are skipped.
+    #[must_use]
+    pub fn with_tools(mut self, tools: impl IntoIterator<Item = RegisteredTool<Deps>>) -> Self {
+        let mut defs = (*self.tool_defs).clone();
+        for tool in tools {
+            let name = &tool.definition.name;
+            if self.find_tool(name).is_some() {
+                warn!(tool_name = %name, "Skipping run tool that clashes with an existing tool");
+                continue;
+            }
+            defs.push(tool.definition.clone());
+            self.extra_tools.push(tool);
+        }
+        self.tool_defs = Arc::new(defs);
+        self
+    }
+
     /// Run to completion.
+    ///
+    /// If the agent has a reviewer, the output is reviewed before the run
+    /// is finalized.
     pub async fn run_to_completion(mut self) -> Result<AgentRunResult<Output>, AgentRunError> {
         while !self.state.finished {
             self.step().await?;
         }
+        self.run_review().await?;
+        if let Some(estimator) = &self.agent.confidence_estimator {
+            self.state.confidence = estimator.estimate(&self.confidence_signals()).await;
+        }
         self.finalize()
     }
 
+    /// Collect the confidence signals of the finished run.
+    fn confidence_signals(&self) -> ConfidenceSignals {
+        let last_response = self.state.responses.last();
+        let mean_logprob = last_response
+            .and_then(ModelResponse::token_logprobs)
+            .filter(|logprobs| !logprobs.is_empty())
+            .map(|logprobs| logprobs.iter().sum::<f64>() / logprobs.len() as f64);
+        ConfidenceSignals {
+            prompt: self.last_prompt(),
+            output: last_response.map(draft_text).unwrap_or_default(),
+            mean_logprob,
+            output_retries: self.state.output_retries,
+            repairs: self.state.repairs,
+            review: self.state.review.clone(),
+        }
+    }
+
+    /// Text of the latest user prompt.
+    fn last_prompt(&self) -> String {
+        self.state
+            .messages
+            .iter()
+            .rev()
+            .find_map(|req| req.user_prompts().last())
+            .map(|part| prompt_text(&part.content))
+            .unwrap_or_default()
+    }
+
+    /// Review the finished run's output, revising it if requested.
+    ///
+    /// A failing reviewer does not fail the run; the draft is kept.
+    async fn run_review(&mut self) -> Result<(), AgentRunError> {
+        let Some(reviewer) = &self.agent.reviewer else {
+            return Ok(());
+        };
+        let Some(last_response) = self.state.responses.last().cloned() else {
+            return Ok(());
+        };
+        let signals = self.confidence_signals();
+        let ctx = ReviewContext {
+            prompt: &signals.prompt,
+            draft: &signals.output,
+            output_retries: self.state.output_retries,
+            confidence: HeuristicEstimator.score(&signals),
+        };
+        if !reviewer.should_review(&ctx) {
+            return Ok(());
+        }
+
+        let (mut review, usage) = match reviewer.review(ctx.prompt, ctx.draft).await {
+            Ok(reviewed) => reviewed,
+            Err(_e) => {
+                warn!(run_id = %self.state.run_id, error = %_e, "Reviewer failed, keeping draft");
+                return Ok(());
+            }
+        };
+        self.state.usage.add_run(&usage);
+
+        if !review.approved && reviewer.review_mode() == ReviewMode::Revise {
+            self.state.messages.push(ModelRequest::with_parts(vec![
+                ModelRequestPart::ModelResponse(Box::new(last_response)),
+            ]));
+            let mut req = ModelRequest::new();
+            req.add_user_prompt(revision_prompt(&review.critique));
+            self.state.messages.push(req);
+            self.state.final_output = None;
+            self.state.finished = false;
+            while !self.state.finished {
+                self.step().await?;
+            }
+            review.revised = true;
+        }
+        self.state.review = Some(review);
+        Ok(())
+    }
+
     /// Execute one step.
     ///
     /// If cancellation is enabled and the token has been triggered,
@@ -352,7 +814,12 @@ where
         if self.state.finished {
             return Ok(StepResult::Finished);
         }
+        let request = self.prepare_request().await?;
+        self.dispatch(request).await
+    }
 
+    /// Start the next step and build the request it will send.
+    pub(crate) async fn prepare_request(&mut self) -> Result<PreparedRequest, AgentRunError> {
         // Check for cancellation at the start of each step
         if let Some(ref token) = self.cancel_token {
             if token.is_cancelled() {
@@ -374,29 +841,98 @@ where
         }
 
         // Get cached tool definitions (pre-computed at build time - no cloning!)
-        let tool_defs = self.agent.tool_definitions();
+        let tool_defs = Arc::clone(&self.tool_defs);
 
         // Build request parameters
-        let params = ModelRequestParameters::new()
-            .with_tools_arc(tool_defs)
-            .with_allow_text(true);
+        let params = self.agent.request_params(tool_defs);
 
         // Process message history
         let messages = self.process_history().await;
+        let settings = request_settings(&self.ctx, &self.agent.request_settings_fns);
+
+        Ok(PreparedRequest {
+            messages,
+            settings,
+            params,
+        })
+    }
+
+    /// Send a prepared request and process the response.
+    pub(crate) async fn dispatch(
+        &mut self,
+        request: PreparedRequest,
+    ) -> Result<StepResult, AgentRunError> {
+        let PreparedRequest {
+            messages,
+            settings,
+            params,
+        } = request;
+
+        check_preflight(
+            self.agent.model(),
+            &messages,
+            &params,
+            [
+                self.agent.usage_limits.as_ref(),
+                self.run_usage_limits.as_ref(),
+            ],
+            &self.state.usage,
+        )
+        .await?;
+        let meter = self.agent.usage_meter();
+        if let Some(meter) = &meter {
+            meter.check()?;
+        }
 
         // Make model request
-        let mut response = self
+        let result = self
             .agent
             .model()
-            .request(&messages, &self.ctx.model_settings, &params)
-            .await?;
+            .request(&messages, &settings, &params)
+            .await;
+        if let Some(audit) = &self.agent.audit {
+            let (model_name, usage, status) = match &result {
+                Ok(response) => (
+                    response
+                        .model_name
+                        .as_deref()
+                        .unwrap_or_else(|| self.agent.model().name()),
+                    response.usage.as_ref(),
+                    AuditStatus::Success,
+                ),
+                Err(e) => (
+                    self.agent.model().name(),
+                    None,
+                    AuditStatus::Error {
+                        message: e.to_string(),
+                    },
+                ),
+            };
+            audit
+                .model_request(&self.ctx.deps, &self.ctx.run_id, model_name, usage, status)
+                .await;
+        }
+        let mut response = result?;
 
         // Persist canonical tool args to avoid carrying malformed raw args in history.
-        canonicalize_tool_call_args_in_response(&mut response);
+        self.state.repairs +=
+            canonicalize_tool_call_args_in_response(&mut response, &self.agent.json_repair);
+        if let Some(prefill) = &self.assistant_prefill {
+            apply_prefill(&mut response, prefill);
+        }
 
         // Update usage
         if let Some(usage) = &response.usage {
-            self.state.usage.add_request(usage.clone());
+            let model_name = response
+                .model_name
+                .as_deref()
+                .unwrap_or_else(|| self.agent.model().name());
+            if let Some(meter) = &meter {
+                meter.record(model_name, usage);
+            }
+            self.state
+                .usage
+                .add_model_request(model_name, usage.clone());
         }
 
         // Store response
@@ -413,10 +949,18 @@ where
         let mut messages = self.state.messages.clone();
 
         // Apply history processors
-        for processor in &self.agent.history_processors {
+        for processor in self.agent.history_processors.iter() {
             messages = processor.process(&self.ctx, messages).await;
         }
 
+        // Instructions only ride on the latest request, never on history.
+        let instructions = self.agent.build_instructions(&self.ctx).await;
+        set_latest_instructions(&mut messages, instructions);
+
+        if let Some(prefill) = &self.assistant_prefill {
+            messages.push(prefill_request(prefill));
+        }
+
         messages
     }
 
@@ -426,6 +970,8 @@ where
     ) -> Result<StepResult, AgentRunError> {
         let mut tool_calls = Vec::new();
         let mut found_output = None;
+        // Output with invalid fields, and the output tool call that carried it
+        let mut invalid_output = None;
 
         for part in &response.parts {
             match part {
@@ -434,7 +980,9 @@ where
                         // Try to parse as output
                         match self.agent.output_schema.parse_text(&text.content) {
                             Ok(output) => found_output = Some(output),
-                            Err(OutputParseError::NotFound) => {}
+                            Err(OutputParseError::InvalidFields(errors)) => {
+                                invalid_output = Some((errors, None));
+                            }
                             Err(_) => {} // Try other parts
                         }
                     }
@@ -443,13 +991,20 @@ where
                     // Check if this is the output tool
                     if self.agent.is_output_tool(&tc.tool_name) {
                         let args = tc.args.to_json();
-                        if let Ok(output) = self
+                        match self
                             .agent
                             .output_schema
                             .parse_tool_call(&tc.tool_name, &args)
                         {
-                            found_output = Some(output);
-                            continue;
+                            Ok(output) => {
+                                found_output = Some(output);
+                                continue;
+                            }
+                            Err(OutputParseError::InvalidFields(errors)) => {
+                                invalid_output = Some((errors, Some(tc.clone())));
+                                continue;
+                            }
+                            Err(_) => {}
                         }
                     }
 
@@ -503,6 +1058,20 @@ where
                     return Ok(StepResult::RetryingOutput);
                 }
             }
+        } else if let Some((errors, output_call)) = invalid_output {
+            let error = OutputValidationError::fields(errors);
+            self.state.output_retries += 1;
+            if self.state.output_retries > self.agent.max_output_retries {
+                return Err(AgentRunError::OutputValidationFailed(error));
+            }
+
+            match output_call {
+                // Answer the output tool call so the model sees which
+                // arguments to fix
+                Some(tc) => self.add_output_tool_retry(&tc, error),
+                None => self.add_retry_message(error)?,
+            }
+            return Ok(StepResult::RetryingOutput);
         }
 
         // Check if we should finish
@@ -542,11 +1111,23 @@ where
         &mut self,
         calls: Vec<serdes_ai_core::messages::ToolCallPart>,
     ) -> Vec<(String, Option<String>, Result<ToolReturn, ToolError>)> {
-        if self.agent.parallel_tool_calls {
+        let mut returns = if self.agent.parallel_tool_calls {
             self.execute_tools_parallel(calls).await
         } else {
             self.execute_tools_sequential(calls).await
+        };
+
+        // Keep oversized returns out of the context window.
+        for (tool_name, _, result) in &mut returns {
+            let limit = self
+                .find_tool(tool_name)
+                .and_then(|t| t.return_limit.as_ref());
+            if let (Some(limit), Ok(ret)) = (limit, result) {
+                let content = std::mem::take(&mut ret.content);
+                ret.content = limit.apply(tool_name, content).await;
+            }
         }
+        returns
     }
 
     /// Execute tool calls sequentially (original behavior).
@@ -573,7 +1154,7 @@ where
 
             self.state.usage.record_tool_call();
 
-            let tool = match self.agent.find_tool(&tc.tool_name) {
+            let tool = match self.find_tool(&tc.tool_name) {
                 Some(t) => t,
                 None => {
                     returns.push((
@@ -585,23 +1166,45 @@ where
                 }
             };
 
-            // Create tool context
-            let tool_ctx = self.ctx.for_tool(&tc.tool_name, tc.tool_call_id.clone());
-
-            // Execute with retries
             let args = tc.args.to_json();
+            let key = idempotency_key(&self.ctx.run_id, &tc.tool_name, &args);
+            if let Some(ret) = self.repeated_call(tool, &key) {
+                returns.push((tc.tool_name.clone(), tc.tool_call_id.clone(), Ok(ret)));
+                continue;
+            }
+
+            // Create tool context
+            let tool_ctx = self
+                .ctx
+                .for_tool(&tc.tool_name, tc.tool_call_id.clone())
+                .with_retries(self.tool_retry_count(&tc.tool_name), tool.max_retries)
+                .with_idempotency_key(key.clone());
+
+            // Execute with retries; `ModelRetry` goes back to the model instead
+            let started = Instant::now();
             let mut retries = 0;
             let result = loop {
                 match tool.executor.execute(args.clone(), &tool_ctx).await {
                     Ok(r) => break Ok(r),
-                    Err(e) if e.is_retryable() && retries < tool.max_retries => {
+                    Err(e)
+                        if e.is_retryable()
+                            && !e.is_model_retry()
+                            && retries < tool.max_retries =>
+                    {
                         retries += 1;
                         continue;
                     }
                     Err(e) => break Err(e),
                 }
             };
-
+            self.state.tool_stats.record(ToolCallRecord::from_result(
+                &tc.tool_name,
+                started.elapsed(),
+                args.to_string().len(),
+                &result,
+            ));
+
+            self.remember_call(&tc.tool_name, key, &result);
             returns.push((tc.tool_name.clone(), tc.tool_call_id.clone(), result));
         }
 
@@ -620,17 +1223,32 @@ where
             self.state.usage.record_tool_call();
         }
 
+        let keys: Vec<_> = calls
+            .iter()
+            .map(|tc| idempotency_key(&self.ctx.run_id, &tc.tool_name, &tc.args.to_json()))
+            .collect();
+
         // Build futures for each tool call
         let futures: Vec<_> = calls
             .into_iter()
-            .map(|tc| {
+            .zip(&keys)
+            .map(|(tc, key)| {
                 let tool_name = tc.tool_name.clone();
                 let tool_call_id = tc.tool_call_id.clone();
                 let args = tc.args.to_json();
 
                 // Look up tool (we need to clone Arc references for async move)
-                let tool = self.agent.find_tool(&tc.tool_name).cloned();
-                let tool_ctx = self.ctx.for_tool(&tc.tool_name, tc.tool_call_id.clone());
+                let tool = self.find_tool(&tc.tool_name).cloned();
+                let repeated = tool.as_ref().and_then(|t| self.repeated_call(t, key));
+                let tool_ctx = self
+                    .ctx
+                    .for_tool(&tc.tool_name, tc.tool_call_id.clone())
+                    .with_retries(
+                        self.tool_retry_count(&tc.tool_name),
+                        tool.as_ref().map_or(0, |t| t.max_retries),
+                    )
+                    .with_idempotency_key(key.clone());
+                let tool_stats = Arc::clone(&self.state.tool_stats);
 
                 async move {
                     let tool = match tool {
@@ -643,22 +1261,36 @@ where
                             );
                         }
                     };
+                    if let Some(ret) = repeated {
+                        return (tool_name, tool_call_id, Ok(ret));
+                    }
 
                     // Execute with retries
                     let max_retries = tool.max_retries;
                     let executor = tool.executor;
+                    let started = Instant::now();
                     let mut retries = 0;
 
                     let result = loop {
                         match executor.execute(args.clone(), &tool_ctx).await {
                             Ok(r) => break Ok(r),
-                            Err(e) if e.is_retryable() && retries < max_retries => {
+                            Err(e)
+                                if e.is_retryable()
+                                    && !e.is_model_retry()
+                                    && retries < max_retries =>
+                            {
                                 retries += 1;
                                 continue;
                             }
                             Err(e) => break Err(e),
                         }
                     };
+                    tool_stats.record(ToolCallRecord::from_result(
+                        &tool_name,
+                        started.elapsed(),
+                        args.to_string().len(),
+                        &result,
+                    ));
 
                     (tool_name, tool_call_id, result)
                 }
@@ -666,11 +1298,15 @@ where
             .collect();
 
         // Execute all futures, respecting concurrency limit if set
-        if let Some(max_concurrent) = self.agent.max_concurrent_tools {
+        let returns = if let Some(max_concurrent) = self.agent.max_concurrent_tools {
             self.execute_with_semaphore(futures, max_concurrent).await
         } else {
             join_all(futures).await
+        };
+        for ((tool_name, _, result), key) in returns.iter().zip(keys) {
+            self.remember_call(tool_name, key, result);
         }
+        returns
     }
 
     /// Execute futures with a concurrency limit using a semaphore.
@@ -727,12 +1363,41 @@ where
         for (tool_name, tool_call_id, result) in returns {
             match result {
                 Ok(ret) => {
+                    self.state.tool_retries.remove(&tool_name);
                     let mut part = ToolReturnPart::new(&tool_name, ret.content);
                     if let Some(id) = tool_call_id {
                         part = part.with_tool_call_id(id);
                     }
                     req.parts.push(ModelRequestPart::ToolReturn(part));
                 }
+                Err(ToolError::ModelRetry(message)) => {
+                    let max_retries = self.find_tool(&tool_name).map_or(0, |t| t.max_retries);
+                    let part = tool_retry_prompt(
+                        &mut self.state.tool_retries,
+                        &tool_name,
+                        tool_call_id,
+                        message,
+                        max_retries,
+                    )?;
+                    req.parts.push(ModelRequestPart::RetryPrompt(part));
+                }
+                Err(e)
+                    if e.is_unavailable()
+                        && self.failure_policy(&tool_name) == ToolFailurePolicy::Fail =>
+                {
+                    return Err(AgentRunError::Tool(e));
+                }
+                Err(e)
+                    if e.is_unavailable()
+                        && self.failure_policy(&tool_name) == ToolFailurePolicy::Degrade =>
+                {
+                    let message = ToolFailurePolicy::unavailable_message(&tool_name, &e);
+                    let mut part = ToolReturnPart::error(&tool_name, message);
+                    if let Some(id) = tool_call_id {
+                        part = part.with_tool_call_id(id);
+                    }
+                    req.parts.push(ModelRequestPart::ToolReturn(part));
+                }
                 Err(e) => {
                     let mut part = RetryPromptPart::new(format!("Tool error: {}", e));
                     part = part.with_tool_name(&tool_name);
@@ -751,6 +1416,46 @@ where
         Ok(())
     }
 
+    /// Failure policy of a tool; unknown tools are reported.
+    fn failure_policy(&self, tool_name: &str) -> ToolFailurePolicy {
+        self.find_tool(tool_name)
+            .map_or(ToolFailurePolicy::Report, |t| t.failure_policy)
+    }
+
+    /// Number of times the model has retried a tool in this run.
+    /// The earlier result of a call to a tool that skips repeated calls.
+    fn repeated_call(&self, tool: &RegisteredTool<Deps>, key: &str) -> Option<ToolReturn> {
+        if !tool.executor.skip_repeated_calls() {
+            return None;
+        }
+        self.state.completed_calls.get(key).cloned()
+    }
+
+    /// Keep a successful result of a tool that skips repeated calls.
+    fn remember_call(
+        &mut self,
+        tool_name: &str,
+        key: String,
+        result: &Result<ToolReturn, ToolError>,
+    ) {
+        let skips = self
+            .find_tool(tool_name)
+            .is_some_and(|t| t.executor.skip_repeated_calls());
+        if let (true, Ok(ret)) = (skips, result) {
+            self.state.completed_calls.insert(key, ret.clone());
+        }
+    }
+
+    fn find_tool(&self, name: &str) -> Option<&RegisteredTool<Deps>> {
+        self.agent
+            .find_tool(name)
+            .or_else(|| self.extra_tools.iter().find(|t| t.definition.name == name))
+    }
+
+    fn tool_retry_count(&self, tool_name: &str) -> u32 {
+        self.state.tool_retries.get(tool_name).copied().unwrap_or(0)
+    }
+
     fn add_retry_message(&mut self, error: OutputValidationError) -> Result<(), AgentRunError> {
         let mut req = ModelRequest::new();
         let part = RetryPromptPart::new(error.retry_message());
@@ -759,6 +1464,30 @@ where
         Ok(())
     }
 
+    fn add_output_tool_retry(
+        &mut self,
+        call: &serdes_ai_core::messages::ToolCallPart,
+        error: OutputValidationError,
+    ) {
+        if let Some(last_response) = self.state.responses.last() {
+            let mut response_req = ModelRequest::new();
+            response_req
+                .parts
+                .push(ModelRequestPart::ModelResponse(Box::new(
+                    last_response.clone(),
+                )));
+            self.state.messages.push(response_req);
+        }
+
+        let mut part = RetryPromptPart::new(error.retry_message()).with_tool_name(&call.tool_name);
+        if let Some(id) = &call.tool_call_id {
+            part = part.with_tool_call_id(id);
+        }
+        let mut req = ModelRequest::new();
+        req.parts.push(ModelRequestPart::RetryPrompt(part));
+        self.state.messages.push(req);
+    }
+
     async fn validate_output(&self, output: Output) -> Result<Output, OutputValidationError> {
         let mut output = output;
         for validator in &self.agent.output_validators {
@@ -767,20 +1496,68 @@ where
         Ok(output)
     }
 
-    fn finalize(self) -> Result<AgentRunResult<Output>, AgentRunError> {
+    pub(crate) fn finalize(self) -> Result<AgentRunResult<Output>, AgentRunError> {
+        let confidence = self
+            .state
+            .confidence
+            .unwrap_or_else(|| HeuristicEstimator.score(&self.confidence_signals()));
         let output = self.state.final_output.ok_or(AgentRunError::NoOutput)?;
+        let tool_stats = self.state.tool_stats.snapshot();
+        self.agent
+            .record_tool_usage(self.tool_usage_category.as_deref(), &tool_stats);
+        let (messages, responses) = match &self.agent.history_transform {
+            Some(transform) => (
+                transform.transform(self.state.messages),
+                transform_responses(transform.as_ref(), self.state.responses),
+            ),
+            None => (self.state.messages, self.state.responses),
+        };
 
         Ok(AgentRunResult {
             output,
-            messages: self.state.messages,
-            responses: self.state.responses,
+            messages,
+            responses,
             usage: self.state.usage,
             run_id: self.state.run_id,
             finish_reason: self.state.finish_reason.unwrap_or(FinishReason::Stop),
             metadata: self.ctx.metadata.clone(),
+            tool_stats,
+            review: self.state.review,
+            confidence,
         })
     }
 
+    /// Save the run state ahead of a step.
+    pub(crate) fn snapshot(&self) -> RunSnapshot {
+        RunSnapshot {
+            messages: self.state.messages.clone(),
+            responses: self.state.responses.clone(),
+            step: self.state.step,
+            output_retries: self.state.output_retries,
+            tool_retries: self.state.tool_retries.clone(),
+            finish_reason: self.state.finish_reason,
+        }
+    }
+
+    /// Restore a saved run state.
+    ///
+    /// Usage is not rolled back, since those tokens were spent.
+    pub(crate) fn restore(&mut self, snapshot: RunSnapshot) {
+        self.state.messages = snapshot.messages;
+        self.state.responses = snapshot.responses;
+        self.state.step = snapshot.step;
+        self.state.output_retries = snapshot.output_retries;
+        self.state.tool_retries = snapshot.tool_retries;
+        self.state.finish_reason = snapshot.finish_reason;
+        self.state.final_output = None;
+        self.state.finished = false;
+    }
+
+    /// Get the responses received so far.
+    pub fn responses(&self) -> &[ModelResponse] {
+        &self.state.responses
+    }
+
     /// Get current messages.
     pub fn messages(&self) -> &[ModelRequest] {
         &self.state.messages
@@ -843,6 +1620,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::builder::{agent, agent_with_deps};
+    use crate::tool_return_limit::ToolReturnLimit;
+    use serdes_ai_models::FunctionModel;
 
     #[test]
     fn test_run_options_default() {
@@ -879,7 +1659,7 @@ mod tests {
             .with_tool_call_id("call_1"),
         ));
 
-        canonicalize_tool_call_args_in_response(&mut response);
+        canonicalize_tool_call_args_in_response(&mut response, &JsonRepairer::default());
 
         match &response.parts[0] {
             ModelResponsePart::ToolCall(tc) => {
@@ -888,4 +1668,760 @@ mod tests {
             _ => panic!("expected tool call part"),
         }
     }
+
+    fn retrying_model(
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+        tool_calls: usize,
+    ) -> FunctionModel {
+        FunctionModel::new(move |_, _| {
+            let step = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if step < tool_calls {
+                ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    "lookup",
+                    serde_json::json!({"id": step}),
+                )])
+                .with_finish_reason(FinishReason::ToolCall)
+            } else {
+                ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_model_retry_sends_tool_guidance() {
+        let model = retrying_model(Arc::default(), 2);
+        let seen_retries = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = {
+            let seen_retries = Arc::clone(&seen_retries);
+            agent(model)
+                .tool_fn(
+                    "lookup",
+                    "Look up a record",
+                    move |ctx, _args: JsonValue| {
+                        seen_retries
+                            .lock()
+                            .unwrap()
+                            .push((ctx.retry_count, ctx.max_retries));
+                        if ctx.is_retry() {
+                            Ok(ToolReturn::text("found"))
+                        } else {
+                            Err(ToolError::model_retry("Use an id between 1 and 10"))
+                        }
+                    },
+                )
+                .tool_max_retries("lookup", 2)
+                .build()
+        };
+
+        let result = agent.run("find it", ()).await.unwrap();
+        assert_eq!(result.output, "done");
+        // ModelRetry isn't retried in place
+        assert_eq!(*seen_retries.lock().unwrap(), vec![(0, 2), (1, 2)]);
+
+        let retry = result
+            .messages
+            .iter()
+            .flat_map(|m| &m.parts)
+            .find_map(|p| match p {
+                ModelRequestPart::RetryPrompt(r) => Some(r),
+                _ => None,
+            })
+            .expect("expected a retry prompt");
+        assert_eq!(retry.content.message(), "Use an id between 1 and 10");
+        assert_eq!(retry.tool_name.as_deref(), Some("lookup"));
+    }
+
+    #[tokio::test]
+    async fn test_output_field_errors_are_retried_with_paths() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Member {
+            name: String,
+            age: u32,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Team {
+            members: Vec<Member>,
+        }
+
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let model = {
+            let calls = Arc::clone(&calls);
+            FunctionModel::new(move |_, _| {
+                let age = match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                    0 => serde_json::json!("thirty"),
+                    _ => serde_json::json!(30),
+                };
+                ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    "final_result",
+                    serde_json::json!({"members": [
+                        {"name": "Ada", "age": 36},
+                        {"name": "Bob", "age": age},
+                    ]}),
+                )])
+                .with_finish_reason(FinishReason::ToolCall)
+            })
+        };
+        let agent = agent(model)
+            .output_tool::<Team>("final_result", serde_json::json!({"type": "object"}))
+            .build();
+
+        let result = agent.run("who is on the team?", ()).await.unwrap();
+        assert_eq!(result.output.members[1].name, "Bob");
+        assert_eq!(result.output.members[1].age, 30);
+
+        let retry = result
+            .messages
+            .iter()
+            .flat_map(|m| &m.parts)
+            .find_map(|p| match p {
+                ModelRequestPart::RetryPrompt(r) => Some(r),
+                _ => None,
+            })
+            .expect("expected a retry prompt");
+        assert_eq!(retry.tool_name.as_deref(), Some("final_result"));
+        assert!(retry
+            .content
+            .message()
+            .contains("/members/1/age: invalid type: string \"thirty\", expected u32"));
+    }
+
+    #[tokio::test]
+    async fn test_model_retry_exceeds_max_retries() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let agent = agent(retrying_model(Arc::clone(&calls), usize::MAX))
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Err(ToolError::model_retry("Try again"))
+            })
+            .tool_max_retries("lookup", 1)
+            .build();
+
+        let err = agent.run("find it", ()).await.unwrap_err();
+        assert!(matches!(err, AgentRunError::MaxRetriesExceeded { .. }));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_instructions_only_on_latest_request() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::<Vec<Option<String>>>::new()));
+        let model = {
+            let seen = Arc::clone(&seen);
+            FunctionModel::new(move |messages, _| {
+                let mut seen = seen.lock().unwrap();
+                seen.push(messages.iter().map(|m| m.instructions.clone()).collect());
+                if seen.len() == 1 {
+                    ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                        "lookup",
+                        serde_json::json!({}),
+                    )])
+                    .with_finish_reason(FinishReason::ToolCall)
+                } else {
+                    ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+                }
+            })
+        };
+        let agent = agent(model)
+            .system_prompt("You are helpful.")
+            .instructions("Be brief.")
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("found"))
+            })
+            .build();
+
+        let history = vec![ModelRequest::new().with_instructions("Stale.")];
+        let result = agent
+            .run_with_options("find it", (), RunOptions::new().message_history(history))
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        for step in seen.iter() {
+            let (last, rest) = step.split_last().unwrap();
+            assert_eq!(last.as_deref(), Some("Be brief."));
+            assert!(rest.iter().all(Option::is_none));
+        }
+        assert!(result.messages.iter().all(|m| m.instructions.is_none()));
+        assert_eq!(
+            result.messages[1].system_prompts().next().unwrap().content,
+            "You are helpful."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_selection() {
+        let noop = |_ctx: &RunContext<()>, _args: JsonValue| Ok(ToolReturn::text("ok"));
+        let agent = agent(FunctionModel::constant_text("done"))
+            .tool_fn("ask_user", "Ask the user a question", noop)
+            .tool_fn("get_weather", "Get the weather forecast for a city", noop)
+            .tool_fn("send_email", "Send an email", noop)
+            .tool_selection(crate::ToolSelection::top_k(1).always_include(["ask_user"]))
+            .build();
+
+        let mut run = agent
+            .start_run("What's the weather in Paris?", (), RunOptions::new())
+            .await
+            .unwrap();
+        let request = run.prepare_request().await.unwrap();
+        let names: Vec<_> = request
+            .params
+            .tools
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, ["ask_user", "get_weather"]);
+    }
+
+    #[tokio::test]
+    async fn test_tool_routing() {
+        use crate::ToolUsageStore;
+
+        let store = Arc::new(crate::InMemoryToolUsageStore::new());
+        let agent = agent(retrying_model(Arc::default(), 1))
+            .tool_fn(
+                "ask_user",
+                "Ask the user a question",
+                |_ctx, _args: JsonValue| Ok(ToolReturn::text("ok")),
+            )
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("found"))
+            })
+            .tool_routing(crate::ToolRouting::new(store.clone()).prune_unused_after(1))
+            .build();
+
+        agent.run("find it", ()).await.unwrap();
+        let usage = store.load(crate::DEFAULT_TOOL_USAGE_CATEGORY);
+        assert_eq!(usage.runs, 1);
+        assert_eq!(usage.calls("lookup"), 1);
+
+        let mut run = agent
+            .start_run("find it again", (), RunOptions::new())
+            .await
+            .unwrap();
+        let request = run.prepare_request().await.unwrap();
+        let names: Vec<_> = request
+            .params
+            .tools
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(names, ["lookup"]);
+    }
+
+    #[tokio::test]
+    async fn test_history_transform() {
+        let model = FunctionModel::new(|_, _| {
+            ModelResponse::with_parts(vec![
+                ModelResponsePart::thinking("secret reasoning"),
+                ModelResponsePart::text("done"),
+            ])
+            .with_finish_reason(FinishReason::Stop)
+        });
+        let agent = agent(model)
+            .history_transform(crate::RedactHistory::new().strip_thinking(true))
+            .build();
+
+        let result = agent.run("hi", ()).await.unwrap();
+        assert_eq!(result.responses[0].thinking_parts().count(), 0);
+        let forked = result.fork();
+        let last = forked.messages().last().unwrap();
+        assert!(matches!(
+            last.parts.as_slice(),
+            [ModelRequestPart::ModelResponse(r)] if r.parts.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_settings_precedence() {
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        let model = {
+            let seen = Arc::clone(&seen);
+            FunctionModel::new(move |_, settings| {
+                *seen.lock().unwrap() = Some(settings.clone());
+                ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+            })
+        };
+        let agent = agent(model)
+            .model_settings(
+                ModelSettings::new()
+                    .temperature(0.2)
+                    .max_tokens(500)
+                    .seed(1),
+            )
+            .request_settings_fn(|ctx| {
+                assert_eq!(ctx.model_settings.temperature, Some(0.7));
+                Some(ModelSettings::new().max_tokens(100))
+            })
+            .build();
+
+        agent
+            .run_with_options(
+                "hi",
+                (),
+                RunOptions::new().model_settings(ModelSettings::new().temperature(0.7)),
+            )
+            .await
+            .unwrap();
+
+        let settings = seen.lock().unwrap().clone().unwrap();
+        assert_eq!(settings.seed, Some(1)); // agent default
+        assert_eq!(settings.temperature, Some(0.7)); // run override
+        assert_eq!(settings.max_tokens, Some(100)); // per-request hook
+    }
+
+    #[test]
+    fn test_apply_prefill() {
+        let mut response = ModelResponse::text("\"a\": 1}");
+        apply_prefill(&mut response, "{");
+        assert_eq!(response.text_content(), "{\"a\": 1}");
+
+        // A reply that repeats the prefill is kept as is
+        apply_prefill(&mut response, "{");
+        assert_eq!(response.text_content(), "{\"a\": 1}");
+
+        let mut response = ModelResponse::new();
+        apply_prefill(&mut response, "{");
+        assert_eq!(response.text_content(), "{");
+
+        let mut response = ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+            "lookup",
+            serde_json::json!({}),
+        )]);
+        apply_prefill(&mut response, "{");
+        assert_eq!(response.parts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_assistant_prefill() {
+        let model = FunctionModel::new(|messages, _| {
+            let last = messages.last().unwrap();
+            match last.parts.as_slice() {
+                [ModelRequestPart::ModelResponse(r)] if r.text_content() == "{" => {
+                    ModelResponse::text("\"answer\": 42}")
+                }
+                _ => ModelResponse::text("no prefill"),
+            }
+        });
+        let agent = agent(model).output_type::<JsonValue>().build();
+
+        let result = agent
+            .run_with_options("hi", (), RunOptions::new().assistant_prefill("{"))
+            .await
+            .unwrap();
+        assert_eq!(result.output, serde_json::json!({"answer": 42}));
+
+        // The stored reply is whole, and the partial message is not kept
+        assert_eq!(result.responses[0].text_content(), "{\"answer\": 42}");
+        assert!(!result
+            .messages
+            .iter()
+            .flat_map(|r| &r.parts)
+            .any(|p| matches!(p, ModelRequestPart::ModelResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_key_resolver_and_run_api_key() {
+        struct Tenant {
+            key: &'static str,
+        }
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let model = {
+            let seen = seen.clone();
+            FunctionModel::new(move |_, settings| {
+                seen.lock()
+                    .unwrap()
+                    .push(settings.api_key_or("sk-model").to_string());
+                ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+            })
+        };
+        let agent = agent_with_deps::<Tenant, _>(model)
+            .key_resolver(|tenant| Some(ApiKey::new(tenant.key)))
+            .build();
+
+        agent.run("hi", Tenant { key: "sk-acme" }).await.unwrap();
+        agent
+            .run_with_options(
+                "hi",
+                Tenant { key: "sk-acme" },
+                RunOptions::new().api_key("sk-override"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec!["sk-acme", "sk-override"]);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_rejects_oversized_request() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let model = {
+            let calls = calls.clone();
+            FunctionModel::new(move |_, _| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+            })
+            .with_profile(serdes_ai_models::ModelProfile::new().with_context_window(100))
+        };
+        let agent = agent(model)
+            .usage_limits(UsageLimits::new().preflight(true))
+            .build();
+
+        let err = agent.run("x".repeat(1000), ()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            AgentRunError::UsageLimitExceeded(UsageLimitError::ContextWindow { limit: 100, .. })
+        ));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // Short prompts still go through
+        agent.run("hi", ()).await.unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_usage_meter_blocks_when_budget_exhausted() {
+        let meter = Arc::new(crate::UsageMeter::new().with_budget(100));
+        let model = || {
+            FunctionModel::new(|_, _| {
+                ModelResponse::text("done")
+                    .with_finish_reason(FinishReason::Stop)
+                    .with_usage(serdes_ai_core::RequestUsage::with_tokens(50, 10))
+            })
+        };
+        let first = agent(model()).usage_meter(meter.clone()).build();
+        let second = agent(model()).usage_meter(meter.clone()).build();
+
+        first.run("hi", ()).await.unwrap();
+        second.run("hi", ()).await.unwrap();
+        assert_eq!(meter.used(), 120);
+
+        let err = first.run("hi", ()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            AgentRunError::UsageLimitExceeded(UsageLimitError::BudgetExhausted {
+                used: 120,
+                budget: 100
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_skip_repeated_tool_calls() {
+        // Every step repeats the same call, as a retried step would
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let model = FunctionModel::new(move |_, _| {
+            if calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < 3 {
+                ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    "lookup",
+                    serde_json::json!({"id": 1}),
+                )])
+                .with_finish_reason(FinishReason::ToolCall)
+            } else {
+                ModelResponse::text("done").with_finish_reason(FinishReason::Stop)
+            }
+        });
+        let runs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let agent = agent(model)
+            .tool_fn("lookup", "Look up a record", {
+                let runs = runs.clone();
+                move |ctx, _args: JsonValue| {
+                    runs.lock().unwrap().push(ctx.idempotency_key.clone());
+                    Ok(ToolReturn::text("found"))
+                }
+            })
+            .skip_repeated_tool_calls("lookup")
+            .build();
+
+        let result = agent.run("find it", ()).await.unwrap();
+        assert_eq!(
+            result
+                .messages
+                .iter()
+                .flat_map(|m| m.tool_returns())
+                .count(),
+            3
+        );
+        let runs = runs.lock().unwrap();
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].as_deref().unwrap().starts_with("lookup-"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_return_limit_truncates() {
+        let agent = agent(retrying_model(Arc::default(), 1))
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("x".repeat(100)))
+            })
+            .tool_return_limit("lookup", ToolReturnLimit::truncate(10))
+            .build();
+
+        let result = agent.run("find it", ()).await.unwrap();
+        let ret = result
+            .messages
+            .iter()
+            .flat_map(|m| m.tool_returns())
+            .next()
+            .expect("expected a tool return");
+        assert_eq!(
+            ret.content.as_text(),
+            Some("xxxxxxxxxx\n[... truncated 90 characters]")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_failure_policy() {
+        let unavailable = |policy| {
+            agent(retrying_model(Arc::default(), 1))
+                .max_tool_retries(0)
+                .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                    Err(ToolError::retryable("connection refused"))
+                })
+                .tool_failure_policy("lookup", policy)
+                .build()
+        };
+
+        let result = unavailable(ToolFailurePolicy::Degrade)
+            .run("find it", ())
+            .await
+            .unwrap();
+        assert_eq!(result.output, "done");
+        let ret = result
+            .messages
+            .iter()
+            .flat_map(|m| m.tool_returns())
+            .next()
+            .expect("expected a tool return");
+        assert!(ret.content.is_error());
+        assert!(ret
+            .content
+            .as_text()
+            .unwrap()
+            .starts_with("Tool 'lookup' is currently unavailable"));
+
+        let err = unavailable(ToolFailurePolicy::Fail)
+            .run("find it", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AgentRunError::Tool(ToolError::ExecutionFailed { .. })
+        ));
+
+        // Errors that aren't about availability are still reported
+        let result = agent(retrying_model(Arc::default(), 1))
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Err(ToolError::execution_failed("bad id"))
+            })
+            .tool_failure_policy("lookup", ToolFailurePolicy::Fail)
+            .build()
+            .run("find it", ())
+            .await
+            .unwrap();
+        assert_eq!(result.output, "done");
+    }
+
+    #[tokio::test]
+    async fn test_mock_clock_history_is_stable() {
+        let _guard = serdes_ai_core::clock::use_clock(serdes_ai_core::MockClock::epoch());
+        let agent = agent(retrying_model(Arc::default(), 0)).build();
+
+        let history = |result: AgentRunResult<String>| {
+            serde_json::to_string(result.fork().messages()).unwrap()
+        };
+        let first = history(agent.run("find it", ()).await.unwrap());
+        let second = history(agent.run("find it", ()).await.unwrap());
+        assert_eq!(first, second);
+        assert!(first.contains("1970-01-01T00:00:00Z"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_id_generator() {
+        let agent = agent(retrying_model(Arc::default(), 0))
+            .id_generator(
+                serdes_ai_core::SequentialIds::new()
+                    .with_prefix(serdes_ai_core::IdKind::Run, "acme-run-"),
+            )
+            .build();
+
+        assert_eq!(agent.run("a", ()).await.unwrap().run_id, "acme-run-1");
+        assert_eq!(agent.run("b", ()).await.unwrap().run_id, "acme-run-2");
+    }
+
+    #[tokio::test]
+    async fn test_tool_stats() {
+        let agent = agent(retrying_model(Arc::default(), 2))
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("found"))
+            })
+            .build();
+
+        let result = agent.run("find it", ()).await.unwrap();
+        let stats = result.tool_stats().get("lookup").expect("expected stats");
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.args_bytes > 0);
+        assert!(stats.return_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn test_tool_permissions() {
+        use serdes_ai_tools::{Permissions, Principal};
+
+        let steps = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let ran = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let agent = {
+            let ran = Arc::clone(&ran);
+            agent_with_deps::<Principal, _>(retrying_model(Arc::clone(&steps), 1))
+                .tool_fn(
+                    "lookup",
+                    "Look up a record",
+                    move |_ctx, _args: JsonValue| {
+                        ran.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        Ok(ToolReturn::text("found"))
+                    },
+                )
+                .tool_permissions("lookup", Permissions::new().scope("db:read"))
+                .build()
+        };
+
+        let guest = Principal::new("guest", Permissions::new());
+        let result = agent.run("find it", guest).await.unwrap();
+        assert_eq!(ran.load(std::sync::atomic::Ordering::SeqCst), 0);
+        let refused = result.messages.iter().flat_map(|m| &m.parts).any(|part| {
+            matches!(part, ModelRequestPart::RetryPrompt(p) if p.content.message().contains("db:read"))
+        });
+        assert!(refused);
+
+        steps.store(0, std::sync::atomic::Ordering::SeqCst);
+        let analyst = Principal::new("analyst", Permissions::new().scope("db:*"));
+        agent.run("find it", analyst).await.unwrap();
+        assert_eq!(ran.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_calls() {
+        use crate::audit::{AuditEvent, AuditLog, InMemoryAuditSink};
+
+        let sink = Arc::new(InMemoryAuditSink::new());
+        let agent = agent_with_deps::<String, _>(retrying_model(Arc::default(), 1))
+            .tool_fn("lookup", "Look up a record", |_ctx, _args: JsonValue| {
+                Ok(ToolReturn::text("found"))
+            })
+            .audit(AuditLog::new(sink.clone()).with_actor(|user: &String| Some(user.clone())))
+            .build();
+
+        let result = agent.run("find it", "alice".to_string()).await.unwrap();
+        let records = sink.records();
+        assert_eq!(records.len(), 3);
+        assert!(records
+            .iter()
+            .all(|r| r.run_id == result.run_id && r.actor.as_deref() == Some("alice")));
+        assert!(records.iter().all(|r| r.status.is_success()));
+        assert!(matches!(
+            &records[1].event,
+            AuditEvent::ToolCall { tool_name, args_sha256, .. }
+                if tool_name == "lookup"
+                    && *args_sha256 == crate::audit::hash_args(&serde_json::json!({"id": 0}))
+        ));
+        assert!(matches!(records[2].event, AuditEvent::ModelRequest { .. }));
+    }
+
+    fn distance_model() -> FunctionModel {
+        FunctionModel::new(|messages, _| {
+            let revising = messages
+                .iter()
+                .flat_map(|m| m.user_prompts())
+                .any(|p| prompt_text(&p.content).contains("reviewer found problems"));
+            ModelResponse::text(if revising { "42 km" } else { "42" })
+        })
+    }
+
+    fn units_reviewer() -> FunctionModel {
+        FunctionModel::new(|messages, _| {
+            let request = messages
+                .iter()
+                .flat_map(|m| m.user_prompts())
+                .map(|p| prompt_text(&p.content))
+                .collect::<String>();
+            ModelResponse::text(if request.contains("42 km") {
+                crate::review::APPROVED
+            } else {
+                "The distance has no unit."
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reviewer_revises_output() {
+        let agent = agent(distance_model())
+            .with_reviewer(units_reviewer())
+            .build();
+
+        let result = agent.run("How far is it?", ()).await.unwrap();
+        assert_eq!(result.output, "42 km");
+        let review = result.review().unwrap();
+        assert!(!review.approved && review.revised);
+        assert_eq!(review.critique, "The distance has no unit.");
+    }
+
+    #[tokio::test]
+    async fn test_reviewer_critique_and_trigger() {
+        use crate::review::{ReviewMode, ReviewTrigger, Reviewer};
+
+        let critic = agent(distance_model())
+            .with_reviewer(Reviewer::new(units_reviewer()).mode(ReviewMode::Critique))
+            .build();
+        let result = critic.run("How far is it?", ()).await.unwrap();
+        assert_eq!(result.output, "42");
+        assert!(!result.review().unwrap().revised);
+
+        let gated = agent(distance_model())
+            .with_reviewer(Reviewer::new(units_reviewer()).trigger(ReviewTrigger::ValidationFailed))
+            .build();
+        let result = gated.run("How far is it?", ()).await.unwrap();
+        assert_eq!(result.output, "42");
+        assert!(result.review().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_confidence_signals() {
+        let model = FunctionModel::new(|_, _| {
+            ModelResponse::text("Paris").with_vendor_details(serde_json::json!({
+                "logprobs": {"content": [{"token": "Paris", "logprob": -0.2}]}
+            }))
+        });
+        let result = agent(model)
+            .build()
+            .run("Capital of France?", ())
+            .await
+            .unwrap();
+        assert!((result.confidence() - (-0.2f64).exp()).abs() < 1e-9);
+
+        let custom = agent(distance_model())
+            .confidence_estimator(|signals: &crate::confidence::ConfidenceSignals| {
+                if signals.output.contains("km") {
+                    0.9
+                } else {
+                    0.1
+                }
+            })
+            .build();
+        let result = custom.run("How far is it?", ()).await.unwrap();
+        assert_eq!(result.confidence(), 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_reviewer_low_confidence_trigger() {
+        use crate::review::{ReviewTrigger, Reviewer};
+
+        let agent = agent(distance_model())
+            .with_reviewer(
+                Reviewer::new(units_reviewer()).trigger(ReviewTrigger::LowConfidence(0.5)),
+            )
+            .build();
+        let result = agent.run("How far is it?", ()).await.unwrap();
+        assert!(result.review().is_none());
+        assert_eq!(result.confidence(), 1.0);
+    }
 }
@@ -0,0 +1,868 @@
+//! Recurring agent runs.
+//!
+//! A [`Scheduler`] runs an agent on a schedule, for monitoring and report
+//! generation agents that nobody prompts by hand. Each [`Job`] has a
+//! [`Schedule`] (a cron expression or a fixed interval) and a prompt template
+//! filled in with the date of the run. Finished runs are persisted to a
+//! [`ConversationStore`] and an [`ArtifactStore`] if configured, and reported
+//! to success and failure hooks, e.g. to page someone when a run fails.
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, Job, Schedule, Scheduler, CancellationToken};
+//! use serdes_ai_core::artifacts::FileArtifactStore;
+//! use std::sync::Arc;
+//!
+//! let agent = agent(model).system_prompt("You write status reports.").build();
+//! let scheduler = Scheduler::new(agent, ())
+//!     .job(
+//!         Job::new(
+//!             "weekday-report",
+//!             Schedule::cron("0 9 * * mon-fri")?,
+//!             "Summarize yesterday's incidents for the {date} report.",
+//!         )
+//!         .timezone(chrono::FixedOffset::east_opt(3600).unwrap()),
+//!     )
+//!     .artifact_store(Arc::new(FileArtifactStore::open("reports")?))
+//!     .on_failure(|record| alert(&record.job, record.error.as_deref().unwrap_or("")));
+//!
+//! let cancel = CancellationToken::new();
+//! Arc::new(scheduler).run(cancel.clone()).await;
+//! ```
+//!
+//! Cron expressions have the usual five fields (minute, hour, day of month,
+//! month, day of week) with `*`, lists, ranges, steps and three-letter month
+//! and weekday names, plus the `@hourly`, `@daily`, `@weekly`, `@monthly`
+//! and `@yearly` shorthands. As in cron, a run is due when either the day of
+//! month or the day of week matches if both are restricted.
+//!
+//! Prompt templates can use `{date}`, `{time}`, `{datetime}`, `{weekday}`,
+//! `{job}` and `{run}`, plus variables set with [`Job::var`]. Unknown
+//! placeholders are left as they are, and `{{` and `}}` produce literal braces.
+
+use crate::agent::Agent;
+use crate::conversations::ConversationStore;
+use crate::run::RunOptions;
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone, Timelike, Utc,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serdes_ai_core::artifacts::{ArtifactRef, ArtifactStore};
+use serdes_ai_core::messages::BinaryContent;
+use serdes_ai_core::{now_utc, ConversationId};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Error parsing a schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid schedule '{expression}': {message}")]
+pub struct ScheduleError {
+    /// The expression that failed to parse.
+    pub expression: String,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl ScheduleError {
+    fn new(expression: &str, message: impl Into<String>) -> Self {
+        Self {
+            expression: expression.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A parsed five-field cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression.
+    pub fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => trimmed,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(ScheduleError::new(
+                expression,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        };
+        let field = |value: &str, min, max, names: &[&str], first| {
+            parse_field(value, min, max, names, first)
+                .map_err(|message| ScheduleError::new(expression, message))
+        };
+        let mut weekdays = field(weekday, 0, 7, &WEEKDAYS, 0)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            expression: trimmed.to_string(),
+            minutes: field(minute, 0, 59, &[], 0)?,
+            hours: field(hour, 0, 23, &[], 0)?,
+            days: field(day, 1, 31, &[], 0)?,
+            months: field(month, 1, 12, &MONTHS, 1)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+
+    /// Get the expression this schedule was parsed from.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Get the first matching minute strictly after `after`.
+    ///
+    /// Returns `None` if nothing matches within the next few years, e.g. for
+    /// `0 0 31 2 *`.
+    #[must_use]
+    pub fn next_after(&self, after: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        const MAX_STEPS: usize = 100_000;
+
+        let start = after.naive_local();
+        let mut t = start.date().and_hms_opt(start.hour(), start.minute(), 0)?
+            + chrono::Duration::minutes(1);
+        for _ in 0..MAX_STEPS {
+            if !has(self.months, t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = midnight(NaiveDate::from_ymd_opt(year, month, 1)?);
+            } else if !self.day_matches(t.date()) {
+                t = midnight(t.date().succ_opt()?);
+            } else if !has(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + chrono::Duration::hours(1);
+            } else if !has(self.minutes, t.minute()) {
+                t += chrono::Duration::minutes(1);
+            } else {
+                return after.offset().from_local_datetime(&t).single();
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(chrono::NaiveTime::MIN)
+}
+
+/// Parse one cron field into a bit set of allowed values.
+///
+/// `names` are the names of the values starting at `first`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], first: u32) -> Result<u64, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let lower = s.to_ascii_lowercase();
+        let n = match names.iter().position(|name| *name == lower) {
+            Some(i) => i as u32 + first,
+            None => s.parse().map_err(|_| format!("invalid value '{s}'"))?,
+        };
+        if (min..=max).contains(&n) {
+            Ok(n)
+        } else {
+            Err(format!("value {n} out of range {min}-{max}"))
+        }
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("invalid step '{step}'"))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((a, b)) => (value(a)?, value(b)?),
+            // `5/15` means from 5 to the end in steps of 15
+            None if step.is_some() => (value(range)?, max),
+            None => {
+                let n = value(range)?;
+                (n, n)
+            }
+        };
+        if start > end {
+            return Err(format!("invalid range '{range}'"));
+        }
+        for n in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << n;
+        }
+    }
+    Ok(bits)
+}
+
+/// When a [`Job`] runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// On the minutes matching a cron expression.
+    Cron(CronSchedule),
+    /// At a fixed interval, counted from the previous run.
+    Every(Duration),
+}
+
+impl Schedule {
+    /// Parse a cron expression.
+    pub fn cron(expression: &str) -> Result<Self, ScheduleError> {
+        CronSchedule::parse(expression).map(Self::Cron)
+    }
+
+    /// Run at a fixed interval, of at least one millisecond.
+    #[must_use]
+    pub fn every(interval: Duration) -> Self {
+        Self::Every(interval.max(Duration::from_millis(1)))
+    }
+
+    /// Get the next run time strictly after `after`.
+    #[must_use]
+    pub fn next_after(&self, after: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Self::Cron(cron) => cron.next_after(after),
+            Self::Every(interval) => {
+                after.checked_add_signed(chrono::Duration::from_std(*interval).ok()?)
+            }
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::cron(s)
+    }
+}
+
+/// A recurring agent run.
+#[derive(Debug, Clone)]
+pub struct Job {
+    name: String,
+    schedule: Schedule,
+    template: String,
+    vars: BTreeMap<String, String>,
+    timezone: FixedOffset,
+    options: RunOptions,
+}
+
+impl Job {
+    /// Create a job running `template` on `schedule`, in UTC.
+    pub fn new(name: impl Into<String>, schedule: Schedule, template: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            schedule,
+            template: template.into(),
+            vars: BTreeMap::new(),
+            timezone: Utc.fix(),
+            options: RunOptions::default(),
+        }
+    }
+
+    /// Set a template variable.
+    ///
+    /// Variables take precedence over the built-in placeholders.
+    #[must_use]
+    pub fn var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Evaluate the schedule and render dates in `timezone`.
+    ///
+    /// Also the run's timezone, unless the run options set one.
+    #[must_use]
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Set the options of each run.
+    #[must_use]
+    pub fn run_options(mut self, options: RunOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Get the job name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the schedule.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get the next run time strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule
+            .next_after(after.with_timezone(&self.timezone))
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Render the prompt of run number `run`, scheduled at `at`.
+    pub fn render(&self, at: DateTime<Utc>, run: u64) -> String {
+        let local = at.with_timezone(&self.timezone);
+        let builtin = |name: &str| match name {
+            "date" => Some(local.format("%Y-%m-%d").to_string()),
+            "time" => Some(local.format("%H:%M").to_string()),
+            "datetime" => Some(local.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            "weekday" => Some(local.format("%A").to_string()),
+            "job" => Some(self.name.clone()),
+            "run" => Some(run.to_string()),
+            _ => None,
+        };
+
+        let mut out = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+        while let Some(i) = rest.find(['{', '}']) {
+            out.push_str(&rest[..i]);
+            rest = &rest[i..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                out.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            let name = rest[1..]
+                .find('}')
+                .map(|end| &rest[1..=end])
+                .filter(|_| rest.starts_with('{'));
+            let value = name.and_then(|name| {
+                self.vars
+                    .get(name)
+                    .cloned()
+                    .or_else(|| builtin(name))
+                    .map(|value| (name.len() + 2, value))
+            });
+            match value {
+                Some((len, value)) => {
+                    out.push_str(&value);
+                    rest = &rest[len..];
+                }
+                None => {
+                    out.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn options(&self, run: u64, scheduled_at: DateTime<Utc>) -> RunOptions {
+        let mut options = self.options.clone();
+        options.timezone.get_or_insert(self.timezone);
+        let metadata = options
+            .metadata
+            .get_or_insert_with(|| JsonValue::Object(Default::default()));
+        if let Some(metadata) = metadata.as_object_mut() {
+            metadata.insert("job".into(), self.name.clone().into());
+            metadata.insert("run".into(), run.into());
+            metadata.insert("scheduled_at".into(), scheduled_at.to_rfc3339().into());
+        }
+        options
+    }
+}
+
+/// Outcome of one scheduled run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    /// Name of the job.
+    pub job: String,
+    /// Run number of the job, starting at 1.
+    pub run: u64,
+    /// When the run was due.
+    pub scheduled_at: DateTime<Utc>,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+    /// When the run finished.
+    pub finished_at: DateTime<Utc>,
+    /// The rendered prompt.
+    pub prompt: String,
+    /// The output, if the agent finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<JsonValue>,
+    /// ID of the saved conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<ConversationId>,
+    /// The saved output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<ArtifactRef>,
+    /// Why the run failed, or why its results could not be saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JobRecord {
+    /// Check if the run finished and its results were saved.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Callback receiving finished runs.
+pub type JobHook = Arc<dyn Fn(&JobRecord) + Send + Sync>;
+
+struct JobSlot {
+    job: Job,
+    runs: AtomicU64,
+    running: AtomicBool,
+}
+
+/// Clears a job's running flag when its run ends, even by panic.
+struct RunningGuard<'a>(&'a AtomicBool);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Runs an agent on a schedule.
+pub struct Scheduler<Deps, Output = String> {
+    agent: Arc<Agent<Deps, Output>>,
+    deps: Deps,
+    jobs: Vec<JobSlot>,
+    conversations: Option<Arc<dyn ConversationStore>>,
+    artifacts: Option<Arc<dyn ArtifactStore>>,
+    on_success: Vec<JobHook>,
+    on_failure: Vec<JobHook>,
+}
+
+impl<Deps, Output> Scheduler<Deps, Output>
+where
+    Deps: Clone + Send + Sync + 'static,
+    Output: Serialize + Send + Sync + 'static,
+{
+    /// Create a scheduler without jobs, running `agent` with `deps`.
+    pub fn new(agent: impl Into<Arc<Agent<Deps, Output>>>, deps: Deps) -> Self {
+        Self {
+            agent: agent.into(),
+            deps,
+            jobs: Vec::new(),
+            conversations: None,
+            artifacts: None,
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+        }
+    }
+
+    /// Add a job.
+    #[must_use]
+    pub fn job(mut self, job: Job) -> Self {
+        self.jobs.push(JobSlot {
+            job,
+            runs: AtomicU64::new(0),
+            running: AtomicBool::new(false),
+        });
+        self
+    }
+
+    /// Save the conversation of every finished run.
+    #[must_use]
+    pub fn conversation_store(mut self, store: Arc<dyn ConversationStore>) -> Self {
+        self.conversations = Some(store);
+        self
+    }
+
+    /// Save the output of every finished run.
+    ///
+    /// Text outputs are stored as `text/plain`, others as JSON.
+    #[must_use]
+    pub fn artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifacts = Some(store);
+        self
+    }
+
+    /// Call `f` after every successful run.
+    #[must_use]
+    pub fn on_success<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&JobRecord) + Send + Sync + 'static,
+    {
+        self.on_success.push(Arc::new(f));
+        self
+    }
+
+    /// Call `f` after every failed run, e.g. to send an alert.
+    #[must_use]
+    pub fn on_failure<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&JobRecord) + Send + Sync + 'static,
+    {
+        self.on_failure.push(Arc::new(f));
+        self
+    }
+
+    /// Get the jobs.
+    pub fn jobs(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter().map(|slot| &slot.job)
+    }
+
+    /// Get the job that runs next after `after`, and when.
+    pub fn next_run(&self, after: DateTime<Utc>) -> Option<(&Job, DateTime<Utc>)> {
+        self.jobs()
+            .filter_map(|job| Some((job, job.next_after(after)?)))
+            .min_by_key(|(_, at)| *at)
+    }
+
+    /// Run a job now, outside its schedule.
+    ///
+    /// Returns `None` if there is no job named `name`.
+    pub async fn run_job(&self, name: &str) -> Option<JobRecord> {
+        let slot = self.jobs.iter().find(|slot| slot.job.name == name)?;
+        Some(self.execute(slot, now_utc()).await)
+    }
+
+    /// Run jobs as they come due until `cancel` is triggered.
+    ///
+    /// A run that is due while the previous run of the same job is still in
+    /// progress is skipped, as are runs missed while the process was busy.
+    /// Runs in progress when `cancel` is triggered are waited for.
+    pub async fn run(self: Arc<Self>, cancel: CancellationToken) {
+        let mut tasks = JoinSet::new();
+        let start = now_utc();
+        let mut next: Vec<_> = self.jobs().map(|job| job.next_after(start)).collect();
+
+        while let Some(due) = next.iter().flatten().min().copied() {
+            while tasks.try_join_next().is_some() {}
+
+            let wait = (due - now_utc()).to_std().unwrap_or_default();
+            if serdes_ai_core::clock::clock().skip_wait(wait) {
+                tokio::task::yield_now().await;
+                if cancel.is_cancelled() {
+                    break;
+                }
+            } else {
+                tokio::select! {
+                    () = cancel.cancelled() => break,
+                    () = tokio::time::sleep(wait) => {}
+                }
+            }
+
+            let now = now_utc();
+            for (i, slot) in self.jobs.iter().enumerate() {
+                let Some(at) = next[i].filter(|at| *at <= now) else {
+                    continue;
+                };
+                next[i] = slot.job.next_after(now.max(at));
+                if slot.running.swap(true, Ordering::SeqCst) {
+                    warn!(job = %slot.job.name, "Previous run still in progress, skipping");
+                    continue;
+                }
+                let scheduler = Arc::clone(&self);
+                tasks.spawn(async move {
+                    let slot = &scheduler.jobs[i];
+                    let _running = RunningGuard(&slot.running);
+                    scheduler.execute(slot, at).await;
+                });
+            }
+        }
+
+        if next.iter().all(Option::is_none) {
+            cancel.cancelled().await;
+        }
+        while tasks.join_next().await.is_some() {}
+    }
+
+    async fn execute(&self, slot: &JobSlot, scheduled_at: DateTime<Utc>) -> JobRecord {
+        let job = &slot.job;
+        let run = slot.runs.fetch_add(1, Ordering::SeqCst) + 1;
+        let prompt = job.render(scheduled_at, run);
+        let started_at = now_utc();
+        let result = self
+            .agent
+            .run_with_options(
+                prompt.clone(),
+                self.deps.clone(),
+                job.options(run, scheduled_at),
+            )
+            .await;
+
+        let mut record = JobRecord {
+            job: job.name.clone(),
+            run,
+            scheduled_at,
+            started_at,
+            finished_at: now_utc(),
+            prompt,
+            output: None,
+            conversation_id: None,
+            artifact: None,
+            error: None,
+        };
+        match result {
+            Ok(result) => {
+                if let Err(e) = self.persist(&result, &mut record) {
+                    record.error = Some(e);
+                }
+            }
+            Err(e) => record.error = Some(e.to_string()),
+        }
+
+        if record.is_success() {
+            self.on_success.iter().for_each(|hook| hook(&record));
+        } else {
+            warn!(job = %record.job, run, error = ?record.error, "Scheduled run failed");
+            self.on_failure.iter().for_each(|hook| hook(&record));
+        }
+        record
+    }
+
+    fn persist(
+        &self,
+        result: &crate::run::AgentRunResult<Output>,
+        record: &mut JobRecord,
+    ) -> Result<(), String> {
+        let output = serde_json::to_value(&result.output)
+            .map_err(|e| format!("Failed to serialize output: {e}"))?;
+        record.output = Some(output.clone());
+
+        if let Some(store) = &self.conversations {
+            let conversation = result.fork();
+            store
+                .save(&conversation)
+                .map_err(|e| format!("Failed to save conversation: {e}"))?;
+            record.conversation_id = Some(conversation.id().clone());
+        }
+        if let Some(store) = &self.artifacts {
+            let content = match output {
+                JsonValue::String(text) => BinaryContent::new(text.into_bytes(), "text/plain"),
+                other => BinaryContent::new(
+                    serde_json::to_vec_pretty(&other).unwrap_or_default(),
+                    "application/json",
+                ),
+            };
+            let artifact = store
+                .put(content)
+                .map_err(|e| format!("Failed to save output: {e}"))?;
+            record.artifact = Some(artifact);
+        }
+        Ok(())
+    }
+}
+
+impl<Deps, Output> fmt::Debug for Scheduler<Deps, Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field(
+                "jobs",
+                &self.jobs.iter().map(|s| &s.job.name).collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversations::InMemoryConversationStore;
+    use crate::errors::OutputValidationError;
+    use serdes_ai_core::artifacts::InMemoryArtifactStore;
+    use serdes_ai_models::FunctionModel;
+    use std::sync::Mutex;
+
+    fn at(s: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(s).unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let next = |expr: &str, after: &str| {
+            CronSchedule::parse(expr)
+                .unwrap()
+                .next_after(at(after))
+                .map(|t| t.to_rfc3339())
+        };
+        // 2026-10-17 is a Saturday
+        assert_eq!(
+            next("*/15 9-17 * * mon-fri", "2026-10-17T10:00:00Z").as_deref(),
+            Some("2026-10-19T09:00:00+00:00")
+        );
+        assert_eq!(
+            next("*/15 9-17 * * mon-fri", "2026-10-19T09:07:30Z").as_deref(),
+            Some("2026-10-19T09:15:00+00:00")
+        );
+        assert_eq!(
+            next("@daily", "2026-10-17T10:00:00+02:00").as_deref(),
+            Some("2026-10-18T00:00:00+02:00")
+        );
+        assert_eq!(
+            next("0 0 29 feb *", "2026-01-01T00:00:00Z").as_deref(),
+            Some("2028-02-29T00:00:00+00:00")
+        );
+        // Day of month or day of week
+        assert_eq!(
+            next("0 12 1 * sun", "2026-10-17T00:00:00Z").as_deref(),
+            Some("2026-10-18T12:00:00+00:00")
+        );
+        assert_eq!(next("0 0 31 2 *", "2026-01-01T00:00:00Z"), None);
+
+        assert!(CronSchedule::parse("61 * * * *").is_err());
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 * * 7").is_ok());
+    }
+
+    #[test]
+    fn test_render_template() {
+        let job = Job::new(
+            "report",
+            Schedule::every(Duration::from_secs(60)),
+            "Run {run} of {job} for {date} ({weekday} {time}) in {region}, {{literal}} {unknown}",
+        )
+        .var("region", "eu-west")
+        .timezone(FixedOffset::east_opt(2 * 3600).unwrap());
+        let scheduled = at("2026-10-17T22:30:00Z").with_timezone(&Utc);
+        assert_eq!(
+            job.render(scheduled, 3),
+            "Run 3 of report for 2026-10-18 (Sunday 00:30) in eu-west, {literal} {unknown}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_job_persists_results() {
+        let agent = crate::agent(FunctionModel::constant_text("All systems nominal")).build();
+        let conversations = Arc::new(InMemoryConversationStore::new());
+        let artifacts = Arc::new(InMemoryArtifactStore::new());
+        let scheduler = Scheduler::new(agent, ())
+            .job(Job::new(
+                "status",
+                Schedule::cron("@hourly").unwrap(),
+                "Status for {date}",
+            ))
+            .conversation_store(conversations.clone())
+            .artifact_store(artifacts.clone());
+
+        let record = scheduler.run_job("status").await.unwrap();
+        assert!(record.is_success(), "{:?}", record.error);
+        assert_eq!(record.run, 1);
+        assert_eq!(record.output, Some(JsonValue::from("All systems nominal")));
+        let conversation = conversations
+            .load(record.conversation_id.as_ref().unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(conversation.steps() >= 1);
+        let stored = artifacts
+            .get(&record.artifact.unwrap().hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.data, b"All systems nominal");
+        assert!(scheduler.run_job("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failure_hook() {
+        let agent = crate::agent(FunctionModel::constant_text("bad"))
+            .max_output_retries(0)
+            .output_validator_fn(|_, _| Err(OutputValidationError::failed("always fails")))
+            .build();
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let seen = failures.clone();
+        let scheduler = Scheduler::new(agent, ())
+            .job(Job::new(
+                "check",
+                Schedule::every(Duration::from_secs(60)),
+                "Check",
+            ))
+            .on_success(|_| panic!("run should fail"))
+            .on_failure(move |record| seen.lock().unwrap().push(record.clone()));
+
+        let record = scheduler.run_job("check").await.unwrap();
+        assert!(!record.is_success());
+        assert!(record.output.is_none());
+        assert_eq!(failures.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_loop() {
+        let agent = crate::agent(FunctionModel::constant_text("tick")).build();
+        let cancel = CancellationToken::new();
+        let runs = Arc::new(AtomicU64::new(0));
+        let (stop, counter) = (cancel.clone(), runs.clone());
+        let scheduler = Scheduler::new(agent, ())
+            .job(Job::new(
+                "tick",
+                Schedule::every(Duration::from_millis(10)),
+                "Tick {run}",
+            ))
+            .on_success(move |_| {
+                if counter.fetch_add(1, Ordering::SeqCst) + 1 >= 3 {
+                    stop.cancel();
+                }
+            });
+
+        tokio::time::timeout(Duration::from_secs(5), Arc::new(scheduler).run(cancel))
+            .await
+            .unwrap();
+        assert!(runs.load(Ordering::SeqCst) >= 3);
+    }
+}
@@ -0,0 +1,239 @@
+//! Writing streamed output straight to storage.
+//!
+//! Very long generations (reports, code dumps) don't need to be collected in
+//! memory by the caller. [`AgentStream::pipe_to`](crate::AgentStream::pipe_to)
+//! writes every text delta and generated file to a [`StreamSink`] as it
+//! arrives:
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::FileSink;
+//!
+//! let mut sink = FileSink::create("report.md").await?;
+//! let piped = agent.run_stream("Write the quarterly report", deps).await?.pipe_to(&mut sink).await?;
+//! println!("wrote {} bytes and {} files", piped.text_bytes, piped.files);
+//! ```
+//!
+//! Implement [`StreamSink`] to write to object storage or any other
+//! destination; [`WriterSink`] adapts any [`AsyncWrite`].
+
+use async_trait::async_trait;
+use serdes_ai_core::messages::FilePart;
+use serdes_ai_core::ModelRequest;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+/// Destination for streamed output.
+#[async_trait]
+pub trait StreamSink: Send {
+    /// Write a chunk of text.
+    async fn write_text(&mut self, text: &str) -> io::Result<()>;
+
+    /// Write a file produced by the model.
+    ///
+    /// The default ignores files.
+    async fn write_file(&mut self, file: &FilePart) -> io::Result<()> {
+        let _ = file;
+        Ok(())
+    }
+
+    /// Flush pending output once the run is complete.
+    async fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S: StreamSink + ?Sized> StreamSink for Box<S> {
+    async fn write_text(&mut self, text: &str) -> io::Result<()> {
+        (**self).write_text(text).await
+    }
+
+    async fn write_file(&mut self, file: &FilePart) -> io::Result<()> {
+        (**self).write_file(file).await
+    }
+
+    async fn finish(&mut self) -> io::Result<()> {
+        (**self).finish().await
+    }
+}
+
+/// Outcome of piping a stream into a sink.
+#[derive(Debug, Clone)]
+pub struct PipedRun {
+    /// Run ID.
+    pub run_id: String,
+    /// Bytes of text written.
+    pub text_bytes: u64,
+    /// Number of files written.
+    pub files: usize,
+    /// Complete message history from the run.
+    pub messages: Vec<ModelRequest>,
+}
+
+/// Sink writing text to any [`AsyncWrite`]; files are ignored.
+#[derive(Debug)]
+pub struct WriterSink<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin + Send> WriterSink<W> {
+    /// Create a sink writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Get the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send> StreamSink for WriterSink<W> {
+    async fn write_text(&mut self, text: &str) -> io::Result<()> {
+        self.writer.write_all(text.as_bytes()).await
+    }
+
+    async fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush().await
+    }
+}
+
+/// Sink writing text to a file on disk.
+///
+/// Files produced by the model are written next to it as `<stem>-<n>.<ext>`,
+/// or into the directory set with [`with_files_dir`](Self::with_files_dir).
+#[derive(Debug)]
+pub struct FileSink {
+    writer: BufWriter<tokio::fs::File>,
+    path: PathBuf,
+    files_dir: PathBuf,
+    written_files: Vec<PathBuf>,
+}
+
+impl FileSink {
+    /// Create (or truncate) the file at `path`.
+    pub async fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = tokio::fs::File::create(&path).await?;
+        let files_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(Self {
+            writer: BufWriter::new(file),
+            path,
+            files_dir,
+            written_files: Vec::new(),
+        })
+    }
+
+    /// Set the directory for files produced by the model.
+    #[must_use]
+    pub fn with_files_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.files_dir = dir.into();
+        self
+    }
+
+    /// Get the path of the text file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the paths of the files written so far.
+    pub fn written_files(&self) -> &[PathBuf] {
+        &self.written_files
+    }
+}
+
+#[async_trait]
+impl StreamSink for FileSink {
+    async fn write_text(&mut self, text: &str) -> io::Result<()> {
+        self.writer.write_all(text.as_bytes()).await
+    }
+
+    async fn write_file(&mut self, file: &FilePart) -> io::Result<()> {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "output".to_string());
+        let name = format!(
+            "{}-{}.{}",
+            stem,
+            self.written_files.len() + 1,
+            extension_for(&file.content.media_type)
+        );
+        tokio::fs::create_dir_all(&self.files_dir).await?;
+        let path = self.files_dir.join(name);
+        tokio::fs::write(&path, &file.content.data).await?;
+        self.written_files.push(path);
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush().await
+    }
+}
+
+/// File extension for a media type, e.g. `png` for `image/png`.
+fn extension_for(media_type: &str) -> String {
+    let subtype = media_type
+        .split(';')
+        .next()
+        .and_then(|t| t.split('/').nth(1))
+        .map(str::trim)
+        .unwrap_or_default();
+    let subtype = subtype.split('+').next().unwrap_or_default();
+    let subtype = subtype.strip_prefix("x-").unwrap_or(subtype);
+    match subtype {
+        "" | "octet-stream" => "bin".to_string(),
+        "jpeg" => "jpg".to_string(),
+        "plain" => "txt".to_string(),
+        "mpeg" if media_type.starts_with("audio/") => "mp3".to_string(),
+        other => other.to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_for() {
+        assert_eq!(extension_for("image/png"), "png");
+        assert_eq!(extension_for("image/jpeg"), "jpg");
+        assert_eq!(extension_for("image/svg+xml"), "svg");
+        assert_eq!(extension_for("text/plain; charset=utf-8"), "txt");
+        assert_eq!(extension_for("audio/x-wav"), "wav");
+        assert_eq!(extension_for("audio/mpeg"), "mp3");
+        assert_eq!(extension_for("application/octet-stream"), "bin");
+        assert_eq!(extension_for("garbage"), "bin");
+    }
+
+    #[tokio::test]
+    async fn test_file_sink() {
+        let dir = std::env::temp_dir().join(format!("serdes-ai-sink-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let mut sink = FileSink::create(dir.join("report.md"))
+            .await
+            .unwrap()
+            .with_files_dir(dir.join("assets"));
+        sink.write_text("# Report\n").await.unwrap();
+        sink.write_file(&FilePart::from_bytes(vec![1, 2, 3], "image/png"))
+            .await
+            .unwrap();
+        sink.write_text("done").await.unwrap();
+        sink.finish().await.unwrap();
+
+        let text = tokio::fs::read_to_string(sink.path()).await.unwrap();
+        assert_eq!(text, "# Report\ndone");
+        assert_eq!(
+            sink.written_files(),
+            [dir.join("assets").join("report-1.png")]
+        );
+        let data = tokio::fs::read(&sink.written_files()[0]).await.unwrap();
+        assert_eq!(data, [1, 2, 3]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
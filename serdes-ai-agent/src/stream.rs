@@ -3,22 +3,33 @@
 //! This module provides streaming support for agent runs with real
 //! character-by-character streaming from the model.
 
-use crate::agent::{Agent, RegisteredTool};
-use crate::context::{generate_run_id, RunContext, RunUsage};
-use crate::errors::AgentRunError;
-use crate::run::{CompressionStrategy, RunOptions};
-use chrono::Utc;
+use crate::agent::{Agent, RegisteredTool, ToolFailurePolicy};
+use crate::audit::AuditStatus;
+use crate::context::{idempotency_key, RunContext, RunUsage};
+use crate::errors::{AgentRunError, OutputValidationError};
+use crate::output::OutputValidator;
+use crate::run::{
+    apply_prefill, check_preflight, prefill_request, request_settings, resolve_run_settings,
+    set_latest_instructions, tool_retry_prompt, CompressionStrategy, RunOptions,
+};
+use crate::sink::{PipedRun, StreamSink};
 use futures::{Stream, StreamExt};
 use serdes_ai_core::messages::{
-    ModelResponseStreamEvent, ToolCallArgs, ToolReturnPart, UserContent,
+    FilePart, JsonRepairer, ModelResponseStreamEvent, RetryPromptPart, ToolCallArgs,
+    ToolReturnPart, UserContent,
 };
+use serdes_ai_core::now_utc;
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart,
 };
 use serdes_ai_models::ModelRequestParameters;
+use serdes_ai_streaming::{ManagedPart, ModelResponsePartsManager};
+use serdes_ai_tools::{ToolCallRecord, ToolError, ToolReturn};
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
@@ -89,6 +100,13 @@ pub enum AgentStreamEvent {
         tool_name: String,
         tool_call_id: Option<String>,
     },
+    /// Malformed tool call arguments were repaired before execution.
+    ToolArgsRepaired {
+        tool_name: String,
+        tool_call_id: Option<String>,
+        /// Names of the repair passes that were applied.
+        passes: Vec<String>,
+    },
     /// Tool executed.
     ToolExecuted {
         tool_name: String,
@@ -98,8 +116,21 @@ pub enum AgentStreamEvent {
     },
     /// Thinking delta (for reasoning models).
     ThinkingDelta { text: String },
+    /// The model produced a file (e.g. a generated image).
+    FileReceived { file: FilePart },
     /// Model response completed.
     ResponseComplete { step: u32 },
+    /// Streamed text output was rejected by an output validator.
+    ///
+    /// The model is asked to try again unless output retries are exhausted,
+    /// in which case the run ends with
+    /// [`AgentRunError::OutputValidationFailed`].
+    OutputRejected {
+        /// Why the output was rejected.
+        message: String,
+        /// Whether generation was aborted before the response completed.
+        aborted: bool,
+    },
     /// Output ready.
     OutputReady,
     /// Run completed.
@@ -140,14 +171,181 @@ pub struct AgentStream {
     cancel_token: Option<CancellationToken>,
 }
 
+/// Text of a streamed text chunk, led by the prefill if it is the first one.
+///
+/// Mirrors [`apply_prefill`]: a reply that repeats the prefill is left alone.
+fn with_prefill(pending: &mut Option<&str>, text: &str) -> String {
+    match pending.take() {
+        Some(prefill) if !text.starts_with(prefill) => format!("{prefill}{text}"),
+        _ => text.to_string(),
+    }
+}
+
+/// Tool return content for a failed call, following the tool's
+/// [`ToolFailurePolicy`]; `None` if the run should fail.
+fn tool_error_content<Deps>(
+    tool: &RegisteredTool<Deps>,
+    tool_name: &str,
+    error: &ToolError,
+) -> Option<String> {
+    let policy = if error.is_unavailable() {
+        tool.failure_policy
+    } else {
+        ToolFailurePolicy::Report
+    };
+    match policy {
+        ToolFailurePolicy::Report => Some(format!("Tool error: {}", error)),
+        ToolFailurePolicy::Degrade => {
+            Some(ToolFailurePolicy::unavailable_message(tool_name, error))
+        }
+        ToolFailurePolicy::Fail => None,
+    }
+}
+
 /// Canonicalize tool-call arguments in a model response before persisting it.
-fn canonicalize_tool_call_args_in_response(response: &mut ModelResponse) {
+///
+/// Returns a [`AgentStreamEvent::ToolArgsRepaired`] event for each call whose
+/// arguments had to be repaired.
+fn canonicalize_tool_call_args_in_response(
+    response: &mut ModelResponse,
+    repairer: &JsonRepairer,
+) -> Vec<AgentStreamEvent> {
+    let mut events = Vec::new();
     for part in &mut response.parts {
         if let ModelResponsePart::ToolCall(tc) = part {
-            let repaired = tc.args.to_json();
+            let (repaired, passes) = tc.args.to_json_with(repairer);
+            if !passes.is_empty() {
+                events.push(AgentStreamEvent::ToolArgsRepaired {
+                    tool_name: tc.tool_name.clone(),
+                    tool_call_id: tc.tool_call_id.clone(),
+                    passes,
+                });
+            }
             tc.args = ToolCallArgs::Json(repaired);
         }
     }
+    events
+}
+
+/// Checks streamed text output against the agent's output validators.
+///
+/// Partial checks run on every text delta so generation can be aborted as
+/// soon as the output can no longer become valid; constraints that need the
+/// complete text are deferred until the response finishes. Text in a
+/// response that calls tools is not final output and is never checked.
+struct StreamingTextCheck<Output, Deps> {
+    validators: Vec<Arc<dyn OutputValidator<Output, Deps>>>,
+    max_retries: u32,
+    retries: u32,
+    text: String,
+    has_tool_call: bool,
+    rejection: Option<OutputValidationError>,
+}
+
+impl<Output, Deps> StreamingTextCheck<Output, Deps> {
+    fn new(agent: &Agent<Deps, Output>) -> Self {
+        Self {
+            validators: agent.output_validators.clone(),
+            max_retries: agent.max_output_retries,
+            retries: 0,
+            text: String::new(),
+            has_tool_call: false,
+            rejection: None,
+        }
+    }
+
+    /// Reset per-response state before a new model request.
+    fn start_response(&mut self) {
+        self.text.clear();
+        self.has_tool_call = false;
+        self.rejection = None;
+    }
+
+    fn tool_call_started(&mut self) {
+        self.has_tool_call = true;
+    }
+
+    /// Record a text delta. Returns `false` if generation should be aborted.
+    fn push(&mut self, delta: &str) -> bool {
+        if self.validators.is_empty() || self.has_tool_call {
+            return true;
+        }
+        self.text.push_str(delta);
+        let text = &self.text;
+        let result = self
+            .validators
+            .iter()
+            .try_for_each(|v| v.check_partial_text(text));
+        self.record(result)
+    }
+
+    /// Check the complete text of a response. Returns `false` if rejected.
+    fn finish(&mut self) -> bool {
+        if self.validators.is_empty() || self.has_tool_call {
+            return true;
+        }
+        let text = &self.text;
+        let result = self
+            .validators
+            .iter()
+            .try_for_each(|v| v.check_final_text(text));
+        self.record(result)
+    }
+
+    fn record(&mut self, result: Result<(), OutputValidationError>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.rejection = Some(e);
+                false
+            }
+        }
+    }
+
+    fn is_rejected(&self) -> bool {
+        self.rejection.is_some()
+    }
+
+    /// Turn the current rejection into a retry prompt appended to `messages`.
+    ///
+    /// Returns the event to emit, or the error ending the run once output
+    /// retries are exhausted.
+    fn retry(
+        &mut self,
+        response: &ModelResponse,
+        messages: &mut Vec<ModelRequest>,
+        aborted: bool,
+    ) -> Result<AgentStreamEvent, AgentRunError> {
+        let error = self
+            .rejection
+            .take()
+            .unwrap_or_else(|| OutputValidationError::failed("Output rejected"));
+        self.retries += 1;
+        if self.retries > self.max_retries {
+            return Err(AgentRunError::OutputValidationFailed(error));
+        }
+
+        let event = AgentStreamEvent::OutputRejected {
+            message: error.to_string(),
+            aborted,
+        };
+
+        let mut response_req = ModelRequest::new();
+        response_req
+            .parts
+            .push(ModelRequestPart::ModelResponse(Box::new(response.clone())));
+        messages.push(response_req);
+
+        let mut retry_req = ModelRequest::new();
+        retry_req
+            .parts
+            .push(ModelRequestPart::RetryPrompt(RetryPromptPart::new(
+                error.retry_message(),
+            )));
+        messages.push(retry_req);
+
+        Ok(event)
+    }
 }
 
 impl AgentStream {
@@ -165,28 +363,41 @@ impl AgentStream {
         Deps: Send + Sync + 'static,
         Output: Send + Sync + 'static,
     {
-        let run_id = generate_run_id();
+        let run_id = agent.new_run_id();
         let (tx, rx) = mpsc::channel(64);
 
         // Clone what we need for the spawned task
         let model = agent.model_arc();
         let model_name = model.name().to_string();
-        let model_settings = options
-            .model_settings
-            .clone()
-            .unwrap_or_else(|| agent.model_settings.clone());
+        let model_settings = resolve_run_settings(agent, &options, &deps);
+        let request_settings_fns = agent.request_settings_fns.clone();
+        let language = agent.detect_language(&prompt).await;
 
         // Get the static system prompt - for streaming we use just the static part
         // Dynamic prompts are not supported in streaming mode for simplicity
         let static_system_prompt = agent.static_system_prompt().to_string();
-
-        let tool_definitions = agent.tool_definitions();
+        let static_instructions = Some(agent.static_instructions())
+            .filter(|i| !i.is_empty())
+            .map(str::to_string);
+
+        let tool_usage_category = agent.tool_usage_category(&prompt);
+        let tool_definitions = agent
+            .run_tool_definitions(&prompt, tool_usage_category.as_deref())
+            .await;
+        let tool_routing = agent.tool_routing.clone();
+        let request_params = agent.request_params(Arc::clone(&tool_definitions));
         let _end_strategy = agent.end_strategy;
         let usage_limits = agent.usage_limits.clone();
         let run_usage_limits = options.usage_limits.clone();
+        let usage_meter = agent.usage_meter();
+        let audit = agent.audit.clone();
 
         // Clone tool executors - now possible because RegisteredTool implements Clone!
         let tools: Vec<RegisteredTool<Deps>> = agent.tools.to_vec();
+        let tool_stats = agent.new_tool_stats();
+        let json_repair = agent.json_repair.clone();
+        let history_transform = agent.history_transform.clone();
+        let mut text_check = StreamingTextCheck::new(agent);
 
         // Wrap deps in Arc for shared access in tool execution
         let deps = Arc::new(deps);
@@ -194,6 +405,7 @@ impl AgentStream {
         let initial_history = options.message_history.clone();
         let _metadata = options.metadata.clone();
         let compression_config = options.compression.clone();
+        let assistant_prefill = options.assistant_prefill.clone();
         let run_id_clone = run_id.clone();
 
         debug!(run_id = %run_id, "AgentStream: spawning streaming task");
@@ -217,6 +429,8 @@ impl AgentStream {
 
             // Build initial messages
             let mut messages = initial_history.unwrap_or_default();
+            // Instructions of earlier runs are not part of the history
+            set_latest_instructions(&mut messages, None);
             debug!(
                 initial_messages = messages.len(),
                 "AgentStream: building messages"
@@ -236,6 +450,8 @@ impl AgentStream {
 
             let mut responses: Vec<ModelResponse> = Vec::new();
             let mut usage = RunUsage::new();
+            let mut tool_retries: HashMap<String, u32> = HashMap::new();
+            let mut completed_calls: HashMap<String, ToolReturn> = HashMap::new();
             let mut step = 0u32;
             let mut finished = false;
             let mut finish_reason: Option<FinishReason>;
@@ -269,9 +485,7 @@ impl AgentStream {
                 }
 
                 // Build request parameters
-                let params = ModelRequestParameters::new()
-                    .with_tools_arc(tool_definitions.clone())
-                    .with_allow_text(true);
+                let params = request_params.clone();
 
                 // === Context Size Calculation & Compression ===
 
@@ -482,9 +696,37 @@ impl AgentStream {
                     message_count = messages.len(),
                     "AgentStream: calling model.request_stream"
                 );
-                let stream_result = model
-                    .request_stream(&messages, &model_settings, &params)
-                    .await;
+                let settings = {
+                    let mut ctx = RunContext::with_shared_deps(deps.clone(), model_name.clone());
+                    ctx.model_settings = model_settings.clone();
+                    ctx.language = language.clone();
+                    request_settings(&ctx, &request_settings_fns)
+                };
+                set_latest_instructions(&mut messages, static_instructions.clone());
+                if let Err(e) = check_preflight(
+                    model.as_ref(),
+                    &messages,
+                    &params,
+                    [usage_limits.as_ref(), run_usage_limits.as_ref()],
+                    &usage,
+                )
+                .await
+                {
+                    let _ = tx.send(Err(e.into())).await;
+                    return;
+                }
+                if let Some(Err(e)) = usage_meter.as_ref().map(|m| m.check()) {
+                    let _ = tx.send(Err(e.into())).await;
+                    return;
+                }
+                if let Some(prefill) = &assistant_prefill {
+                    messages.push(prefill_request(prefill));
+                }
+                let stream_result = model.request_stream(&messages, &settings, &params).await;
+                if assistant_prefill.is_some() {
+                    messages.pop();
+                }
+                set_latest_instructions(&mut messages, None);
 
                 let mut model_stream = match stream_result {
                     Ok(s) => {
@@ -498,13 +740,23 @@ impl AgentStream {
                                 message: e.to_string(),
                             }))
                             .await;
+                        if let Some(audit) = &audit {
+                            let status = AuditStatus::Error {
+                                message: e.to_string(),
+                            };
+                            audit
+                                .model_request(&deps, &run_id_clone, model.name(), None, status)
+                                .await;
+                        }
                         let _ = tx.send(Err(AgentRunError::Model(e))).await;
                         return;
                     }
                 };
 
                 // Collect response parts while streaming
-                let mut response_parts: Vec<ModelResponsePart> = Vec::new();
+                let mut parts_manager = ModelResponsePartsManager::new();
+                text_check.start_response();
+                let mut pending_prefill = assistant_prefill.as_deref();
                 // Track stream events (used by tracing when enabled)
                 let mut stream_event_count = 0u32;
 
@@ -517,19 +769,27 @@ impl AgentStream {
                     }
                     match event_result {
                         Ok(event) => {
+                            parts_manager.handle_event(&event);
                             match event {
                                 ModelResponseStreamEvent::PartStart(start) => {
                                     match &start.part {
                                         ModelResponsePart::Text(t) => {
                                             if !t.content.is_empty() {
+                                                let text =
+                                                    with_prefill(&mut pending_prefill, &t.content);
                                                 let _ = tx
                                                     .send(Ok(AgentStreamEvent::TextDelta {
-                                                        text: t.content.clone(),
+                                                        text: text.clone(),
                                                     }))
                                                     .await;
+                                                if !text_check.push(&text) {
+                                                    // Dropping the stream aborts generation
+                                                    break;
+                                                }
                                             }
                                         }
                                         ModelResponsePart::ToolCall(tc) => {
+                                            text_check.tool_call_started();
                                             let _ = tx
                                                 .send(Ok(AgentStreamEvent::ToolCallStart {
                                                     tool_name: tc.tool_name.clone(),
@@ -549,6 +809,13 @@ impl AgentStream {
                                                 }
                                             }
                                         }
+                                        ModelResponsePart::File(file) => {
+                                            let _ = tx
+                                                .send(Ok(AgentStreamEvent::FileReceived {
+                                                    file: file.clone(),
+                                                }))
+                                                .await;
+                                        }
                                         ModelResponsePart::Thinking(t) => {
                                             if !t.content.is_empty() {
                                                 let _ = tx
@@ -560,29 +827,30 @@ impl AgentStream {
                                         }
                                         _ => {}
                                     }
-                                    response_parts.push(start.part.clone());
                                 }
                                 ModelResponseStreamEvent::PartDelta(delta) => {
                                     use serdes_ai_core::messages::ModelResponsePartDelta;
                                     match &delta.delta {
                                         ModelResponsePartDelta::Text(t) => {
+                                            let text = with_prefill(
+                                                &mut pending_prefill,
+                                                &t.content_delta,
+                                            );
                                             let _ = tx
                                                 .send(Ok(AgentStreamEvent::TextDelta {
-                                                    text: t.content_delta.clone(),
+                                                    text: text.clone(),
                                                 }))
                                                 .await;
-                                            // Update the part
-                                            if let Some(ModelResponsePart::Text(ref mut text)) =
-                                                response_parts.get_mut(delta.index)
-                                            {
-                                                text.content.push_str(&t.content_delta);
+                                            if !text_check.push(&text) {
+                                                break;
                                             }
                                         }
                                         ModelResponsePartDelta::ToolCall(tc) => {
                                             // Get tool_call_id from the existing response part
-                                            let tool_call_id =
-                                                response_parts.get(delta.index).and_then(|p| {
-                                                    if let ModelResponsePart::ToolCall(tc) = p {
+                                            let tool_call_id = parts_manager
+                                                .event_part(delta.index)
+                                                .and_then(|p| {
+                                                    if let ManagedPart::ToolCall(tc) = p {
                                                         tc.tool_call_id.clone()
                                                     } else {
                                                         None
@@ -594,13 +862,6 @@ impl AgentStream {
                                                     tool_call_id,
                                                 }))
                                                 .await;
-                                            // Update args - accumulate the delta into the tool call
-                                            if let Some(ModelResponsePart::ToolCall(
-                                                ref mut tool_call,
-                                            )) = response_parts.get_mut(delta.index)
-                                            {
-                                                tc.apply(tool_call);
-                                            }
                                         }
                                         ModelResponsePartDelta::Thinking(t) => {
                                             let _ = tx
@@ -608,12 +869,6 @@ impl AgentStream {
                                                     text: t.content_delta.clone(),
                                                 }))
                                                 .await;
-                                            if let Some(ModelResponsePart::Thinking(
-                                                ref mut think,
-                                            )) = response_parts.get_mut(delta.index)
-                                            {
-                                                t.apply(think);
-                                            }
                                         }
                                         _ => {}
                                     }
@@ -629,6 +884,14 @@ impl AgentStream {
                                     message: e.to_string(),
                                 }))
                                 .await;
+                            if let Some(audit) = &audit {
+                                let status = AuditStatus::Error {
+                                    message: e.to_string(),
+                                };
+                                audit
+                                    .model_request(&deps, &run_id_clone, model.name(), None, status)
+                                    .await;
+                            }
                             let _ = tx.send(Err(AgentRunError::Model(e))).await;
                             return;
                         }
@@ -637,22 +900,34 @@ impl AgentStream {
 
                 info!(
                     stream_events = stream_event_count,
-                    parts = response_parts.len(),
+                    parts = parts_manager.len(),
                     "AgentStream: finished processing model stream"
                 );
 
                 // Build the complete response
                 let mut response = ModelResponse {
-                    parts: response_parts.clone(),
+                    parts: parts_manager.get_parts(),
                     model_name: Some(model.name().to_string()),
-                    timestamp: Utc::now(),
+                    timestamp: now_utc(),
                     finish_reason: Some(FinishReason::Stop),
                     usage: None,
                     vendor_id: None,
                     vendor_details: None,
+                    alternatives: Vec::new(),
                     kind: "response".to_string(),
                 };
-                canonicalize_tool_call_args_in_response(&mut response);
+                if let Some(audit) = &audit {
+                    let status = AuditStatus::Success;
+                    audit
+                        .model_request(&deps, &run_id_clone, model.name(), None, status)
+                        .await;
+                }
+                if let Some(prefill) = &assistant_prefill {
+                    apply_prefill(&mut response, prefill);
+                }
+                for event in canonicalize_tool_call_args_in_response(&mut response, &json_repair) {
+                    let _ = tx.send(Ok(event)).await;
+                }
 
                 finish_reason = response.finish_reason;
                 responses.push(response.clone());
@@ -662,6 +937,19 @@ impl AgentStream {
                     .send(Ok(AgentStreamEvent::ResponseComplete { step }))
                     .await;
 
+                if text_check.is_rejected() {
+                    match text_check.retry(&response, &mut messages, true) {
+                        Ok(event) => {
+                            let _ = tx.send(Ok(event)).await;
+                            continue;
+                        }
+                        Err(e) => {
+                            let _ = tx.send(Err(e)).await;
+                            return;
+                        }
+                    }
+                }
+
                 // Check for tool calls that need execution
                 let tool_calls: Vec<_> = response
                     .parts
@@ -700,17 +988,47 @@ impl AgentStream {
 
                         match tool {
                             Some(tool) => {
+                                let args = tc.args.to_json();
+                                let key = idempotency_key(&run_id_clone, &tc.tool_name, &args);
+                                let skip_repeated = tool.executor.skip_repeated_calls();
+
                                 // Create a RunContext for tool execution
-                                let tool_ctx =
+                                let mut tool_ctx =
                                     RunContext::with_shared_deps(deps.clone(), model_name.clone())
-                                        .for_tool(&tc.tool_name, tc.tool_call_id.clone());
-
-                                // Execute the tool
-                                let result =
-                                    tool.executor.execute(tc.args.to_json(), &tool_ctx).await;
+                                        .for_tool(&tc.tool_name, tc.tool_call_id.clone())
+                                        .with_retries(
+                                            tool_retries.get(&tc.tool_name).copied().unwrap_or(0),
+                                            tool.max_retries,
+                                        )
+                                        .with_idempotency_key(key.clone());
+                                tool_ctx.language = language.clone();
+
+                                // Reuse the result of a repeated call to a non-idempotent tool
+                                let result = match completed_calls
+                                    .get(&key)
+                                    .filter(|_| skip_repeated)
+                                {
+                                    Some(ret) => Ok(ret.clone()),
+                                    None => {
+                                        let started = Instant::now();
+                                        let result =
+                                            tool.executor.execute(args.clone(), &tool_ctx).await;
+                                        tool_stats.record(ToolCallRecord::from_result(
+                                            &tc.tool_name,
+                                            started.elapsed(),
+                                            args.to_string().len(),
+                                            &result,
+                                        ));
+                                        if let (true, Ok(ret)) = (skip_repeated, &result) {
+                                            completed_calls.insert(key, ret.clone());
+                                        }
+                                        result
+                                    }
+                                };
 
                                 match result {
                                     Ok(ret) => {
+                                        tool_retries.remove(&tc.tool_name);
                                         let _ = tx
                                             .send(Ok(AgentStreamEvent::ToolExecuted {
                                                 tool_name: tc.tool_name.clone(),
@@ -720,14 +1038,52 @@ impl AgentStream {
                                             }))
                                             .await;
 
+                                        let content = match tools
+                                            .iter()
+                                            .find(|t| t.definition.name == tc.tool_name)
+                                            .and_then(|t| t.return_limit.as_ref())
+                                        {
+                                            Some(limit) => {
+                                                limit.apply(&tc.tool_name, ret.content).await
+                                            }
+                                            None => ret.content,
+                                        };
                                         // Use ToolReturnPart for successful execution
-                                        let mut part =
-                                            ToolReturnPart::new(&tc.tool_name, ret.content);
+                                        let mut part = ToolReturnPart::new(&tc.tool_name, content);
                                         if let Some(id) = tc.tool_call_id.clone() {
                                             part = part.with_tool_call_id(id);
                                         }
                                         tool_req.parts.push(ModelRequestPart::ToolReturn(part));
                                     }
+                                    Err(ToolError::ModelRetry(message)) => {
+                                        let _ = tx
+                                            .send(Ok(AgentStreamEvent::ToolExecuted {
+                                                tool_name: tc.tool_name.clone(),
+                                                tool_call_id: tc.tool_call_id.clone(),
+                                                success: false,
+                                                error: Some(message.clone()),
+                                            }))
+                                            .await;
+
+                                        // Send the tool's guidance back for the model to retry
+                                        match tool_retry_prompt(
+                                            &mut tool_retries,
+                                            &tc.tool_name,
+                                            tc.tool_call_id.clone(),
+                                            message,
+                                            tool.max_retries,
+                                        ) {
+                                            Ok(part) => {
+                                                tool_req
+                                                    .parts
+                                                    .push(ModelRequestPart::RetryPrompt(part));
+                                            }
+                                            Err(e) => {
+                                                let _ = tx.send(Err(e)).await;
+                                                return;
+                                            }
+                                        }
+                                    }
                                     Err(e) => {
                                         let error_msg = e.to_string();
                                         let _ = tx
@@ -739,11 +1095,15 @@ impl AgentStream {
                                             }))
                                             .await;
 
+                                        let Some(content) =
+                                            tool_error_content(tool, &tc.tool_name, &e)
+                                        else {
+                                            let _ = tx.send(Err(AgentRunError::Tool(e))).await;
+                                            return;
+                                        };
                                         // Use ToolReturnPart with error content for tool errors
-                                        let mut part = ToolReturnPart::error(
-                                            &tc.tool_name,
-                                            format!("Tool error: {}", e),
-                                        );
+                                        let mut part =
+                                            ToolReturnPart::error(&tc.tool_name, content);
                                         if let Some(id) = tc.tool_call_id.clone() {
                                             part = part.with_tool_call_id(id);
                                         }
@@ -785,6 +1145,19 @@ impl AgentStream {
 
                 // No tool calls - check finish condition
                 if finish_reason == Some(FinishReason::Stop) {
+                    if !text_check.finish() {
+                        match text_check.retry(&response, &mut messages, false) {
+                            Ok(event) => {
+                                let _ = tx.send(Ok(event)).await;
+                                continue;
+                            }
+                            Err(e) => {
+                                let _ = tx.send(Err(e)).await;
+                                return;
+                            }
+                        }
+                    }
+
                     // Add final response to messages for complete history
                     let mut response_req = ModelRequest::new();
                     response_req
@@ -797,11 +1170,18 @@ impl AgentStream {
                 }
             }
 
+            if let (Some(routing), Some(category)) = (&tool_routing, &tool_usage_category) {
+                routing.record(category, &tool_stats.snapshot());
+            }
+
             // Emit RunComplete
             let _ = tx
                 .send(Ok(AgentStreamEvent::RunComplete {
                     run_id: run_id_clone,
-                    messages,
+                    messages: match &history_transform {
+                        Some(transform) => transform.transform(messages),
+                        None => messages,
+                    },
                 }))
                 .await;
         });
@@ -848,28 +1228,42 @@ impl AgentStream {
         Deps: Send + Sync + 'static,
         Output: Send + Sync + 'static,
     {
-        let run_id = generate_run_id();
+        let run_id = agent.new_run_id();
         let (tx, rx) = mpsc::channel(64);
 
         // Clone what we need for the spawned task
         let model = agent.model_arc();
         let model_name = model.name().to_string();
-        let model_settings = options
-            .model_settings
-            .clone()
-            .unwrap_or_else(|| agent.model_settings.clone());
+        let model_settings = resolve_run_settings(agent, &options, &deps);
+        let request_settings_fns = agent.request_settings_fns.clone();
+        let language = agent.detect_language(&prompt).await;
 
         let static_system_prompt = agent.static_system_prompt().to_string();
-        let tool_definitions = agent.tool_definitions();
+        let static_instructions = Some(agent.static_instructions())
+            .filter(|i| !i.is_empty())
+            .map(str::to_string);
+        let tool_usage_category = agent.tool_usage_category(&prompt);
+        let tool_definitions = agent
+            .run_tool_definitions(&prompt, tool_usage_category.as_deref())
+            .await;
+        let tool_routing = agent.tool_routing.clone();
+        let request_params = agent.request_params(Arc::clone(&tool_definitions));
         let _end_strategy = agent.end_strategy;
         let usage_limits = agent.usage_limits.clone();
         let run_usage_limits = options.usage_limits.clone();
+        let usage_meter = agent.usage_meter();
+        let audit = agent.audit.clone();
         let tools: Vec<RegisteredTool<Deps>> = agent.tools.to_vec();
+        let tool_stats = agent.new_tool_stats();
+        let json_repair = agent.json_repair.clone();
+        let history_transform = agent.history_transform.clone();
+        let mut text_check = StreamingTextCheck::new(agent);
         let deps = Arc::new(deps);
 
         let initial_history = options.message_history.clone();
         let _metadata = options.metadata.clone();
         let compression_config = options.compression.clone();
+        let assistant_prefill = options.assistant_prefill.clone();
         let run_id_clone = run_id.clone();
         let cancel_token_clone = cancel_token.clone();
 
@@ -896,6 +1290,8 @@ impl AgentStream {
 
             // Build initial messages
             let mut messages = initial_history.unwrap_or_default();
+            // Instructions of earlier runs are not part of the history
+            set_latest_instructions(&mut messages, None);
 
             if !static_system_prompt.is_empty() {
                 let mut req = ModelRequest::new();
@@ -909,6 +1305,8 @@ impl AgentStream {
 
             let mut responses: Vec<ModelResponse> = Vec::new();
             let mut usage = RunUsage::new();
+            let mut tool_retries: HashMap<String, u32> = HashMap::new();
+            let mut completed_calls: HashMap<String, ToolReturn> = HashMap::new();
             let mut step = 0u32;
             let mut finished = false;
             let mut finish_reason: Option<FinishReason>;
@@ -962,9 +1360,7 @@ impl AgentStream {
                     return;
                 }
 
-                let params = ModelRequestParameters::new()
-                    .with_tools_arc(tool_definitions.clone())
-                    .with_allow_text(true);
+                let params = request_params.clone();
 
                 // Context size calculation (simplified - full version in main new())
                 let (request_bytes, estimated_tokens) = {
@@ -999,9 +1395,37 @@ impl AgentStream {
                 }
 
                 // Make streaming request with cancellation support
-                let stream_result = model
-                    .request_stream(&messages, &model_settings, &params)
-                    .await;
+                let settings = {
+                    let mut ctx = RunContext::with_shared_deps(deps.clone(), model_name.clone());
+                    ctx.model_settings = model_settings.clone();
+                    ctx.language = language.clone();
+                    request_settings(&ctx, &request_settings_fns)
+                };
+                set_latest_instructions(&mut messages, static_instructions.clone());
+                if let Err(e) = check_preflight(
+                    model.as_ref(),
+                    &messages,
+                    &params,
+                    [usage_limits.as_ref(), run_usage_limits.as_ref()],
+                    &usage,
+                )
+                .await
+                {
+                    let _ = tx.send(Err(e.into())).await;
+                    return;
+                }
+                if let Some(Err(e)) = usage_meter.as_ref().map(|m| m.check()) {
+                    let _ = tx.send(Err(e.into())).await;
+                    return;
+                }
+                if let Some(prefill) = &assistant_prefill {
+                    messages.push(prefill_request(prefill));
+                }
+                let stream_result = model.request_stream(&messages, &settings, &params).await;
+                if assistant_prefill.is_some() {
+                    messages.pop();
+                }
+                set_latest_instructions(&mut messages, None);
 
                 let mut model_stream = match stream_result {
                     Ok(s) => s,
@@ -1011,12 +1435,22 @@ impl AgentStream {
                                 message: e.to_string(),
                             }))
                             .await;
+                        if let Some(audit) = &audit {
+                            let status = AuditStatus::Error {
+                                message: e.to_string(),
+                            };
+                            audit
+                                .model_request(&deps, &run_id_clone, model.name(), None, status)
+                                .await;
+                        }
                         let _ = tx.send(Err(AgentRunError::Model(e))).await;
                         return;
                     }
                 };
 
-                let mut response_parts: Vec<ModelResponsePart> = Vec::new();
+                let mut parts_manager = ModelResponsePartsManager::new();
+                text_check.start_response();
+                let mut pending_prefill = assistant_prefill.as_deref();
 
                 // Process stream events with cancellation check
                 loop {
@@ -1047,20 +1481,30 @@ impl AgentStream {
                         event_result = model_stream.next() => {
                             match event_result {
                                 Some(Ok(event)) => {
+                                    parts_manager.handle_event(&event);
                                     match event {
                                         ModelResponseStreamEvent::PartStart(start) => {
                                             match &start.part {
                                                 ModelResponsePart::Text(t) => {
                                                     if !t.content.is_empty() {
-                                                        accumulated_text.push_str(&t.content);
+                                                        let text = with_prefill(
+                                                            &mut pending_prefill,
+                                                            &t.content,
+                                                        );
+                                                        accumulated_text.push_str(&text);
                                                         let _ = tx
                                                             .send(Ok(AgentStreamEvent::TextDelta {
-                                                                text: t.content.clone(),
+                                                                text: text.clone(),
                                                             }))
                                                             .await;
+                                                        if !text_check.push(&text) {
+                                                            // Dropping the stream aborts generation
+                                                            break;
+                                                        }
                                                     }
                                                 }
                                                 ModelResponsePart::ToolCall(tc) => {
+                                                    text_check.tool_call_started();
                                                     pending_tool_names.push(tc.tool_name.clone());
                                                     let _ = tx
                                                         .send(Ok(AgentStreamEvent::ToolCallStart {
@@ -1079,6 +1523,13 @@ impl AgentStream {
                                                         }
                                                     }
                                                 }
+                                                ModelResponsePart::File(file) => {
+                                                    let _ = tx
+                                                        .send(Ok(AgentStreamEvent::FileReceived {
+                                                            file: file.clone(),
+                                                        }))
+                                                        .await;
+                                                }
                                                 ModelResponsePart::Thinking(t) => {
                                                     if !t.content.is_empty() {
                                                         accumulated_thinking.push_str(&t.content);
@@ -1091,28 +1542,29 @@ impl AgentStream {
                                                 }
                                                 _ => {}
                                             }
-                                            response_parts.push(start.part.clone());
                                         }
                                         ModelResponseStreamEvent::PartDelta(delta) => {
                                             use serdes_ai_core::messages::ModelResponsePartDelta;
                                             match &delta.delta {
                                                 ModelResponsePartDelta::Text(t) => {
-                                                    accumulated_text.push_str(&t.content_delta);
+                                                    let text = with_prefill(
+                                                        &mut pending_prefill,
+                                                        &t.content_delta,
+                                                    );
+                                                    accumulated_text.push_str(&text);
                                                     let _ = tx
                                                         .send(Ok(AgentStreamEvent::TextDelta {
-                                                            text: t.content_delta.clone(),
+                                                            text: text.clone(),
                                                         }))
                                                         .await;
-                                                    if let Some(ModelResponsePart::Text(ref mut text)) =
-                                                        response_parts.get_mut(delta.index)
-                                                    {
-                                                        text.content.push_str(&t.content_delta);
+                                                    if !text_check.push(&text) {
+                                                        break;
                                                     }
                                                 }
                                                 ModelResponsePartDelta::ToolCall(tc) => {
                                                     let tool_call_id =
-                                                        response_parts.get(delta.index).and_then(|p| {
-                                                            if let ModelResponsePart::ToolCall(tc) = p {
+                                                        parts_manager.event_part(delta.index).and_then(|p| {
+                                                            if let ManagedPart::ToolCall(tc) = p {
                                                                 tc.tool_call_id.clone()
                                                             } else {
                                                                 None
@@ -1124,12 +1576,6 @@ impl AgentStream {
                                                             tool_call_id,
                                                         }))
                                                         .await;
-                                                    if let Some(ModelResponsePart::ToolCall(
-                                                        ref mut tool_call,
-                                                    )) = response_parts.get_mut(delta.index)
-                                                    {
-                                                        tc.apply(tool_call);
-                                                    }
                                                 }
                                                 ModelResponsePartDelta::Thinking(t) => {
                                                     accumulated_thinking.push_str(&t.content_delta);
@@ -1138,12 +1584,6 @@ impl AgentStream {
                                                             text: t.content_delta.clone(),
                                                         }))
                                                         .await;
-                                                    if let Some(ModelResponsePart::Thinking(
-                                                        ref mut think,
-                                                    )) = response_parts.get_mut(delta.index)
-                                                    {
-                                                        t.apply(think);
-                                                    }
                                                 }
                                                 _ => {}
                                             }
@@ -1157,6 +1597,14 @@ impl AgentStream {
                                             message: e.to_string(),
                                         }))
                                         .await;
+                                    if let Some(audit) = &audit {
+                                        let status = AuditStatus::Error {
+                                            message: e.to_string(),
+                                        };
+                                        audit
+                                            .model_request(&deps, &run_id_clone, model.name(), None, status)
+                                            .await;
+                                    }
                                     let _ = tx.send(Err(AgentRunError::Model(e))).await;
                                     return;
                                 }
@@ -1171,16 +1619,28 @@ impl AgentStream {
 
                 // Build the complete response
                 let mut response = ModelResponse {
-                    parts: response_parts.clone(),
+                    parts: parts_manager.get_parts(),
                     model_name: Some(model.name().to_string()),
-                    timestamp: Utc::now(),
+                    timestamp: now_utc(),
                     finish_reason: Some(FinishReason::Stop),
                     usage: None,
                     vendor_id: None,
                     vendor_details: None,
+                    alternatives: Vec::new(),
                     kind: "response".to_string(),
                 };
-                canonicalize_tool_call_args_in_response(&mut response);
+                if let Some(audit) = &audit {
+                    let status = AuditStatus::Success;
+                    audit
+                        .model_request(&deps, &run_id_clone, model.name(), None, status)
+                        .await;
+                }
+                if let Some(prefill) = &assistant_prefill {
+                    apply_prefill(&mut response, prefill);
+                }
+                for event in canonicalize_tool_call_args_in_response(&mut response, &json_repair) {
+                    let _ = tx.send(Ok(event)).await;
+                }
 
                 finish_reason = response.finish_reason;
                 responses.push(response.clone());
@@ -1189,6 +1649,19 @@ impl AgentStream {
                     .send(Ok(AgentStreamEvent::ResponseComplete { step }))
                     .await;
 
+                if text_check.is_rejected() {
+                    match text_check.retry(&response, &mut messages, true) {
+                        Ok(event) => {
+                            let _ = tx.send(Ok(event)).await;
+                            continue;
+                        }
+                        Err(e) => {
+                            let _ = tx.send(Err(e)).await;
+                            return;
+                        }
+                    }
+                }
+
                 // Check for tool calls
                 let tool_calls: Vec<_> = response
                     .parts
@@ -1249,15 +1722,47 @@ impl AgentStream {
 
                         match tool {
                             Some(tool) => {
-                                let tool_ctx =
-                                    RunContext::with_shared_deps(deps.clone(), model_name.clone())
-                                        .for_tool(&tc.tool_name, tc.tool_call_id.clone());
+                                let args = tc.args.to_json();
+                                let key = idempotency_key(&run_id_clone, &tc.tool_name, &args);
+                                let skip_repeated = tool.executor.skip_repeated_calls();
 
-                                let result =
-                                    tool.executor.execute(tc.args.to_json(), &tool_ctx).await;
+                                // Create a RunContext for tool execution
+                                let mut tool_ctx =
+                                    RunContext::with_shared_deps(deps.clone(), model_name.clone())
+                                        .for_tool(&tc.tool_name, tc.tool_call_id.clone())
+                                        .with_retries(
+                                            tool_retries.get(&tc.tool_name).copied().unwrap_or(0),
+                                            tool.max_retries,
+                                        )
+                                        .with_idempotency_key(key.clone());
+                                tool_ctx.language = language.clone();
+
+                                // Reuse the result of a repeated call to a non-idempotent tool
+                                let result = match completed_calls
+                                    .get(&key)
+                                    .filter(|_| skip_repeated)
+                                {
+                                    Some(ret) => Ok(ret.clone()),
+                                    None => {
+                                        let started = Instant::now();
+                                        let result =
+                                            tool.executor.execute(args.clone(), &tool_ctx).await;
+                                        tool_stats.record(ToolCallRecord::from_result(
+                                            &tc.tool_name,
+                                            started.elapsed(),
+                                            args.to_string().len(),
+                                            &result,
+                                        ));
+                                        if let (true, Ok(ret)) = (skip_repeated, &result) {
+                                            completed_calls.insert(key, ret.clone());
+                                        }
+                                        result
+                                    }
+                                };
 
                                 match result {
                                     Ok(ret) => {
+                                        tool_retries.remove(&tc.tool_name);
                                         let _ = tx
                                             .send(Ok(AgentStreamEvent::ToolExecuted {
                                                 tool_name: tc.tool_name.clone(),
@@ -1267,13 +1772,51 @@ impl AgentStream {
                                             }))
                                             .await;
 
-                                        let mut part =
-                                            ToolReturnPart::new(&tc.tool_name, ret.content);
+                                        let content = match tools
+                                            .iter()
+                                            .find(|t| t.definition.name == tc.tool_name)
+                                            .and_then(|t| t.return_limit.as_ref())
+                                        {
+                                            Some(limit) => {
+                                                limit.apply(&tc.tool_name, ret.content).await
+                                            }
+                                            None => ret.content,
+                                        };
+                                        let mut part = ToolReturnPart::new(&tc.tool_name, content);
                                         if let Some(id) = tc.tool_call_id.clone() {
                                             part = part.with_tool_call_id(id);
                                         }
                                         tool_req.parts.push(ModelRequestPart::ToolReturn(part));
                                     }
+                                    Err(ToolError::ModelRetry(message)) => {
+                                        let _ = tx
+                                            .send(Ok(AgentStreamEvent::ToolExecuted {
+                                                tool_name: tc.tool_name.clone(),
+                                                tool_call_id: tc.tool_call_id.clone(),
+                                                success: false,
+                                                error: Some(message.clone()),
+                                            }))
+                                            .await;
+
+                                        // Send the tool's guidance back for the model to retry
+                                        match tool_retry_prompt(
+                                            &mut tool_retries,
+                                            &tc.tool_name,
+                                            tc.tool_call_id.clone(),
+                                            message,
+                                            tool.max_retries,
+                                        ) {
+                                            Ok(part) => {
+                                                tool_req
+                                                    .parts
+                                                    .push(ModelRequestPart::RetryPrompt(part));
+                                            }
+                                            Err(e) => {
+                                                let _ = tx.send(Err(e)).await;
+                                                return;
+                                            }
+                                        }
+                                    }
                                     Err(e) => {
                                         let error_msg = e.to_string();
                                         let _ = tx
@@ -1285,10 +1828,14 @@ impl AgentStream {
                                             }))
                                             .await;
 
-                                        let mut part = ToolReturnPart::error(
-                                            &tc.tool_name,
-                                            format!("Tool error: {}", e),
-                                        );
+                                        let Some(content) =
+                                            tool_error_content(tool, &tc.tool_name, &e)
+                                        else {
+                                            let _ = tx.send(Err(AgentRunError::Tool(e))).await;
+                                            return;
+                                        };
+                                        let mut part =
+                                            ToolReturnPart::error(&tc.tool_name, content);
                                         if let Some(id) = tc.tool_call_id.clone() {
                                             part = part.with_tool_call_id(id);
                                         }
@@ -1327,6 +1874,19 @@ impl AgentStream {
                 }
 
                 if finish_reason == Some(FinishReason::Stop) {
+                    if !text_check.finish() {
+                        match text_check.retry(&response, &mut messages, false) {
+                            Ok(event) => {
+                                let _ = tx.send(Ok(event)).await;
+                                continue;
+                            }
+                            Err(e) => {
+                                let _ = tx.send(Err(e)).await;
+                                return;
+                            }
+                        }
+                    }
+
                     // Add final response to messages for complete history
                     let mut response_req = ModelRequest::new();
                     response_req
@@ -1339,10 +1899,17 @@ impl AgentStream {
                 }
             }
 
+            if let (Some(routing), Some(category)) = (&tool_routing, &tool_usage_category) {
+                routing.record(category, &tool_stats.snapshot());
+            }
+
             let _ = tx
                 .send(Ok(AgentStreamEvent::RunComplete {
                     run_id: run_id_clone,
-                    messages,
+                    messages: match &history_transform {
+                        Some(transform) => transform.transform(messages),
+                        None => messages,
+                    },
                 }))
                 .await;
         });
@@ -1385,6 +1952,43 @@ impl AgentStream {
     pub fn cancellation_token(&self) -> Option<&CancellationToken> {
         self.cancel_token.as_ref()
     }
+
+    /// Drive the run to completion, writing its output to a sink.
+    ///
+    /// Text deltas and generated files are written as they arrive instead of
+    /// being collected, and the sink is finished once the run completes. Text
+    /// of every response is written, including responses that call tools or
+    /// that an output validator later rejects.
+    pub async fn pipe_to<S: StreamSink + ?Sized>(
+        mut self,
+        sink: &mut S,
+    ) -> Result<PipedRun, AgentRunError> {
+        let mut text_bytes = 0u64;
+        let mut files = 0usize;
+        while let Some(event) = self.next().await {
+            match event? {
+                AgentStreamEvent::TextDelta { text } => {
+                    sink.write_text(&text).await.map_err(AgentRunError::Sink)?;
+                    text_bytes += text.len() as u64;
+                }
+                AgentStreamEvent::FileReceived { file } => {
+                    sink.write_file(&file).await.map_err(AgentRunError::Sink)?;
+                    files += 1;
+                }
+                AgentStreamEvent::RunComplete { run_id, messages } => {
+                    sink.finish().await.map_err(AgentRunError::Sink)?;
+                    return Ok(PipedRun {
+                        run_id,
+                        text_bytes,
+                        files,
+                        messages,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Err(AgentRunError::UnexpectedStop)
+    }
 }
 
 impl Stream for AgentStream {
@@ -1400,7 +2004,7 @@ mod tests {
     use super::*;
     use crate::builder::agent;
     use futures::{stream, StreamExt};
-    use serdes_ai_core::messages::{ModelRequestPart, TextPart, ToolCallPart};
+    use serdes_ai_core::messages::{JsonRepairPolicy, ModelRequestPart, TextPart, ToolCallPart};
     use serdes_ai_models::FunctionModel;
     use std::sync::{
         atomic::{AtomicUsize, Ordering},
@@ -1492,7 +2096,7 @@ mod tests {
             .with_tool_call_id("call_1"),
         ));
 
-        canonicalize_tool_call_args_in_response(&mut response);
+        canonicalize_tool_call_args_in_response(&mut response, &JsonRepairer::default());
 
         match &response.parts[0] {
             ModelResponsePart::ToolCall(tc) => {
@@ -1502,6 +2106,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_canonicalize_tool_call_args_reports_repairs() {
+        let mut response = ModelResponse::new();
+        response.add_part(ModelResponsePart::ToolCall(
+            ToolCallPart::new("demo_tool", ToolCallArgs::string("{foo: 1,}"))
+                .with_tool_call_id("call_1"),
+        ));
+        response.add_part(ModelResponsePart::ToolCall(ToolCallPart::new(
+            "other",
+            ToolCallArgs::string("{\"a\": 1}"),
+        )));
+
+        let events = canonicalize_tool_call_args_in_response(
+            &mut response.clone(),
+            &JsonRepairer::default(),
+        );
+        match &events[..] {
+            [AgentStreamEvent::ToolArgsRepaired {
+                tool_name,
+                tool_call_id,
+                passes,
+            }] => {
+                assert_eq!(tool_name, "demo_tool");
+                assert_eq!(tool_call_id.as_deref(), Some("call_1"));
+                assert_eq!(passes, &["trailing_commas", "unquoted_keys"]);
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+
+        let off = JsonRepairer::new(JsonRepairPolicy::Off);
+        let events = canonicalize_tool_call_args_in_response(&mut response, &off);
+        assert!(events.is_empty());
+        match &response.parts[0] {
+            ModelResponsePart::ToolCall(tc) => {
+                assert_eq!(tc.args.to_json()["_error"], "parse_failed");
+            }
+            _ => panic!("expected tool call part"),
+        }
+    }
+
     #[tokio::test]
     async fn test_run_complete_messages_persist_canonical_tool_call_args() {
         let call_count = Arc::new(AtomicUsize::new(0));
@@ -1579,4 +2223,250 @@ mod tests {
             "expected at least one tool call in persisted RunComplete messages"
         );
     }
+
+    #[tokio::test]
+    async fn test_unavailable_tool_fails_stream() {
+        let model = FunctionModel::with_stream(|_messages, _settings| {
+            Box::pin(stream::iter(vec![
+                Ok(ModelResponseStreamEvent::part_start(
+                    0,
+                    ModelResponsePart::ToolCall(ToolCallPart::new(
+                        "remote",
+                        ToolCallArgs::Json(serde_json::json!({})),
+                    )),
+                )),
+                Ok(ModelResponseStreamEvent::part_end(0)),
+            ]))
+        });
+        let agent = agent(model)
+            .max_tool_retries(0)
+            .tool_fn("remote", "Remote tool", |_ctx, _args: serde_json::Value| {
+                Err(ToolError::retryable("connection refused"))
+            })
+            .tool_failure_policy("remote", ToolFailurePolicy::Fail)
+            .build();
+
+        let mut stream = agent.run_stream("call it", ()).await.unwrap();
+        let mut error = None;
+        while let Some(event) = stream.next().await {
+            if let Err(e) = event {
+                error = Some(e);
+                break;
+            }
+        }
+        assert!(matches!(error, Some(AgentRunError::Tool(_))));
+    }
+
+    fn text_stream(chunks: &[&str]) -> serdes_ai_models::StreamedResponse {
+        let mut events = vec![Ok(ModelResponseStreamEvent::part_start(
+            0,
+            ModelResponsePart::Text(TextPart::new(chunks[0])),
+        ))];
+        events.extend(
+            chunks[1..]
+                .iter()
+                .map(|c| Ok(ModelResponseStreamEvent::text_delta(0, *c))),
+        );
+        events.push(Ok(ModelResponseStreamEvent::part_end(0)));
+        Box::pin(stream::iter(events))
+    }
+
+    #[tokio::test]
+    async fn test_overlong_text_output_aborts_and_retries() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let model = {
+            let call_count = Arc::clone(&call_count);
+            FunctionModel::with_stream(move |_messages, _settings| {
+                if call_count.fetch_add(1, Ordering::SeqCst) == 0 {
+                    text_stream(&["way ", "too ", "long ", "for ", "the ", "limit"])
+                } else {
+                    text_stream(&["short"])
+                }
+            })
+        };
+
+        let agent = agent(model)
+            .output_validator(crate::output::LengthValidator::new().max(8))
+            .build();
+
+        let mut stream = agent.run_stream("hi", ()).await.unwrap();
+        let mut rejected = Vec::new();
+        let mut messages = None;
+        while let Some(event) = stream.next().await {
+            match event.unwrap() {
+                AgentStreamEvent::OutputRejected { message, aborted } => {
+                    rejected.push((message, aborted))
+                }
+                AgentStreamEvent::RunComplete { messages: m, .. } => messages = Some(m),
+                _ => {}
+            }
+        }
+
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected[0].0.contains("too long"));
+        assert!(rejected[0].1);
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+
+        let messages = messages.unwrap();
+        let texts: Vec<_> = messages
+            .iter()
+            .flat_map(|r| &r.parts)
+            .filter_map(|p| match p {
+                ModelRequestPart::ModelResponse(r) => Some(r.text_content()),
+                _ => None,
+            })
+            .collect();
+        // Generation stopped at the first delta over the limit
+        assert_eq!(texts, ["way too long ", "short"]);
+        assert!(messages
+            .iter()
+            .flat_map(|r| &r.parts)
+            .any(|p| matches!(p, ModelRequestPart::RetryPrompt(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assistant_prefill_streamed() {
+        let model = FunctionModel::with_stream(|messages, _settings| {
+            assert!(matches!(
+                messages.last().unwrap().parts.as_slice(),
+                [ModelRequestPart::ModelResponse(r)] if r.text_content() == "{"
+            ));
+            text_stream(&["\"a\"", ": 1}"])
+        });
+        let agent = agent(model).build();
+
+        let options = RunOptions::new().assistant_prefill("{");
+        let mut stream = agent
+            .run_stream_with_options("hi", (), options)
+            .await
+            .unwrap();
+        let mut text = String::new();
+        let mut messages = None;
+        while let Some(event) = stream.next().await {
+            match event.unwrap() {
+                AgentStreamEvent::TextDelta { text: delta } => text.push_str(&delta),
+                AgentStreamEvent::RunComplete { messages: m, .. } => messages = Some(m),
+                _ => {}
+            }
+        }
+
+        assert_eq!(text, "{\"a\": 1}");
+        let responses: Vec<_> = messages
+            .unwrap()
+            .iter()
+            .flat_map(|r| &r.parts)
+            .filter_map(|p| match p {
+                ModelRequestPart::ModelResponse(r) => Some(r.text_content()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(responses, ["{\"a\": 1}"]);
+    }
+
+    #[derive(Default)]
+    struct RecordingSink {
+        text: String,
+        files: Vec<String>,
+        finished: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl StreamSink for RecordingSink {
+        async fn write_text(&mut self, text: &str) -> std::io::Result<()> {
+            self.text.push_str(text);
+            Ok(())
+        }
+
+        async fn write_file(&mut self, file: &FilePart) -> std::io::Result<()> {
+            self.files.push(file.content.media_type.clone());
+            Ok(())
+        }
+
+        async fn finish(&mut self) -> std::io::Result<()> {
+            self.finished = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipe_to_sink() {
+        let model = FunctionModel::with_stream(|_messages, _settings| {
+            let events = vec![
+                Ok(ModelResponseStreamEvent::part_start(
+                    0,
+                    ModelResponsePart::Text(TextPart::new("Chart: ")),
+                )),
+                Ok(ModelResponseStreamEvent::text_delta(0, "see below")),
+                Ok(ModelResponseStreamEvent::part_end(0)),
+                Ok(ModelResponseStreamEvent::part_start(
+                    1,
+                    ModelResponsePart::File(FilePart::from_bytes(vec![0; 4], "image/png")),
+                )),
+                Ok(ModelResponseStreamEvent::part_end(1)),
+            ];
+            Box::pin(stream::iter(events))
+        });
+        let agent = agent(model).build();
+
+        let mut sink = RecordingSink::default();
+        let piped = agent
+            .run_stream("Draw a chart", ())
+            .await
+            .unwrap()
+            .pipe_to(&mut sink)
+            .await
+            .unwrap();
+
+        assert_eq!(sink.text, "Chart: see below");
+        assert_eq!(sink.files, ["image/png"]);
+        assert!(sink.finished);
+        assert_eq!(piped.text_bytes, 16);
+        assert_eq!(piped.files, 1);
+        assert!(!piped.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pipe_to_sink_error() {
+        struct FailingSink;
+
+        #[async_trait::async_trait]
+        impl StreamSink for FailingSink {
+            async fn write_text(&mut self, _text: &str) -> std::io::Result<()> {
+                Err(std::io::Error::other("disk full"))
+            }
+        }
+
+        let model = FunctionModel::with_stream(|_messages, _settings| text_stream(&["hi"]));
+        let agent = agent(model).build();
+
+        let err = agent
+            .run_stream("hi", ())
+            .await
+            .unwrap()
+            .pipe_to(&mut FailingSink)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AgentRunError::Sink(_)));
+    }
+
+    #[tokio::test]
+    async fn test_final_text_check_fails_after_retries() {
+        let model = FunctionModel::with_stream(|_messages, _settings| text_stream(&["hi"]));
+        let agent = agent(model)
+            .output_validator(crate::output::LengthValidator::new().min(10))
+            .max_output_retries(0)
+            .build();
+
+        let mut stream = agent.run_stream("hi", ()).await.unwrap();
+        let mut error = None;
+        while let Some(event) = stream.next().await {
+            if let Err(e) = event {
+                error = Some(e);
+            }
+        }
+        assert!(matches!(
+            error,
+            Some(AgentRunError::OutputValidationFailed(_))
+        ));
+    }
 }
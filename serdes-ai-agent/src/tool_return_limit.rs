@@ -0,0 +1,272 @@
+//! Size limits for tool return content.
+//!
+//! A single tool call can return far more text than the context window holds.
+//! A [`ToolReturnLimit`] caps the size of a tool's return and picks what to do
+//! with oversized content:
+//!
+//! - [`OversizedReturn::Truncate`] keeps the beginning and appends a marker
+//! - [`OversizedReturn::Artifact`] stores the full content in an
+//!   [`ArtifactStore`] and passes the model its URI (or URL) plus a preview
+//! - [`OversizedReturn::Summarize`] asks a (cheap) model for a summary
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, ToolReturnLimit};
+//!
+//! let agent = agent(model)
+//!     .tool_fn("fetch_page", "Fetch a web page", fetch_page)
+//!     .tool_return_limit("fetch_page", ToolReturnLimit::summarize(4_000, cheap_model))
+//!     .build();
+//! ```
+
+use serdes_ai_core::artifacts::ArtifactStore;
+use serdes_ai_core::messages::{BinaryContent, ToolReturnContent};
+use serdes_ai_core::{ModelRequest, ModelSettings};
+use serdes_ai_models::{Model, ModelRequestParameters};
+use std::fmt;
+use std::sync::Arc;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// What to do with tool output over the limit.
+#[derive(Clone)]
+pub enum OversizedReturn {
+    /// Keep the beginning and append a truncation marker.
+    Truncate,
+    /// Store the full output and send a reference with a preview.
+    ///
+    /// Falls back to truncation if the store fails.
+    Artifact(Arc<dyn ArtifactStore>),
+    /// Summarize the output with a model.
+    ///
+    /// Falls back to truncation if the model request fails.
+    Summarize(Arc<dyn Model>),
+}
+
+impl fmt::Debug for OversizedReturn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncate => f.write_str("Truncate"),
+            Self::Artifact(_) => f.write_str("Artifact"),
+            Self::Summarize(model) => f.debug_tuple("Summarize").field(&model.name()).finish(),
+        }
+    }
+}
+
+/// Maximum size of a tool's return content.
+#[derive(Debug, Clone)]
+pub struct ToolReturnLimit {
+    max_chars: usize,
+    strategy: OversizedReturn,
+}
+
+impl ToolReturnLimit {
+    /// Truncate returns longer than `max_chars` characters.
+    #[must_use]
+    pub fn truncate(max_chars: usize) -> Self {
+        Self {
+            max_chars,
+            strategy: OversizedReturn::Truncate,
+        }
+    }
+
+    /// Store returns longer than `max_chars` characters in an artifact store.
+    #[must_use]
+    pub fn artifact(max_chars: usize, store: Arc<dyn ArtifactStore>) -> Self {
+        Self {
+            max_chars,
+            strategy: OversizedReturn::Artifact(store),
+        }
+    }
+
+    /// Summarize returns longer than `max_chars` characters with a model.
+    #[must_use]
+    pub fn summarize(max_chars: usize, model: Arc<dyn Model>) -> Self {
+        Self {
+            max_chars,
+            strategy: OversizedReturn::Summarize(model),
+        }
+    }
+
+    /// Get the size limit in characters.
+    #[must_use]
+    pub fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    /// Get the strategy for oversized returns.
+    #[must_use]
+    pub fn strategy(&self) -> &OversizedReturn {
+        &self.strategy
+    }
+
+    /// Apply the limit to a tool's return content.
+    ///
+    /// Content within the limit, images and errors are returned unchanged.
+    pub async fn apply(&self, tool_name: &str, content: ToolReturnContent) -> ToolReturnContent {
+        if matches!(
+            content,
+            ToolReturnContent::Image { .. } | ToolReturnContent::Error { .. }
+        ) {
+            return content;
+        }
+        let text = content.to_string_content();
+        let size = text.chars().count();
+        if size <= self.max_chars {
+            return content;
+        }
+
+        match &self.strategy {
+            OversizedReturn::Truncate => ToolReturnContent::text(truncate(&text, self.max_chars)),
+            OversizedReturn::Artifact(store) => {
+                let preview = truncate(&text, self.max_chars);
+                let content = BinaryContent::new(text.into_bytes(), "text/plain; charset=utf-8");
+                match store.put(content) {
+                    Ok(artifact) => ToolReturnContent::text(format!(
+                        "[Output of {} characters stored as {}]\n{}",
+                        size,
+                        artifact.url.clone().unwrap_or_else(|| artifact.uri()),
+                        preview
+                    )),
+                    Err(_e) => {
+                        warn!(
+                            "Failed to store output of tool {}, truncating: {}",
+                            tool_name, _e
+                        );
+                        ToolReturnContent::text(preview)
+                    }
+                }
+            }
+            OversizedReturn::Summarize(model) => {
+                match summarize(model.as_ref(), tool_name, &text, self.max_chars).await {
+                    Some(summary) => ToolReturnContent::text(format!(
+                        "[Summary of {} characters of output]\n{}",
+                        size,
+                        truncate(&summary, self.max_chars)
+                    )),
+                    None => ToolReturnContent::text(truncate(&text, self.max_chars)),
+                }
+            }
+        }
+    }
+}
+
+/// Keep the first `max_chars` characters and mark how much was dropped.
+fn truncate(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n[... truncated {} characters]", kept, total - max_chars)
+}
+
+async fn summarize(
+    model: &dyn Model,
+    tool_name: &str,
+    text: &str,
+    max_chars: usize,
+) -> Option<String> {
+    let mut request = ModelRequest::new();
+    request.add_user_prompt(format!(
+        "Summarize the following output of the tool `{}` in at most {} characters. \
+         Keep names, numbers and identifiers that may be needed later.\n\n{}",
+        tool_name, max_chars, text
+    ));
+
+    match model
+        .request(
+            &[request],
+            &ModelSettings::default(),
+            &ModelRequestParameters::new(),
+        )
+        .await
+    {
+        Ok(response) => Some(response.text_content()).filter(|s| !s.is_empty()),
+        Err(_e) => {
+            warn!(
+                "Failed to summarize output of tool {}, truncating: {}",
+                tool_name, _e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::artifacts::{content_hash, InMemoryArtifactStore};
+    use serdes_ai_core::ModelResponse;
+    use serdes_ai_models::FunctionModel;
+
+    fn long_text() -> ToolReturnContent {
+        ToolReturnContent::text("x".repeat(100))
+    }
+
+    #[tokio::test]
+    async fn test_within_limit_unchanged() {
+        let limit = ToolReturnLimit::truncate(100);
+        let content = limit.apply("t", long_text()).await;
+        assert_eq!(content, long_text());
+    }
+
+    #[tokio::test]
+    async fn test_truncate() {
+        let limit = ToolReturnLimit::truncate(10);
+        let content = limit.apply("t", long_text()).await;
+        assert_eq!(
+            content.as_text(),
+            Some("xxxxxxxxxx\n[... truncated 90 characters]")
+        );
+
+        let error = ToolReturnContent::error("e".repeat(100));
+        assert_eq!(limit.apply("t", error.clone()).await, error);
+    }
+
+    #[tokio::test]
+    async fn test_artifact() {
+        let store = Arc::new(InMemoryArtifactStore::new());
+        let limit = ToolReturnLimit::artifact(10, store.clone());
+        let content = limit.apply("fetch", long_text()).await;
+
+        let hash = content_hash("x".repeat(100).as_bytes());
+        let text = content.as_text().unwrap();
+        assert!(text.starts_with(&format!(
+            "[Output of 100 characters stored as artifact://{hash}]"
+        )));
+        let stored = store.get(&hash).unwrap().unwrap();
+        assert_eq!(stored.data, "x".repeat(100).into_bytes());
+        assert_eq!(stored.media_type, "text/plain; charset=utf-8");
+    }
+
+    #[tokio::test]
+    async fn test_summarize() {
+        let model = FunctionModel::new(|messages, _| {
+            let prompt = messages[0]
+                .user_prompts()
+                .next()
+                .unwrap()
+                .content
+                .as_text()
+                .unwrap();
+            assert!(prompt.contains("`fetch`"));
+            ModelResponse::text("a page of x")
+        });
+        let limit = ToolReturnLimit::summarize(10, Arc::new(model));
+        let content = limit.apply("fetch", long_text()).await;
+
+        assert_eq!(
+            content.as_text(),
+            Some("[Summary of 100 characters of output]\na page of \n[... truncated 1 characters]")
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Relevance-based tool selection.
+//!
+//! Every registered tool definition is sent with every request, so agents
+//! with many tools pay for all of them in prompt tokens. A [`ToolSelection`]
+//! ranks the tools by relevance to the run's prompt and only sends the best
+//! ones, up to a count and an optional token budget. Tools on the allowlist
+//! are always sent.
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, ToolSelection};
+//!
+//! let agent = agent(model)
+//!     .tools(many_tools)
+//!     .tool_selection(
+//!         ToolSelection::top_k(8)
+//!             .with_token_budget(2_000)
+//!             .always_include(["ask_user"]),
+//!     )
+//!     .build();
+//! ```
+//!
+//! The default [`KeywordRanker`] scores tools by words shared with the
+//! prompt; implement [`ToolRanker`] to rank by embedding similarity instead.
+
+use async_trait::async_trait;
+use serdes_ai_tools::ToolDefinition;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Scores tools by relevance to a query.
+#[async_trait]
+pub trait ToolRanker: Send + Sync {
+    /// Score each tool, in order; higher is more relevant.
+    async fn score(&self, query: &str, tools: &[ToolDefinition]) -> Vec<f64>;
+}
+
+/// Ranks tools by the words their name and description share with the query.
+///
+/// Matches in the tool name count double.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeywordRanker;
+
+impl KeywordRanker {
+    /// Create a keyword ranker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn score_tool(query: &HashSet<String>, tool: &ToolDefinition) -> f64 {
+        let name = words(&tool.name);
+        let description = words(&tool.description);
+        query
+            .iter()
+            .map(|word| {
+                if name.contains(word) {
+                    2.0
+                } else if description.contains(word) {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .sum()
+    }
+}
+
+#[async_trait]
+impl ToolRanker for KeywordRanker {
+    async fn score(&self, query: &str, tools: &[ToolDefinition]) -> Vec<f64> {
+        let query = words(query);
+        tools
+            .iter()
+            .map(|tool| Self::score_tool(&query, tool))
+            .collect()
+    }
+}
+
+/// Common words that say nothing about a tool.
+const STOPWORDS: &[&str] = &[
+    "and", "are", "but", "can", "for", "from", "has", "have", "how", "into", "not", "the", "this",
+    "that", "what", "when", "where", "which", "who", "why", "will", "with", "you", "your",
+];
+
+/// Lowercase words of at least three characters, splitting identifiers such
+/// as `get_weather` and `getWeather` and skipping stopwords.
+fn words(text: &str) -> HashSet<String> {
+    let mut spaced = String::with_capacity(text.len());
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if c.is_uppercase() && prev_lower {
+            spaced.push(' ');
+        }
+        prev_lower = c.is_lowercase();
+        spaced.push(c);
+    }
+    spaced
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Sends only the tools most relevant to a run's prompt.
+#[derive(Clone)]
+pub struct ToolSelection {
+    top_k: usize,
+    token_budget: Option<usize>,
+    always_include: HashSet<String>,
+    ranker: Arc<dyn ToolRanker>,
+}
+
+impl ToolSelection {
+    /// Send at most `k` tools besides the allowlisted ones.
+    #[must_use]
+    pub fn top_k(k: usize) -> Self {
+        Self {
+            top_k: k,
+            token_budget: None,
+            always_include: HashSet::new(),
+            ranker: Arc::new(KeywordRanker),
+        }
+    }
+
+    /// Stop adding ranked tools once their definitions would exceed this
+    /// many (estimated) tokens.
+    ///
+    /// Allowlisted tools count towards the budget but are never dropped.
+    #[must_use]
+    pub fn with_token_budget(mut self, tokens: usize) -> Self {
+        self.token_budget = Some(tokens);
+        self
+    }
+
+    /// Always send these tools.
+    #[must_use]
+    pub fn always_include(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.always_include
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the ranker.
+    #[must_use]
+    pub fn with_ranker(mut self, ranker: impl ToolRanker + 'static) -> Self {
+        self.ranker = Arc::new(ranker);
+        self
+    }
+
+    /// Select the tools to send for `query`, in their original order.
+    pub async fn select(&self, query: &str, tools: &[ToolDefinition]) -> Vec<ToolDefinition> {
+        let scores = self.ranker.score(query, tools).await;
+        let mut keep = vec![false; tools.len()];
+        let mut tokens = 0;
+        for (i, tool) in tools.iter().enumerate() {
+            if self.always_include.contains(&tool.name) {
+                keep[i] = true;
+                tokens += estimated_tokens(tool);
+            }
+        }
+
+        let mut ranked: Vec<usize> = (0..tools.len()).filter(|&i| !keep[i]).collect();
+        // Stable, so ties keep registration order
+        ranked.sort_by(|&a, &b| {
+            let score = |i: usize| scores.get(i).copied().unwrap_or(0.0);
+            score(b).total_cmp(&score(a))
+        });
+        for i in ranked.into_iter().take(self.top_k) {
+            let cost = estimated_tokens(&tools[i]);
+            if self
+                .token_budget
+                .is_some_and(|budget| tokens + cost > budget)
+            {
+                continue;
+            }
+            keep[i] = true;
+            tokens += cost;
+        }
+
+        tools
+            .iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|(tool, _)| tool.clone())
+            .collect()
+    }
+}
+
+impl fmt::Debug for ToolSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolSelection")
+            .field("top_k", &self.top_k)
+            .field("token_budget", &self.token_budget)
+            .field("always_include", &self.always_include)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Estimate the tokens of a tool definition (~bytes / 4).
+fn estimated_tokens(tool: &ToolDefinition) -> usize {
+    serde_json::to_vec(tool).map_or(0, |json| json.len() / 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools() -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition::new("ask_user", "Ask the user a question"),
+            ToolDefinition::new("get_weather", "Get the current weather forecast for a city"),
+            ToolDefinition::new("searchFlights", "Search flights between two airports"),
+            ToolDefinition::new("send_email", "Send an email"),
+        ]
+    }
+
+    fn names(tools: &[ToolDefinition]) -> Vec<&str> {
+        tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let words = words("getWeather for the new_york office");
+        let mut words: Vec<_> = words.into_iter().collect();
+        words.sort();
+        assert_eq!(words, ["get", "new", "office", "weather", "york"]);
+    }
+
+    #[tokio::test]
+    async fn test_keyword_ranker() {
+        let scores = KeywordRanker
+            .score("What's the weather forecast in Paris?", &tools())
+            .await;
+        assert_eq!(scores[1], 3.0);
+        assert_eq!(scores[0], 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_select_top_k_with_allowlist() {
+        let selection = ToolSelection::top_k(1).always_include(["ask_user"]);
+        let selected = selection
+            .select("Find flights to Paris and search for hotels", &tools())
+            .await;
+        assert_eq!(names(&selected), ["ask_user", "searchFlights"]);
+    }
+
+    #[tokio::test]
+    async fn test_select_token_budget() {
+        let tools = tools();
+        let budget = estimated_tokens(&tools[1]);
+        let selected = ToolSelection::top_k(4)
+            .with_token_budget(budget)
+            .select("weather", &tools)
+            .await;
+        assert_eq!(names(&selected), ["get_weather"]);
+    }
+}
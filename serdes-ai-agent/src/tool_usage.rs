@@ -0,0 +1,367 @@
+//! Usage-driven tool routing.
+//!
+//! Agents often register tools that are rarely, if ever, called for a given
+//! kind of conversation. A [`ToolRouting`] records how often each tool is
+//! called per conversation category in a [`ToolUsageStore`], and on later
+//! runs sends the most used tools first. Once a category has enough history,
+//! tools that were never called can be pruned altogether.
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{agent, FileToolUsageStore, ToolRouting};
+//! use std::sync::Arc;
+//!
+//! let store = Arc::new(FileToolUsageStore::open("tool_usage.json")?);
+//! let agent = agent(model)
+//!     .tools(many_tools)
+//!     .tool_routing(
+//!         ToolRouting::new(store)
+//!             .with_classifier(|prompt| {
+//!                 if prompt.contains("invoice") { "billing" } else { "support" }.to_string()
+//!             })
+//!             .prune_unused_after(50)
+//!             .always_include(["ask_user"]),
+//!     )
+//!     .build();
+//! ```
+//!
+//! Routing is applied before any [`ToolSelection`](crate::ToolSelection).
+
+use serde::{Deserialize, Serialize};
+use serdes_ai_tools::{ToolCallStats, ToolDefinition};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Category used when no classifier is configured.
+pub const DEFAULT_TOOL_USAGE_CATEGORY: &str = "default";
+
+/// Tool usage recorded for one conversation category.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolUsage {
+    /// Number of recorded runs.
+    pub runs: u64,
+    /// Number of calls per tool name.
+    pub calls: HashMap<String, u64>,
+}
+
+impl ToolUsage {
+    /// Get the number of calls of a tool.
+    #[must_use]
+    pub fn calls(&self, tool_name: &str) -> u64 {
+        self.calls.get(tool_name).copied().unwrap_or(0)
+    }
+
+    fn record(&mut self, calls: &HashMap<String, u64>) {
+        self.runs += 1;
+        for (name, count) in calls {
+            *self.calls.entry(name.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Storage for tool usage counters across runs.
+pub trait ToolUsageStore: Send + Sync {
+    /// Load the usage recorded for a category.
+    fn load(&self, category: &str) -> ToolUsage;
+
+    /// Record one run's calls per tool name.
+    fn record(&self, category: &str, calls: &HashMap<String, u64>);
+}
+
+/// In-memory [`ToolUsageStore`].
+#[derive(Debug, Default)]
+pub struct InMemoryToolUsageStore {
+    usage: RwLock<HashMap<String, ToolUsage>>,
+}
+
+impl InMemoryToolUsageStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the usage of all categories.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<String, ToolUsage> {
+        self.usage.read().map(|u| u.clone()).unwrap_or_default()
+    }
+}
+
+impl ToolUsageStore for InMemoryToolUsageStore {
+    fn load(&self, category: &str) -> ToolUsage {
+        self.usage
+            .read()
+            .ok()
+            .and_then(|u| u.get(category).cloned())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, category: &str, calls: &HashMap<String, u64>) {
+        if let Ok(mut usage) = self.usage.write() {
+            usage.entry(category.to_string()).or_default().record(calls);
+        }
+    }
+}
+
+/// [`ToolUsageStore`] persisted as a JSON file.
+///
+/// The file is read once when opened and rewritten after every recorded run.
+#[derive(Debug)]
+pub struct FileToolUsageStore {
+    path: PathBuf,
+    usage: RwLock<HashMap<String, ToolUsage>>,
+}
+
+impl FileToolUsageStore {
+    /// Open a store, starting empty if the file does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let usage = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            path,
+            usage: RwLock::new(usage),
+        })
+    }
+
+    /// Get the file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&self, usage: &HashMap<String, ToolUsage>) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(usage)?;
+        // Write then rename, so a crash never leaves a truncated file
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+impl ToolUsageStore for FileToolUsageStore {
+    fn load(&self, category: &str) -> ToolUsage {
+        self.usage
+            .read()
+            .ok()
+            .and_then(|u| u.get(category).cloned())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, category: &str, calls: &HashMap<String, u64>) {
+        let Ok(mut usage) = self.usage.write() else {
+            return;
+        };
+        usage.entry(category.to_string()).or_default().record(calls);
+        if let Err(_e) = self.save(&usage) {
+            warn!(path = %self.path.display(), error = %_e, "Failed to save tool usage");
+        }
+    }
+}
+
+/// Function mapping a run's prompt to its conversation category.
+pub type CategoryFn = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Orders and prunes the tools sent to the model by historical usage.
+#[derive(Clone)]
+pub struct ToolRouting {
+    store: Arc<dyn ToolUsageStore>,
+    classifier: Option<CategoryFn>,
+    prune_after: Option<u64>,
+    always_include: HashSet<String>,
+}
+
+impl ToolRouting {
+    /// Route tools by the usage recorded in `store`.
+    #[must_use]
+    pub fn new(store: Arc<dyn ToolUsageStore>) -> Self {
+        Self {
+            store,
+            classifier: None,
+            prune_after: None,
+            always_include: HashSet::new(),
+        }
+    }
+
+    /// Categorize runs by their prompt.
+    ///
+    /// Without a classifier every run falls into
+    /// [`DEFAULT_TOOL_USAGE_CATEGORY`].
+    #[must_use]
+    pub fn with_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Drop tools that were never called once a category has at least
+    /// `runs` recorded runs.
+    #[must_use]
+    pub fn prune_unused_after(mut self, runs: u64) -> Self {
+        self.prune_after = Some(runs);
+        self
+    }
+
+    /// Never prune these tools.
+    #[must_use]
+    pub fn always_include(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.always_include
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Get the usage store.
+    #[must_use]
+    pub fn store(&self) -> &Arc<dyn ToolUsageStore> {
+        &self.store
+    }
+
+    /// Get the category of a prompt.
+    #[must_use]
+    pub fn category(&self, prompt: &str) -> String {
+        match &self.classifier {
+            Some(classifier) => classifier(prompt),
+            None => DEFAULT_TOOL_USAGE_CATEGORY.to_string(),
+        }
+    }
+
+    /// Order `tools` by their calls in `category`, most used first, and
+    /// prune unused ones if the category has enough history.
+    #[must_use]
+    pub fn route(&self, category: &str, tools: &[ToolDefinition]) -> Vec<ToolDefinition> {
+        let usage = self.store.load(category);
+        let prune = self.prune_after.is_some_and(|runs| usage.runs >= runs);
+
+        let mut routed: Vec<ToolDefinition> = tools
+            .iter()
+            .filter(|tool| {
+                !prune || usage.calls(&tool.name) > 0 || self.always_include.contains(&tool.name)
+            })
+            .cloned()
+            .collect();
+        // Stable, so ties keep registration order
+        routed.sort_by_key(|tool| std::cmp::Reverse(usage.calls(&tool.name)));
+        routed
+    }
+
+    /// Record the tool calls of a finished run in `category`.
+    pub fn record(&self, category: &str, stats: &HashMap<String, ToolCallStats>) {
+        let calls = stats
+            .iter()
+            .map(|(name, stats)| (name.clone(), stats.calls))
+            .collect();
+        self.store.record(category, &calls);
+    }
+}
+
+impl fmt::Debug for ToolRouting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRouting")
+            .field("prune_after", &self.prune_after)
+            .field("always_include", &self.always_include)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools() -> Vec<ToolDefinition> {
+        vec![
+            ToolDefinition::new("ask_user", "Ask the user a question"),
+            ToolDefinition::new("get_weather", "Get the weather"),
+            ToolDefinition::new("send_email", "Send an email"),
+        ]
+    }
+
+    fn names(tools: &[ToolDefinition]) -> Vec<&str> {
+        tools.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    fn calls(counts: &[(&str, u64)]) -> HashMap<String, u64> {
+        counts.iter().map(|(n, c)| (n.to_string(), *c)).collect()
+    }
+
+    #[test]
+    fn test_route_orders_by_usage() {
+        let store = Arc::new(InMemoryToolUsageStore::new());
+        store.record("travel", &calls(&[("get_weather", 3), ("send_email", 1)]));
+        let routing = ToolRouting::new(store);
+
+        assert_eq!(
+            names(&routing.route("travel", &tools())),
+            ["get_weather", "send_email", "ask_user"]
+        );
+        // Other categories are unaffected
+        assert_eq!(
+            names(&routing.route("billing", &tools())),
+            ["ask_user", "get_weather", "send_email"]
+        );
+    }
+
+    #[test]
+    fn test_route_prunes_unused() {
+        let store = Arc::new(InMemoryToolUsageStore::new());
+        let routing = ToolRouting::new(store)
+            .prune_unused_after(2)
+            .always_include(["ask_user"]);
+
+        routing
+            .store()
+            .record("default", &calls(&[("get_weather", 1)]));
+        assert_eq!(routing.route("default", &tools()).len(), 3);
+
+        routing.store().record("default", &HashMap::new());
+        assert_eq!(
+            names(&routing.route("default", &tools())),
+            ["get_weather", "ask_user"]
+        );
+    }
+
+    #[test]
+    fn test_category() {
+        let store = Arc::new(InMemoryToolUsageStore::new());
+        let routing = ToolRouting::new(store.clone());
+        assert_eq!(routing.category("hi"), DEFAULT_TOOL_USAGE_CATEGORY);
+
+        let routing = routing.with_classifier(|prompt| prompt.len().to_string());
+        assert_eq!(routing.category("hi"), "2");
+    }
+
+    #[test]
+    fn test_file_store_persists() {
+        let dir =
+            std::env::temp_dir().join(format!("serdes-ai-tool-usage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("usage.json");
+
+        let store = FileToolUsageStore::open(&path).unwrap();
+        store.record("default", &calls(&[("get_weather", 2)]));
+
+        let reopened = FileToolUsageStore::open(&path).unwrap();
+        let usage = reopened.load("default");
+        assert_eq!(usage.runs, 1);
+        assert_eq!(usage.calls("get_weather"), 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
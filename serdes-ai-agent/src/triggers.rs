@@ -0,0 +1,757 @@
+//! Inputs for long-lived agents.
+//!
+//! A [`Trigger`] produces [`TriggerEvent`]s for an
+//! [`AgentWorker`](crate::AgentWorker), which runs the agent once per event.
+//! Events go through a bounded queue: when the worker falls behind,
+//! [`TriggerSink::send`] waits for room, so triggers slow down instead of
+//! piling up work.
+//!
+//! Built-in triggers:
+//!
+//! - [`ChannelTrigger`] forwards events sent on a tokio channel
+//! - [`WebhookTrigger`] accepts `POST` requests on a local HTTP port and
+//!   answers `429 Too Many Requests` while the queue is full
+//! - [`FileWatchTrigger`] polls a directory for new and changed files
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{AgentWorker, ChannelTrigger, TriggerEvent, WebhookTrigger};
+//!
+//! let (tx, channel) = ChannelTrigger::channel(16);
+//! let webhook = WebhookTrigger::bind("127.0.0.1:8080")?.with_secret("s3cret");
+//!
+//! let worker = AgentWorker::new(agent, ())
+//!     .trigger(channel)
+//!     .trigger(webhook)
+//!     .max_concurrency(4);
+//! tokio::spawn(worker.run(cancel.clone()));
+//!
+//! tx.send(TriggerEvent::new("cli", "Triage the new tickets")).await?;
+//! ```
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::debug;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {};
+}
+
+/// An input for an agent run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerEvent {
+    /// Name of the trigger that produced the event.
+    pub source: String,
+    /// The prompt to run.
+    pub prompt: String,
+    /// Extra data passed on in the run's metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<JsonValue>,
+}
+
+impl TriggerEvent {
+    /// Create an event.
+    pub fn new(source: impl Into<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            prompt: prompt.into(),
+            metadata: None,
+        }
+    }
+
+    /// Attach metadata.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: JsonValue) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+/// The sending end of a worker's event queue.
+#[derive(Debug, Clone)]
+pub struct TriggerSink {
+    tx: mpsc::Sender<TriggerEvent>,
+}
+
+impl TriggerSink {
+    /// Create a queue holding up to `capacity` events.
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<TriggerEvent>) {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        (Self { tx }, rx)
+    }
+
+    /// Queue an event, waiting while the queue is full.
+    ///
+    /// Returns `false` once the worker has stopped.
+    pub async fn send(&self, event: TriggerEvent) -> bool {
+        self.tx.send(event).await.is_ok()
+    }
+
+    /// Queue an event if there is room.
+    pub fn try_send(
+        &self,
+        event: TriggerEvent,
+    ) -> Result<(), mpsc::error::TrySendError<TriggerEvent>> {
+        self.tx.try_send(event)
+    }
+
+    /// Number of events that can be queued without waiting.
+    pub fn capacity(&self) -> usize {
+        self.tx.capacity()
+    }
+
+    /// Check if the worker has stopped.
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+}
+
+/// A source of [`TriggerEvent`]s.
+#[async_trait]
+pub trait Trigger: Send + 'static {
+    /// Name of the trigger, used in logs and as the source of the events it
+    /// creates.
+    fn name(&self) -> &str;
+
+    /// Produce events into `sink` until `cancel` is triggered, the sink is
+    /// closed or the trigger runs out of events.
+    async fn run(self: Box<Self>, sink: TriggerSink, cancel: CancellationToken) -> io::Result<()>;
+}
+
+/// Trigger forwarding events sent on a channel.
+#[derive(Debug)]
+pub struct ChannelTrigger {
+    name: String,
+    rx: mpsc::Receiver<TriggerEvent>,
+}
+
+impl ChannelTrigger {
+    /// Forward events received on `rx`.
+    pub fn new(rx: mpsc::Receiver<TriggerEvent>) -> Self {
+        Self {
+            name: "channel".to_string(),
+            rx,
+        }
+    }
+
+    /// Create a channel holding up to `capacity` events and its trigger.
+    pub fn channel(capacity: usize) -> (mpsc::Sender<TriggerEvent>, Self) {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        (tx, Self::new(rx))
+    }
+
+    /// Set the trigger name.
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+}
+
+#[async_trait]
+impl Trigger for ChannelTrigger {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(
+        mut self: Box<Self>,
+        sink: TriggerSink,
+        cancel: CancellationToken,
+    ) -> io::Result<()> {
+        loop {
+            let event = tokio::select! {
+                () = cancel.cancelled() => return Ok(()),
+                event = self.rx.recv() => event,
+            };
+            let Some(event) = event else {
+                return Ok(());
+            };
+            if !sink.send(event).await {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Maximum size of a webhook request's headers.
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// Time allowed for reading a webhook request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Trigger accepting events over HTTP.
+///
+/// Every `POST` to the configured path (default `/`) queues one event. The
+/// body is either plain text, used as the prompt, or a JSON object with a
+/// `prompt` string and optional `metadata`. Responses:
+///
+/// - `202 Accepted` once the event is queued
+/// - `429 Too Many Requests` while the queue is full
+/// - `401 Unauthorized` if a secret is set and the request lacks it
+/// - `400`, `404`, `405`, `411` or `413` for malformed requests
+///
+/// Only `Content-Length` bodies are supported; put a TLS-terminating proxy in
+/// front of the trigger to expose it beyond localhost.
+pub struct WebhookTrigger {
+    name: String,
+    listener: std::net::TcpListener,
+    path: String,
+    secret: Option<String>,
+    max_body_bytes: usize,
+}
+
+impl WebhookTrigger {
+    /// Listen on `addr`.
+    ///
+    /// Binding to port 0 picks a free port; see [`local_addr`](Self::local_addr).
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            name: "webhook".to_string(),
+            listener,
+            path: "/".to_string(),
+            secret: None,
+            max_body_bytes: 1024 * 1024,
+        })
+    }
+
+    /// Set the trigger name.
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Only accept requests to `path`.
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Require `Authorization: Bearer <secret>` or `X-Webhook-Secret: <secret>`.
+    #[must_use]
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Reject bodies larger than `max` bytes (default 1 MiB).
+    #[must_use]
+    pub fn with_max_body_bytes(mut self, max: usize) -> Self {
+        self.max_body_bytes = max;
+        self
+    }
+
+    /// Get the address the trigger listens on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn authorized(&self, request: &HttpRequest) -> bool {
+        let Some(secret) = &self.secret else {
+            return true;
+        };
+        let bearer = request
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "));
+        [bearer, request.header("x-webhook-secret")]
+            .into_iter()
+            .flatten()
+            .any(|given| constant_time_eq(given.trim().as_bytes(), secret.as_bytes()))
+    }
+
+    fn accept(&self, request: HttpRequest, sink: &TriggerSink) -> (u16, JsonValue) {
+        let path = request.path.split('?').next().unwrap_or_default();
+        if path != self.path {
+            return error(404, "not found");
+        }
+        if request.method != "POST" {
+            return error(405, "only POST is supported");
+        }
+        if !self.authorized(&request) {
+            return error(401, "missing or invalid secret");
+        }
+        let event = match parse_body(&request.body) {
+            Ok((prompt, metadata)) => TriggerEvent {
+                source: self.name.clone(),
+                prompt,
+                metadata,
+            },
+            Err(message) => return error(400, message),
+        };
+        match sink.try_send(event) {
+            Ok(()) => (202, serde_json::json!({"status": "accepted"})),
+            Err(mpsc::error::TrySendError::Full(_)) => error(429, "queue full, retry later"),
+            Err(mpsc::error::TrySendError::Closed(_)) => error(503, "worker stopped"),
+        }
+    }
+
+    async fn handle(&self, mut stream: TcpStream, sink: &TriggerSink) -> io::Result<()> {
+        let read =
+            tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream, self.max_body_bytes));
+        let (status, body) = match read.await {
+            Ok(Ok(request)) => self.accept(request, sink),
+            Ok(Err((status, message))) => error(status, message),
+            Err(_) => error(408, "request timeout"),
+        };
+        write_response(&mut stream, status, &body).await
+    }
+}
+
+impl fmt::Debug for WebhookTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookTrigger")
+            .field("name", &self.name)
+            .field("addr", &self.listener.local_addr().ok())
+            .field("path", &self.path)
+            .field("has_secret", &self.secret.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Trigger for WebhookTrigger {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(self: Box<Self>, sink: TriggerSink, cancel: CancellationToken) -> io::Result<()> {
+        let listener = TcpListener::from_std(self.listener.try_clone()?)?;
+        let this = Arc::new(*self);
+        let mut connections = tokio::task::JoinSet::new();
+        loop {
+            while connections.try_join_next().is_some() {}
+            let (stream, _peer) = tokio::select! {
+                () = cancel.cancelled() => break,
+                accepted = listener.accept() => accepted?,
+            };
+            debug!(trigger = %this.name, peer = %_peer, "Webhook request");
+            let (this, sink) = (Arc::clone(&this), sink.clone());
+            connections.spawn(async move {
+                let _ = this.handle(stream, &sink).await;
+            });
+        }
+        connections.shutdown().await;
+        Ok(())
+    }
+}
+
+fn error(status: u16, message: &str) -> (u16, JsonValue) {
+    (status, serde_json::json!({"error": message}))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Get the prompt and metadata of a webhook body.
+fn parse_body(body: &[u8]) -> Result<(String, Option<JsonValue>), &'static str> {
+    let prompt = match serde_json::from_slice::<JsonValue>(body) {
+        Ok(JsonValue::Object(mut object)) => {
+            let Some(JsonValue::String(prompt)) = object.remove("prompt") else {
+                return Err("JSON body needs a 'prompt' string");
+            };
+            return if prompt.trim().is_empty() {
+                Err("empty prompt")
+            } else {
+                Ok((prompt, object.remove("metadata")))
+            };
+        }
+        Ok(JsonValue::String(prompt)) => prompt,
+        _ => std::str::from_utf8(body)
+            .map_err(|_| "body is not UTF-8 text")?
+            .to_string(),
+    };
+    if prompt.trim().is_empty() {
+        Err("empty prompt")
+    } else {
+        Ok((prompt, None))
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+async fn read_request(
+    stream: &mut TcpStream,
+    max_body: usize,
+) -> Result<HttpRequest, (u16, &'static str)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err((431, "headers too large"));
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return Err((400, "incomplete request")),
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err((400, "malformed request line"));
+    };
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let mut request = HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        body: buf.split_off(head_end + 4),
+    };
+
+    if request.header("transfer-encoding").is_some() {
+        return Err((411, "Content-Length required"));
+    }
+    let length = match request.header("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| (400, "invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > max_body {
+        return Err((413, "body too large"));
+    }
+    while request.body.len() < length {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return Err((400, "incomplete body")),
+            Ok(n) => request.body.extend_from_slice(&chunk[..n]),
+        }
+    }
+    request.body.truncate(length);
+    Ok(request)
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &JsonValue) -> io::Result<()> {
+    let reason = match status {
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "",
+    };
+    let body = body.to_string();
+    let retry_after = if status == 429 {
+        "Retry-After: 1\r\n"
+    } else {
+        ""
+    };
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\n{retry_after}Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Function building the prompt for a changed file from its path and content.
+pub type FilePromptFn = Arc<dyn Fn(&Path, &str) -> String + Send + Sync>;
+
+/// Trigger polling a directory for new and changed files.
+///
+/// A file counts as changed when its size or modification time differs from
+/// the previous poll. Files present when the trigger starts are ignored
+/// unless [`include_existing`](Self::include_existing) is set.
+pub struct FileWatchTrigger {
+    name: String,
+    dir: PathBuf,
+    interval: Duration,
+    extensions: Vec<String>,
+    include_existing: bool,
+    max_bytes: usize,
+    prompt: FilePromptFn,
+}
+
+impl FileWatchTrigger {
+    /// Watch the files directly in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            name: "file_watch".to_string(),
+            dir: dir.into(),
+            interval: Duration::from_secs(1),
+            extensions: Vec::new(),
+            include_existing: false,
+            max_bytes: 64 * 1024,
+            prompt: Arc::new(|path, content| {
+                format!("The file {} changed:\n\n{content}", path.display())
+            }),
+        }
+    }
+
+    /// Set the trigger name.
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Poll every `interval` (default 1 second).
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Only watch files with one of these extensions.
+    #[must_use]
+    pub fn with_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extensions = extensions
+            .into_iter()
+            .map(|e| e.into().trim_start_matches('.').to_string())
+            .collect();
+        self
+    }
+
+    /// Also report the files present when the trigger starts.
+    #[must_use]
+    pub fn include_existing(mut self, include: bool) -> Self {
+        self.include_existing = include;
+        self
+    }
+
+    /// Pass at most `max` bytes of a file's content to the prompt (default 64 KiB).
+    #[must_use]
+    pub fn with_max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    /// Build prompts with `f` instead of quoting the file.
+    #[must_use]
+    pub fn with_prompt<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Path, &str) -> String + Send + Sync + 'static,
+    {
+        self.prompt = Arc::new(f);
+        self
+    }
+
+    async fn scan(&self) -> io::Result<HashMap<PathBuf, (SystemTime, u64)>> {
+        let mut files = HashMap::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let watched = self.extensions.is_empty()
+                || path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| self.extensions.iter().any(|w| w == e));
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            if watched && meta.is_file() {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.insert(path, (modified, meta.len()));
+            }
+        }
+        Ok(files)
+    }
+
+    async fn event(&self, path: &Path, size: u64) -> Option<TriggerEvent> {
+        let bytes = tokio::fs::read(path).await.ok()?;
+        let mut end = bytes.len().min(self.max_bytes);
+        let content = loop {
+            match std::str::from_utf8(&bytes[..end]) {
+                Ok(text) => break text.to_string(),
+                // Cut in the middle of a character
+                Err(e) if e.error_len().is_none() => end = e.valid_up_to(),
+                Err(_) => break String::from_utf8_lossy(&bytes[..end]).into_owned(),
+            }
+        };
+        Some(
+            TriggerEvent::new(&self.name, (self.prompt)(path, &content)).with_metadata(
+                serde_json::json!({
+                    "path": path.display().to_string(),
+                    "size": size,
+                    "truncated": bytes.len() > end,
+                }),
+            ),
+        )
+    }
+}
+
+impl fmt::Debug for FileWatchTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileWatchTrigger")
+            .field("name", &self.name)
+            .field("dir", &self.dir)
+            .field("interval", &self.interval)
+            .field("extensions", &self.extensions)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Trigger for FileWatchTrigger {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn run(self: Box<Self>, sink: TriggerSink, cancel: CancellationToken) -> io::Result<()> {
+        let mut seen = if self.include_existing {
+            HashMap::new()
+        } else {
+            self.scan().await?
+        };
+        let mut first = self.include_existing;
+        loop {
+            if !first {
+                tokio::select! {
+                    () = cancel.cancelled() => return Ok(()),
+                    () = tokio::time::sleep(self.interval) => {}
+                }
+            }
+            first = false;
+
+            let current = self.scan().await?;
+            let mut changed: Vec<_> = current
+                .iter()
+                .filter(|(path, state)| seen.get(*path) != Some(*state))
+                .map(|(path, (_, size))| (path.clone(), *size))
+                .collect();
+            changed.sort();
+            seen = current;
+            for (path, size) in changed {
+                let Some(event) = self.event(&path, size).await else {
+                    continue;
+                };
+                tokio::select! {
+                    () = cancel.cancelled() => return Ok(()),
+                    sent = sink.send(event) => if !sent { return Ok(()) },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn post(addr: SocketAddr, headers: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /hook HTTP/1.1\r\nHost: localhost\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_webhook_trigger() {
+        let trigger = WebhookTrigger::bind("127.0.0.1:0")
+            .unwrap()
+            .with_path("/hook")
+            .with_secret("s3cret");
+        let addr = trigger.local_addr().unwrap();
+        let (sink, mut rx) = TriggerSink::channel(1);
+        let cancel = CancellationToken::new();
+        let server = tokio::spawn(Box::new(trigger).run(sink, cancel.clone()));
+
+        let response = post(addr, "", "hello").await;
+        assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+
+        let auth = "Authorization: Bearer s3cret\r\n";
+        let body = r#"{"prompt": "Triage ticket 42", "metadata": {"ticket": 42}}"#;
+        let response = post(addr, auth, body).await;
+        assert!(response.starts_with("HTTP/1.1 202"), "{response}");
+        // The queue holds one event
+        let response = post(addr, auth, "second").await;
+        assert!(response.starts_with("HTTP/1.1 429"), "{response}");
+        let response = post(addr, auth, r#"{"text": "no prompt"}"#).await;
+        assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.source, "webhook");
+        assert_eq!(event.prompt, "Triage ticket 42");
+        assert_eq!(event.metadata, Some(serde_json::json!({"ticket": 42})));
+
+        cancel.cancel();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_watch_trigger() {
+        let dir = std::env::temp_dir().join(format!("serdes-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("old.txt"), "already there").unwrap();
+
+        let trigger = FileWatchTrigger::new(&dir)
+            .with_interval(Duration::from_millis(10))
+            .with_extensions([".txt"]);
+        let (sink, mut rx) = TriggerSink::channel(4);
+        let cancel = CancellationToken::new();
+        let watcher = tokio::spawn(Box::new(trigger).run(sink, cancel.clone()));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        std::fs::write(dir.join("ignored.bin"), "binary").unwrap();
+        std::fs::write(dir.join("new.txt"), "fresh content").unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(event.prompt.contains("new.txt"));
+        assert!(event.prompt.contains("fresh content"));
+        assert_eq!(event.metadata.unwrap()["size"], 13);
+
+        cancel.cancel();
+        watcher.await.unwrap().unwrap();
+        assert!(rx.try_recv().is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+//! Shared usage metering across agents.
+//!
+//! A [`UsageMeter`] accumulates token usage from every agent that reports
+//! into it, and enforces an optional token budget shared by all of them:
+//!
+//! - once usage crosses the warning threshold (80% by default), a
+//!   [`BudgetEvent::Warning`] is sent to the registered callbacks
+//! - once the budget is used up, a [`BudgetEvent::Exhausted`] is sent and
+//!   further requests fail with [`UsageLimitError::BudgetExhausted`]
+//!
+//! Agents report into the meter set with
+//! [`AgentBuilder::usage_meter`](crate::AgentBuilder::usage_meter), or into
+//! the process-wide meter installed with [`UsageMeter::install_global`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{BudgetEvent, UsageMeter};
+//!
+//! let meter = UsageMeter::new()
+//!     .with_budget(50_000_000)
+//!     .on_budget_event(|event| match event {
+//!         BudgetEvent::Warning { used, budget } => alert(used, budget),
+//!         BudgetEvent::Exhausted { .. } => page_on_call(),
+//!     });
+//! UsageMeter::install_global(meter);
+//!
+//! // At the start of each billing period
+//! UsageMeter::global().unwrap().reset();
+//! ```
+
+use crate::context::UsageTotals;
+use crate::errors::UsageLimitError;
+use serdes_ai_core::RequestUsage;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Process-wide meter that agents without their own meter report into.
+static GLOBAL_METER: RwLock<Option<Arc<UsageMeter>>> = RwLock::new(None);
+
+/// Default fraction of the budget at which a warning is sent.
+pub const DEFAULT_BUDGET_WARN_RATIO: f64 = 0.8;
+
+/// A budget threshold crossed by the meter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetEvent {
+    /// Usage crossed the warning threshold.
+    Warning {
+        /// Tokens used so far.
+        used: u64,
+        /// Token budget.
+        budget: u64,
+    },
+    /// The budget is used up; further requests are blocked.
+    Exhausted {
+        /// Tokens used so far.
+        used: u64,
+        /// Token budget.
+        budget: u64,
+    },
+}
+
+/// Callback for budget events.
+pub type BudgetCallback = Arc<dyn Fn(BudgetEvent) + Send + Sync>;
+
+#[derive(Debug, Default)]
+struct MeterState {
+    totals: UsageTotals,
+    by_model: BTreeMap<String, UsageTotals>,
+    warned: bool,
+    exhausted: bool,
+}
+
+/// Thread-safe token usage meter with an optional shared budget.
+pub struct UsageMeter {
+    budget: Option<u64>,
+    warn_ratio: f64,
+    callbacks: Vec<BudgetCallback>,
+    state: Mutex<MeterState>,
+}
+
+impl fmt::Debug for UsageMeter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageMeter")
+            .field("budget", &self.budget)
+            .field("warn_ratio", &self.warn_ratio)
+            .field("used", &self.used())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for UsageMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageMeter {
+    /// Create a meter without a budget.
+    pub fn new() -> Self {
+        Self {
+            budget: None,
+            warn_ratio: DEFAULT_BUDGET_WARN_RATIO,
+            callbacks: Vec::new(),
+            state: Mutex::new(MeterState::default()),
+        }
+    }
+
+    /// Set the total token budget.
+    #[must_use]
+    pub fn with_budget(mut self, tokens: u64) -> Self {
+        self.budget = Some(tokens);
+        self
+    }
+
+    /// Set the fraction of the budget at which a warning is sent.
+    #[must_use]
+    pub fn with_warn_ratio(mut self, ratio: f64) -> Self {
+        self.warn_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a callback for budget events.
+    ///
+    /// Each event is sent once; [`reset`](Self::reset) re-arms them.
+    #[must_use]
+    pub fn on_budget_event<F>(mut self, f: F) -> Self
+    where
+        F: Fn(BudgetEvent) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Arc::new(f));
+        self
+    }
+
+    /// Install a meter as the process-wide meter and return it.
+    pub fn install_global(meter: UsageMeter) -> Arc<UsageMeter> {
+        let meter = Arc::new(meter);
+        *GLOBAL_METER.write().unwrap() = Some(Arc::clone(&meter));
+        meter
+    }
+
+    /// Remove the process-wide meter.
+    pub fn uninstall_global() -> Option<Arc<UsageMeter>> {
+        GLOBAL_METER.write().unwrap().take()
+    }
+
+    /// Get the process-wide meter, if one is installed.
+    pub fn global() -> Option<Arc<UsageMeter>> {
+        GLOBAL_METER.read().unwrap().clone()
+    }
+
+    /// Get the token budget.
+    pub fn budget(&self) -> Option<u64> {
+        self.budget
+    }
+
+    /// Total tokens used.
+    pub fn used(&self) -> u64 {
+        self.state.lock().unwrap().totals.total_tokens
+    }
+
+    /// Tokens left in the budget.
+    pub fn remaining(&self) -> Option<u64> {
+        self.budget.map(|b| b.saturating_sub(self.used()))
+    }
+
+    /// Usage totals across all agents.
+    pub fn totals(&self) -> UsageTotals {
+        self.state.lock().unwrap().totals.clone()
+    }
+
+    /// Usage totals per model name.
+    pub fn by_model(&self) -> BTreeMap<String, UsageTotals> {
+        self.state.lock().unwrap().by_model.clone()
+    }
+
+    /// Check that the budget allows another request.
+    pub fn check(&self) -> Result<(), UsageLimitError> {
+        match self.budget {
+            Some(budget) => {
+                let used = self.used();
+                if used >= budget {
+                    Err(UsageLimitError::BudgetExhausted { used, budget })
+                } else {
+                    Ok(())
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Record the usage of a model request.
+    pub fn record(&self, model_name: &str, usage: &RequestUsage) {
+        let tokens = UsageTotals::from_request(usage);
+        let events = {
+            let mut state = self.state.lock().unwrap();
+            state.totals.add(&tokens);
+            state
+                .by_model
+                .entry(model_name.to_string())
+                .or_default()
+                .add(&tokens);
+            self.crossed_thresholds(&mut state)
+        };
+        for event in events {
+            for callback in &self.callbacks {
+                callback(event);
+            }
+        }
+    }
+
+    /// Clear all usage, e.g. at the start of a new billing period.
+    pub fn reset(&self) {
+        *self.state.lock().unwrap() = MeterState::default();
+    }
+
+    fn crossed_thresholds(&self, state: &mut MeterState) -> Vec<BudgetEvent> {
+        let Some(budget) = self.budget else {
+            return Vec::new();
+        };
+        let used = state.totals.total_tokens;
+        let mut events = Vec::new();
+        if !state.warned && used as f64 >= budget as f64 * self.warn_ratio {
+            state.warned = true;
+            events.push(BudgetEvent::Warning { used, budget });
+        }
+        if !state.exhausted && used >= budget {
+            state.exhausted = true;
+            events.push(BudgetEvent::Exhausted { used, budget });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_events_fire_once() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let meter = UsageMeter::new().with_budget(100).on_budget_event({
+            let events = Arc::clone(&events);
+            move |e| events.lock().unwrap().push(e)
+        });
+
+        meter.record("gpt-4o", &RequestUsage::with_tokens(50, 10));
+        assert!(events.lock().unwrap().is_empty());
+        assert!(meter.check().is_ok());
+
+        meter.record("gpt-4o", &RequestUsage::with_tokens(20, 5));
+        meter.record("claude", &RequestUsage::with_tokens(3, 2));
+        assert_eq!(
+            *events.lock().unwrap(),
+            [BudgetEvent::Warning {
+                used: 85,
+                budget: 100
+            }]
+        );
+
+        meter.record("claude", &RequestUsage::with_tokens(10, 10));
+        assert_eq!(events.lock().unwrap().len(), 2);
+        assert!(matches!(
+            meter.check(),
+            Err(UsageLimitError::BudgetExhausted {
+                used: 110,
+                budget: 100
+            })
+        ));
+        assert_eq!(meter.by_model()["claude"].total_tokens, 25);
+        assert_eq!(meter.remaining(), Some(0));
+
+        meter.reset();
+        assert!(meter.check().is_ok());
+        assert_eq!(meter.used(), 0);
+    }
+}
@@ -0,0 +1,331 @@
+//! Resident agent services.
+//!
+//! An [`AgentWorker`] keeps an agent running as a service: it starts its
+//! [`Trigger`]s, queues their events and runs the agent once per event,
+//! with at most [`max_concurrency`](AgentWorker::max_concurrency) runs at a
+//! time. Events are only taken off the queue when a run slot is free, so a
+//! full queue pushes back on the triggers.
+//!
+//! ```rust,ignore
+//! use serdes_ai_agent::{AgentWorker, CancellationToken, FileWatchTrigger};
+//!
+//! let worker = AgentWorker::new(agent, deps)
+//!     .trigger(FileWatchTrigger::new("inbox").with_extensions(["eml"]))
+//!     .queue_capacity(32)
+//!     .max_concurrency(2)
+//!     .on_complete(|event, result| match result {
+//!         Ok(result) => println!("{}: {}", event.source, result.output),
+//!         Err(e) => eprintln!("{} failed: {e}", event.source),
+//!     });
+//!
+//! let cancel = CancellationToken::new();
+//! let stats = worker.run(cancel.clone()).await;
+//! ```
+
+use crate::agent::Agent;
+use crate::errors::AgentRunError;
+use crate::run::{AgentRunResult, RunOptions};
+use crate::triggers::{Trigger, TriggerEvent, TriggerSink};
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+// Conditional tracing - use no-op macros when tracing feature is disabled
+#[cfg(feature = "tracing-integration")]
+use tracing::warn;
+
+#[cfg(not(feature = "tracing-integration"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Callback receiving each event with the outcome of its run.
+pub type CompletionHook<Output> =
+    Arc<dyn Fn(&TriggerEvent, &Result<AgentRunResult<Output>, AgentRunError>) + Send + Sync>;
+
+/// Counters of a worker's runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// Events taken off the queue.
+    pub received: u64,
+    /// Runs that finished with output.
+    pub succeeded: u64,
+    /// Runs that failed.
+    pub failed: u64,
+}
+
+/// Runs an agent for every event of its triggers.
+pub struct AgentWorker<Deps, Output = String> {
+    agent: Arc<Agent<Deps, Output>>,
+    deps: Deps,
+    triggers: Vec<Box<dyn Trigger>>,
+    queue_capacity: usize,
+    max_concurrency: usize,
+    options: RunOptions,
+    on_complete: Vec<CompletionHook<Output>>,
+}
+
+/// What each run task needs from the worker.
+struct Runner<Deps, Output> {
+    agent: Arc<Agent<Deps, Output>>,
+    deps: Deps,
+    options: RunOptions,
+    on_complete: Vec<CompletionHook<Output>>,
+}
+
+impl<Deps, Output> Runner<Deps, Output>
+where
+    Deps: Clone + Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    async fn handle(&self, event: TriggerEvent) -> bool {
+        let mut options = self.options.clone();
+        let metadata = options
+            .metadata
+            .get_or_insert_with(|| JsonValue::Object(Default::default()));
+        if let Some(metadata) = metadata.as_object_mut() {
+            metadata.insert("trigger".into(), event.source.clone().into());
+            if let Some(extra) = &event.metadata {
+                metadata.insert("trigger_metadata".into(), extra.clone());
+            }
+        }
+
+        let result = self
+            .agent
+            .run_with_options(event.prompt.clone(), self.deps.clone(), options)
+            .await;
+        if let Err(_e) = &result {
+            warn!(trigger = %event.source, error = %_e, "Triggered run failed");
+        }
+        self.on_complete
+            .iter()
+            .for_each(|hook| hook(&event, &result));
+        result.is_ok()
+    }
+}
+
+impl<Deps, Output> AgentWorker<Deps, Output>
+where
+    Deps: Clone + Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    /// Create a worker without triggers, running `agent` with `deps`.
+    pub fn new(agent: impl Into<Arc<Agent<Deps, Output>>>, deps: Deps) -> Self {
+        Self {
+            agent: agent.into(),
+            deps,
+            triggers: Vec::new(),
+            queue_capacity: 64,
+            max_concurrency: 4,
+            options: RunOptions::default(),
+            on_complete: Vec::new(),
+        }
+    }
+
+    /// Add a trigger.
+    #[must_use]
+    pub fn trigger(mut self, trigger: impl Trigger) -> Self {
+        self.triggers.push(Box::new(trigger));
+        self
+    }
+
+    /// Queue at most `capacity` events waiting for a run slot (default 64).
+    #[must_use]
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity.max(1);
+        self
+    }
+
+    /// Run at most `max` events at a time (default 4).
+    #[must_use]
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
+    }
+
+    /// Set the options of each run.
+    ///
+    /// The trigger name and event metadata are added to the run metadata.
+    #[must_use]
+    pub fn run_options(mut self, options: RunOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Call `f` when a run finishes.
+    #[must_use]
+    pub fn on_complete<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&TriggerEvent, &Result<AgentRunResult<Output>, AgentRunError>)
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on_complete.push(Arc::new(f));
+        self
+    }
+
+    /// Process events until `cancel` is triggered or every trigger is done.
+    ///
+    /// Runs in progress are waited for; events still queued when `cancel`
+    /// is triggered are dropped.
+    pub async fn run(self, cancel: CancellationToken) -> WorkerStats {
+        let (sink, mut queue) = TriggerSink::channel(self.queue_capacity);
+        let stop_triggers = cancel.child_token();
+        let mut triggers = JoinSet::new();
+        for trigger in self.triggers {
+            let (sink, cancel) = (sink.clone(), stop_triggers.clone());
+            triggers.spawn(async move {
+                let _name = trigger.name().to_string();
+                if let Err(_e) = trigger.run(sink, cancel).await {
+                    warn!(trigger = %_name, error = %_e, "Trigger stopped with an error");
+                }
+            });
+        }
+        drop(sink);
+
+        let runner = Arc::new(Runner {
+            agent: self.agent,
+            deps: self.deps,
+            options: self.options,
+            on_complete: self.on_complete,
+        });
+        let slots = Arc::new(Semaphore::new(self.max_concurrency));
+        let mut runs = JoinSet::new();
+        let mut stats = WorkerStats::default();
+        let tally = |stats: &mut WorkerStats, outcome: Result<bool, _>| match outcome {
+            Ok(true) => stats.succeeded += 1,
+            _ => stats.failed += 1,
+        };
+
+        loop {
+            // Wait for a free slot before taking an event off the queue
+            let permit = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                permit = Arc::clone(&slots).acquire_owned() => {
+                    permit.expect("worker semaphore is never closed")
+                }
+            };
+            let event = tokio::select! {
+                biased;
+                () = cancel.cancelled() => break,
+                event = queue.recv() => event,
+            };
+            let Some(event) = event else {
+                break;
+            };
+            stats.received += 1;
+            while let Some(outcome) = runs.try_join_next() {
+                tally(&mut stats, outcome);
+            }
+            let runner = Arc::clone(&runner);
+            runs.spawn(async move {
+                let _permit = permit;
+                runner.handle(event).await
+            });
+        }
+
+        stop_triggers.cancel();
+        while triggers.join_next().await.is_some() {}
+        while let Some(outcome) = runs.join_next().await {
+            tally(&mut stats, outcome);
+        }
+        stats
+    }
+}
+
+impl<Deps, Output> fmt::Debug for AgentWorker<Deps, Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentWorker")
+            .field(
+                "triggers",
+                &self.triggers.iter().map(|t| t.name()).collect::<Vec<_>>(),
+            )
+            .field("queue_capacity", &self.queue_capacity)
+            .field("max_concurrency", &self.max_concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::triggers::ChannelTrigger;
+    use serdes_ai_core::messages::ModelResponse;
+    use serdes_ai_models::FunctionModel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_worker_limits_concurrency() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (a, p) = (active.clone(), peak.clone());
+        let model = FunctionModel::new(move |_, _| {
+            let now = a.fetch_add(1, Ordering::SeqCst) + 1;
+            p.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(30));
+            a.fetch_sub(1, Ordering::SeqCst);
+            ModelResponse::text("done")
+        });
+        let agent = crate::agent(model).build();
+
+        let (tx, trigger) = ChannelTrigger::channel(8);
+        let outputs = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let seen = outputs.clone();
+        let worker = AgentWorker::new(agent, ())
+            .trigger(trigger.with_name("queue"))
+            .queue_capacity(2)
+            .max_concurrency(2)
+            .on_complete(move |event, result| {
+                seen.lock().push((
+                    event.source.clone(),
+                    result.as_ref().unwrap().output.clone(),
+                ));
+            });
+
+        for i in 0..6 {
+            tx.send(TriggerEvent::new("cli", format!("job {i}")))
+                .await
+                .unwrap();
+        }
+        drop(tx);
+
+        let stats = tokio::time::timeout(
+            Duration::from_secs(10),
+            worker.run(CancellationToken::new()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            stats,
+            WorkerStats {
+                received: 6,
+                succeeded: 6,
+                failed: 0
+            }
+        );
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(outputs.lock().len(), 6);
+        assert_eq!(outputs.lock()[0].1, "done");
+    }
+
+    #[tokio::test]
+    async fn test_worker_stops_on_cancel() {
+        let agent = crate::agent(FunctionModel::constant_text("ok")).build();
+        let (_tx, trigger) = ChannelTrigger::channel(1);
+        let cancel = CancellationToken::new();
+        let worker = AgentWorker::new(agent, ()).trigger(trigger);
+        let handle = tokio::spawn(worker.run(cancel.clone()));
+        cancel.cancel();
+        let stats = tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats, WorkerStats::default());
+    }
+}
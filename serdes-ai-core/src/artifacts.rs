@@ -0,0 +1,351 @@
+//! Content-addressed storage for generated files.
+//!
+//! Images from image generation, files written by code execution and
+//! [`FilePart`]s returned by models can be large. Instead of carrying them
+//! around as base64, they are put in an [`ArtifactStore`] under the SHA-256
+//! hash of their bytes and passed around as an [`ArtifactRef`]. Storing the
+//! same bytes twice yields the same reference.
+//!
+//! A store can be given a URL hook, so UI adapters emit links to large files
+//! instead of inlining them:
+//!
+//! ```rust
+//! use serdes_ai_core::artifacts::{ArtifactStore, InMemoryArtifactStore};
+//! use serdes_ai_core::messages::FilePart;
+//!
+//! let store = InMemoryArtifactStore::new()
+//!     .with_url_fn(|artifact| Some(format!("https://cdn.example.com/a/{}", artifact.hash)));
+//!
+//! let file = FilePart::from_bytes(vec![0; 64 * 1024], "image/png");
+//! let link = store.link(&file, 16 * 1024).unwrap();
+//! assert!(link.starts_with("https://cdn.example.com/a/"));
+//! ```
+
+use crate::messages::{BinaryContent, FilePart};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Reference to content in an [`ArtifactStore`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ArtifactRef {
+    /// Hex SHA-256 hash of the content.
+    pub hash: String,
+    /// MIME type of the content.
+    pub media_type: String,
+    /// Size of the content in bytes.
+    pub size: u64,
+    /// URL the content can be fetched from, if the store serves it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl ArtifactRef {
+    /// Get the `artifact://` URI of the content.
+    #[must_use]
+    pub fn uri(&self) -> String {
+        format!("artifact://{}", self.hash)
+    }
+}
+
+/// Hex SHA-256 hash of some content.
+#[must_use]
+pub fn content_hash(data: &[u8]) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, data);
+    digest.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Encode content as a `data:` URL.
+#[must_use]
+pub fn data_url(content: &BinaryContent) -> String {
+    format!(
+        "data:{};base64,{}",
+        content.media_type,
+        base64::engine::general_purpose::STANDARD.encode(&content.data)
+    )
+}
+
+/// Function generating the URL of an artifact.
+pub type ArtifactUrlFn = Arc<dyn Fn(&ArtifactRef) -> Option<String> + Send + Sync>;
+
+/// Storage for content addressed by its hash.
+pub trait ArtifactStore: Send + Sync {
+    /// Store content, returning its reference.
+    fn put(&self, content: BinaryContent) -> io::Result<ArtifactRef>;
+
+    /// Get content by hash.
+    fn get(&self, hash: &str) -> io::Result<Option<BinaryContent>>;
+
+    /// Get the URL of an artifact, if the store serves it.
+    ///
+    /// The default serves nothing, so callers fall back to inlining.
+    fn url(&self, artifact: &ArtifactRef) -> Option<String> {
+        let _ = artifact;
+        None
+    }
+
+    /// Store the content of a file part.
+    fn put_file(&self, file: &FilePart) -> io::Result<ArtifactRef> {
+        self.put(file.content.clone())
+    }
+
+    /// Get content by hash as a file part.
+    fn get_file(&self, hash: &str) -> io::Result<Option<FilePart>> {
+        Ok(self.get(hash)?.map(FilePart::new))
+    }
+
+    /// Get a link to a file for a UI.
+    ///
+    /// Files up to `inline_limit` bytes, and files the store has no URL
+    /// for, are inlined as `data:` URLs; larger files are stored and linked.
+    fn link(&self, file: &FilePart, inline_limit: usize) -> io::Result<String> {
+        if file.content.len() <= inline_limit {
+            return Ok(data_url(&file.content));
+        }
+        let artifact = self.put_file(file)?;
+        Ok(artifact.url.unwrap_or_else(|| data_url(&file.content)))
+    }
+}
+
+impl<S: ArtifactStore + ?Sized> ArtifactStore for Arc<S> {
+    fn put(&self, content: BinaryContent) -> io::Result<ArtifactRef> {
+        (**self).put(content)
+    }
+
+    fn get(&self, hash: &str) -> io::Result<Option<BinaryContent>> {
+        (**self).get(hash)
+    }
+
+    fn url(&self, artifact: &ArtifactRef) -> Option<String> {
+        (**self).url(artifact)
+    }
+}
+
+/// Build the reference of content, with its URL from `url_fn`.
+fn reference(content: &BinaryContent, url_fn: Option<&ArtifactUrlFn>) -> ArtifactRef {
+    let mut artifact = ArtifactRef {
+        hash: content_hash(&content.data),
+        media_type: content.media_type.clone(),
+        size: content.data.len() as u64,
+        url: None,
+    };
+    artifact.url = url_fn.and_then(|url| url(&artifact));
+    artifact
+}
+
+/// In-memory [`ArtifactStore`].
+#[derive(Default)]
+pub struct InMemoryArtifactStore {
+    artifacts: RwLock<HashMap<String, BinaryContent>>,
+    url_fn: Option<ArtifactUrlFn>,
+}
+
+impl InMemoryArtifactStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate artifact URLs with `url_fn`.
+    #[must_use]
+    pub fn with_url_fn<F>(mut self, url_fn: F) -> Self
+    where
+        F: Fn(&ArtifactRef) -> Option<String> + Send + Sync + 'static,
+    {
+        self.url_fn = Some(Arc::new(url_fn));
+        self
+    }
+
+    /// Number of stored artifacts.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.artifacts.read().map_or(0, |a| a.len())
+    }
+
+    /// Check if the store is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ArtifactStore for InMemoryArtifactStore {
+    fn put(&self, content: BinaryContent) -> io::Result<ArtifactRef> {
+        let artifact = reference(&content, self.url_fn.as_ref());
+        self.artifacts
+            .write()
+            .map_err(|_| io::Error::other("artifact store lock poisoned"))?
+            .insert(artifact.hash.clone(), content);
+        Ok(artifact)
+    }
+
+    fn get(&self, hash: &str) -> io::Result<Option<BinaryContent>> {
+        Ok(self
+            .artifacts
+            .read()
+            .map_err(|_| io::Error::other("artifact store lock poisoned"))?
+            .get(hash)
+            .cloned())
+    }
+
+    fn url(&self, artifact: &ArtifactRef) -> Option<String> {
+        self.url_fn.as_ref().and_then(|url| url(artifact))
+    }
+}
+
+impl fmt::Debug for InMemoryArtifactStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryArtifactStore")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`ArtifactStore`] keeping each artifact in a file named by its hash.
+///
+/// The media type is kept next to the content in a `<hash>.type` file.
+pub struct FileArtifactStore {
+    dir: PathBuf,
+    url_fn: Option<ArtifactUrlFn>,
+}
+
+impl FileArtifactStore {
+    /// Open a store in `dir`, creating the directory if needed.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, url_fn: None })
+    }
+
+    /// Generate artifact URLs with `url_fn`.
+    #[must_use]
+    pub fn with_url_fn<F>(mut self, url_fn: F) -> Self
+    where
+        F: Fn(&ArtifactRef) -> Option<String> + Send + Sync + 'static,
+    {
+        self.url_fn = Some(Arc::new(url_fn));
+        self
+    }
+
+    /// Get the directory.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the path of an artifact's content, if `hash` is a valid hash.
+    #[must_use]
+    pub fn path(&self, hash: &str) -> Option<PathBuf> {
+        let valid = hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit());
+        valid.then(|| self.dir.join(hash))
+    }
+}
+
+impl ArtifactStore for FileArtifactStore {
+    fn put(&self, content: BinaryContent) -> io::Result<ArtifactRef> {
+        let artifact = reference(&content, self.url_fn.as_ref());
+        let path = self.dir.join(&artifact.hash);
+        if !path.exists() {
+            // Write then rename, so readers never see a partial file
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, &content.data)?;
+            std::fs::rename(&tmp, &path)?;
+        }
+        std::fs::write(path.with_extension("type"), &content.media_type)?;
+        Ok(artifact)
+    }
+
+    fn get(&self, hash: &str) -> io::Result<Option<BinaryContent>> {
+        let Some(path) = self.path(hash) else {
+            return Ok(None);
+        };
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let media_type = std::fs::read_to_string(path.with_extension("type"))
+            .unwrap_or_else(|_| "application/octet-stream".to_string());
+        Ok(Some(BinaryContent::new(data, media_type)))
+    }
+
+    fn url(&self, artifact: &ArtifactRef) -> Option<String> {
+        self.url_fn.as_ref().and_then(|url| url(artifact))
+    }
+}
+
+impl fmt::Debug for FileArtifactStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileArtifactStore")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_is_content_addressed() {
+        let store = InMemoryArtifactStore::new();
+        let a = store
+            .put(BinaryContent::new(b"hello".to_vec(), "text/plain"))
+            .unwrap();
+        let b = store
+            .put(BinaryContent::new(b"hello".to_vec(), "text/plain"))
+            .unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(
+            a.hash,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(a.size, 5);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get(&a.hash).unwrap().unwrap().data, b"hello");
+        assert!(store.get("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_link_inlines_small_files() {
+        let store = InMemoryArtifactStore::new()
+            .with_url_fn(|a| Some(format!("https://files.example.com/{}", a.hash)));
+        let small = FilePart::from_bytes(vec![1, 2, 3], "image/png");
+        let large = FilePart::from_bytes(vec![0; 100], "image/png");
+
+        assert_eq!(
+            store.link(&small, 10).unwrap(),
+            "data:image/png;base64,AQID"
+        );
+        assert!(store
+            .link(&large, 10)
+            .unwrap()
+            .starts_with("https://files.example.com/"));
+        assert_eq!(store.len(), 1);
+
+        // Without a URL hook, everything is inlined
+        let plain = InMemoryArtifactStore::new();
+        assert!(plain.link(&large, 10).unwrap().starts_with("data:"));
+    }
+
+    #[test]
+    fn test_file_store_roundtrip() {
+        let dir =
+            std::env::temp_dir().join(format!("serdes-ai-artifacts-{}", uuid::Uuid::new_v4()));
+        let store = FileArtifactStore::open(&dir).unwrap();
+        let file = FilePart::from_bytes(vec![7; 32], "application/pdf");
+
+        let artifact = store.put_file(&file).unwrap();
+        let reopened = FileArtifactStore::open(&dir).unwrap();
+        assert_eq!(reopened.get_file(&artifact.hash).unwrap(), Some(file));
+        assert!(reopened.get("../etc/passwd").unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,202 @@
+//! Injectable clock.
+//!
+//! Message timestamps, run start times and retry waits read the time through
+//! the current [`Clock`] rather than the system clock, so tests and recorded
+//! replays can pin it. The [`SystemClock`] is used unless a clock is
+//! installed, either for the whole process with [`set_clock`] or for the
+//! current thread with [`use_clock`]:
+//!
+//! ```rust
+//! use serdes_ai_core::clock::{use_clock, MockClock};
+//! use serdes_ai_core::ModelRequest;
+//! use std::time::Duration;
+//!
+//! let clock = MockClock::epoch();
+//! let _guard = use_clock(clock.clone());
+//!
+//! let request = || {
+//!     let mut request = ModelRequest::new();
+//!     request.add_user_prompt("Hello");
+//!     serde_json::to_string(&request).unwrap()
+//! };
+//! assert_eq!(request(), request());
+//!
+//! clock.advance(Duration::from_secs(5));
+//! assert_eq!(serdes_ai_core::now_utc().timestamp(), 5);
+//! ```
+//!
+//! Thread clocks take precedence over the process clock. Async tests on a
+//! current-thread runtime (the `#[tokio::test]` default) see the thread
+//! clock in spawned tasks too.
+
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::Duration;
+
+/// Source of the current time.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Get the current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Let `duration` pass without waiting, if the clock is simulated.
+    ///
+    /// Returns `false` if the caller has to actually wait, which is what the
+    /// default does.
+    fn skip_wait(&self, duration: Duration) -> bool {
+        let _ = duration;
+        false
+    }
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// Waits are skipped and advance the clock instead, so retry backoff takes
+/// no real time. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl MockClock {
+    /// Create a clock stopped at `now`.
+    #[must_use]
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Create a clock stopped at the Unix epoch.
+    #[must_use]
+    pub fn epoch() -> Self {
+        Self::new(DateTime::UNIX_EPOCH)
+    }
+
+    /// Set the time.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.lock() = now;
+    }
+
+    /// Move the time forward.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.lock();
+        *now += chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DateTime<Utc>> {
+        self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.lock()
+    }
+
+    fn skip_wait(&self, duration: Duration) -> bool {
+        self.advance(duration);
+        true
+    }
+}
+
+static PROCESS_CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+thread_local! {
+    static THREAD_CLOCK: RefCell<Option<Arc<dyn Clock>>> = const { RefCell::new(None) };
+}
+
+/// Install a clock for the whole process.
+pub fn set_clock(clock: impl Clock + 'static) {
+    *PROCESS_CLOCK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(clock));
+}
+
+/// Go back to the system clock for the whole process.
+pub fn reset_clock() {
+    *PROCESS_CLOCK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Install a clock for the current thread until the guard is dropped.
+#[must_use = "the clock is uninstalled when the guard is dropped"]
+pub fn use_clock(clock: impl Clock + 'static) -> ClockGuard {
+    let previous = THREAD_CLOCK.with(|c| c.borrow_mut().replace(Arc::new(clock)));
+    ClockGuard { previous }
+}
+
+/// Restores the previous thread clock when dropped.
+#[derive(Debug)]
+pub struct ClockGuard {
+    previous: Option<Arc<dyn Clock>>,
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        THREAD_CLOCK.with(|c| *c.borrow_mut() = previous);
+    }
+}
+
+/// Get the current clock.
+#[must_use]
+pub fn clock() -> Arc<dyn Clock> {
+    THREAD_CLOCK
+        .with(|c| c.borrow().clone())
+        .or_else(|| {
+            PROCESS_CLOCK
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+        .unwrap_or_else(|| Arc::new(SystemClock))
+}
+
+/// Get the current time from the current clock.
+#[must_use]
+pub fn now() -> DateTime<Utc> {
+    clock().now()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::epoch();
+        assert_eq!(clock.now().timestamp(), 0);
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now().timestamp_millis(), 1500);
+        assert!(clock.skip_wait(Duration::from_secs(1)));
+        assert_eq!(clock.now().timestamp_millis(), 2500);
+        assert!(!SystemClock.skip_wait(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_thread_clock_guard() {
+        let outer = use_clock(MockClock::epoch());
+        {
+            let _inner = use_clock(MockClock::new(
+                DateTime::UNIX_EPOCH + chrono::Duration::days(1),
+            ));
+            assert_eq!(now().timestamp(), 86_400);
+        }
+        assert_eq!(now().timestamp(), 0);
+        drop(outer);
+        assert!(now().timestamp() > 0);
+    }
+}
@@ -0,0 +1,602 @@
+//! Structured multi-file code edits.
+//!
+//! [`CodeEdits`] is an output type for coding agents: a list of per-file
+//! edits, each either a search/replace of an exact snippet or a unified diff.
+//! Edits are checked against a [`WorkspaceSnapshot`] of the files they touch,
+//! so a model whose edit does not apply can be asked to retry before anything
+//! is written to disk.
+//!
+//! ```rust
+//! use serdes_ai_core::code_edits::{CodeEdits, FileEdit, WorkspaceSnapshot};
+//!
+//! let snapshot = WorkspaceSnapshot::new().with_file("src/lib.rs", "fn answer() -> u32 {\n    41\n}\n");
+//! let edits = CodeEdits::new(vec![FileEdit::replace("src/lib.rs", "    41", "    42")]);
+//!
+//! let updated = edits.apply(&snapshot).unwrap();
+//! assert_eq!(updated.get("src/lib.rs"), Some("fn answer() -> u32 {\n    42\n}\n"));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use thiserror::Error;
+
+/// Instructions describing the [`CodeEdits`] format to a model.
+pub const CODE_EDITS_INSTRUCTIONS: &str = "Return your changes as a list of file edits. \
+    Paths are relative to the workspace root. To change a file, give an exact snippet of its \
+    current content as `search` (unique in the file, with a few lines of context) and its \
+    new text as `replace`; to create a file, use an empty `search`. For larger changes, give \
+    a unified diff of the file as `diff` instead.";
+
+/// Edits to the files of a workspace, applied in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeEdits {
+    /// The edits.
+    pub edits: Vec<FileEdit>,
+    /// Short description of the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// An edit of one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FileEdit {
+    /// Replace an exact snippet of the file.
+    Replace {
+        /// Path relative to the workspace root.
+        path: String,
+        /// Snippet to replace; must occur exactly once. Empty to create the file.
+        search: String,
+        /// Replacement text.
+        replace: String,
+    },
+    /// Apply a unified diff to the file.
+    Diff {
+        /// Path relative to the workspace root.
+        path: String,
+        /// Unified diff of the file; `+++ /dev/null` deletes it.
+        diff: String,
+    },
+}
+
+impl FileEdit {
+    /// Create a search/replace edit.
+    #[must_use]
+    pub fn replace(
+        path: impl Into<String>,
+        search: impl Into<String>,
+        replace: impl Into<String>,
+    ) -> Self {
+        Self::Replace {
+            path: path.into(),
+            search: search.into(),
+            replace: replace.into(),
+        }
+    }
+
+    /// Create a unified diff edit.
+    #[must_use]
+    pub fn diff(path: impl Into<String>, diff: impl Into<String>) -> Self {
+        Self::Diff {
+            path: path.into(),
+            diff: diff.into(),
+        }
+    }
+
+    /// Get the path of the edited file.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Replace { path, .. } | Self::Diff { path, .. } => path,
+        }
+    }
+
+    /// Apply the edit to the current content of the file.
+    ///
+    /// `None` means the file does not exist; returning `None` deletes it.
+    fn apply_to(&self, content: Option<&str>) -> Result<Option<String>, String> {
+        match self {
+            Self::Replace {
+                search, replace, ..
+            } => match content {
+                None if search.is_empty() => Ok(Some(replace.clone())),
+                None => Err("file does not exist".to_string()),
+                Some(_) if search.is_empty() => {
+                    Err("file already exists; `search` must not be empty".to_string())
+                }
+                Some(content) => match content.matches(search.as_str()).count() {
+                    1 => Ok(Some(content.replacen(search.as_str(), replace, 1))),
+                    0 => Err("`search` text not found in file".to_string()),
+                    n => Err(format!(
+                        "`search` text occurs {n} times in file; include more context"
+                    )),
+                },
+            },
+            Self::Diff { diff, .. } => apply_unified_diff(content, diff),
+        }
+    }
+}
+
+/// Why an edit is invalid or does not apply.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("edit {index} ({path}): {message}")]
+pub struct CodeEditError {
+    /// Index of the edit.
+    pub index: usize,
+    /// Path of the edited file.
+    pub path: String,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl CodeEdits {
+    /// Create from a list of edits.
+    #[must_use]
+    pub fn new(edits: Vec<FileEdit>) -> Self {
+        Self {
+            edits,
+            summary: None,
+        }
+    }
+
+    /// Split a multi-file unified diff, e.g. from `git diff`, into edits.
+    pub fn from_patch(patch: &str) -> Result<Self, CodeEditError> {
+        let mut edits = Vec::new();
+        let lines: Vec<&str> = patch.lines().collect();
+        let mut start = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let is_header = lines[i].starts_with("--- ")
+                && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "));
+            if is_header {
+                if let Some(s) = start {
+                    edits.push(file_diff(&lines[s..i], edits.len())?);
+                }
+                start = Some(i);
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+        match start {
+            Some(s) => edits.push(file_diff(&lines[s..], edits.len())?),
+            None => {
+                return Err(CodeEditError {
+                    index: 0,
+                    path: String::new(),
+                    message: "no `---`/`+++` file headers in patch".to_string(),
+                })
+            }
+        }
+        Ok(Self::new(edits))
+    }
+
+    /// JSON schema of the format, for structured output.
+    #[must_use]
+    pub fn json_schema() -> JsonValue {
+        let path = serde_json::json!({
+            "type": "string",
+            "description": "Path of the file, relative to the workspace root"
+        });
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "edits": {
+                    "type": "array",
+                    "description": "File edits, applied in order",
+                    "items": {
+                        "anyOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "path": path,
+                                    "search": {
+                                        "type": "string",
+                                        "description": "Exact current text to replace, unique in the file; empty to create the file"
+                                    },
+                                    "replace": {"type": "string", "description": "Replacement text"}
+                                },
+                                "required": ["path", "search", "replace"],
+                                "additionalProperties": false
+                            },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "path": path,
+                                    "diff": {"type": "string", "description": "Unified diff of the file"}
+                                },
+                                "required": ["path", "diff"],
+                                "additionalProperties": false
+                            }
+                        ]
+                    }
+                },
+                "summary": {"type": "string", "description": "Short description of the change"}
+            },
+            "required": ["edits"]
+        })
+    }
+
+    /// Get the paths of the edited files, in order of first edit.
+    #[must_use]
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = Vec::new();
+        for edit in &self.edits {
+            if !paths.contains(&edit.path()) {
+                paths.push(edit.path());
+            }
+        }
+        paths
+    }
+
+    /// Check that every path is relative and stays inside the workspace.
+    pub fn validate_paths(&self) -> Result<(), CodeEditError> {
+        for (index, edit) in self.edits.iter().enumerate() {
+            check_path(edit.path()).map_err(|message| CodeEditError {
+                index,
+                path: edit.path().to_string(),
+                message,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Apply the edits to a snapshot, returning the updated snapshot.
+    ///
+    /// Fails on the first edit that is invalid or does not apply; the
+    /// snapshot passed in is left unchanged.
+    pub fn apply(&self, snapshot: &WorkspaceSnapshot) -> Result<WorkspaceSnapshot, CodeEditError> {
+        self.validate_paths()?;
+        let mut updated = snapshot.clone();
+        for (index, edit) in self.edits.iter().enumerate() {
+            let path = normalize_path(edit.path());
+            let result = edit
+                .apply_to(updated.get(path))
+                .map_err(|message| CodeEditError {
+                    index,
+                    path: edit.path().to_string(),
+                    message,
+                })?;
+            match result {
+                Some(content) => updated.insert(path, content),
+                None => {
+                    updated.remove(path);
+                }
+            }
+        }
+        Ok(updated)
+    }
+
+    /// Collect every problem with the edits against a snapshot.
+    ///
+    /// Unlike [`apply`](Self::apply), checking continues after a failing
+    /// edit, so all problems can be reported to the model at once.
+    #[must_use]
+    pub fn check(&self, snapshot: &WorkspaceSnapshot) -> Vec<CodeEditError> {
+        let mut errors = Vec::new();
+        let mut updated = snapshot.clone();
+        for (index, edit) in self.edits.iter().enumerate() {
+            let error = |message| CodeEditError {
+                index,
+                path: edit.path().to_string(),
+                message,
+            };
+            if let Err(message) = check_path(edit.path()) {
+                errors.push(error(message));
+                continue;
+            }
+            let path = normalize_path(edit.path());
+            match edit.apply_to(updated.get(path)) {
+                Ok(Some(content)) => updated.insert(path, content),
+                Ok(None) => {
+                    updated.remove(path);
+                }
+                Err(message) => errors.push(error(message)),
+            }
+        }
+        errors
+    }
+}
+
+/// Contents of the files of a workspace, by relative path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    files: BTreeMap<String, String>,
+}
+
+impl WorkspaceSnapshot {
+    /// Create an empty snapshot.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file.
+    #[must_use]
+    pub fn with_file(mut self, path: impl AsRef<str>, content: impl Into<String>) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    /// Add or replace a file.
+    pub fn insert(&mut self, path: impl AsRef<str>, content: impl Into<String>) {
+        self.files
+            .insert(normalize_path(path.as_ref()).to_string(), content.into());
+    }
+
+    /// Remove a file, returning its content.
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        self.files.remove(normalize_path(path))
+    }
+
+    /// Get the content of a file.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(normalize_path(path)).map(String::as_str)
+    }
+
+    /// Iterate over paths and contents.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.files.iter().map(|(p, c)| (p.as_str(), c.as_str()))
+    }
+
+    /// Number of files.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Check if the snapshot has no files.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl<P: AsRef<str>, C: Into<String>> FromIterator<(P, C)> for WorkspaceSnapshot {
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut snapshot = Self::new();
+        for (path, content) in iter {
+            snapshot.insert(path, content);
+        }
+        snapshot
+    }
+}
+
+fn normalize_path(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+/// Check that a path is relative and stays inside the workspace.
+pub fn check_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("path is empty".to_string());
+    }
+    if path.contains('\\') || path.contains('\0') {
+        return Err("path must use `/` separators".to_string());
+    }
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            Component::ParentDir => return Err("path must not contain `..`".to_string()),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err("path must be relative to the workspace root".to_string())
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Build the edit of one file of a multi-file patch.
+fn file_diff(lines: &[&str], index: usize) -> Result<FileEdit, CodeEditError> {
+    let header_path = |line: &str, prefix: &str| {
+        let path = line[4..].split('\t').next().unwrap_or_default().trim();
+        (path != "/dev/null").then(|| path.strip_prefix(prefix).unwrap_or(path).to_string())
+    };
+    let path = header_path(lines[1], "b/")
+        .or_else(|| header_path(lines[0], "a/"))
+        .ok_or_else(|| CodeEditError {
+            index,
+            path: String::new(),
+            message: "file headers name no file".to_string(),
+        })?;
+    Ok(FileEdit::diff(path, lines.join("\n")))
+}
+
+struct Hunk {
+    old_start: usize,
+    old: Vec<String>,
+    new: Vec<String>,
+}
+
+fn parse_hunks(diff: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks = Vec::new();
+    let mut current: Option<Hunk> = None;
+    let lines: Vec<&str> = diff.trim_end_matches('\n').lines().collect();
+    for line in lines {
+        if let Some(header) = line.strip_prefix("@@") {
+            hunks.extend(current.take());
+            let old_start = header
+                .trim()
+                .strip_prefix('-')
+                .and_then(|rest| rest.split([',', ' ']).next())
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| format!("malformed hunk header `{line}`"))?;
+            current = Some(Hunk {
+                old_start,
+                old: Vec::new(),
+                new: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = current.as_mut() else {
+            // Headers before the first hunk
+            continue;
+        };
+        match line.chars().next() {
+            Some(' ') | None => {
+                let text = line.get(1..).unwrap_or_default().to_string();
+                hunk.old.push(text.clone());
+                hunk.new.push(text);
+            }
+            Some('-') => hunk.old.push(line[1..].to_string()),
+            Some('+') => hunk.new.push(line[1..].to_string()),
+            Some('\\') => {}
+            // `diff --git`, `index` and similar lines end the hunk
+            Some(_) => hunks.extend(current.take()),
+        }
+    }
+    hunks.extend(current);
+    if hunks.is_empty() {
+        return Err("diff has no hunks".to_string());
+    }
+    Ok(hunks)
+}
+
+/// Apply a unified diff of one file to its content.
+fn apply_unified_diff(content: Option<&str>, diff: &str) -> Result<Option<String>, String> {
+    if diff.lines().any(|l| l.trim_end() == "+++ /dev/null") {
+        return match content {
+            Some(_) => Ok(None),
+            None => Err("file to delete does not exist".to_string()),
+        };
+    }
+    let hunks = parse_hunks(diff)?;
+    let mut lines: Vec<String> = content
+        .unwrap_or_default()
+        .lines()
+        .map(String::from)
+        .collect();
+    let trailing_newline = content.map_or(true, |c| c.is_empty() || c.ends_with('\n'));
+
+    let mut cursor = 0;
+    let mut delta: isize = 0;
+    for (n, hunk) in hunks.iter().enumerate() {
+        let expected = (hunk.old_start.saturating_sub(1) as isize + delta).max(0) as usize;
+        let at = find_block(&lines, &hunk.old, cursor, expected).ok_or_else(|| {
+            format!(
+                "hunk {} does not apply: its context was not found near line {}",
+                n + 1,
+                hunk.old_start
+            )
+        })?;
+        lines.splice(at..at + hunk.old.len(), hunk.new.iter().cloned());
+        cursor = at + hunk.new.len();
+        delta += hunk.new.len() as isize - hunk.old.len() as isize;
+    }
+
+    let mut updated = lines.join("\n");
+    if trailing_newline && !updated.is_empty() {
+        updated.push('\n');
+    }
+    Ok(Some(updated))
+}
+
+/// Find `block` in `lines` at or after `from`, closest to `expected`.
+///
+/// Trailing whitespace is ignored, since models often drop it.
+fn find_block(lines: &[String], block: &[String], from: usize, expected: usize) -> Option<usize> {
+    if block.is_empty() {
+        return Some(expected.clamp(from, lines.len()));
+    }
+    let matches_at = |at: usize| {
+        lines[at..at + block.len()]
+            .iter()
+            .zip(block)
+            .all(|(a, b)| a.trim_end() == b.trim_end())
+    };
+    let last = lines.len().checked_sub(block.len())?;
+    (from..=last)
+        .filter(|&at| matches_at(at))
+        .min_by_key(|&at| at.abs_diff(expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "fn a() {}\n\nfn b() {}\n\nfn c() {}\n";
+
+    #[test]
+    fn test_replace_edits() {
+        let snapshot = WorkspaceSnapshot::new().with_file("./src/lib.rs", LIB);
+        let edits = CodeEdits::new(vec![
+            FileEdit::replace("src/lib.rs", "fn b() {}", "fn b() -> u8 { 1 }"),
+            FileEdit::replace("src/new.rs", "", "pub mod x;\n"),
+        ]);
+        let updated = edits.apply(&snapshot).unwrap();
+        assert_eq!(
+            updated.get("src/lib.rs"),
+            Some("fn a() {}\n\nfn b() -> u8 { 1 }\n\nfn c() {}\n")
+        );
+        assert_eq!(updated.get("src/new.rs"), Some("pub mod x;\n"));
+
+        let ambiguous = CodeEdits::new(vec![FileEdit::replace("src/lib.rs", "fn", "pub fn")]);
+        let err = ambiguous.apply(&snapshot).unwrap_err();
+        assert!(err.message.contains("3 times"));
+    }
+
+    #[test]
+    fn test_paths_must_stay_in_workspace() {
+        for path in ["/etc/passwd", "../outside.rs", "src/../../x", ""] {
+            let edits = CodeEdits::new(vec![FileEdit::replace(path, "", "x")]);
+            assert!(edits.validate_paths().is_err(), "{path}");
+        }
+        let edits = CodeEdits::new(vec![FileEdit::replace("./src/ok.rs", "", "x")]);
+        assert!(edits.validate_paths().is_ok());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let snapshot = WorkspaceSnapshot::new().with_file("src/lib.rs", LIB);
+        let diff = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -3,3 +3,4 @@\n fn b() {}\n \n-fn c() {}\n+fn c() {\n+}\n";
+        let updated = CodeEdits::new(vec![FileEdit::diff("src/lib.rs", diff)])
+            .apply(&snapshot)
+            .unwrap();
+        assert_eq!(
+            updated.get("src/lib.rs"),
+            Some("fn a() {}\n\nfn b() {}\n\nfn c() {\n}\n")
+        );
+
+        // Line numbers that are off still apply if the context is found
+        let shifted = diff.replace("@@ -3,3", "@@ -1,3");
+        assert!(CodeEdits::new(vec![FileEdit::diff("src/lib.rs", shifted)])
+            .apply(&snapshot)
+            .is_ok());
+
+        let stale = diff.replace(" fn b() {}", " fn z() {}");
+        let errors = CodeEdits::new(vec![FileEdit::diff("src/lib.rs", stale)]).check(&snapshot);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("does not apply"));
+    }
+
+    #[test]
+    fn test_from_patch() {
+        let patch = "diff --git a/a.txt b/a.txt\nindex 1..2 100644\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\ndiff --git a/b.txt b/b.txt\n--- /dev/null\n+++ b/b.txt\n@@ -0,0 +1 @@\n+created\n--- a/c.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-gone\n";
+        let edits = CodeEdits::from_patch(patch).unwrap();
+        assert_eq!(edits.paths(), ["a.txt", "b.txt", "c.txt"]);
+
+        let snapshot: WorkspaceSnapshot = [("a.txt", "old\n"), ("c.txt", "gone\n")]
+            .into_iter()
+            .collect();
+        let updated = edits.apply(&snapshot).unwrap();
+        assert_eq!(updated.get("a.txt"), Some("new\n"));
+        assert_eq!(updated.get("b.txt"), Some("created\n"));
+        assert_eq!(updated.get("c.txt"), None);
+    }
+
+    #[test]
+    fn test_deserialize_edit_kinds() {
+        let edits: CodeEdits = serde_json::from_value(serde_json::json!({
+            "edits": [
+                {"path": "a.rs", "search": "x", "replace": "y"},
+                {"path": "b.rs", "diff": "@@ -1 +1 @@\n-a\n+b"}
+            ]
+        }))
+        .unwrap();
+        assert!(matches!(edits.edits[0], FileEdit::Replace { .. }));
+        assert!(matches!(edits.edits[1], FileEdit::Diff { .. }));
+    }
+}
@@ -0,0 +1,239 @@
+//! Encryption at rest for file-backed stores.
+//!
+//! Persisted graph state and conversations routinely contain user data, so
+//! file-backed stores can encrypt what they write with AES-256-GCM. Keys
+//! come from a [`KeyProvider`]; [`EnvKeyProvider`] reads a base64-encoded
+//! 32-byte key from an environment variable.
+//!
+//! Encrypted files start with a short header. Once a key is set, stores
+//! reject plaintext files unless plaintext migration is explicitly enabled,
+//! so files dropped into a store's directory can't inject data.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_graph::{EnvKeyProvider, FilePersistence};
+//!
+//! // SERDES_AI_ENCRYPTION_KEY=$(openssl rand -base64 32)
+//! let persistence = FilePersistence::new("./state")
+//!     .with_encryption(&EnvKeyProvider::default())?;
+//! ```
+
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+use thiserror::Error;
+
+/// Header of encrypted files.
+const MAGIC: &[u8] = b"SAIENC1\0";
+
+/// Environment variable read by [`EnvKeyProvider::default`].
+pub const DEFAULT_KEY_ENV: &str = "SERDES_AI_ENCRYPTION_KEY";
+
+/// Error loading a key, or encrypting or decrypting data.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct EncryptionError {
+    /// What went wrong.
+    pub message: String,
+}
+
+impl EncryptionError {
+    /// Create an error.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+/// A 256-bit encryption key.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Create a key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Decode a base64-encoded key.
+    pub fn from_base64(encoded: &str) -> Result<Self, EncryptionError> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| EncryptionError::new(format!("invalid base64 key: {e}")))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|b: Vec<u8>| {
+            EncryptionError::new(format!("key must be 32 bytes, got {}", b.len()))
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// Generate a random key.
+    pub fn generate() -> Result<Self, EncryptionError> {
+        let mut bytes = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| EncryptionError::new("failed to generate key"))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+/// Source of the key used to encrypt persisted state.
+pub trait KeyProvider: Send + Sync {
+    /// Get the encryption key.
+    fn key(&self) -> Result<EncryptionKey, EncryptionError>;
+}
+
+impl KeyProvider for EncryptionKey {
+    fn key(&self) -> Result<EncryptionKey, EncryptionError> {
+        Ok(self.clone())
+    }
+}
+
+/// Reads a base64-encoded key from an environment variable.
+#[derive(Debug, Clone)]
+pub struct EnvKeyProvider {
+    var: String,
+}
+
+impl EnvKeyProvider {
+    /// Read the key from the given variable.
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+}
+
+impl Default for EnvKeyProvider {
+    fn default() -> Self {
+        Self::new(DEFAULT_KEY_ENV)
+    }
+}
+
+impl KeyProvider for EnvKeyProvider {
+    fn key(&self) -> Result<EncryptionKey, EncryptionError> {
+        let encoded = std::env::var(&self.var).map_err(|_| {
+            EncryptionError::new(format!("environment variable {} is not set", self.var))
+        })?;
+        EncryptionKey::from_base64(&encoded)
+    }
+}
+
+/// AES-256-GCM cipher for files written by persistence stores.
+///
+/// Each file gets a fresh random nonce. The associated data binds the
+/// ciphertext to its file, so swapping encrypted files fails to decrypt.
+pub struct FileCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl FileCipher {
+    /// Create a cipher with the key from a provider.
+    pub fn new(provider: &dyn KeyProvider) -> Result<Self, EncryptionError> {
+        let key = provider.key()?;
+        let key = UnboundKey::new(&AES_256_GCM, &key.0)
+            .map_err(|_| EncryptionError::new("invalid AES-256 key"))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Check if data was written by [`encrypt`](Self::encrypt).
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    /// Encrypt data, binding it to `aad` (usually the file name).
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| EncryptionError::new("failed to generate nonce"))?;
+
+        let mut sealed = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut sealed,
+            )
+            .map_err(|_| EncryptionError::new("encryption failed"))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + sealed.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Decrypt data written by [`encrypt`](Self::encrypt) with the same `aad`.
+    pub fn decrypt(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let body = data
+            .strip_prefix(MAGIC)
+            .filter(|body| body.len() >= NONCE_LEN)
+            .ok_or_else(|| EncryptionError::new("not an encrypted file"))?;
+        let (nonce, sealed) = body.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| EncryptionError::new("invalid nonce"))?;
+
+        let mut sealed = sealed.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::from(aad), &mut sealed)
+            .map_err(|_| EncryptionError::new("decryption failed (wrong key or corrupt file)"))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+impl fmt::Debug for FileCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileCipher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = FileCipher::new(&EncryptionKey::generate().unwrap()).unwrap();
+        let sealed = cipher.encrypt(b"secret state", b"run_state.json").unwrap();
+
+        assert!(FileCipher::is_encrypted(&sealed));
+        assert!(!sealed.windows(6).any(|w| w == b"secret"));
+        assert_eq!(
+            cipher.decrypt(&sealed, b"run_state.json").unwrap(),
+            b"secret state"
+        );
+        // Bound to the file it was written to
+        assert!(cipher.decrypt(&sealed, b"other_state.json").is_err());
+
+        let other = FileCipher::new(&EncryptionKey::generate().unwrap()).unwrap();
+        assert!(other.decrypt(&sealed, b"run_state.json").is_err());
+    }
+
+    #[test]
+    fn test_env_key_provider() {
+        let var = "SERDES_AI_TEST_ENCRYPTION_KEY";
+        assert!(EnvKeyProvider::new(var).key().is_err());
+
+        std::env::set_var(var, "c2hvcnQ=");
+        assert!(EnvKeyProvider::new(var).key().is_err());
+
+        std::env::set_var(
+            var,
+            base64::engine::general_purpose::STANDARD.encode([7u8; 32]),
+        );
+        assert!(FileCipher::new(&EnvKeyProvider::new(var)).is_ok());
+        std::env::remove_var(var);
+    }
+}
@@ -2,13 +2,244 @@
 //!
 //! This module provides functions for generating unique identifiers
 //! for tool calls, runs, and other entities.
+//!
+//! IDs come from the current [`IdGenerator`], random UUIDs unless another
+//! generator is installed for the process with [`set_id_generator`] or for
+//! the current thread with [`use_id_generator`]. Sequential IDs make tests
+//! deterministic:
+//!
+//! ```rust
+//! use serdes_ai_core::identifier::{generate_run_id, use_id_generator, SequentialIds};
+//!
+//! let _guard = use_id_generator(SequentialIds::new());
+//! assert_eq!(generate_run_id(), "run_1");
+//! assert_eq!(generate_run_id(), "run_2");
+//! ```
 
 use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
 use uuid::Uuid;
 
+/// Kind of entity an ID is generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdKind {
+    /// A tool call.
+    ToolCall,
+    /// An agent run.
+    Run,
+    /// A message.
+    Message,
+    /// A conversation.
+    Conversation,
+    /// A part of a streamed model response.
+    Part,
+}
+
+impl IdKind {
+    const ALL: [IdKind; 5] = [
+        IdKind::ToolCall,
+        IdKind::Run,
+        IdKind::Message,
+        IdKind::Conversation,
+        IdKind::Part,
+    ];
+
+    /// Get the default prefix for IDs of this kind.
+    #[must_use]
+    pub fn default_prefix(self) -> &'static str {
+        match self {
+            IdKind::ToolCall => "call_",
+            IdKind::Run => "run_",
+            IdKind::Message => "msg_",
+            IdKind::Conversation => "conv_",
+            IdKind::Part => "part_",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Source of unique identifiers.
+pub trait IdGenerator: Send + Sync + fmt::Debug {
+    /// Generate an ID for an entity of the given kind.
+    fn generate(&self, kind: IdKind) -> String;
+}
+
+/// Per-kind ID prefixes.
+#[derive(Debug, Clone)]
+struct Prefixes([String; 5]);
+
+impl Default for Prefixes {
+    fn default() -> Self {
+        Self(IdKind::ALL.map(|kind| kind.default_prefix().to_string()))
+    }
+}
+
+impl Prefixes {
+    fn get(&self, kind: IdKind) -> &str {
+        &self.0[kind.index()]
+    }
+}
+
+/// UUID version used by [`UuidIds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UuidVersion {
+    V4,
+    V7,
+}
+
+/// Prefixed UUIDs, e.g. `run_0190b5…`.
+///
+/// Random (v4) by default; [`v7`](Self::v7) IDs sort by creation time.
+#[derive(Debug, Clone)]
+pub struct UuidIds {
+    version: UuidVersion,
+    prefixes: Prefixes,
+}
+
+impl UuidIds {
+    /// Create a generator of random (v4) UUIDs.
+    #[must_use]
+    pub fn v4() -> Self {
+        Self {
+            version: UuidVersion::V4,
+            prefixes: Prefixes::default(),
+        }
+    }
+
+    /// Create a generator of time-ordered (v7) UUIDs.
+    #[must_use]
+    pub fn v7() -> Self {
+        Self {
+            version: UuidVersion::V7,
+            prefixes: Prefixes::default(),
+        }
+    }
+
+    /// Set the prefix of IDs of one kind.
+    #[must_use]
+    pub fn with_prefix(mut self, kind: IdKind, prefix: impl Into<String>) -> Self {
+        self.prefixes.0[kind.index()] = prefix.into();
+        self
+    }
+}
+
+impl Default for UuidIds {
+    fn default() -> Self {
+        Self::v4()
+    }
+}
+
+impl IdGenerator for UuidIds {
+    fn generate(&self, kind: IdKind) -> String {
+        let uuid = match self.version {
+            UuidVersion::V4 => Uuid::new_v4(),
+            UuidVersion::V7 => Uuid::now_v7(),
+        };
+        format!("{}{}", self.prefixes.get(kind), uuid.simple())
+    }
+}
+
+/// Prefixed counters, e.g. `run_1`, `call_1`, `call_2`.
+///
+/// Each kind counts from 1 on its own. Meant for tests and replays.
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    counters: [AtomicU64; 5],
+    prefixes: Prefixes,
+}
+
+impl SequentialIds {
+    /// Create a generator counting from 1.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the prefix of IDs of one kind.
+    #[must_use]
+    pub fn with_prefix(mut self, kind: IdKind, prefix: impl Into<String>) -> Self {
+        self.prefixes.0[kind.index()] = prefix.into();
+        self
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn generate(&self, kind: IdKind) -> String {
+        let n = self.counters[kind.index()].fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{}{}", self.prefixes.get(kind), n)
+    }
+}
+
+static PROCESS_ID_GENERATOR: RwLock<Option<Arc<dyn IdGenerator>>> = RwLock::new(None);
+
+thread_local! {
+    static THREAD_ID_GENERATOR: RefCell<Option<Arc<dyn IdGenerator>>> = const { RefCell::new(None) };
+}
+
+/// Install an ID generator for the whole process.
+pub fn set_id_generator(generator: impl IdGenerator + 'static) {
+    *PROCESS_ID_GENERATOR
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(generator));
+}
+
+/// Go back to random UUIDs for the whole process.
+pub fn reset_id_generator() {
+    *PROCESS_ID_GENERATOR
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Install an ID generator for the current thread until the guard is dropped.
+#[must_use = "the generator is uninstalled when the guard is dropped"]
+pub fn use_id_generator(generator: impl IdGenerator + 'static) -> IdGeneratorGuard {
+    let previous = THREAD_ID_GENERATOR.with(|g| g.borrow_mut().replace(Arc::new(generator)));
+    IdGeneratorGuard { previous }
+}
+
+/// Restores the previous thread ID generator when dropped.
+#[derive(Debug)]
+pub struct IdGeneratorGuard {
+    previous: Option<Arc<dyn IdGenerator>>,
+}
+
+impl Drop for IdGeneratorGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        THREAD_ID_GENERATOR.with(|g| *g.borrow_mut() = previous);
+    }
+}
+
+/// Get the current ID generator.
+#[must_use]
+pub fn id_generator() -> Arc<dyn IdGenerator> {
+    THREAD_ID_GENERATOR
+        .with(|g| g.borrow().clone())
+        .or_else(|| {
+            PROCESS_ID_GENERATOR
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        })
+        .unwrap_or_else(|| Arc::new(UuidIds::v4()))
+}
+
+/// Generate an ID of the given kind with the current generator.
+#[must_use]
+pub fn generate_id(kind: IdKind) -> String {
+    id_generator().generate(kind)
+}
+
 /// Generate a unique tool call ID.
 ///
-/// Returns a UUID v4 string in the format used by most LLM providers.
+/// Returns a UUID v4 string in the format used by most LLM providers,
+/// unless another [`IdGenerator`] is installed.
 ///
 /// # Example
 ///
@@ -21,7 +252,7 @@ use uuid::Uuid;
 /// ```
 #[must_use]
 pub fn generate_tool_call_id() -> String {
-    format!("call_{}", Uuid::new_v4().simple())
+    generate_id(IdKind::ToolCall)
 }
 
 /// Generate a unique run ID.
@@ -38,7 +269,7 @@ pub fn generate_tool_call_id() -> String {
 /// ```
 #[must_use]
 pub fn generate_run_id() -> String {
-    format!("run_{}", Uuid::new_v4().simple())
+    generate_id(IdKind::Run)
 }
 
 /// Generate a unique message ID.
@@ -46,7 +277,7 @@ pub fn generate_run_id() -> String {
 /// Returns a UUID v4 string prefixed with "msg_".
 #[must_use]
 pub fn generate_message_id() -> String {
-    format!("msg_{}", Uuid::new_v4().simple())
+    generate_id(IdKind::Message)
 }
 
 /// Generate a unique conversation ID.
@@ -54,7 +285,15 @@ pub fn generate_message_id() -> String {
 /// Returns a UUID v4 string prefixed with "conv_".
 #[must_use]
 pub fn generate_conversation_id() -> String {
-    format!("conv_{}", Uuid::new_v4().simple())
+    generate_id(IdKind::Conversation)
+}
+
+/// Generate a unique part ID for a streamed response part.
+///
+/// Returns a UUID v4 string prefixed with "part_".
+#[must_use]
+pub fn generate_part_id() -> String {
+    generate_id(IdKind::Part)
 }
 
 /// Generate a raw UUID v4 string (no prefix).
@@ -73,6 +312,9 @@ pub fn generate_short_id() -> String {
 
 /// Get the current UTC timestamp.
 ///
+/// Reads the current [`Clock`](crate::clock::Clock), the system clock
+/// unless another one is installed.
+///
 /// # Example
 ///
 /// ```rust
@@ -83,7 +325,7 @@ pub fn generate_short_id() -> String {
 /// ```
 #[must_use]
 pub fn now_utc() -> DateTime<Utc> {
-    Utc::now()
+    crate::clock::now()
 }
 
 /// Parse a timestamp from an ISO 8601 string.
@@ -308,6 +550,33 @@ mod tests {
         assert!((now - parsed).num_seconds().abs() <= 1);
     }
 
+    #[test]
+    fn test_sequential_ids() {
+        let ids = SequentialIds::new().with_prefix(IdKind::Run, "acme-run-");
+        assert_eq!(ids.generate(IdKind::Run), "acme-run-1");
+        assert_eq!(ids.generate(IdKind::ToolCall), "call_1");
+        assert_eq!(ids.generate(IdKind::Run), "acme-run-2");
+    }
+
+    #[test]
+    fn test_uuid_v7_ids_sort_by_time() {
+        let ids = UuidIds::v7().with_prefix(IdKind::Message, "m-");
+        let first = ids.generate(IdKind::Message);
+        let second = ids.generate(IdKind::Message);
+        assert!(first.starts_with("m-"));
+        assert!(first < second);
+    }
+
+    #[test]
+    fn test_thread_id_generator() {
+        {
+            let _guard = use_id_generator(SequentialIds::new());
+            assert_eq!(ToolCallId::new().as_str(), "call_1");
+            assert_eq!(generate_conversation_id(), "conv_1");
+        }
+        assert_eq!(generate_run_id().len(), 36);
+    }
+
     #[test]
     fn test_serde_roundtrip() {
         let id = ToolCallId::new();
@@ -9,11 +9,17 @@
 //! - **Usage**: Token usage tracking and limits
 //! - **Settings**: Model configuration options
 //! - **Identifiers**: Type-safe IDs for conversations, messages, runs
+//! - **Trace context**: W3C `traceparent` propagation across HTTP calls
+//! - **Artifacts**: Content-addressed storage for generated files
+//! - **Code edits**: Structured multi-file edits for coding agents
+//! - **Encryption**: AES-256-GCM encryption at rest for file-backed stores
 //!
 //! ## Feature Flags
 //!
 //! - `tracing-integration`: Enable tracing instrumentation
-//! - `otel`: Enable OpenTelemetry integration
+//! - `otel`: Enable OpenTelemetry integration, including `traceparent`
+//!   headers on outgoing provider and MCP requests
+//! - `encryption`: Enable encryption at rest for file-backed stores
 //! - `full`: Enable all optional features
 //!
 //! ## Example
@@ -50,17 +56,28 @@
 #![deny(unsafe_code)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod artifacts;
+pub mod clock;
+pub mod code_edits;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod errors;
 pub mod format;
 pub mod identifier;
 pub mod messages;
 pub mod settings;
+pub mod trace_context;
 pub mod usage;
 
 // Re-exports for convenience
+pub use artifacts::{ArtifactRef, ArtifactStore};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use code_edits::{CodeEditError, CodeEdits, FileEdit, WorkspaceSnapshot};
 pub use errors::{Result, SerdesAiError};
 pub use format::{format_as_xml, format_as_xml_with_options, XmlFormatError, XmlFormatOptions};
-pub use identifier::{now_utc, ConversationId, RunId, ToolCallId};
+pub use identifier::{
+    now_utc, ConversationId, IdGenerator, IdKind, RunId, SequentialIds, ToolCallId, UuidIds,
+};
 pub use messages::{
     BinaryContent,
     // Builtin tools (web search, code execution, file search)
@@ -97,7 +114,8 @@ pub use messages::{
     WebSearchResult,
     WebSearchResults,
 };
-pub use settings::ModelSettings;
+pub use settings::{ApiKey, ModelSettings};
+pub use trace_context::TraceContext;
 pub use usage::{RequestUsage, RunUsage, UsageLimits};
 
 /// Prelude module for common imports.
@@ -143,6 +161,6 @@ pub mod prelude {
         WebSearchResult,
         WebSearchResults,
     };
-    pub use crate::settings::ModelSettings;
+    pub use crate::settings::{ApiKey, ModelSettings};
     pub use crate::usage::{RequestUsage, RunUsage, UsageLimits};
 }
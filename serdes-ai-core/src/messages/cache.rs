@@ -3,6 +3,7 @@
 //! This module defines the cache point marker that can be inserted into
 //! message sequences to indicate caching boundaries.
 
+use crate::identifier::now_utc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -27,7 +28,7 @@ impl CachePoint {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
             cache_type: None,
         }
     }
@@ -27,45 +27,43 @@ impl ModelResponseStreamEvent {
     /// Create a part start event.
     #[must_use]
     pub fn part_start(index: usize, part: ModelResponsePart) -> Self {
-        Self::PartStart(PartStartEvent { index, part })
+        Self::PartStart(PartStartEvent::new(index, part))
     }
 
     /// Create a text delta event.
     #[must_use]
     pub fn text_delta(index: usize, content_delta: impl Into<String>) -> Self {
-        Self::PartDelta(PartDeltaEvent {
+        Self::PartDelta(PartDeltaEvent::new(
             index,
-            delta: ModelResponsePartDelta::Text(TextPartDelta::new(content_delta)),
-        })
+            ModelResponsePartDelta::Text(TextPartDelta::new(content_delta)),
+        ))
     }
 
     /// Create a tool call delta event.
     #[must_use]
     pub fn tool_call_delta(index: usize, args_delta: impl Into<String>) -> Self {
-        Self::PartDelta(PartDeltaEvent {
+        Self::PartDelta(PartDeltaEvent::new(
             index,
-            delta: ModelResponsePartDelta::ToolCall(ToolCallPartDelta::new(args_delta)),
-        })
+            ModelResponsePartDelta::ToolCall(ToolCallPartDelta::new(args_delta)),
+        ))
     }
 
     /// Create a thinking delta event.
     #[must_use]
     pub fn thinking_delta(index: usize, content_delta: impl Into<String>) -> Self {
-        Self::PartDelta(PartDeltaEvent {
+        Self::PartDelta(PartDeltaEvent::new(
             index,
-            delta: ModelResponsePartDelta::Thinking(ThinkingPartDelta::new(content_delta)),
-        })
+            ModelResponsePartDelta::Thinking(ThinkingPartDelta::new(content_delta)),
+        ))
     }
 
     /// Create a builtin tool call delta event.
     #[must_use]
     pub fn builtin_tool_call_delta(index: usize, args_delta: impl Into<String>) -> Self {
-        Self::PartDelta(PartDeltaEvent {
+        Self::PartDelta(PartDeltaEvent::new(
             index,
-            delta: ModelResponsePartDelta::BuiltinToolCall(BuiltinToolCallPartDelta::new(
-                args_delta,
-            )),
-        })
+            ModelResponsePartDelta::BuiltinToolCall(BuiltinToolCallPartDelta::new(args_delta)),
+        ))
     }
 
     /// Create a file part start event.
@@ -74,25 +72,22 @@ impl ModelResponseStreamEvent {
     /// with the full file content.
     #[must_use]
     pub fn file_part(index: usize, part: FilePart) -> Self {
-        Self::PartStart(PartStartEvent {
-            index,
-            part: ModelResponsePart::File(part),
-        })
+        Self::PartStart(PartStartEvent::new(index, ModelResponsePart::File(part)))
     }
 
     /// Create a builtin tool call start event.
     #[must_use]
     pub fn builtin_tool_call_start(index: usize, part: BuiltinToolCallPart) -> Self {
-        Self::PartStart(PartStartEvent {
+        Self::PartStart(PartStartEvent::new(
             index,
-            part: ModelResponsePart::BuiltinToolCall(part),
-        })
+            ModelResponsePart::BuiltinToolCall(part),
+        ))
     }
 
     /// Create a part end event.
     #[must_use]
     pub fn part_end(index: usize) -> Self {
-        Self::PartEnd(PartEndEvent { index })
+        Self::PartEnd(PartEndEvent::new(index))
     }
 
     /// Get the part index.
@@ -105,6 +100,28 @@ impl ModelResponseStreamEvent {
         }
     }
 
+    /// Get the stable ID of the part, if one was assigned.
+    #[must_use]
+    pub fn part_id(&self) -> Option<&str> {
+        match self {
+            Self::PartStart(e) => e.part_id.as_deref(),
+            Self::PartDelta(e) => e.part_id.as_deref(),
+            Self::PartEnd(e) => e.part_id.as_deref(),
+        }
+    }
+
+    /// Set the stable ID of the part.
+    #[must_use]
+    pub fn with_part_id(mut self, id: impl Into<String>) -> Self {
+        let id = Some(id.into());
+        match &mut self {
+            Self::PartStart(e) => e.part_id = id,
+            Self::PartDelta(e) => e.part_id = id,
+            Self::PartEnd(e) => e.part_id = id,
+        }
+        self
+    }
+
     /// Check if this is a start event.
     #[must_use]
     pub fn is_start(&self) -> bool {
@@ -131,13 +148,27 @@ pub struct PartStartEvent {
     pub index: usize,
     /// The initial part data.
     pub part: ModelResponsePart,
+    /// Stable ID of the part, shared by all events for it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_id: Option<String>,
 }
 
 impl PartStartEvent {
     /// Create a new part start event.
     #[must_use]
     pub fn new(index: usize, part: ModelResponsePart) -> Self {
-        Self { index, part }
+        Self {
+            index,
+            part,
+            part_id: None,
+        }
+    }
+
+    /// Set the stable part ID.
+    #[must_use]
+    pub fn with_part_id(mut self, id: impl Into<String>) -> Self {
+        self.part_id = Some(id.into());
+        self
     }
 
     /// Create a text part start.
@@ -191,13 +222,27 @@ pub struct PartDeltaEvent {
     pub index: usize,
     /// The delta content.
     pub delta: ModelResponsePartDelta,
+    /// Stable ID of the part, shared by all events for it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_id: Option<String>,
 }
 
 impl PartDeltaEvent {
     /// Create a new delta event.
     #[must_use]
     pub fn new(index: usize, delta: ModelResponsePartDelta) -> Self {
-        Self { index, delta }
+        Self {
+            index,
+            delta,
+            part_id: None,
+        }
+    }
+
+    /// Set the stable part ID.
+    #[must_use]
+    pub fn with_part_id(mut self, id: impl Into<String>) -> Self {
+        self.part_id = Some(id.into());
+        self
     }
 
     /// Create a text delta.
@@ -639,17 +684,30 @@ impl Default for BuiltinToolCallPartDelta {
 }
 
 /// Event indicating a part has ended.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PartEndEvent {
     /// Index of the part that ended.
     pub index: usize,
+    /// Stable ID of the part, shared by all events for it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_id: Option<String>,
 }
 
 impl PartEndEvent {
     /// Create a new part end event.
     #[must_use]
     pub fn new(index: usize) -> Self {
-        Self { index }
+        Self {
+            index,
+            part_id: None,
+        }
+    }
+
+    /// Set the stable part ID.
+    #[must_use]
+    pub fn with_part_id(mut self, id: impl Into<String>) -> Self {
+        self.part_id = Some(id.into());
+        self
     }
 }
 
@@ -0,0 +1,454 @@
+//! Repair of malformed JSON emitted by models.
+//!
+//! Models sometimes produce JSON-ish tool call arguments with trailing
+//! commas, unquoted keys or missing closing braces. [`JsonRepairer`] fixes
+//! these according to a [`JsonRepairPolicy`], optionally followed by custom
+//! [`JsonRepairPass`]es.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use serdes_ai_core::messages::{JsonRepairPolicy, JsonRepairer};
+//!
+//! let repairer = JsonRepairer::new(JsonRepairPolicy::Conservative);
+//! let repaired = repairer.repair(r#"{"a": 1,"#).unwrap();
+//! assert_eq!(repaired.value["a"], 1);
+//! assert_eq!(repaired.passes, vec!["close_brackets", "trailing_commas"]);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// How aggressively malformed JSON is repaired.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonRepairPolicy {
+    /// Never repair. Custom passes don't run either.
+    Off,
+    /// Only structural fixes: trailing commas and unclosed braces/brackets.
+    Conservative,
+    /// All built-in fixes, including quoting keys and replacing single quotes.
+    #[default]
+    Aggressive,
+}
+
+/// A custom repair pass.
+///
+/// Passes run in registration order after the built-in repairs, and only
+/// while the input still fails to parse. Closures of type
+/// `Fn(&str) -> Option<String>` implement this trait.
+pub trait JsonRepairPass: Send + Sync {
+    /// Name reported when the pass changed the input.
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Rewrite the input, or return `None` if the pass doesn't apply.
+    fn repair(&self, input: &str) -> Option<String>;
+}
+
+impl<F> JsonRepairPass for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn repair(&self, input: &str) -> Option<String> {
+        self(input)
+    }
+}
+
+/// A named custom repair pass.
+struct NamedPass<F> {
+    name: String,
+    f: F,
+}
+
+impl<F> JsonRepairPass for NamedPass<F>
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn repair(&self, input: &str) -> Option<String> {
+        (self.f)(input)
+    }
+}
+
+/// Result of a successful repair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepairedJson {
+    /// The parsed value.
+    pub value: serde_json::Value,
+    /// Names of the passes that changed the input, in order.
+    ///
+    /// Empty if the input was already valid JSON.
+    pub passes: Vec<String>,
+}
+
+impl RepairedJson {
+    /// Check whether any repair was applied.
+    #[must_use]
+    pub fn is_repaired(&self) -> bool {
+        !self.passes.is_empty()
+    }
+}
+
+/// Repairs malformed JSON according to a policy and custom passes.
+#[derive(Clone, Default)]
+pub struct JsonRepairer {
+    policy: JsonRepairPolicy,
+    passes: Vec<Arc<dyn JsonRepairPass>>,
+}
+
+impl fmt::Debug for JsonRepairer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonRepairer")
+            .field("policy", &self.policy)
+            .field(
+                "passes",
+                &self.passes.iter().map(|p| p.name()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl JsonRepairer {
+    /// Create a repairer with the given policy.
+    #[must_use]
+    pub fn new(policy: JsonRepairPolicy) -> Self {
+        Self {
+            policy,
+            passes: Vec::new(),
+        }
+    }
+
+    /// Set the policy.
+    #[must_use]
+    pub fn with_policy(mut self, policy: JsonRepairPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Add a custom repair pass.
+    #[must_use]
+    pub fn with_pass(mut self, pass: impl JsonRepairPass + 'static) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+
+    /// Add a custom repair pass from a closure, with a name for diagnostics.
+    #[must_use]
+    pub fn with_named_pass<F>(self, name: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.with_pass(NamedPass {
+            name: name.into(),
+            f,
+        })
+    }
+
+    /// Get the policy.
+    #[must_use]
+    pub fn policy(&self) -> JsonRepairPolicy {
+        self.policy
+    }
+
+    /// Parse `input`, repairing it if needed.
+    ///
+    /// Returns `None` if the input can't be parsed even after repair.
+    #[must_use]
+    pub fn repair(&self, input: &str) -> Option<RepairedJson> {
+        let input = input.trim();
+
+        if let Ok(value) = serde_json::from_str(input) {
+            return Some(RepairedJson {
+                value,
+                passes: Vec::new(),
+            });
+        }
+        if self.policy == JsonRepairPolicy::Off {
+            return None;
+        }
+
+        let mut current = input.to_string();
+        let mut passes = Vec::new();
+
+        apply(&mut current, &mut passes, "close_brackets", close_brackets);
+        apply(
+            &mut current,
+            &mut passes,
+            "trailing_commas",
+            remove_trailing_commas,
+        );
+        if self.policy == JsonRepairPolicy::Aggressive {
+            apply(
+                &mut current,
+                &mut passes,
+                "unquoted_keys",
+                quote_unquoted_keys,
+            );
+            // Only if no double quotes are present
+            if current.contains('\'') && !current.contains('"') {
+                apply(&mut current, &mut passes, "single_quotes", |s| {
+                    s.replace('\'', "\"")
+                });
+            }
+        }
+
+        if let Ok(value) = serde_json::from_str(&current) {
+            return Some(RepairedJson { value, passes });
+        }
+
+        for pass in &self.passes {
+            if let Some(repaired) = pass.repair(&current) {
+                apply(&mut current, &mut passes, pass.name(), |_| repaired);
+                if let Ok(value) = serde_json::from_str(&current) {
+                    return Some(RepairedJson { value, passes });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Apply one repair step, recording its name if it changed the input.
+fn apply(
+    current: &mut String,
+    passes: &mut Vec<String>,
+    name: &str,
+    step: impl FnOnce(&str) -> String,
+) {
+    let repaired = step(current);
+    if repaired != *current {
+        *current = repaired;
+        passes.push(name.to_string());
+    }
+}
+
+/// Close unclosed braces and brackets, innermost first.
+/// E.g., `{"a": [1, 2` -> `{"a": [1, 2]}`
+fn close_brackets(s: &str) -> String {
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in s.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => open.push('}'),
+            '[' => open.push(']'),
+            '}' | ']' => {
+                open.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut repaired = s.to_string();
+    repaired.extend(open.into_iter().rev());
+    repaired
+}
+
+/// Remove trailing commas before `}` or `]`.
+/// E.g., `{"a": 1,}` -> `{"a": 1}`
+fn remove_trailing_commas(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let chars: Vec<char> = s.chars().collect();
+    let len = chars.len();
+
+    let mut i = 0;
+    while i < len {
+        let c = chars[i];
+        if c == ',' {
+            // Look ahead for whitespace followed by } or ]
+            let mut j = i + 1;
+            while j < len && chars[j].is_whitespace() {
+                j += 1;
+            }
+            if j < len && (chars[j] == '}' || chars[j] == ']') {
+                // Skip this comma
+                i += 1;
+                continue;
+            }
+        }
+        result.push(c);
+        i += 1;
+    }
+    result
+}
+
+/// Attempt to quote unquoted keys in JSON-like strings.
+/// E.g., `{foo: "bar"}` -> `{"foo": "bar"}`
+fn quote_unquoted_keys(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 32);
+    let chars: Vec<char> = s.chars().collect();
+    let len = chars.len();
+
+    let mut i = 0;
+    while i < len {
+        let c = chars[i];
+
+        // After { or , we might have an unquoted key
+        if c == '{' || c == ',' {
+            result.push(c);
+            i += 1;
+
+            // Skip whitespace
+            while i < len && chars[i].is_whitespace() {
+                result.push(chars[i]);
+                i += 1;
+            }
+
+            // Check if we have an unquoted identifier followed by :
+            if i < len && is_ident_start(chars[i]) {
+                let key_start = i;
+                while i < len && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                let key = &s[key_start..i];
+
+                // Skip whitespace after key
+                while i < len && chars[i].is_whitespace() {
+                    i += 1;
+                }
+
+                // If followed by :, this was an unquoted key
+                if i < len && chars[i] == ':' {
+                    result.push('"');
+                    result.push_str(key);
+                    result.push('"');
+                } else {
+                    // Not a key, just push what we read
+                    result.push_str(key);
+                }
+            }
+        } else {
+            result.push(c);
+            i += 1;
+        }
+    }
+    result
+}
+
+/// Check if a character can start an identifier.
+#[inline]
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Check if a character can be part of an identifier.
+#[inline]
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repair_json(s: &str) -> Option<serde_json::Value> {
+        JsonRepairer::default().repair(s).map(|r| r.value)
+    }
+
+    #[test]
+    fn test_repair_json_valid_passthrough() {
+        // Already valid JSON should pass through
+        let result = repair_json(r#"{"valid": true}"#);
+        assert!(result.is_some());
+        assert_eq!(result.unwrap()["valid"], true);
+    }
+
+    #[test]
+    fn test_repair_json_nested_trailing_comma() {
+        let result = repair_json(r#"{"outer": {"inner": 1,},}"#);
+        assert!(result.is_some());
+        let v = result.unwrap();
+        assert_eq!(v["outer"]["inner"], 1);
+    }
+
+    #[test]
+    fn test_repair_json_array_trailing_comma() {
+        let result = repair_json(r#"[1, 2, 3,]"#);
+        assert!(result.is_some());
+        assert_eq!(result.unwrap(), serde_json::json!([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_repair_json_multiple_unquoted_keys() {
+        let result = repair_json(r#"{foo: 1, bar: 2}"#);
+        assert!(result.is_some());
+        let v = result.unwrap();
+        assert_eq!(v["foo"], 1);
+        assert_eq!(v["bar"], 2);
+    }
+
+    #[test]
+    fn test_remove_trailing_commas_helper() {
+        assert_eq!(remove_trailing_commas("{\"a\": 1,}"), "{\"a\": 1}");
+        assert_eq!(remove_trailing_commas("[1, 2,]"), "[1, 2]");
+        assert_eq!(remove_trailing_commas("{\"a\": 1,  }"), "{\"a\": 1  }");
+    }
+
+    #[test]
+    fn test_quote_unquoted_keys_helper() {
+        assert_eq!(quote_unquoted_keys("{foo: 1}"), "{\"foo\": 1}");
+        assert_eq!(
+            quote_unquoted_keys("{foo: 1, bar: 2}"),
+            "{\"foo\": 1, \"bar\": 2}"
+        );
+    }
+
+    #[test]
+    fn test_repair_reports_passes() {
+        let repaired = JsonRepairer::default().repair("{'a': 1,}").unwrap();
+        assert_eq!(repaired.value["a"], 1);
+        assert_eq!(repaired.passes, vec!["trailing_commas", "single_quotes"]);
+
+        let valid = JsonRepairer::default().repair(r#"{"a": 1}"#).unwrap();
+        assert!(!valid.is_repaired());
+    }
+
+    #[test]
+    fn test_policy_off() {
+        let repairer = JsonRepairer::new(JsonRepairPolicy::Off);
+        assert!(repairer.repair(r#"{"a": 1,}"#).is_none());
+        assert!(repairer.repair(r#"{"a": 1}"#).is_some());
+    }
+
+    #[test]
+    fn test_policy_conservative() {
+        let repairer = JsonRepairer::new(JsonRepairPolicy::Conservative);
+        assert!(repairer.repair(r#"{"a": [1, 2,"#).is_some());
+        assert!(repairer.repair(r#"{foo: 1}"#).is_none());
+    }
+
+    #[test]
+    fn test_custom_pass() {
+        let repairer =
+            JsonRepairer::new(JsonRepairPolicy::Conservative).with_named_pass("strip_fence", |s| {
+                s.strip_prefix("```json")
+                    .and_then(|s| s.strip_suffix("```"))
+                    .map(str::to_string)
+            });
+        let repaired = repairer.repair("```json{\"a\": 1}```").unwrap();
+        assert_eq!(repaired.value["a"], 1);
+        assert_eq!(repaired.passes, vec!["strip_fence"]);
+
+        // Custom passes don't run when repair is off
+        let off = repairer.with_policy(JsonRepairPolicy::Off);
+        assert!(off.repair("```json{\"a\": 1}```").is_none());
+    }
+}
@@ -8,6 +8,7 @@
 //! - **Tool types**: [`ToolCallPart`], [`ToolReturnPart`], and related
 //! - **Streaming**: [`ModelResponseStreamEvent`] and delta types
 //! - **Caching**: [`CachePoint`] for prompt caching
+//! - **Interop**: [`pydantic`] conversion to and from pydantic-ai message JSON
 //!
 //! ## Example
 //!
@@ -30,8 +31,10 @@
 pub mod cache;
 pub mod content;
 pub mod events;
+pub mod json_repair;
 pub mod media;
 pub mod parts;
+pub mod pydantic;
 pub mod request;
 pub mod response;
 pub mod tool_return;
@@ -47,15 +50,17 @@ pub use events::{
     BuiltinToolCallPartDelta, ModelResponsePartDelta, ModelResponseStreamEvent, PartDeltaEvent,
     PartEndEvent, PartStartEvent, TextPartDelta, ThinkingPartDelta, ToolCallPartDelta,
 };
+pub use json_repair::{JsonRepairPass, JsonRepairPolicy, JsonRepairer, RepairedJson};
 pub use media::{AudioMediaType, DocumentMediaType, ImageMediaType, VideoMediaType};
 pub use parts::{
     BinaryContent, BuiltinToolCallPart, BuiltinToolReturnContent, BuiltinToolReturnPart,
     CodeExecutionResult, FilePart, FileSearchResult, FileSearchResults, TextPart, ThinkingPart,
     ToolCallArgs, ToolCallPart, WebSearchResult, WebSearchResults,
 };
+pub use pydantic::{from_pydantic_json, from_pydantic_str, to_pydantic_json, PydanticWireError};
 pub use request::{
-    ModelRequest, ModelRequestPart, RetryContent, RetryPromptPart, SystemPromptPart,
-    ToolReturnPart, UserPromptPart,
+    instructions_as_system_prompt, latest_instructions, ModelRequest, ModelRequestPart,
+    RetryContent, RetryPromptPart, SystemPromptPart, ToolReturnPart, UserPromptPart,
 };
 pub use response::{FinishReason, ModelResponse, ModelResponsePart};
 pub use tool_return::{ToolReturn, ToolReturnContent, ToolReturnError, ToolReturnItem};
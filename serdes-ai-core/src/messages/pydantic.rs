@@ -0,0 +1,967 @@
+//! Wire compatibility with pydantic-ai message JSON.
+//!
+//! pydantic-ai serializes conversations with `ModelMessagesTypeAdapter` as a
+//! flat list of `request` and `response` messages. The native serde format of
+//! this crate differs in a few places (content tags, usage field names, tool
+//! return content, and model responses nested inside requests), so this module
+//! converts between the two:
+//!
+//! - [`to_pydantic_json`] turns a history into the JSON pydantic-ai produces
+//! - [`from_pydantic_json`] reads JSON produced by pydantic-ai into a history
+//!
+//! ```rust
+//! use serdes_ai_core::messages::pydantic::{from_pydantic_json, to_pydantic_json};
+//! use serdes_ai_core::messages::{ModelRequest, ModelRequestPart, ModelResponse};
+//!
+//! let mut request = ModelRequest::new();
+//! request.add_user_prompt("Hello!");
+//! let response =
+//!     ModelRequest::with_parts(vec![ModelRequestPart::ModelResponse(Box::new(
+//!         ModelResponse::text("Hi there!"),
+//!     ))]);
+//!
+//! let json = to_pydantic_json(&[request, response]);
+//! assert_eq!(json[0]["kind"], "request");
+//! assert_eq!(json[1]["parts"][0]["part_kind"], "text");
+//!
+//! let history = from_pydantic_json(&json).unwrap();
+//! assert_eq!(history.len(), 2);
+//! ```
+//!
+//! Fields pydantic-ai has no equivalent for (such as response alternatives and
+//! `provider_details` on parts) are dropped on export. Builtin tool returns,
+//! which pydantic-ai keeps in the response that made the call, are moved
+//! there on export and back into the request on import.
+
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Timelike, Utc};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use super::content::{
+    AudioContent, AudioUrl, BinaryAudio, BinaryDocument, BinaryFile, BinaryImage, BinaryVideo,
+    DocumentContent, DocumentUrl, FileContent, FileUrl, ImageContent, ImageUrl, UserContent,
+    UserContentPart, VideoContent, VideoUrl,
+};
+use super::parts::{
+    BinaryContent, BuiltinToolCallPart, BuiltinToolReturnContent, BuiltinToolReturnPart, FilePart,
+    TextPart, ThinkingPart, ToolCallArgs, ToolCallPart,
+};
+use super::request::{
+    ModelRequest, ModelRequestPart, RetryContent, RetryPromptPart, SystemPromptPart,
+    ToolReturnPart, UserPromptPart,
+};
+use super::response::{FinishReason, ModelResponse, ModelResponsePart};
+use super::tool_return::{ToolReturnContent, ToolReturnItem};
+use crate::identifier::now_utc;
+use crate::usage::RequestUsage;
+
+/// Error reading pydantic-ai message JSON.
+#[derive(Debug, Error)]
+pub enum PydanticWireError {
+    /// The input is not valid JSON.
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A required field is missing or has the wrong type.
+    #[error("Missing or invalid field '{0}'")]
+    Field(&'static str),
+    /// A message, part or content kind is not known.
+    #[error("Unknown {field} '{kind}'")]
+    UnknownKind {
+        /// Name of the discriminator field.
+        field: &'static str,
+        /// The unknown value.
+        kind: String,
+    },
+}
+
+type Result<T> = std::result::Result<T, PydanticWireError>;
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Convert a history to pydantic-ai message JSON.
+///
+/// Model responses nested in requests become separate `response` messages,
+/// in order.
+#[must_use]
+pub fn to_pydantic_json(history: &[ModelRequest]) -> Value {
+    let mut messages: Vec<Value> = Vec::new();
+    // Index of the last response message, for builtin tool returns
+    let mut last_response: Option<usize> = None;
+
+    for request in history {
+        let mut parts = Vec::new();
+        let flush = |parts: &mut Vec<Value>, messages: &mut Vec<Value>| {
+            if !parts.is_empty() {
+                messages.push(request_message(std::mem::take(parts), request));
+            }
+        };
+        for part in &request.parts {
+            match part {
+                ModelRequestPart::ModelResponse(response) => {
+                    flush(&mut parts, &mut messages);
+                    messages.push(response_message(response));
+                    last_response = Some(messages.len() - 1);
+                }
+                ModelRequestPart::BuiltinToolReturn(ret) => {
+                    if let Some(i) = last_response {
+                        if let Some(parts) = messages[i]["parts"].as_array_mut() {
+                            parts.push(builtin_tool_return_part(ret));
+                        }
+                    }
+                }
+                part => parts.extend(request_part(part)),
+            }
+        }
+        flush(&mut parts, &mut messages);
+    }
+
+    Value::Array(messages)
+}
+
+fn request_message(parts: Vec<Value>, request: &ModelRequest) -> Value {
+    json!({
+        "parts": parts,
+        "instructions": request.instructions,
+        "kind": "request",
+    })
+}
+
+fn request_part(part: &ModelRequestPart) -> Option<Value> {
+    Some(match part {
+        ModelRequestPart::SystemPrompt(p) => json!({
+            "content": p.content,
+            "timestamp": timestamp(&p.timestamp),
+            "dynamic_ref": p.dynamic_ref,
+            "part_kind": "system-prompt",
+        }),
+        ModelRequestPart::UserPrompt(p) => json!({
+            "content": user_content(&p.content),
+            "timestamp": timestamp(&p.timestamp),
+            "part_kind": "user-prompt",
+        }),
+        ModelRequestPart::ToolReturn(p) => with_tool_call_id(
+            json!({
+                "tool_name": p.tool_name,
+                "content": tool_return_content(&p.content),
+                "metadata": null,
+                "timestamp": timestamp(&p.timestamp),
+                "part_kind": "tool-return",
+            }),
+            p.tool_call_id.as_deref(),
+        ),
+        ModelRequestPart::RetryPrompt(p) => with_tool_call_id(
+            json!({
+                "content": retry_content(&p.content),
+                "tool_name": p.tool_name,
+                "timestamp": timestamp(&p.timestamp),
+                "part_kind": "retry-prompt",
+            }),
+            p.tool_call_id.as_deref(),
+        ),
+        ModelRequestPart::BuiltinToolReturn(_) | ModelRequestPart::ModelResponse(_) => return None,
+    })
+}
+
+fn response_message(response: &ModelResponse) -> Value {
+    let parts: Vec<Value> = response.parts.iter().map(response_part).collect();
+    json!({
+        "parts": parts,
+        "usage": usage(response.usage.as_ref()),
+        "model_name": response.model_name,
+        "timestamp": timestamp(&response.timestamp),
+        "kind": "response",
+        "provider_name": null,
+        "provider_details": response.vendor_details,
+        "provider_response_id": response.vendor_id,
+        "finish_reason": response.finish_reason.map(finish_reason),
+    })
+}
+
+fn response_part(part: &ModelResponsePart) -> Value {
+    match part {
+        ModelResponsePart::Text(p) => json!({
+            "content": p.content,
+            "id": p.id,
+            "part_kind": "text",
+        }),
+        ModelResponsePart::ToolCall(p) => with_tool_call_id(
+            json!({
+                "tool_name": p.tool_name,
+                "args": tool_call_args(&p.args),
+                "id": p.id,
+                "part_kind": "tool-call",
+            }),
+            p.tool_call_id.as_deref(),
+        ),
+        ModelResponsePart::Thinking(p) => json!({
+            "content": p.content,
+            "id": p.id,
+            "signature": p.signature,
+            "provider_name": p.provider_name,
+            "part_kind": "thinking",
+        }),
+        ModelResponsePart::File(p) => json!({
+            "content": binary(&p.content.data, &p.content.media_type),
+            "id": p.id,
+            "provider_name": p.provider_name,
+            "part_kind": "file",
+        }),
+        ModelResponsePart::BuiltinToolCall(p) => with_tool_call_id(
+            json!({
+                "tool_name": p.tool_name,
+                "args": tool_call_args(&p.args),
+                "id": p.id,
+                "provider_name": null,
+                "part_kind": "builtin-tool-call",
+            }),
+            p.tool_call_id.as_deref(),
+        ),
+    }
+}
+
+fn builtin_tool_return_part(part: &BuiltinToolReturnPart) -> Value {
+    json!({
+        "tool_name": part.tool_name,
+        "content": part.content,
+        "tool_call_id": part.tool_call_id,
+        "metadata": null,
+        "timestamp": timestamp(&part.timestamp),
+        "provider_name": null,
+        "part_kind": "builtin-tool-return",
+    })
+}
+
+/// Add a `tool_call_id`, which pydantic-ai generates itself when absent.
+fn with_tool_call_id(mut part: Value, id: Option<&str>) -> Value {
+    if let (Some(id), Some(obj)) = (id, part.as_object_mut()) {
+        obj.insert("tool_call_id".into(), json!(id));
+    }
+    part
+}
+
+/// Format a timestamp like pydantic: microseconds, omitted when zero.
+fn timestamp(ts: &DateTime<Utc>) -> String {
+    let format = if ts.nanosecond() / 1_000 == 0 {
+        SecondsFormat::Secs
+    } else {
+        SecondsFormat::Micros
+    };
+    ts.to_rfc3339_opts(format, true)
+}
+
+fn user_content(content: &UserContent) -> Value {
+    match content {
+        UserContent::Text(text) => json!(text),
+        UserContent::Parts(parts) => parts.iter().map(user_content_part).collect(),
+    }
+}
+
+fn user_content_part(part: &UserContentPart) -> Value {
+    match part {
+        UserContentPart::Text { text } => json!(text),
+        UserContentPart::Image { image } => match image {
+            ImageContent::Url(u) => file_url(
+                "image-url",
+                &u.url,
+                u.media_type.map(|m| m.mime_type()),
+                u.force_download,
+                u.vendor_metadata.as_ref(),
+            ),
+            ImageContent::Binary(b) => binary(&b.data, b.media_type.mime_type()),
+        },
+        UserContentPart::Audio { audio } => match audio {
+            AudioContent::Url(u) => file_url(
+                "audio-url",
+                &u.url,
+                u.media_type.map(|m| m.mime_type()),
+                u.force_download,
+                u.vendor_metadata.as_ref(),
+            ),
+            AudioContent::Binary(b) => binary(&b.data, b.media_type.mime_type()),
+        },
+        UserContentPart::Video { video } => match video {
+            VideoContent::Url(u) => file_url(
+                "video-url",
+                &u.url,
+                u.media_type.map(|m| m.mime_type()),
+                u.force_download,
+                u.vendor_metadata.as_ref(),
+            ),
+            VideoContent::Binary(b) => binary(&b.data, b.media_type.mime_type()),
+        },
+        UserContentPart::Document { document } => match document {
+            DocumentContent::Url(u) => file_url(
+                "document-url",
+                &u.url,
+                u.media_type.map(|m| m.mime_type()),
+                u.force_download,
+                u.vendor_metadata.as_ref(),
+            ),
+            DocumentContent::Binary(b) => binary(&b.data, b.media_type.mime_type()),
+        },
+        UserContentPart::File { file } => match file {
+            FileContent::Url(u) => file_url(
+                "document-url",
+                &u.url,
+                u.mime_type.as_deref(),
+                u.force_download,
+                u.vendor_metadata.as_ref(),
+            ),
+            FileContent::Binary(b) => binary(&b.data, &b.mime_type),
+        },
+    }
+}
+
+fn file_url(
+    kind: &str,
+    url: &str,
+    media_type: Option<&str>,
+    force_download: bool,
+    vendor_metadata: Option<&Value>,
+) -> Value {
+    let mut value = json!({
+        "url": url,
+        "force_download": force_download,
+        "vendor_metadata": vendor_metadata,
+        "kind": kind,
+    });
+    if let (Some(media_type), Some(obj)) = (media_type, value.as_object_mut()) {
+        obj.insert("media_type".into(), json!(media_type));
+    }
+    value
+}
+
+fn binary(data: &[u8], media_type: &str) -> Value {
+    json!({
+        "data": STANDARD.encode(data),
+        "media_type": media_type,
+        "vendor_metadata": null,
+        "kind": "binary",
+    })
+}
+
+fn tool_return_content(content: &ToolReturnContent) -> Value {
+    match content {
+        ToolReturnContent::Text { content } => json!(content),
+        ToolReturnContent::Json { content } => content.clone(),
+        ToolReturnContent::Image { image } => user_content_part(&UserContentPart::Image {
+            image: image.clone(),
+        }),
+        ToolReturnContent::Error { error } => json!(error.message),
+        ToolReturnContent::Multiple { items } => items
+            .iter()
+            .map(|item| match item {
+                ToolReturnItem::Text { content } => json!(content),
+                ToolReturnItem::Json { value } => value.clone(),
+                ToolReturnItem::Image { image } => user_content_part(&UserContentPart::Image {
+                    image: image.clone(),
+                }),
+                ToolReturnItem::Error { message } => json!(message),
+            })
+            .collect(),
+    }
+}
+
+fn retry_content(content: &RetryContent) -> Value {
+    match content {
+        RetryContent::Structured {
+            errors: Some(errors),
+            ..
+        } => errors
+            .iter()
+            .map(|msg| {
+                json!({
+                    "type": "value_error",
+                    "loc": [],
+                    "msg": msg,
+                    "input": null,
+                })
+            })
+            .collect(),
+        content => json!(content.message()),
+    }
+}
+
+fn tool_call_args(args: &ToolCallArgs) -> Value {
+    match args {
+        ToolCallArgs::Json(value) => value.clone(),
+        ToolCallArgs::String(s) => json!(s),
+    }
+}
+
+fn usage(usage: Option<&RequestUsage>) -> Value {
+    let usage = usage.cloned().unwrap_or_default();
+    let details = match usage.details {
+        Some(Value::Object(details)) => Value::Object(
+            details
+                .into_iter()
+                .filter(|(_, v)| v.is_u64())
+                .collect::<Map<_, _>>(),
+        ),
+        _ => json!({}),
+    };
+    json!({
+        "input_tokens": usage.request_tokens.unwrap_or(0),
+        "cache_write_tokens": usage.cache_creation_tokens.unwrap_or(0),
+        "cache_read_tokens": usage.cache_read_tokens.unwrap_or(0),
+        "output_tokens": usage.response_tokens.unwrap_or(0),
+        "input_audio_tokens": 0,
+        "cache_audio_read_tokens": 0,
+        "output_audio_tokens": 0,
+        "details": details,
+    })
+}
+
+fn finish_reason(reason: FinishReason) -> &'static str {
+    match reason {
+        FinishReason::Stop | FinishReason::EndTurn | FinishReason::StopSequence => "stop",
+        FinishReason::Length => "length",
+        FinishReason::ContentFilter => "content_filter",
+        FinishReason::ToolCall => "tool_call",
+        FinishReason::Error => "error",
+    }
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Read a history from pydantic-ai message JSON.
+///
+/// Each `response` message becomes a request holding the response, followed
+/// by any builtin tool returns it contains.
+pub fn from_pydantic_json(value: &Value) -> Result<Vec<ModelRequest>> {
+    let messages = value
+        .as_array()
+        .ok_or(PydanticWireError::Field("messages"))?;
+    messages.iter().map(message).collect()
+}
+
+/// Read a history from a pydantic-ai message JSON string.
+pub fn from_pydantic_str(json: &str) -> Result<Vec<ModelRequest>> {
+    from_pydantic_json(&serde_json::from_str(json)?)
+}
+
+fn message(value: &Value) -> Result<ModelRequest> {
+    let parts = value["parts"]
+        .as_array()
+        .ok_or(PydanticWireError::Field("parts"))?;
+    match str_field(value, "kind")? {
+        "request" => {
+            let mut request = parts
+                .iter()
+                .map(import_request_part)
+                .collect::<Result<ModelRequest>>()?;
+            request.instructions = opt_string(value, "instructions");
+            Ok(request)
+        }
+        "response" => {
+            let mut response = ModelResponse::new();
+            let mut builtin_returns = Vec::new();
+            for part in parts {
+                if part["part_kind"] == "builtin-tool-return" {
+                    builtin_returns.push(ModelRequestPart::BuiltinToolReturn(
+                        import_builtin_tool_return(part)?,
+                    ));
+                } else {
+                    response.parts.push(import_response_part(part)?);
+                }
+            }
+            response.model_name = opt_string(value, "model_name");
+            response.timestamp = import_timestamp(value)?;
+            response.finish_reason = value["finish_reason"]
+                .as_str()
+                .map(import_finish_reason)
+                .transpose()?;
+            response.usage = Some(import_usage(&value["usage"]));
+            response.vendor_id = opt_string(value, "provider_response_id");
+            response.vendor_details =
+                Some(value["provider_details"].clone()).filter(|details| !details.is_null());
+
+            let mut request = ModelRequest::new();
+            request
+                .parts
+                .push(ModelRequestPart::ModelResponse(Box::new(response)));
+            request.parts.extend(builtin_returns);
+            Ok(request)
+        }
+        kind => Err(unknown("kind", kind)),
+    }
+}
+
+fn import_request_part(value: &Value) -> Result<ModelRequestPart> {
+    let timestamp = import_timestamp(value)?;
+    Ok(match str_field(value, "part_kind")? {
+        "system-prompt" => ModelRequestPart::SystemPrompt(SystemPromptPart {
+            content: str_field(value, "content")?.to_string(),
+            timestamp,
+            dynamic_ref: opt_string(value, "dynamic_ref"),
+        }),
+        "user-prompt" => ModelRequestPart::UserPrompt(
+            UserPromptPart::new(import_user_content(&value["content"])?).with_timestamp(timestamp),
+        ),
+        "tool-return" => ModelRequestPart::ToolReturn(ToolReturnPart {
+            tool_name: str_field(value, "tool_name")?.to_string(),
+            content: match &value["content"] {
+                Value::String(s) => ToolReturnContent::text(s.clone()),
+                other => ToolReturnContent::json(other.clone()),
+            },
+            tool_call_id: opt_string(value, "tool_call_id"),
+            timestamp,
+        }),
+        "retry-prompt" => ModelRequestPart::RetryPrompt(RetryPromptPart {
+            content: import_retry_content(&value["content"])?,
+            tool_name: opt_string(value, "tool_name"),
+            tool_call_id: opt_string(value, "tool_call_id"),
+            timestamp,
+        }),
+        kind => return Err(unknown("part_kind", kind)),
+    })
+}
+
+fn import_response_part(value: &Value) -> Result<ModelResponsePart> {
+    Ok(match str_field(value, "part_kind")? {
+        "text" => {
+            let mut part = TextPart::new(str_field(value, "content")?);
+            part.id = opt_string(value, "id");
+            ModelResponsePart::Text(part)
+        }
+        "tool-call" => {
+            let mut part = ToolCallPart::new(
+                str_field(value, "tool_name")?,
+                import_tool_call_args(&value["args"]),
+            );
+            part.tool_call_id = opt_string(value, "tool_call_id");
+            part.id = opt_string(value, "id");
+            ModelResponsePart::ToolCall(part)
+        }
+        "thinking" => {
+            let mut part = ThinkingPart::new(str_field(value, "content")?);
+            part.id = opt_string(value, "id");
+            part.signature = opt_string(value, "signature");
+            part.provider_name = opt_string(value, "provider_name");
+            ModelResponsePart::Thinking(part)
+        }
+        "file" => {
+            let content = &value["content"];
+            let mut part = FilePart::new(BinaryContent::new(
+                import_base64(content)?,
+                str_field(content, "media_type")?,
+            ));
+            part.id = opt_string(value, "id");
+            part.provider_name = opt_string(value, "provider_name");
+            ModelResponsePart::File(part)
+        }
+        "builtin-tool-call" => {
+            let mut part = BuiltinToolCallPart::new(
+                str_field(value, "tool_name")?,
+                import_tool_call_args(&value["args"]),
+            );
+            part.tool_call_id = opt_string(value, "tool_call_id");
+            part.id = opt_string(value, "id");
+            ModelResponsePart::BuiltinToolCall(part)
+        }
+        kind => return Err(unknown("part_kind", kind)),
+    })
+}
+
+fn import_builtin_tool_return(value: &Value) -> Result<BuiltinToolReturnPart> {
+    let tool_name = str_field(value, "tool_name")?;
+    let content = &value["content"];
+    let content = serde_json::from_value(content.clone())
+        .unwrap_or_else(|_| BuiltinToolReturnContent::other(tool_name, content.clone()));
+    Ok(
+        BuiltinToolReturnPart::new(tool_name, content, str_field(value, "tool_call_id")?)
+            .with_timestamp(import_timestamp(value)?),
+    )
+}
+
+fn import_user_content(value: &Value) -> Result<UserContent> {
+    match value {
+        Value::String(text) => Ok(UserContent::text(text.clone())),
+        Value::Array(items) => items
+            .iter()
+            .map(import_user_content_part)
+            .collect::<Result<Vec<_>>>()
+            .map(UserContent::Parts),
+        _ => Err(PydanticWireError::Field("content")),
+    }
+}
+
+fn import_user_content_part(value: &Value) -> Result<UserContentPart> {
+    if let Some(text) = value.as_str() {
+        return Ok(UserContentPart::text(text));
+    }
+    let media_type = value["media_type"].as_str();
+    let force_download = value["force_download"].as_bool().unwrap_or(false);
+    let vendor_metadata = Some(value["vendor_metadata"].clone()).filter(|v| !v.is_null());
+
+    Ok(match str_field(value, "kind")? {
+        "image-url" => UserContentPart::Image {
+            image: ImageContent::Url(ImageUrl {
+                url: str_field(value, "url")?.to_string(),
+                media_type: media_type.and_then(|m| m.parse().ok()),
+                force_download,
+                vendor_metadata,
+            }),
+        },
+        "audio-url" => UserContentPart::Audio {
+            audio: AudioContent::Url(AudioUrl {
+                url: str_field(value, "url")?.to_string(),
+                media_type: media_type.and_then(|m| m.parse().ok()),
+                force_download,
+                vendor_metadata,
+            }),
+        },
+        "video-url" => UserContentPart::Video {
+            video: VideoContent::Url(VideoUrl {
+                url: str_field(value, "url")?.to_string(),
+                media_type: media_type.and_then(|m| m.parse().ok()),
+                force_download,
+                vendor_metadata,
+            }),
+        },
+        "document-url" => {
+            let url = str_field(value, "url")?.to_string();
+            match media_type.map(FromStr::from_str) {
+                Some(Err(_)) => UserContentPart::File {
+                    file: FileContent::Url(FileUrl {
+                        url,
+                        mime_type: media_type.map(str::to_string),
+                        force_download,
+                        vendor_metadata,
+                    }),
+                },
+                media_type => UserContentPart::Document {
+                    document: DocumentContent::Url(DocumentUrl {
+                        url,
+                        media_type: media_type.and_then(|m| m.ok()),
+                        force_download,
+                        vendor_metadata,
+                    }),
+                },
+            }
+        }
+        "binary" => {
+            let data = import_base64(value)?;
+            let media_type = media_type.ok_or(PydanticWireError::Field("media_type"))?;
+            import_binary(data, media_type)
+        }
+        kind => return Err(unknown("kind", kind)),
+    })
+}
+
+/// Pick the content type of binary data by its media type.
+fn import_binary(data: Vec<u8>, media_type: &str) -> UserContentPart {
+    if let Ok(m) = media_type.parse() {
+        UserContentPart::Image {
+            image: ImageContent::Binary(BinaryImage::new(data, m)),
+        }
+    } else if let Ok(m) = media_type.parse() {
+        UserContentPart::Audio {
+            audio: AudioContent::Binary(BinaryAudio::new(data, m)),
+        }
+    } else if let Ok(m) = media_type.parse() {
+        UserContentPart::Video {
+            video: VideoContent::Binary(BinaryVideo::new(data, m)),
+        }
+    } else if let Ok(m) = media_type.parse() {
+        UserContentPart::Document {
+            document: DocumentContent::Binary(BinaryDocument::new(data, m)),
+        }
+    } else {
+        UserContentPart::File {
+            file: FileContent::Binary(BinaryFile::new(data, media_type)),
+        }
+    }
+}
+
+fn import_retry_content(value: &Value) -> Result<RetryContent> {
+    match value {
+        Value::String(text) => Ok(RetryContent::text(text.clone())),
+        Value::Array(errors) => {
+            let errors: Vec<String> = errors
+                .iter()
+                .map(|e| e["msg"].as_str().unwrap_or_default().to_string())
+                .collect();
+            Ok(RetryContent::structured(
+                format!("{} validation errors", errors.len()),
+                Some(errors),
+            ))
+        }
+        _ => Err(PydanticWireError::Field("content")),
+    }
+}
+
+fn import_tool_call_args(value: &Value) -> ToolCallArgs {
+    match value {
+        Value::String(s) => ToolCallArgs::String(s.clone()),
+        Value::Null => ToolCallArgs::default(),
+        other => ToolCallArgs::Json(other.clone()),
+    }
+}
+
+fn import_usage(value: &Value) -> RequestUsage {
+    let tokens = |field: &str| value[field].as_u64();
+    let mut usage = RequestUsage::new();
+    if let Some(tokens) = tokens("input_tokens") {
+        usage = usage.request_tokens(tokens);
+    }
+    if let Some(tokens) = tokens("output_tokens") {
+        usage = usage.response_tokens(tokens);
+    }
+    usage.cache_creation_tokens = tokens("cache_write_tokens").filter(|&t| t > 0);
+    usage.cache_read_tokens = tokens("cache_read_tokens").filter(|&t| t > 0);
+    usage.details =
+        Some(value["details"].clone()).filter(|d| d.as_object().is_some_and(|d| !d.is_empty()));
+    usage
+}
+
+fn import_finish_reason(reason: &str) -> Result<FinishReason> {
+    Ok(match reason {
+        "stop" => FinishReason::Stop,
+        "length" => FinishReason::Length,
+        "content_filter" => FinishReason::ContentFilter,
+        "tool_call" => FinishReason::ToolCall,
+        "error" => FinishReason::Error,
+        reason => return Err(unknown("finish_reason", reason)),
+    })
+}
+
+fn import_timestamp(value: &Value) -> Result<DateTime<Utc>> {
+    match value["timestamp"].as_str() {
+        Some(ts) => DateTime::parse_from_rfc3339(ts)
+            .map(|ts| ts.with_timezone(&Utc))
+            .map_err(|_| PydanticWireError::Field("timestamp")),
+        None => Ok(now_utc()),
+    }
+}
+
+fn import_base64(value: &Value) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(str_field(value, "data")?)
+        .map_err(|_| PydanticWireError::Field("data"))
+}
+
+fn str_field<'a>(value: &'a Value, field: &'static str) -> Result<&'a str> {
+    value[field].as_str().ok_or(PydanticWireError::Field(field))
+}
+
+fn opt_string(value: &Value, field: &str) -> Option<String> {
+    value[field].as_str().map(str::to_string)
+}
+
+fn unknown(field: &'static str, kind: &str) -> PydanticWireError {
+    PydanticWireError::UnknownKind {
+        field,
+        kind: kind.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::ImageMediaType;
+    use chrono::TimeZone;
+
+    fn ts() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap()
+    }
+
+    /// A transcript as dumped by `ModelMessagesTypeAdapter.dump_json`.
+    fn pydantic_transcript() -> Value {
+        json!([
+            {
+                "parts": [
+                    {
+                        "content": "Be brief.",
+                        "timestamp": "2025-01-02T03:04:05Z",
+                        "dynamic_ref": null,
+                        "part_kind": "system-prompt"
+                    },
+                    {
+                        "content": [
+                            "What is in this image?",
+                            {
+                                "url": "https://example.com/cat.png",
+                                "force_download": false,
+                                "vendor_metadata": null,
+                                "kind": "image-url",
+                                "media_type": "image/png"
+                            }
+                        ],
+                        "timestamp": "2025-01-02T03:04:05.123456Z",
+                        "part_kind": "user-prompt"
+                    }
+                ],
+                "instructions": null,
+                "kind": "request"
+            },
+            {
+                "parts": [
+                    {
+                        "tool_name": "describe",
+                        "args": {"detail": "high"},
+                        "tool_call_id": "call_1",
+                        "id": null,
+                        "part_kind": "tool-call"
+                    }
+                ],
+                "usage": {
+                    "input_tokens": 12,
+                    "cache_write_tokens": 0,
+                    "cache_read_tokens": 0,
+                    "output_tokens": 3,
+                    "input_audio_tokens": 0,
+                    "cache_audio_read_tokens": 0,
+                    "output_audio_tokens": 0,
+                    "details": {}
+                },
+                "model_name": "gpt-4o",
+                "timestamp": "2025-01-02T03:04:05Z",
+                "kind": "response",
+                "provider_name": null,
+                "provider_details": null,
+                "provider_response_id": "resp_1",
+                "finish_reason": "tool_call"
+            },
+            {
+                "parts": [
+                    {
+                        "tool_name": "describe",
+                        "content": {"animal": "cat"},
+                        "tool_call_id": "call_1",
+                        "metadata": null,
+                        "timestamp": "2025-01-02T03:04:05Z",
+                        "part_kind": "tool-return"
+                    }
+                ],
+                "instructions": null,
+                "kind": "request"
+            }
+        ])
+    }
+
+    #[test]
+    fn test_import_pydantic_transcript() {
+        let history = from_pydantic_json(&pydantic_transcript()).unwrap();
+        assert_eq!(history.len(), 3);
+
+        let ModelRequestPart::UserPrompt(prompt) = &history[0].parts[1] else {
+            panic!("expected user prompt");
+        };
+        let UserContent::Parts(parts) = &prompt.content else {
+            panic!("expected content parts");
+        };
+        assert_eq!(parts[0], UserContentPart::text("What is in this image?"));
+        assert_eq!(
+            parts[1],
+            UserContentPart::Image {
+                image: ImageContent::Url(
+                    ImageUrl::new("https://example.com/cat.png")
+                        .with_media_type(ImageMediaType::Png)
+                ),
+            }
+        );
+
+        let ModelRequestPart::ModelResponse(response) = &history[1].parts[0] else {
+            panic!("expected model response");
+        };
+        assert_eq!(response.finish_reason, Some(FinishReason::ToolCall));
+        assert_eq!(response.vendor_id.as_deref(), Some("resp_1"));
+        assert_eq!(response.usage.as_ref().unwrap().request_tokens, Some(12));
+        assert_eq!(
+            response.tool_call_parts().next().unwrap().tool_name,
+            "describe"
+        );
+
+        let ModelRequestPart::ToolReturn(ret) = &history[2].parts[0] else {
+            panic!("expected tool return");
+        };
+        assert_eq!(
+            ret.content,
+            ToolReturnContent::json(json!({"animal": "cat"}))
+        );
+    }
+
+    #[test]
+    fn test_export_matches_pydantic_transcript() {
+        let history = from_pydantic_json(&pydantic_transcript()).unwrap();
+        assert_eq!(to_pydantic_json(&history), pydantic_transcript());
+    }
+
+    #[test]
+    fn test_export_splits_nested_responses() {
+        let mut request = ModelRequest::new();
+        request.add_user_prompt("Search the web");
+        request.parts.push(ModelRequestPart::ModelResponse(Box::new(
+            ModelResponse::with_parts(vec![ModelResponsePart::builtin_tool_call(
+                "web_search",
+                json!({"query": "rust"}),
+            )])
+            .with_finish_reason(FinishReason::EndTurn),
+        )));
+        request.add_builtin_tool_return(BuiltinToolReturnPart::new(
+            "web_search",
+            BuiltinToolReturnContent::other("web_search", json!([])),
+            "call_1",
+        ));
+
+        let json = to_pydantic_json(&[request]);
+        let messages = json.as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["finish_reason"], "stop");
+        assert_eq!(messages[1]["parts"][1]["part_kind"], "builtin-tool-return");
+
+        let history = from_pydantic_json(&json).unwrap();
+        assert!(history[1].parts[1].is_builtin_tool_return());
+    }
+
+    #[test]
+    fn test_binary_and_retry_roundtrip() {
+        let mut request = ModelRequest::new();
+        request.add_user_prompt(UserContent::Parts(vec![UserContentPart::image_binary(
+            vec![1, 2, 3],
+            ImageMediaType::Png,
+        )]));
+        request.add_part(ModelRequestPart::RetryPrompt(
+            RetryPromptPart::new(RetryContent::structured(
+                "1 validation errors",
+                Some(vec!["field required".into()]),
+            ))
+            .with_tool_call_id("call_1")
+            .with_timestamp(ts()),
+        ));
+
+        let json = to_pydantic_json(&[request.clone()]);
+        let user = &json[0]["parts"][0]["content"][0];
+        assert_eq!(user["kind"], "binary");
+        assert_eq!(user["data"], "AQID");
+        assert_eq!(json[0]["parts"][1]["content"][0]["msg"], "field required");
+
+        let history = from_pydantic_json(&json).unwrap();
+        assert_eq!(history[0].parts[1], request.parts[1]);
+        let ModelRequestPart::UserPrompt(prompt) = &history[0].parts[0] else {
+            panic!("expected user prompt");
+        };
+        assert_eq!(
+            prompt.content,
+            UserContent::Parts(vec![UserContentPart::image_binary(
+                vec![1, 2, 3],
+                ImageMediaType::Png
+            )])
+        );
+    }
+
+    #[test]
+    fn test_unknown_kind() {
+        let err = from_pydantic_str(r#"[{"parts": [], "kind": "event"}]"#).unwrap_err();
+        assert!(matches!(err, PydanticWireError::UnknownKind { .. }));
+    }
+}
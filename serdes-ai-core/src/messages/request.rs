@@ -3,6 +3,9 @@
 //! This module defines the message types that are sent TO the model,
 //! including system prompts, user prompts, tool returns, and retry prompts.
 
+use std::borrow::Cow;
+
+use crate::identifier::now_utc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +21,13 @@ pub struct ModelRequest {
     /// Kind identifier.
     #[serde(default = "default_request_kind")]
     pub kind: String,
+    /// Instructions for this request only.
+    ///
+    /// Unlike system prompts, instructions are not part of the conversation:
+    /// providers only use those of the most recent request, so history replays
+    /// don't carry stale instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
 }
 
 fn default_request_kind() -> String {
@@ -31,6 +41,7 @@ impl ModelRequest {
         Self {
             parts: Vec::new(),
             kind: "request".to_string(),
+            instructions: None,
         }
     }
 
@@ -40,9 +51,17 @@ impl ModelRequest {
         Self {
             parts,
             kind: "request".to_string(),
+            instructions: None,
         }
     }
 
+    /// Set the instructions.
+    #[must_use]
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
     /// Add a part.
     pub fn add_part(&mut self, part: ModelRequestPart) {
         self.parts.push(part);
@@ -136,6 +155,45 @@ impl ModelRequest {
     }
 }
 
+/// Get the instructions of the most recent request that has them.
+#[must_use]
+pub fn latest_instructions(requests: &[ModelRequest]) -> Option<&str> {
+    requests
+        .iter()
+        .rev()
+        .find_map(|r| r.instructions.as_deref())
+        .filter(|i| !i.is_empty())
+}
+
+/// Turn the latest instructions into a system prompt.
+///
+/// For providers without a dedicated instructions field. The instructions are
+/// inserted after the leading system prompts of the first request, and all
+/// `instructions` fields are cleared. Borrows when there is nothing to do.
+#[must_use]
+pub fn instructions_as_system_prompt(requests: &[ModelRequest]) -> Cow<'_, [ModelRequest]> {
+    if requests.iter().all(|r| r.instructions.is_none()) {
+        return Cow::Borrowed(requests);
+    }
+    let instructions = latest_instructions(requests).map(str::to_string);
+    let mut requests = requests.to_vec();
+    for request in &mut requests {
+        request.instructions = None;
+    }
+    if let (Some(instructions), Some(first)) = (instructions, requests.first_mut()) {
+        let index = first
+            .parts
+            .iter()
+            .take_while(|p| matches!(p, ModelRequestPart::SystemPrompt(_)))
+            .count();
+        first.parts.insert(
+            index,
+            ModelRequestPart::SystemPrompt(SystemPromptPart::new(instructions)),
+        );
+    }
+    Cow::Owned(requests)
+}
+
 impl Default for ModelRequest {
     fn default() -> Self {
         Self::new()
@@ -229,7 +287,7 @@ impl SystemPromptPart {
     pub fn new(content: impl Into<String>) -> Self {
         Self {
             content: content.into(),
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
             dynamic_ref: None,
         }
     }
@@ -285,7 +343,7 @@ impl UserPromptPart {
     pub fn new(content: impl Into<UserContent>) -> Self {
         Self {
             content: content.into(),
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
         }
     }
 
@@ -346,7 +404,7 @@ impl ToolReturnPart {
             tool_name: tool_name.into(),
             content: content.into(),
             tool_call_id: None,
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
         }
     }
 
@@ -469,7 +527,7 @@ impl RetryPromptPart {
             content: content.into(),
             tool_name: None,
             tool_call_id: None,
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
         }
     }
 
@@ -617,4 +675,57 @@ mod tests {
         assert_eq!(req.len(), parsed.len());
         assert_eq!(parsed.builtin_tool_returns().count(), 1);
     }
+
+    #[test]
+    fn test_instructions_serde_skips_none() {
+        let req = ModelRequest::new();
+        let json = serde_json::to_value(&req).unwrap();
+        assert!(json.get("instructions").is_none());
+
+        let req = ModelRequest::new().with_instructions("Be brief.");
+        let json = serde_json::to_string(&req).unwrap();
+        let parsed: ModelRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.instructions.as_deref(), Some("Be brief."));
+    }
+
+    #[test]
+    fn test_latest_instructions() {
+        let requests = vec![
+            ModelRequest::new().with_instructions("old"),
+            ModelRequest::new(),
+            ModelRequest::new().with_instructions("new"),
+        ];
+        assert_eq!(latest_instructions(&requests), Some("new"));
+        assert_eq!(latest_instructions(&requests[..2]), Some("old"));
+        assert_eq!(latest_instructions(&[ModelRequest::new()]), None);
+    }
+
+    #[test]
+    fn test_instructions_as_system_prompt() {
+        let mut first = ModelRequest::new();
+        first.add_system_prompt("You are helpful.");
+        first.add_user_prompt("Hi");
+        let mut last = ModelRequest::new().with_instructions("Be brief.");
+        last.add_user_prompt("Again");
+        let requests = vec![first, last];
+
+        let folded = instructions_as_system_prompt(&requests);
+        assert!(folded.iter().all(|r| r.instructions.is_none()));
+        let prompts: Vec<_> = folded[0]
+            .system_prompts()
+            .map(|p| p.content.as_str())
+            .collect();
+        assert_eq!(prompts, ["You are helpful.", "Be brief."]);
+        assert!(matches!(
+            folded[0].parts[2],
+            ModelRequestPart::UserPrompt(_)
+        ));
+        assert_eq!(folded[1].system_prompts().count(), 0);
+
+        let plain = vec![ModelRequest::new()];
+        assert!(matches!(
+            instructions_as_system_prompt(&plain),
+            Cow::Borrowed(_)
+        ));
+    }
 }
@@ -3,6 +3,7 @@
 //! This module defines the message types that are returned FROM the model,
 //! including text content, tool calls, and thinking/reasoning content.
 
+use crate::identifier::now_utc;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +32,13 @@ pub struct ModelResponse {
     /// Vendor-specific details.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vendor_details: Option<serde_json::Value>,
+    /// Further choices when more than one completion was requested with
+    /// [`ModelSettings::n`](crate::ModelSettings::n).
+    ///
+    /// Only filled by providers that support sampling several completions;
+    /// usage for all of them is reported on this response.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<ModelResponse>,
     /// Kind identifier.
     #[serde(default = "default_response_kind")]
     pub kind: String,
@@ -47,11 +55,12 @@ impl ModelResponse {
         Self {
             parts: Vec::new(),
             model_name: None,
-            timestamp: Utc::now(),
+            timestamp: now_utc(),
             finish_reason: None,
             usage: None,
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         }
     }
@@ -111,6 +120,28 @@ impl ModelResponse {
         self
     }
 
+    /// Set the alternative choices.
+    #[must_use]
+    pub fn with_alternatives(mut self, alternatives: Vec<ModelResponse>) -> Self {
+        self.alternatives = alternatives;
+        self
+    }
+
+    /// Iterate over all choices: this response, then its alternatives.
+    pub fn choices(&self) -> impl Iterator<Item = &ModelResponse> {
+        std::iter::once(self).chain(&self.alternatives)
+    }
+
+    /// Split into all choices, dropping nested alternatives.
+    #[must_use]
+    pub fn into_choices(mut self) -> Vec<ModelResponse> {
+        let alternatives = std::mem::take(&mut self.alternatives);
+        let mut choices = Vec::with_capacity(alternatives.len() + 1);
+        choices.push(self);
+        choices.extend(alternatives);
+        choices
+    }
+
     /// Get all text parts.
     pub fn text_parts(&self) -> impl Iterator<Item = &TextPart> {
         self.parts.iter().filter_map(|p| match p {
@@ -206,6 +237,26 @@ impl ModelResponse {
             .join("")
     }
 
+    /// Get the log probabilities of the output tokens, if returned.
+    ///
+    /// Providers put them in `vendor_details` under `logprobs`, in OpenAI's
+    /// `{"content": [{"token": ..., "logprob": ...}]}` shape.
+    #[must_use]
+    pub fn token_logprobs(&self) -> Option<Vec<f64>> {
+        let content = self
+            .vendor_details
+            .as_ref()?
+            .get("logprobs")?
+            .get("content")?
+            .as_array()?;
+        Some(
+            content
+                .iter()
+                .filter_map(|token| token.get("logprob")?.as_f64())
+                .collect(),
+        )
+    }
+
     /// Check if this response contains tool calls.
     #[must_use]
     pub fn has_tool_calls(&self) -> bool {
@@ -447,6 +498,18 @@ mod tests {
         assert_eq!(response.tool_call_parts().count(), 1);
     }
 
+    #[test]
+    fn test_token_logprobs() {
+        let response = ModelResponse::text("Hi there").with_vendor_details(serde_json::json!({
+            "logprobs": {"content": [
+                {"token": "Hi", "logprob": -0.25},
+                {"token": " there", "logprob": -0.5},
+            ]}
+        }));
+        assert_eq!(response.token_logprobs(), Some(vec![-0.25, -0.5]));
+        assert_eq!(ModelResponse::text("Hi").token_logprobs(), None);
+    }
+
     #[test]
     fn test_finish_reason() {
         assert!(FinishReason::Stop.is_complete());
@@ -467,5 +530,22 @@ mod tests {
         let parsed: ModelResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(response.len(), parsed.len());
         assert_eq!(response.model_name, parsed.model_name);
+        assert!(!json.contains("alternatives"));
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let response = ModelResponse::text("a")
+            .with_alternatives(vec![ModelResponse::text("b"), ModelResponse::text("c")]);
+        let texts: Vec<_> = response.choices().map(|r| r.text_content()).collect();
+        assert_eq!(texts, ["a", "b", "c"]);
+
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: ModelResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, response);
+
+        let choices = response.into_choices();
+        assert_eq!(choices.len(), 3);
+        assert!(choices[0].alternatives.is_empty());
     }
 }
@@ -2,10 +2,63 @@
 //!
 //! This module provides the `ModelSettings` type for configuring model behavior,
 //! including temperature, token limits, and other generation parameters.
+//!
+//! # Precedence
+//!
+//! Settings come in layers that are combined with [`ModelSettings::merge`],
+//! from lowest to highest priority:
+//!
+//! 1. Agent defaults (set on the agent builder)
+//! 2. Run overrides (`RunOptions::model_settings`)
+//! 3. Per-request overrides (returned by request settings hooks)
+//!
+//! A field set in a higher layer replaces the same field in lower layers;
+//! unset fields fall through. `extra` objects are merged key by key.
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// API key overriding a model's configured credentials for a request.
+///
+/// Used to bill requests to a tenant's own key without constructing a model
+/// per tenant. The key is redacted from `Debug` output and never serialized.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(Arc<str>);
+
+impl ApiKey {
+    /// Create a key.
+    #[must_use]
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(Arc::from(key.into()))
+    }
+
+    /// Get the key for building request headers.
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ApiKey(***)")
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
 /// Settings for model generation.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ModelSettings {
@@ -41,6 +94,13 @@ pub struct ModelSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<u64>,
 
+    /// Number of completions to sample per request.
+    ///
+    /// Providers that support it return the extra completions as
+    /// `ModelResponse::alternatives`; others ignore it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+
     /// Request timeout.
     #[serde(
         skip_serializing_if = "Option::is_none",
@@ -55,6 +115,29 @@ pub struct ModelSettings {
     /// Extra provider-specific settings.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra: Option<serde_json::Value>,
+
+    /// API key overriding the model's configured key.
+    ///
+    /// Honored by models that authenticate with a static key; never
+    /// serialized.
+    #[serde(skip)]
+    pub api_key: Option<ApiKey>,
+
+    /// Session key routing a conversation to the same backend.
+    ///
+    /// Requests sharing a key are kept on the same backend by wrappers
+    /// such as the fallback model and gateways (through `GatewayOptions` in
+    /// `serdes-ai-providers`), and sent as OpenAI's `prompt_cache_key`, so
+    /// provider prompt caches are hit more often.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_affinity: Option<String>,
+
+    /// Whether to return the log probabilities of output tokens.
+    ///
+    /// Providers that support it attach them to the response, see
+    /// [`ModelResponse::token_logprobs`](crate::ModelResponse::token_logprobs).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
 }
 
 impl ModelSettings {
@@ -127,6 +210,13 @@ impl ModelSettings {
         self
     }
 
+    /// Set the number of completions to sample.
+    #[must_use]
+    pub fn n(mut self, n: u32) -> Self {
+        self.n = Some(n);
+        self
+    }
+
     /// Set timeout.
     #[must_use]
     pub fn timeout(mut self, timeout: Duration) -> Self {
@@ -154,9 +244,37 @@ impl ModelSettings {
         self
     }
 
+    /// Set the API key override.
+    #[must_use]
+    pub fn api_key(mut self, key: impl Into<ApiKey>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Set the session affinity key.
+    #[must_use]
+    pub fn session_affinity(mut self, key: impl Into<String>) -> Self {
+        self.session_affinity = Some(key.into());
+        self
+    }
+
+    /// Set whether token log probabilities are returned.
+    #[must_use]
+    pub fn logprobs(mut self, logprobs: bool) -> Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Get the API key to use, falling back to the model's configured key.
+    #[must_use]
+    pub fn api_key_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.api_key.as_ref().map_or(default, ApiKey::expose)
+    }
+
     /// Merge with another settings, preferring values from `other`.
     ///
     /// Values in `other` override values in `self` when both are present.
+    /// `extra` objects are merged recursively instead of replaced.
     #[must_use]
     pub fn merge(&self, other: &ModelSettings) -> ModelSettings {
         ModelSettings {
@@ -168,6 +286,7 @@ impl ModelSettings {
             presence_penalty: other.presence_penalty.or(self.presence_penalty),
             stop: other.stop.clone().or_else(|| self.stop.clone()),
             seed: other.seed.or(self.seed),
+            n: other.n.or(self.n),
             timeout: other.timeout.or(self.timeout),
             parallel_tool_calls: other.parallel_tool_calls.or(self.parallel_tool_calls),
             extra: match (&self.extra, &other.extra) {
@@ -176,9 +295,35 @@ impl ModelSettings {
                 (Some(a), None) => Some(a.clone()),
                 (None, None) => None,
             },
+            api_key: other.api_key.clone().or_else(|| self.api_key.clone()),
+            session_affinity: other
+                .session_affinity
+                .clone()
+                .or_else(|| self.session_affinity.clone()),
+            logprobs: other.logprobs.or(self.logprobs),
         }
     }
 
+    /// Merge layers in order, later layers taking precedence.
+    ///
+    /// ```rust
+    /// use serdes_ai_core::ModelSettings;
+    ///
+    /// let agent = ModelSettings::new().temperature(0.2).max_tokens(500);
+    /// let run = ModelSettings::new().temperature(0.7);
+    /// let request = ModelSettings::new().max_tokens(100);
+    ///
+    /// let settings = ModelSettings::merge_all([&agent, &run, &request]);
+    /// assert_eq!(settings.temperature, Some(0.7));
+    /// assert_eq!(settings.max_tokens, Some(100));
+    /// ```
+    #[must_use]
+    pub fn merge_all<'a>(layers: impl IntoIterator<Item = &'a ModelSettings>) -> ModelSettings {
+        layers
+            .into_iter()
+            .fold(ModelSettings::default(), |acc, layer| acc.merge(layer))
+    }
+
     /// Check if all settings are None.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -190,9 +335,13 @@ impl ModelSettings {
             && self.presence_penalty.is_none()
             && self.stop.is_none()
             && self.seed.is_none()
+            && self.n.is_none()
             && self.timeout.is_none()
             && self.parallel_tool_calls.is_none()
             && self.extra.is_none()
+            && self.api_key.is_none()
+            && self.session_affinity.is_none()
+            && self.logprobs.is_none()
     }
 }
 
@@ -289,6 +438,42 @@ mod tests {
         assert_eq!(merged.top_p, Some(0.9)); // from override
     }
 
+    #[test]
+    fn test_model_settings_merge_all_precedence() {
+        let agent = ModelSettings::new()
+            .temperature(0.2)
+            .max_tokens(500)
+            .extra(serde_json::json!({"a": 1, "nested": {"x": 1}}));
+        let run = ModelSettings::new()
+            .temperature(0.7)
+            .extra(serde_json::json!({"nested": {"y": 2}}));
+        let request = ModelSettings::new()
+            .max_tokens(100)
+            .extra(serde_json::json!({"a": 3}));
+
+        let merged = ModelSettings::merge_all([&agent, &run, &request]);
+        assert_eq!(merged.temperature, Some(0.7));
+        assert_eq!(merged.max_tokens, Some(100));
+        assert_eq!(
+            merged.extra,
+            Some(serde_json::json!({"a": 3, "nested": {"x": 1, "y": 2}}))
+        );
+
+        assert!(ModelSettings::merge_all([]).is_empty());
+    }
+
+    #[test]
+    fn test_api_key_override() {
+        let base = ModelSettings::new().api_key("sk-agent");
+        let merged = base.merge(&ModelSettings::new().api_key("sk-tenant"));
+        assert_eq!(merged.api_key_or("sk-model"), "sk-tenant");
+        assert_eq!(ModelSettings::new().api_key_or("sk-model"), "sk-model");
+
+        assert!(!format!("{:?}", merged).contains("sk-tenant"));
+        let json = serde_json::to_string(&merged).unwrap();
+        assert!(!json.contains("sk-tenant"));
+    }
+
     #[test]
     fn test_model_settings_timeout() {
         let settings = ModelSettings::new().timeout_secs(30);
@@ -0,0 +1,259 @@
+//! W3C trace context propagation.
+//!
+//! With the `otel` feature enabled and a `tracing-opentelemetry` layer
+//! installed, [`current_headers`] returns the `traceparent` and `tracestate`
+//! headers of the current span. Model and MCP HTTP clients attach them to
+//! every outgoing request, so provider gateways join the agent's trace.
+//! Servers do the reverse: they parse incoming headers with
+//! [`TraceContext::from_headers`] and make the remote span the parent of
+//! their own with [`TraceContext::set_parent`].
+//!
+//! Without the `otel` feature [`current_headers`] is always empty, but trace
+//! contexts can still be parsed and carried along.
+//!
+//! ```rust
+//! use serdes_ai_core::trace_context::TraceContext;
+//!
+//! let cx = TraceContext::from_headers(|name| match name {
+//!     "traceparent" => Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+//!     _ => None,
+//! })
+//! .unwrap();
+//! assert_eq!(cx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+//! assert!(cx.sampled());
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the header carrying the trace and parent span ids.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Name of the header carrying vendor-specific trace state.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// A validated W3C trace context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    traceparent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse a `traceparent` header value and optional `tracestate`.
+    ///
+    /// Returns `None` if `traceparent` is malformed or has all-zero ids. An
+    /// empty `tracestate` is dropped.
+    pub fn parse(traceparent: &str, tracestate: Option<&str>) -> Option<Self> {
+        let traceparent = traceparent.trim().to_ascii_lowercase();
+        let mut fields = traceparent.split('-');
+        let version = fields.next()?;
+        let trace_id = fields.next()?;
+        let parent_id = fields.next()?;
+        let flags = fields.next()?;
+        // Version 00 has exactly four fields; later versions may add more
+        if version == "ff" || (version == "00" && fields.next().is_some()) {
+            return None;
+        }
+        let valid = is_hex(version, 2)
+            && is_hex(trace_id, 32)
+            && is_hex(parent_id, 16)
+            && is_hex(flags, 2)
+            && trace_id.bytes().any(|b| b != b'0')
+            && parent_id.bytes().any(|b| b != b'0');
+        if !valid {
+            return None;
+        }
+        let tracestate = tracestate
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+        Some(Self {
+            traceparent,
+            tracestate,
+        })
+    }
+
+    /// Parse the trace context from request headers, looked up by lowercase
+    /// name.
+    pub fn from_headers<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        Self::parse(get(TRACEPARENT_HEADER)?, get(TRACESTATE_HEADER))
+    }
+
+    /// Get the trace context of the current span.
+    ///
+    /// Always `None` without the `otel` feature, or when the current span is
+    /// not recorded by OpenTelemetry.
+    pub fn current() -> Option<Self> {
+        #[cfg(feature = "otel")]
+        {
+            use opentelemetry::trace::TraceContextExt;
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+            let cx = tracing::Span::current().context();
+            let span = cx.span();
+            let span_context = span.span_context();
+            if !span_context.is_valid() {
+                return None;
+            }
+            let traceparent = format!(
+                "00-{:032x}-{:016x}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags()
+            );
+            let tracestate = span_context.trace_state().header();
+            Self::parse(&traceparent, Some(&tracestate))
+        }
+        #[cfg(not(feature = "otel"))]
+        {
+            None
+        }
+    }
+
+    /// Get the `traceparent` header value.
+    pub fn traceparent(&self) -> &str {
+        &self.traceparent
+    }
+
+    /// Get the `tracestate` header value, if any.
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// Get the trace id as 32 lowercase hex digits.
+    pub fn trace_id(&self) -> &str {
+        &self.traceparent[3..35]
+    }
+
+    /// Get the parent span id as 16 lowercase hex digits.
+    pub fn parent_id(&self) -> &str {
+        &self.traceparent[36..52]
+    }
+
+    /// Check whether the caller sampled the trace.
+    pub fn sampled(&self) -> bool {
+        u8::from_str_radix(&self.traceparent[53..55], 16).is_ok_and(|flags| flags & 1 == 1)
+    }
+
+    /// Get the headers to send, as `(name, value)` pairs.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![(TRACEPARENT_HEADER, self.traceparent.clone())];
+        if let Some(tracestate) = &self.tracestate {
+            headers.push((TRACESTATE_HEADER, tracestate.clone()));
+        }
+        headers
+    }
+
+    /// Make this remote context the parent of `span`, so the span joins the
+    /// caller's trace.
+    #[cfg(feature = "otel")]
+    pub fn set_parent(&self, span: &tracing::Span) {
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let (Ok(trace_id), Ok(span_id)) = (
+            TraceId::from_hex(self.trace_id()),
+            SpanId::from_hex(self.parent_id()),
+        ) else {
+            return;
+        };
+        let flags = if self.sampled() {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::default()
+        };
+        let state = self
+            .tracestate
+            .as_deref()
+            .and_then(|s| s.parse::<TraceState>().ok())
+            .unwrap_or_default();
+        let span_context = SpanContext::new(trace_id, span_id, flags, true, state);
+        span.set_parent(opentelemetry::Context::new().with_remote_span_context(span_context));
+    }
+}
+
+/// Get the trace headers to attach to an outgoing request.
+///
+/// Empty unless the `otel` feature is enabled and the current span is
+/// recorded by OpenTelemetry.
+pub fn current_headers() -> Vec<(&'static str, String)> {
+    TraceContext::current()
+        .map(|cx| cx.headers())
+        .unwrap_or_default()
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_parse() {
+        let cx = TraceContext::parse(TRACEPARENT, Some("congo=t61rcWkgMzE")).unwrap();
+        assert_eq!(cx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(cx.parent_id(), "00f067aa0ba902b7");
+        assert!(cx.sampled());
+        assert_eq!(
+            cx.headers(),
+            vec![
+                (TRACEPARENT_HEADER, TRACEPARENT.to_string()),
+                (TRACESTATE_HEADER, "congo=t61rcWkgMzE".to_string()),
+            ]
+        );
+
+        let unsampled = TRACEPARENT.replace("-01", "-00");
+        assert!(!TraceContext::parse(&unsampled, Some("")).unwrap().sampled());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid() {
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e473g-00f067aa0ba902b7-01",
+        ] {
+            assert!(TraceContext::parse(invalid, None).is_none(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_current_without_span() {
+        assert!(current_headers().is_empty());
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn test_set_parent_and_current() {
+        use opentelemetry::trace::TracerProvider as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let remote = TraceContext::parse(TRACEPARENT, Some("congo=t61rcWkgMzE")).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            remote.set_parent(&span);
+            let _enter = span.enter();
+
+            let cx = TraceContext::current().unwrap();
+            assert_eq!(cx.trace_id(), remote.trace_id());
+            assert_ne!(cx.parent_id(), remote.parent_id());
+            assert_eq!(cx.tracestate(), Some("congo=t61rcWkgMzE"));
+            assert_eq!(current_headers().len(), 2);
+        });
+    }
+}
@@ -0,0 +1,694 @@
+//! Evaluating agents directly.
+//!
+//! [`EvalRunner::run_agent`] runs each case of a dataset through an
+//! [`Agent`], building the run from the case's [`AgentInput`]: its own
+//! dependencies, message history and model settings. The whole run is
+//! captured in an [`AgentRunOutput`], so evaluators can look beyond the final
+//! text at the tool calls the agent made and the tokens it used.
+//!
+//! [`EvalRunner::run_suite`] does the same for an [`EvalSuite`], building each
+//! case's dependencies with a [`Fixture`] so cases run hermetically.
+//!
+//! ```ignore
+//! use serdes_ai_evals::{AgentInput, Case, ContainsScorer, Dataset, EvalRunner};
+//!
+//! let dataset = Dataset::new()
+//!     .case(Case::new(AgentInput::new("What's the weather in Paris?"))
+//!         .with_expected_output("Paris".to_string()));
+//!
+//! let report = EvalRunner::new()
+//!     .evaluator(ContainsScorer::new("sunny"))
+//!     .run_agent(&agent, &dataset)
+//!     .await?;
+//!
+//! for case in &report.cases {
+//!     println!("{}: {:?}", case.name, case.output.tool_names());
+//! }
+//! ```
+
+use crate::case::{Case, EvalCase, Expected};
+use crate::dataset::Dataset;
+use crate::error::EvalResult;
+use crate::evaluator::{EvaluationResult, EvaluatorContext, NamedEvaluationResult, TypedEvaluator};
+use crate::events::Progress;
+use crate::fixture::Fixture;
+use crate::report::{CaseResult, EvaluationReport};
+use crate::runner::EvalRunner;
+use crate::suite::EvalSuite;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use serdes_ai_agent::{Agent, AgentRunResult, RunOptions, RunUsage};
+use serdes_ai_core::{ModelRequest, ModelSettings};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+
+/// Evaluator that sees the full context of an agent case.
+pub type AgentEvaluator<Deps, Output, Expected = String, Metadata = ()> =
+    dyn TypedEvaluator<AgentInput<Deps>, AgentRunOutput<Output>, Expected, Metadata>;
+
+/// Name of the evaluation recorded when the agent run itself fails.
+pub const AGENT_RUN_EVALUATOR: &str = "AgentRun";
+
+/// Name of the evaluation recorded when a case's fixture fails.
+pub const FIXTURE_EVALUATOR: &str = "Fixture";
+
+/// Inputs of a case run against an agent.
+#[derive(Debug, Clone)]
+pub struct AgentInput<Deps = ()> {
+    /// The user prompt.
+    pub prompt: String,
+    /// Dependencies for this case's run.
+    pub deps: Deps,
+    /// Conversation to continue from.
+    pub message_history: Vec<ModelRequest>,
+    /// Model settings for this case, over the agent defaults.
+    pub model_settings: Option<ModelSettings>,
+}
+
+impl AgentInput<()> {
+    /// Create inputs for an agent without dependencies.
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self::with_deps(prompt, ())
+    }
+}
+
+impl<Deps> AgentInput<Deps> {
+    /// Create inputs with dependencies for this case.
+    pub fn with_deps(prompt: impl Into<String>, deps: Deps) -> Self {
+        Self {
+            prompt: prompt.into(),
+            deps,
+            message_history: Vec::new(),
+            model_settings: None,
+        }
+    }
+
+    /// Set the conversation to continue from.
+    pub fn with_message_history(mut self, history: Vec<ModelRequest>) -> Self {
+        self.message_history = history;
+        self
+    }
+
+    /// Set model settings for this case.
+    pub fn with_model_settings(mut self, settings: ModelSettings) -> Self {
+        self.model_settings = Some(settings);
+        self
+    }
+
+    /// Build the run options for this case.
+    pub fn run_options(&self) -> RunOptions {
+        let mut options = RunOptions::new();
+        if !self.message_history.is_empty() {
+            options = options.message_history(self.message_history.clone());
+        }
+        if let Some(settings) = &self.model_settings {
+            options = options.model_settings(settings.clone());
+        }
+        options
+    }
+}
+
+/// A tool call made during an agent run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    /// Name of the tool.
+    pub tool_name: String,
+    /// Arguments passed to the tool.
+    pub args: JsonValue,
+}
+
+/// Everything captured from running one case through an agent.
+#[derive(Debug, Clone)]
+pub struct AgentRunOutput<Output> {
+    /// The run result, or the error message if the run failed.
+    pub result: Result<AgentRunResult<Output>, String>,
+    /// The output as text: strings as-is, structured output as JSON.
+    pub text: String,
+    /// Tool calls made by the agent, in order.
+    pub tool_calls: Vec<ToolCallRecord>,
+}
+
+impl<Output: Serialize> AgentRunOutput<Output> {
+    /// Capture the result of a run.
+    pub fn from_result<E: fmt::Display>(result: Result<AgentRunResult<Output>, E>) -> Self {
+        match result {
+            Ok(result) => {
+                let text = match serde_json::to_value(&result.output) {
+                    Ok(JsonValue::String(text)) => text,
+                    Ok(value) => value.to_string(),
+                    Err(e) => format!("<unserializable output: {}>", e),
+                };
+                let tool_calls = result
+                    .responses
+                    .iter()
+                    .flat_map(|response| response.tool_call_parts())
+                    .map(|call| ToolCallRecord {
+                        tool_name: call.tool_name.clone(),
+                        args: call.args.to_json(),
+                    })
+                    .collect();
+                Self {
+                    result: Ok(result),
+                    text,
+                    tool_calls,
+                }
+            }
+            Err(e) => Self {
+                text: e.to_string(),
+                result: Err(e.to_string()),
+                tool_calls: Vec::new(),
+            },
+        }
+    }
+}
+
+impl<Output> AgentRunOutput<Output> {
+    /// Get the output, if the run succeeded.
+    pub fn output(&self) -> Option<&Output> {
+        self.result.as_ref().ok().map(|r| &r.output)
+    }
+
+    /// Get the error, if the run failed.
+    pub fn error(&self) -> Option<&str> {
+        self.result.as_ref().err().map(String::as_str)
+    }
+
+    /// Get token usage, if the run succeeded.
+    pub fn usage(&self) -> Option<&RunUsage> {
+        self.result.as_ref().ok().map(|r| &r.usage)
+    }
+
+    /// Get the names of the tools called, in order.
+    pub fn tool_names(&self) -> Vec<&str> {
+        self.tool_calls
+            .iter()
+            .map(|c| c.tool_name.as_str())
+            .collect()
+    }
+
+    /// Check whether a tool was called.
+    pub fn called_tool(&self, name: &str) -> bool {
+        self.tool_calls.iter().any(|c| c.tool_name == name)
+    }
+}
+
+impl<Output> AsRef<str> for AgentRunOutput<Output> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl<Output> fmt::Display for AgentRunOutput<Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl EvalRunner {
+    /// Run each case of a dataset through an agent.
+    ///
+    /// The runner's evaluators score the output text against the expected
+    /// output. Cases whose run fails get an [`AGENT_RUN_EVALUATOR`] error
+    /// instead of being scored.
+    pub async fn run_agent<Deps, Output, Expected, Metadata>(
+        &self,
+        agent: &Agent<Deps, Output>,
+        dataset: &Dataset<AgentInput<Deps>, Expected, Metadata>,
+    ) -> EvalResult<EvaluationReport<AgentRunOutput<Output>>>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        Expected: AsRef<str> + Send + Sync,
+        Metadata: Send + Sync,
+    {
+        self.run_agent_with(agent, dataset, &[]).await
+    }
+
+    /// Like [`run_agent`](Self::run_agent), additionally scoring each case
+    /// with evaluators that see its inputs, the whole run and its metadata.
+    pub async fn run_agent_with<Deps, Output, Expected, Metadata>(
+        &self,
+        agent: &Agent<Deps, Output>,
+        dataset: &Dataset<AgentInput<Deps>, Expected, Metadata>,
+        evaluators: &[&AgentEvaluator<Deps, Output, Expected, Metadata>],
+    ) -> EvalResult<EvaluationReport<AgentRunOutput<Output>>>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        Expected: AsRef<str> + Send + Sync,
+        Metadata: Send + Sync,
+    {
+        let progress = Progress::start(&self.events, dataset.name.clone(), dataset.len());
+        let results = self
+            .run_cases(&progress, dataset.cases.len(), |idx| {
+                let case = &dataset.cases[idx];
+                (
+                    case.display_name(idx),
+                    self.run_agent_case(idx, case, agent, evaluators),
+                )
+            })
+            .await;
+        let report = EvaluationReport::new(results);
+        progress.finish(&report.summary);
+        Ok(report)
+    }
+
+    /// Run each case of a suite through an agent, building its dependencies
+    /// per case with `fixture`.
+    ///
+    /// The agent gets a clone of the dependencies; the fixture tears down the
+    /// original once the case is scored, whether it passed or not. Outputs
+    /// are scored by the case's expectations and by the runner's evaluators,
+    /// which get the case's first exact expectation as the expected output.
+    pub async fn run_suite<Deps, Output, F>(
+        &self,
+        agent: &Agent<Deps, Output>,
+        suite: &EvalSuite,
+        fixture: &F,
+    ) -> EvalResult<EvaluationReport<AgentRunOutput<Output>>>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        F: Fixture<Deps>,
+    {
+        let progress = Progress::start(&self.events, Some(suite.name.clone()), suite.len());
+        let results = self
+            .run_cases(&progress, suite.cases.len(), |idx| {
+                let case = &suite.cases[idx];
+                (
+                    suite_case_name(case, idx),
+                    self.run_suite_case(idx, case, agent, fixture),
+                )
+            })
+            .await;
+        let report = EvaluationReport::new(results).with_name(suite.name.clone());
+        progress.finish(&report.summary);
+        Ok(report)
+    }
+
+    /// Run cases concurrently, or one by one until the first failure in
+    /// fail-fast mode.
+    async fn run_cases<T, C, Fut>(
+        &self,
+        progress: &Progress<'_>,
+        len: usize,
+        run_case: C,
+    ) -> Vec<CaseResult<T>>
+    where
+        T: AsRef<str>,
+        C: Fn(usize) -> (String, Fut),
+        Fut: Future<Output = CaseResult<T>>,
+    {
+        let run_case = |idx| {
+            let (name, case) = run_case(idx);
+            async move {
+                progress.case_started(idx, name);
+                let result = case.await;
+                progress.case_finished(&result);
+                result
+            }
+        };
+
+        if self.options.fail_fast {
+            let mut results = Vec::new();
+            for idx in 0..len {
+                let result = run_case(idx).await;
+                let failed = result.failed() || result.errored();
+                results.push(result);
+                if failed {
+                    break;
+                }
+            }
+            return results;
+        }
+
+        let semaphore = Arc::new(Semaphore::new(self.options.concurrency.max(1)));
+        let run_case = &run_case;
+        let tasks: Vec<_> = (0..len)
+            .map(|idx| {
+                let sem = semaphore.clone();
+                async move {
+                    let _permit = sem.acquire().await.expect("Semaphore closed");
+                    run_case(idx).await
+                }
+            })
+            .collect();
+        futures::future::join_all(tasks).await
+    }
+
+    /// Helper to run a single case through the agent.
+    async fn run_agent_case<Deps, Output, Expected, Metadata>(
+        &self,
+        idx: usize,
+        case: &Case<AgentInput<Deps>, Expected, Metadata>,
+        agent: &Agent<Deps, Output>,
+        evaluators: &[&AgentEvaluator<Deps, Output, Expected, Metadata>],
+    ) -> CaseResult<AgentRunOutput<Output>>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        Expected: AsRef<str> + Send + Sync,
+        Metadata: Send + Sync,
+    {
+        let name = case.display_name(idx);
+        let start = Instant::now();
+
+        let input = &case.inputs;
+        let result = agent
+            .run_with_options(
+                input.prompt.clone(),
+                input.deps.clone(),
+                input.run_options(),
+            )
+            .await;
+        let output = AgentRunOutput::from_result(result);
+
+        if let Some(error) = output.error() {
+            let evaluations = vec![NamedEvaluationResult::new(
+                AGENT_RUN_EVALUATOR,
+                EvaluationResult::error(error),
+            )];
+            return CaseResult::new(name, idx, output, evaluations, start.elapsed());
+        }
+
+        if self.options.skip_without_expected && case.expected_output.is_none() {
+            return CaseResult::new(name, idx, output, Vec::new(), start.elapsed());
+        }
+
+        let expected_str = case.expected_output.as_ref().map(|e| e.as_ref());
+        let ctx = EvaluatorContext::new(
+            input,
+            &output,
+            case.expected_output.as_ref(),
+            case.metadata.as_ref(),
+        );
+        let eval_future = async {
+            let mut results = self.evaluators.evaluate(&output.text, expected_str).await;
+            for evaluator in evaluators {
+                let result = evaluator.evaluate(&ctx).await;
+                results.push(NamedEvaluationResult::new(evaluator.name(), result));
+            }
+            results
+        };
+        let evaluations = self.with_eval_timeout(eval_future).await;
+
+        CaseResult::new(name, idx, output, evaluations, start.elapsed())
+    }
+
+    /// Helper to run a single suite case with its fixture.
+    async fn run_suite_case<Deps, Output, F>(
+        &self,
+        idx: usize,
+        case: &EvalCase,
+        agent: &Agent<Deps, Output>,
+        fixture: &F,
+    ) -> CaseResult<AgentRunOutput<Output>>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        F: Fixture<Deps>,
+    {
+        let name = suite_case_name(case, idx);
+        let start = Instant::now();
+
+        let deps = match fixture.setup(case).await {
+            Ok(deps) => deps,
+            Err(e) => {
+                let error = format!("Fixture setup failed: {}", e);
+                let evaluations = vec![NamedEvaluationResult::new(
+                    FIXTURE_EVALUATOR,
+                    EvaluationResult::error(&error),
+                )];
+                let output = AgentRunOutput::from_result(Err(error));
+                return CaseResult::new(name, idx, output, evaluations, start.elapsed());
+            }
+        };
+
+        let result = agent.run(case.input.clone(), deps.clone()).await;
+        let output = AgentRunOutput::from_result(result);
+
+        let mut evaluations = if let Some(error) = output.error() {
+            vec![NamedEvaluationResult::new(
+                AGENT_RUN_EVALUATOR,
+                EvaluationResult::error(error),
+            )]
+        } else if self.options.skip_without_expected && case.expected.is_empty() {
+            Vec::new()
+        } else {
+            let expected = case.expected.iter().find_map(|e| match e {
+                Expected::Exact { value } => Some(value.as_str()),
+                _ => None,
+            });
+            let eval_future = async {
+                let mut results = case.evaluate(&output.text);
+                results.extend(self.evaluators.evaluate(&output.text, expected).await);
+                results
+            };
+            self.with_eval_timeout(eval_future).await
+        };
+
+        if let Err(e) = fixture.teardown(deps).await {
+            evaluations.push(NamedEvaluationResult::new(
+                FIXTURE_EVALUATOR,
+                EvaluationResult::error(format!("Fixture teardown failed: {}", e)),
+            ));
+        }
+
+        CaseResult::new(name, idx, output, evaluations, start.elapsed())
+    }
+
+    async fn with_eval_timeout(
+        &self,
+        eval_future: impl Future<Output = Vec<NamedEvaluationResult>>,
+    ) -> Vec<NamedEvaluationResult> {
+        match self.options.timeout {
+            Some(timeout_duration) => match timeout(timeout_duration, eval_future).await {
+                Ok(results) => results,
+                Err(_) => vec![NamedEvaluationResult::new(
+                    "Timeout",
+                    EvaluationResult::Error {
+                        error: format!("Evaluation exceeded timeout of {:?}", timeout_duration),
+                    },
+                )],
+            },
+            None => eval_future.await,
+        }
+    }
+}
+
+fn suite_case_name(case: &EvalCase, idx: usize) -> String {
+    case.name.clone().unwrap_or_else(|| format!("case_{}", idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scorers::ContainsScorer;
+    use async_trait::async_trait;
+    use serdes_ai_core::{ModelResponse, ModelResponsePart};
+    use serdes_ai_models::FunctionModel;
+
+    /// Calls `lookup` once, then answers with the number of user prompts seen.
+    fn lookup_model() -> FunctionModel {
+        FunctionModel::new(|messages, _| {
+            let has_return = messages.last().is_some_and(|m| {
+                m.parts
+                    .iter()
+                    .any(|p| matches!(p, serdes_ai_core::ModelRequestPart::ToolReturn(_)))
+            });
+            if has_return {
+                let prompts: usize = messages.iter().map(|m| m.user_prompts().count()).sum();
+                ModelResponse::text(format!("answer after {} prompts", prompts))
+            } else {
+                ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    "lookup",
+                    serde_json::json!({"key": "a"}),
+                )])
+            }
+        })
+    }
+
+    fn agent() -> Agent<String, String> {
+        serdes_ai_agent::agent_with_deps::<String, _>(lookup_model())
+            .tool_fn("lookup", "Look up a key", |ctx, _args: JsonValue| {
+                Ok(serdes_ai_tools::ToolReturn::text(ctx.deps.as_str()))
+            })
+            .build()
+    }
+
+    struct CalledLookup;
+
+    #[async_trait]
+    impl TypedEvaluator<AgentInput<String>, AgentRunOutput<String>, String> for CalledLookup {
+        fn name(&self) -> &str {
+            "CalledLookup"
+        }
+
+        async fn evaluate(
+            &self,
+            ctx: &EvaluatorContext<'_, AgentInput<String>, AgentRunOutput<String>, String>,
+        ) -> EvaluationResult {
+            if ctx.output.called_tool("lookup") && ctx.inputs.deps == "secret" {
+                EvaluationResult::pass()
+            } else {
+                EvaluationResult::fail("lookup not called")
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_agent_captures_run() {
+        let mut history = ModelRequest::new();
+        history.add_user_prompt("Earlier question");
+        let dataset = Dataset::new()
+            .case(
+                Case::new(AgentInput::with_deps("Look up a", "secret".to_string()))
+                    .with_expected_output("answer".to_string()),
+            )
+            .case(
+                Case::new(
+                    AgentInput::with_deps("Again", "secret".to_string())
+                        .with_message_history(vec![history]),
+                )
+                .with_expected_output("answer".to_string()),
+            );
+
+        let report = EvalRunner::new()
+            .evaluator(ContainsScorer::new("answer"))
+            .run_agent_with(&agent(), &dataset, &[&CalledLookup])
+            .await
+            .unwrap();
+
+        assert_eq!(report.cases.len(), 2);
+        let first = &report.cases[0];
+        assert!(first.passed(), "{}", first);
+        assert_eq!(first.output.tool_names(), vec!["lookup"]);
+        assert_eq!(
+            first.output.tool_calls[0].args,
+            serde_json::json!({"key": "a"})
+        );
+        assert!(first.output.usage().is_some());
+        assert_eq!(first.output.result.as_ref().unwrap().responses.len(), 2);
+
+        assert_eq!(first.output.text, "answer after 1 prompts");
+
+        // The message history is part of the second run
+        assert_eq!(report.cases[1].output.text, "answer after 2 prompts");
+    }
+
+    #[tokio::test]
+    async fn test_run_agent_records_run_errors() {
+        let model = serdes_ai_models::MockModel::new("mock").with_text_response("Hello");
+        let agent = serdes_ai_agent::agent(model)
+            .max_output_retries(0)
+            .output_validator_fn(|_, _| {
+                Err(serdes_ai_agent::OutputValidationError::failed("rejected"))
+            })
+            .build();
+        let dataset: Dataset<AgentInput, String> =
+            Dataset::new().case(Case::new(AgentInput::new("Hi")));
+
+        let report = EvalRunner::new()
+            .evaluator(ContainsScorer::new("x"))
+            .run_agent(&agent, &dataset)
+            .await
+            .unwrap();
+
+        let case = &report.cases[0];
+        assert!(case.output.error().is_some());
+        assert_eq!(case.evaluations[0].evaluator, AGENT_RUN_EVALUATOR);
+        assert!(case.errored());
+    }
+
+    type FakeDb = Arc<std::sync::Mutex<Vec<String>>>;
+
+    /// Adds a user, then answers with everyone in the database.
+    fn users_agent() -> Agent<FakeDb, String> {
+        let model = FunctionModel::new(|messages, _| {
+            let users = messages.last().and_then(|m| {
+                m.parts.iter().find_map(|p| match p {
+                    serdes_ai_core::ModelRequestPart::ToolReturn(r) => {
+                        Some(r.content.to_string_content())
+                    }
+                    _ => None,
+                })
+            });
+            match users {
+                Some(users) => ModelResponse::text(users),
+                None => ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                    "add_user",
+                    serde_json::json!({"name": "new"}),
+                )]),
+            }
+        });
+        serdes_ai_agent::agent_with_deps::<FakeDb, _>(model)
+            .tool_fn("add_user", "Add a user", |ctx, args: JsonValue| {
+                let mut users = ctx.deps.lock().unwrap();
+                users.push(args["name"].as_str().unwrap_or_default().to_string());
+                Ok(serdes_ai_tools::ToolReturn::text(users.join(",")))
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_run_suite_with_fixture() {
+        use crate::fixture::fixture_fn;
+        use crate::scorers::ExactMatchScorer;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let torn_down = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&torn_down);
+        let fixture = fixture_fn(|case: &EvalCase| {
+            let users: Vec<String> = case
+                .fixture
+                .clone()
+                .and_then(|seed| serde_json::from_value(seed).ok())
+                .unwrap_or_default();
+            async move { Ok(Arc::new(std::sync::Mutex::new(users))) }
+        })
+        .with_teardown(move |_db: FakeDb| {
+            let counter = Arc::clone(&counter);
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        // Each case starts from its own seed, whatever the others wrote
+        let suite = EvalSuite::new("users")
+            .add_case(
+                EvalCase::new()
+                    .name("seeded")
+                    .input("Add a user")
+                    .fixture(serde_json::json!(["ada"]))
+                    .expected_exact("ada,new"),
+            )
+            .add_case(EvalCase::new().input("Add a user").expected_exact("new"));
+
+        let report = EvalRunner::new()
+            .evaluator(ExactMatchScorer::new())
+            .run_suite(&users_agent(), &suite, &fixture)
+            .await
+            .unwrap();
+
+        assert_eq!(report.summary.passed, 2);
+        assert_eq!(report.cases[0].name, "seeded");
+        assert_eq!(report.cases[1].name, "case_1");
+        assert_eq!(report.cases[0].evaluations.len(), 2);
+        assert_eq!(torn_down.load(Ordering::SeqCst), 2);
+
+        let failing = fixture_fn(|_: &EvalCase| async {
+            Err::<FakeDb, _>(crate::EvalError::task_failed("database unavailable"))
+        });
+        let report = EvalRunner::new()
+            .run_suite(&users_agent(), &suite, &failing)
+            .await
+            .unwrap();
+        assert!(report.cases.iter().all(|c| c.errored()));
+        assert_eq!(report.cases[0].evaluations[0].evaluator, FIXTURE_EVALUATOR);
+    }
+}
@@ -1,5 +1,6 @@
 //! Evaluation case definitions.
 
+use crate::evaluator::{EvaluationResult, NamedEvaluationResult};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -93,6 +94,10 @@ pub struct EvalCase {
     /// Tags for filtering.
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Data a [`Fixture`](crate::Fixture) uses to build this case's
+    /// dependencies, e.g. rows to seed a fake database with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixture: Option<serde_json::Value>,
 }
 
 /// Expected output criteria.
@@ -177,6 +182,7 @@ impl EvalCase {
             input: String::new(),
             expected: Vec::new(),
             tags: Vec::new(),
+            fixture: None,
         }
     }
 
@@ -222,6 +228,12 @@ impl EvalCase {
         self
     }
 
+    /// Set the fixture data for this case.
+    pub fn fixture(mut self, data: serde_json::Value) -> Self {
+        self.fixture = Some(data);
+        self
+    }
+
     /// Check if all expectations are satisfied.
     pub fn check(&self, output: &str) -> Vec<(&Expected, bool)> {
         self.expected
@@ -245,6 +257,26 @@ impl EvalCase {
     pub fn all_pass(&self, output: &str) -> bool {
         self.check(output).iter().all(|(_, passed)| *passed)
     }
+
+    /// Evaluate each expectation, named after the expectation.
+    ///
+    /// Semantic and custom expectations can't be checked here and are
+    /// skipped.
+    pub fn evaluate(&self, output: &str) -> Vec<NamedEvaluationResult> {
+        self.check(output)
+            .into_iter()
+            .map(|(exp, passed)| {
+                let result = match exp {
+                    Expected::Semantic { .. } | Expected::Custom { .. } => {
+                        EvaluationResult::skip("requires an external evaluator")
+                    }
+                    _ if passed => EvaluationResult::pass(),
+                    _ => EvaluationResult::fail(format!("expected {}", exp)),
+                };
+                NamedEvaluationResult::new(exp.to_string(), result)
+            })
+            .collect()
+    }
 }
 
 impl Default for EvalCase {
@@ -299,6 +331,33 @@ mod tests {
         assert!(!case.all_pass("hello world"));
     }
 
+    #[test]
+    fn test_eval_case_evaluate() {
+        let case = EvalCase::new()
+            .expected_contains("4")
+            .expected_exact("5")
+            .expected_semantic("four", 0.8);
+
+        let results = case.evaluate("4");
+        assert!(results[0].result.is_pass());
+        assert_eq!(results[0].evaluator, "contains(4)");
+        assert!(results[1].result.is_fail());
+        assert!(results[2].result.is_skip());
+    }
+
+    #[test]
+    fn test_eval_case_fixture_roundtrip() {
+        let case = EvalCase::new()
+            .input("List users")
+            .fixture(serde_json::json!({"users": ["ada"]}));
+        let json = serde_json::to_string(&case).unwrap();
+        let parsed: EvalCase = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.fixture, case.fixture);
+
+        let json = serde_json::to_string(&EvalCase::new()).unwrap();
+        assert!(!json.contains("fixture"));
+    }
+
     #[test]
     fn test_expected_display() {
         assert_eq!(Expected::exact("foo").to_string(), "exact(foo)");
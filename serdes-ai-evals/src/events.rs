@@ -0,0 +1,148 @@
+//! Progress events from evaluation runs.
+//!
+//! [`EvalRunner::subscribe`](crate::EvalRunner::subscribe) returns a receiver
+//! of [`EvalEvent`]s, so long-running evaluations can drive a dashboard while
+//! they run instead of waiting for the final report:
+//!
+//! ```ignore
+//! let runner = EvalRunner::new().evaluator(ExactMatchScorer::new());
+//! let mut events = runner.subscribe();
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         if let EvalEvent::SuiteProgress { completed, total, .. } = event {
+//!             println!("{completed}/{total}");
+//!         }
+//!     }
+//! });
+//! let report = runner.run_dataset(&dataset, task).await?;
+//! ```
+
+use crate::evaluator::NamedEvaluationResult;
+use crate::report::{CaseResult, ReportSummary};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before it lags.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Event emitted while an evaluation runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EvalEvent {
+    /// A run started.
+    SuiteStarted {
+        /// Name of the suite or dataset, if any.
+        name: Option<String>,
+        /// Number of cases to run.
+        total_cases: usize,
+    },
+    /// A case started.
+    CaseStarted {
+        /// Case index.
+        index: usize,
+        /// Case name.
+        name: String,
+    },
+    /// A case finished and was scored.
+    CaseFinished {
+        /// Case index.
+        index: usize,
+        /// Case name.
+        name: String,
+        /// Task output as text.
+        output: String,
+        /// Whether all evaluations passed.
+        passed: bool,
+        /// Evaluator results.
+        evaluations: Vec<NamedEvaluationResult>,
+        /// Execution duration in milliseconds.
+        duration_ms: u64,
+    },
+    /// Progress after a case finished.
+    SuiteProgress {
+        /// Cases finished so far.
+        completed: usize,
+        /// Number of cases to run.
+        total: usize,
+        /// Cases that passed so far.
+        passed: usize,
+        /// Cases with a failed evaluation so far.
+        failed: usize,
+    },
+    /// A run finished.
+    SuiteFinished {
+        /// Summary of the final report.
+        summary: ReportSummary,
+    },
+}
+
+/// Reports the progress of one run to subscribers.
+pub(crate) struct Progress<'a> {
+    events: &'a broadcast::Sender<EvalEvent>,
+    total: usize,
+    completed: AtomicUsize,
+    passed: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl<'a> Progress<'a> {
+    /// Start tracking a run of `total` cases.
+    pub(crate) fn start(
+        events: &'a broadcast::Sender<EvalEvent>,
+        name: Option<String>,
+        total: usize,
+    ) -> Self {
+        // Sending only fails without subscribers.
+        let _ = events.send(EvalEvent::SuiteStarted {
+            name,
+            total_cases: total,
+        });
+        Self {
+            events,
+            total,
+            completed: AtomicUsize::new(0),
+            passed: AtomicUsize::new(0),
+            failed: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn case_started(&self, index: usize, name: impl Into<String>) {
+        let _ = self.events.send(EvalEvent::CaseStarted {
+            index,
+            name: name.into(),
+        });
+    }
+
+    pub(crate) fn case_finished<T: AsRef<str>>(&self, result: &CaseResult<T>) {
+        let passed = result.passed();
+        let _ = self.events.send(EvalEvent::CaseFinished {
+            index: result.index,
+            name: result.name.clone(),
+            output: result.output.as_ref().to_string(),
+            passed,
+            evaluations: result.evaluations.clone(),
+            duration_ms: result.duration.as_millis() as u64,
+        });
+
+        let completed = self.completed.fetch_add(1, Ordering::SeqCst) + 1;
+        if passed {
+            self.passed.fetch_add(1, Ordering::SeqCst);
+        }
+        if result.failed() {
+            self.failed.fetch_add(1, Ordering::SeqCst);
+        }
+        let _ = self.events.send(EvalEvent::SuiteProgress {
+            completed,
+            total: self.total,
+            passed: self.passed.load(Ordering::SeqCst),
+            failed: self.failed.load(Ordering::SeqCst),
+        });
+    }
+
+    pub(crate) fn finish(&self, summary: &ReportSummary) {
+        let _ = self.events.send(EvalEvent::SuiteFinished {
+            summary: summary.clone(),
+        });
+    }
+}
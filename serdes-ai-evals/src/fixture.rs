@@ -0,0 +1,99 @@
+//! Per-case fixtures.
+//!
+//! A [`Fixture`] builds fresh dependencies for every [`EvalCase`], e.g. a fake
+//! database seeded from the case's [`fixture`](EvalCase::fixture) data, and
+//! tears them down once the case is scored. Cases that exercise tools then run
+//! hermetically: nothing one case writes is visible to another.
+//!
+//! ```ignore
+//! use serdes_ai_evals::{fixture_fn, EvalCase};
+//!
+//! let fixture = fixture_fn(|case: &EvalCase| {
+//!     let rows = case.fixture.clone().unwrap_or_default();
+//!     async move { Ok(FakeDb::seeded(rows)) }
+//! })
+//! .with_teardown(|db: FakeDb| async move {
+//!     db.close().await;
+//!     Ok(())
+//! });
+//! ```
+
+use crate::case::EvalCase;
+use crate::error::EvalResult;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::future::Future;
+
+/// Builds and tears down the dependencies of a single case.
+#[async_trait]
+pub trait Fixture<Deps: Send + 'static>: Send + Sync {
+    /// Build dependencies for a case.
+    async fn setup(&self, case: &EvalCase) -> EvalResult<Deps>;
+
+    /// Release the dependencies once the case is done.
+    ///
+    /// Called whether the case passed or not. The default just drops them.
+    async fn teardown(&self, deps: Deps) -> EvalResult<()> {
+        drop(deps);
+        Ok(())
+    }
+}
+
+/// Fixture for agents without dependencies.
+#[async_trait]
+impl Fixture<()> for () {
+    async fn setup(&self, _case: &EvalCase) -> EvalResult<()> {
+        Ok(())
+    }
+}
+
+type TeardownFn<Deps> = dyn Fn(Deps) -> BoxFuture<'static, EvalResult<()>> + Send + Sync;
+
+/// Fixture built from closures, see [`fixture_fn`].
+pub struct FnFixture<Deps, S> {
+    setup: S,
+    teardown: Option<Box<TeardownFn<Deps>>>,
+}
+
+/// Create a fixture from a setup function.
+pub fn fixture_fn<Deps, S, Fut>(setup: S) -> FnFixture<Deps, S>
+where
+    S: Fn(&EvalCase) -> Fut + Send + Sync,
+    Fut: Future<Output = EvalResult<Deps>> + Send,
+{
+    FnFixture {
+        setup,
+        teardown: None,
+    }
+}
+
+impl<Deps, S> FnFixture<Deps, S> {
+    /// Set the teardown function.
+    pub fn with_teardown<T, Fut>(mut self, teardown: T) -> Self
+    where
+        T: Fn(Deps) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = EvalResult<()>> + Send + 'static,
+    {
+        self.teardown = Some(Box::new(move |deps| Box::pin(teardown(deps))));
+        self
+    }
+}
+
+#[async_trait]
+impl<Deps, S, Fut> Fixture<Deps> for FnFixture<Deps, S>
+where
+    Deps: Send + 'static,
+    S: Fn(&EvalCase) -> Fut + Send + Sync,
+    Fut: Future<Output = EvalResult<Deps>> + Send,
+{
+    async fn setup(&self, case: &EvalCase) -> EvalResult<Deps> {
+        (self.setup)(case).await
+    }
+
+    async fn teardown(&self, deps: Deps) -> EvalResult<()> {
+        match &self.teardown {
+            Some(teardown) => teardown(deps).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -10,8 +10,13 @@
 //! - **[`Case`] / [`EvalCase`]**: Individual test cases with inputs and expected outputs
 //! - **[`Dataset`] / [`EvalSuite`]**: Collections of test cases
 //! - **[`Evaluator`]**: Trait for implementing custom evaluators
-//! - **[`EvalRunner`]**: Runs evaluations and collects results
+//! - **[`EvalRunner`]**: Runs evaluations and collects results, or runs an
+//!   agent over a dataset directly with `run_agent` (feature `agent`)
 //! - **[`EvaluationReport`]**: Detailed results with statistics
+//! - **[`EvalEvent`]**: Progress events streamed while evaluations run
+//! - **[`Fixture`]**: Builds and tears down per-case dependencies
+//! - **[`PromptOptimizer`]**: Improves an agent's system prompt against a
+//!   suite with a meta-model (feature `agent`)
 //!
 //! ## Built-in Evaluators
 //!
@@ -19,6 +24,10 @@
 //! - **[`ContainsScorer`]**: Output must contain expected substring
 //! - **[`RegexScorer`]**: Output must match regex pattern
 //! - **[`LengthScorer`]**: Output must meet length constraints
+//! - **[`AbsoluteToleranceScorer`]**: Number in the output must be within a
+//!   tolerance of the expected value
+//! - **[`NumericRangeScorer`]**: Number in the output must lie in a range
+//! - **[`JsonPathScorer`]**: Value at a path in JSON output must match
 //! - **[`FunctionScorer`]**: Custom evaluation function
 //!
 //! ## Example
@@ -60,11 +69,17 @@
 #![warn(missing_docs)]
 #![deny(unsafe_code)]
 
+#[cfg(feature = "agent")]
+pub mod agent;
 pub mod case;
 pub mod dataset;
 pub mod error;
 pub mod evaluator;
+pub mod events;
+pub mod fixture;
 pub mod metrics;
+#[cfg(feature = "agent")]
+pub mod optimizer;
 pub mod report;
 pub mod result;
 pub mod runner;
@@ -72,6 +87,11 @@ pub mod scorers;
 pub mod suite;
 
 // Re-exports
+#[cfg(feature = "agent")]
+pub use agent::{
+    AgentEvaluator, AgentInput, AgentRunOutput, ToolCallRecord, AGENT_RUN_EVALUATOR,
+    FIXTURE_EVALUATOR,
+};
 pub use case::{Case, EvalCase, Expected};
 pub use dataset::{Dataset, DatasetBuilder};
 pub use error::{EvalError, EvalResult};
@@ -79,13 +99,18 @@ pub use evaluator::{
     BoxedEvaluator, EvaluationResult, Evaluator, EvaluatorContext, EvaluatorSet,
     NamedEvaluationResult, TypedEvaluator,
 };
+pub use events::{EvalEvent, EVENT_CHANNEL_CAPACITY};
+pub use fixture::{fixture_fn, Fixture, FnFixture};
 pub use metrics::{AggregateMetrics, EvalMetrics, TokenUsage};
+#[cfg(feature = "agent")]
+pub use optimizer::{OptimizationReport, PromptOptimizer, PromptVariant, StopReason};
 pub use report::{CaseResult, EvaluationReport, EvaluatorStats, ReportSummary};
 pub use result::EvalResult as LegacyEvalResult;
 pub use runner::{quick_eval, EvalOptions, EvalRunner};
 pub use scorers::{
-    AlwaysFailScorer, AlwaysPassScorer, ContainsScorer, ExactMatchScorer, FunctionScorer,
-    LengthScorer, LlmJudgeScorer, NotContainsScorer, RegexScorer, Scorer,
+    extract_number, AbsoluteToleranceScorer, AlwaysFailScorer, AlwaysPassScorer, ContainsScorer,
+    ExactMatchScorer, FunctionScorer, JsonPathScorer, LengthScorer, LlmJudgeScorer,
+    NotContainsScorer, NumericRangeScorer, RegexScorer, Scorer,
 };
 pub use suite::EvalSuite;
 
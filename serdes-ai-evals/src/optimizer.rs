@@ -0,0 +1,501 @@
+//! Dataset-driven system prompt optimization.
+//!
+//! [`PromptOptimizer`] improves an agent's system prompt against an
+//! [`EvalSuite`]: a meta-model is shown the prompts tried so far with their
+//! scores, plus cases the best prompt gets wrong, and asked for a better
+//! prompt. Each proposal is scored by running the suite; it replaces the best
+//! prompt only if it scores higher. This is the APE/OPRO loop in its simplest
+//! form.
+//!
+//! Every proposal costs a full suite run, so the loop is bounded by a number
+//! of iterations, a number of evaluated variants and optionally a token
+//! budget. The [`OptimizationReport`] lists every variant tried.
+//!
+//! ```ignore
+//! use serdes_ai_evals::{EvalRunner, ExactMatchScorer, PromptOptimizer};
+//!
+//! let report = PromptOptimizer::new(meta_model)
+//!     .runner(EvalRunner::new().evaluator(ExactMatchScorer::new()))
+//!     .max_iterations(5)
+//!     .max_tokens(200_000)
+//!     .optimize(&agent, &suite, &())
+//!     .await?;
+//!
+//! println!("{}", report.to_text());
+//! let agent = agent.with_system_prompt(report.best_prompt());
+//! ```
+
+use crate::agent::AgentRunOutput;
+use crate::error::{EvalError, EvalResult};
+use crate::evaluator::EvaluationResult;
+use crate::fixture::Fixture;
+use crate::report::EvaluationReport;
+use crate::runner::EvalRunner;
+use crate::suite::EvalSuite;
+use serde::{Deserialize, Serialize};
+use serdes_ai_agent::Agent;
+use serdes_ai_models::Model;
+
+const META_SYSTEM_PROMPT: &str = "You improve system prompts for an AI assistant. \
+    You are shown prompts that were tried, with their scores on a test set from 0 to 1, \
+    and test cases the best prompt got wrong. Reply with a new system prompt only, \
+    without commentary, headings or quotes.";
+
+/// Number of failed cases shown to the meta-model.
+const MAX_FAILURE_EXAMPLES: usize = 3;
+
+/// A system prompt tried by the optimizer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptVariant {
+    /// The system prompt.
+    pub prompt: String,
+    /// Iteration that proposed it; 0 for the agent's original prompt.
+    pub iteration: usize,
+    /// Score on the suite, from 0 to 1.
+    pub score: f64,
+    /// Fraction of cases passed.
+    pub pass_rate: f64,
+    /// Whether it became the best prompt when it was tried.
+    pub accepted: bool,
+    /// Tokens used to propose and evaluate it.
+    pub tokens: u64,
+}
+
+/// Why the optimizer stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// All iterations ran.
+    Completed,
+    /// The maximum number of variants was evaluated.
+    VariantLimit,
+    /// The token budget was used up.
+    TokenLimit,
+}
+
+/// Result of an optimization run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// Variants in the order they were tried, starting with the original.
+    pub variants: Vec<PromptVariant>,
+    /// Index of the best variant.
+    pub best: usize,
+    /// Tokens used by the meta-model and the suite runs.
+    pub total_tokens: u64,
+    /// Why the optimizer stopped.
+    pub stop_reason: StopReason,
+}
+
+impl OptimizationReport {
+    /// Get the agent's original prompt and its score.
+    pub fn baseline(&self) -> &PromptVariant {
+        &self.variants[0]
+    }
+
+    /// Get the best variant.
+    pub fn best(&self) -> &PromptVariant {
+        &self.variants[self.best]
+    }
+
+    /// Get the best system prompt.
+    pub fn best_prompt(&self) -> &str {
+        &self.best().prompt
+    }
+
+    /// Get the score gained over the original prompt.
+    pub fn improvement(&self) -> f64 {
+        self.best().score - self.baseline().score
+    }
+
+    /// Render as text.
+    pub fn to_text(&self) -> String {
+        let mut output = String::from("\nPrompt Optimization Report\n");
+        output.push_str("══════════════════════════\n\n");
+        output.push_str(&format!(
+            "Baseline score: {:.3}\nBest score: {:.3} ({:+.3})\n",
+            self.baseline().score,
+            self.best().score,
+            self.improvement()
+        ));
+        output.push_str(&format!(
+            "Variants: {}\nTokens: {}\nStopped: {:?}\n\n",
+            self.variants.len(),
+            self.total_tokens,
+            self.stop_reason
+        ));
+        for (i, variant) in self.variants.iter().enumerate() {
+            let marker = if i == self.best { "*" } else { " " };
+            output.push_str(&format!(
+                "{} [{}] score {:.3}, pass rate {:.1}%: {}\n",
+                marker,
+                variant.iteration,
+                variant.score,
+                variant.pass_rate * 100.0,
+                first_line(&variant.prompt)
+            ));
+        }
+        output
+    }
+}
+
+fn first_line(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > 80 {
+        format!("{}...", line.chars().take(80).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Iteratively improves an agent's system prompt against an eval suite.
+pub struct PromptOptimizer {
+    meta_agent: Agent<(), String>,
+    runner: EvalRunner,
+    max_iterations: usize,
+    variants_per_iteration: usize,
+    max_variants: Option<usize>,
+    max_tokens: Option<u64>,
+    min_improvement: f64,
+}
+
+impl PromptOptimizer {
+    /// Create an optimizer proposing prompts with `meta_model`.
+    pub fn new<M: Model + 'static>(meta_model: M) -> Self {
+        let meta_agent = serdes_ai_agent::agent(meta_model)
+            .system_prompt(META_SYSTEM_PROMPT)
+            .temperature(1.0)
+            .build();
+        Self {
+            meta_agent,
+            runner: EvalRunner::new(),
+            max_iterations: 3,
+            variants_per_iteration: 2,
+            max_variants: None,
+            max_tokens: None,
+            min_improvement: 0.0,
+        }
+    }
+
+    /// Set the runner scoring each variant.
+    ///
+    /// Its evaluators score outputs in addition to the cases' expectations.
+    pub fn runner(mut self, runner: EvalRunner) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Set the number of iterations (default: 3).
+    pub fn max_iterations(mut self, n: usize) -> Self {
+        self.max_iterations = n;
+        self
+    }
+
+    /// Set the number of variants proposed per iteration (default: 2).
+    pub fn variants_per_iteration(mut self, n: usize) -> Self {
+        self.variants_per_iteration = n.max(1);
+        self
+    }
+
+    /// Stop after evaluating this many variants, not counting the original.
+    pub fn max_variants(mut self, n: usize) -> Self {
+        self.max_variants = Some(n);
+        self
+    }
+
+    /// Stop once the meta-model and the suite runs used this many tokens.
+    pub fn max_tokens(mut self, tokens: u64) -> Self {
+        self.max_tokens = Some(tokens);
+        self
+    }
+
+    /// Require variants to beat the best score by more than this margin.
+    pub fn min_improvement(mut self, margin: f64) -> Self {
+        self.min_improvement = margin;
+        self
+    }
+
+    /// Optimize the system prompt of `agent` against `suite`.
+    ///
+    /// The agent's own prompt is scored first as the baseline. Each case's
+    /// dependencies are built with `fixture`, as in [`EvalRunner::run_suite`].
+    pub async fn optimize<Deps, Output, F>(
+        &self,
+        agent: &Agent<Deps, Output>,
+        suite: &EvalSuite,
+        fixture: &F,
+    ) -> EvalResult<OptimizationReport>
+    where
+        Deps: Clone + Send + Sync + 'static,
+        Output: Serialize + Send + Sync + 'static,
+        F: Fixture<Deps>,
+    {
+        let baseline_prompt = agent.static_system_prompt().to_string();
+        let report = self.runner.run_suite(agent, suite, fixture).await?;
+        let tokens = run_tokens(&report);
+        let mut best_failures = failures(&report, suite);
+        let mut variants = vec![variant(baseline_prompt, 0, &report, true, tokens)];
+        let mut best = 0;
+        let mut total_tokens = tokens;
+
+        for iteration in 1..=self.max_iterations {
+            for _ in 0..self.variants_per_iteration {
+                if self.max_variants.is_some_and(|max| variants.len() > max) {
+                    return Ok(finish(
+                        variants,
+                        best,
+                        total_tokens,
+                        StopReason::VariantLimit,
+                    ));
+                }
+                if self.max_tokens.is_some_and(|max| total_tokens >= max) {
+                    return Ok(finish(variants, best, total_tokens, StopReason::TokenLimit));
+                }
+
+                let (prompt, meta_tokens) = self.propose(&variants, &best_failures).await?;
+                total_tokens += meta_tokens;
+                if prompt.is_empty() || variants.iter().any(|v| v.prompt == prompt) {
+                    continue;
+                }
+
+                let candidate = agent.with_system_prompt(prompt.clone());
+                let report = self.runner.run_suite(&candidate, suite, fixture).await?;
+                let tokens = run_tokens(&report);
+                total_tokens += tokens;
+
+                let score = score(&report);
+                let accepted = score > variants[best].score + self.min_improvement;
+                if accepted {
+                    best = variants.len();
+                    best_failures = failures(&report, suite);
+                }
+                variants.push(variant(
+                    prompt,
+                    iteration,
+                    &report,
+                    accepted,
+                    meta_tokens + tokens,
+                ));
+            }
+        }
+
+        Ok(finish(variants, best, total_tokens, StopReason::Completed))
+    }
+
+    /// Ask the meta-model for a new prompt, returning it and the tokens used.
+    async fn propose(
+        &self,
+        variants: &[PromptVariant],
+        failures: &[String],
+    ) -> EvalResult<(String, u64)> {
+        let mut tried: Vec<&PromptVariant> = variants.iter().collect();
+        tried.sort_by(|a, b| a.score.total_cmp(&b.score));
+
+        let mut request = String::from("Prompts tried so far, from lowest to highest score:\n\n");
+        for variant in tried {
+            request.push_str(&format!(
+                "<prompt score=\"{:.3}\">\n{}\n</prompt>\n\n",
+                variant.score, variant.prompt
+            ));
+        }
+        if !failures.is_empty() {
+            request.push_str("Cases the best prompt got wrong:\n\n");
+            for failure in failures {
+                request.push_str(failure);
+                request.push('\n');
+            }
+        }
+        request.push_str("Write a new system prompt that scores higher than all of the above.");
+
+        let result = self
+            .meta_agent
+            .run(request, ())
+            .await
+            .map_err(|e| EvalError::task_failed(format!("Meta-model failed: {}", e)))?;
+        let prompt = result
+            .output
+            .trim()
+            .trim_matches(|c| c == '"' || c == '`')
+            .trim()
+            .to_string();
+        Ok((prompt, result.usage.total_tokens))
+    }
+}
+
+impl std::fmt::Debug for PromptOptimizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PromptOptimizer")
+            .field("max_iterations", &self.max_iterations)
+            .field("variants_per_iteration", &self.variants_per_iteration)
+            .field("max_variants", &self.max_variants)
+            .field("max_tokens", &self.max_tokens)
+            .field("min_improvement", &self.min_improvement)
+            .finish_non_exhaustive()
+    }
+}
+
+fn finish(
+    variants: Vec<PromptVariant>,
+    best: usize,
+    total_tokens: u64,
+    stop_reason: StopReason,
+) -> OptimizationReport {
+    OptimizationReport {
+        variants,
+        best,
+        total_tokens,
+        stop_reason,
+    }
+}
+
+fn variant<Output>(
+    prompt: String,
+    iteration: usize,
+    report: &EvaluationReport<AgentRunOutput<Output>>,
+    accepted: bool,
+    tokens: u64,
+) -> PromptVariant {
+    PromptVariant {
+        prompt,
+        iteration,
+        score: score(report),
+        pass_rate: report.summary.pass_rate,
+        accepted,
+        tokens,
+    }
+}
+
+/// Score of a suite run: the average evaluator score, or the pass rate.
+fn score<T>(report: &EvaluationReport<T>) -> f64 {
+    report
+        .summary
+        .average_score
+        .unwrap_or(report.summary.pass_rate)
+}
+
+fn run_tokens<Output>(report: &EvaluationReport<AgentRunOutput<Output>>) -> u64 {
+    report
+        .cases
+        .iter()
+        .filter_map(|c| c.output.usage())
+        .map(|u| u.total_tokens)
+        .sum()
+}
+
+/// Describe failed cases for the meta-model.
+fn failures<Output>(
+    report: &EvaluationReport<AgentRunOutput<Output>>,
+    suite: &EvalSuite,
+) -> Vec<String> {
+    report
+        .cases
+        .iter()
+        .filter(|c| !c.passed())
+        .take(MAX_FAILURE_EXAMPLES)
+        .map(|c| {
+            let input = suite
+                .cases
+                .get(c.index)
+                .map(|case| case.input.as_str())
+                .unwrap_or_default();
+            let problems: Vec<String> = c
+                .evaluations
+                .iter()
+                .filter_map(|e| match &e.result {
+                    EvaluationResult::Fail { reason, .. } => {
+                        Some(format!("{}: {}", e.evaluator, reason))
+                    }
+                    EvaluationResult::Error { error } => {
+                        Some(format!("{}: {}", e.evaluator, error))
+                    }
+                    _ => None,
+                })
+                .collect();
+            format!(
+                "Input: {}\nOutput: {}\nProblems: {}\n",
+                input,
+                c.output.text,
+                problems.join("; ")
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case::EvalCase;
+    use serdes_ai_core::{ModelRequestPart, ModelResponse};
+    use serdes_ai_models::FunctionModel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Answers "yes" only when told to in the system prompt.
+    fn agent() -> Agent<(), String> {
+        let model = FunctionModel::new(|messages, _| {
+            let told = messages.iter().any(|m| {
+                m.parts.iter().any(
+                    |p| matches!(p, ModelRequestPart::SystemPrompt(s) if s.content.contains("yes")),
+                )
+            });
+            ModelResponse::text(if told { "yes" } else { "no" })
+        });
+        serdes_ai_agent::agent(model)
+            .system_prompt("Answer the question.")
+            .build()
+    }
+
+    fn suite() -> EvalSuite {
+        EvalSuite::new("yes")
+            .add_case(EvalCase::new().input("Is Rust fast?").expected_exact("yes"))
+            .add_case(EvalCase::new().input("Is Rust safe?").expected_exact("yes"))
+    }
+
+    fn meta_model(proposals: &'static [&'static str]) -> (FunctionModel, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let model = FunctionModel::new(move |_, _| {
+            let i = counter.fetch_add(1, Ordering::SeqCst);
+            ModelResponse::text(proposals[i.min(proposals.len() - 1)])
+        });
+        (model, calls)
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_keeps_improvements() {
+        let (model, calls) = meta_model(&["Always reply no.", "\"Always answer yes.\""]);
+        let report = PromptOptimizer::new(model)
+            .max_iterations(2)
+            .variants_per_iteration(2)
+            .optimize(&agent(), &suite(), &())
+            .await
+            .unwrap();
+
+        assert_eq!(report.stop_reason, StopReason::Completed);
+        assert_eq!(report.baseline().prompt, "Answer the question.");
+        assert_eq!(report.baseline().score, 0.0);
+        assert_eq!(report.best_prompt(), "Always answer yes.");
+        assert_eq!(report.improvement(), 1.0);
+        // Repeated proposals are not evaluated again
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(report.variants.len(), 3);
+        assert!(!report.variants[1].accepted);
+        assert!(report.variants[2].accepted);
+        assert!(report.to_text().contains("* [1] score 1.000"));
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_variant_limit() {
+        let (model, calls) = meta_model(&["Say no.", "Say yes."]);
+        let report = PromptOptimizer::new(model)
+            .max_iterations(5)
+            .max_variants(1)
+            .optimize(&agent(), &suite(), &())
+            .await
+            .unwrap();
+
+        assert_eq!(report.stop_reason, StopReason::VariantLimit);
+        assert_eq!(report.variants.len(), 2);
+        assert_eq!(report.best, 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -4,11 +4,12 @@ use crate::case::Case;
 use crate::dataset::Dataset;
 use crate::error::EvalResult;
 use crate::evaluator::{EvaluationResult, Evaluator, EvaluatorSet, NamedEvaluationResult};
+use crate::events::{EvalEvent, Progress, EVENT_CHANNEL_CAPACITY};
 use crate::report::{CaseResult, EvaluationReport};
 use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Semaphore;
+use tokio::sync::{broadcast, Semaphore};
 use tokio::time::timeout;
 
 /// Options for running evaluations.
@@ -77,19 +78,31 @@ impl EvalOptions {
 
 /// Evaluation runner.
 pub struct EvalRunner {
-    evaluators: EvaluatorSet,
-    options: EvalOptions,
+    pub(crate) evaluators: EvaluatorSet,
+    pub(crate) options: EvalOptions,
+    pub(crate) events: broadcast::Sender<EvalEvent>,
 }
 
 impl EvalRunner {
     /// Create a new runner.
     pub fn new() -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
         Self {
             evaluators: EvaluatorSet::new(),
             options: EvalOptions::default(),
+            events,
         }
     }
 
+    /// Subscribe to events from this runner's evaluations.
+    ///
+    /// Events are only sent while someone is subscribed. A subscriber that
+    /// falls more than [`EVENT_CHANNEL_CAPACITY`] events behind gets
+    /// [`broadcast::error::RecvError::Lagged`] and misses the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<EvalEvent> {
+        self.events.subscribe()
+    }
+
     /// Add an evaluator.
     pub fn evaluator<E: Evaluator + 'static>(mut self, evaluator: E) -> Self {
         self.evaluators = self.evaluators.with_evaluator(evaluator);
@@ -119,14 +132,17 @@ impl EvalRunner {
         let options = &self.options;
         let evaluators = &self.evaluators;
         let task = &task;
+        let progress = &Progress::start(&self.events, dataset.name.clone(), dataset.len());
 
         let mut results = Vec::new();
 
         if options.fail_fast {
             for (idx, case) in dataset.cases.iter().enumerate() {
+                progress.case_started(idx, case.display_name(idx));
                 let result = self
                     .run_single_case(idx, case, task, options, evaluators)
                     .await;
+                progress.case_finished(&result);
                 let failed = result.failed();
                 results.push(result);
                 if failed {
@@ -144,8 +160,12 @@ impl EvalRunner {
                     let sem = semaphore.clone();
                     async move {
                         let _permit = sem.acquire().await.expect("Semaphore closed");
-                        self.run_single_case(idx, case, task, options, evaluators)
-                            .await
+                        progress.case_started(idx, case.display_name(idx));
+                        let result = self
+                            .run_single_case(idx, case, task, options, evaluators)
+                            .await;
+                        progress.case_finished(&result);
+                        result
                     }
                 })
                 .collect();
@@ -153,7 +173,9 @@ impl EvalRunner {
             results = futures::future::join_all(tasks).await;
         }
 
-        Ok(EvaluationReport::new(results))
+        let report = EvaluationReport::new(results);
+        progress.finish(&report.summary);
+        Ok(report)
     }
 
     /// Helper to run a single case evaluation.
@@ -217,14 +239,17 @@ impl EvalRunner {
         let options = &self.options;
         let evaluators = &self.evaluators;
         let task = &task;
+        let progress = &Progress::start(&self.events, None, cases.len());
 
         let mut results = Vec::new();
 
         if options.fail_fast {
             for (idx, (input, expected)) in cases.iter().enumerate() {
+                progress.case_started(idx, format!("case_{}", idx));
                 let result = self
                     .run_simple_case(idx, input, expected, task, options, evaluators)
                     .await;
+                progress.case_finished(&result);
                 let failed = result.failed();
                 results.push(result);
                 if failed {
@@ -241,8 +266,12 @@ impl EvalRunner {
                     let sem = semaphore.clone();
                     async move {
                         let _permit = sem.acquire().await.expect("Semaphore closed");
-                        self.run_simple_case(idx, input, expected, task, options, evaluators)
-                            .await
+                        progress.case_started(idx, format!("case_{}", idx));
+                        let result = self
+                            .run_simple_case(idx, input, expected, task, options, evaluators)
+                            .await;
+                        progress.case_finished(&result);
+                        result
                     }
                 })
                 .collect();
@@ -250,7 +279,9 @@ impl EvalRunner {
             results = futures::future::join_all(tasks).await;
         }
 
-        Ok(EvaluationReport::new(results))
+        let report = EvaluationReport::new(results);
+        progress.finish(&report.summary);
+        Ok(report)
     }
 
     /// Helper to run a single simple case evaluation.
@@ -382,6 +413,60 @@ mod tests {
         assert!(options.verbose);
     }
 
+    #[tokio::test]
+    async fn test_eval_events() {
+        let runner = EvalRunner::new()
+            .evaluator(ExactMatchScorer::new())
+            .options(EvalOptions::new().concurrency(1));
+        let mut events = runner.subscribe();
+
+        let cases = vec![
+            ("a".to_string(), Some("a".to_string())),
+            ("b".to_string(), Some("c".to_string())),
+        ];
+        runner
+            .run_simple(&cases, |s| {
+                let s = s.to_string();
+                async move { s }
+            })
+            .await
+            .unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert_eq!(received.len(), 8);
+        assert!(matches!(
+            received[0],
+            EvalEvent::SuiteStarted { total_cases: 2, .. }
+        ));
+        assert!(
+            matches!(&received[1], EvalEvent::CaseStarted { index: 0, name } if name == "case_0")
+        );
+        assert!(matches!(
+            &received[2],
+            EvalEvent::CaseFinished { passed: true, output, .. } if output == "a"
+        ));
+        assert!(matches!(
+            received[6],
+            EvalEvent::SuiteProgress {
+                completed: 2,
+                total: 2,
+                passed: 1,
+                failed: 1
+            }
+        ));
+        match &received[7] {
+            EvalEvent::SuiteFinished { summary } => assert_eq!(summary.passed, 1),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Events serialize with a type tag for web dashboards
+        let json = serde_json::to_value(&received[3]).unwrap();
+        assert_eq!(json["type"], "suite_progress");
+    }
+
     #[tokio::test]
     async fn test_quick_eval() {
         let report = quick_eval(vec![("a", Some("a")), ("b", Some("c"))], |s| {
@@ -1,8 +1,11 @@
 //! Common evaluator implementations (scorers).
 
+use crate::error::{EvalError, EvalResult};
 use crate::evaluator::{EvaluationResult, Evaluator};
 use async_trait::async_trait;
 use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::sync::OnceLock;
 
 /// Re-export Scorer as alias for Evaluator.
 pub use crate::evaluator::Evaluator as Scorer;
@@ -366,6 +369,394 @@ where
     }
 }
 
+/// Extract the first number from text, e.g. `42` from "The answer is 42.".
+///
+/// Thousands separators (`1,234.5`) and exponents (`1e-3`) are understood.
+pub fn extract_number(text: &str) -> Option<f64> {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    let re = NUMBER.get_or_init(|| {
+        Regex::new(r"[-+]?(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?(?:[eE][-+]?\d+)?|[-+]?\.\d+")
+            .expect("valid number regex")
+    });
+    re.find(text)
+        .and_then(|m| m.as_str().replace(',', "").parse().ok())
+}
+
+/// Evaluator that compares the number in the output to a target, within a
+/// tolerance.
+///
+/// The target is the number in the expected output unless set with
+/// [`target`](Self::target).
+#[derive(Debug, Clone)]
+pub struct AbsoluteToleranceScorer {
+    /// Maximum allowed absolute difference.
+    pub tolerance: f64,
+    /// Fixed target, instead of the expected output.
+    pub target: Option<f64>,
+}
+
+impl AbsoluteToleranceScorer {
+    /// Create a scorer allowing the given absolute difference.
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance: tolerance.abs(),
+            target: None,
+        }
+    }
+
+    /// Compare against a fixed value instead of the expected output.
+    pub fn target(mut self, target: f64) -> Self {
+        self.target = Some(target);
+        self
+    }
+}
+
+#[async_trait]
+impl Evaluator for AbsoluteToleranceScorer {
+    fn name(&self) -> &str {
+        "AbsoluteTolerance"
+    }
+
+    async fn evaluate_str(&self, output: &str, expected: Option<&str>) -> EvaluationResult {
+        let target = match (self.target, expected) {
+            (Some(target), _) => target,
+            (None, Some(expected)) => match extract_number(expected) {
+                Some(target) => target,
+                None => return EvaluationResult::error("Expected output contains no number"),
+            },
+            (None, None) => return EvaluationResult::skip("No expected output provided"),
+        };
+        let Some(actual) = extract_number(output) else {
+            return EvaluationResult::fail("Output contains no number");
+        };
+        compare_within(actual, target, self.tolerance)
+    }
+}
+
+/// Evaluator that checks the number in the output lies within a range.
+#[derive(Debug, Clone, Default)]
+pub struct NumericRangeScorer {
+    /// Minimum value (inclusive).
+    pub min: Option<f64>,
+    /// Maximum value (inclusive).
+    pub max: Option<f64>,
+}
+
+impl NumericRangeScorer {
+    /// Create a new range scorer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum value.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Set the maximum value.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Set both min and max.
+    pub fn between(self, min: f64, max: f64) -> Self {
+        self.min(min).max(max)
+    }
+}
+
+#[async_trait]
+impl Evaluator for NumericRangeScorer {
+    fn name(&self) -> &str {
+        "NumericRange"
+    }
+
+    async fn evaluate_str(&self, output: &str, _expected: Option<&str>) -> EvaluationResult {
+        match extract_number(output) {
+            Some(actual) => check_range(actual, self.min, self.max),
+            None => EvaluationResult::fail("Output contains no number"),
+        }
+    }
+}
+
+fn compare_within(actual: f64, target: f64, tolerance: f64) -> EvaluationResult {
+    let diff = (actual - target).abs();
+    if diff <= tolerance {
+        EvaluationResult::pass()
+    } else {
+        EvaluationResult::fail_with_details(
+            format!("{} is not within {} of {}", actual, tolerance, target),
+            serde_json::json!({
+                "expected": target,
+                "actual": actual,
+                "difference": diff,
+                "tolerance": tolerance
+            }),
+        )
+    }
+}
+
+fn check_range(actual: f64, min: Option<f64>, max: Option<f64>) -> EvaluationResult {
+    if let Some(min) = min {
+        if actual < min {
+            return EvaluationResult::fail(format!("{} is below the minimum {}", actual, min));
+        }
+    }
+    if let Some(max) = max {
+        if actual > max {
+            return EvaluationResult::fail(format!("{} is above the maximum {}", actual, max));
+        }
+    }
+    EvaluationResult::pass()
+}
+
+/// Segment of a JSON path.
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Evaluator that checks a value inside JSON output.
+///
+/// The output is parsed as JSON; fenced code blocks and text around a single
+/// object or array are tolerated. Paths use a JSONPath subset: `$.a.b[0]`,
+/// `$['a key']`, or just `a.b.0`.
+///
+/// Without an assertion, the value at the path must equal the expected
+/// output (parsed as JSON when possible). Numbers compare within the
+/// tolerance, which defaults to exact.
+#[derive(Debug, Clone)]
+pub struct JsonPathScorer {
+    path: String,
+    segments: Vec<PathSegment>,
+    equals: Option<JsonValue>,
+    tolerance: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    exists_only: bool,
+}
+
+impl JsonPathScorer {
+    /// Create a scorer for the value at `path`.
+    pub fn new(path: impl Into<String>) -> EvalResult<Self> {
+        let path = path.into();
+        let segments = parse_json_path(&path).map_err(|message| {
+            EvalError::evaluator_failed("JsonPath", format!("invalid path '{}': {}", path, message))
+        })?;
+        Ok(Self {
+            path,
+            segments,
+            equals: None,
+            tolerance: 0.0,
+            min: None,
+            max: None,
+            exists_only: false,
+        })
+    }
+
+    /// Require the value to equal `value`, instead of the expected output.
+    pub fn equals(mut self, value: impl Into<JsonValue>) -> Self {
+        self.equals = Some(value.into());
+        self
+    }
+
+    /// Allow numbers to differ by up to `tolerance`.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+
+    /// Require the value to be a number within `min..=max`.
+    pub fn between(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Require the value to be a number of at least `min`.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Require the value to be a number of at most `max`.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Only require the path to exist.
+    pub fn exists(mut self) -> Self {
+        self.exists_only = true;
+        self
+    }
+
+    /// Get the path.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn select<'a>(&self, value: &'a JsonValue) -> Option<&'a JsonValue> {
+        self.segments
+            .iter()
+            .try_fold(value, |value, segment| match segment {
+                PathSegment::Key(key) => value.get(key),
+                PathSegment::Index(index) => {
+                    value.get(index).or_else(|| value.get(index.to_string()))
+                }
+            })
+    }
+
+    fn compare(&self, actual: &JsonValue, expected: &JsonValue) -> EvaluationResult {
+        if let (Some(actual), Some(target)) = (actual.as_f64(), json_number(expected)) {
+            return compare_within(actual, target, self.tolerance);
+        }
+        let matches = match (actual, expected) {
+            // Expected outputs are strings; compare text to text.
+            (JsonValue::String(a), JsonValue::String(e)) => a == e,
+            (a, JsonValue::String(e)) => serde_json::to_string(a).is_ok_and(|a| a == *e),
+            (a, e) => a == e,
+        };
+        if matches {
+            EvaluationResult::pass()
+        } else {
+            EvaluationResult::fail_with_details(
+                format!("Value at '{}' does not match", self.path),
+                serde_json::json!({
+                    "expected": expected,
+                    "actual": actual
+                }),
+            )
+        }
+    }
+}
+
+#[async_trait]
+impl Evaluator for JsonPathScorer {
+    fn name(&self) -> &str {
+        "JsonPath"
+    }
+
+    async fn evaluate_str(&self, output: &str, expected: Option<&str>) -> EvaluationResult {
+        let Some(json) = parse_json_output(output) else {
+            return EvaluationResult::fail("Output is not valid JSON");
+        };
+        let Some(actual) = self.select(&json) else {
+            return EvaluationResult::fail(format!("No value at '{}'", self.path));
+        };
+        if self.exists_only {
+            return EvaluationResult::pass();
+        }
+
+        if self.min.is_some() || self.max.is_some() {
+            let Some(number) = actual.as_f64() else {
+                return EvaluationResult::fail(format!(
+                    "Value at '{}' is not a number: {}",
+                    self.path, actual
+                ));
+            };
+            let result = check_range(number, self.min, self.max);
+            if !result.is_pass() || self.equals.is_none() {
+                return result;
+            }
+        }
+
+        match (&self.equals, expected) {
+            (Some(value), _) => self.compare(actual, value),
+            (None, Some(expected)) => {
+                let expected = serde_json::from_str(expected)
+                    .unwrap_or_else(|_| JsonValue::String(expected.to_string()));
+                self.compare(actual, &expected)
+            }
+            (None, None) => EvaluationResult::skip("No expected output provided"),
+        }
+    }
+}
+
+/// Read a number from a JSON number or a numeric string.
+fn json_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        JsonValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse JSON out of model output.
+fn parse_json_output(output: &str) -> Option<JsonValue> {
+    let trimmed = output.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+    // Fenced code block
+    if let Some(start) = trimmed.find("```") {
+        let body = &trimmed[start + 3..];
+        let body = body.split_once('\n').map_or(body, |(_, rest)| rest);
+        if let Some(end) = body.find("```") {
+            if let Ok(value) = serde_json::from_str(body[..end].trim()) {
+                return Some(value);
+            }
+        }
+    }
+    // Object or array surrounded by prose
+    let start = trimmed.find(['{', '['])?;
+    let close = if trimmed[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = trimmed.rfind(close)?;
+    serde_json::from_str(trimmed.get(start..=end)?).ok()
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.trim();
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    let mut first = path.len() == rest.len();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or("unclosed '['")?;
+            let inner = after[..end].trim();
+            if let Some(key) = inner
+                .strip_prefix('\'')
+                .and_then(|k| k.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')))
+            {
+                segments.push(PathSegment::Key(key.to_string()));
+            } else {
+                let index = inner
+                    .parse()
+                    .map_err(|_| format!("invalid index '{}'", inner))?;
+                segments.push(PathSegment::Index(index));
+            }
+            rest = &after[end + 1..];
+        } else {
+            let after = match rest.strip_prefix('.') {
+                Some(after) => after,
+                None if first => rest,
+                None => return Err(format!("unexpected '{}'", rest)),
+            };
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let key = &after[..end];
+            if key.is_empty() {
+                return Err("empty key".to_string());
+            }
+            // Bare numbers index arrays in dotted paths
+            segments.push(match key.parse() {
+                Ok(index) => PathSegment::Index(index),
+                Err(_) => PathSegment::Key(key.to_string()),
+            });
+            rest = &after[end..];
+        }
+        first = false;
+    }
+    Ok(segments)
+}
+
 /// LLM-as-judge placeholder (would need agent integration).
 #[derive(Debug, Clone)]
 pub struct LlmJudgeScorer {
@@ -538,4 +929,76 @@ mod tests {
         assert!(scorer.evaluate_str("hello world", None).await.is_pass());
         assert!(scorer.evaluate_str("hi", None).await.is_fail());
     }
+
+    #[test]
+    fn test_extract_number() {
+        assert_eq!(extract_number("The answer is 42."), Some(42.0));
+        assert_eq!(extract_number("Total: 1,234.5 USD"), Some(1234.5));
+        assert_eq!(extract_number("-3.5e2 degrees"), Some(-350.0));
+        assert_eq!(extract_number("about .5"), Some(0.5));
+        assert_eq!(extract_number("no numbers"), None);
+    }
+
+    #[tokio::test]
+    async fn test_absolute_tolerance() {
+        let scorer = AbsoluteToleranceScorer::new(0.1);
+        assert!(scorer
+            .evaluate_str("Pi is about 3.14", Some("3.14159"))
+            .await
+            .is_pass());
+        assert!(scorer.evaluate_str("3.5", Some("3.14")).await.is_fail());
+        assert!(scorer.evaluate_str("no idea", Some("3.14")).await.is_fail());
+        assert!(scorer.evaluate_str("3.14", None).await.is_skip());
+
+        let scorer = AbsoluteToleranceScorer::new(1.0).target(100.0);
+        assert!(scorer.evaluate_str("99.5%", None).await.is_pass());
+    }
+
+    #[tokio::test]
+    async fn test_numeric_range() {
+        let scorer = NumericRangeScorer::new().between(0.0, 1.0);
+        assert!(scorer.evaluate_str("confidence: 0.8", None).await.is_pass());
+        assert!(scorer.evaluate_str("confidence: 1.2", None).await.is_fail());
+        assert!(scorer.evaluate_str("high", None).await.is_fail());
+    }
+
+    #[test]
+    fn test_parse_json_path() {
+        use PathSegment::{Index, Key};
+        assert_eq!(
+            parse_json_path("$.items[0].price").unwrap(),
+            vec![Key("items".into()), Index(0), Key("price".into())]
+        );
+        assert_eq!(
+            parse_json_path("items.0['unit price']").unwrap(),
+            vec![Key("items".into()), Index(0), Key("unit price".into())]
+        );
+        assert!(parse_json_path("$").unwrap().is_empty());
+        assert!(parse_json_path("$.items[x]").is_err());
+        assert!(JsonPathScorer::new("a..b").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_path_scorer() {
+        let output =
+            "Here you go:\n```json\n{\"total\": 10.02, \"items\": [{\"name\": \"tea\"}]}\n```";
+
+        let scorer = JsonPathScorer::new("$.total").unwrap().tolerance(0.05);
+        assert!(scorer.evaluate_str(output, Some("10")).await.is_pass());
+        assert!(scorer.evaluate_str(output, Some("11")).await.is_fail());
+
+        let scorer = JsonPathScorer::new("$.items[0].name").unwrap();
+        assert!(scorer.evaluate_str(output, Some("tea")).await.is_pass());
+        assert!(scorer.evaluate_str(output, Some("coffee")).await.is_fail());
+
+        let scorer = JsonPathScorer::new("items.0.name").unwrap().equals("tea");
+        assert!(scorer.evaluate_str(output, None).await.is_pass());
+
+        let scorer = JsonPathScorer::new("$.total").unwrap().between(0.0, 10.0);
+        assert!(scorer.evaluate_str(output, None).await.is_fail());
+
+        let scorer = JsonPathScorer::new("$.missing").unwrap().exists();
+        assert!(scorer.evaluate_str(output, None).await.is_fail());
+        assert!(scorer.evaluate_str("not json", None).await.is_fail());
+    }
 }
@@ -0,0 +1,81 @@
+/*
+ * C interface to serdes-ai agents.
+ *
+ * All strings are NUL-terminated UTF-8. Strings returned by the library are
+ * owned by the caller and must be released with serdes_ai_string_free.
+ * Message histories use pydantic-ai's message JSON.
+ */
+
+#ifndef SERDES_AI_H
+#define SERDES_AI_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status code returned by fallible calls. */
+typedef enum SerdesAiStatus {
+    /* The call succeeded. */
+    SERDES_AI_STATUS_OK = 0,
+    /* An argument was null, not UTF-8 or not valid JSON. */
+    SERDES_AI_STATUS_INVALID_ARGUMENT = 1,
+    /* The run failed. */
+    SERDES_AI_STATUS_RUN_FAILED = 2,
+    /* The run was cancelled by the event callback. */
+    SERDES_AI_STATUS_CANCELLED = 3,
+    /* The library panicked. */
+    SERDES_AI_STATUS_PANIC = 4,
+} SerdesAiStatus;
+
+/* Opaque agent handle. */
+typedef struct SerdesAiAgent SerdesAiAgent;
+
+/*
+ * Callback receiving stream events as JSON objects with a "type" field, e.g.
+ * {"type": "text_delta", "text": "Hel"}. The string is only valid for the
+ * duration of the call. Return false to cancel the run.
+ */
+typedef bool (*SerdesAiEventCallback)(const char *event_json, void *user_data);
+
+/* Get the library version. The string is static and must not be freed. */
+const char *serdes_ai_version(void);
+
+/* Free a string returned by the library. Null is ignored. */
+void serdes_ai_string_free(char *s);
+
+/*
+ * Build an agent from a JSON config, e.g.
+ * {"model": "openai:gpt-4o", "system_prompt": "Be concise."}.
+ *
+ * Optional keys: system_prompt, instructions, name, temperature, max_tokens,
+ * api_key, base_url. Returns null on failure and writes the reason to
+ * error_out, if it is not null.
+ */
+SerdesAiAgent *serdes_ai_agent_new(const char *config_json, char **error_out);
+
+/* Free an agent. Null is ignored. No run may be using it. */
+void serdes_ai_agent_free(SerdesAiAgent *agent);
+
+/*
+ * Run an agent, blocking until the run ends.
+ *
+ * message_history_json and callback may be null. Events are delivered on the
+ * calling thread. On success result_json_out receives
+ * {"run_id", "output", "messages"}; otherwise error_out receives the reason.
+ * Both out pointers may be null.
+ */
+SerdesAiStatus serdes_ai_agent_run(const SerdesAiAgent *agent,
+                                   const char *prompt,
+                                   const char *message_history_json,
+                                   SerdesAiEventCallback callback,
+                                   void *user_data,
+                                   char **result_json_out,
+                                   char **error_out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SERDES_AI_H */
@@ -0,0 +1,86 @@
+//! Agent configuration read from JSON.
+
+use serde::Deserialize;
+use serdes_ai::agent::{Agent, AgentBuilder, ModelConfig};
+use serdes_ai::models::ModelError;
+
+/// Configuration of an agent built across the C ABI.
+///
+/// ```json
+/// {
+///   "model": "openai:gpt-4o",
+///   "system_prompt": "Be concise.",
+///   "temperature": 0.2
+/// }
+/// ```
+///
+/// Only `model` is required. API keys are read from the environment unless
+/// `api_key` is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentConfig {
+    /// Model spec, e.g. `"anthropic:claude-3-5-sonnet-20241022"`.
+    pub model: String,
+    /// System prompt.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Instructions.
+    #[serde(default)]
+    pub instructions: Option<String>,
+    /// Agent name.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Sampling temperature.
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Maximum tokens per response.
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// API key, overriding the environment.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Base URL of the provider API.
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl AgentConfig {
+    /// Parse a configuration from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Build the model and wrap the agent settings around it.
+    pub fn builder(&self) -> Result<AgentBuilder<(), String>, ModelError> {
+        let mut config = ModelConfig::new(self.model.as_str());
+        if let Some(key) = &self.api_key {
+            config = config.with_api_key(key.as_str());
+        }
+        if let Some(url) = &self.base_url {
+            config = config.with_base_url(url.as_str());
+        }
+
+        let mut builder = AgentBuilder::from_config(config)?;
+        if let Some(prompt) = &self.system_prompt {
+            builder = builder.system_prompt(prompt.as_str());
+        }
+        if let Some(instructions) = &self.instructions {
+            builder = builder.instructions(instructions.as_str());
+        }
+        if let Some(name) = &self.name {
+            builder = builder.name(name.as_str());
+        }
+        if let Some(temperature) = self.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(tokens) = self.max_tokens {
+            builder = builder.max_tokens(tokens);
+        }
+        Ok(builder)
+    }
+
+    /// Build the agent.
+    pub fn build(&self) -> Result<Agent<(), String>, ModelError> {
+        Ok(self.builder()?.build())
+    }
+}
@@ -0,0 +1,116 @@
+//! Stream events as JSON.
+
+use serde_json::{json, Value};
+use serdes_ai::agent::AgentStreamEvent;
+use serdes_ai::core::messages::to_pydantic_json;
+
+/// Convert a stream event to the JSON object handed to hosts.
+///
+/// Every object has a `type` field in snake case, e.g.
+/// `{"type": "text_delta", "text": "Hel"}`. A completed run carries its
+/// history as pydantic-ai message JSON.
+pub fn event_json(event: &AgentStreamEvent) -> Value {
+    match event {
+        AgentStreamEvent::RunStart { run_id } => json!({"type": "run_start", "run_id": run_id}),
+        AgentStreamEvent::ContextInfo {
+            estimated_tokens,
+            request_bytes,
+            context_limit,
+        } => json!({
+            "type": "context_info",
+            "estimated_tokens": estimated_tokens,
+            "request_bytes": request_bytes,
+            "context_limit": context_limit,
+        }),
+        AgentStreamEvent::ContextCompressed {
+            original_tokens,
+            compressed_tokens,
+            strategy,
+            ..
+        } => json!({
+            "type": "context_compressed",
+            "original_tokens": original_tokens,
+            "compressed_tokens": compressed_tokens,
+            "strategy": strategy,
+        }),
+        AgentStreamEvent::RequestStart { step } => json!({"type": "request_start", "step": step}),
+        AgentStreamEvent::TextDelta { text } => json!({"type": "text_delta", "text": text}),
+        AgentStreamEvent::ThinkingDelta { text } => {
+            json!({"type": "thinking_delta", "text": text})
+        }
+        AgentStreamEvent::ToolCallStart {
+            tool_name,
+            tool_call_id,
+        } => json!({
+            "type": "tool_call_start",
+            "tool_name": tool_name,
+            "tool_call_id": tool_call_id,
+        }),
+        AgentStreamEvent::ToolCallDelta {
+            delta,
+            tool_call_id,
+        } => json!({
+            "type": "tool_call_delta",
+            "delta": delta,
+            "tool_call_id": tool_call_id,
+        }),
+        AgentStreamEvent::ToolCallComplete {
+            tool_name,
+            tool_call_id,
+        } => json!({
+            "type": "tool_call_complete",
+            "tool_name": tool_name,
+            "tool_call_id": tool_call_id,
+        }),
+        AgentStreamEvent::ToolArgsRepaired {
+            tool_name,
+            tool_call_id,
+            passes,
+        } => json!({
+            "type": "tool_args_repaired",
+            "tool_name": tool_name,
+            "tool_call_id": tool_call_id,
+            "passes": passes,
+        }),
+        AgentStreamEvent::ToolExecuted {
+            tool_name,
+            tool_call_id,
+            success,
+            error,
+        } => json!({
+            "type": "tool_executed",
+            "tool_name": tool_name,
+            "tool_call_id": tool_call_id,
+            "success": success,
+            "error": error,
+        }),
+        AgentStreamEvent::FileReceived { file } => {
+            json!({"type": "file_received", "file": file})
+        }
+        AgentStreamEvent::ResponseComplete { step } => {
+            json!({"type": "response_complete", "step": step})
+        }
+        AgentStreamEvent::OutputRejected { message, aborted } => json!({
+            "type": "output_rejected",
+            "message": message,
+            "aborted": aborted,
+        }),
+        AgentStreamEvent::OutputReady => json!({"type": "output_ready"}),
+        AgentStreamEvent::RunComplete { run_id, messages } => json!({
+            "type": "run_complete",
+            "run_id": run_id,
+            "messages": to_pydantic_json(messages),
+        }),
+        AgentStreamEvent::Error { message } => json!({"type": "error", "message": message}),
+        AgentStreamEvent::Cancelled {
+            partial_text,
+            partial_thinking,
+            pending_tools,
+        } => json!({
+            "type": "cancelled",
+            "partial_text": partial_text,
+            "partial_thinking": partial_thinking,
+            "pending_tools": pending_tools,
+        }),
+    }
+}
@@ -0,0 +1,359 @@
+//! # serdes-ai-ffi
+//!
+//! A minimal C ABI for embedding serdes-ai agents in Swift, Kotlin, Go or
+//! any other host that can call C.
+//!
+//! The surface is deliberately small and JSON-based, so it stays stable as
+//! the Rust API evolves:
+//!
+//! - [`serdes_ai_agent_new`] builds an agent from a JSON [`AgentConfig`]
+//! - [`serdes_ai_agent_run`] runs it, streaming JSON events to a callback
+//! - [`serdes_ai_agent_free`] and [`serdes_ai_string_free`] release handles
+//!   and strings returned by the library
+//!
+//! All strings are NUL-terminated UTF-8. Strings returned by the library are
+//! owned by the caller and must be released with [`serdes_ai_string_free`].
+//! Message histories use pydantic-ai's message JSON.
+//!
+//! The C header lives in `include/serdes_ai.h`.
+//!
+//! ```c
+//! char *error = NULL;
+//! SerdesAiAgent *agent = serdes_ai_agent_new("{\"model\": \"openai:gpt-4o\"}", &error);
+//!
+//! char *result = NULL;
+//! SerdesAiStatus status =
+//!     serdes_ai_agent_run(agent, "Hello!", NULL, on_event, NULL, &result, &error);
+//! if (status == SERDES_AI_STATUS_OK) {
+//!     puts(result);
+//!     serdes_ai_string_free(result);
+//! }
+//! serdes_ai_agent_free(agent);
+//! ```
+//!
+//! Runs execute on a runtime owned by the library; the calling thread blocks
+//! until the run ends and receives every event on the same thread.
+
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::OnceLock;
+
+mod config;
+mod event;
+mod run;
+
+pub use config::AgentConfig;
+pub use event::event_json;
+pub use run::{run_streamed, RunFailure, RunOutput};
+
+use serdes_ai::agent::{Agent, CancellationToken};
+use serdes_ai::core::messages::from_pydantic_str;
+
+/// Status code returned by fallible calls.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerdesAiStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// An argument was null, not UTF-8 or not valid JSON.
+    InvalidArgument = 1,
+    /// The run failed.
+    RunFailed = 2,
+    /// The run was cancelled by the event callback.
+    Cancelled = 3,
+    /// The library panicked.
+    Panic = 4,
+}
+
+/// Callback receiving stream events as JSON objects.
+///
+/// The string is only valid for the duration of the call. Return `false` to
+/// cancel the run.
+pub type SerdesAiEventCallback =
+    Option<unsafe extern "C" fn(event_json: *const c_char, user_data: *mut c_void) -> bool>;
+
+/// Opaque agent handle.
+pub struct SerdesAiAgent {
+    agent: Agent<(), String>,
+}
+
+impl SerdesAiAgent {
+    /// Wrap an agent in a handle.
+    pub fn new(agent: Agent<(), String>) -> Self {
+        Self { agent }
+    }
+}
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("serdes-ai-ffi")
+            .build()
+            .expect("Failed to start the serdes-ai runtime")
+    })
+}
+
+/// Copy a string for the caller, dropping interior NULs.
+fn to_c_string(s: String) -> *mut c_char {
+    let bytes: Vec<u8> = s.into_bytes().into_iter().filter(|b| *b != 0).collect();
+    CString::new(bytes).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Write a string to an optional out pointer.
+unsafe fn write_out(out: *mut *mut c_char, s: String) {
+    if !out.is_null() {
+        *out = to_c_string(s);
+    }
+}
+
+/// Read a required string argument.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("'{name}' must not be null"));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("'{name}' is not valid UTF-8"))
+}
+
+/// Get the library version.
+///
+/// The returned string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn serdes_ai_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Free a string returned by the library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn serdes_ai_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Build an agent from a JSON [`AgentConfig`].
+///
+/// Returns null on failure and writes the reason to `error_out`, if it is
+/// not null.
+///
+/// # Safety
+///
+/// `config_json` must be null or a NUL-terminated string. `error_out` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn serdes_ai_agent_new(
+    config_json: *const c_char,
+    error_out: *mut *mut c_char,
+) -> *mut SerdesAiAgent {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let json = str_arg(config_json, "config_json")?;
+        let config = AgentConfig::from_json(json).map_err(|e| format!("Invalid config: {e}"))?;
+        config.build().map_err(|e| e.to_string())
+    }));
+    match result {
+        Ok(Ok(agent)) => Box::into_raw(Box::new(SerdesAiAgent::new(agent))),
+        Ok(Err(message)) => {
+            write_out(error_out, message);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            write_out(error_out, "Panic while building the agent".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free an agent. Null is ignored.
+///
+/// # Safety
+///
+/// `agent` must be null or a handle returned by [`serdes_ai_agent_new`] that
+/// has not been freed yet, and no run may be using it.
+#[no_mangle]
+pub unsafe extern "C" fn serdes_ai_agent_free(agent: *mut SerdesAiAgent) {
+    if !agent.is_null() {
+        drop(Box::from_raw(agent));
+    }
+}
+
+/// Run an agent, blocking until the run ends.
+///
+/// `message_history_json` is optional pydantic-ai message JSON. Every stream
+/// event is passed to `callback`, if set, on the calling thread. On success
+/// `result_json_out` receives `{"run_id", "output", "messages"}`; otherwise
+/// `error_out` receives the reason. Both out pointers may be null.
+///
+/// # Safety
+///
+/// `agent` must be a live handle. `prompt` and `message_history_json` must be
+/// null or NUL-terminated strings. The out pointers must be null or valid for
+/// writes. `callback` must be safe to call with `user_data`.
+#[no_mangle]
+pub unsafe extern "C" fn serdes_ai_agent_run(
+    agent: *const SerdesAiAgent,
+    prompt: *const c_char,
+    message_history_json: *const c_char,
+    callback: SerdesAiEventCallback,
+    user_data: *mut c_void,
+    result_json_out: *mut *mut c_char,
+    error_out: *mut *mut c_char,
+) -> SerdesAiStatus {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let Some(handle) = agent.as_ref() else {
+            return Err((
+                SerdesAiStatus::InvalidArgument,
+                "'agent' must not be null".to_string(),
+            ));
+        };
+        let prompt = str_arg(prompt, "prompt").map_err(|e| (SerdesAiStatus::InvalidArgument, e))?;
+        let history = if message_history_json.is_null() {
+            Vec::new()
+        } else {
+            let json = str_arg(message_history_json, "message_history_json")
+                .map_err(|e| (SerdesAiStatus::InvalidArgument, e))?;
+            from_pydantic_str(json).map_err(|e| {
+                (
+                    SerdesAiStatus::InvalidArgument,
+                    format!("Invalid message history: {e}"),
+                )
+            })?
+        };
+
+        let on_event = |event: &serdes_ai::agent::AgentStreamEvent| match callback {
+            Some(callback) => match CString::new(event_json(event).to_string()) {
+                Ok(json) => callback(json.as_ptr(), user_data),
+                Err(_) => true,
+            },
+            None => true,
+        };
+        let run = run_streamed(
+            &handle.agent,
+            prompt.to_string(),
+            history,
+            CancellationToken::new(),
+            on_event,
+        );
+        runtime().block_on(run).map_err(|failure| match failure {
+            RunFailure::Cancelled => (SerdesAiStatus::Cancelled, failure.to_string()),
+            RunFailure::Failed(_) => (SerdesAiStatus::RunFailed, failure.to_string()),
+        })
+    }));
+
+    match result {
+        Ok(Ok(output)) => {
+            let json = serde_json::to_string(&output).unwrap_or_default();
+            write_out(result_json_out, json);
+            SerdesAiStatus::Ok
+        }
+        Ok(Err((status, message))) => {
+            write_out(error_out, message);
+            status
+        }
+        Err(_) => {
+            write_out(error_out, "Panic during the run".to_string());
+            SerdesAiStatus::Panic
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use serdes_ai::core::messages::{ModelResponsePart, ModelResponseStreamEvent, TextPart};
+    use serdes_ai_models::FunctionModel;
+
+    fn streaming_agent() -> SerdesAiAgent {
+        let model = FunctionModel::with_stream(|_messages, _settings| {
+            Box::pin(stream::iter(vec![
+                Ok(ModelResponseStreamEvent::part_start(
+                    0,
+                    ModelResponsePart::Text(TextPart::new("Hello")),
+                )),
+                Ok(ModelResponseStreamEvent::text_delta(0, ", world")),
+                Ok(ModelResponseStreamEvent::part_end(0)),
+            ]))
+        });
+        SerdesAiAgent::new(serdes_ai::agent::agent(model).build())
+    }
+
+    unsafe extern "C" fn collect(event_json: *const c_char, user_data: *mut c_void) -> bool {
+        let events = &mut *user_data.cast::<Vec<serde_json::Value>>();
+        let json = CStr::from_ptr(event_json).to_str().unwrap();
+        events.push(serde_json::from_str(json).unwrap());
+        true
+    }
+
+    unsafe fn take_string(s: *mut c_char) -> String {
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        serdes_ai_string_free(s);
+        owned
+    }
+
+    #[test]
+    fn test_run_streams_events() {
+        let agent = streaming_agent();
+        let prompt = CString::new("hi").unwrap();
+        let mut events: Vec<serde_json::Value> = Vec::new();
+        let mut result = ptr::null_mut();
+
+        let status = unsafe {
+            serdes_ai_agent_run(
+                &agent,
+                prompt.as_ptr(),
+                ptr::null(),
+                Some(collect),
+                (&mut events as *mut Vec<serde_json::Value>).cast(),
+                &mut result,
+                ptr::null_mut(),
+            )
+        };
+
+        assert_eq!(status, SerdesAiStatus::Ok);
+        let result: serde_json::Value =
+            serde_json::from_str(&unsafe { take_string(result) }).unwrap();
+        assert_eq!(result["output"], "Hello, world");
+        assert_eq!(result["messages"].as_array().unwrap().len(), 2);
+
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(types.first(), Some(&"run_start"));
+        assert_eq!(types.last(), Some(&"run_complete"));
+        assert!(events
+            .iter()
+            .any(|e| e["type"] == "text_delta" && e["text"] == ", world"));
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut error = ptr::null_mut();
+        let config = CString::new(r#"{"modle": "openai:gpt-4o"}"#).unwrap();
+        let agent = unsafe { serdes_ai_agent_new(config.as_ptr(), &mut error) };
+        assert!(agent.is_null());
+        assert!(unsafe { take_string(error) }.starts_with("Invalid config"));
+
+        let agent = streaming_agent();
+        let mut error = ptr::null_mut();
+        let status = unsafe {
+            serdes_ai_agent_run(
+                &agent,
+                ptr::null(),
+                ptr::null(),
+                None,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut error,
+            )
+        };
+        assert_eq!(status, SerdesAiStatus::InvalidArgument);
+        assert_eq!(unsafe { take_string(error) }, "'prompt' must not be null");
+    }
+}
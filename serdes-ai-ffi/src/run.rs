@@ -0,0 +1,93 @@
+//! Driving a streamed run for a host.
+
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use serdes_ai::agent::{
+    Agent, AgentRunError, AgentStream, AgentStreamEvent, CancellationToken, RunOptions,
+};
+use serdes_ai::core::messages::{to_pydantic_json, ModelRequestPart};
+use serdes_ai::core::ModelRequest;
+use std::fmt;
+
+/// The outcome of a completed run.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutput {
+    /// The run ID.
+    pub run_id: String,
+    /// Text of the final model response.
+    pub output: String,
+    /// The full history as pydantic-ai message JSON.
+    pub messages: Value,
+}
+
+/// Why a run did not complete.
+#[derive(Debug)]
+pub enum RunFailure {
+    /// The run was cancelled.
+    Cancelled,
+    /// The run failed.
+    Failed(AgentRunError),
+}
+
+impl fmt::Display for RunFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("Run was cancelled"),
+            Self::Failed(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Stream a run, handing every event to `on_event`.
+///
+/// The run is cancelled when `cancel` is triggered or `on_event` returns
+/// `false`. Events are delivered on the task awaiting this future.
+pub async fn run_streamed<F>(
+    agent: &Agent<(), String>,
+    prompt: String,
+    history: Vec<ModelRequest>,
+    cancel: CancellationToken,
+    mut on_event: F,
+) -> Result<RunOutput, RunFailure>
+where
+    F: FnMut(&AgentStreamEvent) -> bool,
+{
+    let options = RunOptions::new().message_history(history);
+    let mut stream =
+        AgentStream::new_with_cancel(agent, prompt.into(), (), options, cancel.clone())
+            .await
+            .map_err(RunFailure::Failed)?;
+
+    while let Some(event) = stream.next().await {
+        let event = event.map_err(RunFailure::Failed)?;
+        if !on_event(&event) {
+            cancel.cancel();
+        }
+        match event {
+            AgentStreamEvent::RunComplete { run_id, messages } => {
+                return Ok(RunOutput {
+                    run_id,
+                    output: final_text(&messages),
+                    messages: to_pydantic_json(&messages),
+                });
+            }
+            AgentStreamEvent::Cancelled { .. } => return Err(RunFailure::Cancelled),
+            _ => {}
+        }
+    }
+    Err(RunFailure::Failed(AgentRunError::UnexpectedStop))
+}
+
+/// Text of the last model response in a history.
+fn final_text(messages: &[ModelRequest]) -> String {
+    messages
+        .iter()
+        .rev()
+        .flat_map(|request| request.parts.iter().rev())
+        .find_map(|part| match part {
+            ModelRequestPart::ModelResponse(response) => Some(response.text_content()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
@@ -5,6 +5,7 @@ use crate::error::{GraphError, GraphResult};
 use crate::executor::ExecutionOptions;
 use crate::node::{BaseNode, Node, NodeDef, NodeResult};
 use crate::state::{generate_run_id, GraphRunContext, GraphRunResult, GraphState};
+use crate::tools::ToolRegistry;
 use std::collections::HashMap;
 
 /// A graph for multi-agent workflows.
@@ -20,6 +21,7 @@ where
     finish_nodes: Vec<String>,
     max_steps: u32,
     auto_instrument: bool,
+    tools: ToolRegistry,
 }
 
 impl<State, Deps, End> Graph<State, Deps, End>
@@ -38,6 +40,7 @@ where
             finish_nodes: Vec::new(),
             max_steps: 100,
             auto_instrument: true,
+            tools: ToolRegistry::new(),
         }
     }
 
@@ -59,6 +62,24 @@ where
         self
     }
 
+    /// Share a tool with every node of the graph.
+    ///
+    /// Nodes read shared tools from [`GraphRunContext::tools`]; an
+    /// [`AgentNode`](crate::AgentNode) adds the tools matching its agent to
+    /// each run.
+    pub fn tool<T>(self, tool: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.tools.register(tool);
+        self
+    }
+
+    /// Get the tools shared by every node.
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
     /// Add a node to the graph.
     pub fn node<N>(mut self, name: impl Into<String>, node: N) -> Self
     where
@@ -268,7 +289,9 @@ where
     {
         let run_id = options.run_id.take().unwrap_or_else(generate_run_id);
         let max_steps = options.max_steps;
-        let mut ctx = GraphRunContext::new(state, deps, &run_id).with_max_steps(max_steps);
+        let mut ctx = GraphRunContext::new(state, deps, &run_id)
+            .with_max_steps(max_steps)
+            .with_tools(self.tools.clone());
         let mut history = Vec::new();
         let mut steps = 0;
 
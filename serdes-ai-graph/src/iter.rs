@@ -3,6 +3,7 @@
 use crate::error::GraphError;
 use crate::node::{BaseNode, NodeResult};
 use crate::state::{generate_run_id, GraphRunContext, GraphRunResult, GraphState};
+use crate::tools::ToolRegistry;
 use std::marker::PhantomData;
 
 /// Iterator for stepping through a graph.
@@ -43,6 +44,12 @@ where
         }
     }
 
+    /// Set the tools shared with every node.
+    pub fn with_tools(mut self, tools: ToolRegistry) -> Self {
+        self.ctx.tools = tools;
+        self
+    }
+
     /// Execute the next step.
     pub async fn step(&mut self) -> Option<StepResult<State>> {
         if self.finished {
@@ -16,7 +16,7 @@
 //! ## Node Types
 //!
 //! - **[`FunctionNode`]**: Execute an async function
-//! - **[`AgentNode`]**: Run an agent and update state
+//! - **[`AgentNode`]**: Run an agent and map its output into state (`agent` feature)
 //! - **[`RouterNode`]**: Dynamic routing based on state
 //! - **[`ConditionalNode`]**: Branch based on condition
 //!
@@ -24,7 +24,8 @@
 //!
 //! - **[`StatePersistence`]**: Trait for saving/loading state
 //! - **[`InMemoryPersistence`]**: In-memory state storage
-//! - **[`FilePersistence`]**: File-based state storage
+//! - **[`FilePersistence`]**: File-based state storage, optionally encrypted
+//!   at rest with AES-256-GCM (`encryption` feature)
 //!
 //! ## Example
 //!
@@ -65,6 +66,8 @@
 #![deny(unsafe_code)]
 
 pub mod edge;
+#[cfg(feature = "encryption")]
+pub use serdes_ai_core::encryption;
 pub mod error;
 pub mod executor;
 pub mod graph;
@@ -73,9 +76,12 @@ pub mod mermaid;
 pub mod node;
 pub mod persistence;
 pub mod state;
+pub mod tools;
 
 // Re-exports
 pub use edge::{Edge, EdgeBuilder};
+#[cfg(feature = "encryption")]
+pub use encryption::{EncryptionError, EncryptionKey, EnvKeyProvider, FileCipher, KeyProvider};
 pub use error::{GraphError, GraphResult};
 pub use executor::{ExecutionOptions, GraphExecutor, NoPersistence};
 pub use graph::{Graph, SimpleGraph};
@@ -85,10 +91,12 @@ pub use mermaid::{
     generate_flowchart, generate_mermaid, MermaidBuilder, MermaidDirection, MermaidOptions,
 };
 pub use node::{
-    AgentNode, BaseNode, ConditionalNode, End, FunctionNode, Node, NodeDef, NodeResult, RouterNode,
+    AgentNode, BaseNode, ConditionalNode, End, FunctionNode, Node, NodeDef, NodeResult, PromptFn,
+    RouterNode,
 };
 pub use persistence::{FilePersistence, InMemoryPersistence, PersistenceError, StatePersistence};
 pub use state::{generate_run_id, GraphRunContext, GraphRunResult, GraphState, PersistableState};
+pub use tools::ToolRegistry;
 
 /// Prelude for common imports.
 pub mod prelude {
@@ -1,5 +1,7 @@
 //! Graph node types.
 
+#[cfg(feature = "agent")]
+use crate::error::GraphError;
 use crate::error::GraphResult;
 use crate::state::{GraphRunContext, GraphState};
 use async_trait::async_trait;
@@ -133,15 +135,22 @@ where
     }
 }
 
+/// Builds an agent prompt from the graph state.
+pub type PromptFn<State> = Arc<dyn Fn(&State) -> String + Send + Sync>;
+
 /// A node that runs an agent.
+///
+/// With the `agent` feature, an `AgentNode` wrapping a serdes-ai agent is a
+/// [`BaseNode`]: it builds a prompt from the state with
+/// [`with_prompt`](Self::with_prompt), runs the agent with the graph's deps
+/// and shared tools, and folds the output back into the state.
 #[allow(dead_code)]
-pub struct AgentNode<State, Agent, UpdateFn>
-where
-    UpdateFn: Fn(State, &Agent) -> State + Send + Sync,
-{
+pub struct AgentNode<State, Agent, UpdateFn> {
     name: String,
     agent: Arc<Agent>,
     update_state: UpdateFn,
+    prompt: Option<PromptFn<State>>,
+    next: Option<String>,
     _phantom: PhantomData<State>,
 }
 
@@ -155,14 +164,126 @@ where
             name: name.into(),
             agent: Arc::new(agent),
             update_state,
+            prompt: None,
+            next: None,
             _phantom: PhantomData,
         }
     }
+}
 
+impl<State, Agent, UpdateFn> AgentNode<State, Agent, UpdateFn> {
     /// Get a reference to the agent.
     pub fn agent(&self) -> &Agent {
         &self.agent
     }
+
+    /// Set how the agent prompt is built from the state.
+    pub fn with_prompt<F>(mut self, prompt: F) -> Self
+    where
+        F: Fn(&State) -> String + Send + Sync + 'static,
+    {
+        self.prompt = Some(Arc::new(prompt));
+        self
+    }
+
+    /// Continue to the named node after this one.
+    ///
+    /// Without a next node, the graph ends after this node.
+    pub fn then(mut self, next: impl Into<String>) -> Self {
+        self.next = Some(next.into());
+        self
+    }
+}
+
+#[cfg(feature = "agent")]
+impl<State, Deps, Output>
+    AgentNode<State, serdes_ai_agent::Agent<Deps, Output>, fn(State, Output) -> State>
+{
+    /// Create a node from a serdes-ai agent.
+    ///
+    /// The output is discarded until a mapper is set with
+    /// [`with_output_mapper`](Self::with_output_mapper).
+    pub fn from_agent(
+        name: impl Into<String>,
+        agent: serdes_ai_agent::Agent<Deps, Output>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            agent: Arc::new(agent),
+            update_state: |state, _| state,
+            prompt: None,
+            next: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "agent")]
+impl<State, Deps, Output, UpdateFn>
+    AgentNode<State, serdes_ai_agent::Agent<Deps, Output>, UpdateFn>
+{
+    /// Map the agent's output into the graph state.
+    pub fn with_output_mapper<F>(
+        self,
+        mapper: F,
+    ) -> AgentNode<State, serdes_ai_agent::Agent<Deps, Output>, F>
+    where
+        F: Fn(State, Output) -> State + Send + Sync,
+    {
+        AgentNode {
+            name: self.name,
+            agent: self.agent,
+            update_state: mapper,
+            prompt: self.prompt,
+            next: self.next,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "agent")]
+#[async_trait]
+impl<State, Deps, End, Output, F> BaseNode<State, Deps, End>
+    for AgentNode<State, serdes_ai_agent::Agent<Deps, Output>, F>
+where
+    State: GraphState,
+    Deps: Clone + Send + Sync + 'static,
+    End: Default + Send + 'static,
+    Output: Send + Sync + 'static,
+    F: Fn(State, Output) -> State + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run the agent with the graph's shared [`RegisteredTool`]s added.
+    ///
+    /// [`RegisteredTool`]: serdes_ai_agent::RegisteredTool
+    async fn run(
+        &self,
+        ctx: &mut GraphRunContext<State, Deps>,
+    ) -> GraphResult<NodeResult<State, Deps, End>> {
+        let prompt = self.prompt.as_ref().ok_or_else(|| {
+            GraphError::execution_failed(&self.name, "no prompt set, use AgentNode::with_prompt")
+        })?;
+        let tools = ctx.tools.get::<serdes_ai_agent::RegisteredTool<Deps>>();
+        let result = self
+            .agent
+            .run_with_tools(
+                prompt(&ctx.state),
+                ctx.deps.clone(),
+                serdes_ai_agent::RunOptions::new(),
+                tools,
+            )
+            .await
+            .map_err(|e| GraphError::execution_failed(&self.name, e.to_string()))?;
+        ctx.state = (self.update_state)(ctx.state.clone(), result.into_output());
+
+        Ok(match &self.next {
+            Some(next) => NodeResult::next_named(next.clone()),
+            None => NodeResult::end(End::default()),
+        })
+    }
 }
 
 /// A node that routes based on state.
@@ -281,4 +402,110 @@ mod tests {
         assert_eq!(router.route(&TestState { value: 1 }), "positive");
         assert_eq!(router.route(&TestState { value: -1 }), "negative");
     }
+
+    #[cfg(feature = "agent")]
+    mod agent {
+        use super::*;
+        use crate::Graph;
+        use serdes_ai_agent::{RegisteredTool, RunContext, ToolExecutor};
+        use serdes_ai_core::messages::ModelResponsePart;
+        use serdes_ai_core::{FinishReason, ModelRequest, ModelResponse};
+        use serdes_ai_models::FunctionModel;
+        use serdes_ai_tools::{ToolDefinition, ToolError, ToolReturn};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Debug, Clone, Default)]
+        struct Research {
+            topic: String,
+            findings: Option<String>,
+            summary_len: usize,
+        }
+
+        struct Lookup(Arc<AtomicUsize>);
+
+        #[async_trait]
+        impl ToolExecutor<()> for Lookup {
+            async fn execute(
+                &self,
+                _args: serde_json::Value,
+                _ctx: &RunContext<()>,
+            ) -> Result<ToolReturn, ToolError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(ToolReturn::text("rust is fast"))
+            }
+        }
+
+        /// Calls `lookup` once, then answers depending on whether it ran.
+        fn lookup_model() -> FunctionModel {
+            FunctionModel::new(|messages: &[ModelRequest], _| {
+                let looked_up = messages.iter().any(|m| m.tool_returns().next().is_some());
+                if looked_up {
+                    ModelResponse::text("found it").with_finish_reason(FinishReason::Stop)
+                } else {
+                    ModelResponse::with_parts(vec![ModelResponsePart::tool_call(
+                        "lookup",
+                        serde_json::json!({}),
+                    )])
+                    .with_finish_reason(FinishReason::ToolCall)
+                }
+            })
+        }
+
+        #[tokio::test]
+        async fn test_agent_nodes_share_tools_and_map_output() {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let lookup = RegisteredTool {
+                definition: ToolDefinition::new("lookup", "Look up a topic"),
+                executor: Arc::new(Lookup(calls.clone())),
+                max_retries: 0,
+                return_limit: None,
+                failure_policy: Default::default(),
+            };
+
+            let research =
+                AgentNode::from_agent("research", serdes_ai_agent::agent(lookup_model()).build())
+                    .with_prompt(|s: &Research| format!("Research {}", s.topic))
+                    .with_output_mapper(|s: Research, out: String| Research {
+                        findings: Some(out),
+                        ..s
+                    })
+                    .then("summarize");
+            let summarize =
+                AgentNode::from_agent("summarize", serdes_ai_agent::agent(lookup_model()).build())
+                    .with_prompt(|s: &Research| format!("Summarize {:?}", s.findings))
+                    .with_output_mapper(|s: Research, out: String| Research {
+                        summary_len: out.len(),
+                        ..s
+                    });
+
+            let graph: Graph<Research, (), ()> = Graph::new()
+                .tool(lookup)
+                .node("research", research)
+                .node("summarize", summarize)
+                .entry("research")
+                .build()
+                .unwrap();
+            let state = Research {
+                topic: "rust".into(),
+                ..Default::default()
+            };
+            let result = graph.run(state, ()).await.unwrap();
+
+            assert_eq!(result.state.findings.as_deref(), Some("found it"));
+            assert_eq!(result.state.summary_len, "found it".len());
+            assert_eq!(calls.load(Ordering::SeqCst), 2);
+        }
+
+        #[tokio::test]
+        async fn test_agent_node_requires_prompt() {
+            let node =
+                AgentNode::from_agent("research", serdes_ai_agent::agent(lookup_model()).build());
+            let mut ctx = GraphRunContext::new(Research::default(), (), "run");
+            let err = BaseNode::<Research, (), ()>::run(&node, &mut ctx)
+                .await
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("no prompt set"));
+        }
+    }
 }
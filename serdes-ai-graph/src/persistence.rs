@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use parking_lot::RwLock;
 use serde::{de::DeserializeOwned, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Error during persistence operations.
@@ -19,6 +19,11 @@ pub enum PersistenceError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Encryption or decryption failed.
+    #[cfg(feature = "encryption")]
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
     /// State not found.
     #[error("State not found for run: {0}")]
     NotFound(String),
@@ -28,6 +33,13 @@ pub enum PersistenceError {
     Other(String),
 }
 
+#[cfg(feature = "encryption")]
+impl From<crate::encryption::EncryptionError> for PersistenceError {
+    fn from(e: crate::encryption::EncryptionError) -> Self {
+        PersistenceError::Encryption(e.message)
+    }
+}
+
 impl From<PersistenceError> for GraphError {
     fn from(e: PersistenceError) -> Self {
         GraphError::Persistence(e.to_string())
@@ -148,8 +160,15 @@ where
 }
 
 /// File-based state persistence.
+///
+/// With the `encryption` feature, files can be encrypted at rest with
+/// [`with_encryption`](Self::with_encryption).
 pub struct FilePersistence {
     directory: PathBuf,
+    #[cfg(feature = "encryption")]
+    cipher: Option<Arc<crate::encryption::FileCipher>>,
+    #[cfg(feature = "encryption")]
+    plaintext_migration: bool,
 }
 
 impl FilePersistence {
@@ -157,9 +176,70 @@ impl FilePersistence {
     pub fn new(directory: impl Into<PathBuf>) -> Self {
         Self {
             directory: directory.into(),
+            #[cfg(feature = "encryption")]
+            cipher: None,
+            #[cfg(feature = "encryption")]
+            plaintext_migration: false,
         }
     }
 
+    /// Encrypt files with AES-256-GCM using the provider's key.
+    ///
+    /// Once a key is set, plaintext files are rejected, since anyone able to
+    /// write to the directory could use them to inject state. See
+    /// [`with_plaintext_migration`](Self::with_plaintext_migration).
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption(
+        mut self,
+        provider: &dyn crate::encryption::KeyProvider,
+    ) -> Result<Self, PersistenceError> {
+        self.cipher = Some(Arc::new(crate::encryption::FileCipher::new(provider)?));
+        Ok(self)
+    }
+
+    /// Also read plaintext files when encryption is enabled.
+    ///
+    /// Meant for migrating files written before encryption was turned on;
+    /// they are encrypted the next time they are saved. Off by default.
+    #[cfg(feature = "encryption")]
+    pub fn with_plaintext_migration(mut self, allow: bool) -> Self {
+        self.plaintext_migration = allow;
+        self
+    }
+
+    async fn write_file(&self, path: &Path, content: String) -> Result<(), PersistenceError> {
+        #[cfg(feature = "encryption")]
+        if let Some(cipher) = &self.cipher {
+            let sealed = cipher.encrypt(content.as_bytes(), file_name(path).as_bytes())?;
+            tokio::fs::write(path, sealed).await?;
+            return Ok(());
+        }
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    async fn read_file(&self, path: &Path) -> Result<Vec<u8>, PersistenceError> {
+        let data = tokio::fs::read(path).await?;
+        #[cfg(feature = "encryption")]
+        if crate::encryption::FileCipher::is_encrypted(&data) {
+            let cipher = self.cipher.as_ref().ok_or_else(|| {
+                PersistenceError::Encryption(format!(
+                    "{} is encrypted but no key is configured",
+                    path.display()
+                ))
+            })?;
+            return Ok(cipher.decrypt(&data, file_name(path).as_bytes())?);
+        }
+        #[cfg(feature = "encryption")]
+        if self.cipher.is_some() && !self.plaintext_migration {
+            return Err(PersistenceError::Encryption(format!(
+                "{} is not encrypted; enable plaintext migration to read it",
+                path.display()
+            )));
+        }
+        Ok(data)
+    }
+
     /// Ensure the directory exists.
     pub async fn ensure_dir(&self) -> Result<(), PersistenceError> {
         tokio::fs::create_dir_all(&self.directory).await?;
@@ -175,6 +255,13 @@ impl FilePersistence {
     }
 }
 
+#[cfg(feature = "encryption")]
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
 #[async_trait]
 impl<State, End> StatePersistence<State, End> for FilePersistence
 where
@@ -194,8 +281,7 @@ where
             "step": step
         });
         let content = serde_json::to_string_pretty(&data)?;
-        tokio::fs::write(&path, content).await?;
-        Ok(())
+        self.write_file(&path, content).await
     }
 
     async fn load_state(&self, run_id: &str) -> Result<Option<(State, u32)>, PersistenceError> {
@@ -204,8 +290,8 @@ where
             return Ok(None);
         }
 
-        let content = tokio::fs::read_to_string(&path).await?;
-        let value: serde_json::Value = serde_json::from_str(&content)?;
+        let content = self.read_file(&path).await?;
+        let value: serde_json::Value = serde_json::from_slice(&content)?;
         let state: State = serde_json::from_value(value["state"].clone())?;
         let step = value["step"].as_u64().unwrap_or(0) as u32;
         Ok(Some((state, step)))
@@ -215,8 +301,7 @@ where
         self.ensure_dir().await?;
         let path = self.result_path(run_id);
         let content = serde_json::to_string_pretty(result)?;
-        tokio::fs::write(&path, content).await?;
-        Ok(())
+        self.write_file(&path, content).await
     }
 
     async fn load_result(&self, run_id: &str) -> Result<Option<End>, PersistenceError> {
@@ -225,8 +310,8 @@ where
             return Ok(None);
         }
 
-        let content = tokio::fs::read_to_string(&path).await?;
-        let result: End = serde_json::from_str(&content)?;
+        let content = self.read_file(&path).await?;
+        let result: End = serde_json::from_slice(&content)?;
         Ok(Some(result))
     }
 
@@ -348,4 +433,56 @@ mod tests {
         // Cleanup
         let _ = StatePersistence::<TestState, String>::delete(&persistence, "test_run").await;
     }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_encrypted_file_persistence() {
+        use crate::encryption::EncryptionKey;
+
+        let temp_dir = std::env::temp_dir().join("serdes_ai_test_encrypted");
+        let plain = FilePersistence::new(&temp_dir);
+        let key = EncryptionKey::generate().unwrap();
+        let encrypted = FilePersistence::new(&temp_dir)
+            .with_encryption(&key)
+            .unwrap();
+        let state = TestState { value: 42 };
+
+        // Plaintext files are rejected unless migrating
+        StatePersistence::<TestState, String>::save_state(&plain, "enc_run", &state, 1)
+            .await
+            .unwrap();
+        let err = StatePersistence::<TestState, String>::load_state(&encrypted, "enc_run")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PersistenceError::Encryption(_)));
+        let migrating = FilePersistence::new(&temp_dir)
+            .with_encryption(&key)
+            .unwrap()
+            .with_plaintext_migration(true);
+        let loaded = StatePersistence::<TestState, String>::load_state(&migrating, "enc_run")
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some((state.clone(), 1)));
+
+        StatePersistence::<TestState, String>::save_state(&encrypted, "enc_run", &state, 2)
+            .await
+            .unwrap();
+        let raw = tokio::fs::read(temp_dir.join("enc_run_state.json"))
+            .await
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&raw).contains("value"));
+
+        let loaded = StatePersistence::<TestState, String>::load_state(&encrypted, "enc_run")
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some((state, 2)));
+
+        // Without the key, encrypted files can't be read
+        let err = StatePersistence::<TestState, String>::load_state(&plain, "enc_run")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PersistenceError::Encryption(_)));
+
+        let _ = StatePersistence::<TestState, String>::delete(&plain, "enc_run").await;
+    }
 }
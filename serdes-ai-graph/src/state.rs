@@ -1,5 +1,6 @@
 //! Graph state types.
 
+use crate::tools::ToolRegistry;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 
@@ -25,6 +26,8 @@ pub struct GraphRunContext<State, Deps = ()> {
     pub run_id: String,
     /// Maximum steps allowed.
     pub max_steps: u32,
+    /// Tools shared by every node of the graph.
+    pub tools: ToolRegistry,
 }
 
 impl<State, Deps> GraphRunContext<State, Deps> {
@@ -36,6 +39,7 @@ impl<State, Deps> GraphRunContext<State, Deps> {
             step: 0,
             run_id: run_id.into(),
             max_steps: 100,
+            tools: ToolRegistry::new(),
         }
     }
 
@@ -45,6 +49,12 @@ impl<State, Deps> GraphRunContext<State, Deps> {
         self
     }
 
+    /// Set the shared tools.
+    pub fn with_tools(mut self, tools: ToolRegistry) -> Self {
+        self.tools = tools;
+        self
+    }
+
     /// Increment step counter.
     pub fn increment_step(&mut self) {
         self.step += 1;
@@ -64,6 +74,7 @@ impl<State: Default, Deps: Default> Default for GraphRunContext<State, Deps> {
             step: 0,
             run_id: generate_run_id(),
             max_steps: 100,
+            tools: ToolRegistry::new(),
         }
     }
 }
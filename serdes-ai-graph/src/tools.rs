@@ -0,0 +1,89 @@
+//! Tools shared across the nodes of a graph.
+//!
+//! A [`ToolRegistry`] holds tools registered once on a [`Graph`](crate::Graph)
+//! and hands them to every node through the [`GraphRunContext`](crate::GraphRunContext).
+//! Tools are stored by type, so agents with different dependency types can
+//! share one registry: each node only sees the tools of the type it asks for.
+
+use parking_lot::RwLock;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Registry of tools shared by every node of a graph.
+///
+/// Cloning is cheap; clones share the same tools.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Arc<RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl ToolRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tool.
+    pub fn register<T>(&self, tool: T)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.tools
+            .write()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<T>::new()))
+            .downcast_mut::<Vec<T>>()
+            .expect("tools are stored under their own type id")
+            .push(tool);
+    }
+
+    /// Get all registered tools of type `T`.
+    pub fn get<T>(&self) -> Vec<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.tools
+            .read()
+            .get(&TypeId::of::<T>())
+            .and_then(|tools| tools.downcast_ref::<Vec<T>>())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Check if no tools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tools.read().is_empty()
+    }
+}
+
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolRegistry")
+            .field("types", &self.tools.read().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Search(&'static str);
+
+    #[test]
+    fn test_registry_by_type() {
+        let registry = ToolRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.register(Search("web"));
+        registry.clone().register(Search("docs"));
+        registry.register(42u32);
+
+        assert_eq!(registry.get::<Search>(), [Search("web"), Search("docs")]);
+        assert_eq!(registry.get::<u32>(), [42]);
+        assert!(registry.get::<String>().is_empty());
+    }
+}
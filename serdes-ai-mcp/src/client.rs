@@ -3,18 +3,74 @@
 //! This module provides the `McpClient` for connecting to MCP servers.
 
 use crate::error::{McpError, McpResult};
-use crate::transport::{McpTransport, StdioTransport};
+use crate::transport::{McpTransport, NotificationHandler, StdioTransport};
 use crate::types::{
-    CallToolParams, CallToolResult, Implementation, InitializeParams, InitializeResult,
-    JsonRpcNotification, JsonRpcRequest, ListPromptsResult, ListResourcesResult, ListToolsResult,
-    McpTool, ReadResourceParams, ReadResourceResult, RequestId, ServerCapabilities,
+    CallToolParams, CallToolResult, CompleteParams, CompleteResult, Completion, CompletionArgument,
+    CompletionReference, Implementation, InitializeParams, InitializeResult, JsonRpcNotification,
+    JsonRpcRequest, JsonRpcResponse, ListPromptsResult, ListResourcesResult, ListToolsResult,
+    LoggingLevel, LoggingMessageParams, McpTool, ReadResourceParams, ReadResourceResult, RequestId,
+    ServerCapabilities, SetLevelParams, LOGGING_MESSAGE,
 };
+use futures::future::BoxFuture;
+use parking_lot::RwLock;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::sync::atomic::{AtomicI64, Ordering};
+use serdes_ai_retries::ExponentialBackoff;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// Callback for log messages sent by the server.
+pub type LogHandler = Arc<dyn Fn(&LoggingMessageParams) + Send + Sync>;
+
+/// Creates a new transport each time a reconnecting client connects.
+pub type TransportFactory =
+    Arc<dyn Fn() -> BoxFuture<'static, McpResult<Arc<dyn McpTransport>>> + Send + Sync>;
+
+/// Callback for connection events of a reconnecting client.
+pub type ConnectionEventHandler = Arc<dyn Fn(&ConnectionEvent) + Send + Sync>;
+
+/// Connection lifecycle event of a reconnecting client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// The connection to the server was lost.
+    Disconnected,
+    /// A reconnect attempt starts after `delay`.
+    Reconnecting {
+        /// Attempt number, starting at 1.
+        attempt: u32,
+        /// Backoff delay before the attempt.
+        delay: Duration,
+    },
+    /// Connected again, and re-initialized if the client was initialized.
+    Reconnected {
+        /// Attempt that succeeded.
+        attempt: u32,
+    },
+    /// A reconnect attempt failed.
+    ReconnectFailed {
+        /// Attempt that failed.
+        attempt: u32,
+        /// Error message.
+        error: String,
+    },
+    /// All reconnect attempts failed.
+    GaveUp {
+        /// Number of attempts made.
+        attempts: u32,
+    },
+}
+
+/// Reconnect configuration of a supervised client.
+struct Reconnect {
+    factory: TransportFactory,
+    backoff: ExponentialBackoff,
+    /// Serializes reconnects so concurrent callers share one restart.
+    lock: Mutex<()>,
+}
+
 /// MCP client for connecting to servers.
 ///
 /// # Example
@@ -28,32 +84,132 @@ use tokio::sync::Mutex;
 /// let tools = client.list_tools().await?;
 /// println!("Available tools: {:?}", tools);
 /// ```
+///
+/// # Server logs
+///
+/// Log messages the server sends with `notifications/message` are forwarded to
+/// [`tracing`] under the `serdes_ai_mcp::server_log` target by default. Use
+/// [`with_log_handler`](Self::with_log_handler) to handle them yourself and
+/// [`set_log_level`](Self::set_log_level) to choose how much the server sends.
+///
+/// # Reconnecting
+///
+/// A client created with [`stdio_with_reconnect`](Self::stdio_with_reconnect)
+/// or [`with_reconnect`](Self::with_reconnect) restarts the connection when it
+/// is lost, e.g. because the server process crashed. Restarts use exponential
+/// backoff, replay the initialize handshake, and retry the interrupted request
+/// once. Note that a retried `tools/call` may run twice if the server crashed
+/// after executing it. Subscribe to [`ConnectionEvent`]s with
+/// [`with_connection_handler`](Self::with_connection_handler).
 pub struct McpClient {
-    transport: Arc<dyn McpTransport>,
+    transport: RwLock<Arc<dyn McpTransport>>,
+    reconnect: Option<Reconnect>,
     request_id: AtomicI64,
     server_capabilities: Mutex<Option<ServerCapabilities>>,
     server_info: Mutex<Option<Implementation>>,
     initialized: Mutex<bool>,
+    log_handler: Arc<RwLock<LogHandler>>,
+    connection_handler: Option<ConnectionEventHandler>,
+    closed: AtomicBool,
 }
 
 impl McpClient {
     /// Create a new client with a transport.
     pub fn new(transport: impl McpTransport + 'static) -> Self {
+        Self::from_transport(Arc::new(transport), None)
+    }
+
+    /// Create a client that reconnects through `factory` when the connection is lost.
+    ///
+    /// `backoff.max_retries` limits the attempts per reconnect.
+    pub async fn with_reconnect<F, Fut, T>(
+        factory: F,
+        backoff: ExponentialBackoff,
+    ) -> McpResult<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = McpResult<T>> + Send + 'static,
+        T: McpTransport + 'static,
+    {
+        let factory: TransportFactory = Arc::new(move || {
+            let connect = factory();
+            Box::pin(async move { Ok(Arc::new(connect.await?) as Arc<dyn McpTransport>) })
+        });
+        let transport = factory().await?;
+        Ok(Self::from_transport(
+            transport,
+            Some(Reconnect {
+                factory,
+                backoff,
+                lock: Mutex::new(()),
+            }),
+        ))
+    }
+
+    fn from_transport(transport: Arc<dyn McpTransport>, reconnect: Option<Reconnect>) -> Self {
+        let log_handler: Arc<RwLock<LogHandler>> = Arc::new(RwLock::new(Arc::new(trace_log)));
+        transport.set_notification_handler(log_dispatcher(log_handler.clone()));
+
         Self {
-            transport: Arc::new(transport),
+            transport: RwLock::new(transport),
+            reconnect,
             request_id: AtomicI64::new(1),
             server_capabilities: Mutex::new(None),
             server_info: Mutex::new(None),
             initialized: Mutex::new(false),
+            log_handler,
+            connection_handler: None,
+            closed: AtomicBool::new(false),
         }
     }
 
+    /// Receive connection events of a reconnecting client.
+    pub fn with_connection_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.connection_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Handle server log messages with a callback instead of `tracing`.
+    pub fn with_log_handler<F>(self, handler: F) -> Self
+    where
+        F: Fn(&LoggingMessageParams) + Send + Sync + 'static,
+    {
+        *self.log_handler.write() = Arc::new(handler);
+        self
+    }
+
     /// Create a client that connects via stdio.
     pub async fn stdio(command: &str, args: &[&str]) -> McpResult<Self> {
         let transport = StdioTransport::spawn(command, args).await?;
         Ok(Self::new(transport))
     }
 
+    /// Create a client that connects via stdio and restarts the server process
+    /// if it exits.
+    pub async fn stdio_with_reconnect(
+        command: &str,
+        args: &[&str],
+        backoff: ExponentialBackoff,
+    ) -> McpResult<Self> {
+        let command = command.to_string();
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        Self::with_reconnect(
+            move || {
+                let command = command.clone();
+                let args = args.clone();
+                async move {
+                    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+                    StdioTransport::spawn(&command, &args).await
+                }
+            },
+            backoff,
+        )
+        .await
+    }
+
     /// Create a client with HTTP transport.
     #[cfg(feature = "reqwest")]
     pub fn http(url: &str) -> Self {
@@ -65,23 +221,38 @@ impl McpClient {
     ///
     /// This must be called before using any other methods.
     pub async fn initialize(&self) -> McpResult<InitializeResult> {
+        let result = self.handshake(&self.transport()).await?;
+        *self.initialized.lock().await = true;
+        Ok(result)
+    }
+
+    /// Run the initialize handshake on a transport.
+    async fn handshake(&self, transport: &Arc<dyn McpTransport>) -> McpResult<InitializeResult> {
         let params =
             InitializeParams::new(Implementation::new("serdes-ai", env!("CARGO_PKG_VERSION")));
-
-        let result: InitializeResult = self.call("initialize", params).await?;
+        let request = JsonRpcRequest::new(self.next_id(), "initialize").with_params(params)?;
+        let result: InitializeResult = parse_response(transport.request(&request).await?)?;
 
         // Store server info
         *self.server_capabilities.lock().await = Some(result.capabilities.clone());
         *self.server_info.lock().await = Some(result.server_info.clone());
 
         // Send initialized notification with empty object params (some servers require this)
-        self.notify("notifications/initialized", serde_json::json!({}))
-            .await?;
-        *self.initialized.lock().await = true;
+        let notification = JsonRpcNotification::new("notifications/initialized")
+            .with_params(serde_json::json!({}))?;
+        transport.notify(&notification).await?;
 
         Ok(result)
     }
 
+    /// Check that the server is responsive.
+    ///
+    /// On a reconnecting client, a lost connection is restarted first.
+    pub async fn ping(&self) -> McpResult<()> {
+        let _: serde_json::Value = self.call("ping", serde_json::json!({})).await?;
+        Ok(())
+    }
+
     /// Check if the client is initialized.
     pub async fn is_initialized(&self) -> bool {
         *self.initialized.lock().await
@@ -122,6 +293,46 @@ impl McpClient {
         self.call("tools/call", params).await
     }
 
+    // ========================================================================
+    // Completion
+    // ========================================================================
+
+    /// Get completion suggestions for an argument of a prompt or resource template.
+    ///
+    /// `value` is what the user has typed so far.
+    pub async fn complete(
+        &self,
+        reference: CompletionReference,
+        argument: &str,
+        value: &str,
+    ) -> McpResult<Completion> {
+        self.ensure_initialized().await?;
+
+        let params = CompleteParams {
+            reference,
+            argument: CompletionArgument {
+                name: argument.to_string(),
+                value: value.to_string(),
+            },
+        };
+        let result: CompleteResult = self.call("completion/complete", params).await?;
+        Ok(result.completion)
+    }
+
+    // ========================================================================
+    // Logging
+    // ========================================================================
+
+    /// Set the minimum level of log messages the server should send.
+    pub async fn set_log_level(&self, level: LoggingLevel) -> McpResult<()> {
+        self.ensure_initialized().await?;
+
+        let _: serde_json::Value = self
+            .call("logging/setLevel", SetLevelParams { level })
+            .await?;
+        Ok(())
+    }
+
     // ========================================================================
     // Resources
     // ========================================================================
@@ -157,14 +368,17 @@ impl McpClient {
     // ========================================================================
 
     /// Close the connection.
+    ///
+    /// A closed client does not reconnect.
     pub async fn close(&self) -> McpResult<()> {
+        self.closed.store(true, Ordering::SeqCst);
         *self.initialized.lock().await = false;
-        self.transport.close().await
+        self.transport().close().await
     }
 
     /// Check if connected.
     pub fn is_connected(&self) -> bool {
-        self.transport.is_connected()
+        self.transport().is_connected()
     }
 
     // ========================================================================
@@ -182,29 +396,154 @@ impl McpClient {
         RequestId::Number(self.request_id.fetch_add(1, Ordering::SeqCst))
     }
 
+    fn transport(&self) -> Arc<dyn McpTransport> {
+        self.transport.read().clone()
+    }
+
     async fn call<P: Serialize, R: DeserializeOwned>(
         &self,
         method: &str,
         params: P,
     ) -> McpResult<R> {
         let request = JsonRpcRequest::new(self.next_id(), method).with_params(params)?;
+        parse_response(self.send(&request).await?)
+    }
+
+    /// Send a request, reconnecting and retrying once if the connection is lost.
+    async fn send(&self, request: &JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        let transport = self.transport();
+        if self.reconnect.is_none() || self.closed.load(Ordering::SeqCst) {
+            return transport.request(request).await;
+        }
 
-        let response = self.transport.request(&request).await?;
+        if transport.is_connected() {
+            match transport.request(request).await {
+                Err(McpError::ConnectionClosed | McpError::Io(_)) => {}
+                result => return result,
+            }
+        }
+        self.reconnect(&transport).await?;
+        self.transport().request(request).await
+    }
+
+    /// Replace a lost transport, with exponential backoff between attempts.
+    async fn reconnect(&self, lost: &Arc<dyn McpTransport>) -> McpResult<()> {
+        let Some(reconnect) = &self.reconnect else {
+            return Err(McpError::ConnectionClosed);
+        };
+        let _guard = reconnect.lock.lock().await;
 
-        if let Some(error) = response.error {
-            return Err(McpError::Protocol {
-                code: error.code,
-                message: error.message,
-            });
+        // Another caller already reconnected while we waited
+        if !Arc::ptr_eq(&self.transport(), lost) {
+            return Ok(());
         }
 
-        let result = response.result.ok_or(McpError::NoResult)?;
-        serde_json::from_value(result).map_err(McpError::from)
+        self.emit(ConnectionEvent::Disconnected);
+        let _ = lost.close().await;
+
+        let attempts = reconnect.backoff.max_retries;
+        let mut last_error = McpError::ConnectionClosed;
+        for attempt in 1..=attempts {
+            let delay = reconnect.backoff.calculate_delay(attempt - 1);
+            self.emit(ConnectionEvent::Reconnecting { attempt, delay });
+            tokio::time::sleep(delay).await;
+
+            match self.connect(&reconnect.factory).await {
+                Ok(transport) => {
+                    *self.transport.write() = transport;
+                    self.emit(ConnectionEvent::Reconnected { attempt });
+                    return Ok(());
+                }
+                Err(e) => {
+                    self.emit(ConnectionEvent::ReconnectFailed {
+                        attempt,
+                        error: e.to_string(),
+                    });
+                    last_error = e;
+                }
+            }
+        }
+
+        self.emit(ConnectionEvent::GaveUp { attempts });
+        Err(last_error)
     }
 
-    async fn notify<P: Serialize>(&self, method: &str, params: P) -> McpResult<()> {
-        let notification = JsonRpcNotification::new(method).with_params(params)?;
-        self.transport.notify(&notification).await
+    /// Open a new transport and replay the handshake if the client was initialized.
+    async fn connect(&self, factory: &TransportFactory) -> McpResult<Arc<dyn McpTransport>> {
+        let transport = factory().await?;
+        transport.set_notification_handler(log_dispatcher(self.log_handler.clone()));
+
+        if *self.initialized.lock().await {
+            if let Err(e) = self.handshake(&transport).await {
+                let _ = transport.close().await;
+                return Err(e);
+            }
+        }
+        Ok(transport)
+    }
+
+    fn emit(&self, event: ConnectionEvent) {
+        tracing::debug!(?event, "MCP connection event");
+        if let Some(handler) = &self.connection_handler {
+            handler(&event);
+        }
+    }
+}
+
+/// Extract the typed result of a response.
+fn parse_response<R: DeserializeOwned>(response: JsonRpcResponse) -> McpResult<R> {
+    if let Some(error) = response.error {
+        return Err(McpError::Protocol {
+            code: error.code,
+            message: error.message,
+        });
+    }
+
+    let result = response.result.ok_or(McpError::NoResult)?;
+    serde_json::from_value(result).map_err(McpError::from)
+}
+
+/// Notification handler that passes server log messages to a [`LogHandler`].
+fn log_dispatcher(log_handler: Arc<RwLock<LogHandler>>) -> NotificationHandler {
+    Arc::new(move |notification| {
+        if notification.method != LOGGING_MESSAGE {
+            return;
+        }
+        let Some(params) = notification
+            .params
+            .and_then(|p| serde_json::from_value::<LoggingMessageParams>(p).ok())
+        else {
+            return;
+        };
+        let handler = log_handler.read().clone();
+        handler(&params);
+    })
+}
+
+/// Forward a server log message to `tracing` at the matching level.
+fn trace_log(params: &LoggingMessageParams) {
+    let logger = params.logger.as_deref().unwrap_or("");
+    let message = match &params.data {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    match params.level {
+        LoggingLevel::Debug => {
+            tracing::debug!(target: "serdes_ai_mcp::server_log", logger, "{}", message)
+        }
+        LoggingLevel::Info | LoggingLevel::Notice => {
+            tracing::info!(target: "serdes_ai_mcp::server_log", logger, "{}", message)
+        }
+        LoggingLevel::Warning => {
+            tracing::warn!(target: "serdes_ai_mcp::server_log", logger, "{}", message)
+        }
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => {
+            tracing::error!(target: "serdes_ai_mcp::server_log", logger, "{}", message)
+        }
     }
 }
 
@@ -214,6 +553,7 @@ pub struct McpClientBuilder {
     args: Vec<String>,
     #[allow(dead_code)]
     url: Option<String>,
+    reconnect: Option<ExponentialBackoff>,
 }
 
 impl Default for McpClientBuilder {
@@ -229,6 +569,7 @@ impl McpClientBuilder {
             command: None,
             args: Vec::new(),
             url: None,
+            reconnect: None,
         }
     }
 
@@ -254,6 +595,12 @@ impl McpClientBuilder {
         self
     }
 
+    /// Restart the stdio server process with this backoff if it exits.
+    pub fn reconnect(mut self, backoff: ExponentialBackoff) -> Self {
+        self.reconnect = Some(backoff);
+        self
+    }
+
     /// Set the URL for HTTP transport.
     #[cfg(feature = "reqwest")]
     pub fn url(mut self, url: impl Into<String>) -> Self {
@@ -270,7 +617,10 @@ impl McpClientBuilder {
 
         if let Some(command) = self.command {
             let args: Vec<&str> = self.args.iter().map(|s| s.as_str()).collect();
-            return McpClient::stdio(&command, &args).await;
+            return match self.reconnect {
+                Some(backoff) => McpClient::stdio_with_reconnect(&command, &args, backoff).await,
+                None => McpClient::stdio(&command, &args).await,
+            };
         }
 
         Err(McpError::Other("No transport configured".to_string()))
@@ -280,8 +630,8 @@ impl McpClientBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::transport::MemoryTransport;
-    use crate::types::JsonRpcResponse;
+    use crate::transport::{MemoryTransport, TransportFault};
+    use crate::types::ListToolsResult;
 
     #[tokio::test]
     async fn test_client_not_initialized() {
@@ -341,4 +691,222 @@ mod tests {
             panic!("Expected numeric IDs");
         }
     }
+
+    #[tokio::test]
+    async fn test_client_log_handler() {
+        let transport = MemoryTransport::new();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let _client = McpClient::new(transport.clone()).with_log_handler(move |params| {
+            sink.lock()
+                .unwrap()
+                .push((params.level, params.data.clone()));
+        });
+
+        let notification = JsonRpcNotification::new(LOGGING_MESSAGE)
+            .with_params(serde_json::json!({"level": "warning", "data": "disk almost full"}))
+            .unwrap();
+        transport.receive_notification(notification);
+        transport.receive_notification(JsonRpcNotification::new("notifications/other"));
+
+        let received = received.lock().unwrap();
+        assert_eq!(
+            *received,
+            vec![(LoggingLevel::Warning, serde_json::json!("disk almost full"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_set_log_level() {
+        let transport = MemoryTransport::new();
+        let init_result = InitializeResult {
+            protocol_version: "2024-11-05".to_string(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("test-server", "1.0.0"),
+            instructions: None,
+        };
+        transport
+            .push_response(JsonRpcResponse::success(1, init_result))
+            .await;
+        transport
+            .push_response(JsonRpcResponse::success(2, serde_json::json!({})))
+            .await;
+
+        let client = McpClient::new(transport.clone());
+        client.initialize().await.unwrap();
+        client.set_log_level(LoggingLevel::Error).await.unwrap();
+
+        let requests = transport.get_requests().await;
+        assert_eq!(requests[1].method, "logging/setLevel");
+        assert_eq!(
+            requests[1].params,
+            Some(serde_json::json!({"level": "error"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_complete() {
+        let transport = MemoryTransport::new();
+        let init_result = InitializeResult {
+            protocol_version: "2024-11-05".to_string(),
+            capabilities: ServerCapabilities::default(),
+            server_info: Implementation::new("test-server", "1.0.0"),
+            instructions: None,
+        };
+        transport
+            .push_response(JsonRpcResponse::success(1, init_result))
+            .await;
+        transport
+            .push_response(JsonRpcResponse::success(
+                2,
+                serde_json::json!({"completion": {"values": ["python"], "total": 1}}),
+            ))
+            .await;
+
+        let client = McpClient::new(transport.clone());
+        client.initialize().await.unwrap();
+        let completion = client
+            .complete(CompletionReference::prompt("code_review"), "language", "py")
+            .await
+            .unwrap();
+        assert_eq!(completion.values, vec!["python"]);
+        assert_eq!(completion.total, Some(1));
+
+        let requests = transport.get_requests().await;
+        assert_eq!(
+            requests[1].params,
+            Some(serde_json::json!({
+                "ref": {"type": "ref/prompt", "name": "code_review"},
+                "argument": {"name": "language", "value": "py"}
+            }))
+        );
+    }
+
+    fn test_server() -> MemoryTransport {
+        MemoryTransport::with_handler(|req| match req.method.as_str() {
+            "initialize" => JsonRpcResponse::success(
+                req.id.clone(),
+                InitializeResult {
+                    protocol_version: "2024-11-05".to_string(),
+                    capabilities: ServerCapabilities::default(),
+                    server_info: Implementation::new("test-server", "1.0.0"),
+                    instructions: None,
+                },
+            ),
+            "tools/list" => JsonRpcResponse::success(
+                req.id.clone(),
+                ListToolsResult {
+                    tools: vec![McpTool::new("echo", serde_json::json!({"type": "object"}))],
+                    next_cursor: None,
+                },
+            ),
+            _ => JsonRpcResponse::success(req.id.clone(), serde_json::json!({})),
+        })
+    }
+
+    fn test_backoff(max_retries: u32) -> ExponentialBackoff {
+        ExponentialBackoff::builder()
+            .max_retries(max_retries)
+            .initial_delay(Duration::from_millis(1))
+            .jitter(0.0)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_client_reconnects_and_replays_initialize() {
+        let server = test_server();
+        let sessions = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let client = {
+            let sessions = sessions.clone();
+            let events = events.clone();
+            McpClient::with_reconnect(
+                move || {
+                    let session = server.session();
+                    sessions.lock().unwrap().push(session.clone());
+                    async move { Ok(session) }
+                },
+                test_backoff(3),
+            )
+            .await
+            .unwrap()
+            .with_connection_handler(move |event| events.lock().unwrap().push(event.clone()))
+        };
+        client.initialize().await.unwrap();
+
+        // The server goes away in the middle of the next call
+        let first = sessions.lock().unwrap()[0].clone();
+        first.inject_fault(TransportFault::Disconnect);
+        let tools = client.list_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+
+        let second = sessions.lock().unwrap()[1].clone();
+        let methods: Vec<String> = second
+            .get_requests()
+            .await
+            .into_iter()
+            .map(|r| r.method)
+            .collect();
+        assert_eq!(methods, vec!["initialize", "tools/list"]);
+        assert_eq!(
+            second.get_notifications().await[0].method,
+            "notifications/initialized"
+        );
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ConnectionEvent::Disconnected,
+                ConnectionEvent::Reconnecting {
+                    attempt: 1,
+                    delay: Duration::from_millis(1)
+                },
+                ConnectionEvent::Reconnected { attempt: 1 },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_reconnect_gives_up() {
+        let server = test_server();
+        let first = server.session();
+        let connects = Arc::new(AtomicI64::new(0));
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let client = {
+            let first = first.clone();
+            let events = events.clone();
+            McpClient::with_reconnect(
+                move || {
+                    let attempt = connects.fetch_add(1, Ordering::SeqCst);
+                    let first = first.clone();
+                    async move {
+                        if attempt == 0 {
+                            Ok(first)
+                        } else {
+                            Err(McpError::Transport("spawn failed".to_string()))
+                        }
+                    }
+                },
+                test_backoff(2),
+            )
+            .await
+            .unwrap()
+            .with_connection_handler(move |event| events.lock().unwrap().push(event.clone()))
+        };
+
+        first.close().await.unwrap();
+        let result = client.ping().await;
+        assert!(matches!(result, Err(McpError::Transport(_))));
+        assert_eq!(
+            events.lock().unwrap().last(),
+            Some(&ConnectionEvent::GaveUp { attempts: 2 })
+        );
+
+        // An explicitly closed client doesn't reconnect
+        client.close().await.unwrap();
+        let result = client.ping().await;
+        assert!(matches!(result, Err(McpError::ConnectionClosed)));
+        assert_eq!(events.lock().unwrap().len(), 6);
+    }
 }
@@ -91,20 +91,30 @@ pub mod server;
 pub use error::{McpError, McpResult};
 pub use resources::{parse_resource_uri, read_file_resource, ResourceManager, ResourceUri};
 pub use toolset::{load_mcp_servers, McpServerConfig, McpToolset, McpTransportConfig};
-pub use transport::{McpTransport, MemoryTransport, StdioTransport};
+pub use transport::{
+    McpTransport, MemoryTransport, NotificationHandler, RequestHandler, StdioTransport,
+    TransportFault,
+};
 pub use types::{
-    CallToolParams, CallToolResult, ClientCapabilities, Implementation, InitializeParams,
-    InitializeResult, JsonRpcError, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse,
-    ListPromptsResult, ListResourcesResult, ListToolsResult, McpTool, Prompt, PromptArgument,
-    ReadResourceParams, ReadResourceResult, RequestId, ResourceContent, ResourceTemplate,
-    ServerCapabilities, ToolResultContent,
+    CallToolParams, CallToolResult, ClientCapabilities, CompleteParams, CompleteResult, Completion,
+    CompletionArgument, CompletionReference, Implementation, InitializeParams, InitializeResult,
+    JsonRpcError, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
+    ListResourcesResult, ListToolsResult, LoggingLevel, LoggingMessageParams, McpTool, Prompt,
+    PromptArgument, ReadResourceParams, ReadResourceResult, RequestId, ResourceContent,
+    ResourceTemplate, ServerCapabilities, SetLevelParams, ToolResultContent, LOGGING_MESSAGE,
 };
 
 #[cfg(feature = "client")]
-pub use client::{McpClient, McpClientBuilder};
+pub use client::{
+    ConnectionEvent, ConnectionEventHandler, LogHandler, McpClient, McpClientBuilder,
+    TransportFactory,
+};
 
 #[cfg(feature = "server")]
-pub use server::{AsyncFnToolHandler, FnToolHandler, McpServer, ToolHandler};
+pub use server::{
+    AsyncFnToolHandler, CompletionFn, FnToolHandler, McpLogger, McpServer, ToolHandler,
+    ToolRegistry, TOOLS_LIST_CHANGED,
+};
 
 /// Prelude for common imports.
 pub mod prelude {
@@ -4,15 +4,19 @@
 
 use crate::error::{McpError, McpResult};
 use crate::types::{
-    CallToolParams, CallToolResult, Implementation, InitializeResult, JsonRpcMessage,
-    JsonRpcResponse, ListToolsResult, McpTool, ServerCapabilities, ToolsCapability,
+    CallToolParams, CallToolResult, CompleteParams, CompleteResult, Completion,
+    CompletionReference, CompletionsCapability, Implementation, InitializeResult, JsonRpcMessage,
+    JsonRpcNotification, JsonRpcResponse, ListToolsResult, LoggingCapability, LoggingLevel,
+    LoggingMessageParams, McpTool, ServerCapabilities, SetLevelParams, ToolsCapability,
+    LOGGING_MESSAGE,
 };
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast;
 
 /// Trait for MCP tool handlers.
 #[async_trait]
@@ -99,8 +103,163 @@ where
     }
 }
 
+/// Callback suggesting values for a prompt or resource template argument.
+///
+/// Receives the value typed so far and returns all matching values.
+pub type CompletionFn = Arc<dyn Fn(&str) -> Vec<String> + Send + Sync>;
+
+/// Notification sent when the tool list changes.
+pub const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
+
+/// Shared, mutable set of tools served by an [`McpServer`].
+///
+/// Cloning the registry gives another handle to the same tools, so tools can
+/// be added or removed while the server is running. Every change broadcasts a
+/// `notifications/tools/list_changed` notification to connected clients.
+#[derive(Clone)]
+pub struct ToolRegistry {
+    tools: Arc<RwLock<HashMap<String, Arc<dyn ToolHandler>>>>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+}
+
+impl ToolRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        let (notifications, _) = broadcast::channel(NOTIFICATION_BUFFER);
+        Self {
+            tools: Arc::new(RwLock::new(HashMap::new())),
+            notifications,
+        }
+    }
+
+    /// Add or replace a tool.
+    pub fn add(&self, handler: impl ToolHandler + 'static) {
+        self.add_arc(Arc::new(handler));
+    }
+
+    /// Add or replace a shared tool handler.
+    pub fn add_arc(&self, handler: Arc<dyn ToolHandler>) {
+        let name = handler.definition().name;
+        self.tools.write().insert(name, handler);
+        self.notify_list_changed();
+    }
+
+    /// Remove a tool, returning whether it existed.
+    pub fn remove(&self, name: &str) -> bool {
+        let removed = self.tools.write().remove(name).is_some();
+        if removed {
+            self.notify_list_changed();
+        }
+        removed
+    }
+
+    /// Get a tool handler by name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ToolHandler>> {
+        self.tools.read().get(name).cloned()
+    }
+
+    /// Check whether a tool is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.tools.read().contains_key(name)
+    }
+
+    /// Get the definitions of all tools.
+    pub fn definitions(&self) -> Vec<McpTool> {
+        self.tools.read().values().map(|h| h.definition()).collect()
+    }
+
+    /// Get the number of tools.
+    pub fn len(&self) -> usize {
+        self.tools.read().len()
+    }
+
+    /// Check if there are no tools.
+    pub fn is_empty(&self) -> bool {
+        self.tools.read().is_empty()
+    }
+
+    /// Subscribe to change notifications.
+    pub fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.notifications.subscribe()
+    }
+
+    fn notify_list_changed(&self) {
+        // No subscribers just means no client is connected yet.
+        let _ = self
+            .notifications
+            .send(JsonRpcNotification::new(TOOLS_LIST_CHANGED));
+    }
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Capacity of the notification channel per connected client.
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// Handle for sending log messages to clients of an [`McpServer`].
+///
+/// Messages are sent as `notifications/message`. Until a client picks a level
+/// with `logging/setLevel`, messages of every level are sent.
+#[derive(Clone)]
+pub struct McpLogger {
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    min_level: Arc<RwLock<Option<LoggingLevel>>>,
+}
+
+impl McpLogger {
+    fn new(notifications: broadcast::Sender<JsonRpcNotification>) -> Self {
+        Self {
+            notifications,
+            min_level: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Send a log message to connected clients.
+    ///
+    /// Messages below the level requested by the client are dropped.
+    pub fn log(&self, level: LoggingLevel, logger: Option<&str>, data: impl Into<JsonValue>) {
+        if !self.enabled(level) {
+            return;
+        }
+        let params = LoggingMessageParams {
+            level,
+            logger: logger.map(str::to_string),
+            data: data.into(),
+        };
+        if let Ok(notification) = JsonRpcNotification::new(LOGGING_MESSAGE).with_params(params) {
+            // No subscribers just means no client is connected yet.
+            let _ = self.notifications.send(notification);
+        }
+    }
+
+    /// Check whether messages of a level are sent.
+    pub fn enabled(&self, level: LoggingLevel) -> bool {
+        self.min_level.read().map_or(true, |min| level >= min)
+    }
+
+    /// Get the minimum level requested by the client, if any.
+    pub fn level(&self) -> Option<LoggingLevel> {
+        *self.min_level.read()
+    }
+
+    fn set_level(&self, level: LoggingLevel) {
+        *self.min_level.write() = Some(level);
+    }
+}
+
 /// MCP server for exposing tools.
 ///
+/// Tools can be added and removed while the server runs, either through the
+/// server itself or a [`ToolRegistry`] handle from [`registry`](Self::registry).
+/// Connected clients are sent `notifications/tools/list_changed` on every change.
+///
+/// Log messages sent with [`log`](Self::log) or a [`McpLogger`] from
+/// [`logger`](Self::logger) reach clients as `notifications/message`.
+///
 /// # Example
 ///
 /// ```ignore
@@ -113,24 +272,34 @@ where
 ///         |args| CallToolResult::text(args.to_string()),
 ///     );
 ///
+/// // Add tools later, e.g. when a plugin is loaded
+/// let registry = server.registry();
+///
+/// server.log(LoggingLevel::Info, Some("startup"), "Server ready");
+///
 /// server.run_stdio().await?;
 /// ```
 pub struct McpServer {
     info: Implementation,
-    tools: RwLock<HashMap<String, Arc<dyn ToolHandler>>>,
+    tools: ToolRegistry,
+    logger: McpLogger,
+    completions: HashMap<(CompletionReference, String), CompletionFn>,
     capabilities: ServerCapabilities,
 }
 
 impl McpServer {
     /// Create a new server.
     pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        let tools = ToolRegistry::new();
+        let logger = McpLogger::new(tools.notifications.clone());
         Self {
             info: Implementation::new(name, version),
-            tools: RwLock::new(HashMap::new()),
+            tools,
+            logger,
+            completions: HashMap::new(),
             capabilities: ServerCapabilities {
-                tools: Some(ToolsCapability {
-                    list_changed: false,
-                }),
+                tools: Some(ToolsCapability { list_changed: true }),
+                logging: Some(LoggingCapability {}),
                 ..Default::default()
             },
         }
@@ -138,10 +307,7 @@ impl McpServer {
 
     /// Add a tool handler.
     pub fn tool(self, handler: impl ToolHandler + 'static) -> Self {
-        let def = handler.definition();
-        self.tools
-            .write()
-            .insert(def.name.clone(), Arc::new(handler));
+        self.tools.add(handler);
         self
     }
 
@@ -150,39 +316,116 @@ impl McpServer {
     where
         F: Fn(JsonValue) -> CallToolResult + Send + Sync + 'static,
     {
-        let name = definition.name.clone();
-        let handler = FnToolHandler::new(definition, handler);
-        self.tools.write().insert(name, Arc::new(handler));
+        self.tools.add(FnToolHandler::new(definition, handler));
+        self
+    }
+
+    /// Add an argument completion callback.
+    ///
+    /// Answers `completion/complete` requests for `argument` of the referenced
+    /// prompt or resource template. At most 100 values are sent; the callback
+    /// can return more and the client is told there are more matches.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let server = McpServer::new("my-server", "1.0.0").completion_fn(
+    ///     CompletionReference::prompt("code_review"),
+    ///     "language",
+    ///     |value| {
+    ///         ["python", "rust", "typescript"]
+    ///             .into_iter()
+    ///             .filter(|lang| lang.starts_with(value))
+    ///             .map(String::from)
+    ///             .collect()
+    ///     },
+    /// );
+    /// ```
+    pub fn completion_fn<F>(
+        mut self,
+        reference: CompletionReference,
+        argument: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(&str) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.completions
+            .insert((reference, argument.into()), Arc::new(handler));
+        self.capabilities.completions = Some(CompletionsCapability {});
         self
     }
 
+    /// Add or replace a tool on a running server.
+    pub fn add_tool(&self, handler: impl ToolHandler + 'static) {
+        self.tools.add(handler);
+    }
+
+    /// Remove a tool from a running server, returning whether it existed.
+    pub fn remove_tool(&self, name: &str) -> bool {
+        self.tools.remove(name)
+    }
+
+    /// Get a handle to the tool registry.
+    pub fn registry(&self) -> ToolRegistry {
+        self.tools.clone()
+    }
+
+    /// Send a log message to connected clients.
+    pub fn log(&self, level: LoggingLevel, logger: Option<&str>, data: impl Into<JsonValue>) {
+        self.logger.log(level, logger, data);
+    }
+
+    /// Get a handle for sending log messages, e.g. from tool handlers.
+    pub fn logger(&self) -> McpLogger {
+        self.logger.clone()
+    }
+
     /// Run the server on stdio.
     pub async fn run_stdio(&self) -> McpResult<()> {
-        let stdin = tokio::io::stdin();
-        let mut stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
+        self.run(tokio::io::stdin(), tokio::io::stdout()).await
+    }
+
+    /// Run the server over a reader and writer of newline-delimited JSON-RPC.
+    ///
+    /// Tool list change notifications and log messages are written as they happen.
+    pub async fn run<R, W>(&self, reader: R, mut writer: W) -> McpResult<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut reader = BufReader::new(reader);
+        let mut notifications = self.tools.subscribe();
         let mut line = String::new();
 
         loop {
-            line.clear();
-            match reader.read_line(&mut line).await {
-                Ok(0) => break, // EOF
-                Ok(_) => {
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() {
-                        continue;
+            tokio::select! {
+                read = reader.read_line(&mut line) => {
+                    match read {
+                        Ok(0) => break, // EOF
+                        Ok(_) => {
+                            let trimmed = line.trim();
+                            if !trimmed.is_empty() {
+                                if let Some(resp) = self.handle_message(trimmed).await {
+                                    write_message(&mut writer, &resp).await?;
+                                }
+                            }
+                            line.clear();
+                        }
+                        Err(e) => return Err(McpError::Io(e)),
                     }
-
-                    let response = self.handle_message(trimmed).await;
-
-                    if let Some(resp) = response {
-                        let json = serde_json::to_string(&resp).map_err(McpError::Json)?;
-                        stdout.write_all(json.as_bytes()).await?;
-                        stdout.write_all(b"\n").await?;
-                        stdout.flush().await?;
+                }
+                notification = notifications.recv() => {
+                    match notification {
+                        Ok(notification) => write_message(&mut writer, &notification).await?,
+                        // Missed some changes; one notification covers them all.
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            let notification = JsonRpcNotification::new(TOOLS_LIST_CHANGED);
+                            write_message(&mut writer, &notification).await?;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {}
                     }
                 }
-                Err(e) => return Err(McpError::Io(e)),
             }
         }
 
@@ -221,9 +464,58 @@ impl McpServer {
                 };
                 Some(JsonRpcResponse::success(request.id, result))
             }
+            "ping" => Some(JsonRpcResponse::success(request.id, serde_json::json!({}))),
+            "completion/complete" => {
+                let params: CompleteParams = match request.params {
+                    Some(p) => match serde_json::from_value(p) {
+                        Ok(params) => params,
+                        Err(e) => {
+                            return Some(JsonRpcResponse::error(
+                                request.id,
+                                -32602,
+                                format!("Invalid params: {}", e),
+                            ));
+                        }
+                    },
+                    None => {
+                        return Some(JsonRpcResponse::error(request.id, -32602, "Missing params"));
+                    }
+                };
+
+                // Arguments without a callback have no suggestions
+                let values = self
+                    .completions
+                    .get(&(params.reference, params.argument.name))
+                    .map(|complete| complete(&params.argument.value))
+                    .unwrap_or_default();
+                let result = CompleteResult {
+                    completion: Completion::from_values(values),
+                };
+                Some(JsonRpcResponse::success(request.id, result))
+            }
+            "logging/setLevel" => {
+                let params: SetLevelParams = match request
+                    .params
+                    .map(serde_json::from_value)
+                    .transpose()
+                {
+                    Ok(Some(params)) => params,
+                    Ok(None) => {
+                        return Some(JsonRpcResponse::error(request.id, -32602, "Missing params"));
+                    }
+                    Err(e) => {
+                        return Some(JsonRpcResponse::error(
+                            request.id,
+                            -32602,
+                            format!("Invalid params: {}", e),
+                        ));
+                    }
+                };
+                self.logger.set_level(params.level);
+                Some(JsonRpcResponse::success(request.id, serde_json::json!({})))
+            }
             "tools/list" => {
-                let tools: Vec<McpTool> =
-                    self.tools.read().values().map(|h| h.definition()).collect();
+                let tools = self.tools.definitions();
                 let result = ListToolsResult {
                     tools,
                     next_cursor: None,
@@ -247,8 +539,8 @@ impl McpServer {
                     }
                 };
 
-                let handler = match self.tools.read().get(&params.name) {
-                    Some(h) => h.clone(),
+                let handler = match self.tools.get(&params.name) {
+                    Some(h) => h,
                     None => {
                         return Some(JsonRpcResponse::error(
                             request.id,
@@ -279,10 +571,23 @@ impl McpServer {
 
     /// Get registered tool count.
     pub fn tool_count(&self) -> usize {
-        self.tools.read().len()
+        self.tools.len()
     }
 }
 
+/// Write one JSON-RPC message as a line.
+async fn write_message<W, T>(writer: &mut W, message: &T) -> McpResult<()>
+where
+    W: AsyncWrite + Unpin,
+    T: serde::Serialize,
+{
+    let json = serde_json::to_string(message).map_err(McpError::Json)?;
+    writer.write_all(json.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
+
 impl Default for McpServer {
     fn default() -> Self {
         Self::new("mcp-server", "0.1.0")
@@ -368,4 +673,119 @@ mod tests {
         // Notifications don't get responses
         assert!(response.is_none());
     }
+
+    #[test]
+    fn test_registry_add_remove_notifies() {
+        let server = McpServer::new("test", "1.0.0");
+        let registry = server.registry();
+        let mut notifications = registry.subscribe();
+
+        server.add_tool(FnToolHandler::new(
+            McpTool::new("echo", serde_json::json!({"type": "object"})),
+            |args| CallToolResult::text(args.to_string()),
+        ));
+        assert_eq!(server.tool_count(), 1);
+        assert!(registry.contains("echo"));
+        assert_eq!(notifications.try_recv().unwrap().method, TOOLS_LIST_CHANGED);
+
+        assert!(registry.remove("echo"));
+        assert!(!server.remove_tool("echo"));
+        assert_eq!(server.tool_count(), 0);
+        assert_eq!(notifications.try_recv().unwrap().method, TOOLS_LIST_CHANGED);
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_sends_list_changed() {
+        let server = Arc::new(McpServer::new("test", "1.0.0"));
+        let (client, server_io) = tokio::io::duplex(4096);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let (client_read, mut client_write) = tokio::io::split(client);
+
+        let running = {
+            let server = Arc::clone(&server);
+            tokio::spawn(async move { server.run(server_read, server_write).await })
+        };
+
+        let mut lines = BufReader::new(client_read).lines();
+        client_write
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"initialize\",\"params\":{}}\n")
+            .await
+            .unwrap();
+        let init: JsonValue =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(
+            init["result"]["capabilities"]["tools"]["listChanged"],
+            serde_json::json!(true)
+        );
+
+        server.registry().add(FnToolHandler::new(
+            McpTool::new("late", serde_json::json!({"type": "object"})),
+            |_| CallToolResult::text("ok"),
+        ));
+        let notification: JsonValue =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(notification["method"], TOOLS_LIST_CHANGED);
+        assert!(notification.get("id").is_none());
+
+        // Closing both halves of the client ends the server loop
+        drop(client_write);
+        drop(lines);
+        running.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_log_respects_level() {
+        let server = McpServer::new("test", "1.0.0");
+        let mut notifications = server.registry().subscribe();
+
+        server.log(LoggingLevel::Debug, Some("db"), "connecting");
+        let notification = notifications.try_recv().unwrap();
+        assert_eq!(notification.method, LOGGING_MESSAGE);
+        assert_eq!(
+            notification.params,
+            Some(serde_json::json!({"level": "debug", "logger": "db", "data": "connecting"}))
+        );
+
+        let message =
+            r#"{"jsonrpc":"2.0","id":1,"method":"logging/setLevel","params":{"level":"warning"}}"#;
+        let response = server.handle_message(message).await.unwrap();
+        assert!(!response.is_error());
+        assert_eq!(server.logger().level(), Some(LoggingLevel::Warning));
+
+        server.log(LoggingLevel::Info, None, "dropped");
+        server
+            .logger()
+            .log(LoggingLevel::Error, None, serde_json::json!({"code": 7}));
+        let notification = notifications.try_recv().unwrap();
+        assert_eq!(notification.params.unwrap()["level"], "error");
+        assert!(notifications.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_completion() {
+        let server = McpServer::new("test", "1.0.0").completion_fn(
+            CompletionReference::prompt("code_review"),
+            "language",
+            |value| {
+                ["python", "perl", "rust"]
+                    .into_iter()
+                    .filter(|lang| lang.starts_with(value))
+                    .map(String::from)
+                    .collect()
+            },
+        );
+        assert!(server.capabilities.completions.is_some());
+
+        let message = r#"{"jsonrpc":"2.0","id":1,"method":"completion/complete","params":{"ref":{"type":"ref/prompt","name":"code_review"},"argument":{"name":"language","value":"p"}}}"#;
+        let response = server.handle_message(message).await.unwrap();
+        let result: CompleteResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(result.completion.values, vec!["python", "perl"]);
+        assert_eq!(result.completion.has_more, Some(false));
+
+        let message = r#"{"jsonrpc":"2.0","id":2,"method":"completion/complete","params":{"ref":{"type":"ref/resource","uri":"file:///{path}"},"argument":{"name":"path","value":""}}}"#;
+        let response = server.handle_message(message).await.unwrap();
+        let result: CompleteResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert!(result.completion.values.is_empty());
+    }
 }
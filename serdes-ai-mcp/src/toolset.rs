@@ -9,6 +9,7 @@ use crate::types::{McpTool, ToolResultContent};
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use serde_json::Value as JsonValue;
+use serdes_ai_retries::ExponentialBackoff;
 use serdes_ai_tools::definition::ToolDefinition;
 use serdes_ai_tools::return_types::ToolReturn;
 use serdes_ai_tools::ToolError;
@@ -64,8 +65,11 @@ impl<Deps> McpToolset<Deps> {
     }
 
     /// Connect via stdio and create toolset.
+    ///
+    /// The server process is restarted with the default backoff if it exits.
     pub async fn stdio(command: &str, args: &[&str]) -> McpResult<Self> {
-        let client = McpClient::stdio(command, args).await?;
+        let client =
+            McpClient::stdio_with_reconnect(command, args, ExponentialBackoff::default()).await?;
         client.initialize().await?;
         Ok(Self::new(client))
     }
@@ -97,7 +101,11 @@ impl<Deps> McpToolset<Deps> {
             mcp_tool.description.clone().unwrap_or_default(),
         );
 
-        ToolsetTool::new(definition).with_max_retries(2)
+        let tool = ToolsetTool::new(definition).with_max_retries(2);
+        match &mcp_tool.annotations {
+            Some(annotations) => tool.with_annotations(annotations.clone()),
+            None => tool,
+        }
     }
 
     fn convert_tools_to_map(&self, tools: &[McpTool]) -> HashMap<String, ToolsetTool> {
@@ -328,6 +336,7 @@ pub async fn load_mcp_servers(configs: Vec<McpServerConfig>) -> McpResult<Vec<Mc
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transport::MemoryTransport;
 
     #[test]
     fn test_server_config() {
@@ -357,4 +366,26 @@ mod tests {
         assert_eq!(mcp_tool.name, "search");
         assert_eq!(mcp_tool.description, Some("Search for things".to_string()));
     }
+
+    #[test]
+    fn test_convert_tool_annotations() {
+        let toolset: McpToolset = McpToolset::new(McpClient::new(MemoryTransport::new()));
+        let mcp_tool: McpTool = serde_json::from_value(serde_json::json!({
+            "name": "delete_file",
+            "inputSchema": {"type": "object"},
+            "annotations": {"destructiveHint": true, "idempotentHint": true}
+        }))
+        .unwrap();
+
+        let tool = toolset.convert_to_toolset_tool(&mcp_tool);
+        let annotations = tool.annotations.unwrap();
+        assert!(annotations.is_destructive());
+        assert!(annotations.is_idempotent());
+
+        let plain = McpTool::new("search", serde_json::json!({"type": "object"}));
+        assert!(toolset
+            .convert_to_toolset_tool(&plain)
+            .annotations
+            .is_none());
+    }
 }
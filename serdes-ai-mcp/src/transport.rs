@@ -5,12 +5,18 @@
 use crate::error::{McpError, McpResult};
 use crate::types::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
 use tokio::sync::{oneshot, Mutex};
 
+/// Callback for notifications sent by the server.
+pub type NotificationHandler = Arc<dyn Fn(JsonRpcNotification) + Send + Sync>;
+
 /// Trait for MCP transport implementations.
 #[async_trait]
 pub trait McpTransport: Send + Sync {
@@ -25,6 +31,11 @@ pub trait McpTransport: Send + Sync {
 
     /// Check if the transport is connected.
     fn is_connected(&self) -> bool;
+
+    /// Set the handler for notifications sent by the server.
+    ///
+    /// Transports that cannot receive server notifications ignore the handler.
+    fn set_notification_handler(&self, _handler: NotificationHandler) {}
 }
 
 /// Stdio transport for local MCP servers.
@@ -34,7 +45,8 @@ pub struct StdioTransport {
     child: Arc<Mutex<Option<Child>>>,
     stdin: Arc<Mutex<ChildStdin>>,
     pending: Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>>,
-    connected: Arc<std::sync::atomic::AtomicBool>,
+    connected: Arc<AtomicBool>,
+    notification_handler: Arc<RwLock<Option<NotificationHandler>>>,
 }
 
 impl StdioTransport {
@@ -61,9 +73,16 @@ impl StdioTransport {
         let stderr = child.stderr.take();
 
         let pending = Arc::new(Mutex::new(HashMap::new()));
+        let connected = Arc::new(AtomicBool::new(true));
+        let notification_handler = Arc::new(RwLock::new(None));
 
         // Spawn reader task for stdout
-        tokio::spawn(Self::reader_task(stdout, pending.clone()));
+        tokio::spawn(Self::reader_task(
+            stdout,
+            pending.clone(),
+            connected.clone(),
+            notification_handler.clone(),
+        ));
 
         // Spawn stderr drainer to prevent blocking if server writes to stderr
         if let Some(stderr) = stderr {
@@ -74,7 +93,8 @@ impl StdioTransport {
             child: Arc::new(Mutex::new(Some(child))),
             stdin: Arc::new(Mutex::new(stdin)),
             pending,
-            connected: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            connected,
+            notification_handler,
         })
     }
 
@@ -110,9 +130,16 @@ impl StdioTransport {
         let stderr = child.stderr.take();
 
         let pending = Arc::new(Mutex::new(HashMap::new()));
+        let connected = Arc::new(AtomicBool::new(true));
+        let notification_handler = Arc::new(RwLock::new(None));
 
         // Spawn reader task for stdout
-        tokio::spawn(Self::reader_task(stdout, pending.clone()));
+        tokio::spawn(Self::reader_task(
+            stdout,
+            pending.clone(),
+            connected.clone(),
+            notification_handler.clone(),
+        ));
 
         // Spawn stderr drainer to prevent blocking if server writes to stderr
         if let Some(stderr) = stderr {
@@ -123,13 +150,16 @@ impl StdioTransport {
             child: Arc::new(Mutex::new(Some(child))),
             stdin: Arc::new(Mutex::new(stdin)),
             pending,
-            connected: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            connected,
+            notification_handler,
         })
     }
 
     async fn reader_task(
         stdout: ChildStdout,
         pending: Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>>,
+        connected: Arc<AtomicBool>,
+        notification_handler: Arc<RwLock<Option<NotificationHandler>>>,
     ) {
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
@@ -146,7 +176,18 @@ impl StdioTransport {
 
                     let response: JsonRpcResponse = match serde_json::from_str(trimmed) {
                         Ok(resp) => resp,
-                        Err(_) => continue,
+                        Err(_) => {
+                            // Messages without an id are server notifications
+                            if let Ok(notification) =
+                                serde_json::from_str::<JsonRpcNotification>(trimmed)
+                            {
+                                let handler = notification_handler.read().clone();
+                                if let Some(handler) = handler {
+                                    handler(notification);
+                                }
+                            }
+                            continue;
+                        }
                     };
 
                     let request_id = match &response.id {
@@ -168,6 +209,10 @@ impl StdioTransport {
                 Err(_) => break,
             }
         }
+
+        // The server exited: fail in-flight requests instead of leaving them hanging
+        connected.store(false, Ordering::SeqCst);
+        pending.lock().await.clear();
     }
 
     /// Drain stderr to prevent the process from blocking.
@@ -218,7 +263,14 @@ impl McpTransport for StdioTransport {
         };
 
         let (tx, rx) = oneshot::channel();
-        self.pending.lock().await.insert(request_id, tx);
+        {
+            // Checked under the lock so the reader task can't miss this request on exit
+            let mut pending = self.pending.lock().await;
+            if !self.is_connected() {
+                return Err(McpError::ConnectionClosed);
+            }
+            pending.insert(request_id, tx);
+        }
 
         let json = serde_json::to_string(request)?;
         if let Err(err) = self.send_raw(&json).await {
@@ -235,8 +287,7 @@ impl McpTransport for StdioTransport {
     }
 
     async fn close(&self) -> McpResult<()> {
-        self.connected
-            .store(false, std::sync::atomic::Ordering::SeqCst);
+        self.connected.store(false, Ordering::SeqCst);
 
         let mut child = self.child.lock().await;
         if let Some(mut c) = child.take() {
@@ -246,13 +297,19 @@ impl McpTransport for StdioTransport {
     }
 
     fn is_connected(&self) -> bool {
-        self.connected.load(std::sync::atomic::Ordering::SeqCst)
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn set_notification_handler(&self, handler: NotificationHandler) {
+        *self.notification_handler.write() = Some(handler);
     }
 }
 
 /// HTTP transport for remote MCP servers.
 ///
-/// This transport communicates with an MCP server via HTTP.
+/// This transport communicates with an MCP server via HTTP. With the `otel`
+/// feature of `serdes-ai-core` enabled, requests carry the current span's
+/// `traceparent` header.
 #[cfg(feature = "reqwest")]
 pub struct HttpTransport {
     client: reqwest::Client,
@@ -338,6 +395,9 @@ impl McpTransport for HttpTransport {
         for (key, value) in &self.custom_headers {
             req = req.header(key, value);
         }
+        for (name, value) in serdes_ai_core::trace_context::current_headers() {
+            req = req.header(name, value);
+        }
 
         // Add session ID if we have one
         let session_id = self.session_id.lock().await;
@@ -392,6 +452,9 @@ impl McpTransport for HttpTransport {
         for (key, value) in &self.custom_headers {
             req = req.header(key, value);
         }
+        for (name, value) in serdes_ai_core::trace_context::current_headers() {
+            req = req.header(name, value);
+        }
 
         let session_id = self.session_id.lock().await;
         if let Some(ref id) = *session_id {
@@ -424,12 +487,57 @@ impl McpTransport for HttpTransport {
     }
 }
 
+/// Handler that answers requests sent over a [`MemoryTransport`].
+pub type RequestHandler = Arc<dyn Fn(&JsonRpcRequest) -> JsonRpcResponse + Send + Sync>;
+
+/// Fault injected into the next request of a [`MemoryTransport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportFault {
+    /// Delay the response.
+    Delay(Duration),
+    /// Lose the request; the call fails with [`McpError::Timeout`].
+    Drop,
+    /// Deliver the response twice; the copy answers the following request.
+    Duplicate,
+    /// Disconnect during the call; the call and later calls fail with
+    /// [`McpError::ConnectionClosed`] until [`MemoryTransport::reconnect`].
+    Disconnect,
+}
+
 /// Memory transport for testing.
+///
+/// Clones share the same state, so a test can keep a handle after passing the
+/// transport to a client.
+///
+/// Requests are answered from responses queued with
+/// [`push_response`](Self::push_response), falling back to the
+/// [`RequestHandler`] given to [`with_handler`](Self::with_handler). Faults
+/// queued with [`inject_fault`](Self::inject_fault) apply to the following
+/// requests in order, so failure scenarios are deterministic.
+///
+/// # Example
+///
+/// ```ignore
+/// use serdes_ai_mcp::{JsonRpcResponse, MemoryTransport, TransportFault};
+///
+/// let transport = MemoryTransport::with_handler(|req| JsonRpcResponse::success(req.id.clone(), "ok"));
+/// transport.inject_fault(TransportFault::Drop);
+///
+/// // Each session is an independent connection to the same handler
+/// let other = transport.session();
+/// ```
+#[derive(Clone)]
 pub struct MemoryTransport {
-    responses: Arc<Mutex<std::collections::VecDeque<JsonRpcResponse>>>,
+    session_id: u64,
+    next_session: Arc<AtomicU64>,
+    handler: Option<RequestHandler>,
+    responses: Arc<Mutex<VecDeque<JsonRpcResponse>>>,
     requests: Arc<Mutex<Vec<JsonRpcRequest>>>,
     notifications: Arc<Mutex<Vec<JsonRpcNotification>>>,
-    connected: Arc<std::sync::atomic::AtomicBool>,
+    faults: Arc<RwLock<VecDeque<TransportFault>>>,
+    latency: Arc<RwLock<Duration>>,
+    connected: Arc<AtomicBool>,
+    notification_handler: Arc<RwLock<Option<NotificationHandler>>>,
 }
 
 impl Default for MemoryTransport {
@@ -441,24 +549,83 @@ impl Default for MemoryTransport {
 impl MemoryTransport {
     /// Create a new memory transport.
     pub fn new() -> Self {
+        Self::build(0, Arc::new(AtomicU64::new(1)), None)
+    }
+
+    /// Create a memory transport that answers requests with a handler.
+    pub fn with_handler<F>(handler: F) -> Self
+    where
+        F: Fn(&JsonRpcRequest) -> JsonRpcResponse + Send + Sync + 'static,
+    {
+        Self::build(0, Arc::new(AtomicU64::new(1)), Some(Arc::new(handler)))
+    }
+
+    fn build(
+        session_id: u64,
+        next_session: Arc<AtomicU64>,
+        handler: Option<RequestHandler>,
+    ) -> Self {
         Self {
-            responses: Arc::new(Mutex::new(std::collections::VecDeque::new())),
+            session_id,
+            next_session,
+            handler,
+            responses: Arc::new(Mutex::new(VecDeque::new())),
             requests: Arc::new(Mutex::new(Vec::new())),
             notifications: Arc::new(Mutex::new(Vec::new())),
-            connected: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            faults: Arc::new(RwLock::new(VecDeque::new())),
+            latency: Arc::new(RwLock::new(Duration::ZERO)),
+            connected: Arc::new(AtomicBool::new(true)),
+            notification_handler: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Open another session to the same handler.
+    ///
+    /// The session has its own connection state, queued responses, faults
+    /// and recorded messages, so several clients can run side by side.
+    pub fn session(&self) -> Self {
+        let id = self.next_session.fetch_add(1, Ordering::SeqCst);
+        Self::build(id, self.next_session.clone(), self.handler.clone())
+    }
+
+    /// Get the session ID. The first session is `0`.
+    pub fn session_id(&self) -> u64 {
+        self.session_id
+    }
+
     /// Add a response to be returned.
     pub async fn push_response(&self, response: JsonRpcResponse) {
         self.responses.lock().await.push_back(response);
     }
 
+    /// Queue a fault. Queued faults apply to the following requests, one each.
+    pub fn inject_fault(&self, fault: TransportFault) {
+        self.faults.write().push_back(fault);
+    }
+
+    /// Delay every response by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        *self.latency.write() = latency;
+    }
+
+    /// Restore the connection after a close or injected disconnect.
+    pub fn reconnect(&self) {
+        self.connected.store(true, Ordering::SeqCst);
+    }
+
     /// Get recorded requests.
     pub async fn get_requests(&self) -> Vec<JsonRpcRequest> {
         self.requests.lock().await.clone()
     }
 
+    /// Deliver a notification as if sent by the server.
+    pub fn receive_notification(&self, notification: JsonRpcNotification) {
+        let handler = self.notification_handler.read().clone();
+        if let Some(handler) = handler {
+            handler(notification);
+        }
+    }
+
     /// Get recorded notifications.
     pub async fn get_notifications(&self) -> Vec<JsonRpcNotification> {
         self.notifications.lock().await.clone()
@@ -469,19 +636,51 @@ impl MemoryTransport {
         self.responses.lock().await.clear();
         self.requests.lock().await.clear();
         self.notifications.lock().await.clear();
+        self.faults.write().clear();
+    }
+
+    async fn respond(&self, request: &JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        if let Some(response) = self.responses.lock().await.pop_front() {
+            return Ok(response);
+        }
+        match &self.handler {
+            Some(handler) => Ok(handler(request)),
+            None => Err(McpError::NoResult),
+        }
     }
 }
 
 #[async_trait]
 impl McpTransport for MemoryTransport {
     async fn request(&self, request: &JsonRpcRequest) -> McpResult<JsonRpcResponse> {
+        if !self.is_connected() {
+            return Err(McpError::ConnectionClosed);
+        }
         self.requests.lock().await.push(request.clone());
 
-        self.responses
-            .lock()
-            .await
-            .pop_front()
-            .ok_or(McpError::NoResult)
+        let latency = *self.latency.read();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        let fault = self.faults.write().pop_front();
+        match fault {
+            None => self.respond(request).await,
+            Some(TransportFault::Delay(delay)) => {
+                tokio::time::sleep(delay).await;
+                self.respond(request).await
+            }
+            Some(TransportFault::Drop) => Err(McpError::Timeout),
+            Some(TransportFault::Duplicate) => {
+                let response = self.respond(request).await?;
+                self.responses.lock().await.push_front(response.clone());
+                Ok(response)
+            }
+            Some(TransportFault::Disconnect) => {
+                self.connected.store(false, Ordering::SeqCst);
+                Err(McpError::ConnectionClosed)
+            }
+        }
     }
 
     async fn notify(&self, notification: &JsonRpcNotification) -> McpResult<()> {
@@ -490,13 +689,16 @@ impl McpTransport for MemoryTransport {
     }
 
     async fn close(&self) -> McpResult<()> {
-        self.connected
-            .store(false, std::sync::atomic::Ordering::SeqCst);
+        self.connected.store(false, Ordering::SeqCst);
         Ok(())
     }
 
     fn is_connected(&self) -> bool {
-        self.connected.load(std::sync::atomic::Ordering::SeqCst)
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn set_notification_handler(&self, handler: NotificationHandler) {
+        *self.notification_handler.write() = Some(handler);
     }
 }
 
@@ -534,6 +736,76 @@ mod tests {
         assert_eq!(notifications.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_memory_transport_sessions() {
+        let transport = MemoryTransport::with_handler(|req| {
+            JsonRpcResponse::success(req.id.clone(), req.method.clone())
+        });
+        let other = transport.session();
+        assert_eq!(transport.session_id(), 0);
+        assert_eq!(other.session_id(), 1);
+
+        let (req_a, req_b) = (JsonRpcRequest::new(1, "a"), JsonRpcRequest::new(1, "b"));
+        let (a, b) = tokio::join!(transport.request(&req_a), other.request(&req_b));
+        assert_eq!(a.unwrap().result, Some(serde_json::json!("a")));
+        assert_eq!(b.unwrap().result, Some(serde_json::json!("b")));
+
+        // Sessions are isolated from each other
+        other.close().await.unwrap();
+        assert!(transport.is_connected());
+        assert_eq!(transport.get_requests().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_faults() {
+        let transport = MemoryTransport::with_handler(|req| {
+            JsonRpcResponse::success(req.id.clone(), req.method.clone())
+        });
+        transport.inject_fault(TransportFault::Drop);
+        transport.inject_fault(TransportFault::Duplicate);
+        transport.inject_fault(TransportFault::Delay(Duration::from_millis(5)));
+        transport.inject_fault(TransportFault::Disconnect);
+
+        let result = transport.request(&JsonRpcRequest::new(1, "first")).await;
+        assert!(matches!(result, Err(McpError::Timeout)));
+
+        let result = transport.request(&JsonRpcRequest::new(2, "second")).await;
+        assert_eq!(result.unwrap().id, RequestId::Number(2));
+
+        // The duplicate answers the next request, with a stale id
+        let started = std::time::Instant::now();
+        let result = transport.request(&JsonRpcRequest::new(3, "third")).await;
+        assert_eq!(result.unwrap().id, RequestId::Number(2));
+        assert!(started.elapsed() >= Duration::from_millis(5));
+
+        let result = transport.request(&JsonRpcRequest::new(4, "fourth")).await;
+        assert!(matches!(result, Err(McpError::ConnectionClosed)));
+        assert!(!transport.is_connected());
+        let result = transport.request(&JsonRpcRequest::new(5, "fifth")).await;
+        assert!(matches!(result, Err(McpError::ConnectionClosed)));
+
+        transport.reconnect();
+        let result = transport.request(&JsonRpcRequest::new(6, "sixth")).await;
+        assert_eq!(result.unwrap().id, RequestId::Number(6));
+    }
+
+    #[tokio::test]
+    async fn test_stdio_exit_fails_pending_requests() {
+        // `true` exits right away without answering
+        let transport = StdioTransport::spawn("true", &[]).await.unwrap();
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            transport.request(&JsonRpcRequest::new(1, "ping")),
+        )
+        .await
+        .expect("request should fail instead of hanging");
+
+        assert!(matches!(
+            result,
+            Err(McpError::ConnectionClosed | McpError::Io(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_memory_transport_close() {
         let transport = MemoryTransport::new();
@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use serdes_ai_toolsets::ToolAnnotations;
 
 // ============================================================================
 // JSON-RPC Types
@@ -307,6 +308,9 @@ pub struct ServerCapabilities {
     /// Logging capability.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub logging: Option<LoggingCapability>,
+    /// Argument completion capability.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
 }
 
 /// Tools capability.
@@ -343,6 +347,10 @@ pub struct PromptsCapability {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LoggingCapability {}
 
+/// Argument completion capability.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompletionsCapability {}
+
 // ============================================================================
 // MCP Types - Tools
 // ============================================================================
@@ -358,6 +366,9 @@ pub struct McpTool {
     pub description: Option<String>,
     /// Input schema (JSON Schema).
     pub input_schema: JsonValue,
+    /// Behavior hints (read-only, destructive, idempotent, open world).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
 }
 
 impl McpTool {
@@ -367,6 +378,7 @@ impl McpTool {
             name: name.into(),
             description: None,
             input_schema,
+            annotations: None,
         }
     }
 
@@ -375,6 +387,12 @@ impl McpTool {
         self.description = Some(description.into());
         self
     }
+
+    /// Set behavior hints.
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
 }
 
 /// List tools result.
@@ -630,6 +648,145 @@ pub enum PromptMessageContent {
     },
 }
 
+// ============================================================================
+// MCP Types - Completion
+// ============================================================================
+
+/// Maximum number of values in a completion result.
+pub const MAX_COMPLETION_VALUES: usize = 100;
+
+/// What is being completed: a prompt or a resource template.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    /// Reference to a prompt.
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// Prompt name.
+        name: String,
+    },
+    /// Reference to a resource template.
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// Resource template URI.
+        uri: String,
+    },
+}
+
+impl CompletionReference {
+    /// Reference a prompt by name.
+    pub fn prompt(name: impl Into<String>) -> Self {
+        Self::Prompt { name: name.into() }
+    }
+
+    /// Reference a resource template by URI.
+    pub fn resource(uri: impl Into<String>) -> Self {
+        Self::Resource { uri: uri.into() }
+    }
+}
+
+/// Argument being completed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    /// Argument name.
+    pub name: String,
+    /// Value typed so far.
+    pub value: String,
+}
+
+/// Parameters of a `completion/complete` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteParams {
+    /// Prompt or resource template the argument belongs to.
+    #[serde(rename = "ref")]
+    pub reference: CompletionReference,
+    /// Argument being completed.
+    pub argument: CompletionArgument,
+}
+
+/// Completion suggestions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    /// Suggested values, at most [`MAX_COMPLETION_VALUES`].
+    pub values: Vec<String>,
+    /// Total number of matches, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// Whether there are more matches than returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+impl Completion {
+    /// Build a completion from all matches, keeping the first
+    /// [`MAX_COMPLETION_VALUES`].
+    pub fn from_values(mut values: Vec<String>) -> Self {
+        let total = values.len();
+        values.truncate(MAX_COMPLETION_VALUES);
+        Self {
+            has_more: Some(total > values.len()),
+            total: Some(total),
+            values,
+        }
+    }
+}
+
+/// Result of a `completion/complete` request.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompleteResult {
+    /// Completion suggestions.
+    pub completion: Completion,
+}
+
+// ============================================================================
+// MCP Types - Logging
+// ============================================================================
+
+/// Notification carrying a server log message.
+pub const LOGGING_MESSAGE: &str = "notifications/message";
+
+/// Log severity, ordered from least to most severe (RFC 5424).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingLevel {
+    /// Detailed debugging information.
+    Debug,
+    /// General informational messages.
+    Info,
+    /// Normal but significant events.
+    Notice,
+    /// Warning conditions.
+    Warning,
+    /// Error conditions.
+    Error,
+    /// Critical conditions.
+    Critical,
+    /// Action must be taken immediately.
+    Alert,
+    /// System is unusable.
+    Emergency,
+}
+
+/// Parameters of a `notifications/message` notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingMessageParams {
+    /// Severity.
+    pub level: LoggingLevel,
+    /// Name of the logger that produced the message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// Message payload (any JSON value).
+    pub data: JsonValue,
+}
+
+/// Parameters of a `logging/setLevel` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevelParams {
+    /// Minimum level the server should send.
+    pub level: LoggingLevel,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -712,4 +869,35 @@ mod tests {
         let parsed: McpTool = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.name, "test");
     }
+
+    #[test]
+    fn test_logging_level() {
+        assert!(LoggingLevel::Debug < LoggingLevel::Warning);
+        assert!(LoggingLevel::Emergency > LoggingLevel::Alert);
+        assert_eq!(
+            serde_json::to_value(LoggingLevel::Warning).unwrap(),
+            serde_json::json!("warning")
+        );
+
+        let params: LoggingMessageParams =
+            serde_json::from_value(serde_json::json!({"level": "error", "data": "boom"})).unwrap();
+        assert_eq!(params.level, LoggingLevel::Error);
+        assert!(params.logger.is_none());
+    }
+
+    #[test]
+    fn test_complete_params() {
+        let params: CompleteParams = serde_json::from_value(serde_json::json!({
+            "ref": {"type": "ref/prompt", "name": "code_review"},
+            "argument": {"name": "language", "value": "py"}
+        }))
+        .unwrap();
+        assert_eq!(params.reference, CompletionReference::prompt("code_review"));
+        assert_eq!(params.argument.value, "py");
+
+        let completion = Completion::from_values((0..150).map(|i| i.to_string()).collect());
+        assert_eq!(completion.values.len(), MAX_COMPLETION_VALUES);
+        assert_eq!(completion.total, Some(150));
+        assert_eq!(completion.has_more, Some(true));
+    }
 }
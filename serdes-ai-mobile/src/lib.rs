@@ -0,0 +1,372 @@
+//! # serdes-ai-mobile
+//!
+//! [uniffi](https://mozilla.github.io/uniffi-rs/) bindings for running
+//! serdes-ai agents in iOS and Android apps that call cloud models.
+//!
+//! The API is narrowed to what an on-device agent app needs:
+//!
+//! - [`Agent::new`] builds an agent from an [`AgentConfig`]
+//! - [`Agent::run`] runs it, delivering [`AgentEvent`]s to an
+//!   [`EventListener`] while the run streams
+//! - [`Cancellation::cancel`] stops a run from any thread
+//!
+//! `run` is an `async` function on the Kotlin and Swift side (a `suspend fun`
+//! and an `async throws` method) driven by a tokio runtime owned by the
+//! library. Message histories use pydantic-ai's message JSON, so a
+//! conversation can be persisted as a string and resumed later.
+//!
+//! ## TLS
+//!
+//! HTTP goes through reqwest with rustls and the bundled Mozilla root
+//! certificates, so no OpenSSL has to be cross-compiled and the same
+//! binary works on both platforms' TLS stacks.
+//!
+//! ## Generating bindings
+//!
+//! ```bash
+//! cargo build -p serdes-ai-mobile --release
+//! cargo run -p serdes-ai-mobile --features bindgen --bin uniffi-bindgen -- \
+//!     generate --library target/release/libserdes_ai_mobile.so \
+//!     --language kotlin --out-dir bindings/kotlin
+//! ```
+
+use serdes_ai::agent::{AgentStreamEvent, CancellationToken};
+use serdes_ai::core::messages::from_pydantic_str;
+use serdes_ai_ffi::{event_json, run_streamed, RunFailure};
+use std::sync::Arc;
+
+uniffi::setup_scaffolding!();
+
+/// Errors surfaced to Kotlin and Swift.
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum MobileError {
+    /// The agent could not be built from its config.
+    #[error("Invalid config: {message}")]
+    InvalidConfig {
+        /// Why the config was rejected.
+        message: String,
+    },
+    /// The message history is not valid pydantic-ai message JSON.
+    #[error("Invalid message history: {message}")]
+    InvalidHistory {
+        /// Why the history was rejected.
+        message: String,
+    },
+    /// The run failed.
+    #[error("Run failed: {message}")]
+    RunFailed {
+        /// Why the run failed.
+        message: String,
+    },
+    /// The run was cancelled.
+    #[error("Run was cancelled")]
+    Cancelled,
+}
+
+/// Configuration of an agent.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct AgentConfig {
+    /// Model spec, e.g. `"anthropic:claude-3-5-sonnet-20241022"`.
+    pub model: String,
+    /// System prompt.
+    #[uniffi(default = None)]
+    pub system_prompt: Option<String>,
+    /// Instructions.
+    #[uniffi(default = None)]
+    pub instructions: Option<String>,
+    /// Sampling temperature.
+    #[uniffi(default = None)]
+    pub temperature: Option<f64>,
+    /// Maximum tokens per response.
+    #[uniffi(default = None)]
+    pub max_tokens: Option<u64>,
+    /// API key. Apps should fetch it from their backend rather than ship it.
+    #[uniffi(default = None)]
+    pub api_key: Option<String>,
+    /// Base URL of the provider API, e.g. the app's own proxy.
+    #[uniffi(default = None)]
+    pub base_url: Option<String>,
+}
+
+impl From<AgentConfig> for serdes_ai_ffi::AgentConfig {
+    fn from(config: AgentConfig) -> Self {
+        Self {
+            model: config.model,
+            system_prompt: config.system_prompt,
+            instructions: config.instructions,
+            name: None,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            api_key: config.api_key,
+            base_url: config.base_url,
+        }
+    }
+}
+
+/// The result of a completed run.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RunResult {
+    /// The run ID.
+    pub run_id: String,
+    /// Text of the final model response.
+    pub output: String,
+    /// The full history as pydantic-ai message JSON.
+    pub messages_json: String,
+}
+
+/// An event emitted while a run streams.
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum AgentEvent {
+    /// The run started.
+    RunStart { run_id: String },
+    /// A model request started.
+    RequestStart { step: u32 },
+    /// Text was received.
+    TextDelta { text: String },
+    /// Reasoning text was received.
+    ThinkingDelta { text: String },
+    /// The model started calling a tool.
+    ToolCallStart {
+        tool_name: String,
+        tool_call_id: Option<String>,
+    },
+    /// A tool finished.
+    ToolExecuted {
+        tool_name: String,
+        tool_call_id: Option<String>,
+        success: bool,
+        error: Option<String>,
+    },
+    /// A model response completed.
+    ResponseComplete { step: u32 },
+    /// The output was rejected and the model will be asked again.
+    OutputRejected { message: String },
+    /// An error occurred.
+    Error { message: String },
+    /// The run was cancelled.
+    Cancelled { partial_text: Option<String> },
+    /// The run completed.
+    RunComplete { run_id: String },
+    /// Any other event, as a JSON object with a `type` field.
+    Other { json: String },
+}
+
+impl From<&AgentStreamEvent> for AgentEvent {
+    fn from(event: &AgentStreamEvent) -> Self {
+        match event {
+            AgentStreamEvent::RunStart { run_id } => Self::RunStart {
+                run_id: run_id.clone(),
+            },
+            AgentStreamEvent::RequestStart { step } => Self::RequestStart { step: *step },
+            AgentStreamEvent::TextDelta { text } => Self::TextDelta { text: text.clone() },
+            AgentStreamEvent::ThinkingDelta { text } => Self::ThinkingDelta { text: text.clone() },
+            AgentStreamEvent::ToolCallStart {
+                tool_name,
+                tool_call_id,
+            } => Self::ToolCallStart {
+                tool_name: tool_name.clone(),
+                tool_call_id: tool_call_id.clone(),
+            },
+            AgentStreamEvent::ToolExecuted {
+                tool_name,
+                tool_call_id,
+                success,
+                error,
+            } => Self::ToolExecuted {
+                tool_name: tool_name.clone(),
+                tool_call_id: tool_call_id.clone(),
+                success: *success,
+                error: error.clone(),
+            },
+            AgentStreamEvent::ResponseComplete { step } => Self::ResponseComplete { step: *step },
+            AgentStreamEvent::OutputRejected { message, .. } => Self::OutputRejected {
+                message: message.clone(),
+            },
+            AgentStreamEvent::Error { message } => Self::Error {
+                message: message.clone(),
+            },
+            AgentStreamEvent::Cancelled { partial_text, .. } => Self::Cancelled {
+                partial_text: partial_text.clone(),
+            },
+            AgentStreamEvent::RunComplete { run_id, .. } => Self::RunComplete {
+                run_id: run_id.clone(),
+            },
+            event => Self::Other {
+                json: event_json(event).to_string(),
+            },
+        }
+    }
+}
+
+/// Receives the events of a run.
+///
+/// Implemented in Kotlin or Swift. Events arrive on a library thread, so
+/// UI updates must be dispatched to the main thread.
+#[uniffi::export(with_foreign)]
+pub trait EventListener: Send + Sync {
+    /// Handle one event.
+    fn on_event(&self, event: AgentEvent);
+}
+
+/// Cancels a run, e.g. when the user leaves the screen.
+#[derive(Debug, Default, uniffi::Object)]
+pub struct Cancellation {
+    token: CancellationToken,
+}
+
+#[uniffi::export]
+impl Cancellation {
+    /// Create a cancellation that has not been triggered.
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Cancel the run. The run ends with [`MobileError::Cancelled`].
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Check whether the run was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+/// An agent with text output.
+#[derive(uniffi::Object)]
+pub struct Agent {
+    inner: serdes_ai::agent::Agent<(), String>,
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl Agent {
+    /// Build an agent.
+    #[uniffi::constructor]
+    pub fn new(config: AgentConfig) -> Result<Arc<Self>, MobileError> {
+        let inner = serdes_ai_ffi::AgentConfig::from(config)
+            .build()
+            .map_err(|e| MobileError::InvalidConfig {
+                message: e.to_string(),
+            })?;
+        Ok(Arc::new(Self { inner }))
+    }
+
+    /// Run the agent.
+    ///
+    /// `message_history_json` is the `messages_json` of an earlier
+    /// [`RunResult`] to continue a conversation.
+    pub async fn run(
+        &self,
+        prompt: String,
+        message_history_json: Option<String>,
+        listener: Option<Arc<dyn EventListener>>,
+        cancellation: Option<Arc<Cancellation>>,
+    ) -> Result<RunResult, MobileError> {
+        let history = match message_history_json {
+            Some(json) => from_pydantic_str(&json).map_err(|e| MobileError::InvalidHistory {
+                message: e.to_string(),
+            })?,
+            None => Vec::new(),
+        };
+        let token = cancellation.map_or_else(CancellationToken::new, |c| c.token.clone());
+
+        let output = run_streamed(&self.inner, prompt, history, token, |event| {
+            if let Some(listener) = &listener {
+                listener.on_event(AgentEvent::from(event));
+            }
+            true
+        })
+        .await
+        .map_err(|failure| match failure {
+            RunFailure::Cancelled => MobileError::Cancelled,
+            RunFailure::Failed(e) => MobileError::RunFailed {
+                message: e.to_string(),
+            },
+        })?;
+
+        Ok(RunResult {
+            run_id: output.run_id,
+            output: output.output,
+            messages_json: output.messages.to_string(),
+        })
+    }
+}
+
+impl Agent {
+    /// Wrap an existing agent.
+    pub fn from_agent(agent: serdes_ai::agent::Agent<(), String>) -> Arc<Self> {
+        Arc::new(Self { inner: agent })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use serdes_ai::core::messages::{ModelResponsePart, ModelResponseStreamEvent, TextPart};
+    use serdes_ai_models::FunctionModel;
+    use std::sync::Mutex;
+
+    fn streaming_agent() -> Arc<Agent> {
+        let model = FunctionModel::with_stream(|_messages, _settings| {
+            Box::pin(stream::iter(vec![
+                Ok(ModelResponseStreamEvent::part_start(
+                    0,
+                    ModelResponsePart::Text(TextPart::new("Bonjour")),
+                )),
+                Ok(ModelResponseStreamEvent::part_end(0)),
+            ]))
+        });
+        Agent::from_agent(serdes_ai::agent::agent(model).build())
+    }
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<AgentEvent>>);
+
+    impl EventListener for Recorder {
+        fn on_event(&self, event: AgentEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_delivers_events() {
+        let agent = streaming_agent();
+        let recorder = Arc::new(Recorder::default());
+
+        let result = agent
+            .run("hi".into(), None, Some(recorder.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(result.output, "Bonjour");
+
+        let events = recorder.0.lock().unwrap().clone();
+        assert!(matches!(events.first(), Some(AgentEvent::RunStart { .. })));
+        assert!(events.contains(&AgentEvent::TextDelta {
+            text: "Bonjour".into()
+        }));
+        assert_eq!(
+            events.last(),
+            Some(&AgentEvent::RunComplete {
+                run_id: result.run_id.clone()
+            })
+        );
+
+        // The history continues the conversation
+        let followup = agent
+            .run("again".into(), Some(result.messages_json), None, None)
+            .await
+            .unwrap();
+        assert_eq!(followup.output, "Bonjour");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_history() {
+        let err = streaming_agent()
+            .run("hi".into(), Some("{}".into()), None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MobileError::InvalidHistory { .. }));
+    }
+}
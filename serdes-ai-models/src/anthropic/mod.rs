@@ -37,7 +37,7 @@ pub mod stream;
 pub mod types;
 
 // Re-exports
-pub use model::AnthropicModel;
+pub use model::{anthropic_tools, AnthropicModel};
 pub use types::{
     AnthropicContent, AnthropicError, AnthropicMessage, AnthropicTool, AnthropicToolChoice,
     AnthropicUsage, CacheControl, ContentBlock, ContentBlockDelta, ContentBlockStart,
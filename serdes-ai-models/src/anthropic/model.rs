@@ -3,6 +3,9 @@
 use super::stream::AnthropicStreamParser;
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_capture::{HttpCapture, HttpExchange};
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::{anthropic_claude_profile, ModelProfile};
 use async_trait::async_trait;
@@ -10,8 +13,8 @@ use base64::Engine;
 use reqwest::header::HeaderMap;
 use reqwest::Client;
 use serdes_ai_core::messages::{
-    DocumentContent, ImageContent, RetryPromptPart, TextPart, ThinkingPart, ToolCallArgs,
-    ToolCallPart, ToolReturnPart, UserContent, UserContentPart,
+    latest_instructions, DocumentContent, ImageContent, RetryPromptPart, TextPart, ThinkingPart,
+    ToolCallArgs, ToolCallPart, ToolReturnPart, UserContent, UserContentPart,
 };
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
@@ -37,6 +40,8 @@ pub struct AnthropicModel {
     enable_caching: bool,
     /// Anthropic API version.
     api_version: String,
+    /// Capture of raw HTTP exchanges, if enabled.
+    http_capture: Option<HttpCapture>,
 }
 
 impl AnthropicModel {
@@ -56,6 +61,7 @@ impl AnthropicModel {
             thinking_budget: None,
             enable_caching: false,
             api_version: "2023-06-01".to_string(),
+            http_capture: HttpCapture::from_env(),
         }
     }
 
@@ -87,6 +93,16 @@ impl AnthropicModel {
         self
     }
 
+    /// Capture raw HTTP exchanges into `capture` for debugging.
+    ///
+    /// See [`http_capture`](crate::http_capture); also enabled by the
+    /// `SERDES_AI_HTTP_CAPTURE` environment variable.
+    #[must_use]
+    pub fn with_http_capture(mut self, capture: HttpCapture) -> Self {
+        self.http_capture = Some(capture);
+        self
+    }
+
     /// Set a custom profile.
     #[must_use]
     pub fn with_profile(mut self, profile: ModelProfile) -> Self {
@@ -222,7 +238,7 @@ impl AnthropicModel {
             }
         }
 
-        let system = if system_parts.is_empty() {
+        let mut system = if system_parts.is_empty() {
             None
         } else if self.enable_caching && system_parts.len() == 1 {
             Some(SystemContent::cached(
@@ -232,6 +248,29 @@ impl AnthropicModel {
             Some(SystemContent::text(system_parts.join("\n\n")))
         };
 
+        // Instructions change between requests, so they go after the
+        // (possibly cached) system prompt as their own block.
+        if let Some(instructions) = latest_instructions(requests) {
+            let block = SystemBlock::Text {
+                text: instructions.to_string(),
+                cache_control: None,
+            };
+            system = Some(match system {
+                None => SystemContent::text(instructions),
+                Some(SystemContent::Text(text)) => SystemContent::Blocks(vec![
+                    SystemBlock::Text {
+                        text,
+                        cache_control: None,
+                    },
+                    block,
+                ]),
+                Some(SystemContent::Blocks(mut blocks)) => {
+                    blocks.push(block);
+                    SystemContent::Blocks(blocks)
+                }
+            });
+        }
+
         (system, api_messages)
     }
 
@@ -399,22 +438,7 @@ impl AnthropicModel {
 
     /// Convert tool definitions to Anthropic format.
     fn convert_tools(&self, tools: &[ToolDefinition]) -> Vec<AnthropicTool> {
-        tools
-            .iter()
-            .enumerate()
-            .map(|(i, t)| {
-                let schema = serde_json::to_value(&t.parameters_json_schema)
-                    .unwrap_or(serde_json::json!({}));
-                let mut tool = AnthropicTool::new(&t.name, &t.description, schema);
-
-                // Cache the last tool definition for efficiency
-                if self.enable_caching && i == tools.len() - 1 {
-                    tool = tool.with_cache();
-                }
-
-                tool
-            })
-            .collect()
+        anthropic_tools(tools, self.enable_caching)
     }
 
     /// Convert tool choice.
@@ -537,6 +561,7 @@ impl AnthropicModel {
             usage: Some(usage),
             vendor_id: Some(resp.id),
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -597,6 +622,10 @@ impl Model for AnthropicModel {
         &self.profile
     }
 
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.http_capture.as_ref().and_then(HttpCapture::last)
+    }
+
     async fn request(
         &self,
         messages: &[ModelRequest],
@@ -610,7 +639,7 @@ impl Model for AnthropicModel {
         let mut request = self
             .client
             .post(format!("{}/v1/messages", self.base_url))
-            .header("x-api-key", &self.api_key)
+            .header("x-api-key", settings.api_key_or(&self.api_key))
             .header("anthropic-version", &self.api_version)
             .header("Content-Type", "application/json")
             .timeout(timeout);
@@ -625,21 +654,27 @@ impl Model for AnthropicModel {
             request = request.header("anthropic-beta", "prompt-caching-2024-07-31");
         }
 
-        let response = request.json(&body).send().await?;
+        let (meter, body) = RequestMeter::start(&body)?;
+        let meter = meter.capture(
+            self.http_capture.as_ref(),
+            format!("{}/v1/messages", self.base_url),
+            &body,
+        );
+        let response = request.body(body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
             let headers = response.headers().clone();
             let body = response.text().await.unwrap_or_default();
+            meter.record(status, body.as_bytes());
             return Err(self.handle_error_response(status, &body, &headers));
         }
 
-        let resp: MessagesResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (resp, metrics) = meter.read_json::<MessagesResponse>(response).await?;
 
-        self.parse_response(resp)
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -655,7 +690,7 @@ impl Model for AnthropicModel {
         let mut request = self
             .client
             .post(format!("{}/v1/messages", self.base_url))
-            .header("x-api-key", &self.api_key)
+            .header("x-api-key", settings.api_key_or(&self.api_key))
             .header("anthropic-version", &self.api_version)
             .header("Content-Type", "application/json")
             .timeout(timeout);
@@ -668,7 +703,7 @@ impl Model for AnthropicModel {
             request = request.header("anthropic-beta", "prompt-caching-2024-07-31");
         }
 
-        let response = request.json(&body).send().await?;
+        let response = request.json(&body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
@@ -684,6 +719,31 @@ impl Model for AnthropicModel {
     }
 }
 
+/// Convert tool definitions to the `tools` blocks of a messages request.
+///
+/// This is exactly what [`AnthropicModel`] sends; serialize the result to see
+/// the JSON. With `cache_last`, the last tool gets a cache breakpoint, as the
+/// model does when prompt caching is enabled.
+#[must_use]
+pub fn anthropic_tools(tools: &[ToolDefinition], cache_last: bool) -> Vec<AnthropicTool> {
+    tools
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let schema =
+                serde_json::to_value(&t.parameters_json_schema).unwrap_or(serde_json::json!({}));
+            let mut tool = AnthropicTool::new(&t.name, &t.description, schema);
+
+            // Cache the last tool definition for efficiency
+            if cache_last && i == tools.len() - 1 {
+                tool = tool.with_cache();
+            }
+
+            tool
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -719,6 +779,30 @@ mod tests {
         assert!(matches!(converted, AnthropicContent::Text(ref t) if t == "Hello!"));
     }
 
+    #[test]
+    fn test_convert_messages_instructions_after_cached_system() {
+        let model = AnthropicModel::new("claude-3-5-sonnet-20241022", "key").with_caching();
+        let mut first = ModelRequest::new();
+        first.add_system_prompt("You are helpful.");
+        first.add_user_prompt("Hi");
+        let mut second = ModelRequest::new().with_instructions("Answer in French.");
+        second.add_user_prompt("Again");
+
+        let (system, _) = model.convert_messages(&[first, second]);
+        let Some(SystemContent::Blocks(blocks)) = system else {
+            panic!("expected system blocks");
+        };
+        assert_eq!(blocks.len(), 2);
+        assert!(matches!(
+            &blocks[0],
+            SystemBlock::Text { text, cache_control: Some(_) } if text == "You are helpful."
+        ));
+        assert!(matches!(
+            &blocks[1],
+            SystemBlock::Text { text, cache_control: None } if text == "Answer in French."
+        ));
+    }
+
     #[test]
     fn test_convert_tools() {
         use serdes_ai_tools::ObjectJsonSchema;
@@ -732,6 +816,26 @@ mod tests {
         assert_eq!(converted[0].name, "search");
     }
 
+    #[test]
+    fn test_anthropic_tools_json() {
+        use serdes_ai_tools::ObjectJsonSchema;
+
+        let tools = vec![
+            ToolDefinition::new("search", "Search the web")
+                .with_parameters(ObjectJsonSchema::new()),
+            ToolDefinition::new("fetch", "Fetch a page"),
+        ];
+
+        let json = serde_json::to_value(anthropic_tools(&tools, true)).unwrap();
+        assert_eq!(json[0]["name"], "search");
+        assert_eq!(json[0]["input_schema"]["type"], "object");
+        assert!(json[0].get("cache_control").is_none());
+        assert_eq!(json[1]["cache_control"]["type"], "ephemeral");
+
+        let json = serde_json::to_value(anthropic_tools(&tools, false)).unwrap();
+        assert!(json[1].get("cache_control").is_none());
+    }
+
     #[test]
     fn test_convert_tool_choice() {
         let model = AnthropicModel::new("claude-3-5-sonnet-20241022", "key");
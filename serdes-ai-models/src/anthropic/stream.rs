@@ -4,337 +4,161 @@
 
 use super::types::{ContentBlockDelta, ContentBlockStart, StreamEvent};
 use crate::error::ModelError;
-use bytes::Bytes;
-use futures::Stream;
-use pin_project_lite::pin_project;
+use crate::stream_adapter::{AdapterStream, PartKey, PartTracker, StreamAdapter};
 use serdes_ai_core::messages::{
-    ModelResponsePartDelta, ModelResponseStreamEvent, PartDeltaEvent, PartEndEvent, PartStartEvent,
-    TextPart, ThinkingPart, ThinkingPartDelta, ToolCallPart,
+    ModelResponsePartDelta, ModelResponseStreamEvent, TextPart, TextPartDelta, ThinkingPart,
+    ThinkingPartDelta, ToolCallPart, ToolCallPartDelta,
 };
 use serdes_ai_core::ModelResponsePart;
-use std::collections::HashMap;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-
-pin_project! {
-    /// Anthropic SSE stream parser.
-    pub struct AnthropicStreamParser<S> {
-        #[pin]
-        inner: S,
-        buffer: String,
-        // Track content blocks in progress
-        blocks: HashMap<usize, BlockState>,
-        // Message metadata
-        message_id: Option<String>,
-        model: Option<String>,
-        // Usage tracking
-        input_tokens: u64,
-        output_tokens: u64,
-        cache_creation_tokens: Option<u64>,
-        cache_read_tokens: Option<u64>,
-        // Finished
-        done: bool,
-    }
-}
 
-/// State for an in-progress content block.
-#[derive(Debug, Clone)]
-enum BlockState {
-    Text {
-        content: String,
-    },
-    ToolUse {
-        #[allow(dead_code)]
-        id: String,
-        #[allow(dead_code)]
-        name: String,
-        input_json: String,
-    },
-    Thinking {
-        content: String,
-        signature: Option<String>,
-    },
-    RedactedThinking {
-        /// The encrypted signature data.
-        #[allow(dead_code)]
-        signature: String,
-    },
+/// Anthropic SSE stream parser.
+pub type AnthropicStreamParser<S> = AdapterStream<S, AnthropicStreamAdapter>;
+
+/// Maps Anthropic Messages API stream events to stream events.
+#[derive(Debug, Default)]
+pub struct AnthropicStreamAdapter {
+    // Track content blocks in progress
+    parts: PartTracker,
+    // Message metadata
+    message_id: Option<String>,
+    model: Option<String>,
+    // Usage tracking
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: Option<u64>,
+    cache_read_tokens: Option<u64>,
+    // Finished
+    done: bool,
 }
 
-impl<S> AnthropicStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    /// Create a new stream parser.
-    pub fn new(inner: S) -> Self {
-        Self {
-            inner,
-            buffer: String::new(),
-            blocks: HashMap::new(),
-            message_id: None,
-            model: None,
-            input_tokens: 0,
-            output_tokens: 0,
-            cache_creation_tokens: None,
-            cache_read_tokens: None,
-            done: false,
-        }
+impl AnthropicStreamAdapter {
+    /// Get the message ID, once `message_start` was received.
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id.as_deref()
     }
-}
-
-impl<S> Stream for AnthropicStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    type Item = Result<ModelResponseStreamEvent, ModelError>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
-
-        if *this.done {
-            return Poll::Ready(None);
-        }
-
-        loop {
-            // Check if we have complete events in the buffer
-            // Anthropic uses "event: type\ndata: json\n\n" format
-            while let Some(event_result) = parse_next_event(this.buffer) {
-                match event_result {
-                    Ok((event_type, data)) => {
-                        if let Some(result) = process_event(
-                            &event_type,
-                            &data,
-                            this.blocks,
-                            this.message_id,
-                            this.model,
-                            this.input_tokens,
-                            this.output_tokens,
-                            this.cache_creation_tokens,
-                            this.cache_read_tokens,
-                            this.done,
-                        ) {
-                            return Poll::Ready(Some(result));
-                        }
-                    }
-                    Err(e) => {
-                        return Poll::Ready(Some(Err(e)));
-                    }
-                }
-            }
 
-            // Need more data
-            match this.inner.as_mut().poll_next(cx) {
-                Poll::Ready(Some(Ok(bytes))) => {
-                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                        this.buffer.push_str(text);
-                    }
-                }
-                Poll::Ready(Some(Err(e))) => {
-                    return Poll::Ready(Some(Err(ModelError::Other(e.into()))));
-                }
-                Poll::Ready(None) => {
-                    *this.done = true;
-                    return Poll::Ready(None);
-                }
-                Poll::Pending => return Poll::Pending,
-            }
-        }
+    /// Get the model name, once `message_start` was received.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
     }
-}
 
-/// Parse the next complete event from the buffer.
-/// Returns Some((event_type, data)) if a complete event was found.
-fn parse_next_event(buffer: &mut String) -> Option<Result<(String, String), ModelError>> {
-    // Look for event: and data: lines followed by blank line
-    let mut event_type = None;
-    let mut data = None;
-    let mut end_pos = 0;
-    let mut found_event = false;
-
-    for (i, line) in buffer.lines().enumerate() {
-        if let Some(stripped) = line.strip_prefix("event: ") {
-            event_type = Some(stripped.to_string());
-        } else if let Some(stripped) = line.strip_prefix("data: ") {
-            data = Some(stripped.to_string());
-        } else if line.is_empty() && (event_type.is_some() || data.is_some()) {
-            // Calculate position after this empty line
-            end_pos = buffer
-                .lines()
-                .take(i + 1)
-                .map(|l| l.len() + 1) // +1 for newline
-                .sum();
-            found_event = true;
-            break;
-        }
+    /// Get the input token count.
+    pub fn input_tokens(&self) -> u64 {
+        self.input_tokens
     }
 
-    if found_event {
-        // Remove processed content from buffer
-        buffer.drain(..end_pos.min(buffer.len()));
+    /// Get the output token count.
+    pub fn output_tokens(&self) -> u64 {
+        self.output_tokens
+    }
 
-        match (event_type, data) {
-            (Some(et), Some(d)) => return Some(Ok((et, d))),
-            (None, Some(d)) => return Some(Ok(("message".to_string(), d))),
-            _ => {}
-        }
+    /// Get the cache creation input token count.
+    pub fn cache_creation_tokens(&self) -> Option<u64> {
+        self.cache_creation_tokens
     }
 
-    None
+    /// Get the cache read input token count.
+    pub fn cache_read_tokens(&self) -> Option<u64> {
+        self.cache_read_tokens
+    }
 }
 
-/// Process a parsed event into a stream event.
-#[allow(clippy::too_many_arguments)]
-fn process_event(
-    _event_type: &str,
-    data: &str,
-    blocks: &mut HashMap<usize, BlockState>,
-    message_id: &mut Option<String>,
-    model: &mut Option<String>,
-    input_tokens: &mut u64,
-    output_tokens: &mut u64,
-    cache_creation_tokens: &mut Option<u64>,
-    cache_read_tokens: &mut Option<u64>,
-    done: &mut bool,
-) -> Option<Result<ModelResponseStreamEvent, ModelError>> {
-    // Parse the JSON data
-    let event: StreamEvent = match serde_json::from_str(data) {
-        Ok(e) => e,
-        Err(e) => {
-            tracing::warn!("Failed to parse stream event: {} - data: {}", e, data);
-            return None;
-        }
-    };
-
-    match event {
-        StreamEvent::MessageStart { message } => {
-            *message_id = Some(message.id);
-            *model = Some(message.model);
-            *input_tokens = message.usage.input_tokens;
-            *cache_creation_tokens = message.usage.cache_creation_input_tokens;
-            *cache_read_tokens = message.usage.cache_read_input_tokens;
-            None
-        }
+impl StreamAdapter for AnthropicStreamAdapter {
+    type Chunk = StreamEvent;
+
+    fn map_chunk(
+        &mut self,
+        event: StreamEvent,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let event = match event {
+            StreamEvent::MessageStart { message } => {
+                self.message_id = Some(message.id);
+                self.model = Some(message.model);
+                self.input_tokens = message.usage.input_tokens;
+                self.cache_creation_tokens = message.usage.cache_creation_input_tokens;
+                self.cache_read_tokens = message.usage.cache_read_input_tokens;
+                None
+            }
 
-        StreamEvent::ContentBlockStart {
-            index,
-            content_block,
-        } => {
-            let (state, part) = match content_block {
-                ContentBlockStart::Text { text } => (
-                    BlockState::Text {
-                        content: text.clone(),
-                    },
-                    ModelResponsePart::Text(TextPart::new(&text)),
-                ),
-                ContentBlockStart::ToolUse { id, name, input } => (
-                    BlockState::ToolUse {
-                        id: id.clone(),
-                        name: name.clone(),
-                        input_json: serde_json::to_string(&input).unwrap_or_default(),
-                    },
-                    ModelResponsePart::ToolCall(
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let part = match content_block {
+                    ContentBlockStart::Text { text } => {
+                        ModelResponsePart::Text(TextPart::new(&text))
+                    }
+                    ContentBlockStart::ToolUse { id, name, input } => ModelResponsePart::ToolCall(
                         ToolCallPart::new(&name, input).with_tool_call_id(&id),
                     ),
-                ),
-                ContentBlockStart::Thinking { thinking } => (
-                    BlockState::Thinking {
-                        content: thinking.clone(),
-                        signature: None,
-                    },
-                    ModelResponsePart::Thinking(ThinkingPart::new(&thinking)),
-                ),
-                ContentBlockStart::RedactedThinking { data } => (
-                    BlockState::RedactedThinking {
-                        signature: data.clone(),
-                    },
-                    ModelResponsePart::Thinking(ThinkingPart::redacted(&data, "anthropic")),
-                ),
-            };
-
-            blocks.insert(index, state);
-            Some(Ok(ModelResponseStreamEvent::PartStart(
-                PartStartEvent::new(index, part),
-            )))
-        }
+                    ContentBlockStart::Thinking { thinking } => {
+                        ModelResponsePart::Thinking(ThinkingPart::new(&thinking))
+                    }
+                    ContentBlockStart::RedactedThinking { data } => {
+                        ModelResponsePart::Thinking(ThinkingPart::redacted(&data, "anthropic"))
+                    }
+                };
 
-        StreamEvent::ContentBlockDelta { index, delta } => {
-            let state = blocks.get_mut(&index)?;
+                Some(self.parts.start(PartKey::Block(index as u64), part))
+            }
 
-            match delta {
-                ContentBlockDelta::TextDelta { text } => {
-                    if let BlockState::Text { content } = state {
-                        content.push_str(&text);
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let delta = match delta {
+                    ContentBlockDelta::TextDelta { text } => {
+                        ModelResponsePartDelta::Text(TextPartDelta::new(text))
                     }
-                    Some(Ok(ModelResponseStreamEvent::PartDelta(
-                        PartDeltaEvent::text(index, text),
-                    )))
-                }
-                ContentBlockDelta::InputJsonDelta { partial_json } => {
-                    if let BlockState::ToolUse { input_json, .. } = state {
-                        input_json.push_str(&partial_json);
-                    }
-                    Some(Ok(ModelResponseStreamEvent::PartDelta(
-                        PartDeltaEvent::tool_call_args(index, partial_json),
-                    )))
-                }
-                ContentBlockDelta::ThinkingDelta { thinking } => {
-                    if let BlockState::Thinking { content, .. } = state {
-                        content.push_str(&thinking);
+                    ContentBlockDelta::InputJsonDelta { partial_json } => {
+                        ModelResponsePartDelta::ToolCall(ToolCallPartDelta::new(partial_json))
                     }
-                    Some(Ok(ModelResponseStreamEvent::PartDelta(
-                        PartDeltaEvent::thinking(index, thinking),
-                    )))
-                }
-                ContentBlockDelta::SignatureDelta { signature } => {
-                    if let BlockState::Thinking { signature: sig, .. } = state {
-                        match sig {
-                            Some(s) => s.push_str(&signature),
-                            None => *sig = Some(signature.clone()),
-                        }
+                    ContentBlockDelta::ThinkingDelta { thinking } => {
+                        ModelResponsePartDelta::Thinking(ThinkingPartDelta::new(thinking))
                     }
                     // Emit signature delta so agents can track it
-                    Some(Ok(ModelResponseStreamEvent::PartDelta(PartDeltaEvent {
-                        index,
-                        delta: ModelResponsePartDelta::Thinking(
+                    ContentBlockDelta::SignatureDelta { signature } => {
+                        ModelResponsePartDelta::Thinking(
                             ThinkingPartDelta::new("").with_signature_delta(signature),
-                        ),
-                    })))
-                }
+                        )
+                    }
+                };
+                self.parts.delta(&PartKey::Block(index as u64), delta)
             }
-        }
 
-        StreamEvent::ContentBlockStop { index } => {
-            blocks.remove(&index);
-            Some(Ok(ModelResponseStreamEvent::PartEnd(PartEndEvent {
-                index,
-            })))
-        }
+            StreamEvent::ContentBlockStop { index } => {
+                self.parts.close(&PartKey::Block(index as u64))
+            }
 
-        StreamEvent::MessageDelta { delta: _, usage } => {
-            if let Some(u) = usage {
-                *output_tokens = u.output_tokens;
+            StreamEvent::MessageDelta { delta: _, usage } => {
+                if let Some(u) = usage {
+                    self.output_tokens = u.output_tokens;
+                }
+                // We don't emit finish reason as event since core doesn't have it
+                None
             }
-            // We don't emit finish reason as event since core doesn't have it
-            None
-        }
 
-        StreamEvent::MessageStop => {
-            *done = true;
-            None
-        }
+            StreamEvent::MessageStop => {
+                self.done = true;
+                None
+            }
+
+            StreamEvent::Ping => None,
 
-        StreamEvent::Ping => None,
+            StreamEvent::Error { error } => {
+                return Err(ModelError::api_with_code(error.message, error.error_type));
+            }
+        };
+
+        Ok(event.into_iter().collect())
+    }
 
-        StreamEvent::Error { error } => Some(Err(ModelError::api_with_code(
-            error.message,
-            error.error_type,
-        ))),
+    fn is_done(&self) -> bool {
+        self.done
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bytes::Bytes;
     use futures::stream;
     use futures::StreamExt;
 
@@ -5,11 +5,15 @@
 use super::stream::AntigravityStreamParser;
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::ModelProfile;
 use async_trait::async_trait;
 use reqwest::Client;
-use serdes_ai_core::messages::{ThinkingPart, ToolCallPart, UserContentPart};
+use serdes_ai_core::messages::{
+    instructions_as_system_prompt, ThinkingPart, ToolCallPart, UserContentPart,
+};
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
     RequestUsage,
@@ -192,6 +196,8 @@ impl AntigravityModel {
         &self,
         requests: &[ModelRequest],
     ) -> (Option<SystemInstruction>, Vec<Content>) {
+        let folded = instructions_as_system_prompt(requests);
+        let requests = &*folded;
         let mut contents = Vec::new();
         let mut system_parts: Vec<String> = Vec::new();
 
@@ -517,6 +523,7 @@ impl AntigravityModel {
             usage,
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         }
     }
@@ -550,11 +557,13 @@ impl Model for AntigravityModel {
             "Antigravity: making request"
         );
 
+        let (meter, body) = RequestMeter::start(&request_body)?;
         let response = self
             .client
             .post(&url)
             .headers(headers)
-            .json(&request_body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -577,8 +586,10 @@ impl Model for AntigravityModel {
             });
         }
 
-        let api_response: AntigravityResponse = response.json().await?;
-        Ok(self.convert_response(api_response))
+        let (api_response, metrics) = meter.read_json::<AntigravityResponse>(response).await?;
+        let mut response = self.convert_response(api_response);
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -611,6 +622,7 @@ impl Model for AntigravityModel {
             .post(&url)
             .headers(headers)
             .json(&request_body)
+            .trace_headers()
             .send()
             .await?;
 
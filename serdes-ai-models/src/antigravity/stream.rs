@@ -4,306 +4,125 @@
 
 use super::types::*;
 use crate::error::ModelError;
-use bytes::Bytes;
-use futures::Stream;
-use pin_project_lite::pin_project;
-use serdes_ai_core::messages::{
-    ModelResponseStreamEvent, PartDeltaEvent, PartEndEvent, PartStartEvent, TextPart, ThinkingPart,
-    ToolCallPart,
-};
+use crate::stream_adapter::{AdapterStream, PartKey, PartTracker, StreamAdapter};
+use serdes_ai_core::messages::{ModelResponseStreamEvent, ToolCallPart};
 use serdes_ai_core::ModelResponsePart;
-use std::collections::HashMap;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-use tracing::{trace, warn};
 
-pin_project! {
-    /// SSE stream parser for Antigravity responses.
-    pub struct AntigravityStreamParser<S> {
-        #[pin]
-        inner: S,
-        buffer: String,
-        // Track parts in progress
-        parts: HashMap<usize, PartState>,
-        // Current part index
-        next_part_index: usize,
-        // Finished
-        done: bool,
-    }
-}
-
-/// State for an in-progress part.
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-enum PartState {
-    Text { content: String },
-    FunctionCall { name: String, args: String },
-    Thinking { content: String },
-}
-
-impl<S> AntigravityStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    /// Create a new stream parser.
-    pub fn new(inner: S) -> Self {
-        Self {
-            inner,
-            buffer: String::new(),
-            parts: HashMap::new(),
-            next_part_index: 0,
-            done: false,
-        }
-    }
-}
-
-impl<S> Stream for AntigravityStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    type Item = Result<ModelResponseStreamEvent, ModelError>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
-
-        if *this.done {
-            return Poll::Ready(None);
-        }
-
-        loop {
-            // Try both \r\n\r\n (Windows) and \n\n (Unix) line endings
-            let separator = if this.buffer.contains("\r\n\r\n") {
-                "\r\n\r\n"
-            } else {
-                "\n\n"
-            };
-
-            // Parse complete SSE events from buffer
-            while let Some(event_end) = this.buffer.find(separator) {
-                let event = this
-                    .buffer
-                    .drain(..event_end + separator.len())
-                    .collect::<String>();
-
-                // Parse SSE event lines
-                for line in event.lines() {
-                    if let Some(data) = line.strip_prefix("data: ") {
-                        // Handle [DONE] marker
-                        if data == "[DONE]" {
-                            *this.done = true;
-                            return Poll::Ready(None);
-                        }
-
-                        // Parse the JSON response
-                        match serde_json::from_str::<AntigravityResponse>(data) {
-                            Ok(response) => {
-                                if let Some(event) = process_response(
-                                    &response,
-                                    this.parts,
-                                    this.next_part_index,
-                                    this.done,
-                                ) {
-                                    return Poll::Ready(Some(event));
-                                }
-                            }
-                            Err(e) => {
-                                warn!("Failed to parse Antigravity stream chunk: {}", e);
-                                trace!("Raw data that failed: {}", data);
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Need more data
-            match this.inner.as_mut().poll_next(cx) {
-                Poll::Ready(Some(Ok(bytes))) => {
-                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                        this.buffer.push_str(text);
-                    }
-                }
-                Poll::Ready(Some(Err(e))) => {
-                    return Poll::Ready(Some(Err(ModelError::Other(e.into()))));
-                }
-                Poll::Ready(None) => {
-                    *this.done = true;
-                    // Process any remaining buffer
-                    if !this.buffer.is_empty() {
-                        let remaining = std::mem::take(this.buffer);
-                        for line in remaining.lines() {
-                            if let Some(data) = line.strip_prefix("data: ") {
-                                if data != "[DONE]" {
-                                    if let Ok(response) =
-                                        serde_json::from_str::<AntigravityResponse>(data)
-                                    {
-                                        if let Some(event) = process_response(
-                                            &response,
-                                            this.parts,
-                                            this.next_part_index,
-                                            this.done,
-                                        ) {
-                                            return Poll::Ready(Some(event));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    return Poll::Ready(None);
-                }
-                Poll::Pending => return Poll::Pending,
-            }
-        }
-    }
+/// SSE stream parser for Antigravity responses.
+pub type AntigravityStreamParser<S> = AdapterStream<S, AntigravityStreamAdapter>;
+
+/// Maps Antigravity response chunks to stream events.
+#[derive(Debug, Default)]
+pub struct AntigravityStreamAdapter {
+    // Track parts in progress
+    parts: PartTracker,
+    // Number of function calls seen so far
+    function_calls: u64,
+    // Finished
+    done: bool,
 }
 
-/// Process a response chunk into stream events.
-fn process_response(
-    response: &AntigravityResponse,
-    parts: &mut HashMap<usize, PartState>,
-    next_part_index: &mut usize,
-    done: &mut bool,
-) -> Option<Result<ModelResponseStreamEvent, ModelError>> {
-    // Get the first candidate from the wrapped response
-    let candidate = response.response.candidates.first()?;
-    let content = candidate.content.as_ref()?;
-
-    // Process each part
-    for part in &content.parts {
-        match part {
-            Part::Text { text } => {
-                if text.is_empty() {
-                    continue;
+impl StreamAdapter for AntigravityStreamAdapter {
+    type Chunk = AntigravityResponse;
+
+    fn map_chunk(
+        &mut self,
+        response: AntigravityResponse,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let mut events = Vec::new();
+
+        // Get the first candidate from the wrapped response
+        let Some(candidate) = response.response.candidates.first() else {
+            return Ok(events);
+        };
+
+        // Process each part
+        for part in candidate.content.iter().flat_map(|c| &c.parts) {
+            match part {
+                Part::Text { text } if !text.is_empty() => {
+                    events.push(self.parts.text(text));
                 }
-
-                // Check if we have an existing text part
-                let text_part_idx = parts.iter().find_map(|(idx, state)| {
-                    if matches!(state, PartState::Text { .. }) {
-                        Some(*idx)
-                    } else {
-                        None
+                Part::FunctionCall {
+                    function_call,
+                    thought_signature,
+                } => {
+                    // Function calls arrive complete, each one is a new part
+                    let key = PartKey::ToolCall(self.function_calls);
+                    self.function_calls += 1;
+
+                    let mut tool_part =
+                        ToolCallPart::new(&function_call.name, function_call.args.clone());
+                    if let Some(id) = &function_call.id {
+                        tool_part = tool_part.with_tool_call_id(id);
                     }
-                });
 
-                if let Some(idx) = text_part_idx {
-                    // Emit delta for existing part
-                    if let Some(PartState::Text { content }) = parts.get_mut(&idx) {
-                        let delta = text.clone();
-                        content.push_str(&delta);
-                        return Some(Ok(ModelResponseStreamEvent::PartDelta(
-                            PartDeltaEvent::text(idx, delta),
-                        )));
+                    // Store thought signature in provider_details for multi-turn tool calls
+                    if let Some(sig) = thought_signature {
+                        let mut details = serde_json::Map::new();
+                        details.insert(
+                            "thoughtSignature".to_string(),
+                            serde_json::Value::String(sig.clone()),
+                        );
+                        tool_part.provider_details = Some(details);
                     }
-                } else {
-                    // Start new text part
-                    let idx = *next_part_index;
-                    *next_part_index += 1;
-                    parts.insert(
-                        idx,
-                        PartState::Text {
-                            content: text.clone(),
-                        },
-                    );
-                    return Some(Ok(ModelResponseStreamEvent::PartStart(
-                        PartStartEvent::new(idx, ModelResponsePart::Text(TextPart::new(text))),
-                    )));
-                }
-            }
-            Part::FunctionCall {
-                function_call,
-                thought_signature,
-            } => {
-                // Start new function call part
-                let idx = *next_part_index;
-                *next_part_index += 1;
 
-                let mut tool_part =
-                    ToolCallPart::new(&function_call.name, function_call.args.clone());
-                if let Some(id) = &function_call.id {
-                    tool_part = tool_part.with_tool_call_id(id);
-                }
-
-                // Store thought signature in provider_details for multi-turn tool calls
-                if let Some(sig) = thought_signature {
-                    let mut details = serde_json::Map::new();
-                    details.insert(
-                        "thoughtSignature".to_string(),
-                        serde_json::Value::String(sig.clone()),
+                    events.push(
+                        self.parts
+                            .start(key, ModelResponsePart::ToolCall(tool_part)),
                     );
-                    tool_part.provider_details = Some(details);
                 }
-
-                parts.insert(
-                    idx,
-                    PartState::FunctionCall {
-                        name: function_call.name.clone(),
-                        args: serde_json::to_string(&function_call.args).unwrap_or_default(),
-                    },
-                );
-
-                return Some(Ok(ModelResponseStreamEvent::PartStart(
-                    PartStartEvent::new(idx, ModelResponsePart::ToolCall(tool_part)),
-                )));
-            }
-            Part::Thinking { thought: _, text } => {
                 // thought is a bool flag, text contains the actual thinking content
-                if text.is_empty() {
-                    continue;
-                }
-
-                // Check if we have an existing thinking part
-                let think_part_idx = parts.iter().find_map(|(idx, state)| {
-                    if matches!(state, PartState::Thinking { .. }) {
-                        Some(*idx)
-                    } else {
-                        None
-                    }
-                });
-
-                if let Some(idx) = think_part_idx {
-                    if let Some(PartState::Thinking { content }) = parts.get_mut(&idx) {
-                        let delta = text.clone();
-                        content.push_str(&delta);
-                        return Some(Ok(ModelResponseStreamEvent::PartDelta(
-                            PartDeltaEvent::thinking(idx, delta),
-                        )));
-                    }
-                } else {
-                    let idx = *next_part_index;
-                    *next_part_index += 1;
-                    parts.insert(
-                        idx,
-                        PartState::Thinking {
-                            content: text.clone(),
-                        },
-                    );
-                    return Some(Ok(ModelResponseStreamEvent::PartStart(
-                        PartStartEvent::new(
-                            idx,
-                            ModelResponsePart::Thinking(ThinkingPart::new(text)),
-                        ),
-                    )));
+                Part::Thinking { thought: _, text } if !text.is_empty() => {
+                    events.push(self.parts.thinking(text));
                 }
-            }
-            Part::ThoughtSignature { .. } => {
                 // Thought signatures are used for multi-turn, we can skip them for now
-                continue;
+                _ => {}
             }
-            _ => {}
         }
+
+        // Check for finish - emit end events for all parts
+        if candidate.finish_reason.is_some() {
+            self.done = true;
+            events.extend(self.parts.close_all());
+        }
+
+        Ok(events)
     }
 
-    // Check for finish
-    if candidate.finish_reason.is_some() {
-        *done = true;
+    fn is_done(&self) -> bool {
+        self.done
+    }
+}
 
-        // Emit end events for all parts
-        if let Some((idx, _)) = parts.drain().next() {
-            return Some(Ok(ModelResponseStreamEvent::PartEnd(PartEndEvent {
-                index: idx,
-            })));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures::{stream, StreamExt};
+
+    #[tokio::test]
+    async fn test_parse_parts_in_one_chunk() {
+        let chunk = r#"{"response":{"candidates":[{"content":{"role":"model","parts":[{"text":"Hi"},{"functionCall":{"name":"search","args":{"q":"rust"},"id":"call_1"}}]},"finishReason":"STOP"}]}}"#;
+        let inner = stream::iter(vec![Ok::<_, reqwest::Error>(Bytes::from(format!(
+            "data: {chunk}\r\n\r\n"
+        )))]);
+        let events: Vec<_> = AntigravityStreamParser::new(inner)
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        let kinds: Vec<_> = events.iter().map(|e| (e.is_start(), e.index())).collect();
+        assert_eq!(kinds, vec![(true, 0), (true, 1), (false, 0), (false, 1)]);
+        assert_eq!(events[1].part_id(), events[3].part_id());
+        match &events[1] {
+            ModelResponseStreamEvent::PartStart(start) => match &start.part {
+                ModelResponsePart::ToolCall(tc) => {
+                    assert_eq!(tc.tool_name, "search");
+                    assert_eq!(tc.tool_call_id.as_deref(), Some("call_1"));
+                }
+                other => panic!("Expected tool call, got {:?}", other),
+            },
+            other => panic!("Expected PartStart, got {:?}", other),
         }
     }
-
-    None
 }
@@ -26,6 +26,7 @@ use async_trait::async_trait;
 use std::time::Duration;
 
 use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::openai::OpenAIChatModel;
 use crate::profile::ModelProfile;
@@ -127,6 +128,10 @@ impl Model for AzureOpenAIModel {
         self.inner.profile()
     }
 
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
     async fn request(
         &self,
         messages: &[ModelRequest],
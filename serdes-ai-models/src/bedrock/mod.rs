@@ -43,8 +43,11 @@ use reqwest::Client;
 use std::time::Duration;
 
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::profile::ModelProfile;
+use serdes_ai_core::messages::instructions_as_system_prompt;
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
     RequestUsage, TextPart, ThinkingPart, ToolCallPart, UserContent, UserContentPart,
@@ -217,6 +220,8 @@ impl BedrockModel {
         settings: &ModelSettings,
         params: &ModelRequestParameters,
     ) -> types::ConverseRequest {
+        let folded = instructions_as_system_prompt(messages);
+        let messages = &*folded;
         let api_messages = self.convert_messages(messages);
         let system = self.extract_system_prompt(messages);
         let tool_config = self.build_tool_config(params);
@@ -475,6 +480,7 @@ impl BedrockModel {
             timestamp: serdes_ai_core::identifier::now_utc(),
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -524,13 +530,15 @@ impl Model for BedrockModel {
 
         // Note: In production, you would use AWS SigV4 signing.
         // This is a simplified implementation.
+        let (meter, body) = RequestMeter::start(&body)?;
         let response = self
             .client
             .post(self.endpoint())
             .header("Content-Type", "application/json")
             .timeout(timeout)
             // AWS SigV4 headers would go here
-            .json(&body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -540,12 +548,11 @@ impl Model for BedrockModel {
             return Err(ModelError::http(status, text));
         }
 
-        let converse_response: types::ConverseResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
-
-        self.parse_response(converse_response)
+        let (converse_response, metrics) =
+            meter.read_json::<types::ConverseResponse>(response).await?;
+        let mut response = self.parse_response(converse_response)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -0,0 +1,240 @@
+//! Static catalog of well-known model limits and prices.
+//!
+//! Used to enrich [`ModelWithMetadata`](crate::ModelWithMetadata) with data
+//! that isn't part of a [`ModelProfile`](crate::ModelProfile). Prices are
+//! list prices in USD per million tokens and can go stale; applications that
+//! bill on them should override them.
+
+/// Limits, prices and knowledge cutoff of a model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelInfo {
+    /// Model name prefix this entry applies to.
+    pub name: &'static str,
+    /// Context window in tokens.
+    pub context_window: u64,
+    /// Maximum output tokens.
+    pub max_output_tokens: u64,
+    /// Input price in USD per million tokens.
+    pub input_price: f64,
+    /// Output price in USD per million tokens.
+    pub output_price: f64,
+    /// Training data cutoff (`YYYY-MM`).
+    pub knowledge_cutoff: Option<&'static str>,
+}
+
+const fn info(
+    name: &'static str,
+    context_window: u64,
+    max_output_tokens: u64,
+    input_price: f64,
+    output_price: f64,
+    knowledge_cutoff: Option<&'static str>,
+) -> ModelInfo {
+    ModelInfo {
+        name,
+        context_window,
+        max_output_tokens,
+        input_price,
+        output_price,
+        knowledge_cutoff,
+    }
+}
+
+/// Known models, matched by longest name prefix.
+pub static MODEL_CATALOG: &[ModelInfo] = &[
+    // OpenAI
+    info("gpt-4o", 128_000, 16_384, 2.50, 10.00, Some("2023-10")),
+    info("gpt-4o-mini", 128_000, 16_384, 0.15, 0.60, Some("2023-10")),
+    info("gpt-4-turbo", 128_000, 4_096, 10.00, 30.00, Some("2023-12")),
+    info("gpt-4.1", 1_047_576, 32_768, 2.00, 8.00, Some("2024-06")),
+    info(
+        "gpt-4.1-mini",
+        1_047_576,
+        32_768,
+        0.40,
+        1.60,
+        Some("2024-06"),
+    ),
+    info(
+        "gpt-4.1-nano",
+        1_047_576,
+        32_768,
+        0.10,
+        0.40,
+        Some("2024-06"),
+    ),
+    info("o1", 200_000, 100_000, 15.00, 60.00, Some("2023-10")),
+    info("o1-mini", 128_000, 65_536, 1.10, 4.40, Some("2023-10")),
+    info("o3", 200_000, 100_000, 2.00, 8.00, Some("2024-06")),
+    info("o3-mini", 200_000, 100_000, 1.10, 4.40, Some("2023-10")),
+    info("o4-mini", 200_000, 100_000, 1.10, 4.40, Some("2024-06")),
+    // Anthropic
+    info(
+        "claude-3-opus",
+        200_000,
+        4_096,
+        15.00,
+        75.00,
+        Some("2023-08"),
+    ),
+    info(
+        "claude-3-haiku",
+        200_000,
+        4_096,
+        0.25,
+        1.25,
+        Some("2023-08"),
+    ),
+    info(
+        "claude-3-5-sonnet",
+        200_000,
+        8_192,
+        3.00,
+        15.00,
+        Some("2024-04"),
+    ),
+    info(
+        "claude-3-5-haiku",
+        200_000,
+        8_192,
+        0.80,
+        4.00,
+        Some("2024-07"),
+    ),
+    info(
+        "claude-3-7-sonnet",
+        200_000,
+        64_000,
+        3.00,
+        15.00,
+        Some("2024-11"),
+    ),
+    info(
+        "claude-sonnet-4",
+        200_000,
+        64_000,
+        3.00,
+        15.00,
+        Some("2025-03"),
+    ),
+    info(
+        "claude-opus-4",
+        200_000,
+        32_000,
+        15.00,
+        75.00,
+        Some("2025-03"),
+    ),
+    // Google
+    info(
+        "gemini-1.5-pro",
+        2_097_152,
+        8_192,
+        1.25,
+        5.00,
+        Some("2024-05"),
+    ),
+    info(
+        "gemini-1.5-flash",
+        1_048_576,
+        8_192,
+        0.075,
+        0.30,
+        Some("2024-05"),
+    ),
+    info(
+        "gemini-2.0-flash",
+        1_048_576,
+        8_192,
+        0.10,
+        0.40,
+        Some("2024-08"),
+    ),
+    info(
+        "gemini-2.5-pro",
+        1_048_576,
+        65_536,
+        1.25,
+        10.00,
+        Some("2025-01"),
+    ),
+    info(
+        "gemini-2.5-flash",
+        1_048_576,
+        65_536,
+        0.30,
+        2.50,
+        Some("2025-01"),
+    ),
+    // Mistral
+    info("mistral-large", 128_000, 8_192, 2.00, 6.00, None),
+    info("mistral-small", 32_000, 8_192, 0.20, 0.60, None),
+    info("codestral", 256_000, 8_192, 0.30, 0.90, None),
+    // Groq
+    info(
+        "llama-3.1-8b-instant",
+        131_072,
+        8_192,
+        0.05,
+        0.08,
+        Some("2023-12"),
+    ),
+    info(
+        "llama-3.1-70b-versatile",
+        131_072,
+        8_192,
+        0.59,
+        0.79,
+        Some("2023-12"),
+    ),
+];
+
+/// Look up a model by name.
+///
+/// Provider prefixes like `openai/` or Bedrock's `anthropic.` are ignored, and the
+/// longest matching name prefix wins, so dated snapshots such as
+/// `claude-3-5-sonnet-20241022` resolve to their family.
+pub fn lookup_model_info(model_name: &str) -> Option<&'static ModelInfo> {
+    let name = model_name
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_name)
+        .to_ascii_lowercase();
+    let name = name
+        .rsplit_once("anthropic.")
+        .map_or(name.as_str(), |(_, rest)| rest);
+
+    MODEL_CATALOG
+        .iter()
+        .filter(|info| name.starts_with(info.name))
+        .max_by_key(|info| info.name.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_longest_prefix() {
+        assert_eq!(lookup_model_info("gpt-4o").unwrap().name, "gpt-4o");
+        assert_eq!(
+            lookup_model_info("gpt-4o-mini-2024-07-18").unwrap().name,
+            "gpt-4o-mini"
+        );
+        assert_eq!(lookup_model_info("o3-mini").unwrap().name, "o3-mini");
+    }
+
+    #[test]
+    fn test_lookup_strips_provider_prefix() {
+        let info = lookup_model_info("anthropic/claude-3-5-sonnet-20241022").unwrap();
+        assert_eq!(info.name, "claude-3-5-sonnet");
+        assert_eq!(info.context_window, 200_000);
+        let info = lookup_model_info("us.anthropic.claude-3-opus-20240229-v1:0").unwrap();
+        assert_eq!(info.name, "claude-3-opus");
+    }
+
+    #[test]
+    fn test_lookup_unknown() {
+        assert!(lookup_model_info("my-finetune").is_none());
+    }
+}
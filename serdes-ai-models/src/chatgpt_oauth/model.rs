@@ -2,13 +2,16 @@
 
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::{openai_gpt4o_profile, ModelProfile};
+use crate::stream_adapter::PartTracker;
 use async_trait::async_trait;
 use base64::Engine;
 use reqwest::Client;
 use serdes_ai_core::messages::{
-    ImageContent, PartStartEvent, TextPart, ToolCallArgs, ToolCallPart, UserContent,
+    instructions_as_system_prompt, ImageContent, TextPart, ToolCallArgs, ToolCallPart, UserContent,
     UserContentPart, UserPromptPart,
 };
 use serdes_ai_core::{
@@ -152,6 +155,8 @@ impl ChatGptOAuthModel {
         params: &ModelRequestParameters,
         _stream: bool, // Unused: Codex API always requires stream=true
     ) -> CodexRequest {
+        let folded = instructions_as_system_prompt(messages);
+        let messages = &*folded;
         // Collect custom system prompts to prepend to first user message
         let mut custom_system_prompts: Vec<String> = Vec::new();
         let mut input_items: Vec<InputItem> = Vec::new();
@@ -406,6 +411,7 @@ impl ChatGptOAuthModel {
             usage,
             vendor_id: Some(response.id),
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         }
     }
@@ -414,12 +420,14 @@ impl ChatGptOAuthModel {
     async fn parse_sse_response(
         &self,
         response: reqwest::Response,
+        meter: RequestMeter,
     ) -> Result<ModelResponse, ModelError> {
         let mut collected_text = String::new();
         let mut tool_calls: Vec<(String, String, String)> = Vec::new(); // (name, arguments, call_id)
         let mut final_response: Option<serde_json::Value> = None;
 
         let body = response.text().await?;
+        let metrics = meter.finish(body.len());
 
         // Parse SSE format: lines starting with "data: "
         for line in body.lines() {
@@ -583,7 +591,7 @@ impl ChatGptOAuthModel {
             (None, None, None)
         };
 
-        Ok(ModelResponse {
+        let mut response = ModelResponse {
             parts,
             model_name,
             timestamp: chrono::Utc::now(),
@@ -591,8 +599,11 @@ impl ChatGptOAuthModel {
             usage,
             vendor_id,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
-        })
+        };
+        metrics.attach(&mut response);
+        Ok(response)
     }
 }
 
@@ -629,9 +640,11 @@ impl Model for ChatGptOAuthModel {
             request_builder = request_builder.header("ChatGPT-Account-Id", account_id);
         }
 
+        let (meter, body) = RequestMeter::start(&request)?;
         let response = request_builder
             .timeout(Duration::from_secs(300)) // Longer timeout for streaming
-            .json(&request)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -646,7 +659,7 @@ impl Model for ChatGptOAuthModel {
         }
 
         // Parse SSE stream and collect response
-        self.parse_sse_response(response).await
+        self.parse_sse_response(response, meter).await
     }
 
     async fn request_stream(
@@ -659,20 +672,8 @@ impl Model for ChatGptOAuthModel {
         // TODO: Implement proper SSE streaming
         let response = self.request(messages, settings, params).await?;
 
-        use serdes_ai_core::messages::ModelResponseStreamEvent;
-
-        let events: Vec<Result<ModelResponseStreamEvent, ModelError>> = response
-            .parts
-            .into_iter()
-            .enumerate()
-            .map(|(idx, part)| {
-                Ok(ModelResponseStreamEvent::PartStart(PartStartEvent::new(
-                    idx, part,
-                )))
-            })
-            .collect();
-
-        Ok(Box::pin(futures::stream::iter(events)))
+        let events = PartTracker::new().replay(response.parts);
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
     }
 
     fn profile(&self) -> &ModelProfile {
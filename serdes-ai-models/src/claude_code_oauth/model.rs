@@ -2,14 +2,16 @@
 
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::{anthropic_claude_profile, ModelProfile};
 use async_trait::async_trait;
 use base64::Engine;
 use reqwest::Client;
 use serdes_ai_core::messages::{
-    DocumentContent, ImageContent, TextPart, ThinkingPart, ToolCallArgs, ToolCallPart, UserContent,
-    UserContentPart, UserPromptPart,
+    instructions_as_system_prompt, DocumentContent, ImageContent, TextPart, ThinkingPart,
+    ToolCallArgs, ToolCallPart, UserContent, UserContentPart, UserPromptPart,
 };
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
@@ -111,6 +113,8 @@ impl ClaudeCodeOAuthModel {
     /// 1. The actual system prompt is always the hardcoded CLAUDE_CODE_INSTRUCTIONS
     /// 2. Any user-provided system prompts are prepended to the first user message
     fn convert_messages(&self, requests: &[ModelRequest]) -> (Option<String>, Vec<ClaudeMessage>) {
+        let folded = instructions_as_system_prompt(requests);
+        let requests = &*folded;
         // Collect all system prompts to prepend to first user message
         let mut collected_system_prompts: Vec<String> = Vec::new();
         let mut messages = Vec::new();
@@ -397,6 +401,7 @@ impl ClaudeCodeOAuthModel {
             usage,
             vendor_id: Some(response.id),
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         }
     }
@@ -515,11 +520,13 @@ impl Model for ClaudeCodeOAuthModel {
         );
 
         debug!("ClaudeCodeOAuth: sending HTTP request...");
+        let (meter, body) = RequestMeter::start(&request_body)?;
         let response = self
             .client
             .post(&url)
             .headers(headers)
-            .json(&request_body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -542,12 +549,14 @@ impl Model for ClaudeCodeOAuthModel {
             });
         }
 
-        let claude_response: ClaudeResponse = response.json().await?;
+        let (claude_response, metrics) = meter.read_json::<ClaudeResponse>(response).await?;
         info!(
             content_parts = claude_response.content.len(),
             "ClaudeCodeOAuth: parsed response successfully"
         );
-        Ok(self.convert_response(claude_response))
+        let mut response = self.convert_response(claude_response);
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -605,6 +614,7 @@ impl Model for ClaudeCodeOAuthModel {
             .post(&url)
             .headers(headers)
             .json(&request_body)
+            .trace_headers()
             .send()
             .await?;
 
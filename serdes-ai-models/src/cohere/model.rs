@@ -5,12 +5,14 @@
 
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::profile::ModelProfile;
+use crate::stream_adapter::{AdapterStream, PartTracker, StreamAdapter};
 use async_trait::async_trait;
-use bytes::Bytes;
-use futures::Stream;
 use reqwest::Client;
+use serdes_ai_core::messages::instructions_as_system_prompt;
 use serdes_ai_core::{
     messages::{
         ModelResponseStreamEvent, TextPart, ToolCallArgs, ToolCallPart, UserContent,
@@ -20,11 +22,7 @@ use serdes_ai_core::{
     RequestUsage,
 };
 use serdes_ai_tools::ToolDefinition;
-use std::{
-    pin::Pin,
-    task::{Context, Poll},
-    time::Duration,
-};
+use std::time::Duration;
 
 const COHERE_BASE_URL: &str = "https://api.cohere.ai/v2";
 
@@ -104,6 +102,8 @@ impl CohereModel {
         &self,
         requests: &[ModelRequest],
     ) -> (String, Option<Vec<ChatMessage>>, Option<String>) {
+        let folded = instructions_as_system_prompt(requests);
+        let requests = &*folded;
         let mut history = Vec::new();
         let mut system_prompt = None;
         let mut current_message = String::new();
@@ -274,6 +274,7 @@ impl CohereModel {
             usage,
             vendor_id: resp.generation_id,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".into(),
         })
     }
@@ -295,15 +296,21 @@ impl CohereModel {
     async fn send_request(
         &self,
         body: &ChatRequest,
-        timeout: Duration,
-    ) -> Result<reqwest::Response, ModelError> {
+        settings: &ModelSettings,
+    ) -> Result<(reqwest::Response, RequestMeter), ModelError> {
+        let (meter, body) = RequestMeter::start(body)?;
+        let timeout = settings.timeout.unwrap_or(self.default_timeout);
         let response = self
             .client
             .post(format!("{}/chat", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header(
+                "Authorization",
+                format!("Bearer {}", settings.api_key_or(&self.api_key)),
+            )
             .header("Content-Type", "application/json")
             .timeout(timeout)
-            .json(body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -312,7 +319,7 @@ impl CohereModel {
             let body = response.text().await.unwrap_or_default();
             return Err(self.handle_error(status, &body));
         }
-        Ok(response)
+        Ok((response, meter))
     }
 }
 
@@ -335,14 +342,11 @@ impl Model for CohereModel {
         params: &ModelRequestParameters,
     ) -> Result<ModelResponse, ModelError> {
         let body = self.build_request(messages, settings, params, false);
-        let response = self
-            .send_request(&body, settings.timeout.unwrap_or(self.default_timeout))
-            .await?;
-        let resp: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
-        self.parse_response(resp)
+        let (response, meter) = self.send_request(&body, settings).await?;
+        let (resp, metrics) = meter.read_json::<ChatResponse>(response).await?;
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -352,93 +356,44 @@ impl Model for CohereModel {
         params: &ModelRequestParameters,
     ) -> Result<StreamedResponse, ModelError> {
         let body = self.build_request(messages, settings, params, true);
-        let response = self
-            .send_request(&body, settings.timeout.unwrap_or(self.default_timeout))
-            .await?;
+        let (response, _) = self.send_request(&body, settings).await?;
         Ok(Box::pin(CohereStreamParser::new(response.bytes_stream())))
     }
 }
 
-/// Stream parser for Cohere SSE responses.
-pub struct CohereStreamParser<S> {
-    inner: S,
-    buffer: String,
-    started: bool,
-}
+/// Stream parser for Cohere responses.
+pub type CohereStreamParser<S> = AdapterStream<S, CohereStreamAdapter>;
 
-impl<S> CohereStreamParser<S> {
-    /// Create a new Cohere stream parser.
-    pub fn new(stream: S) -> Self {
-        Self {
-            inner: stream,
-            buffer: String::new(),
-            started: false,
-        }
-    }
+/// Maps Cohere stream events to stream events.
+#[derive(Debug, Default)]
+pub struct CohereStreamAdapter {
+    parts: PartTracker,
+    done: bool,
 }
 
-impl<S> Stream for CohereStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
-{
-    type Item = Result<ModelResponseStreamEvent, ModelError>;
-
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        loop {
-            // Try to parse a complete event from buffer
-            if let Some(event) = self.try_parse_event() {
-                return Poll::Ready(Some(Ok(event)));
-            }
-
-            // Need more data
-            match Pin::new(&mut self.inner).poll_next(cx) {
-                Poll::Ready(Some(Ok(bytes))) => {
-                    self.buffer.push_str(&String::from_utf8_lossy(&bytes));
-                }
-                Poll::Ready(Some(Err(e))) => {
-                    return Poll::Ready(Some(Err(ModelError::Network(e.to_string()))));
-                }
-                Poll::Ready(None) => return Poll::Ready(None),
-                Poll::Pending => return Poll::Pending,
+impl StreamAdapter for CohereStreamAdapter {
+    type Chunk = StreamEvent;
+
+    fn map_chunk(
+        &mut self,
+        event: StreamEvent,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let events = match event.event_type.as_str() {
+            "text-generation" => match event.text {
+                Some(text) if !text.is_empty() => vec![self.parts.text(&text)],
+                _ => Vec::new(),
+            },
+            "stream-end" => {
+                self.done = true;
+                self.parts.close_all()
             }
-        }
+            _ => Vec::new(),
+        };
+        Ok(events)
     }
-}
-
-impl<S> CohereStreamParser<S> {
-    fn try_parse_event(&mut self) -> Option<ModelResponseStreamEvent> {
-        // Cohere uses newline-delimited JSON
-        while let Some(pos) = self.buffer.find('\n') {
-            let line = self.buffer[..pos].trim().to_string();
-            self.buffer = self.buffer[pos + 1..].to_string();
-
-            if line.is_empty() {
-                continue;
-            }
 
-            if let Ok(event) = serde_json::from_str::<StreamEvent>(&line) {
-                match event.event_type.as_str() {
-                    "text-generation" => {
-                        if let Some(text) = event.text {
-                            // Start part if we haven't yet
-                            if !self.started {
-                                self.started = true;
-                                return Some(ModelResponseStreamEvent::part_start(
-                                    0,
-                                    ModelResponsePart::Text(TextPart::new("")),
-                                ));
-                            }
-                            return Some(ModelResponseStreamEvent::text_delta(0, &text));
-                        }
-                    }
-                    "stream-end" => {
-                        return Some(ModelResponseStreamEvent::part_end(0));
-                    }
-                    _ => {}
-                }
-            }
-        }
-        None
+    fn is_done(&self) -> bool {
+        self.done
     }
 }
 
@@ -468,4 +423,32 @@ mod tests {
         assert!(profile.supports_tools);
         assert_eq!(profile.context_window, Some(128_000));
     }
+
+    #[tokio::test]
+    async fn test_stream_parser() {
+        use futures::{stream, StreamExt};
+
+        let body = concat!(
+            "{\"event_type\":\"stream-start\"}\n",
+            "{\"event_type\":\"text-generation\",\"text\":\"Hello\"}\n",
+            "{\"event_type\":\"text-generation\",\"text\":\" world\"}\n",
+            "{\"event_type\":\"stream-end\",\"finish_reason\":\"COMPLETE\"}\n",
+        );
+        let inner = stream::iter(vec![Ok::<_, reqwest::Error>(bytes::Bytes::from(body))]);
+        let events: Vec<_> = CohereStreamParser::new(inner)
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 3);
+        assert!(events[0].is_start() && events[1].is_delta() && events[2].is_end());
+        assert!(events[0].part_id().is_some());
+        assert!(events.iter().all(|e| e.part_id() == events[0].part_id()));
+        match &events[0] {
+            ModelResponseStreamEvent::PartStart(start) => {
+                assert_eq!(start.part, ModelResponsePart::text("Hello"));
+            }
+            other => panic!("Expected PartStart, got {:?}", other),
+        }
+    }
 }
@@ -1,5 +1,6 @@
 //! Model-related error types.
 
+use serdes_ai_core::RequestUsage;
 use std::collections::HashMap;
 use std::time::Duration;
 use thiserror::Error;
@@ -90,13 +91,22 @@ pub enum ModelError {
     /// Other error.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
+
+    /// Error from a request that still consumed tokens.
+    #[error("{source}")]
+    WithUsage {
+        /// The underlying error.
+        source: Box<ModelError>,
+        /// Usage billed for the failed request.
+        usage: RequestUsage,
+    },
 }
 
 impl ModelError {
     /// Check if this error is retryable.
     #[must_use]
     pub fn is_retryable(&self) -> bool {
-        match self {
+        match self.inner() {
             ModelError::Timeout(_) => true,
             ModelError::RateLimited { .. } => true,
             ModelError::Connection(_) => true,
@@ -105,15 +115,84 @@ impl ModelError {
         }
     }
 
+    /// Get a stable snake_case name of the error variant, for logs and
+    /// metrics.
+    ///
+    /// Attached usage is ignored, so a rate limit reports `"rate_limited"`
+    /// either way.
+    #[must_use]
+    pub fn class(&self) -> &'static str {
+        match self {
+            ModelError::Http { .. } => "http",
+            ModelError::Api { .. } => "api",
+            ModelError::Timeout(_) => "timeout",
+            ModelError::RateLimited { .. } => "rate_limited",
+            ModelError::Authentication(_) => "authentication",
+            ModelError::InvalidResponse(_) => "invalid_response",
+            ModelError::NotFound(_) => "not_found",
+            ModelError::NotSupported(_) => "not_supported",
+            ModelError::Serialization(_) => "serialization",
+            ModelError::Cancelled => "cancelled",
+            ModelError::Connection(_) => "connection",
+            ModelError::ContentFiltered(_) => "content_filtered",
+            ModelError::ContextLengthExceeded { .. } => "context_length_exceeded",
+            ModelError::Configuration(_) => "configuration",
+            ModelError::Network(_) => "network",
+            ModelError::Other(_) => "other",
+            ModelError::WithUsage { source, .. } => source.class(),
+        }
+    }
+
     /// Get the retry-after duration if applicable.
     #[must_use]
     pub fn retry_after(&self) -> Option<Duration> {
-        match self {
+        match self.inner() {
             ModelError::RateLimited { retry_after } => *retry_after,
             _ => None,
         }
     }
 
+    /// Attach usage billed for the failed request.
+    ///
+    /// Usage already attached to the error is merged.
+    #[must_use]
+    pub fn with_usage(self, usage: RequestUsage) -> Self {
+        match self {
+            ModelError::WithUsage {
+                source,
+                usage: mut existing,
+            } => {
+                existing.merge(&usage);
+                ModelError::WithUsage {
+                    source,
+                    usage: existing,
+                }
+            }
+            error => ModelError::WithUsage {
+                source: Box::new(error),
+                usage,
+            },
+        }
+    }
+
+    /// Get usage billed for the failed request, if known.
+    #[must_use]
+    pub fn usage(&self) -> Option<&RequestUsage> {
+        match self {
+            ModelError::WithUsage { usage, .. } => Some(usage),
+            _ => None,
+        }
+    }
+
+    /// Get the underlying error, without attached usage.
+    #[must_use]
+    pub fn inner(&self) -> &ModelError {
+        match self {
+            ModelError::WithUsage { source, .. } => source.inner(),
+            error => error,
+        }
+    }
+
     /// Create an API error.
     pub fn api(message: impl Into<String>) -> Self {
         Self::Api {
@@ -236,6 +315,18 @@ mod tests {
         assert!(!ModelError::api("Error").is_retryable());
     }
 
+    #[test]
+    fn test_class() {
+        assert_eq!(ModelError::http(503, "Unavailable").class(), "http");
+        assert_eq!(ModelError::rate_limited(None).class(), "rate_limited");
+        assert_eq!(
+            ModelError::Timeout(Duration::from_secs(30))
+                .with_usage(RequestUsage::new())
+                .class(),
+            "timeout"
+        );
+    }
+
     #[test]
     fn test_retry_after() {
         let err = ModelError::rate_limited(Some(Duration::from_secs(60)));
@@ -248,6 +339,19 @@ mod tests {
         assert_eq!(err.retry_after(), None);
     }
 
+    #[test]
+    fn test_with_usage() {
+        let err = ModelError::rate_limited(Some(Duration::from_secs(5)))
+            .with_usage(RequestUsage::with_tokens(10, 0))
+            .with_usage(RequestUsage::with_tokens(5, 2));
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(5)));
+        assert_eq!(err.usage().unwrap().request_tokens, Some(15));
+        assert!(matches!(err.inner(), ModelError::RateLimited { .. }));
+        assert_eq!(err.to_string(), err.inner().to_string());
+        assert!(ModelError::api("x").usage().is_none());
+    }
+
     #[test]
     fn test_error_display() {
         let err = ModelError::api_with_code("Something went wrong", "INVALID_REQUEST");
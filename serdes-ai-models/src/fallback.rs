@@ -18,16 +18,49 @@
 //! // If primary fails with rate limit, automatically tries backup
 //! let response = fallback.request(&messages, &settings, &params).await?;
 //! ```
+//!
+//! Policies can be combined and extended with predicates over the full
+//! [`ModelError`], and a model can be retried before failing over:
+//!
+//! ```rust,ignore
+//! let fallback = FallbackModel::new(models)
+//!     // Retry the same model on server errors...
+//!     .with_same_model_retries(RetryOn::Status(vec![500, 502, 503]), 2)
+//!     // ...and move on when rate limited or out of quota.
+//!     .with_retry_on(
+//!         RetryOn::RateLimits.or(RetryOn::ErrorCodes(vec!["insufficient_quota".into()])),
+//!     );
+//! ```
+//!
+//! When a request falls back, usage billed for failed attempts is added to
+//! the final response's usage, and every attempt is listed under
+//! `fallback_attempts` in its `vendor_details`.
+//!
+//! Requests with a [`ModelSettings::session_affinity`] key start at the
+//! model that last served that key, so a conversation that failed over stays
+//! on the same backend and keeps hitting its prompt cache.
+//!
+//! Every retry and failover is logged as a `WARN` event with the `model`,
+//! `error_class` (see [`ModelError::class`]) and, for same-model retries,
+//! `wait_ms` fields, so elevated fallback rates can be alerted on.
 
 use crate::error::ModelError;
+use crate::http_metrics::{insert_vendor_detail, HttpMetrics};
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::profile::ModelProfile;
 use async_trait::async_trait;
-use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
-use tracing::{debug, warn};
+use serde::Serialize;
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings, RequestUsage};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// User predicate deciding whether an error should be retried.
+pub type RetryPredicate = Arc<dyn Fn(&ModelError) -> bool + Send + Sync>;
 
 /// Policy for determining when to retry with the next model.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Clone, Default)]
 pub enum RetryOn {
     /// Retry on any error.
     #[default]
@@ -36,24 +69,186 @@ pub enum RetryOn {
     RateLimits,
     /// Only retry on transient errors (timeout, connection, server errors).
     Transient,
+    /// Never retry.
+    Never,
+    /// Retry on the given HTTP status codes; rate limits count as `429`.
+    Status(Vec<u16>),
+    /// Retry on the given provider error codes (see [`ModelError::Api`]).
+    ErrorCodes(Vec<String>),
+    /// Retry if any of the policies matches.
+    AnyOf(Vec<RetryOn>),
+    /// Retry if the predicate returns `true`.
+    ///
+    /// The predicate sees the full error, including attached usage.
+    Custom(RetryPredicate),
 }
 
 impl RetryOn {
+    /// Create a policy from a predicate.
+    pub fn when<F>(predicate: F) -> Self
+    where
+        F: Fn(&ModelError) -> bool + Send + Sync + 'static,
+    {
+        RetryOn::Custom(Arc::new(predicate))
+    }
+
+    /// Retry if this policy or `other` matches.
+    #[must_use]
+    pub fn or(self, other: RetryOn) -> Self {
+        match self {
+            RetryOn::AnyOf(mut policies) => {
+                policies.push(other);
+                RetryOn::AnyOf(policies)
+            }
+            policy => RetryOn::AnyOf(vec![policy, other]),
+        }
+    }
+
     /// Check if the given error should trigger a retry.
     #[must_use]
     pub fn should_retry(&self, error: &ModelError) -> bool {
+        let inner = error.inner();
         match self {
             RetryOn::AnyError => true,
-            RetryOn::RateLimits => matches!(error, ModelError::RateLimited { .. }),
-            RetryOn::Transient => match error {
+            RetryOn::RateLimits => matches!(inner, ModelError::RateLimited { .. }),
+            RetryOn::Transient => match inner {
                 ModelError::Timeout(_) | ModelError::Connection(_) | ModelError::Network(_) => true,
                 ModelError::Http { status, .. } => *status >= 500,
                 _ => false,
             },
+            RetryOn::Never => false,
+            RetryOn::Status(statuses) => {
+                let status = match inner {
+                    ModelError::Http { status, .. } => Some(*status),
+                    ModelError::RateLimited { .. } => Some(429),
+                    _ => None,
+                };
+                status.is_some_and(|s| statuses.contains(&s))
+            }
+            RetryOn::ErrorCodes(codes) => match inner {
+                ModelError::Api {
+                    code: Some(code), ..
+                } => codes.iter().any(|c| c == code),
+                _ => false,
+            },
+            RetryOn::AnyOf(policies) => policies.iter().any(|p| p.should_retry(error)),
+            RetryOn::Custom(predicate) => predicate(error),
+        }
+    }
+}
+
+impl std::fmt::Debug for RetryOn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryOn::AnyError => f.write_str("AnyError"),
+            RetryOn::RateLimits => f.write_str("RateLimits"),
+            RetryOn::Transient => f.write_str("Transient"),
+            RetryOn::Never => f.write_str("Never"),
+            RetryOn::Status(statuses) => f.debug_tuple("Status").field(statuses).finish(),
+            RetryOn::ErrorCodes(codes) => f.debug_tuple("ErrorCodes").field(codes).finish(),
+            RetryOn::AnyOf(policies) => f.debug_tuple("AnyOf").field(policies).finish(),
+            RetryOn::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Custom policies are equal only if they share the same predicate.
+impl PartialEq for RetryOn {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RetryOn::AnyError, RetryOn::AnyError)
+            | (RetryOn::RateLimits, RetryOn::RateLimits)
+            | (RetryOn::Transient, RetryOn::Transient)
+            | (RetryOn::Never, RetryOn::Never) => true,
+            (RetryOn::Status(a), RetryOn::Status(b)) => a == b,
+            (RetryOn::ErrorCodes(a), RetryOn::ErrorCodes(b)) => a == b,
+            (RetryOn::AnyOf(a), RetryOn::AnyOf(b)) => a == b,
+            (RetryOn::Custom(a), RetryOn::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
         }
     }
 }
 
+impl Eq for RetryOn {}
+
+/// A single attempt in a fallback chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct FallbackAttempt {
+    /// Identifier of the model tried.
+    pub model: String,
+    /// Usage billed for the attempt, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RequestUsage>,
+    /// Error message if the attempt failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FallbackAttempt {
+    fn failed(model: String, error: &ModelError) -> Self {
+        Self {
+            model,
+            usage: error.usage().cloned(),
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Sum the usage of the given attempts, if any was billed.
+/// Wait before a retry, unless the current clock is simulated.
+async fn wait(delay: Duration) {
+    if !serdes_ai_core::clock::clock().skip_wait(delay) {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn total_usage(attempts: &[FallbackAttempt]) -> Option<RequestUsage> {
+    attempts.iter().filter_map(|a| a.usage.as_ref()).fold(
+        None,
+        |total: Option<RequestUsage>, usage| {
+            let mut total = total.unwrap_or_default();
+            total.merge(usage);
+            Some(total)
+        },
+    )
+}
+
+/// Attach the usage of earlier failed attempts to the final error.
+fn error_with_attempts(error: ModelError, previous: &[FallbackAttempt]) -> ModelError {
+    match total_usage(previous) {
+        Some(usage) => error.with_usage(usage),
+        None => error,
+    }
+}
+
+/// Record failed attempts on a successful response.
+fn attribute_attempts(
+    response: &mut ModelResponse,
+    model: String,
+    mut attempts: Vec<FallbackAttempt>,
+) {
+    if let Some(failed) = total_usage(&attempts) {
+        let mut usage = response.usage.clone().unwrap_or_default();
+        usage.merge(&failed);
+        attempts.push(FallbackAttempt {
+            model,
+            usage: response.usage.replace(usage),
+            error: None,
+        });
+    } else {
+        attempts.push(FallbackAttempt {
+            model,
+            usage: response.usage.clone(),
+            error: None,
+        });
+    }
+
+    let failed = attempts.len() as u32 - 1;
+    let attempts = serde_json::to_value(&attempts).unwrap_or_default();
+    insert_vendor_detail(response, "fallback_attempts", attempts);
+    HttpMetrics::add_retries(response, failed);
+}
+
 /// A model that tries multiple models in order until one succeeds.
 ///
 /// This is useful for:
@@ -63,14 +258,24 @@ impl RetryOn {
 pub struct FallbackModel {
     models: Vec<Box<dyn Model>>,
     retry_on: RetryOn,
+    retry_same_on: RetryOn,
+    max_same_model_retries: u32,
+    retry_delay: Duration,
     profile: ModelProfile,
+    /// Index of the model that last served each session affinity key.
+    affinity: Mutex<HashMap<String, usize>>,
 }
 
+/// Sessions remembered before the affinity table is reset.
+const MAX_AFFINITY_SESSIONS: usize = 10_000;
+
 impl std::fmt::Debug for FallbackModel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FallbackModel")
             .field("model_count", &self.models.len())
             .field("retry_on", &self.retry_on)
+            .field("retry_same_on", &self.retry_same_on)
+            .field("max_same_model_retries", &self.max_same_model_retries)
             .finish()
     }
 }
@@ -98,7 +303,11 @@ impl FallbackModel {
         Self {
             models,
             retry_on: RetryOn::default(),
+            retry_same_on: RetryOn::Never,
+            max_same_model_retries: 0,
+            retry_delay: Duration::from_millis(500),
             profile,
+            affinity: Mutex::new(HashMap::new()),
         }
     }
 
@@ -113,6 +322,28 @@ impl FallbackModel {
         self
     }
 
+    /// Retry the same model before failing over.
+    ///
+    /// Errors matching `retry_on` are retried on the model that failed, up
+    /// to `max_retries` times, before the [`with_retry_on`](Self::with_retry_on)
+    /// policy decides whether to move to the next model.
+    #[must_use]
+    pub fn with_same_model_retries(mut self, retry_on: RetryOn, max_retries: u32) -> Self {
+        self.retry_same_on = retry_on;
+        self.max_same_model_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry of the same model; it doubles
+    /// with each retry (default: 500ms).
+    ///
+    /// A `retry_after` hint from the provider takes precedence.
+    #[must_use]
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
     /// Add another model to the fallback chain.
     ///
     /// # Arguments
@@ -145,10 +376,56 @@ impl FallbackModel {
         self.models.is_empty()
     }
 
+    /// Get the index of the model that last served a session affinity key.
+    #[must_use]
+    pub fn affinity(&self, session: &str) -> Option<usize> {
+        self.affinity.lock().ok()?.get(session).copied()
+    }
+
+    /// Order to try the models in: the session's model first, then the
+    /// rest of the chain.
+    fn model_order(&self, settings: &ModelSettings) -> Vec<usize> {
+        let sticky = settings
+            .session_affinity
+            .as_deref()
+            .and_then(|session| self.affinity(session))
+            .filter(|&i| i < self.models.len());
+        sticky
+            .into_iter()
+            .chain((0..self.models.len()).filter(|&i| Some(i) != sticky))
+            .collect()
+    }
+
+    /// Remember the model that served a session.
+    fn record_affinity(&self, settings: &ModelSettings, index: usize) {
+        let Some(session) = &settings.session_affinity else {
+            return;
+        };
+        if let Ok(mut affinity) = self.affinity.lock() {
+            if affinity.len() >= MAX_AFFINITY_SESSIONS && !affinity.contains_key(session) {
+                affinity.clear();
+            }
+            affinity.insert(session.clone(), index);
+        }
+    }
+
     /// Check if we should retry with the next model for the given error.
     fn should_retry(&self, error: &ModelError) -> bool {
         self.retry_on.should_retry(error)
     }
+
+    /// Delay before retrying the same model, if the error should be retried
+    /// after `retries` earlier retries.
+    fn same_model_retry_delay(&self, error: &ModelError, retries: u32) -> Option<Duration> {
+        if retries >= self.max_same_model_retries || !self.retry_same_on.should_retry(error) {
+            return None;
+        }
+        Some(
+            error
+                .retry_after()
+                .unwrap_or_else(|| self.retry_delay * 2u32.saturating_pow(retries)),
+        )
+    }
 }
 
 #[async_trait]
@@ -181,9 +458,12 @@ impl Model for FallbackModel {
         }
 
         let mut last_error: Option<ModelError> = None;
+        let mut attempts: Vec<FallbackAttempt> = Vec::new();
 
-        for (i, model) in self.models.iter().enumerate() {
-            let is_last = i == self.models.len() - 1;
+        let order = self.model_order(settings);
+        for (i, &index) in order.iter().enumerate() {
+            let model = &self.models[index];
+            let is_last = i == order.len() - 1;
 
             debug!(
                 model = %model.identifier(),
@@ -192,40 +472,70 @@ impl Model for FallbackModel {
                 "Trying model in fallback chain"
             );
 
-            match model.request(messages, settings, params).await {
-                Ok(response) => {
-                    if i > 0 {
-                        debug!(
+            let mut retries = 0;
+            let result = loop {
+                match model.request(messages, settings, params).await {
+                    Err(e) => match self.same_model_retry_delay(&e, retries) {
+                        Some(delay) => {
+                            warn!(
+                                model = %model.identifier(),
+                                retry = retries + 1,
+                                max_retries = self.max_same_model_retries,
+                                wait_ms = delay.as_millis() as u64,
+                                error_class = e.class(),
+                                error = %e,
+                                "Model request failed, retrying same model"
+                            );
+                            attempts.push(FallbackAttempt::failed(model.identifier(), &e));
+                            retries += 1;
+                            wait(delay).await;
+                        }
+                        None => break Err(e),
+                    },
+                    result => break result,
+                }
+            };
+
+            match result {
+                Ok(mut response) => {
+                    self.record_affinity(settings, index);
+                    if !attempts.is_empty() {
+                        info!(
                             model = %model.identifier(),
-                            "Fallback model succeeded after {} previous attempts",
-                            i
+                            failed_attempts = attempts.len(),
+                            "Fallback model succeeded after earlier failures"
                         );
+                        attribute_attempts(&mut response, model.identifier(), attempts);
                     }
                     return Ok(response);
                 }
                 Err(e) => {
-                    warn!(
-                        model = %model.identifier(),
-                        error = %e,
-                        "Model request failed"
-                    );
-
-                    if is_last {
-                        // No more models to try
-                        return Err(e);
-                    }
-
-                    if self.should_retry(&e) {
-                        debug!(
-                            error_type = ?std::mem::discriminant(&e),
-                            "Error is retryable, trying next model"
+                    // Fall back unless this is the last model or the error
+                    // is not retryable
+                    if !is_last && self.should_retry(&e) {
+                        warn!(
+                            model = %model.identifier(),
+                            next_model = %self.models[order[i + 1]].identifier(),
+                            position = i + 1,
+                            total = self.models.len(),
+                            error_class = e.class(),
+                            error = %e,
+                            "Model request failed, falling back to next model"
                         );
+                        attempts.push(FallbackAttempt::failed(model.identifier(), &e));
                         last_error = Some(e);
                         continue;
                     }
 
-                    // Non-retryable error, propagate immediately
-                    return Err(e);
+                    warn!(
+                        model = %model.identifier(),
+                        position = i + 1,
+                        total = self.models.len(),
+                        error_class = e.class(),
+                        error = %e,
+                        "Model request failed"
+                    );
+                    return Err(error_with_attempts(e, &attempts));
                 }
             }
         }
@@ -246,9 +556,12 @@ impl Model for FallbackModel {
         }
 
         let mut last_error: Option<ModelError> = None;
+        let mut attempts: Vec<FallbackAttempt> = Vec::new();
 
-        for (i, model) in self.models.iter().enumerate() {
-            let is_last = i == self.models.len() - 1;
+        let order = self.model_order(settings);
+        for (i, &index) in order.iter().enumerate() {
+            let model = &self.models[index];
+            let is_last = i == order.len() - 1;
 
             debug!(
                 model = %model.identifier(),
@@ -257,38 +570,69 @@ impl Model for FallbackModel {
                 "Trying model in fallback chain (streaming)"
             );
 
-            match model.request_stream(messages, settings, params).await {
+            let mut retries = 0;
+            let result = loop {
+                match model.request_stream(messages, settings, params).await {
+                    Err(e) => match self.same_model_retry_delay(&e, retries) {
+                        Some(delay) => {
+                            warn!(
+                                model = %model.identifier(),
+                                retry = retries + 1,
+                                max_retries = self.max_same_model_retries,
+                                wait_ms = delay.as_millis() as u64,
+                                error_class = e.class(),
+                                error = %e,
+                                "Model stream request failed, retrying same model"
+                            );
+                            attempts.push(FallbackAttempt::failed(model.identifier(), &e));
+                            retries += 1;
+                            wait(delay).await;
+                        }
+                        None => break Err(e),
+                    },
+                    result => break result,
+                }
+            };
+
+            match result {
                 Ok(stream) => {
-                    if i > 0 {
-                        debug!(
+                    self.record_affinity(settings, index);
+                    if !attempts.is_empty() {
+                        info!(
                             model = %model.identifier(),
-                            "Fallback model succeeded after {} previous attempts",
-                            i
+                            failed_attempts = attempts.len(),
+                            "Fallback model succeeded after earlier failures"
                         );
                     }
                     return Ok(stream);
                 }
                 Err(e) => {
-                    warn!(
-                        model = %model.identifier(),
-                        error = %e,
-                        "Model stream request failed"
-                    );
-
-                    if is_last {
-                        return Err(e);
-                    }
-
-                    if self.should_retry(&e) {
-                        debug!(
-                            error_type = ?std::mem::discriminant(&e),
-                            "Error is retryable, trying next model"
+                    // Fall back unless this is the last model or the error
+                    // is not retryable
+                    if !is_last && self.should_retry(&e) {
+                        warn!(
+                            model = %model.identifier(),
+                            next_model = %self.models[order[i + 1]].identifier(),
+                            position = i + 1,
+                            total = self.models.len(),
+                            error_class = e.class(),
+                            error = %e,
+                            "Model stream request failed, falling back to next model"
                         );
+                        attempts.push(FallbackAttempt::failed(model.identifier(), &e));
                         last_error = Some(e);
                         continue;
                     }
 
-                    return Err(e);
+                    warn!(
+                        model = %model.identifier(),
+                        position = i + 1,
+                        total = self.models.len(),
+                        error_class = e.class(),
+                        error = %e,
+                        "Model stream request failed"
+                    );
+                    return Err(error_with_attempts(e, &attempts));
                 }
             }
         }
@@ -311,6 +655,7 @@ mod tests {
     struct FailingMockModel {
         name: String,
         error: ModelError,
+        usage: Option<RequestUsage>,
         call_count: Arc<AtomicUsize>,
         profile: ModelProfile,
     }
@@ -320,11 +665,17 @@ mod tests {
             Self {
                 name: name.into(),
                 error,
+                usage: None,
                 call_count: Arc::new(AtomicUsize::new(0)),
                 profile: ModelProfile::default(),
             }
         }
 
+        fn with_usage(mut self, usage: RequestUsage) -> Self {
+            self.usage = Some(usage);
+            self
+        }
+
         #[allow(dead_code)]
         fn call_count(&self) -> usize {
             self.call_count.load(Ordering::SeqCst)
@@ -353,7 +704,7 @@ mod tests {
         ) -> Result<ModelResponse, ModelError> {
             self.call_count.fetch_add(1, Ordering::SeqCst);
             // Return a new instance of the error type
-            match &self.error {
+            let error = match &self.error {
                 ModelError::RateLimited { retry_after } => Err(ModelError::RateLimited {
                     retry_after: *retry_after,
                 }),
@@ -370,6 +721,10 @@ mod tests {
                     headers: headers.clone(),
                 }),
                 _ => Err(ModelError::api("Generic error")),
+            };
+            match &self.usage {
+                Some(usage) => error.map_err(|e| e.with_usage(usage.clone())),
+                None => error,
             }
         }
 
@@ -388,6 +743,7 @@ mod tests {
     struct SucceedingMockModel {
         name: String,
         response_text: String,
+        usage: Option<RequestUsage>,
         call_count: Arc<AtomicUsize>,
         profile: ModelProfile,
     }
@@ -397,11 +753,17 @@ mod tests {
             Self {
                 name: name.into(),
                 response_text: response.into(),
+                usage: None,
                 call_count: Arc::new(AtomicUsize::new(0)),
                 profile: ModelProfile::default(),
             }
         }
 
+        fn with_usage(mut self, usage: RequestUsage) -> Self {
+            self.usage = Some(usage);
+            self
+        }
+
         #[allow(dead_code)]
         fn call_count(&self) -> usize {
             self.call_count.load(Ordering::SeqCst)
@@ -434,9 +796,10 @@ mod tests {
                 model_name: Some(self.name.clone()),
                 timestamp: chrono::Utc::now(),
                 finish_reason: Some(FinishReason::Stop),
-                usage: None,
+                usage: self.usage.clone(),
                 vendor_id: None,
                 vendor_details: None,
+                alternatives: Vec::new(),
                 kind: "response".to_string(),
             })
         }
@@ -576,6 +939,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_fallback_session_affinity() {
+        let model1 = FailingMockModel::new("model1", ModelError::rate_limited(None));
+        let model2 = SucceedingMockModel::new("model2", "response2");
+
+        let call_count1 = model1.call_count.clone();
+        let call_count2 = model2.call_count.clone();
+
+        let fallback = FallbackModel::new(vec![Box::new(model1), Box::new(model2)]);
+
+        let messages = vec![ModelRequest::new()];
+        let settings = ModelSettings::new().session_affinity("conversation-1");
+        let params = ModelRequestParameters::new();
+
+        fallback
+            .request(&messages, &settings, &params)
+            .await
+            .unwrap();
+        assert_eq!(fallback.affinity("conversation-1"), Some(1));
+
+        // The session sticks to the model that served it
+        fallback
+            .request(&messages, &settings, &params)
+            .await
+            .unwrap();
+        assert_eq!(call_count1.load(Ordering::SeqCst), 1);
+        assert_eq!(call_count2.load(Ordering::SeqCst), 2);
+
+        // Other requests still start at the top of the chain
+        let _ = fallback
+            .request(&messages, &ModelSettings::default(), &params)
+            .await;
+        assert_eq!(call_count1.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn test_fallback_all_models_fail() {
         let model1 = FailingMockModel::new("model1", ModelError::rate_limited(None));
@@ -773,6 +1171,166 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_fallback_attributes_failed_attempt_usage() {
+        let model1 = FailingMockModel::new("model1", ModelError::rate_limited(None))
+            .with_usage(RequestUsage::with_tokens(100, 0));
+        let model2 = SucceedingMockModel::new("model2", "response2")
+            .with_usage(RequestUsage::with_tokens(10, 5));
+
+        let fallback = FallbackModel::new(vec![Box::new(model1), Box::new(model2)]);
+        let response = fallback
+            .request(
+                &[ModelRequest::new()],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap();
+
+        let usage = response.usage.clone().unwrap();
+        assert_eq!(usage.request_tokens, Some(110));
+        assert_eq!(usage.response_tokens, Some(5));
+
+        assert_eq!(HttpMetrics::from_response(&response).unwrap().retries, 1);
+        let attempts = &response.vendor_details.unwrap()["fallback_attempts"];
+        assert_eq!(attempts.as_array().unwrap().len(), 2);
+        assert_eq!(attempts[0]["model"], "failing-mock:model1");
+        assert_eq!(attempts[0]["usage"]["request_tokens"], 100);
+        assert!(attempts[0]["error"].is_string());
+        assert_eq!(attempts[1]["model"], "succeeding-mock:model2");
+        assert_eq!(attempts[1]["usage"]["request_tokens"], 10);
+        assert!(attempts[1].get("error").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_no_attribution_without_fallback() {
+        let model1 = SucceedingMockModel::new("model1", "response1");
+        let fallback = FallbackModel::new(vec![Box::new(model1)]);
+        let response = fallback
+            .request(
+                &[ModelRequest::new()],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap();
+        assert!(response.vendor_details.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_all_fail_keeps_usage() {
+        let model1 = FailingMockModel::new("model1", ModelError::rate_limited(None))
+            .with_usage(RequestUsage::with_tokens(100, 0));
+        let model2 = FailingMockModel::new("model2", ModelError::rate_limited(None))
+            .with_usage(RequestUsage::with_tokens(50, 1));
+
+        let fallback = FallbackModel::new(vec![Box::new(model1), Box::new(model2)]);
+        let err = fallback
+            .request(
+                &[ModelRequest::new()],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err.inner(), ModelError::RateLimited { .. }));
+        let usage = err.usage().unwrap();
+        assert_eq!(usage.request_tokens, Some(150));
+        assert_eq!(usage.response_tokens, Some(1));
+    }
+
+    #[test]
+    fn test_retry_on_extended_policies() {
+        let server_errors = RetryOn::Status(vec![500, 503]);
+        assert!(server_errors.should_retry(&ModelError::http(503, "Unavailable")));
+        assert!(!server_errors.should_retry(&ModelError::http(502, "Bad gateway")));
+        assert!(RetryOn::Status(vec![429]).should_retry(&ModelError::rate_limited(None)));
+
+        let quota = RetryOn::ErrorCodes(vec!["insufficient_quota".into()]);
+        assert!(quota.should_retry(&ModelError::api_with_code(
+            "Out of credits",
+            "insufficient_quota"
+        )));
+        assert!(!quota.should_retry(&ModelError::api("Out of credits")));
+
+        // Predicates see the full error, including attached usage
+        let billed = RetryOn::when(|e| e.usage().is_some());
+        let usage = RequestUsage {
+            request_tokens: Some(10),
+            ..Default::default()
+        };
+        assert!(billed.should_retry(&ModelError::api("x").with_usage(usage)));
+        assert!(!billed.should_retry(&ModelError::api("x")));
+
+        let combined = RetryOn::RateLimits.or(quota).or(RetryOn::Never);
+        assert!(combined.should_retry(&ModelError::rate_limited(None)));
+        assert!(combined.should_retry(&ModelError::api_with_code("x", "insufficient_quota")));
+        assert!(!combined.should_retry(&ModelError::http(500, "x")));
+        assert!(matches!(combined, RetryOn::AnyOf(ref p) if p.len() == 3));
+
+        assert_eq!(billed.clone(), billed);
+        assert_ne!(billed, RetryOn::when(|e| e.usage().is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_same_model_retries_and_failover_policies() {
+        let messages = vec![ModelRequest::new()];
+        let settings = ModelSettings::default();
+        let params = ModelRequestParameters::new();
+        let fallback = |first: FailingMockModel, second: SucceedingMockModel| {
+            FallbackModel::new(vec![Box::new(first), Box::new(second)])
+                .with_same_model_retries(RetryOn::Status(vec![500]), 2)
+                .with_retry_delay(Duration::ZERO)
+                .with_retry_on(RetryOn::RateLimits)
+        };
+
+        // Server errors retry the same model, but don't fail over
+        let model1 = FailingMockModel::new("model1", ModelError::http(500, "Server error"));
+        let model2 = SucceedingMockModel::new("model2", "response2");
+        let (calls1, calls2) = (model1.call_count.clone(), model2.call_count.clone());
+        let err = fallback(model1, model2)
+            .request(&messages, &settings, &params)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.inner(), ModelError::Http { status: 500, .. }));
+        assert_eq!(calls1.load(Ordering::SeqCst), 3);
+        assert_eq!(calls2.load(Ordering::SeqCst), 0);
+
+        // Rate limits fail over right away
+        let model1 = FailingMockModel::new("model1", ModelError::rate_limited(None));
+        let model2 = SucceedingMockModel::new("model2", "response2");
+        let (calls1, calls2) = (model1.call_count.clone(), model2.call_count.clone());
+        let response = fallback(model1, model2)
+            .request(&messages, &settings, &params)
+            .await
+            .unwrap();
+        assert_eq!(response.text_content(), "response2");
+        assert_eq!(calls1.load(Ordering::SeqCst), 1);
+        assert_eq!(calls2.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_same_model_retries_are_recorded() {
+        let model1 = FailingMockModel::new("model1", ModelError::http(500, "Server error"));
+        let model2 = SucceedingMockModel::new("model2", "response2");
+        let fallback = FallbackModel::new(vec![Box::new(model1), Box::new(model2)])
+            .with_same_model_retries(RetryOn::Transient, 1)
+            .with_retry_delay(Duration::ZERO);
+
+        let response = fallback
+            .request(
+                &[ModelRequest::new()],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap();
+        let attempts = &response.vendor_details.unwrap()["fallback_attempts"];
+        assert_eq!(attempts.as_array().unwrap().len(), 3);
+    }
+
     #[test]
     fn test_default_retry_on() {
         let retry_on = RetryOn::default();
@@ -53,7 +53,7 @@ pub mod stream;
 pub mod types;
 
 // Re-exports
-pub use model::GoogleModel;
+pub use model::{gemini_function_declarations, GoogleModel};
 pub use types::{
     Blob, Candidate, CodeExecution, Content, FileData, FunctionCall, FunctionCallingConfig,
     FunctionDeclaration, FunctionResponse, GenerateContentRequest, GenerateContentResponse,
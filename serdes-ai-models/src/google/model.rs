@@ -3,14 +3,16 @@
 use super::stream::GoogleStreamParser;
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::ModelProfile;
 use async_trait::async_trait;
 use base64::Engine;
 use reqwest::Client;
 use serdes_ai_core::messages::{
-    DocumentContent, ImageContent, RetryPromptPart, TextPart, ThinkingPart, ToolCallArgs,
-    ToolCallPart, ToolReturnPart, UserContent, UserContentPart,
+    instructions_as_system_prompt, DocumentContent, ImageContent, RetryPromptPart, TextPart,
+    ThinkingPart, ToolCallArgs, ToolCallPart, ToolReturnPart, UserContent, UserContentPart,
 };
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
@@ -180,7 +182,7 @@ impl GoogleModel {
     }
 
     /// Build the API URL.
-    fn build_url(&self, stream: bool) -> String {
+    fn build_url(&self, stream: bool, settings: &ModelSettings) -> String {
         let action = if stream {
             "streamGenerateContent"
         } else {
@@ -200,13 +202,15 @@ impl GoogleModel {
                 self.base_url,
                 self.model_name,
                 action,
-                self.api_key.as_deref().unwrap_or("")
+                settings.api_key_or(self.api_key.as_deref().unwrap_or(""))
             )
         }
     }
 
     /// Convert our messages to Google format.
     fn convert_messages(&self, requests: &[ModelRequest]) -> (Option<Content>, Vec<Content>) {
+        let folded = instructions_as_system_prompt(requests);
+        let requests = &*folded;
         let mut system_parts: Vec<String> = Vec::new();
         let mut contents: Vec<Content> = Vec::new();
 
@@ -427,15 +431,7 @@ impl GoogleModel {
 
         // Function declarations
         if !tools.is_empty() {
-            let declarations: Vec<_> = tools
-                .iter()
-                .map(|t| {
-                    let params = serde_json::to_value(&t.parameters_json_schema)
-                        .unwrap_or(serde_json::json!({}));
-                    FunctionDeclaration::new(&t.name, &t.description, params)
-                })
-                .collect();
-            google_tools.push(GoogleTool::functions(declarations));
+            google_tools.push(GoogleTool::functions(gemini_function_declarations(tools)));
         }
 
         // Code execution
@@ -607,6 +603,7 @@ impl GoogleModel {
             usage,
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -658,7 +655,7 @@ impl Model for GoogleModel {
         params: &ModelRequestParameters,
     ) -> Result<ModelResponse, ModelError> {
         let body = self.build_request(messages, settings, params);
-        let url = self.build_url(false);
+        let url = self.build_url(false, settings);
 
         let timeout = settings.timeout.unwrap_or(self.default_timeout);
 
@@ -671,7 +668,8 @@ impl Model for GoogleModel {
         // For Vertex AI, would need OAuth token
         // For now, API key is in URL for Google AI
 
-        let response = request.json(&body).send().await?;
+        let (meter, body) = RequestMeter::start(&body)?;
+        let response = request.body(body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
@@ -679,12 +677,11 @@ impl Model for GoogleModel {
             return Err(self.handle_error_response(status, &body));
         }
 
-        let resp: GenerateContentResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (resp, metrics) = meter.read_json::<GenerateContentResponse>(response).await?;
 
-        self.parse_response(resp)
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -694,7 +691,7 @@ impl Model for GoogleModel {
         params: &ModelRequestParameters,
     ) -> Result<StreamedResponse, ModelError> {
         let body = self.build_request(messages, settings, params);
-        let url = self.build_url(true);
+        let url = self.build_url(true, settings);
 
         let timeout = settings.timeout.unwrap_or(self.default_timeout);
 
@@ -704,7 +701,7 @@ impl Model for GoogleModel {
             .header("Content-Type", "application/json")
             .timeout(timeout);
 
-        let response = request.json(&body).send().await?;
+        let response = request.json(&body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
@@ -719,6 +716,23 @@ impl Model for GoogleModel {
     }
 }
 
+/// Convert tool definitions to Gemini `functionDeclarations`.
+///
+/// [`GoogleModel`] sends these wrapped in a single
+/// [`GoogleTool::functions`] entry, followed by the built-in code execution
+/// and search tools when enabled. Serialize the result to see the JSON.
+#[must_use]
+pub fn gemini_function_declarations(tools: &[ToolDefinition]) -> Vec<FunctionDeclaration> {
+    tools
+        .iter()
+        .map(|t| {
+            let params =
+                serde_json::to_value(&t.parameters_json_schema).unwrap_or(serde_json::json!({}));
+            FunctionDeclaration::new(&t.name, &t.description, params)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -755,16 +769,19 @@ mod tests {
     #[test]
     fn test_build_url_google_ai() {
         let model = GoogleModel::new("gemini-2.0-flash", "test-key");
-        let url = model.build_url(false);
+        let url = model.build_url(false, &ModelSettings::default());
         assert!(url.contains("generativelanguage.googleapis.com"));
         assert!(url.contains("generateContent"));
         assert!(url.contains("test-key"));
+
+        let url = model.build_url(false, &ModelSettings::new().api_key("tenant-key"));
+        assert!(url.ends_with("?key=tenant-key"));
     }
 
     #[test]
     fn test_build_url_vertex() {
         let model = GoogleModel::vertex("gemini-2.0-flash", "my-project", "us-central1");
-        let url = model.build_url(false);
+        let url = model.build_url(false, &ModelSettings::default());
         assert!(url.contains("us-central1-aiplatform.googleapis.com"));
         assert!(url.contains("my-project"));
     }
@@ -781,6 +798,22 @@ mod tests {
         assert_eq!(converted.len(), 2); // function + code_execution
     }
 
+    #[test]
+    fn test_gemini_function_declarations_json() {
+        use serdes_ai_tools::ObjectJsonSchema;
+
+        let tools = vec![ToolDefinition::new("search", "Search the web")
+            .with_parameters(ObjectJsonSchema::new())];
+
+        let tool = GoogleTool::functions(gemini_function_declarations(&tools));
+        let json = serde_json::to_value(tool).unwrap();
+        assert_eq!(json["functionDeclarations"][0]["name"], "search");
+        assert_eq!(
+            json["functionDeclarations"][0]["parameters"]["type"],
+            "object"
+        );
+    }
+
     #[test]
     fn test_convert_tool_config() {
         let model = GoogleModel::new("gemini-2.0-flash", "key");
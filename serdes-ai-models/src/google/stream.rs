@@ -5,287 +5,80 @@
 
 use super::types::{GenerateContentResponse, Part};
 use crate::error::ModelError;
-use bytes::Bytes;
-use futures::Stream;
-use pin_project_lite::pin_project;
-use serdes_ai_core::messages::{
-    ModelResponseStreamEvent, PartDeltaEvent, PartEndEvent, PartStartEvent, TextPart, ThinkingPart,
-    ToolCallPart,
-};
+use crate::stream_adapter::{AdapterStream, PartKey, PartTracker, StreamAdapter};
+use serdes_ai_core::messages::{ModelResponseStreamEvent, ToolCallPart};
 use serdes_ai_core::ModelResponsePart;
-use std::collections::HashMap;
-use std::pin::Pin;
-use std::task::{Context, Poll};
 
-pin_project! {
-    /// Google AI stream parser.
-    pub struct GoogleStreamParser<S> {
-        #[pin]
-        inner: S,
-        buffer: String,
-        // Track parts in progress
-        parts: HashMap<usize, PartState>,
-        // Current part index
-        next_part_index: usize,
-        // Finished
-        done: bool,
-    }
-}
-
-/// State for an in-progress part.
-#[derive(Debug, Clone)]
-enum PartState {
-    Text {
-        content: String,
-    },
-    FunctionCall {
-        #[allow(dead_code)]
-        name: String,
-        #[allow(dead_code)]
-        args: String,
-    },
-    Thinking {
-        content: String,
-    },
-}
-
-impl<S> GoogleStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    /// Create a new stream parser.
-    pub fn new(inner: S) -> Self {
-        Self {
-            inner,
-            buffer: String::new(),
-            parts: HashMap::new(),
-            next_part_index: 0,
-            done: false,
-        }
-    }
-}
-
-impl<S> Stream for GoogleStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    type Item = Result<ModelResponseStreamEvent, ModelError>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
-
-        if *this.done {
-            return Poll::Ready(None);
-        }
-
-        loop {
-            // Try to parse complete JSON objects from buffer
-            // Google sends each chunk as a complete JSON object on its own line
-            while let Some(line_end) = this.buffer.find('\n') {
-                let line = this.buffer.drain(..=line_end).collect::<String>();
-                let line = line.trim();
-
-                if line.is_empty() {
-                    continue;
-                }
-
-                // Skip "data: " prefix if present
-                let json_str = line.strip_prefix("data: ").unwrap_or(line);
-
-                // Handle [DONE] marker
-                if json_str == "[DONE]" {
-                    *this.done = true;
-                    return Poll::Ready(None);
-                }
-
-                // Parse the JSON response
-                match serde_json::from_str::<GenerateContentResponse>(json_str) {
-                    Ok(response) => {
-                        if let Some(event) =
-                            process_response(&response, this.parts, this.next_part_index, this.done)
-                        {
-                            return Poll::Ready(Some(event));
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to parse Google stream chunk: {} - {}", e, json_str);
-                    }
-                }
-            }
-
-            // Need more data
-            match this.inner.as_mut().poll_next(cx) {
-                Poll::Ready(Some(Ok(bytes))) => {
-                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                        this.buffer.push_str(text);
-                    }
-                }
-                Poll::Ready(Some(Err(e))) => {
-                    return Poll::Ready(Some(Err(ModelError::Other(e.into()))));
-                }
-                Poll::Ready(None) => {
-                    *this.done = true;
-                    // Process any remaining buffer
-                    if !this.buffer.is_empty() {
-                        let remaining = std::mem::take(this.buffer);
-                        let json_str = remaining.trim();
-                        let json_str = json_str.strip_prefix("data: ").unwrap_or(json_str);
-
-                        if !json_str.is_empty() && json_str != "[DONE]" {
-                            if let Ok(response) =
-                                serde_json::from_str::<GenerateContentResponse>(json_str)
-                            {
-                                if let Some(event) = process_response(
-                                    &response,
-                                    this.parts,
-                                    this.next_part_index,
-                                    this.done,
-                                ) {
-                                    return Poll::Ready(Some(event));
-                                }
-                            }
-                        }
-                    }
-                    return Poll::Ready(None);
-                }
-                Poll::Pending => return Poll::Pending,
-            }
-        }
-    }
+/// Google AI stream parser.
+pub type GoogleStreamParser<S> = AdapterStream<S, GoogleStreamAdapter>;
+
+/// Maps Google `GenerateContentResponse` chunks to stream events.
+#[derive(Debug, Default)]
+pub struct GoogleStreamAdapter {
+    // Track parts in progress
+    parts: PartTracker,
+    // Number of function calls seen so far
+    function_calls: u64,
+    // Finished
+    done: bool,
 }
 
-/// Process a response chunk into stream events.
-fn process_response(
-    response: &GenerateContentResponse,
-    parts: &mut HashMap<usize, PartState>,
-    next_part_index: &mut usize,
-    done: &mut bool,
-) -> Option<Result<ModelResponseStreamEvent, ModelError>> {
-    // Get the first candidate
-    let candidate = response.candidates.first()?;
-    let content = candidate.content.as_ref()?;
+impl StreamAdapter for GoogleStreamAdapter {
+    type Chunk = GenerateContentResponse;
 
-    // Process each part
-    for part in &content.parts {
-        match part {
-            Part::Text { text } => {
-                if text.is_empty() {
-                    continue;
-                }
+    fn map_chunk(
+        &mut self,
+        response: GenerateContentResponse,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let mut events = Vec::new();
 
-                // Check if we have an existing text part
-                let text_part_idx = parts.iter().find_map(|(idx, state)| {
-                    if matches!(state, PartState::Text { .. }) {
-                        Some(*idx)
-                    } else {
-                        None
-                    }
-                });
+        // Get the first candidate
+        let Some(candidate) = response.candidates.first() else {
+            return Ok(events);
+        };
 
-                if let Some(idx) = text_part_idx {
-                    // Emit delta for existing part
-                    if let Some(PartState::Text { content }) = parts.get_mut(&idx) {
-                        let delta = text.clone();
-                        content.push_str(&delta);
-                        return Some(Ok(ModelResponseStreamEvent::PartDelta(
-                            PartDeltaEvent::text(idx, delta),
-                        )));
-                    }
-                } else {
-                    // Start new text part
-                    let idx = *next_part_index;
-                    *next_part_index += 1;
-                    parts.insert(
-                        idx,
-                        PartState::Text {
-                            content: text.clone(),
-                        },
-                    );
-                    return Some(Ok(ModelResponseStreamEvent::PartStart(
-                        PartStartEvent::new(idx, ModelResponsePart::Text(TextPart::new(text))),
-                    )));
+        // Process each part
+        for part in candidate.content.iter().flat_map(|c| &c.parts) {
+            match part {
+                Part::Text { text } if !text.is_empty() => {
+                    events.push(self.parts.text(text));
                 }
-            }
-            Part::FunctionCall { function_call } => {
-                // Start new function call part
-                let idx = *next_part_index;
-                *next_part_index += 1;
-
-                let tool_part = ToolCallPart::new(&function_call.name, function_call.args.clone());
-
-                parts.insert(
-                    idx,
-                    PartState::FunctionCall {
-                        name: function_call.name.clone(),
-                        args: serde_json::to_string(&function_call.args).unwrap_or_default(),
-                    },
-                );
-
-                return Some(Ok(ModelResponseStreamEvent::PartStart(
-                    PartStartEvent::new(idx, ModelResponsePart::ToolCall(tool_part)),
-                )));
-            }
-            Part::Thought { thought } => {
-                if thought.is_empty() {
-                    continue;
+                Part::Thought { thought } if !thought.is_empty() => {
+                    events.push(self.parts.thinking(thought));
                 }
-
-                // Check if we have an existing thinking part
-                let think_part_idx = parts.iter().find_map(|(idx, state)| {
-                    if matches!(state, PartState::Thinking { .. }) {
-                        Some(*idx)
-                    } else {
-                        None
-                    }
-                });
-
-                if let Some(idx) = think_part_idx {
-                    if let Some(PartState::Thinking { content }) = parts.get_mut(&idx) {
-                        let delta = thought.clone();
-                        content.push_str(&delta);
-                        return Some(Ok(ModelResponseStreamEvent::PartDelta(
-                            PartDeltaEvent::thinking(idx, delta),
-                        )));
-                    }
-                } else {
-                    let idx = *next_part_index;
-                    *next_part_index += 1;
-                    parts.insert(
-                        idx,
-                        PartState::Thinking {
-                            content: thought.clone(),
-                        },
+                Part::FunctionCall { function_call } => {
+                    // Function calls arrive complete, each one is a new part
+                    let key = PartKey::ToolCall(self.function_calls);
+                    self.function_calls += 1;
+                    let tool_part =
+                        ToolCallPart::new(&function_call.name, function_call.args.clone());
+                    events.push(
+                        self.parts
+                            .start(key, ModelResponsePart::ToolCall(tool_part)),
                     );
-                    return Some(Ok(ModelResponseStreamEvent::PartStart(
-                        PartStartEvent::new(
-                            idx,
-                            ModelResponsePart::Thinking(ThinkingPart::new(thought)),
-                        ),
-                    )));
                 }
+                _ => {}
             }
-            _ => {}
         }
-    }
-
-    // Check for finish
-    if candidate.finish_reason.is_some() {
-        *done = true;
 
-        // Emit end events for all parts
-        if let Some((idx, _)) = parts.drain().next() {
-            return Some(Ok(ModelResponseStreamEvent::PartEnd(PartEndEvent {
-                index: idx,
-            })));
+        // Check for finish - emit end events for all parts
+        if candidate.finish_reason.is_some() {
+            self.done = true;
+            events.extend(self.parts.close_all());
         }
+
+        Ok(events)
     }
 
-    None
+    fn is_done(&self) -> bool {
+        self.done
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bytes::Bytes;
     use futures::stream;
     use futures::StreamExt;
 
@@ -345,4 +138,25 @@ mod tests {
             events
         );
     }
+
+    #[tokio::test]
+    async fn test_parse_multiple_parts_in_one_chunk() {
+        let chunk = r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Let me search"},{"functionCall":{"name":"search","args":{"q":"rust"}}},{"functionCall":{"name":"lookup","args":{}}}]},"finishReason":"STOP"}]}"#;
+        let stream = stream::iter(vec![Ok(make_chunk(chunk))]);
+        let parser = GoogleStreamParser::new(stream);
+
+        let events: Vec<_> = parser.map(|r| r.unwrap()).collect().await;
+        let starts: Vec<usize> = events
+            .iter()
+            .filter(|e| e.is_start())
+            .map(|e| e.index())
+            .collect();
+        let ends: Vec<usize> = events
+            .iter()
+            .filter(|e| e.is_end())
+            .map(|e| e.index())
+            .collect();
+        assert_eq!(starts, vec![0, 1, 2]);
+        assert_eq!(ends, vec![0, 1, 2]);
+    }
 }
@@ -22,16 +22,52 @@
 //! - `llama-3.2-90b-text-preview` - Llama 3.2 90B
 //! - `mixtral-8x7b-32768` - Mixtral 8x7B, 32K context
 //! - `gemma2-9b-it` - Gemma 2 9B instruction-tuned
+//! - `deepseek-r1-distill-llama-70b` - DeepSeek R1 distill, reasoning model
+//!
+//! ## Reasoning Models
+//!
+//! Reasoning models like `deepseek-r1-distill-llama-70b` return their chain of
+//! thought either inline in `<think>` tags or in a separate field, depending on
+//! the [`GroqReasoningFormat`]. Both are surfaced as thinking parts.
+
+use std::time::Duration;
 
 use async_trait::async_trait;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 
 use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::openai::OpenAIChatModel;
 use crate::profile::ModelProfile;
+use crate::thinking_tags::{split_thinking_tags, ThinkingTagStream};
 use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
 
+/// Groq service tier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroqServiceTier {
+    /// Standard on-demand processing.
+    OnDemand,
+    /// Flex processing: higher rate limits, may fail fast under load.
+    Flex,
+    /// Use on-demand, falling back to flex when rate limited.
+    Auto,
+}
+
+/// How Groq returns reasoning for reasoning models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroqReasoningFormat {
+    /// Reasoning in a separate `reasoning` field.
+    Parsed,
+    /// Reasoning inline in `<think>` tags.
+    Raw,
+    /// Reasoning omitted from the response.
+    Hidden,
+}
+
 /// Groq model client.
 ///
 /// Groq uses an OpenAI-compatible API, so this wraps OpenAIChatModel
@@ -40,6 +76,10 @@ use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
 pub struct GroqModel {
     /// Inner OpenAI-compatible model.
     inner: OpenAIChatModel,
+    /// Service tier.
+    service_tier: Option<GroqServiceTier>,
+    /// Reasoning format.
+    reasoning_format: Option<GroqReasoningFormat>,
 }
 
 impl GroqModel {
@@ -49,7 +89,11 @@ impl GroqModel {
     /// Create a new Groq model with an API key.
     pub fn new(model_name: impl Into<String>, api_key: impl Into<String>) -> Self {
         let inner = OpenAIChatModel::new(model_name, api_key).with_base_url(Self::BASE_URL);
-        Self { inner }
+        Self {
+            inner,
+            service_tier: None,
+            reasoning_format: None,
+        }
     }
 
     /// Create from environment variable `GROQ_API_KEY`.
@@ -66,6 +110,47 @@ impl GroqModel {
         self
     }
 
+    /// Set a custom base URL.
+    #[must_use]
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.inner = self.inner.with_base_url(url);
+        self
+    }
+
+    /// Set the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_timeout(timeout);
+        self
+    }
+
+    /// Set the service tier.
+    #[must_use]
+    pub fn with_service_tier(mut self, tier: GroqServiceTier) -> Self {
+        self.inner = self.inner.with_extra_body_field(
+            "service_tier",
+            serde_json::to_value(tier).unwrap_or_default(),
+        );
+        self.service_tier = Some(tier);
+        self
+    }
+
+    /// Set the reasoning format for reasoning models.
+    #[must_use]
+    pub fn with_reasoning_format(mut self, format: GroqReasoningFormat) -> Self {
+        self.inner = self.inner.with_extra_body_field(
+            "reasoning_format",
+            serde_json::to_value(format).unwrap_or_default(),
+        );
+        self.reasoning_format = Some(format);
+        self
+    }
+
+    /// Create a DeepSeek R1 Distill Llama 70B model.
+    pub fn deepseek_r1_distill(api_key: impl Into<String>) -> Self {
+        Self::new("deepseek-r1-distill-llama-70b", api_key)
+    }
+
     /// Create a Llama 3.1 70B Versatile model.
     pub fn llama_70b(api_key: impl Into<String>) -> Self {
         Self::new("llama-3.1-70b-versatile", api_key)
@@ -95,6 +180,24 @@ impl GroqModel {
     pub fn model_name(&self) -> &str {
         self.inner.name()
     }
+
+    /// Get the service tier.
+    pub fn service_tier(&self) -> Option<GroqServiceTier> {
+        self.service_tier
+    }
+
+    /// Get the reasoning format.
+    pub fn reasoning_format(&self) -> Option<GroqReasoningFormat> {
+        self.reasoning_format
+    }
+
+    /// Whether responses may contain inline `<think>` tags.
+    fn splits_thinking_tags(&self) -> bool {
+        !matches!(
+            self.reasoning_format,
+            Some(GroqReasoningFormat::Parsed | GroqReasoningFormat::Hidden)
+        )
+    }
 }
 
 #[async_trait]
@@ -111,13 +214,22 @@ impl Model for GroqModel {
         self.inner.profile()
     }
 
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
     async fn request(
         &self,
         messages: &[ModelRequest],
         settings: &ModelSettings,
         params: &ModelRequestParameters,
     ) -> Result<ModelResponse, ModelError> {
-        self.inner.request(messages, settings, params).await
+        let mut response = self.inner.request(messages, settings, params).await?;
+        if self.splits_thinking_tags() {
+            let (open, close) = &self.profile().thinking_tags;
+            split_thinking_tags(&mut response, open, close);
+        }
+        Ok(response)
     }
 
     async fn request_stream(
@@ -126,7 +238,15 @@ impl Model for GroqModel {
         settings: &ModelSettings,
         params: &ModelRequestParameters,
     ) -> Result<StreamedResponse, ModelError> {
-        self.inner.request_stream(messages, settings, params).await
+        let stream = self
+            .inner
+            .request_stream(messages, settings, params)
+            .await?;
+        if !self.splits_thinking_tags() {
+            return Ok(stream);
+        }
+        let (open, close) = self.profile().thinking_tags.clone();
+        Ok(Box::pin(ThinkingTagStream::new(stream, open, close)))
     }
 }
 
@@ -152,4 +272,47 @@ mod tests {
         let model = GroqModel::gemma_9b("key");
         assert_eq!(model.name(), "gemma2-9b-it");
     }
+
+    #[test]
+    fn test_groq_base_url_and_timeout() {
+        let model = GroqModel::new("llama-3.1-8b-instant", "key")
+            .with_base_url("http://localhost:8080/v1")
+            .with_timeout(Duration::from_secs(5));
+        assert_eq!(model.inner.base_url(), "http://localhost:8080/v1");
+        assert_eq!(model.inner.timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_groq_settings_in_request_body() {
+        let model = GroqModel::deepseek_r1_distill("key")
+            .with_service_tier(GroqServiceTier::Flex)
+            .with_reasoning_format(GroqReasoningFormat::Parsed);
+        assert_eq!(model.service_tier(), Some(GroqServiceTier::Flex));
+
+        let req = model.inner.build_request(
+            &[ModelRequest::new()],
+            &ModelSettings::default(),
+            &ModelRequestParameters::default(),
+            false,
+        );
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["service_tier"], "flex");
+        assert_eq!(json["reasoning_format"], "parsed");
+        assert!(!model.splits_thinking_tags());
+    }
+
+    #[test]
+    fn test_groq_default_omits_settings() {
+        let model = GroqModel::llama_8b("key");
+        let req = model.inner.build_request(
+            &[ModelRequest::new()],
+            &ModelSettings::default(),
+            &ModelRequestParameters::default(),
+            false,
+        );
+        let json = serde_json::to_value(&req).unwrap();
+        assert!(json.get("service_tier").is_none());
+        assert!(json.get("reasoning_format").is_none());
+        assert!(model.splits_thinking_tags());
+    }
 }
@@ -0,0 +1,316 @@
+//! Capture of raw HTTP exchanges for debugging.
+//!
+//! When a provider rejects a request with an opaque error, the exact JSON that
+//! was sent is usually the quickest way to find out why. Models with capture
+//! enabled keep their latest exchanges in a ring buffer, readable through
+//! [`Model::last_exchange`](crate::Model::last_exchange), and emit each one as
+//! a `debug` tracing event on the `serdes_ai::http` target.
+//!
+//! Capture is enabled per model with `with_http_capture`, or for every model
+//! that supports it by setting [`HTTP_CAPTURE_ENV`] (to `1`, or to the number
+//! of exchanges to keep):
+//!
+//! ```rust,ignore
+//! use serdes_ai_models::{HttpCapture, Model, OpenAIChatModel};
+//!
+//! let model = OpenAIChatModel::new("gpt-4o", api_key).with_http_capture(HttpCapture::new(8));
+//! if let Err(e) = model.request(&messages, &settings, &params).await {
+//!     if let Some(exchange) = model.last_exchange() {
+//!         eprintln!("sent {}\ngot {}", exchange.request_body, exchange.response_body);
+//!     }
+//! }
+//! ```
+//!
+//! Values of credential-like keys in the bodies and query string are redacted
+//! before anything is stored or logged. Only non-streaming requests are
+//! captured.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Environment variable enabling capture for supporting models.
+pub const HTTP_CAPTURE_ENV: &str = "SERDES_AI_HTTP_CAPTURE";
+
+/// Exchanges kept when no capacity is given.
+pub const DEFAULT_CAPTURE_CAPACITY: usize = 16;
+
+/// Replacement for redacted values.
+const REDACTED: &str = "[REDACTED]";
+
+/// Keys whose values are always redacted (compared case-insensitively).
+const SECRET_KEYS: &[&str] = &[
+    "authorization",
+    "api_key",
+    "apikey",
+    "api-key",
+    "x-api-key",
+    "key",
+    "token",
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "client_secret",
+    "secret",
+    "password",
+];
+
+/// One request/response pair sent to a provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpExchange {
+    /// Request URL, with credentials in the query string redacted.
+    pub url: String,
+    /// Request body.
+    pub request_body: JsonValue,
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Response body; a string if it wasn't JSON.
+    pub response_body: JsonValue,
+    /// Time from sending the request to reading the response, in milliseconds.
+    pub latency_ms: u64,
+    /// When the response was read.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl HttpExchange {
+    /// Build an exchange from raw bodies, redacting secrets.
+    pub fn new(url: &str, request_body: &[u8], status: u16, response_body: &[u8]) -> Self {
+        Self {
+            url: redact_url(url),
+            request_body: redacted_body(request_body),
+            status,
+            response_body: redacted_body(response_body),
+            latency_ms: 0,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Set the latency.
+    #[must_use]
+    pub fn with_latency_ms(mut self, latency_ms: u64) -> Self {
+        self.latency_ms = latency_ms;
+        self
+    }
+
+    /// Check if the provider accepted the request.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Ring buffer of the latest HTTP exchanges.
+///
+/// Cheap to clone; clones share the buffer, so one capture can collect the
+/// exchanges of several models.
+#[derive(Clone)]
+pub struct HttpCapture {
+    exchanges: Arc<Mutex<VecDeque<HttpExchange>>>,
+    capacity: usize,
+}
+
+impl HttpCapture {
+    /// Create a capture keeping the latest `capacity` exchanges.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            exchanges: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Create a capture if [`HTTP_CAPTURE_ENV`] is set.
+    ///
+    /// `1`, `true` or `on` keep [`DEFAULT_CAPTURE_CAPACITY`] exchanges, other
+    /// numbers set the capacity; `0`, `false` and `off` disable capture.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(HTTP_CAPTURE_ENV).ok()?;
+        Self::from_env_value(&value)
+    }
+
+    fn from_env_value(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.to_ascii_lowercase().as_str() {
+            "" | "0" | "false" | "off" | "no" => None,
+            "1" => Some(Self::new(DEFAULT_CAPTURE_CAPACITY)),
+            _ => Some(Self::new(value.parse().unwrap_or(DEFAULT_CAPTURE_CAPACITY))),
+        }
+    }
+
+    /// Get the number of exchanges kept.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Record an exchange, dropping the oldest one when full.
+    pub fn record(&self, exchange: HttpExchange) {
+        tracing::debug!(
+            target: "serdes_ai::http",
+            url = %exchange.url,
+            status = exchange.status,
+            latency_ms = exchange.latency_ms,
+            request = %exchange.request_body,
+            response = %exchange.response_body,
+            "HTTP exchange"
+        );
+        let mut exchanges = self.lock();
+        if exchanges.len() == self.capacity {
+            exchanges.pop_front();
+        }
+        exchanges.push_back(exchange);
+    }
+
+    /// Get the most recent exchange.
+    #[must_use]
+    pub fn last(&self) -> Option<HttpExchange> {
+        self.lock().back().cloned()
+    }
+
+    /// Get all kept exchanges, oldest first.
+    #[must_use]
+    pub fn exchanges(&self) -> Vec<HttpExchange> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Get the number of kept exchanges.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if no exchange was kept.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drop all kept exchanges.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<HttpExchange>> {
+        // A panic while holding the lock can't leave the buffer inconsistent.
+        self.exchanges
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for HttpCapture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpCapture")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEYS.contains(&key.as_str())
+        || key.ends_with("_api_key")
+        || key.ends_with("_secret")
+        || key.ends_with("_password")
+}
+
+/// Redact the values of credential-like keys, recursively.
+pub fn redact_json(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) && !value.is_null() {
+                    *value = JsonValue::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// Redact credentials passed in a URL's query string.
+fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_secret_key(key) => format!("{key}={REDACTED}"),
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{base}?{}", query.join("&"))
+}
+
+fn redacted_body(body: &[u8]) -> JsonValue {
+    match serde_json::from_slice(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value
+        }
+        Err(_) => JsonValue::String(String::from_utf8_lossy(body).into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let exchange = HttpExchange::new(
+            "https://example.com/v1/generate?alt=sse&key=AIza-secret",
+            br#"{"model":"m","max_tokens":5,"api_key":"sk-1","auth":{"client_secret":"s"}}"#,
+            400,
+            b"bad request",
+        );
+        assert_eq!(
+            exchange.url,
+            "https://example.com/v1/generate?alt=sse&key=[REDACTED]"
+        );
+        assert_eq!(exchange.request_body["max_tokens"], 5);
+        assert_eq!(exchange.request_body["api_key"], REDACTED);
+        assert_eq!(exchange.request_body["auth"]["client_secret"], REDACTED);
+        assert_eq!(exchange.response_body, "bad request");
+        assert!(!exchange.is_success());
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let capture = HttpCapture::new(2);
+        for status in [200, 201, 202] {
+            capture.record(HttpExchange::new("http://x", b"{}", status, b"{}"));
+        }
+        let statuses: Vec<_> = capture.exchanges().iter().map(|e| e.status).collect();
+        assert_eq!(statuses, [201, 202]);
+        assert_eq!(capture.last().unwrap().status, 202);
+
+        // Clones share the buffer
+        capture.clone().clear();
+        assert!(capture.is_empty());
+    }
+
+    #[test]
+    fn test_env_value() {
+        assert!(HttpCapture::from_env_value("0").is_none());
+        assert!(HttpCapture::from_env_value("off").is_none());
+        assert_eq!(
+            HttpCapture::from_env_value("1").unwrap().capacity(),
+            DEFAULT_CAPTURE_CAPACITY
+        );
+        assert_eq!(
+            HttpCapture::from_env_value("true").unwrap().capacity(),
+            DEFAULT_CAPTURE_CAPACITY
+        );
+        assert_eq!(HttpCapture::from_env_value("64").unwrap().capacity(), 64);
+    }
+}
@@ -0,0 +1,214 @@
+//! Per-request HTTP metrics.
+//!
+//! Every provider records how its HTTP exchange went under the `http` key of
+//! [`ModelResponse::vendor_details`], so slow or oversized requests can be
+//! spotted without enabling full tracing:
+//!
+//! ```json
+//! { "http": { "latency_ms": 812, "request_bytes": 5120, "response_bytes": 934, "retries": 0 } }
+//! ```
+//!
+//! Latency runs from sending the request until the whole response body has
+//! been read. `retries` counts attempts that failed before this response,
+//! e.g. models tried earlier by a [`FallbackModel`](crate::FallbackModel).
+//! Only non-streaming requests are measured.
+
+use crate::error::ModelError;
+use crate::http_capture::{HttpCapture, HttpExchange};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serdes_ai_core::ModelResponse;
+use std::time::{Duration, Instant};
+
+/// Key under which [`HttpMetrics`] are stored in `vendor_details`.
+pub const HTTP_METRICS_KEY: &str = "http";
+
+/// Size and timing of the HTTP exchange behind a model response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpMetrics {
+    /// Time from sending the request to reading the full response, in milliseconds.
+    pub latency_ms: u64,
+    /// Size of the request body in bytes.
+    pub request_bytes: u64,
+    /// Size of the response body in bytes.
+    pub response_bytes: u64,
+    /// Failed attempts before this response.
+    pub retries: u32,
+}
+
+impl HttpMetrics {
+    /// Get the latency as a duration.
+    #[must_use]
+    pub fn latency(&self) -> Duration {
+        Duration::from_millis(self.latency_ms)
+    }
+
+    /// Read the metrics recorded on a response, if any.
+    #[must_use]
+    pub fn from_response(response: &ModelResponse) -> Option<Self> {
+        let value = response.vendor_details.as_ref()?.get(HTTP_METRICS_KEY)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Record the metrics on a response, replacing any already there.
+    pub fn attach(&self, response: &mut ModelResponse) {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        insert_vendor_detail(response, HTTP_METRICS_KEY, value);
+    }
+
+    /// Add failed attempts to the retry count recorded on a response.
+    pub(crate) fn add_retries(response: &mut ModelResponse, retries: u32) {
+        if retries == 0 {
+            return;
+        }
+        let mut metrics = Self::from_response(response).unwrap_or_default();
+        metrics.retries += retries;
+        metrics.attach(response);
+    }
+}
+
+/// Measures one HTTP exchange with a provider.
+pub(crate) struct RequestMeter {
+    started: Instant,
+    request_bytes: usize,
+    capture: Option<PendingCapture>,
+}
+
+/// Request side of an exchange that is being captured.
+struct PendingCapture {
+    capture: HttpCapture,
+    url: String,
+    body: Vec<u8>,
+}
+
+impl RequestMeter {
+    /// Serialize the request body and start the clock.
+    ///
+    /// Returns the body to send, so it's only serialized once.
+    pub(crate) fn start<B: Serialize + ?Sized>(body: &B) -> Result<(Self, Vec<u8>), ModelError> {
+        let body = serde_json::to_vec(body)?;
+        Ok((
+            Self {
+                started: Instant::now(),
+                request_bytes: body.len(),
+                capture: None,
+            },
+            body,
+        ))
+    }
+
+    /// Record the exchange in `capture`, if given.
+    pub(crate) fn capture(
+        mut self,
+        capture: Option<&HttpCapture>,
+        url: impl Into<String>,
+        body: &[u8],
+    ) -> Self {
+        self.capture = capture.map(|capture| PendingCapture {
+            capture: capture.clone(),
+            url: url.into(),
+            body: body.to_vec(),
+        });
+        self
+    }
+
+    /// Record the response to a captured request.
+    pub(crate) fn record(&self, status: u16, response_body: &[u8]) {
+        if let Some(pending) = &self.capture {
+            let exchange = HttpExchange::new(&pending.url, &pending.body, status, response_body)
+                .with_latency_ms(self.started.elapsed().as_millis() as u64);
+            pending.capture.record(exchange);
+        }
+    }
+
+    /// Stop the clock after reading a response body of the given size.
+    pub(crate) fn finish(self, response_bytes: usize) -> HttpMetrics {
+        HttpMetrics {
+            latency_ms: self.started.elapsed().as_millis() as u64,
+            request_bytes: self.request_bytes as u64,
+            response_bytes: response_bytes as u64,
+            retries: 0,
+        }
+    }
+
+    /// Read and decode a JSON response body, stopping the clock.
+    pub(crate) async fn read_json<T: DeserializeOwned>(
+        self,
+        response: reqwest::Response,
+    ) -> Result<(T, HttpMetrics), ModelError> {
+        let status = response.status().as_u16();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        self.record(status, &bytes);
+        let metrics = self.finish(bytes.len());
+        let value = serde_json::from_slice(&bytes)
+            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        Ok((value, metrics))
+    }
+}
+
+/// Insert a value into a response's `vendor_details` object.
+///
+/// Non-object details are kept under a nested `vendor_details` key.
+pub(crate) fn insert_vendor_detail(
+    response: &mut ModelResponse,
+    key: &str,
+    value: serde_json::Value,
+) {
+    match response.vendor_details.as_mut() {
+        Some(serde_json::Value::Object(details)) => {
+            details.insert(key.into(), value);
+        }
+        Some(other) => {
+            *other = serde_json::json!({
+                key: value,
+                "vendor_details": other.take(),
+            });
+        }
+        None => {
+            response.vendor_details = Some(serde_json::json!({ key: value }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_and_read() {
+        let mut response = ModelResponse::text("hi");
+        assert!(HttpMetrics::from_response(&response).is_none());
+
+        let (meter, body) = RequestMeter::start(&serde_json::json!({"a": 1})).unwrap();
+        assert_eq!(body, br#"{"a":1}"#);
+        let metrics = meter.finish(42);
+        assert_eq!(metrics.request_bytes, 7);
+        assert_eq!(metrics.response_bytes, 42);
+        metrics.attach(&mut response);
+
+        HttpMetrics::add_retries(&mut response, 2);
+        let read = HttpMetrics::from_response(&response).unwrap();
+        assert_eq!(read.retries, 2);
+        assert_eq!(read.response_bytes, 42);
+    }
+
+    #[test]
+    fn test_keeps_existing_details() {
+        let mut response = ModelResponse::text("hi");
+        response.vendor_details = Some(serde_json::json!({"provider": "x"}));
+        HttpMetrics::default().attach(&mut response);
+        let details = response.vendor_details.unwrap();
+        assert_eq!(details["provider"], "x");
+        assert_eq!(details["http"]["retries"], 0);
+
+        let mut response = ModelResponse::text("hi");
+        response.vendor_details = Some(serde_json::json!("raw"));
+        HttpMetrics::default().attach(&mut response);
+        let details = response.vendor_details.unwrap();
+        assert_eq!(details["vendor_details"], "raw");
+        assert!(details["http"].is_object());
+    }
+}
@@ -0,0 +1,22 @@
+//! Trace context propagation into provider requests.
+//!
+//! See [`serdes_ai_core::trace_context`]: with the `otel` feature enabled,
+//! every provider request carries the `traceparent` of the span it is sent
+//! from.
+
+use reqwest::RequestBuilder;
+use serdes_ai_core::trace_context;
+
+/// Adds the current trace headers to a request.
+pub(crate) trait TraceHeaders {
+    /// Attach the current span's `traceparent` and `tracestate`, if any.
+    fn trace_headers(self) -> Self;
+}
+
+impl TraceHeaders for RequestBuilder {
+    fn trace_headers(self) -> Self {
+        trace_context::current_headers()
+            .into_iter()
+            .fold(self, |request, (name, value)| request.header(name, value))
+    }
+}
@@ -14,6 +14,9 @@
 //! // Or with explicit token
 //! let model = HuggingFaceModel::new("mistralai/Mistral-7B-Instruct-v0.2", api_token);
 //!
+//! // Models only served through the OpenAI-compatible router
+//! let model = HuggingFaceModel::router_from_env("Qwen/Qwen2.5-72B-Instruct")?;
+//!
 //! // Self-hosted TGI endpoint
 //! let model = HuggingFaceModel::new("my-model", api_token)
 //!     .with_endpoint("http://localhost:8080/generate");
@@ -25,7 +28,8 @@
 //!
 //! ## Supported Models
 //!
-//! Any model hosted on HuggingFace Hub that supports the text-generation inference API:
+//! Any model hosted on HuggingFace Hub that supports the text-generation inference API,
+//! or the chat-completions router (with tool calling) via [`HuggingFaceApi::ChatCompletions`]:
 //! - `meta-llama/Llama-3.1-8B-Instruct`
 //! - `mistralai/Mistral-7B-Instruct-v0.2`
 //! - `google/gemma-2-9b-it`
@@ -34,5 +38,5 @@
 pub mod model;
 pub mod types;
 
-pub use model::HuggingFaceModel;
+pub use model::{HuggingFaceApi, HuggingFaceModel, HF_ROUTER_URL};
 pub use types::{GenerateParameters, GenerateRequest, GenerateResponse};
@@ -1,25 +1,43 @@
 //! HuggingFace model implementation.
 
 use async_trait::async_trait;
-use futures::StreamExt;
 use reqwest::Client;
 use std::time::Duration;
 
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::openai::OpenAIChatModel;
 use crate::profile::ModelProfile;
+use crate::stream_adapter::{AdapterStream, PartTracker, StreamAdapter};
 use serdes_ai_core::{
-    messages::ModelResponseStreamEvent, FinishReason, ModelRequest, ModelRequestPart,
-    ModelResponse, ModelResponsePart, ModelSettings, TextPart, UserContent, UserContentPart,
+    messages::{instructions_as_system_prompt, ModelResponseStreamEvent},
+    FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
+    TextPart, UserContent, UserContentPart,
 };
 
 /// HuggingFace Inference API base URL.
 pub const HF_INFERENCE_URL: &str = "https://api-inference.huggingface.co/models";
 
+/// HuggingFace OpenAI-compatible router base URL.
+pub const HF_ROUTER_URL: &str = "https://router.huggingface.co/v1";
+
+/// Which HuggingFace API a model is served through.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HuggingFaceApi {
+    /// Legacy text-generation endpoint (prompt in, text out).
+    #[default]
+    TextGeneration,
+    /// OpenAI-compatible chat completions, with tool calling.
+    ChatCompletions,
+}
+
 /// HuggingFace model client.
 ///
-/// Supports both the HuggingFace Inference API and self-hosted TGI endpoints.
+/// Supports both the HuggingFace Inference API and self-hosted TGI endpoints,
+/// through either the text-generation or the chat-completions API.
 #[derive(Debug, Clone)]
 pub struct HuggingFaceModel {
     /// Model ID (e.g., "meta-llama/Llama-3.1-8B-Instruct").
@@ -30,6 +48,8 @@ pub struct HuggingFaceModel {
     client: Client,
     /// Custom endpoint URL (for self-hosted TGI).
     endpoint: Option<String>,
+    /// API used for requests.
+    api: HuggingFaceApi,
     /// Model profile.
     profile: ModelProfile,
     /// Default timeout.
@@ -44,6 +64,7 @@ impl HuggingFaceModel {
             api_token: api_token.into(),
             client: Client::new(),
             endpoint: None,
+            api: HuggingFaceApi::TextGeneration,
             profile: Self::default_profile(),
             default_timeout: Duration::from_secs(120),
         }
@@ -57,7 +78,35 @@ impl HuggingFaceModel {
         Ok(Self::new(model_id, api_token))
     }
 
+    /// Create a model served through the chat-completions router.
+    pub fn router(model_id: impl Into<String>, api_token: impl Into<String>) -> Self {
+        Self::new(model_id, api_token).with_api(HuggingFaceApi::ChatCompletions)
+    }
+
+    /// Create a router model from environment variable `HF_TOKEN` or
+    /// `HUGGINGFACE_API_TOKEN`.
+    pub fn router_from_env(model_id: impl Into<String>) -> Result<Self, ModelError> {
+        Ok(Self::from_env(model_id)?.with_api(HuggingFaceApi::ChatCompletions))
+    }
+
+    /// Select the API used for requests.
+    ///
+    /// This also resets the profile to the API's default, so call it before
+    /// [`with_profile`](Self::with_profile).
+    #[must_use]
+    pub fn with_api(mut self, api: HuggingFaceApi) -> Self {
+        self.api = api;
+        self.profile = match api {
+            HuggingFaceApi::TextGeneration => Self::default_profile(),
+            HuggingFaceApi::ChatCompletions => Self::chat_profile(),
+        };
+        self
+    }
+
     /// Set a custom endpoint URL (for self-hosted TGI).
+    ///
+    /// With [`HuggingFaceApi::ChatCompletions`] this is the base URL the
+    /// `/chat/completions` path is appended to (e.g. `http://localhost:8080/v1`).
     #[must_use]
     pub fn with_endpoint(mut self, url: impl Into<String>) -> Self {
         self.endpoint = Some(url.into());
@@ -85,6 +134,11 @@ impl HuggingFaceModel {
         self
     }
 
+    /// Get the API used for requests.
+    pub fn api(&self) -> HuggingFaceApi {
+        self.api
+    }
+
     /// Get the API URL for this model.
     fn api_url(&self) -> String {
         match &self.endpoint {
@@ -107,8 +161,34 @@ impl HuggingFaceModel {
         }
     }
 
+    /// Default profile for models served through the chat-completions router.
+    fn chat_profile() -> ModelProfile {
+        ModelProfile {
+            supports_tools: true,
+            supports_parallel_tools: true,
+            supports_native_structured_output: false,
+            supports_strict_tools: false,
+            supports_system_messages: true,
+            supports_images: false,
+            supports_streaming: true,
+            ..Default::default()
+        }
+    }
+
+    /// Build the OpenAI-compatible model used for the chat-completions API.
+    fn chat_model(&self) -> OpenAIChatModel {
+        let base_url = self.endpoint.as_deref().unwrap_or(HF_ROUTER_URL);
+        OpenAIChatModel::new(&self.model_id, &self.api_token)
+            .with_base_url(base_url)
+            .with_client(self.client.clone())
+            .with_timeout(self.default_timeout)
+            .with_profile(self.profile.clone())
+    }
+
     /// Convert messages to a single prompt string.
     fn build_prompt(&self, messages: &[ModelRequest]) -> String {
+        let folded = instructions_as_system_prompt(messages);
+        let messages = &*folded;
         let mut prompt = String::new();
 
         for request in messages {
@@ -222,6 +302,7 @@ impl HuggingFaceModel {
             timestamp: serdes_ai_core::identifier::now_utc(),
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -263,8 +344,12 @@ impl Model for HuggingFaceModel {
         &self,
         messages: &[ModelRequest],
         settings: &ModelSettings,
-        _params: &ModelRequestParameters,
+        params: &ModelRequestParameters,
     ) -> Result<ModelResponse, ModelError> {
+        if self.api == HuggingFaceApi::ChatCompletions {
+            return self.chat_model().request(messages, settings, params).await;
+        }
+
         let prompt = self.build_prompt(messages);
         let parameters = self.build_parameters(settings);
 
@@ -272,13 +357,18 @@ impl Model for HuggingFaceModel {
 
         let timeout = settings.timeout.unwrap_or(self.default_timeout);
 
+        let (meter, body) = RequestMeter::start(&request_body)?;
         let response = self
             .client
             .post(self.api_url())
-            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header(
+                "Authorization",
+                format!("Bearer {}", settings.api_key_or(&self.api_token)),
+            )
             .header("Content-Type", "application/json")
             .timeout(timeout)
-            .json(&request_body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -288,20 +378,25 @@ impl Model for HuggingFaceModel {
             return Err(self.handle_error(status, &body));
         }
 
-        let resp: GenerateResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
-
-        self.parse_response(resp)
+        let (resp, metrics) = meter.read_json::<GenerateResponse>(response).await?;
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
         &self,
         messages: &[ModelRequest],
         settings: &ModelSettings,
-        _params: &ModelRequestParameters,
+        params: &ModelRequestParameters,
     ) -> Result<StreamedResponse, ModelError> {
+        if self.api == HuggingFaceApi::ChatCompletions {
+            return self
+                .chat_model()
+                .request_stream(messages, settings, params)
+                .await;
+        }
+
         let prompt = self.build_prompt(messages);
         let parameters = self.build_parameters(settings);
 
@@ -314,10 +409,14 @@ impl Model for HuggingFaceModel {
         let response = self
             .client
             .post(self.api_url())
-            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header(
+                "Authorization",
+                format!("Bearer {}", settings.api_key_or(&self.api_token)),
+            )
             .header("Content-Type", "application/json")
             .timeout(timeout)
             .json(&request_body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -327,45 +426,45 @@ impl Model for HuggingFaceModel {
             return Err(self.handle_error(status, &body));
         }
 
-        let stream = response.bytes_stream();
-        let model_id = self.model_id.clone();
-
-        // Parse SSE stream
-        let mapped = stream.filter_map(move |chunk| {
-            let _model_id = model_id.clone();
-            async move {
-                match chunk {
-                    Ok(bytes) => {
-                        let text = String::from_utf8_lossy(&bytes);
-                        // Parse SSE data lines
-                        for line in text.lines() {
-                            if let Some(data) = line.strip_prefix("data:") {
-                                let data = data.trim();
-                                if data.is_empty() || data == "[DONE]" {
-                                    continue;
-                                }
-                                if let Ok(resp) = serde_json::from_str::<StreamResponse>(data) {
-                                    if let Some(token) = resp.token {
-                                        if !token.special {
-                                            return Some(Ok(ModelResponseStreamEvent::text_delta(
-                                                0, token.text,
-                                            )));
-                                        }
-                                    }
-                                    if resp.generated_text.is_some() {
-                                        return Some(Ok(ModelResponseStreamEvent::part_end(0)));
-                                    }
-                                }
-                            }
-                        }
-                        None
-                    }
-                    Err(e) => Some(Err(ModelError::network(e.to_string()))),
-                }
+        Ok(Box::pin(HuggingFaceStreamParser::new(
+            response.bytes_stream(),
+        )))
+    }
+}
+
+/// Stream parser for the text-generation endpoint.
+pub type HuggingFaceStreamParser<S> = AdapterStream<S, HuggingFaceStreamAdapter>;
+
+/// Maps text-generation stream chunks to stream events.
+#[derive(Debug, Default)]
+pub struct HuggingFaceStreamAdapter {
+    parts: PartTracker,
+    done: bool,
+}
+
+impl StreamAdapter for HuggingFaceStreamAdapter {
+    type Chunk = StreamResponse;
+
+    fn map_chunk(
+        &mut self,
+        chunk: StreamResponse,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let mut events = Vec::new();
+        if let Some(token) = chunk.token {
+            if !token.special {
+                events.push(self.parts.text(&token.text));
             }
-        });
+        }
+        // The final chunk carries the full generated text
+        if chunk.generated_text.is_some() {
+            self.done = true;
+            events.extend(self.parts.close_all());
+        }
+        Ok(events)
+    }
 
-        Ok(Box::pin(mapped))
+    fn is_done(&self) -> bool {
+        self.done
     }
 }
 
@@ -407,4 +506,57 @@ mod tests {
         assert!(prompt.contains("Hello!"));
         assert!(prompt.ends_with("<|assistant|>\n"));
     }
+
+    #[test]
+    fn test_router_model() {
+        let model = HuggingFaceModel::router("Qwen/Qwen2.5-72B-Instruct", "token");
+        assert_eq!(model.api(), HuggingFaceApi::ChatCompletions);
+        assert!(model.profile().supports_tools);
+
+        let chat = model.chat_model();
+        assert_eq!(chat.base_url(), HF_ROUTER_URL);
+        assert_eq!(chat.name(), "Qwen/Qwen2.5-72B-Instruct");
+        assert!(!HuggingFaceModel::new("m", "t").profile().supports_tools);
+    }
+
+    #[test]
+    fn test_router_request_includes_tools() {
+        let model = HuggingFaceModel::router("Qwen/Qwen2.5-72B-Instruct", "token")
+            .with_endpoint("http://localhost:8080/v1");
+        let chat = model.chat_model();
+        assert_eq!(chat.base_url(), "http://localhost:8080/v1");
+
+        let mut req = ModelRequest::new();
+        req.add_user_prompt("Weather?");
+        let params =
+            ModelRequestParameters::new().with_tools(vec![serdes_ai_tools::ToolDefinition::new(
+                "get_weather",
+                "Get the weather",
+            )]);
+        let body = chat.build_request(&[req], &ModelSettings::default(), &params, true);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["model"], "Qwen/Qwen2.5-72B-Instruct");
+        assert_eq!(json["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(json["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_stream_parser() {
+        use futures::{stream, StreamExt};
+
+        let body = concat!(
+            "data:{\"token\":{\"id\":1,\"text\":\"Hel\",\"logprob\":null,\"special\":false}}\n\n",
+            "data:{\"token\":{\"id\":2,\"text\":\"lo\",\"logprob\":null,\"special\":false}}\n\n",
+            "data:{\"token\":{\"id\":3,\"text\":\"</s>\",\"logprob\":null,\"special\":true},\"generated_text\":\"Hello\"}\n\n",
+        );
+        let inner = stream::iter(vec![Ok::<_, reqwest::Error>(bytes::Bytes::from(body))]);
+        let events: Vec<_> = HuggingFaceStreamParser::new(inner)
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 3);
+        assert!(events[0].is_start() && events[1].is_delta() && events[2].is_end());
+        assert!(events.iter().all(|e| e.part_id() == events[0].part_id()));
+    }
 }
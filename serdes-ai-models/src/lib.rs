@@ -12,6 +12,8 @@
 //! - **Mistral**: Mistral Large, Small, Codestral (feature: `mistral`)
 //! - **Ollama**: Local models (feature: `ollama`)
 //! - **Bedrock**: AWS-hosted models (feature: `bedrock`)
+//! - **LM Studio** / **llama.cpp**: Local OpenAI-compatible servers (features: `lmstudio`, `llamacpp`)
+//! - **vLLM**: Self-hosted models with guided decoding (feature: `vllm`)
 //!
 //! ## Feature Flags
 //!
@@ -31,6 +33,9 @@
 //! - `ollama`: Ollama local models
 //! - `bedrock`: AWS Bedrock support
 //! - `azure`: Azure OpenAI support
+//! - `lmstudio`: LM Studio local server
+//! - `llamacpp`: llama.cpp server
+//! - `vllm`: vLLM server with guided decoding
 //! - `full`: Enable all providers
 //!
 //! ## Example
@@ -58,11 +63,18 @@
 #![warn(missing_docs)]
 #![deny(unsafe_code)]
 
+pub mod catalog;
 pub mod error;
 pub mod fallback;
+pub mod http_capture;
+pub mod http_metrics;
+mod http_trace;
 pub mod model;
 pub mod profile;
 pub mod schema_transformer;
+pub mod single_flight;
+pub mod stream_adapter;
+pub mod thinking_tags;
 
 // Provider modules (feature-gated)
 
@@ -116,9 +128,33 @@ pub mod openrouter;
 #[cfg_attr(docsrs, doc(cfg(feature = "huggingface")))]
 pub mod huggingface;
 
+/// LM Studio local models.
+#[cfg(feature = "lmstudio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lmstudio")))]
+pub mod lmstudio;
+
+/// llama.cpp server local models.
+#[cfg(feature = "llamacpp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "llamacpp")))]
+pub mod llamacpp;
+
+/// vLLM self-hosted models with guided decoding.
+#[cfg(feature = "vllm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vllm")))]
+pub mod vllm;
+
 #[cfg(feature = "azure")]
 pub use azure::AzureOpenAIModel;
 
+#[cfg(feature = "lmstudio")]
+pub use lmstudio::LmStudioModel;
+
+#[cfg(feature = "llamacpp")]
+pub use llamacpp::LlamaCppModel;
+
+#[cfg(feature = "vllm")]
+pub use vllm::VllmModel;
+
 #[cfg(feature = "openrouter")]
 pub use openrouter::OpenRouterModel;
 
@@ -162,19 +198,27 @@ pub use antigravity::AntigravityModel;
 pub mod mock;
 
 // Re-exports
+pub use catalog::{lookup_model_info, ModelInfo};
 pub use error::{ModelError, ModelResult};
-pub use fallback::{FallbackModel, RetryOn};
+pub use fallback::{FallbackAttempt, FallbackModel, RetryOn, RetryPredicate};
+pub use http_capture::{HttpCapture, HttpExchange, DEFAULT_CAPTURE_CAPACITY, HTTP_CAPTURE_ENV};
+pub use http_metrics::{HttpMetrics, HTTP_METRICS_KEY};
 pub use mock::{FunctionModel, MockModel, TestModel};
 pub use model::{
-    BoxedModel, Model, ModelCapability, ModelRequestParameters, ModelWithMetadata,
-    StreamedResponse, ToolChoice,
+    BoxedModel, CapabilityWarningFn, Model, ModelCapability, ModelRequestParameters,
+    ModelRequestParametersBuilder, ModelWithMetadata, StreamedResponse, ToolChoice,
 };
 pub use profile::{
     anthropic_claude_profile, deepseek_profile, google_gemini_profile, mistral_profile,
     openai_gpt4o_profile, openai_o1_profile, qwen_profile, ModelProfile, OutputMode,
-    DEFAULT_PROFILE, DEFAULT_PROMPTED_OUTPUT_TEMPLATE,
+    PromptedOutputTemplate, DEFAULT_PROFILE, DEFAULT_PROMPTED_OUTPUT_TEMPLATE,
 };
 pub use schema_transformer::JsonSchemaTransformer;
+pub use single_flight::SingleFlightModel;
+pub use stream_adapter::{AdapterStream, PartKey, PartTracker, SseDecoder, StreamAdapter};
+pub use thinking_tags::{
+    split_thinking_tags, Segment, ThinkingTagModel, ThinkingTagSplitter, ThinkingTagStream,
+};
 
 // Re-export provider types for convenience
 #[cfg(feature = "openai")]
@@ -190,7 +234,7 @@ pub use google::GoogleModel as GeminiModel;
 pub use google::GoogleModel;
 
 #[cfg(feature = "groq")]
-pub use groq::GroqModel;
+pub use groq::{GroqModel, GroqReasoningFormat, GroqServiceTier};
 
 #[cfg(feature = "mistral")]
 pub use mistral::MistralModel;
@@ -243,6 +287,15 @@ pub mod prelude {
 
     #[cfg(feature = "cohere")]
     pub use crate::cohere::CohereModel;
+
+    #[cfg(feature = "lmstudio")]
+    pub use crate::lmstudio::LmStudioModel;
+
+    #[cfg(feature = "llamacpp")]
+    pub use crate::llamacpp::LlamaCppModel;
+
+    #[cfg(feature = "vllm")]
+    pub use crate::vllm::VllmModel;
 }
 
 /// Infer a model from a string identifier.
@@ -268,6 +321,8 @@ pub mod prelude {
 /// let model = infer_model("anthropic:claude-3-opus")?;
 /// let model = infer_model("groq:llama-3.1-70b-versatile")?;
 /// let model = infer_model("ollama:llama3.1")?;
+/// let model = infer_model("lmstudio:qwen2.5-7b-instruct")?;
+/// let model = infer_model("llamacpp:")?;
 /// ```
 #[cfg(feature = "openai")]
 pub fn infer_model(identifier: &str) -> ModelResult<std::sync::Arc<dyn Model>> {
@@ -337,13 +392,33 @@ pub fn infer_model(identifier: &str) -> ModelResult<std::sync::Arc<dyn Model>> {
             let model = HuggingFaceModel::from_env(model_name)?;
             Ok(Arc::new(model))
         }
+        #[cfg(feature = "huggingface")]
+        "hf-router" => {
+            let model = HuggingFaceModel::router_from_env(model_name)?;
+            Ok(Arc::new(model))
+        }
         #[cfg(feature = "cohere")]
         "cohere" | "co" => {
             let model = CohereModel::from_env(model_name)?;
             Ok(Arc::new(model))
         }
+        #[cfg(feature = "lmstudio")]
+        "lmstudio" | "lms" => {
+            let model = LmStudioModel::from_env(model_name)?;
+            Ok(Arc::new(model))
+        }
+        #[cfg(feature = "llamacpp")]
+        "llamacpp" | "llama.cpp" => {
+            let model = LlamaCppModel::from_env(model_name)?;
+            Ok(Arc::new(model))
+        }
+        #[cfg(feature = "vllm")]
+        "vllm" => {
+            let model = VllmModel::from_env(model_name)?;
+            Ok(Arc::new(model))
+        }
         _ => Err(ModelError::Configuration(format!(
-            "Unknown provider: {}. Supported: openai, anthropic, groq, mistral, ollama, bedrock, openrouter, huggingface, cohere",
+            "Unknown provider: {}. Supported: openai, anthropic, groq, mistral, ollama, bedrock, openrouter, huggingface, cohere, lmstudio, llamacpp, vllm",
             provider
         ))),
     }
@@ -441,8 +516,17 @@ pub fn build_model_with_config(
                 GroqModel::from_env(model_name)?
             };
 
-            // GroqModel doesn't have with_base_url/with_timeout
-            let _ = (base_url, timeout);
+            let model = if let Some(url) = base_url {
+                model.with_base_url(url)
+            } else {
+                model
+            };
+
+            let model = if let Some(t) = timeout {
+                model.with_timeout(t)
+            } else {
+                model
+            };
 
             Ok(Arc::new(model))
         }
@@ -722,6 +806,18 @@ pub fn build_model_extended(
                 GroqModel::from_env(model_name)?
             };
 
+            let model = if let Some(ref url) = config.base_url {
+                model.with_base_url(url)
+            } else {
+                model
+            };
+
+            let model = if let Some(t) = config.timeout {
+                model.with_timeout(t)
+            } else {
+                model
+            };
+
             let model = if let Some(ref client) = config.client {
                 model.with_client(client.clone())
             } else {
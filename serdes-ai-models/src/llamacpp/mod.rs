@@ -0,0 +1,182 @@
+//! llama.cpp server model implementation.
+//!
+//! The [llama.cpp](https://github.com/ggml-org/llama.cpp) `llama-server`
+//! exposes an OpenAI-compatible API, so this implementation wraps
+//! OpenAIChatModel with local defaults: no API key and a long timeout.
+//!
+//! The server serves a single model, so the model name is only used for
+//! reporting.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use serdes_ai_models::llamacpp::LlamaCppModel;
+//!
+//! let model = LlamaCppModel::new("http://localhost:8080/v1");
+//! // or the default local server
+//! let model = LlamaCppModel::local();
+//! ```
+//!
+//! Tool calling requires starting the server with `--jinja`.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+
+use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
+use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::openai::OpenAIChatModel;
+use crate::profile::ModelProfile;
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
+
+/// llama.cpp server model client.
+#[derive(Debug, Clone)]
+pub struct LlamaCppModel {
+    /// Inner OpenAI-compatible model.
+    inner: OpenAIChatModel,
+}
+
+impl LlamaCppModel {
+    /// Default llama.cpp server URL.
+    pub const DEFAULT_BASE_URL: &'static str = "http://localhost:8080/v1";
+
+    /// Model name reported when none is set.
+    pub const DEFAULT_MODEL_NAME: &'static str = "llama.cpp";
+
+    /// Default timeout; local models can be slow to process long prompts.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+    /// Create a model for the server at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_model(base_url, Self::DEFAULT_MODEL_NAME)
+    }
+
+    /// Create a model for the server at `base_url`, reported as `model_name`.
+    pub fn with_model(base_url: impl Into<String>, model_name: impl Into<String>) -> Self {
+        let inner = OpenAIChatModel::new(model_name, "")
+            .with_base_url(base_url)
+            .with_timeout(Self::DEFAULT_TIMEOUT);
+        Self { inner }
+    }
+
+    /// Create a model for the default local server.
+    pub fn local() -> Self {
+        Self::new(Self::DEFAULT_BASE_URL)
+    }
+
+    /// Create from environment variable `LLAMACPP_BASE_URL`, falling back to
+    /// the default local server. An empty `model_name` uses the default.
+    pub fn from_env(model_name: impl Into<String>) -> Result<Self, ModelError> {
+        let base_url = std::env::var("LLAMACPP_BASE_URL")
+            .unwrap_or_else(|_| Self::DEFAULT_BASE_URL.to_string());
+        let model_name = model_name.into();
+        if model_name.is_empty() {
+            return Ok(Self::new(base_url));
+        }
+        Ok(Self::with_model(base_url, model_name))
+    }
+
+    /// Set the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_timeout(timeout);
+        self
+    }
+
+    /// Set a custom HTTP client.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.inner = self.inner.with_client(client);
+        self
+    }
+
+    /// Set a custom profile.
+    #[must_use]
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.inner = self.inner.with_profile(profile);
+        self
+    }
+
+    /// Get the base URL.
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+}
+
+impl Default for LlamaCppModel {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+#[async_trait]
+impl Model for LlamaCppModel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn system(&self) -> &str {
+        "llamacpp"
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
+    async fn request(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<ModelResponse, ModelError> {
+        self.inner.request(messages, settings, params).await
+    }
+
+    async fn request_stream(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<StreamedResponse, ModelError> {
+        self.inner.request_stream(messages, settings, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_llamacpp_defaults() {
+        let model = LlamaCppModel::new("http://gpu-box:8080/v1");
+        assert_eq!(model.name(), LlamaCppModel::DEFAULT_MODEL_NAME);
+        assert_eq!(model.system(), "llamacpp");
+        assert_eq!(model.base_url(), "http://gpu-box:8080/v1");
+        assert_eq!(model.inner.timeout(), LlamaCppModel::DEFAULT_TIMEOUT);
+        assert_eq!(
+            LlamaCppModel::local().base_url(),
+            "http://localhost:8080/v1"
+        );
+    }
+
+    #[test]
+    fn test_llamacpp_with_model() {
+        let model = LlamaCppModel::with_model(LlamaCppModel::DEFAULT_BASE_URL, "qwen2.5-coder-7b")
+            .with_timeout(Duration::from_secs(30));
+        assert_eq!(model.name(), "qwen2.5-coder-7b");
+        assert_eq!(model.base_url(), LlamaCppModel::DEFAULT_BASE_URL);
+        assert_eq!(model.inner.timeout(), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_llamacpp_from_env_empty_name() {
+        let model = LlamaCppModel::from_env("").unwrap();
+        assert_eq!(model.name(), LlamaCppModel::DEFAULT_MODEL_NAME);
+    }
+}
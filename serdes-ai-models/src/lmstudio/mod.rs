@@ -0,0 +1,169 @@
+//! LM Studio model implementation.
+//!
+//! [LM Studio](https://lmstudio.ai) runs local models behind an
+//! OpenAI-compatible server, so this implementation wraps OpenAIChatModel
+//! with local defaults: no API key, a localhost URL and a long timeout.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use serdes_ai_models::lmstudio::LmStudioModel;
+//!
+//! // Whatever model is loaded in LM Studio
+//! let model = LmStudioModel::local();
+//! // or a specific model
+//! let model = LmStudioModel::new("qwen2.5-7b-instruct");
+//! ```
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+
+use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
+use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::openai::OpenAIChatModel;
+use crate::profile::ModelProfile;
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
+
+/// LM Studio model client.
+#[derive(Debug, Clone)]
+pub struct LmStudioModel {
+    /// Inner OpenAI-compatible model.
+    inner: OpenAIChatModel,
+}
+
+impl LmStudioModel {
+    /// Default LM Studio server URL.
+    pub const DEFAULT_BASE_URL: &'static str = "http://localhost:1234/v1";
+
+    /// Model name used by [`local`](Self::local).
+    pub const LOCAL_MODEL: &'static str = "local-model";
+
+    /// Default timeout; local models can be slow to process long prompts.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+    /// Create a new LM Studio model on the default local server.
+    pub fn new(model_name: impl Into<String>) -> Self {
+        let inner = OpenAIChatModel::new(model_name, "")
+            .with_base_url(Self::DEFAULT_BASE_URL)
+            .with_timeout(Self::DEFAULT_TIMEOUT);
+        Self { inner }
+    }
+
+    /// Create a model that uses whatever model is loaded in LM Studio.
+    pub fn local() -> Self {
+        Self::new(Self::LOCAL_MODEL)
+    }
+
+    /// Create from environment variable `LMSTUDIO_BASE_URL`, falling back to
+    /// the default local server.
+    pub fn from_env(model_name: impl Into<String>) -> Result<Self, ModelError> {
+        let model = Self::new(model_name);
+        Ok(match std::env::var("LMSTUDIO_BASE_URL") {
+            Ok(url) => model.with_base_url(url),
+            Err(_) => model,
+        })
+    }
+
+    /// Set a custom base URL (e.g. LM Studio on another machine).
+    #[must_use]
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.inner = self.inner.with_base_url(url);
+        self
+    }
+
+    /// Set the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_timeout(timeout);
+        self
+    }
+
+    /// Set a custom HTTP client.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.inner = self.inner.with_client(client);
+        self
+    }
+
+    /// Set a custom profile.
+    #[must_use]
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.inner = self.inner.with_profile(profile);
+        self
+    }
+
+    /// Get the base URL.
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+}
+
+impl Default for LmStudioModel {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+#[async_trait]
+impl Model for LmStudioModel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn system(&self) -> &str {
+        "lmstudio"
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
+    async fn request(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<ModelResponse, ModelError> {
+        self.inner.request(messages, settings, params).await
+    }
+
+    async fn request_stream(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<StreamedResponse, ModelError> {
+        self.inner.request_stream(messages, settings, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lmstudio_local_defaults() {
+        let model = LmStudioModel::local();
+        assert_eq!(model.name(), LmStudioModel::LOCAL_MODEL);
+        assert_eq!(model.system(), "lmstudio");
+        assert_eq!(model.base_url(), "http://localhost:1234/v1");
+        assert_eq!(model.inner.timeout(), LmStudioModel::DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn test_lmstudio_custom_url() {
+        let model = LmStudioModel::new("qwen2.5-7b-instruct")
+            .with_base_url("http://192.168.1.10:1234/v1")
+            .with_timeout(Duration::from_secs(30));
+        assert_eq!(model.name(), "qwen2.5-7b-instruct");
+        assert_eq!(model.base_url(), "http://192.168.1.10:1234/v1");
+        assert_eq!(model.inner.timeout(), Duration::from_secs(30));
+    }
+}
@@ -28,13 +28,16 @@ use reqwest::Client;
 use std::time::Duration;
 
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::ModelProfile;
-use serdes_ai_core::messages::ImageContent;
+use serdes_ai_core::messages::{instructions_as_system_prompt, ImageContent};
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
     RequestUsage, TextPart, ToolCallPart, UserContent, UserContentPart,
 };
+use serdes_ai_output::OutputMode;
 
 /// Mistral AI model client.
 #[derive(Debug, Clone)]
@@ -111,6 +114,7 @@ impl MistralModel {
             supports_system_messages: true,
             supports_images: false,
             supports_streaming: true,
+            supports_json_object_output: true,
             ..Default::default()
         }
     }
@@ -140,6 +144,11 @@ impl MistralModel {
             stream: false,
             safe_prompt: None,
             random_seed: settings.seed.map(|s| s as i64),
+            response_format: (params.output_mode == OutputMode::JsonObject).then(|| {
+                types::ResponseFormat {
+                    format_type: "json_object".to_string(),
+                }
+            }),
         })
     }
 
@@ -148,6 +157,8 @@ impl MistralModel {
         &self,
         messages: &[ModelRequest],
     ) -> Result<Vec<types::Message>, ModelError> {
+        let folded = instructions_as_system_prompt(messages);
+        let messages = &*folded;
         let mut result = Vec::new();
 
         for request in messages {
@@ -363,6 +374,7 @@ impl MistralModel {
             timestamp: serdes_ai_core::identifier::now_utc(),
             vendor_id: Some(response.id),
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -391,13 +403,18 @@ impl Model for MistralModel {
         let body = self.build_request(messages, settings, params)?;
         let timeout = settings.timeout.unwrap_or(self.default_timeout);
 
+        let (meter, body) = RequestMeter::start(&body)?;
         let response = self
             .client
             .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header(
+                "Authorization",
+                format!("Bearer {}", settings.api_key_or(&self.api_key)),
+            )
             .header("Content-Type", "application/json")
             .timeout(timeout)
-            .json(&body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -407,12 +424,11 @@ impl Model for MistralModel {
             return Err(ModelError::http(status, text));
         }
 
-        let chat_response: types::ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (chat_response, metrics) = meter.read_json::<types::ChatResponse>(response).await?;
 
-        self.parse_response(chat_response)
+        let mut response = self.parse_response(chat_response)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -32,6 +32,17 @@ pub struct ChatRequest {
     /// Random seed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub random_seed: Option<i64>,
+    /// Response format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+}
+
+/// Response format.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseFormat {
+    /// Format type (text, json_object).
+    #[serde(rename = "type")]
+    pub format_type: String,
 }
 
 /// Message role.
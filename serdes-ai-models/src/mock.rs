@@ -88,11 +88,12 @@ impl MockModel {
         let response = ModelResponse {
             parts: vec![ModelResponsePart::Text(TextPart::new(text))],
             model_name: Some(self.name.clone()),
-            timestamp: chrono::Utc::now(),
+            timestamp: serdes_ai_core::now_utc(),
             finish_reason: Some(serdes_ai_core::FinishReason::Stop),
             usage: None,
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         };
         self.with_response(response)
@@ -144,11 +145,12 @@ impl Model for MockModel {
             Ok(ModelResponse {
                 parts: vec![ModelResponsePart::Text(TextPart::new("Mock response"))],
                 model_name: Some(self.name.clone()),
-                timestamp: chrono::Utc::now(),
+                timestamp: serdes_ai_core::now_utc(),
                 finish_reason: Some(serdes_ai_core::FinishReason::Stop),
                 usage: None,
                 vendor_id: None,
                 vendor_details: None,
+                alternatives: Vec::new(),
                 kind: "response".to_string(),
             })
         } else {
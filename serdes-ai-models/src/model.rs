@@ -6,7 +6,8 @@
 use async_trait::async_trait;
 use futures::Stream;
 use serdes_ai_core::{
-    messages::ModelResponseStreamEvent, ModelRequest, ModelResponse, ModelSettings,
+    messages::{ModelResponseStreamEvent, UserContent, UserContentPart},
+    ModelRequest, ModelRequestPart, ModelResponse, ModelSettings,
 };
 use serdes_ai_output::OutputMode;
 use serdes_ai_tools::{ObjectJsonSchema, ToolDefinition};
@@ -14,6 +15,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
 use crate::profile::ModelProfile;
 
 /// Parameters for a model request.
@@ -23,7 +25,7 @@ pub struct ModelRequestParameters {
     pub tools: Arc<Vec<ToolDefinition>>,
     /// Output schema for structured output.
     pub output_schema: Option<ObjectJsonSchema>,
-    /// Output mode (text, native, prompted, tool).
+    /// Output mode (text, native, prompted, JSON object, tool).
     pub output_mode: OutputMode,
     /// Whether to allow text response.
     pub allow_text_output: bool,
@@ -68,6 +70,27 @@ impl ModelRequestParameters {
         self
     }
 
+    /// Set the output mode from a model profile.
+    ///
+    /// [`OutputMode::Auto`](crate::profile::OutputMode::Auto) is resolved
+    /// against the profile's capabilities.
+    #[must_use]
+    pub fn with_profile_output_mode(
+        mut self,
+        profile: &ModelProfile,
+        mode: crate::profile::OutputMode,
+    ) -> Self {
+        use crate::profile::OutputMode as ProfileMode;
+        self.output_mode = match profile.resolve_output_mode(mode) {
+            ProfileMode::Native => OutputMode::Native,
+            ProfileMode::Prompted => OutputMode::Prompted,
+            ProfileMode::JsonObject => OutputMode::JsonObject,
+            ProfileMode::Text => OutputMode::Text,
+            ProfileMode::Tool | ProfileMode::Auto => OutputMode::Tool,
+        };
+        self
+    }
+
     /// Set allow text output.
     #[must_use]
     pub fn with_allow_text(mut self, allow: bool) -> Self {
@@ -81,6 +104,197 @@ impl ModelRequestParameters {
         self.tool_choice = Some(choice);
         self
     }
+
+    /// Start building parameters checked against a model profile.
+    #[must_use]
+    pub fn builder(profile: &ModelProfile) -> ModelRequestParametersBuilder<'_> {
+        ModelRequestParametersBuilder::new(profile)
+    }
+}
+
+/// Callback receiving a warning when content is adjusted to fit a model.
+pub type CapabilityWarningFn = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Builder for [`ModelRequestParameters`] that checks them against a
+/// [`ModelProfile`] before anything is sent.
+///
+/// Requests the model can't serve, such as tools for a model without tool
+/// support, are rejected by [`build`](Self::build). Message content the model
+/// can't read is stripped by [`prepare_messages`](Self::prepare_messages),
+/// reporting each removal to the warning callback.
+///
+/// # Example
+///
+/// ```ignore
+/// let builder = ModelRequestParameters::builder(model.profile())
+///     .tools(tools)
+///     .on_warning(|msg| eprintln!("{msg}"));
+/// builder.prepare_messages(&mut messages);
+/// let params = builder.build()?;
+/// ```
+pub struct ModelRequestParametersBuilder<'a> {
+    profile: &'a ModelProfile,
+    params: ModelRequestParameters,
+    on_warning: Option<CapabilityWarningFn>,
+}
+
+impl<'a> ModelRequestParametersBuilder<'a> {
+    /// Create a builder for the given profile.
+    #[must_use]
+    pub fn new(profile: &'a ModelProfile) -> Self {
+        Self {
+            profile,
+            params: ModelRequestParameters::default(),
+            on_warning: None,
+        }
+    }
+
+    /// Add tool definitions.
+    #[must_use]
+    pub fn tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.params.tools = Arc::new(tools);
+        self
+    }
+
+    /// Add tool definitions from an Arc.
+    #[must_use]
+    pub fn tools_arc(mut self, tools: Arc<Vec<ToolDefinition>>) -> Self {
+        self.params.tools = tools;
+        self
+    }
+
+    /// Set output schema.
+    #[must_use]
+    pub fn output_schema(mut self, schema: ObjectJsonSchema) -> Self {
+        self.params.output_schema = Some(schema);
+        self
+    }
+
+    /// Set output mode.
+    #[must_use]
+    pub fn output_mode(mut self, mode: OutputMode) -> Self {
+        self.params.output_mode = mode;
+        self
+    }
+
+    /// Set allow text output.
+    #[must_use]
+    pub fn allow_text(mut self, allow: bool) -> Self {
+        self.params.allow_text_output = allow;
+        self
+    }
+
+    /// Set tool choice.
+    #[must_use]
+    pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
+        self.params.tool_choice = Some(choice);
+        self
+    }
+
+    /// Set whether to include usage in streaming responses.
+    #[must_use]
+    pub fn stream_usage(mut self, include: bool) -> Self {
+        self.params.stream_usage = include;
+        self
+    }
+
+    /// Set the callback for content adjustments.
+    ///
+    /// Without a callback, adjustments are logged with `tracing`.
+    #[must_use]
+    pub fn on_warning<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.on_warning = Some(Arc::new(f));
+        self
+    }
+
+    /// Remove user content the model can't read from `messages`.
+    ///
+    /// Returns the number of removed parts.
+    pub fn prepare_messages(&self, messages: &mut [ModelRequest]) -> usize {
+        let profile = self.profile;
+        let mut removed: Vec<(&str, usize)> = Vec::new();
+        for part in messages.iter_mut().flat_map(|m| m.parts.iter_mut()) {
+            let ModelRequestPart::UserPrompt(prompt) = part else {
+                continue;
+            };
+            let UserContent::Parts(parts) = &mut prompt.content else {
+                continue;
+            };
+            parts.retain(|p| {
+                let (kind, supported) = match p {
+                    UserContentPart::Image { .. } => ("image", profile.supports_images),
+                    UserContentPart::Audio { .. } => ("audio", profile.supports_audio),
+                    UserContentPart::Video { .. } => ("video", profile.supports_video),
+                    UserContentPart::Document { .. } => ("document", profile.supports_documents),
+                    _ => return true,
+                };
+                if !supported {
+                    match removed.iter_mut().find(|(k, _)| *k == kind) {
+                        Some((_, count)) => *count += 1,
+                        None => removed.push((kind, 1)),
+                    }
+                }
+                supported
+            });
+        }
+
+        for (kind, count) in &removed {
+            self.warn(&format!(
+                "Removed {} {} part(s) the model does not support",
+                count, kind
+            ));
+        }
+        removed.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Check the parameters against the profile and build them.
+    pub fn build(self) -> Result<ModelRequestParameters, ModelError> {
+        let profile = self.profile;
+        let params = self.params;
+
+        if !params.tools.is_empty() && !profile.supports_tools {
+            return Err(ModelError::not_supported("tool calling"));
+        }
+        match &params.tool_choice {
+            Some(ToolChoice::Required) if params.tools.is_empty() => {
+                return Err(ModelError::configuration(
+                    "tool choice `required` needs at least one tool",
+                ));
+            }
+            Some(ToolChoice::Specific(name)) if !params.tools.iter().any(|t| &t.name == name) => {
+                return Err(ModelError::configuration(format!(
+                    "tool choice names unknown tool `{}`",
+                    name
+                )));
+            }
+            _ => {}
+        }
+        if params.output_schema.is_some() {
+            match params.output_mode {
+                OutputMode::Native if !profile.supports_native_structured_output => {
+                    return Err(ModelError::not_supported("native structured output"));
+                }
+                OutputMode::Tool if !profile.supports_tools => {
+                    return Err(ModelError::not_supported("tool output mode"));
+                }
+                OutputMode::JsonObject if !profile.supports_json_object_output => {
+                    return Err(ModelError::not_supported("JSON object output mode"));
+                }
+                _ => {}
+            }
+        }
+        Ok(params)
+    }
+
+    fn warn(&self, message: &str) {
+        match &self.on_warning {
+            Some(f) => f(message),
+            None => tracing::warn!("{}", message),
+        }
+    }
 }
 
 /// Tool choice strategy.
@@ -147,6 +361,14 @@ pub trait Model: Send + Sync {
         Err(ModelError::not_supported("Token counting"))
     }
 
+    /// Get the latest HTTP exchange with the provider, if captured.
+    ///
+    /// Only models with HTTP capture enabled record exchanges, see
+    /// [`http_capture`](crate::http_capture).
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        None
+    }
+
     /// Check if the model supports a specific capability.
     fn supports(&self, capability: ModelCapability) -> bool {
         let profile = self.profile();
@@ -154,6 +376,7 @@ pub trait Model: Send + Sync {
             ModelCapability::Tools => profile.supports_tools,
             ModelCapability::ParallelTools => profile.supports_parallel_tools,
             ModelCapability::NativeStructuredOutput => profile.supports_native_structured_output,
+            ModelCapability::JsonObjectOutput => profile.supports_json_object_output,
             ModelCapability::StrictTools => profile.supports_strict_tools,
             ModelCapability::SystemMessages => profile.supports_system_messages,
             ModelCapability::Images => profile.supports_images,
@@ -176,6 +399,8 @@ pub enum ModelCapability {
     ParallelTools,
     /// Native structured output.
     NativeStructuredOutput,
+    /// JSON mode without a schema.
+    JsonObjectOutput,
     /// Strict mode for tools.
     StrictTools,
     /// System messages.
@@ -200,6 +425,10 @@ pub enum ModelCapability {
 pub type BoxedModel = Arc<dyn Model>;
 
 /// A model with additional metadata.
+///
+/// Limits and modality flags are populated from the model profile, and
+/// missing limits, prices and knowledge cutoff from the
+/// [model catalog](crate::catalog) when the model is known.
 #[derive(Clone)]
 pub struct ModelWithMetadata {
     /// The underlying model.
@@ -210,16 +439,47 @@ pub struct ModelWithMetadata {
     pub description: Option<String>,
     /// Tags for categorization.
     pub tags: Vec<String>,
+    /// Context window in tokens.
+    pub context_window: Option<u64>,
+    /// Maximum output tokens.
+    pub max_output_tokens: Option<u64>,
+    /// Input price in USD per million tokens.
+    pub input_price: Option<f64>,
+    /// Output price in USD per million tokens.
+    pub output_price: Option<f64>,
+    /// Training data cutoff (`YYYY-MM`).
+    pub knowledge_cutoff: Option<String>,
+    /// Accepts image input.
+    pub supports_images: bool,
+    /// Accepts audio input.
+    pub supports_audio: bool,
+    /// Accepts video input.
+    pub supports_video: bool,
+    /// Accepts document input.
+    pub supports_documents: bool,
 }
 
 impl ModelWithMetadata {
-    /// Create a new model with metadata.
+    /// Create a new model with metadata populated from its profile and the
+    /// model catalog.
     pub fn new(model: BoxedModel) -> Self {
+        let profile = model.profile();
+        let info = crate::catalog::lookup_model_info(model.name());
+
         Self {
-            model,
             display_name: None,
             description: None,
             tags: Vec::new(),
+            context_window: profile.context_window.or(info.map(|i| i.context_window)),
+            max_output_tokens: profile.max_tokens.or(info.map(|i| i.max_output_tokens)),
+            input_price: info.map(|i| i.input_price),
+            output_price: info.map(|i| i.output_price),
+            knowledge_cutoff: info.and_then(|i| i.knowledge_cutoff).map(String::from),
+            supports_images: profile.supports_images,
+            supports_audio: profile.supports_audio,
+            supports_video: profile.supports_video,
+            supports_documents: profile.supports_documents,
+            model,
         }
     }
 
@@ -243,6 +503,65 @@ impl ModelWithMetadata {
         self.tags.push(tag.into());
         self
     }
+
+    /// Set context window.
+    #[must_use]
+    pub fn with_context_window(mut self, tokens: u64) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    /// Set maximum output tokens.
+    #[must_use]
+    pub fn with_max_output_tokens(mut self, tokens: u64) -> Self {
+        self.max_output_tokens = Some(tokens);
+        self
+    }
+
+    /// Set input and output prices in USD per million tokens.
+    #[must_use]
+    pub fn with_pricing(mut self, input_price: f64, output_price: f64) -> Self {
+        self.input_price = Some(input_price);
+        self.output_price = Some(output_price);
+        self
+    }
+
+    /// Set knowledge cutoff.
+    #[must_use]
+    pub fn with_knowledge_cutoff(mut self, cutoff: impl Into<String>) -> Self {
+        self.knowledge_cutoff = Some(cutoff.into());
+        self
+    }
+
+    /// Get the display name, falling back to the model name.
+    pub fn name(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .unwrap_or_else(|| self.model.name())
+    }
+
+    /// Estimate the cost in USD of the given token counts.
+    ///
+    /// Returns `None` if pricing is unknown.
+    pub fn estimate_cost(&self, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+        let input = self.input_price? * input_tokens as f64;
+        let output = self.output_price? * output_tokens as f64;
+        Some((input + output) / 1_000_000.0)
+    }
+}
+
+impl std::fmt::Debug for ModelWithMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModelWithMetadata")
+            .field("model", &self.model.name())
+            .field("display_name", &self.display_name)
+            .field("context_window", &self.context_window)
+            .field("max_output_tokens", &self.max_output_tokens)
+            .field("input_price", &self.input_price)
+            .field("output_price", &self.output_price)
+            .field("knowledge_cutoff", &self.knowledge_cutoff)
+            .finish_non_exhaustive()
+    }
 }
 
 #[cfg(test)]
@@ -261,6 +580,100 @@ mod tests {
         assert!(matches!(params.tool_choice, Some(ToolChoice::Required)));
     }
 
+    #[test]
+    fn test_profile_output_mode() {
+        use crate::profile::{openai_gpt4o_profile, OutputMode as ProfileMode};
+
+        let params = ModelRequestParameters::new()
+            .with_profile_output_mode(&openai_gpt4o_profile(), ProfileMode::Auto);
+        assert_eq!(params.output_mode, OutputMode::Native);
+
+        let params = ModelRequestParameters::new()
+            .with_profile_output_mode(&ModelProfile::new().with_tools(false), ProfileMode::Auto);
+        assert_eq!(params.output_mode, OutputMode::Prompted);
+    }
+
+    #[test]
+    fn test_parameters_builder_capability_checks() {
+        let tool = ToolDefinition::new("search", "Search");
+        let no_tools = ModelProfile::new().with_tools(false);
+
+        let err = ModelRequestParameters::builder(&no_tools)
+            .tools(vec![tool.clone()])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ModelError::NotSupported(_)));
+
+        let err = ModelRequestParameters::builder(&ModelProfile::new())
+            .tools(vec![tool.clone()])
+            .tool_choice(ToolChoice::Specific("fetch".into()))
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("fetch"));
+
+        let params = ModelRequestParameters::builder(&ModelProfile::new())
+            .tools(vec![tool])
+            .tool_choice(ToolChoice::Required)
+            .allow_text(true)
+            .build()
+            .unwrap();
+        assert_eq!(params.tools.len(), 1);
+        assert!(params.allow_text_output);
+    }
+
+    #[test]
+    fn test_parameters_builder_strips_images() {
+        use serdes_ai_core::messages::UserContentPart;
+        use std::sync::Mutex;
+
+        let mut request = ModelRequest::new();
+        request.add_user_prompt(UserContent::parts(vec![
+            UserContentPart::text("What is this?"),
+            UserContentPart::image_url("https://example.com/cat.png"),
+        ]));
+        let mut messages = vec![request];
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let profile = ModelProfile::new().with_images(false);
+        let builder = ModelRequestParameters::builder(&profile).on_warning({
+            let warnings = Arc::clone(&warnings);
+            move |msg| warnings.lock().unwrap().push(msg.to_string())
+        });
+
+        assert_eq!(builder.prepare_messages(&mut messages), 1);
+        assert_eq!(
+            warnings.lock().unwrap().as_slice(),
+            ["Removed 1 image part(s) the model does not support"]
+        );
+        let ModelRequestPart::UserPrompt(prompt) = &messages[0].parts[0] else {
+            panic!("expected user prompt");
+        };
+        assert!(matches!(&prompt.content, UserContent::Parts(p) if p.len() == 1));
+    }
+
+    #[test]
+    fn test_model_metadata_from_catalog() {
+        let model: BoxedModel = Arc::new(crate::mock::MockModel::new("gpt-4o-2024-08-06"));
+        let meta = ModelWithMetadata::new(model);
+        assert_eq!(meta.context_window, Some(128_000));
+        assert_eq!(meta.knowledge_cutoff.as_deref(), Some("2023-10"));
+        assert_eq!(meta.name(), "gpt-4o-2024-08-06");
+
+        let cost = meta.estimate_cost(1_000_000, 100_000).unwrap();
+        assert!((cost - 3.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_model_metadata_unknown_model() {
+        let model: BoxedModel = Arc::new(crate::mock::MockModel::new("my-finetune"));
+        let meta = ModelWithMetadata::new(model)
+            .with_display_name("Fine-tune")
+            .with_pricing(1.0, 2.0);
+        assert_eq!(meta.context_window, None);
+        assert_eq!(meta.name(), "Fine-tune");
+        assert_eq!(meta.estimate_cost(1_000_000, 0), Some(1.0));
+    }
+
     #[test]
     fn test_tool_choice_default() {
         let choice = ToolChoice::default();
@@ -35,9 +35,11 @@ use reqwest::Client;
 use std::time::Duration;
 
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::ModelProfile;
-use serdes_ai_core::messages::ImageContent;
+use serdes_ai_core::messages::{instructions_as_system_prompt, ImageContent};
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
     RequestUsage, TextPart, ToolCallPart, UserContent, UserContentPart,
@@ -171,6 +173,8 @@ impl OllamaModel {
         &self,
         messages: &[ModelRequest],
     ) -> Result<Vec<types::Message>, ModelError> {
+        let folded = instructions_as_system_prompt(messages);
+        let messages = &*folded;
         let mut result = Vec::new();
 
         for request in messages {
@@ -372,6 +376,7 @@ impl OllamaModel {
             timestamp: serdes_ai_core::identifier::now_utc(),
             vendor_id: None,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
@@ -400,12 +405,14 @@ impl Model for OllamaModel {
         let body = self.build_request(messages, settings, params)?;
         let timeout = settings.timeout.unwrap_or(self.default_timeout);
 
+        let (meter, body) = RequestMeter::start(&body)?;
         let response = self
             .client
             .post(format!("{}/api/chat", self.base_url))
             .header("Content-Type", "application/json")
             .timeout(timeout)
-            .json(&body)
+            .body(body)
+            .trace_headers()
             .send()
             .await?;
 
@@ -415,12 +422,11 @@ impl Model for OllamaModel {
             return Err(ModelError::http(status, text));
         }
 
-        let chat_response: types::ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (chat_response, metrics) = meter.read_json::<types::ChatResponse>(response).await?;
 
-        self.parse_response(chat_response)
+        let mut response = self.parse_response(chat_response)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -0,0 +1,516 @@
+//! Background mode for the Responses API.
+//!
+//! Very long reasoning jobs can outlast HTTP timeouts. With `background=true`
+//! the API answers right away with a queued response, which then runs on the
+//! server and is polled, streamed or cancelled by ID.
+//! [`OpenAIResponsesModel::submit_background`] returns a
+//! [`BackgroundResponse`] handle for that.
+//!
+//! ```rust,ignore
+//! use serdes_ai_models::openai::{OpenAIResponsesModel, ReasoningEffort};
+//! use std::time::Duration;
+//!
+//! let model = OpenAIResponsesModel::from_env("o3-pro")?
+//!     .with_reasoning_effort(ReasoningEffort::High);
+//!
+//! let mut job = model
+//!     .submit_background(&messages, &settings, &params)
+//!     .await?
+//!     .with_poll_interval(Duration::from_secs(10));
+//! println!("Submitted {}", job.id());
+//!
+//! // Later, possibly from another process
+//! let mut job = model.background_response(job_id);
+//! let response = job.wait().await?;
+//! ```
+
+use super::responses::{OpenAIResponsesModel, ResponseStatus, ResponsesApiResponse};
+use crate::error::ModelError;
+use crate::model::{ModelRequestParameters, StreamedResponse};
+use crate::stream_adapter::{AdapterStream, PartKey, PartTracker, SseDecoder, StreamAdapter};
+use futures::StreamExt;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serdes_ai_core::messages::{
+    ModelResponsePartDelta, ModelResponseStreamEvent, ToolCallArgs, ToolCallPartDelta,
+};
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelResponsePart, ModelSettings, ToolCallPart};
+use std::time::Duration;
+
+/// Default time between status checks of a background response.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+impl OpenAIResponsesModel {
+    /// Start a response in the background.
+    ///
+    /// Returns as soon as the API has queued the response. Background
+    /// responses are always stored, so this fails if storing is disabled.
+    pub async fn submit_background(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<BackgroundResponse, ModelError> {
+        let mut body = self.build_request(messages, settings, params, true);
+        if body.store == Some(false) {
+            return Err(ModelError::configuration(
+                "Background responses must be stored; remove with_store(false)",
+            ));
+        }
+        body.background = Some(true);
+
+        let response = self.create_request(settings).json(&body).send().await?;
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(self.handle_error_response(status, &body));
+        }
+
+        // Streaming is requested so the response can be streamed by ID
+        // later; only its first event, carrying the ID, is read here.
+        let is_sse = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|t| t.to_str().ok())
+            .is_some_and(|t| t.starts_with("text/event-stream"));
+        let created: ResponsesApiResponse = if is_sse {
+            first_response_event(response).await?
+        } else {
+            response.json().await?
+        };
+
+        Ok(BackgroundResponse {
+            model: self.clone(),
+            id: created.id,
+            status: created.status,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        })
+    }
+
+    /// Get a handle for a background response started earlier.
+    pub fn background_response(&self, id: impl Into<String>) -> BackgroundResponse {
+        BackgroundResponse {
+            model: self.clone(),
+            id: id.into(),
+            status: ResponseStatus::Queued,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+}
+
+/// An event carrying the whole response, such as `response.created`.
+#[derive(Deserialize)]
+struct ResponseEvent {
+    response: ResponsesApiResponse,
+}
+
+/// Read a response stream up to the first event carrying the response.
+async fn first_response_event(
+    response: reqwest::Response,
+) -> Result<ResponsesApiResponse, ModelError> {
+    let mut bytes = response.bytes_stream();
+    let mut decoder = SseDecoder::new();
+    loop {
+        while let Some(data) = decoder.next_data() {
+            if let Ok(event) = serde_json::from_str::<ResponseEvent>(&data) {
+                return Ok(event.response);
+            }
+        }
+        match bytes.next().await {
+            Some(chunk) => decoder.push(&chunk?),
+            None => {
+                return Err(ModelError::invalid_response(
+                    "Stream ended before the background response was created",
+                ))
+            }
+        }
+    }
+}
+
+/// Handle for a response running in the background.
+#[derive(Debug, Clone)]
+pub struct BackgroundResponse {
+    model: OpenAIResponsesModel,
+    id: String,
+    status: ResponseStatus,
+    poll_interval: Duration,
+}
+
+impl BackgroundResponse {
+    /// Set the time between status checks in [`wait`](Self::wait).
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Get the response ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the last known status.
+    pub fn status(&self) -> ResponseStatus {
+        self.status
+    }
+
+    /// Check if the response has stopped running, as of the last check.
+    pub fn is_finished(&self) -> bool {
+        !matches!(
+            self.status,
+            ResponseStatus::Queued | ResponseStatus::InProgress
+        )
+    }
+
+    /// Check the status once.
+    ///
+    /// Returns the response once it has finished, and
+    /// [`ModelError::Cancelled`] if it was cancelled.
+    pub async fn poll(&mut self) -> Result<Option<ModelResponse>, ModelError> {
+        let response = self.model.retrieve_response(&self.id).await?;
+        self.status = response.status;
+        match response.status {
+            ResponseStatus::Queued | ResponseStatus::InProgress => Ok(None),
+            ResponseStatus::Cancelled => Err(ModelError::Cancelled),
+            _ => self.model.process_response(response).map(Some),
+        }
+    }
+
+    /// Poll until the response has finished.
+    pub async fn wait(&mut self) -> Result<ModelResponse, ModelError> {
+        loop {
+            if let Some(response) = self.poll().await? {
+                return Ok(response);
+            }
+            if !serdes_ai_core::clock::clock().skip_wait(self.poll_interval) {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    /// Cancel the response.
+    ///
+    /// See [`OpenAIResponsesModel::cancel_response`].
+    pub async fn cancel(&mut self) -> Result<(), ModelError> {
+        self.status = self.model.cancel_response(&self.id).await?.status;
+        Ok(())
+    }
+
+    /// Stream the response's events from the start.
+    ///
+    /// Works while the response runs and after it has finished.
+    pub async fn stream(&self) -> Result<StreamedResponse, ModelError> {
+        let request = self
+            .model
+            .response_request(Method::GET, &self.id, None)?
+            .query(&[("stream", "true")]);
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(self.model.handle_error_response(status, &body));
+        }
+        Ok(Box::pin(AdapterStream::<_, ResponsesStreamAdapter>::new(
+            response.bytes_stream(),
+        )))
+    }
+}
+
+/// Responses API stream event.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+#[allow(missing_docs)]
+pub enum ResponsesStreamChunk {
+    #[serde(rename = "response.output_text.delta")]
+    OutputTextDelta { delta: String },
+    #[serde(rename = "response.reasoning_summary_text.delta")]
+    ReasoningSummaryTextDelta { delta: String },
+    #[serde(rename = "response.output_item.added")]
+    OutputItemAdded { output_index: u64, item: JsonValue },
+    #[serde(rename = "response.function_call_arguments.delta")]
+    FunctionCallArgumentsDelta { output_index: u64, delta: String },
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone { output_index: u64, item: JsonValue },
+    #[serde(rename = "response.completed")]
+    Completed,
+    #[serde(rename = "response.incomplete")]
+    Incomplete,
+    #[serde(rename = "response.failed")]
+    Failed { response: JsonValue },
+    #[serde(rename = "error")]
+    Error {
+        message: String,
+        #[serde(default)]
+        code: Option<String>,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Maps Responses API stream events to stream events.
+#[derive(Debug, Default)]
+pub struct ResponsesStreamAdapter {
+    parts: PartTracker,
+    done: bool,
+}
+
+impl StreamAdapter for ResponsesStreamAdapter {
+    type Chunk = ResponsesStreamChunk;
+
+    fn map_chunk(
+        &mut self,
+        chunk: ResponsesStreamChunk,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let events = match chunk {
+            ResponsesStreamChunk::OutputTextDelta { delta } => vec![self.parts.text(&delta)],
+            ResponsesStreamChunk::ReasoningSummaryTextDelta { delta } => {
+                vec![self.parts.thinking(&delta)]
+            }
+            ResponsesStreamChunk::OutputItemAdded { output_index, item } => {
+                if item["type"] != "function_call" {
+                    return Ok(Vec::new());
+                }
+                let text = |key: &str| item[key].as_str().unwrap_or_default().to_string();
+                let part = ToolCallPart::new(text("name"), ToolCallArgs::String(text("arguments")))
+                    .with_tool_call_id(text("call_id"));
+                vec![self.parts.start(
+                    PartKey::ToolCall(output_index),
+                    ModelResponsePart::ToolCall(part),
+                )]
+            }
+            ResponsesStreamChunk::FunctionCallArgumentsDelta {
+                output_index,
+                delta,
+            } => self
+                .parts
+                .delta(
+                    &PartKey::ToolCall(output_index),
+                    ModelResponsePartDelta::ToolCall(ToolCallPartDelta::new(delta)),
+                )
+                .into_iter()
+                .collect(),
+            ResponsesStreamChunk::OutputItemDone { output_index, item } => {
+                let key = match item["type"].as_str() {
+                    Some("function_call") => PartKey::ToolCall(output_index),
+                    Some("reasoning") => PartKey::Thinking,
+                    Some("message") => PartKey::Text,
+                    _ => return Ok(Vec::new()),
+                };
+                self.parts.close(&key).into_iter().collect()
+            }
+            ResponsesStreamChunk::Completed | ResponsesStreamChunk::Incomplete => {
+                self.done = true;
+                self.parts.close_all()
+            }
+            ResponsesStreamChunk::Failed { response } => {
+                self.done = true;
+                let error = &response["error"];
+                return Err(ModelError::Api {
+                    message: error["message"]
+                        .as_str()
+                        .unwrap_or("Response failed with unknown error")
+                        .to_string(),
+                    code: error["code"].as_str().map(str::to_string),
+                });
+            }
+            ResponsesStreamChunk::Error { message, code } => {
+                return Err(ModelError::Api { message, code });
+            }
+            ResponsesStreamChunk::Other => Vec::new(),
+        };
+        Ok(events)
+    }
+
+    fn finish(&mut self) -> Vec<ModelResponseStreamEvent> {
+        self.parts.close_all()
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn api_response(status: &str, text: Option<&str>) -> JsonValue {
+        let output = text.map_or(serde_json::json!([]), |text| {
+            serde_json::json!([{
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "content": [{"type": "output_text", "text": text}]
+            }])
+        });
+        serde_json::json!({
+            "id": "resp_bg",
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "o3-pro",
+            "status": status,
+            "output": output,
+        })
+    }
+
+    fn sse(events: &[JsonValue]) -> String {
+        events
+            .iter()
+            .map(|e| format!("event: {}\ndata: {}\n\n", e["type"].as_str().unwrap(), e))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_wait() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .and(body_partial_json(
+                serde_json::json!({"background": true, "stream": true}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(api_response("queued", None)))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/responses/resp_bg"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(api_response("in_progress", None)),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/responses/resp_bg"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(api_response("completed", Some("42"))),
+            )
+            .mount(&server)
+            .await;
+
+        let model = OpenAIResponsesModel::new("o3-pro", "sk-test").with_base_url(server.uri());
+        let mut request = ModelRequest::new();
+        request.add_user_prompt("Think hard");
+        let mut job = model
+            .submit_background(
+                &[request.clone()],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(1));
+        assert_eq!(job.id(), "resp_bg");
+        assert_eq!(job.status(), ResponseStatus::Queued);
+
+        assert!(job.poll().await.unwrap().is_none());
+        assert_eq!(job.status(), ResponseStatus::InProgress);
+        let response = job.wait().await.unwrap();
+        assert_eq!(response.text_content(), "42");
+        assert!(job.is_finished());
+
+        let unstored = model.with_store(false);
+        let err = unstored
+            .submit_background(
+                &[request],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::Configuration(_)));
+    }
+
+    #[tokio::test]
+    async fn test_cancel() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses/resp_bg/cancel"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(api_response("cancelled", None)))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/responses/resp_bg"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(api_response("cancelled", None)))
+            .mount(&server)
+            .await;
+
+        let model = OpenAIResponsesModel::new("o3-pro", "sk-test").with_base_url(server.uri());
+        let mut job = model.background_response("resp_bg");
+        assert!(!job.is_finished());
+        job.cancel().await.unwrap();
+        assert_eq!(job.status(), ResponseStatus::Cancelled);
+        assert!(matches!(job.wait().await, Err(ModelError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_submit_streaming_and_stream_by_id() {
+        let server = MockServer::start().await;
+        let created = sse(&[
+            serde_json::json!({"type": "response.created", "sequence_number": 0, "response": api_response("queued", None)}),
+            serde_json::json!({"type": "response.in_progress", "sequence_number": 1}),
+        ]);
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(created, "text/event-stream"))
+            .mount(&server)
+            .await;
+        let events = sse(&[
+            serde_json::json!({"type": "response.reasoning_summary_text.delta", "delta": "Thinking"}),
+            serde_json::json!({"type": "response.output_item.done", "output_index": 0, "item": {"type": "reasoning"}}),
+            serde_json::json!({"type": "response.output_item.added", "output_index": 1, "item": {"type": "function_call", "call_id": "call_1", "name": "lookup", "arguments": ""}}),
+            serde_json::json!({"type": "response.function_call_arguments.delta", "output_index": 1, "delta": "{\"q\":1}"}),
+            serde_json::json!({"type": "response.output_item.done", "output_index": 1, "item": {"type": "function_call"}}),
+            serde_json::json!({"type": "response.output_text.delta", "delta": "Hel"}),
+            serde_json::json!({"type": "response.output_text.delta", "delta": "lo"}),
+            serde_json::json!({"type": "response.completed", "response": api_response("completed", Some("Hello"))}),
+        ]);
+        Mock::given(method("GET"))
+            .and(path("/responses/resp_bg"))
+            .and(query_param("stream", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let model = OpenAIResponsesModel::new("o3-pro", "sk-test").with_base_url(server.uri());
+        let job = model
+            .submit_background(
+                &[],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(job.id(), "resp_bg");
+
+        let events: Vec<_> = job
+            .stream()
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|e| match e {
+                ModelResponseStreamEvent::PartStart(_) => "start",
+                ModelResponseStreamEvent::PartDelta(_) => "delta",
+                ModelResponseStreamEvent::PartEnd(_) => "end",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            ["start", "end", "start", "delta", "end", "start", "delta", "end"]
+        );
+        let ModelResponseStreamEvent::PartStart(start) = &events[2] else {
+            unreachable!();
+        };
+        let ModelResponsePart::ToolCall(call) = &start.part else {
+            panic!("expected a tool call, got {:?}", start.part);
+        };
+        assert_eq!(call.tool_name, "lookup");
+        assert_eq!(call.tool_call_id.as_deref(), Some("call_1"));
+    }
+}
@@ -3,6 +3,9 @@
 use super::stream::OpenAIStreamParser;
 use super::types::*;
 use crate::error::ModelError;
+use crate::http_capture::{HttpCapture, HttpExchange};
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::profile::{openai_gpt4o_profile, ModelProfile};
 use async_trait::async_trait;
@@ -10,13 +13,15 @@ use base64::Engine;
 use reqwest::header::HeaderMap;
 use reqwest::Client;
 use serdes_ai_core::messages::{
-    ImageContent, RetryPromptPart, SystemPromptPart, TextPart, ThinkingPart, ToolCallArgs,
-    ToolCallPart, ToolReturnPart, UserContent, UserContentPart, UserPromptPart,
+    instructions_as_system_prompt, ImageContent, RetryPromptPart, SystemPromptPart, TextPart,
+    ThinkingPart, ToolCallArgs, ToolCallPart, ToolReturnPart, UserContent, UserContentPart,
+    UserPromptPart,
 };
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
     RequestUsage,
 };
+use serdes_ai_output::OutputMode;
 use serdes_ai_tools::ToolDefinition;
 use std::time::Duration;
 
@@ -31,6 +36,9 @@ pub struct OpenAIChatModel {
     project: Option<String>,
     profile: ModelProfile,
     default_timeout: Duration,
+    extra_body: serde_json::Map<String, serde_json::Value>,
+    /// Capture of raw HTTP exchanges, if enabled.
+    http_capture: Option<HttpCapture>,
 }
 
 impl OpenAIChatModel {
@@ -48,6 +56,8 @@ impl OpenAIChatModel {
             project: None,
             profile,
             default_timeout: Duration::from_secs(120),
+            extra_body: serde_json::Map::new(),
+            http_capture: HttpCapture::from_env(),
         }
     }
 
@@ -94,6 +104,40 @@ impl OpenAIChatModel {
         self
     }
 
+    /// Add a provider-specific field to every request body.
+    ///
+    /// Used by OpenAI-compatible providers that accept fields the OpenAI API
+    /// doesn't know about.
+    #[must_use]
+    pub fn with_extra_body_field(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.extra_body.insert(key.into(), value.into());
+        self
+    }
+
+    /// Get the configured timeout.
+    pub fn timeout(&self) -> Duration {
+        self.default_timeout
+    }
+
+    /// Get the base URL.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Capture raw HTTP exchanges into `capture` for debugging.
+    ///
+    /// See [`http_capture`](crate::http_capture); also enabled by the
+    /// `SERDES_AI_HTTP_CAPTURE` environment variable.
+    #[must_use]
+    pub fn with_http_capture(mut self, capture: HttpCapture) -> Self {
+        self.http_capture = Some(capture);
+        self
+    }
+
     /// Set a custom profile.
     #[must_use]
     pub fn with_profile(mut self, profile: ModelProfile) -> Self {
@@ -113,7 +157,8 @@ impl OpenAIChatModel {
 
     /// Convert our messages to OpenAI format.
     fn convert_messages(&self, requests: &[ModelRequest]) -> Vec<ChatMessage> {
-        requests
+        // Chat completions have no instructions field.
+        instructions_as_system_prompt(requests)
             .iter()
             .flat_map(|req| self.convert_request(req))
             .collect()
@@ -287,19 +332,7 @@ impl OpenAIChatModel {
 
     /// Convert tool definitions to OpenAI format.
     fn convert_tools(&self, tools: &[ToolDefinition]) -> Vec<ChatTool> {
-        tools
-            .iter()
-            .map(|t| {
-                let params = serde_json::to_value(&t.parameters_json_schema)
-                    .unwrap_or(serde_json::json!({}));
-
-                if t.strict.unwrap_or(false) {
-                    ChatTool::function_strict(&t.name, &t.description, params)
-                } else {
-                    ChatTool::function(&t.name, &t.description, params)
-                }
-            })
-            .collect()
+        openai_tools(tools)
     }
 
     /// Convert tool choice.
@@ -313,7 +346,7 @@ impl OpenAIChatModel {
     }
 
     /// Build the request body.
-    fn build_request(
+    pub(crate) fn build_request(
         &self,
         messages: &[ModelRequest],
         settings: &ModelSettings,
@@ -333,10 +366,7 @@ impl OpenAIChatModel {
             .as_ref()
             .map(|c| self.convert_tool_choice(c));
 
-        let response_format = params.output_schema.as_ref().map(|schema| {
-            let schema_value = serde_json::to_value(schema).unwrap_or(serde_json::json!({}));
-            ResponseFormat::json_schema("output", schema_value, true)
-        });
+        let response_format = response_format(params);
 
         ChatCompletionRequest {
             model: self.model_name.clone(),
@@ -349,6 +379,8 @@ impl OpenAIChatModel {
             presence_penalty: settings.presence_penalty,
             frequency_penalty: settings.frequency_penalty,
             seed: settings.seed,
+            // Streamed choices would interleave, so only one is streamed.
+            n: if stream { None } else { settings.n },
             tools,
             tool_choice,
             parallel_tool_calls: settings.parallel_tool_calls,
@@ -362,19 +394,70 @@ impl OpenAIChatModel {
             } else {
                 None
             },
-            logprobs: None,
+            logprobs: settings.logprobs,
             top_logprobs: None,
+            prompt_cache_key: settings.session_affinity.clone(),
+            extra_body: self.extra_body.clone(),
         }
     }
 
     /// Parse OpenAI response to our format.
+    ///
+    /// The first choice becomes the response; further choices (requested with
+    /// `ModelSettings::n`) become its alternatives. Refused alternatives are
+    /// dropped.
     fn parse_response(&self, resp: ChatCompletionResponse) -> Result<ModelResponse, ModelError> {
-        let choice = resp
-            .choices
-            .into_iter()
+        let mut choices = resp.choices.into_iter();
+        let mut choice = choices
             .next()
             .ok_or_else(|| ModelError::invalid_response("No choices in response"))?;
+        let vendor_details = choice
+            .logprobs
+            .take()
+            .filter(|logprobs| !logprobs.is_null())
+            .map(|logprobs| serde_json::json!({ "logprobs": logprobs }));
+        let (parts, finish_reason) = Self::parse_choice(choice)?;
+        let alternatives = choices
+            .filter_map(|choice| Self::parse_choice(choice).ok())
+            .map(|(parts, finish_reason)| ModelResponse {
+                parts,
+                model_name: Some(resp.model.clone()),
+                timestamp: chrono::Utc::now(),
+                finish_reason,
+                usage: None,
+                vendor_id: Some(resp.id.clone()),
+                vendor_details: None,
+                alternatives: Vec::new(),
+                kind: "response".to_string(),
+            })
+            .collect();
+
+        let usage = resp.usage.map(|u| RequestUsage {
+            request_tokens: Some(u.prompt_tokens),
+            response_tokens: Some(u.completion_tokens),
+            total_tokens: Some(u.total_tokens),
+            cache_creation_tokens: None,
+            cache_read_tokens: u.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            details: None,
+        });
+
+        Ok(ModelResponse {
+            parts,
+            model_name: Some(resp.model),
+            timestamp: chrono::Utc::now(),
+            finish_reason,
+            usage,
+            vendor_id: Some(resp.id),
+            vendor_details,
+            alternatives,
+            kind: "response".to_string(),
+        })
+    }
 
+    /// Parse the parts and finish reason of one choice.
+    fn parse_choice(
+        choice: ChatChoice,
+    ) -> Result<(Vec<ModelResponsePart>, Option<FinishReason>), ModelError> {
         let mut parts = Vec::new();
 
         // Check for refusal
@@ -415,25 +498,7 @@ impl OpenAIChatModel {
             _ => FinishReason::Stop,
         });
 
-        let usage = resp.usage.map(|u| RequestUsage {
-            request_tokens: Some(u.prompt_tokens),
-            response_tokens: Some(u.completion_tokens),
-            total_tokens: Some(u.total_tokens),
-            cache_creation_tokens: None,
-            cache_read_tokens: u.prompt_tokens_details.and_then(|d| d.cached_tokens),
-            details: None,
-        });
-
-        Ok(ModelResponse {
-            parts,
-            model_name: Some(resp.model),
-            timestamp: chrono::Utc::now(),
-            finish_reason,
-            usage,
-            vendor_id: Some(resp.id),
-            vendor_details: None,
-            kind: "response".to_string(),
-        })
+        Ok((parts, finish_reason))
     }
 
     fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
@@ -489,6 +554,10 @@ impl Model for OpenAIChatModel {
         &self.profile
     }
 
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.http_capture.as_ref().and_then(HttpCapture::last)
+    }
+
     async fn request(
         &self,
         messages: &[ModelRequest],
@@ -502,10 +571,15 @@ impl Model for OpenAIChatModel {
         let mut request = self
             .client
             .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .timeout(timeout);
 
+        // Local OpenAI-compatible servers run without an API key
+        let api_key = settings.api_key_or(&self.api_key);
+        if !api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
         if let Some(ref org) = self.organization {
             request = request.header("OpenAI-Organization", org);
         }
@@ -513,21 +587,27 @@ impl Model for OpenAIChatModel {
             request = request.header("OpenAI-Project", project);
         }
 
-        let response = request.json(&body).send().await?;
+        let (meter, body) = RequestMeter::start(&body)?;
+        let meter = meter.capture(
+            self.http_capture.as_ref(),
+            format!("{}/chat/completions", self.base_url),
+            &body,
+        );
+        let response = request.body(body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
             let headers = response.headers().clone();
             let body = response.text().await.unwrap_or_default();
+            meter.record(status, body.as_bytes());
             return Err(self.handle_error_response(status, &body, &headers));
         }
 
-        let resp: ChatCompletionResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (resp, metrics) = meter.read_json::<ChatCompletionResponse>(response).await?;
 
-        self.parse_response(resp)
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -543,10 +623,15 @@ impl Model for OpenAIChatModel {
         let mut request = self
             .client
             .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .timeout(timeout);
 
+        // Local OpenAI-compatible servers run without an API key
+        let api_key = settings.api_key_or(&self.api_key);
+        if !api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+
         if let Some(ref org) = self.organization {
             request = request.header("OpenAI-Organization", org);
         }
@@ -554,7 +639,7 @@ impl Model for OpenAIChatModel {
             request = request.header("OpenAI-Project", project);
         }
 
-        let response = request.json(&body).send().await?;
+        let response = request.json(&body).trace_headers().send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
@@ -571,6 +656,42 @@ impl Model for OpenAIChatModel {
     }
 }
 
+/// The `response_format` for a request's output mode.
+///
+/// JSON object mode asks for any JSON object, the schema is only in the
+/// prompt. Otherwise an output schema is sent as a strict JSON schema.
+pub(crate) fn response_format(params: &ModelRequestParameters) -> Option<ResponseFormat> {
+    if params.output_mode == OutputMode::JsonObject {
+        return Some(ResponseFormat::json_object());
+    }
+    params.output_schema.as_ref().map(|schema| {
+        let schema_value = serde_json::to_value(schema).unwrap_or(serde_json::json!({}));
+        ResponseFormat::json_schema("output", schema_value, true)
+    })
+}
+
+/// Convert tool definitions to the `tools` entries of a chat completions
+/// request.
+///
+/// This is exactly what [`OpenAIChatModel`] sends; serialize the result to see
+/// the JSON. Strict tools get `"strict": true`.
+#[must_use]
+pub fn openai_tools(tools: &[ToolDefinition]) -> Vec<ChatTool> {
+    tools
+        .iter()
+        .map(|t| {
+            let params =
+                serde_json::to_value(&t.parameters_json_schema).unwrap_or(serde_json::json!({}));
+
+            if t.strict.unwrap_or(false) {
+                ChatTool::function_strict(&t.name, &t.description, params)
+            } else {
+                ChatTool::function(&t.name, &t.description, params)
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -631,19 +752,37 @@ mod tests {
         assert_eq!(converted[0].function.name, "search");
     }
 
+    #[test]
+    fn test_openai_tools_json() {
+        use serdes_ai_tools::ObjectJsonSchema;
+
+        let tools = vec![ToolDefinition::new("search", "Search the web")
+            .with_parameters(ObjectJsonSchema::new())
+            .with_strict(true)];
+
+        let json = serde_json::to_value(openai_tools(&tools)).unwrap();
+        assert_eq!(json[0]["type"], "function");
+        assert_eq!(json[0]["function"]["name"], "search");
+        assert_eq!(json[0]["function"]["strict"], true);
+        assert_eq!(json[0]["function"]["parameters"]["type"], "object");
+    }
+
     #[test]
     fn test_build_request() {
         let model = OpenAIChatModel::new("gpt-4o", "key");
         let mut req = ModelRequest::new();
         req.add_user_prompt("Hello");
         let messages = vec![req];
-        let settings = ModelSettings::new().temperature(0.7);
+        let settings = ModelSettings::new()
+            .temperature(0.7)
+            .session_affinity("conversation-1");
         let params = ModelRequestParameters::new();
 
         let req = model.build_request(&messages, &settings, &params, false);
 
         assert_eq!(req.model, "gpt-4o");
         assert_eq!(req.temperature, Some(0.7));
+        assert_eq!(req.prompt_cache_key.as_deref(), Some("conversation-1"));
         assert!(req.stream.is_none());
     }
 
@@ -661,4 +800,127 @@ mod tests {
         assert_eq!(req.stream, Some(true));
         assert!(req.stream_options.is_some());
     }
+
+    #[test]
+    fn test_json_object_response_format() {
+        use serdes_ai_tools::ObjectJsonSchema;
+
+        let model = OpenAIChatModel::new("gpt-3.5-turbo", "key");
+        let settings = ModelSettings::new();
+
+        let params = ModelRequestParameters::new()
+            .with_output_schema(ObjectJsonSchema::new())
+            .with_output_mode(OutputMode::JsonObject);
+        let req = model.build_request(&[], &settings, &params, false);
+        let format = req.response_format.unwrap();
+        assert_eq!(format.format_type, "json_object");
+        assert!(format.json_schema.is_none());
+
+        let params = params.with_output_mode(OutputMode::Native);
+        let req = model.build_request(&[], &settings, &params, false);
+        assert_eq!(req.response_format.unwrap().format_type, "json_schema");
+
+        let req = model.build_request(&[], &settings, &ModelRequestParameters::new(), false);
+        assert!(req.response_format.is_none());
+    }
+    #[tokio::test]
+    async fn test_http_capture() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": {"message": "Invalid 'messages': empty array", "type": "invalid_request_error"}
+            })))
+            .mount(&server)
+            .await;
+
+        let capture = HttpCapture::new(4);
+        let model = OpenAIChatModel::new("gpt-4o", "sk-secret")
+            .with_base_url(server.uri())
+            .with_http_capture(capture.clone());
+        assert!(model.last_exchange().is_none());
+
+        let settings = ModelSettings::new().max_tokens(5);
+        let err = model
+            .request(&[], &settings, &ModelRequestParameters::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ModelError::Api { .. }));
+
+        let exchange = model.last_exchange().unwrap();
+        assert_eq!(exchange.status, 400);
+        assert!(exchange.url.ends_with("/chat/completions"));
+        assert_eq!(exchange.request_body["model"], "gpt-4o");
+        assert_eq!(exchange.request_body["max_tokens"], 5);
+        assert_eq!(
+            exchange.response_body["error"]["type"],
+            "invalid_request_error"
+        );
+        assert_eq!(capture.len(), 1);
+    }
+
+    #[test]
+    fn test_n_choices() {
+        let model = OpenAIChatModel::new("gpt-4o", "key");
+        let settings = ModelSettings::new().n(3);
+        let params = ModelRequestParameters::new();
+        assert_eq!(
+            model.build_request(&[], &settings, &params, false).n,
+            Some(3)
+        );
+        assert_eq!(model.build_request(&[], &settings, &params, true).n, None);
+
+        let resp: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-4o",
+            "choices": [
+                {"index": 0, "message": {"role": "assistant", "content": "a"}, "finish_reason": "stop"},
+                {"index": 1, "message": {"role": "assistant", "refusal": "no"}, "finish_reason": "stop"},
+                {"index": 2, "message": {"role": "assistant", "content": "c"}, "finish_reason": "length"}
+            ],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 3, "total_tokens": 8}
+        }))
+        .unwrap();
+        let response = model.parse_response(resp).unwrap();
+
+        let texts: Vec<_> = response.choices().map(|r| r.text_content()).collect();
+        assert_eq!(texts, ["a", "c"]);
+        assert_eq!(
+            response.alternatives[0].finish_reason,
+            Some(FinishReason::Length)
+        );
+        assert!(response.alternatives[0].usage.is_none());
+        assert_eq!(response.usage.unwrap().total_tokens, Some(8));
+    }
+
+    #[test]
+    fn test_logprobs() {
+        let model = OpenAIChatModel::new("gpt-4o", "key");
+        let settings = ModelSettings::new().logprobs(true);
+        let params = ModelRequestParameters::new();
+        assert_eq!(
+            model.build_request(&[], &settings, &params, false).logprobs,
+            Some(true)
+        );
+
+        let resp: ChatCompletionResponse = serde_json::from_value(serde_json::json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1,
+            "model": "gpt-4o",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Yes"},
+                "finish_reason": "stop",
+                "logprobs": {"content": [{"token": "Yes", "logprob": -0.01, "top_logprobs": []}]}
+            }]
+        }))
+        .unwrap();
+        let response = model.parse_response(resp).unwrap();
+        assert_eq!(response.token_logprobs(), Some(vec![-0.01]));
+    }
 }
@@ -30,16 +30,18 @@
 //! let response = model.request(&messages, &settings, &params).await?;
 //! ```
 
+pub mod background;
 pub mod chat;
 pub mod responses;
 pub mod stream;
 pub mod types;
 
 // Re-exports
-pub use chat::OpenAIChatModel;
+pub use background::{BackgroundResponse, ResponsesStreamAdapter, ResponsesStreamChunk};
+pub use chat::{openai_tools, OpenAIChatModel};
 pub use responses::{
     OpenAIResponsesModel, OpenAIResponsesModelSettings, ReasoningEffort, ReasoningSummary,
-    ServiceTier, TruncationMode,
+    ResponseInclude, ServiceTier, TruncationMode,
 };
 pub use types::{
     ChatCompletionChunk, ChatCompletionRequest, ChatCompletionResponse, ChatMessage, ChatTool,
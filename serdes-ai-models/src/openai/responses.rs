@@ -8,18 +8,37 @@
 //! - Has native reasoning support with configurable effort
 //! - Supports built-in tools (web search, code interpreter, file search, etc.)
 //! - Different output format with `ResponseOutputItem` variants
+//! - Responses can be stored on the server, retrieved by ID and continued
+//!   with `previous_response_id` instead of resending the history
+//!
+//! ```rust,ignore
+//! use serdes_ai_models::openai::{OpenAIResponsesModel, ResponseInclude};
+//!
+//! let model = OpenAIResponsesModel::from_env("o3")?
+//!     .with_store(true)
+//!     .with_response_chaining(true)
+//!     .with_include([ResponseInclude::FileSearchResults]);
+//!
+//! // Later turns only send the messages after the last response
+//! let response = model.request(&messages, &settings, &params).await?;
+//! let again = model.retrieve(response.vendor_id.as_deref().unwrap()).await?;
+//! ```
 
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse};
 use crate::profile::{openai_o1_profile, ModelProfile};
+use crate::stream_adapter::PartTracker;
 use async_trait::async_trait;
 use base64::Engine;
-use reqwest::Client;
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use serdes_ai_core::messages::{
-    ImageContent, RetryPromptPart, TextPart, ThinkingPart, ToolCallArgs, ToolCallPart,
-    ToolReturnPart, UserContent, UserContentPart, UserPromptPart,
+    latest_instructions, ImageContent, RetryPromptPart, TextPart, ThinkingPart, ToolCallArgs,
+    ToolCallPart, ToolReturnPart, UserContent, UserContentPart, UserPromptPart,
 };
 use serdes_ai_core::{
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
@@ -58,6 +77,16 @@ pub struct OpenAIResponsesModelSettings {
 
     /// Previous response ID for continuation
     pub previous_response_id: Option<String>,
+
+    /// Whether OpenAI stores responses for later retrieval and chaining
+    pub store: Option<bool>,
+
+    /// Additional output data to include in responses
+    pub include: Vec<ResponseInclude>,
+
+    /// Continue from the last stored response in the history instead of
+    /// resending the whole conversation
+    pub chain_responses: bool,
 }
 
 /// Reasoning effort level for reasoning models.
@@ -174,6 +203,49 @@ impl Serialize for TruncationMode {
     }
 }
 
+/// Additional output data for the `include[]` parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseInclude {
+    /// Search results of file search calls.
+    FileSearchResults,
+    /// Sources of web search calls.
+    WebSearchSources,
+    /// Outputs of code interpreter calls.
+    CodeInterpreterOutputs,
+    /// Image URLs of input messages.
+    InputImageUrls,
+    /// Log probabilities of output text.
+    OutputTextLogprobs,
+    /// Encrypted reasoning, to continue reasoning when responses are not stored.
+    ReasoningEncryptedContent,
+    /// Any other value.
+    Other(String),
+}
+
+impl ResponseInclude {
+    /// Get the API value.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::FileSearchResults => "file_search_call.results",
+            Self::WebSearchSources => "web_search_call.action.sources",
+            Self::CodeInterpreterOutputs => "code_interpreter_call.outputs",
+            Self::InputImageUrls => "message.input_image.image_url",
+            Self::OutputTextLogprobs => "message.output_text.logprobs",
+            Self::ReasoningEncryptedContent => "reasoning.encrypted_content",
+            Self::Other(value) => value,
+        }
+    }
+}
+
+impl Serialize for ResponseInclude {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 // ============================================================================
 // Responses API Request Types
 // ============================================================================
@@ -205,6 +277,9 @@ pub struct ResponsesApiRequest {
     pub top_p: Option<f64>,
     /// Whether to stream the response.
     pub stream: bool,
+    /// Run the response in the background.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>,
     /// Previous response ID for multi-turn.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub previous_response_id: Option<String>,
@@ -220,9 +295,15 @@ pub struct ResponsesApiRequest {
     /// Store response for later retrieval.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub store: Option<bool>,
+    /// Additional output data to include.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<ResponseInclude>>,
     /// Metadata for the request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<JsonValue>,
+    /// Key grouping requests that share a prompt prefix for caching.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
 }
 
 /// Reasoning configuration.
@@ -449,12 +530,17 @@ pub struct ResponsesApiResponse {
     pub metadata: Option<JsonValue>,
     /// Service tier used.
     pub service_tier: Option<String>,
+    /// ID of the response this one continues.
+    #[serde(default)]
+    pub previous_response_id: Option<String>,
 }
 
 /// Response status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ResponseStatus {
+    /// Background response is waiting to start.
+    Queued,
     /// Response is complete.
     Completed,
     /// Response failed.
@@ -715,6 +801,47 @@ impl OpenAIResponsesModel {
         self
     }
 
+    /// Set whether OpenAI stores responses.
+    ///
+    /// Stored responses can be fetched with [`retrieve`](Self::retrieve) and
+    /// continued with `previous_response_id`. The API stores responses unless
+    /// told otherwise.
+    #[must_use]
+    pub fn with_store(mut self, store: bool) -> Self {
+        self.default_settings.store = Some(store);
+        self
+    }
+
+    /// Request additional output data, also when retrieving responses.
+    #[must_use]
+    pub fn with_include(mut self, include: impl IntoIterator<Item = ResponseInclude>) -> Self {
+        self.default_settings.include.extend(include);
+        self
+    }
+
+    /// Continue from a stored response.
+    ///
+    /// The whole history is still sent; see
+    /// [`with_response_chaining`](Self::with_response_chaining) to only send
+    /// new messages.
+    #[must_use]
+    pub fn with_previous_response_id(mut self, id: impl Into<String>) -> Self {
+        self.default_settings.previous_response_id = Some(id.into());
+        self
+    }
+
+    /// Chain requests through stored responses.
+    ///
+    /// When the last response in the history came from the Responses API,
+    /// only the messages after it are sent, with `previous_response_id` set
+    /// to its ID, so the server keeps the conversation state. Has no effect
+    /// when responses are not stored.
+    #[must_use]
+    pub fn with_response_chaining(mut self, enabled: bool) -> Self {
+        self.default_settings.chain_responses = enabled;
+        self
+    }
+
     /// Set the organization ID.
     #[must_use]
     pub fn with_organization(mut self, org: impl Into<String>) -> Self {
@@ -803,6 +930,14 @@ impl OpenAIResponsesModel {
             }
         }
 
+        // Request instructions follow the system prompt.
+        if let Some(latest) = latest_instructions(messages) {
+            instructions = Some(match instructions {
+                Some(system) => format!("{}\n\n{}", system, latest),
+                None => latest.to_string(),
+            });
+        }
+
         (inputs, instructions)
     }
 
@@ -901,6 +1036,39 @@ impl OpenAIResponsesModel {
         }
     }
 
+    /// Split the history after the last response, for chaining.
+    ///
+    /// Returns the response ID and the messages after it, if the response
+    /// was stored by the Responses API and new messages follow it.
+    fn chained_tail(&self, messages: &[ModelRequest]) -> Option<(String, Vec<ModelRequest>)> {
+        if !self.default_settings.chain_responses || self.default_settings.store == Some(false) {
+            return None;
+        }
+        let (req_idx, part_idx, response) =
+            messages.iter().enumerate().rev().find_map(|(i, req)| {
+                req.parts
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(j, part)| match part {
+                        ModelRequestPart::ModelResponse(response) => Some((i, j, response)),
+                        _ => None,
+                    })
+            })?;
+        let id = response
+            .vendor_id
+            .as_deref()
+            .filter(|id| id.starts_with("resp_"))?;
+
+        let mut first = messages[req_idx].clone();
+        first.parts.drain(..=part_idx);
+        let tail: Vec<ModelRequest> = std::iter::once(first)
+            .chain(messages[req_idx + 1..].iter().cloned())
+            .filter(|req| !req.parts.is_empty())
+            .collect();
+        (!tail.is_empty()).then(|| (id.to_string(), tail))
+    }
+
     /// Convert tool definitions to Responses API format.
     fn convert_tools(&self, tools: &[ToolDefinition]) -> Vec<ResponseTool> {
         tools
@@ -920,14 +1088,19 @@ impl OpenAIResponsesModel {
     }
 
     /// Build the request body.
-    fn build_request(
+    pub(crate) fn build_request(
         &self,
         messages: &[ModelRequest],
         settings: &ModelSettings,
         params: &ModelRequestParameters,
         stream: bool,
     ) -> ResponsesApiRequest {
-        let (input, instructions) = self.map_messages(messages, &self.default_settings);
+        let (mut input, instructions) = self.map_messages(messages, &self.default_settings);
+        let mut previous_response_id = self.default_settings.previous_response_id.clone();
+        if let Some((id, tail)) = self.chained_tail(messages) {
+            input = self.map_messages(&tail, &self.default_settings).0;
+            previous_response_id = Some(id);
+        }
 
         let tools = if params.tools.is_empty() {
             None
@@ -961,17 +1134,24 @@ impl OpenAIResponsesModel {
             temperature: settings.temperature,
             top_p: settings.top_p,
             stream,
-            previous_response_id: self.default_settings.previous_response_id.clone(),
+            background: None,
+            previous_response_id,
             service_tier: self.default_settings.service_tier,
             truncation,
             user: None,
-            store: None,
+            store: self.default_settings.store,
+            include: (!self.default_settings.include.is_empty())
+                .then(|| self.default_settings.include.clone()),
             metadata: None,
+            prompt_cache_key: settings.session_affinity.clone(),
         }
     }
 
     /// Parse the Responses API response into our format.
-    fn process_response(&self, resp: ResponsesApiResponse) -> Result<ModelResponse, ModelError> {
+    pub(crate) fn process_response(
+        &self,
+        resp: ResponsesApiResponse,
+    ) -> Result<ModelResponse, ModelError> {
         // Check for errors
         if resp.status == ResponseStatus::Failed {
             if let Some(err) = resp.error {
@@ -1073,12 +1253,13 @@ impl OpenAIResponsesModel {
             usage,
             vendor_id: Some(resp.id),
             vendor_details: resp.metadata,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         })
     }
 
     /// Handle API error response.
-    fn handle_error_response(&self, status: u16, body: &str) -> ModelError {
+    pub(crate) fn handle_error_response(&self, status: u16, body: &str) -> ModelError {
         // Try to parse as OpenAI error
         if let Ok(err) = serde_json::from_str::<super::types::OpenAIError>(body) {
             let code = err.error.code.clone();
@@ -1101,6 +1282,109 @@ impl OpenAIResponsesModel {
 
         ModelError::http(status, body)
     }
+
+    /// Add the authentication and organization headers.
+    fn authorize(&self, request: RequestBuilder, api_key: &str) -> RequestBuilder {
+        let mut request = request.header("Authorization", format!("Bearer {}", api_key));
+        if let Some(ref org) = self.organization {
+            request = request.header("OpenAI-Organization", org);
+        }
+        if let Some(ref project) = self.project {
+            request = request.header("OpenAI-Project", project);
+        }
+        request.trace_headers()
+    }
+
+    /// Build a request creating a response.
+    pub(crate) fn create_request(&self, settings: &ModelSettings) -> RequestBuilder {
+        let timeout = settings.timeout.unwrap_or(self.default_timeout);
+        self.authorize(
+            self.client.post(format!("{}/responses", self.base_url)),
+            settings.api_key_or(&self.api_key),
+        )
+        .timeout(timeout)
+    }
+
+    /// Build a request for a stored response, or one of its `action`s.
+    pub(crate) fn response_request(
+        &self,
+        method: Method,
+        id: &str,
+        action: Option<&str>,
+    ) -> Result<RequestBuilder, ModelError> {
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ModelError::configuration(format!(
+                "Invalid response ID '{}'",
+                id
+            )));
+        }
+        let mut url = format!("{}/responses/{}", self.base_url, id);
+        if let Some(action) = action {
+            url = format!("{}/{}", url, action);
+        }
+        Ok(self.authorize(self.client.request(method, url), &self.api_key))
+    }
+
+    /// Send a request and parse its JSON body.
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, ModelError> {
+        let response = request.send().await?;
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(self.handle_error_response(status, &body));
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a stored response by ID, as returned by the API.
+    ///
+    /// The [`with_include`](Self::with_include) values are requested too.
+    pub async fn retrieve_response(&self, id: &str) -> Result<ResponsesApiResponse, ModelError> {
+        let include: Vec<_> = self
+            .default_settings
+            .include
+            .iter()
+            .map(|i| ("include[]", i.as_str()))
+            .collect();
+        let request = self
+            .response_request(Method::GET, id, None)?
+            .query(&include)
+            .timeout(self.default_timeout);
+        self.send_json(request).await
+    }
+
+    /// Retrieve a stored response by ID.
+    ///
+    /// The ID of a response is its [`ModelResponse::vendor_id`].
+    pub async fn retrieve(&self, id: &str) -> Result<ModelResponse, ModelError> {
+        let response = self.retrieve_response(id).await?;
+        self.process_response(response)
+    }
+
+    /// Cancel a background response, returning it as cancelled.
+    ///
+    /// Cancelling a response that already finished has no effect.
+    pub async fn cancel_response(&self, id: &str) -> Result<ResponsesApiResponse, ModelError> {
+        let request = self
+            .response_request(Method::POST, id, Some("cancel"))?
+            .timeout(self.default_timeout);
+        self.send_json(request).await
+    }
+
+    /// Delete a stored response.
+    pub async fn delete_response(&self, id: &str) -> Result<(), ModelError> {
+        let request = self
+            .response_request(Method::DELETE, id, None)?
+            .timeout(self.default_timeout);
+        self.send_json::<JsonValue>(request).await.map(|_| ())
+    }
 }
 
 #[async_trait]
@@ -1125,23 +1409,12 @@ impl Model for OpenAIResponsesModel {
     ) -> Result<ModelResponse, ModelError> {
         let body = self.build_request(messages, settings, params, false);
 
-        let timeout = settings.timeout.unwrap_or(self.default_timeout);
-
-        let mut request = self
-            .client
-            .post(format!("{}/responses", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .timeout(timeout);
+        let request = self
+            .create_request(settings)
+            .header("Content-Type", "application/json");
 
-        if let Some(ref org) = self.organization {
-            request = request.header("OpenAI-Organization", org);
-        }
-        if let Some(ref project) = self.project {
-            request = request.header("OpenAI-Project", project);
-        }
-
-        let response = request.json(&body).send().await?;
+        let (meter, body) = RequestMeter::start(&body)?;
+        let response = request.body(body).send().await?;
 
         let status = response.status().as_u16();
         if !response.status().is_success() {
@@ -1149,12 +1422,11 @@ impl Model for OpenAIResponsesModel {
             return Err(self.handle_error_response(status, &body));
         }
 
-        let resp: ResponsesApiResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
+        let (resp, metrics) = meter.read_json::<ResponsesApiResponse>(response).await?;
 
-        self.process_response(resp)
+        let mut response = self.process_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -1167,22 +1439,9 @@ impl Model for OpenAIResponsesModel {
         // TODO: Implement proper streaming with ResponsesStreamParser
         let response = self.request(messages, settings, params).await?;
 
-        // Convert to a single-event stream
-        let events: Vec<Result<serdes_ai_core::messages::ModelResponseStreamEvent, ModelError>> =
-            response
-                .parts
-                .into_iter()
-                .enumerate()
-                .map(|(idx, part)| {
-                    Ok(
-                        serdes_ai_core::messages::ModelResponseStreamEvent::PartStart(
-                            serdes_ai_core::messages::PartStartEvent::new(idx, part),
-                        ),
-                    )
-                })
-                .collect();
-
-        Ok(Box::pin(futures::stream::iter(events)))
+        // Replay the complete response as a stream
+        let events = PartTracker::new().replay(response.parts);
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
     }
 }
 
@@ -1241,6 +1500,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_map_messages_instructions() {
+        let model = OpenAIResponsesModel::new("o3-mini", "sk-test");
+        let mut first = ModelRequest::new().with_instructions("Stale.");
+        first.add_system_prompt("You are helpful.");
+        first.add_user_prompt("Hi");
+        let mut second = ModelRequest::new().with_instructions("Be brief.");
+        second.add_user_prompt("Again");
+
+        let (inputs, instructions) =
+            model.map_messages(&[first, second], &OpenAIResponsesModelSettings::default());
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(
+            instructions.as_deref(),
+            Some("You are helpful.\n\nBe brief.")
+        );
+    }
+
     #[test]
     fn test_response_tool_serialization() {
         let tool = ResponseTool::Function {
@@ -1284,4 +1561,118 @@ mod tests {
         assert!(json.contains("\"role\":\"tool\""));
         assert!(json.contains("\"tool_call_id\":\"call_123\""));
     }
+
+    fn stored_history() -> Vec<ModelRequest> {
+        let mut first = ModelRequest::new();
+        first.add_system_prompt("You are helpful.");
+        first.add_user_prompt("Hi");
+        let mut answered = ModelRequest::new();
+        let mut response = ModelResponse::text("Hello!");
+        response.vendor_id = Some("resp_1".to_string());
+        answered
+            .parts
+            .push(ModelRequestPart::ModelResponse(Box::new(response)));
+        answered.add_user_prompt("How are you?");
+        vec![first, answered]
+    }
+
+    #[test]
+    fn test_store_and_include_serialization() {
+        let model = OpenAIResponsesModel::new("o3", "sk-test")
+            .with_store(false)
+            .with_include([
+                ResponseInclude::ReasoningEncryptedContent,
+                ResponseInclude::Other("custom.field".to_string()),
+            ]);
+        let body = model.build_request(
+            &stored_history(),
+            &ModelSettings::default(),
+            &ModelRequestParameters::new(),
+            false,
+        );
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["store"], false);
+        assert_eq!(
+            json["include"],
+            serde_json::json!(["reasoning.encrypted_content", "custom.field"])
+        );
+    }
+
+    #[test]
+    fn test_response_chaining() {
+        let params = ModelRequestParameters::new();
+        let settings = ModelSettings::default();
+        let model = OpenAIResponsesModel::new("o3", "sk-test").with_response_chaining(true);
+        let body = model.build_request(&stored_history(), &settings, &params, false);
+        assert_eq!(body.previous_response_id.as_deref(), Some("resp_1"));
+        assert_eq!(body.input.len(), 1);
+        assert!(matches!(
+            &body.input[0],
+            ResponseInput::User { content: ResponseInputContent::Text(t) } if t == "How are you?"
+        ));
+        assert_eq!(body.instructions.as_deref(), Some("You are helpful."));
+
+        // Not stored, or not chained: the whole history is sent
+        let unstored = model.clone().with_store(false);
+        let body = unstored.build_request(&stored_history(), &settings, &params, false);
+        assert_eq!(body.previous_response_id, None);
+        assert_eq!(body.input.len(), 3);
+        let body = OpenAIResponsesModel::new("o3", "sk-test").build_request(
+            &stored_history(),
+            &settings,
+            &params,
+            false,
+        );
+        assert_eq!(body.input.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_and_delete_response() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/responses/resp_42"))
+            .and(query_param("include[]", "file_search_call.results"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "resp_42",
+                "object": "response",
+                "created_at": 1_700_000_000,
+                "model": "o3",
+                "status": "completed",
+                "previous_response_id": "resp_41",
+                "output": [{
+                    "type": "message",
+                    "id": "msg_1",
+                    "role": "assistant",
+                    "content": [{"type": "output_text", "text": "Stored answer"}]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/responses/resp_42"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"id": "resp_42", "object": "response", "deleted": true}),
+            ))
+            .mount(&server)
+            .await;
+
+        let model = OpenAIResponsesModel::new("o3", "sk-test")
+            .with_base_url(server.uri())
+            .with_include([ResponseInclude::FileSearchResults]);
+        let raw = model.retrieve_response("resp_42").await.unwrap();
+        assert_eq!(raw.previous_response_id.as_deref(), Some("resp_41"));
+        let response = model.retrieve("resp_42").await.unwrap();
+        assert_eq!(response.text_content(), "Stored answer");
+        assert_eq!(response.vendor_id.as_deref(), Some("resp_42"));
+        model.delete_response("resp_42").await.unwrap();
+
+        assert!(matches!(
+            model.retrieve("resp_404").await.unwrap_err(),
+            ModelError::Http { status: 404, .. }
+        ));
+        assert!(model.retrieve("../models").await.is_err());
+    }
 }
@@ -4,41 +4,22 @@
 
 use super::types::ChatCompletionChunk;
 use crate::error::ModelError;
-use bytes::Bytes;
-use futures::Stream;
-use pin_project_lite::pin_project;
+use crate::stream_adapter::{AdapterStream, PartKey, PartTracker, StreamAdapter};
 use serdes_ai_core::messages::{
-    ModelResponseStreamEvent, PartDeltaEvent, PartEndEvent, PartStartEvent, TextPart, ThinkingPart,
-    ThinkingPartDelta, ToolCallArgs, ToolCallPart,
+    ModelResponsePartDelta, ModelResponseStreamEvent, ToolCallArgs, ToolCallPart, ToolCallPartDelta,
 };
 use serdes_ai_core::ModelResponsePart;
 use std::collections::HashMap;
-use std::pin::Pin;
-use std::task::{Context, Poll};
-
-pin_project! {
-    /// OpenAI SSE stream parser.
-    pub struct OpenAIStreamParser<S> {
-        #[pin]
-        inner: S,
-        buffer: String,
-        // Track tool calls in progress (index -> accumulated data)
-        tool_calls: HashMap<u32, ToolCallState>,
-        // Track text part state
-        text_started: bool,
-        // Track if we've emitted a part start for text
-        current_text_index: Option<usize>,
-        // Track thinking/reasoning part state
-        thinking_started: bool,
-        // Track if we've emitted a part start for thinking
-        current_thinking_index: Option<usize>,
-        // Next part index to use
-        next_part_index: usize,
-        // Finished
-        done: bool,
-        // Pending PartEnd events to emit (queued when multiple parts close at once)
-        pending_part_ends: Vec<usize>,
-    }
+
+/// OpenAI SSE stream parser.
+pub type OpenAIStreamParser<S> = AdapterStream<S, OpenAIStreamAdapter>;
+
+/// Maps OpenAI chat completion chunks to stream events.
+#[derive(Debug, Default)]
+pub struct OpenAIStreamAdapter {
+    parts: PartTracker,
+    // Track tool calls in progress (index -> accumulated data)
+    tool_calls: HashMap<u32, ToolCallState>,
 }
 
 /// State for an in-progress tool call.
@@ -47,291 +28,86 @@ struct ToolCallState {
     id: String,
     name: String,
     arguments: String,
-    part_index: usize,
-    started: bool,
-}
-
-impl<S> OpenAIStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    /// Create a new stream parser.
-    pub fn new(inner: S) -> Self {
-        Self {
-            inner,
-            buffer: String::new(),
-            tool_calls: HashMap::new(),
-            text_started: false,
-            current_text_index: None,
-            thinking_started: false,
-            current_thinking_index: None,
-            next_part_index: 0,
-            done: false,
-            pending_part_ends: Vec::new(),
-        }
-    }
 }
 
-impl<S> Stream for OpenAIStreamParser<S>
-where
-    S: Stream<Item = Result<Bytes, reqwest::Error>>,
-{
-    type Item = Result<ModelResponseStreamEvent, ModelError>;
+impl StreamAdapter for OpenAIStreamAdapter {
+    type Chunk = ChatCompletionChunk;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
+    fn map_chunk(
+        &mut self,
+        chunk: ChatCompletionChunk,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+        let mut events = Vec::new();
 
-        if *this.done {
-            return Poll::Ready(None);
-        }
+        for choice in chunk.choices {
+            let delta = choice.delta;
 
-        // First, emit any pending PartEnd events
-        if let Some(idx) = this.pending_part_ends.pop() {
-            return Poll::Ready(Some(Ok(ModelResponseStreamEvent::PartEnd(PartEndEvent {
-                index: idx,
-            }))));
-        }
+            // Handle text content
+            if let Some(content) = delta.content {
+                if !content.is_empty() {
+                    events.push(self.parts.text(&content));
+                }
+            }
 
-        loop {
-            // Check if we have complete lines in the buffer
-            while let Some(newline_pos) = this.buffer.find('\n') {
-                let line = this.buffer.drain(..=newline_pos).collect::<String>();
-                if let Some(event) = parse_sse_line(
-                    &line,
-                    this.tool_calls,
-                    this.text_started,
-                    this.current_text_index,
-                    this.thinking_started,
-                    this.current_thinking_index,
-                    this.next_part_index,
-                    this.done,
-                    this.pending_part_ends,
-                ) {
-                    return Poll::Ready(Some(event));
+            // Handle reasoning/thinking content (for models like GLM-4)
+            if let Some(reasoning) = delta.reasoning_content {
+                if !reasoning.is_empty() {
+                    events.push(self.parts.thinking(&reasoning));
                 }
             }
 
-            // Need more data
-            match this.inner.as_mut().poll_next(cx) {
-                Poll::Ready(Some(Ok(bytes))) => {
-                    if let Ok(text) = std::str::from_utf8(&bytes) {
-                        this.buffer.push_str(text);
-                    }
-                    // Continue to process buffer
+            // Handle tool calls
+            for tc in delta.tool_calls.unwrap_or_default() {
+                let state = self.tool_calls.entry(tc.index).or_default();
+                if let Some(id) = tc.id {
+                    state.id = id;
                 }
-                Poll::Ready(Some(Err(e))) => {
-                    return Poll::Ready(Some(Err(ModelError::Other(e.into()))));
+                let Some(func) = tc.function else {
+                    continue;
+                };
+                if let Some(name) = func.name {
+                    state.name = name;
                 }
-                Poll::Ready(None) => {
-                    // Stream ended, process any remaining buffer
-                    if !this.buffer.is_empty() {
-                        let remaining = std::mem::take(this.buffer);
-                        for line in remaining.lines() {
-                            if let Some(event) = parse_sse_line(
-                                line,
-                                this.tool_calls,
-                                this.text_started,
-                                this.current_text_index,
-                                this.thinking_started,
-                                this.current_thinking_index,
-                                this.next_part_index,
-                                this.done,
-                                this.pending_part_ends,
-                            ) {
-                                return Poll::Ready(Some(event));
-                            }
-                        }
+                let Some(args) = func.arguments else {
+                    continue;
+                };
+                state.arguments.push_str(&args);
+
+                let key = PartKey::ToolCall(u64::from(tc.index));
+                let delta = ModelResponsePartDelta::ToolCall(ToolCallPartDelta::new(&args));
+                match self.parts.delta(&key, delta) {
+                    Some(event) => events.push(event),
+                    None => {
+                        // Include the accumulated args in PartStart (some providers
+                        // like Cerebras send all tool call data in one chunk)
+                        let tool_part = ToolCallPart::new(
+                            &state.name,
+                            ToolCallArgs::String(state.arguments.clone()),
+                        )
+                        .with_tool_call_id(&state.id);
+                        events.push(
+                            self.parts
+                                .start(key, ModelResponsePart::ToolCall(tool_part)),
+                        );
                     }
-                    return Poll::Ready(None);
                 }
-                Poll::Pending => return Poll::Pending,
             }
-        }
-    }
-}
 
-/// Parse a single SSE line into an event.
-#[allow(clippy::too_many_arguments)]
-fn parse_sse_line(
-    line: &str,
-    tool_calls: &mut HashMap<u32, ToolCallState>,
-    text_started: &mut bool,
-    current_text_index: &mut Option<usize>,
-    thinking_started: &mut bool,
-    current_thinking_index: &mut Option<usize>,
-    next_part_index: &mut usize,
-    done: &mut bool,
-    pending_part_ends: &mut Vec<usize>,
-) -> Option<Result<ModelResponseStreamEvent, ModelError>> {
-    let line = line.trim();
-
-    // Skip empty lines and comments
-    if line.is_empty() || line.starts_with(':') {
-        return None;
-    }
-
-    // Handle data lines
-    if let Some(data) = line.strip_prefix("data: ") {
-        // Check for stream end
-        if data == "[DONE]" {
-            *done = true;
-            return None;
-        }
-
-        // Parse the JSON chunk
-        match serde_json::from_str::<ChatCompletionChunk>(data) {
-            Ok(chunk) => {
-                // Process each choice
-                for choice in chunk.choices {
-                    let delta = choice.delta;
-
-                    // Handle text content
-                    if let Some(content) = delta.content {
-                        if !content.is_empty() {
-                            // Start text part if not started
-                            if !*text_started {
-                                *text_started = true;
-                                *current_text_index = Some(*next_part_index);
-                                *next_part_index += 1;
-
-                                // Include the first content in the PartStart
-                                let start = PartStartEvent::new(
-                                    current_text_index.unwrap(),
-                                    ModelResponsePart::Text(TextPart::new(&content)),
-                                );
-                                return Some(Ok(ModelResponseStreamEvent::PartStart(start)));
-                            }
-
-                            // Emit text delta for subsequent content
-                            let delta = PartDeltaEvent::text(current_text_index.unwrap(), content);
-                            return Some(Ok(ModelResponseStreamEvent::PartDelta(delta)));
-                        }
-                    }
-
-                    // Handle reasoning/thinking content (for models like GLM-4)
-                    if let Some(reasoning) = delta.reasoning_content {
-                        if !reasoning.is_empty() {
-                            // Start thinking part if not started
-                            if !*thinking_started {
-                                *thinking_started = true;
-                                *current_thinking_index = Some(*next_part_index);
-                                *next_part_index += 1;
-
-                                // Include the first content in the PartStart
-                                let start = PartStartEvent::new(
-                                    current_thinking_index.unwrap(),
-                                    ModelResponsePart::Thinking(ThinkingPart::new(&reasoning)),
-                                );
-                                return Some(Ok(ModelResponseStreamEvent::PartStart(start)));
-                            }
-
-                            // Emit thinking delta for subsequent content
-                            let delta_event = PartDeltaEvent {
-                                index: current_thinking_index.unwrap(),
-                                delta: serdes_ai_core::messages::ModelResponsePartDelta::Thinking(
-                                    ThinkingPartDelta::new(reasoning),
-                                ),
-                            };
-                            return Some(Ok(ModelResponseStreamEvent::PartDelta(delta_event)));
-                        }
-                    }
-
-                    // Handle tool calls
-                    if let Some(tcs) = delta.tool_calls {
-                        for tc in tcs {
-                            let state = tool_calls.entry(tc.index).or_insert_with(|| {
-                                let part_index = *next_part_index;
-                                *next_part_index += 1;
-                                ToolCallState {
-                                    part_index,
-                                    ..Default::default()
-                                }
-                            });
-
-                            // Update state
-                            if let Some(id) = tc.id {
-                                state.id = id;
-                            }
-                            if let Some(func) = tc.function {
-                                if let Some(name) = func.name {
-                                    state.name = name;
-                                }
-                                if let Some(args) = func.arguments {
-                                    state.arguments.push_str(&args);
-
-                                    // Start part if not started
-                                    if !state.started {
-                                        state.started = true;
-                                        // Include the accumulated args in PartStart (some providers
-                                        // like Cerebras send all tool call data in one chunk)
-                                        let tool_part = ToolCallPart::new(
-                                            &state.name,
-                                            ToolCallArgs::String(state.arguments.clone()),
-                                        )
-                                        .with_tool_call_id(&state.id);
-
-                                        let start = PartStartEvent::new(
-                                            state.part_index,
-                                            ModelResponsePart::ToolCall(tool_part),
-                                        );
-                                        return Some(Ok(ModelResponseStreamEvent::PartStart(
-                                            start,
-                                        )));
-                                    }
-
-                                    // Emit args delta
-                                    let delta =
-                                        PartDeltaEvent::tool_call_args(state.part_index, args);
-                                    return Some(Ok(ModelResponseStreamEvent::PartDelta(delta)));
-                                }
-                            }
-                        }
-                    }
-
-                    // Handle finish reason - emit part end events
-                    if choice.finish_reason.is_some() {
-                        // Collect all part indices that need to be closed
-                        let mut parts_to_close = Vec::new();
-
-                        // End text part if open
-                        if let Some(idx) = current_text_index.take() {
-                            parts_to_close.push(idx);
-                        }
-
-                        // End tool call parts
-                        for (_, state) in tool_calls.drain() {
-                            if state.started {
-                                parts_to_close.push(state.part_index);
-                            }
-                        }
-
-                        // If we have parts to close, return the first one and queue the rest
-                        if !parts_to_close.is_empty() {
-                            // Queue all but the first for later emission
-                            if parts_to_close.len() > 1 {
-                                pending_part_ends.extend(parts_to_close.iter().skip(1).copied());
-                            }
-                            // Return the first one now
-                            let first_idx = parts_to_close[0];
-                            return Some(Ok(ModelResponseStreamEvent::PartEnd(PartEndEvent {
-                                index: first_idx,
-                            })));
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Failed to parse SSE chunk: {} - data: {}", e, data);
+            // Handle finish reason - close all open parts
+            if choice.finish_reason.is_some() {
+                self.tool_calls.clear();
+                events.extend(self.parts.close_all());
             }
         }
-    }
 
-    None
+        Ok(events)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bytes::Bytes;
     use futures::stream;
     use futures::StreamExt;
     use serdes_ai_core::ModelResponsePartDelta;
@@ -40,6 +40,9 @@ pub struct ChatCompletionRequest {
     /// Random seed for reproducibility.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<u64>,
+    /// Number of choices to generate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
     /// Tool definitions.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<ChatTool>>,
@@ -67,6 +70,12 @@ pub struct ChatCompletionRequest {
     /// Top log probabilities.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_logprobs: Option<u32>,
+    /// Key grouping requests that share a prompt prefix for caching.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
+    /// Provider-specific fields merged into the request body.
+    #[serde(flatten)]
+    pub extra_body: serde_json::Map<String, JsonValue>,
 }
 
 impl ChatCompletionRequest {
@@ -83,6 +92,7 @@ impl ChatCompletionRequest {
             presence_penalty: None,
             frequency_penalty: None,
             seed: None,
+            n: None,
             tools: None,
             tool_choice: None,
             parallel_tool_calls: None,
@@ -92,6 +102,8 @@ impl ChatCompletionRequest {
             stream_options: None,
             logprobs: None,
             top_logprobs: None,
+            prompt_cache_key: None,
+            extra_body: serde_json::Map::new(),
         }
     }
 }
@@ -479,6 +491,8 @@ pub struct ResponseMessage {
     pub refusal: Option<String>,
     /// Reasoning/thinking content (for models like GLM-4 that support chain-of-thought).
     /// This is returned by some OpenAI-compatible providers when the model does reasoning.
+    /// Groq returns it as `reasoning`.
+    #[serde(alias = "reasoning")]
     pub reasoning_content: Option<String>,
 }
 
@@ -575,6 +589,7 @@ pub struct ChunkDelta {
     /// Refusal.
     pub refusal: Option<String>,
     /// Reasoning/thinking content delta (for models like GLM-4).
+    #[serde(alias = "reasoning")]
     pub reasoning_content: Option<String>,
 }
 
@@ -1,12 +1,15 @@
 //! OpenRouter model - OpenAI-compatible API routing to multiple providers.
 
-use super::types::{OpenRouterExtras, ProviderPreferences};
+use super::types::{OpenRouterExtras, OpenRouterResponse, ProviderPreferences};
 use crate::error::ModelError;
+use crate::http_metrics::RequestMeter;
+use crate::http_trace::TraceHeaders;
 use crate::model::{Model, ModelRequestParameters, StreamedResponse, ToolChoice};
 use crate::openai::{stream::OpenAIStreamParser, types::*};
 use crate::profile::{openai_gpt4o_profile, ModelProfile};
 use async_trait::async_trait;
 use reqwest::Client;
+use serdes_ai_core::messages::instructions_as_system_prompt;
 use serdes_ai_core::{
     messages::{TextPart, ToolCallArgs, ToolCallPart, UserContent, UserContentPart},
     FinishReason, ModelRequest, ModelRequestPart, ModelResponse, ModelResponsePart, ModelSettings,
@@ -105,19 +108,32 @@ impl OpenRouterModel {
     }
 
     /// Build OpenRouter extras for the request.
-    fn build_extras(&self) -> Option<OpenRouterExtras> {
-        if self.provider_preferences.is_none() && self.transforms.is_none() {
+    ///
+    /// Per-request extras from `settings` override the model defaults.
+    fn build_extras(&self, settings: &ModelSettings) -> Option<OpenRouterExtras> {
+        let request_extras = OpenRouterExtras::from_settings(settings);
+        if self.provider_preferences.is_none()
+            && self.transforms.is_none()
+            && request_extras.is_none()
+        {
             return None;
         }
 
-        Some(OpenRouterExtras {
+        let extras = OpenRouterExtras {
             provider: self.provider_preferences.clone(),
             transforms: self.transforms.clone(),
+            models: None,
             route: None,
+        };
+        Some(match request_extras {
+            Some(request_extras) => extras.merge(&request_extras),
+            None => extras,
         })
     }
 
     fn convert_messages(&self, requests: &[ModelRequest]) -> Vec<ChatMessage> {
+        let folded = instructions_as_system_prompt(requests);
+        let requests = &*folded;
         requests
             .iter()
             .flat_map(|req| {
@@ -199,18 +215,7 @@ impl OpenRouterModel {
     }
 
     fn convert_tools(&self, tools: &[ToolDefinition]) -> Vec<ChatTool> {
-        tools
-            .iter()
-            .map(|t| {
-                let p = serde_json::to_value(&t.parameters_json_schema)
-                    .unwrap_or(serde_json::json!({}));
-                if t.strict.unwrap_or(false) {
-                    ChatTool::function_strict(&t.name, &t.description, p)
-                } else {
-                    ChatTool::function(&t.name, &t.description, p)
-                }
-            })
-            .collect()
+        crate::openai::openai_tools(tools)
     }
 
     fn convert_tool_choice(&self, choice: &ToolChoice) -> ToolChoiceValue {
@@ -241,10 +246,7 @@ impl OpenRouterModel {
             .as_ref()
             .map(|c| self.convert_tool_choice(c));
 
-        let response_format = params.output_schema.as_ref().map(|schema| {
-            let schema_value = serde_json::to_value(schema).unwrap_or(serde_json::json!({}));
-            ResponseFormat::json_schema("output", schema_value, true)
-        });
+        let response_format = crate::openai::chat::response_format(params);
 
         let mut body = serde_json::json!({
             "model": self.model_name,
@@ -282,7 +284,7 @@ impl OpenRouterModel {
         }
 
         // Add OpenRouter extras
-        if let Some(extras) = self.build_extras() {
+        if let Some(extras) = self.build_extras(settings) {
             if let Some(provider) = &extras.provider {
                 obj.insert("provider".into(), serde_json::to_value(provider).unwrap());
             }
@@ -292,12 +294,25 @@ impl OpenRouterModel {
                     serde_json::to_value(transforms).unwrap(),
                 );
             }
+            if let Some(models) = &extras.models {
+                obj.insert("models".into(), serde_json::to_value(models).unwrap());
+            }
+            if let Some(route) = &extras.route {
+                obj.insert("route".into(), route.clone().into());
+            }
         }
 
         body
     }
 
-    fn parse_response(&self, resp: ChatCompletionResponse) -> Result<ModelResponse, ModelError> {
+    fn parse_response(&self, resp: OpenRouterResponse) -> Result<ModelResponse, ModelError> {
+        let provider = resp.provider;
+        let resp = resp.inner;
+        // The model that actually served the request, which may be a fallback
+        let vendor_details = serde_json::json!({
+            "provider": provider,
+            "model": resp.model,
+        });
         let choice = resp
             .choices
             .into_iter()
@@ -347,7 +362,8 @@ impl OpenRouterModel {
             finish_reason,
             usage,
             vendor_id: Some(resp.id),
-            vendor_details: None,
+            vendor_details: Some(vendor_details),
+            alternatives: Vec::new(),
             kind: "response".into(),
         })
     }
@@ -372,12 +388,17 @@ impl OpenRouterModel {
     async fn send_request(
         &self,
         body: &serde_json::Value,
-        timeout: Duration,
-    ) -> Result<reqwest::Response, ModelError> {
+        settings: &ModelSettings,
+    ) -> Result<(reqwest::Response, RequestMeter), ModelError> {
+        let (meter, body) = RequestMeter::start(body)?;
+        let timeout = settings.timeout.unwrap_or(self.default_timeout);
         let mut req = self
             .client
             .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header(
+                "Authorization",
+                format!("Bearer {}", settings.api_key_or(&self.api_key)),
+            )
             .header("Content-Type", "application/json")
             .timeout(timeout);
 
@@ -388,13 +409,13 @@ impl OpenRouterModel {
             req = req.header("X-Title", title);
         }
 
-        let response = req.json(body).send().await?;
+        let response = req.body(body).trace_headers().send().await?;
         if !response.status().is_success() {
             let status = response.status().as_u16();
             let body = response.text().await.unwrap_or_default();
             return Err(self.handle_error(status, &body));
         }
-        Ok(response)
+        Ok((response, meter))
     }
 }
 
@@ -417,14 +438,11 @@ impl Model for OpenRouterModel {
         params: &ModelRequestParameters,
     ) -> Result<ModelResponse, ModelError> {
         let body = self.build_request(messages, settings, params, false);
-        let response = self
-            .send_request(&body, settings.timeout.unwrap_or(self.default_timeout))
-            .await?;
-        let resp: ChatCompletionResponse = response
-            .json()
-            .await
-            .map_err(|e| ModelError::invalid_response(e.to_string()))?;
-        self.parse_response(resp)
+        let (response, meter) = self.send_request(&body, settings).await?;
+        let (resp, metrics) = meter.read_json::<OpenRouterResponse>(response).await?;
+        let mut response = self.parse_response(resp)?;
+        metrics.attach(&mut response);
+        Ok(response)
     }
 
     async fn request_stream(
@@ -434,9 +452,7 @@ impl Model for OpenRouterModel {
         params: &ModelRequestParameters,
     ) -> Result<StreamedResponse, ModelError> {
         let body = self.build_request(messages, settings, params, true);
-        let response = self
-            .send_request(&body, settings.timeout.unwrap_or(self.default_timeout))
-            .await?;
+        let (response, _) = self.send_request(&body, settings).await?;
         Ok(Box::pin(OpenAIStreamParser::new(response.bytes_stream())))
     }
 }
@@ -444,6 +460,7 @@ impl Model for OpenRouterModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::openrouter::{DataCollection, MaxPrice};
 
     #[test]
     fn test_openrouter() {
@@ -466,4 +483,77 @@ mod tests {
         );
         assert!(body.get("provider").is_some() && body.get("transforms").is_some());
     }
+
+    #[test]
+    fn test_per_request_extras_override_defaults() {
+        let model = OpenRouterModel::new("openai/gpt-4o", "key").with_provider_preferences(
+            ProviderPreferences::new()
+                .with_order(vec!["openai".into(), "azure".into()])
+                .with_allow_fallbacks(true),
+        );
+        let settings = OpenRouterExtras::new()
+            .with_provider(
+                ProviderPreferences::new()
+                    .with_allow_fallbacks(false)
+                    .with_data_collection(DataCollection::Deny)
+                    .with_max_price(MaxPrice::new().with_prompt(2.5).with_completion(10.0)),
+            )
+            .with_fallback_models(vec!["anthropic/claude-3.5-sonnet".into()])
+            .apply_to(ModelSettings::new());
+        let body = model.build_request(
+            &[ModelRequest::new()],
+            &settings,
+            &ModelRequestParameters::new(),
+            false,
+        );
+
+        let provider = &body["provider"];
+        assert_eq!(provider["order"], serde_json::json!(["openai", "azure"]));
+        assert_eq!(provider["allow_fallbacks"], false);
+        assert_eq!(provider["data_collection"], "deny");
+        assert_eq!(provider["max_price"]["prompt"], 2.5);
+        assert_eq!(body["route"], "fallback");
+        assert_eq!(
+            body["models"],
+            serde_json::json!(["anthropic/claude-3.5-sonnet"])
+        );
+    }
+
+    #[test]
+    fn test_no_extras_by_default() {
+        let model = OpenRouterModel::new("openai/gpt-4o", "key");
+        let body = model.build_request(
+            &[ModelRequest::new()],
+            &ModelSettings::new(),
+            &ModelRequestParameters::new(),
+            false,
+        );
+        assert!(body.get("provider").is_none() && body.get("route").is_none());
+    }
+
+    #[test]
+    fn test_parse_response_vendor_details() {
+        let model = OpenRouterModel::new("openai/gpt-4o", "key");
+        let resp: OpenRouterResponse = serde_json::from_value(serde_json::json!({
+            "id": "gen-123",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "anthropic/claude-3.5-sonnet",
+            "provider": "Anthropic",
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": "Hi"},
+                "finish_reason": "stop"
+            }]
+        }))
+        .unwrap();
+        let response = model.parse_response(resp).unwrap();
+        let details = response.vendor_details.unwrap();
+        assert_eq!(details["provider"], "Anthropic");
+        assert_eq!(details["model"], "anthropic/claude-3.5-sonnet");
+        assert_eq!(
+            response.model_name.as_deref(),
+            Some("anthropic/claude-3.5-sonnet")
+        );
+    }
 }
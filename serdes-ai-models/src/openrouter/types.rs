@@ -1,6 +1,8 @@
 //! OpenRouter-specific types for provider routing and preferences.
 
+use crate::openai::types::ChatCompletionResponse;
 use serde::{Deserialize, Serialize};
+use serdes_ai_core::ModelSettings;
 
 /// Provider routing preferences.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,6 +19,9 @@ pub struct ProviderPreferences {
     /// Data collection preference.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data_collection: Option<DataCollection>,
+    /// Maximum prices the request may be routed at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_price: Option<MaxPrice>,
 }
 
 impl ProviderPreferences {
@@ -42,6 +47,81 @@ impl ProviderPreferences {
         self.allow_fallbacks = Some(allow);
         self
     }
+    /// Set data collection policy.
+    #[must_use]
+    pub fn with_data_collection(mut self, policy: DataCollection) -> Self {
+        self.data_collection = Some(policy);
+        self
+    }
+    /// Set price caps.
+    #[must_use]
+    pub fn with_max_price(mut self, price: MaxPrice) -> Self {
+        self.max_price = Some(price);
+        self
+    }
+
+    /// Merge with other preferences, preferring values from `other`.
+    #[must_use]
+    pub fn merge(&self, other: &ProviderPreferences) -> ProviderPreferences {
+        ProviderPreferences {
+            order: other.order.clone().or_else(|| self.order.clone()),
+            quantizations: other
+                .quantizations
+                .clone()
+                .or_else(|| self.quantizations.clone()),
+            allow_fallbacks: other.allow_fallbacks.or(self.allow_fallbacks),
+            data_collection: other.data_collection.or(self.data_collection),
+            max_price: other.max_price.or(self.max_price),
+        }
+    }
+}
+
+/// Price caps in USD per million tokens (or per request/image).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaxPrice {
+    /// Max price per million prompt tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<f64>,
+    /// Max price per million completion tokens.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<f64>,
+    /// Max price per request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<f64>,
+    /// Max price per image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<f64>,
+}
+
+impl MaxPrice {
+    /// Create empty price caps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Set prompt price cap.
+    #[must_use]
+    pub fn with_prompt(mut self, price: f64) -> Self {
+        self.prompt = Some(price);
+        self
+    }
+    /// Set completion price cap.
+    #[must_use]
+    pub fn with_completion(mut self, price: f64) -> Self {
+        self.completion = Some(price);
+        self
+    }
+    /// Set per-request price cap.
+    #[must_use]
+    pub fn with_request(mut self, price: f64) -> Self {
+        self.request = Some(price);
+        self
+    }
+    /// Set per-image price cap.
+    #[must_use]
+    pub fn with_image(mut self, price: f64) -> Self {
+        self.image = Some(price);
+        self
+    }
 }
 
 /// Quantization preference levels.
@@ -81,6 +161,9 @@ pub struct OpenRouterExtras {
     /// Message transforms (e.g., "middle-out").
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transforms: Option<Vec<String>>,
+    /// Fallback models to try if the primary model fails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<String>>,
     /// Route (model fallback chain).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub route: Option<String>,
@@ -103,6 +186,68 @@ impl OpenRouterExtras {
         self.transforms = Some(t);
         self
     }
+    /// Set fallback models, using the `fallback` route.
+    #[must_use]
+    pub fn with_fallback_models(mut self, models: Vec<String>) -> Self {
+        self.models = Some(models);
+        self.route = Some("fallback".into());
+        self
+    }
+
+    /// Key under which extras are read from [`ModelSettings::extra`].
+    pub const SETTINGS_KEY: &'static str = "openrouter";
+
+    /// Read per-request extras from model settings.
+    ///
+    /// Extras live under the `"openrouter"` key of [`ModelSettings::extra`].
+    /// Invalid extras are logged and ignored.
+    pub fn from_settings(settings: &ModelSettings) -> Option<Self> {
+        let value = settings.extra.as_ref()?.get(Self::SETTINGS_KEY)?;
+        match serde_json::from_value(value.clone()) {
+            Ok(extras) => Some(extras),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid OpenRouter settings: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Store these extras in model settings for a single request.
+    #[must_use]
+    pub fn apply_to(&self, settings: ModelSettings) -> ModelSettings {
+        let extra = serde_json::json!({
+            Self::SETTINGS_KEY: serde_json::to_value(self).unwrap_or_default(),
+        });
+        settings.merge(&ModelSettings::new().extra(extra))
+    }
+
+    /// Merge with other extras, preferring values from `other`.
+    #[must_use]
+    pub fn merge(&self, other: &OpenRouterExtras) -> OpenRouterExtras {
+        let provider = match (&self.provider, &other.provider) {
+            (Some(a), Some(b)) => Some(a.merge(b)),
+            (a, b) => b.clone().or_else(|| a.clone()),
+        };
+        OpenRouterExtras {
+            provider,
+            transforms: other.transforms.clone().or_else(|| self.transforms.clone()),
+            models: other.models.clone().or_else(|| self.models.clone()),
+            route: other.route.clone().or_else(|| self.route.clone()),
+        }
+    }
+}
+
+/// OpenRouter chat completion response.
+///
+/// Adds the upstream provider to the OpenAI-compatible response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenRouterResponse {
+    /// Upstream provider that served the request.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// OpenAI-compatible response.
+    #[serde(flatten)]
+    pub inner: ChatCompletionResponse,
 }
 
 /// Common OpenRouter model identifiers.
@@ -126,4 +271,38 @@ mod tests {
         .unwrap()
         .contains("anthropic"));
     }
+
+    #[test]
+    fn test_provider_preferences_merge() {
+        let base = ProviderPreferences::new()
+            .with_order(vec!["anthropic".into()])
+            .with_allow_fallbacks(true);
+        let merged = base.merge(
+            &ProviderPreferences::new()
+                .with_allow_fallbacks(false)
+                .with_data_collection(DataCollection::Deny),
+        );
+        assert_eq!(merged.order, Some(vec!["anthropic".to_string()]));
+        assert_eq!(merged.allow_fallbacks, Some(false));
+        assert_eq!(merged.data_collection, Some(DataCollection::Deny));
+    }
+
+    #[test]
+    fn test_extras_settings_roundtrip() {
+        let extras = OpenRouterExtras::new()
+            .with_provider(
+                ProviderPreferences::new().with_max_price(MaxPrice::new().with_prompt(1.0)),
+            )
+            .with_fallback_models(vec!["openai/gpt-4o".into()]);
+        let settings = extras.apply_to(ModelSettings::new().temperature(0.5));
+        assert_eq!(settings.temperature, Some(0.5));
+
+        let parsed = OpenRouterExtras::from_settings(&settings).unwrap();
+        assert_eq!(parsed.route.as_deref(), Some("fallback"));
+        assert_eq!(
+            parsed.provider.unwrap().max_price.unwrap().prompt,
+            Some(1.0)
+        );
+        assert!(OpenRouterExtras::from_settings(&ModelSettings::new()).is_none());
+    }
 }
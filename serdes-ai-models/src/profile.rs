@@ -17,8 +17,18 @@ pub enum OutputMode {
     Native,
     /// Include schema in prompt and ask model to output JSON.
     Prompted,
+    /// Request JSON mode (`response_format: json_object`) and include the
+    /// schema in the prompt.
+    ///
+    /// For models that support JSON mode but not JSON schemas.
+    JsonObject,
     /// Plain text output (no structured output).
     Text,
+    /// Pick the best mode the model supports: native, then tool, then JSON
+    /// object, then prompted.
+    ///
+    /// Resolved against a profile with [`ModelProfile::resolve_output_mode`].
+    Auto,
 }
 
 impl std::fmt::Display for OutputMode {
@@ -27,7 +37,9 @@ impl std::fmt::Display for OutputMode {
             OutputMode::Tool => write!(f, "tool"),
             OutputMode::Native => write!(f, "native"),
             OutputMode::Prompted => write!(f, "prompted"),
+            OutputMode::JsonObject => write!(f, "json_object"),
             OutputMode::Text => write!(f, "text"),
+            OutputMode::Auto => write!(f, "auto"),
         }
     }
 }
@@ -81,13 +93,152 @@ pub struct ModelProfile {
     pub prompted_output_template: String,
     /// Whether native output mode requires schema in instructions too.
     pub native_output_requires_schema_in_instructions: bool,
+    /// Model supports JSON mode (`response_format: json_object`) without a schema.
+    pub supports_json_object_output: bool,
 }
 /// Default template for prompted structured output.
+///
+/// See [`PromptedOutputTemplate`] for the placeholders.
 pub const DEFAULT_PROMPTED_OUTPUT_TEMPLATE: &str = r#"Output your response as JSON matching this schema:
 ```json
 {schema}
 ```
+Examples of valid output:{examples}
 Output only valid JSON, no additional text."#;
+
+/// Localized variants of [`DEFAULT_PROMPTED_OUTPUT_TEMPLATE`], keyed by language.
+const LOCALIZED_PROMPTED_OUTPUT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "de",
+        r#"Gib deine Antwort als JSON aus, das diesem Schema entspricht:
+```json
+{schema}
+```
+Beispiele für gültige Ausgaben:{examples}
+Gib nur gültiges JSON aus, ohne zusätzlichen Text."#,
+    ),
+    (
+        "es",
+        r#"Escribe tu respuesta como JSON que cumpla este esquema:
+```json
+{schema}
+```
+Ejemplos de salida válida:{examples}
+Escribe solo JSON válido, sin texto adicional."#,
+    ),
+    (
+        "fr",
+        r#"Réponds en JSON conforme à ce schéma :
+```json
+{schema}
+```
+Exemples de sortie valide :{examples}
+Réponds uniquement avec du JSON valide, sans texte supplémentaire."#,
+    ),
+    (
+        "ja",
+        r#"次のスキーマに従うJSONで回答してください:
+```json
+{schema}
+```
+有効な出力の例:{examples}
+有効なJSONのみを出力し、それ以外のテキストは含めないでください。"#,
+    ),
+    (
+        "zh",
+        r#"请按照以下模式以 JSON 格式输出你的回答：
+```json
+{schema}
+```
+有效输出示例：{examples}
+只输出有效的 JSON，不要包含任何其他文本。"#,
+    ),
+];
+
+/// Template for prompted structured output instructions.
+///
+/// Placeholders:
+/// - `{schema}`: the JSON schema of the output.
+/// - `{examples}`: example outputs, each in a JSON code block. Lines containing
+///   this placeholder are dropped when there are no examples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptedOutputTemplate {
+    template: String,
+}
+
+impl PromptedOutputTemplate {
+    /// Create a template from a string.
+    #[must_use]
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Get the built-in template for a locale such as `"de"` or `"fr-CA"`.
+    ///
+    /// Returns `None` for languages without a translation. English uses
+    /// [`DEFAULT_PROMPTED_OUTPUT_TEMPLATE`].
+    #[must_use]
+    pub fn localized(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if language == "en" {
+            return Some(Self::default());
+        }
+        LOCALIZED_PROMPTED_OUTPUT_TEMPLATES
+            .iter()
+            .find(|(lang, _)| *lang == language)
+            .map(|(_, template)| Self::new(*template))
+    }
+
+    /// Get the raw template string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Render the template with a schema and example outputs.
+    #[must_use]
+    pub fn render(&self, schema: &str, examples: &[String]) -> String {
+        let template = if examples.is_empty() {
+            self.template
+                .lines()
+                .filter(|line| !line.contains("{examples}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            let rendered: String = examples
+                .iter()
+                .map(|e| format!("\n```json\n{}\n```", e))
+                .collect();
+            self.template.replace("{examples}", &rendered)
+        };
+        template.replace("{schema}", schema)
+    }
+}
+
+impl Default for PromptedOutputTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROMPTED_OUTPUT_TEMPLATE)
+    }
+}
+
+impl From<&str> for PromptedOutputTemplate {
+    fn from(template: &str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for PromptedOutputTemplate {
+    fn from(template: String) -> Self {
+        Self::new(template)
+    }
+}
+
 impl Default for ModelProfile {
     fn default() -> Self {
         Self {
@@ -111,6 +262,7 @@ impl Default for ModelProfile {
             default_structured_output_mode: OutputMode::default(),
             prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
             native_output_requires_schema_in_instructions: false,
+            supports_json_object_output: false,
         }
     }
 }
@@ -219,6 +371,13 @@ impl ModelProfile {
         self
     }
 
+    /// Set JSON mode support.
+    #[must_use]
+    pub fn with_json_object_output(mut self, supported: bool) -> Self {
+        self.supports_json_object_output = supported;
+        self
+    }
+
     /// Set default structured output mode.
     #[must_use]
     pub fn with_default_structured_output_mode(mut self, mode: OutputMode) -> Self {
@@ -253,10 +412,51 @@ impl ModelProfile {
         &self.thinking_tags.1
     }
 
+    /// Resolve [`OutputMode::Auto`] to a concrete mode for this model.
+    ///
+    /// Picks [`OutputMode::Native`] when native structured output is
+    /// supported, else [`OutputMode::Tool`] when tools are, else
+    /// [`OutputMode::JsonObject`] when JSON mode is, else
+    /// [`OutputMode::Prompted`] using the
+    /// [prompted output template](Self::format_prompted_output).
+    /// Other modes are returned unchanged.
+    #[must_use]
+    pub fn resolve_output_mode(&self, mode: OutputMode) -> OutputMode {
+        if mode != OutputMode::Auto {
+            return mode;
+        }
+        let resolved = if self.supports_native_structured_output {
+            OutputMode::Native
+        } else if self.supports_tools {
+            OutputMode::Tool
+        } else if self.supports_json_object_output {
+            OutputMode::JsonObject
+        } else {
+            OutputMode::Prompted
+        };
+        tracing::debug!(
+            mode = %resolved,
+            native = self.supports_native_structured_output,
+            tools = self.supports_tools,
+            json_object = self.supports_json_object_output,
+            "Auto-selected structured output mode"
+        );
+        resolved
+    }
+
+    /// The concrete structured output mode for this model.
+    ///
+    /// Same as [`default_structured_output_mode`](Self::default_structured_output_mode),
+    /// with [`OutputMode::Auto`] resolved.
+    #[must_use]
+    pub fn structured_output_mode(&self) -> OutputMode {
+        self.resolve_output_mode(self.default_structured_output_mode)
+    }
+
     /// Format the prompted output template with a schema.
     #[must_use]
     pub fn format_prompted_output(&self, schema: &str) -> String {
-        self.prompted_output_template.replace("{schema}", schema)
+        PromptedOutputTemplate::new(self.prompted_output_template.as_str()).render(schema, &[])
     }
 }
 /// Default model profile.
@@ -287,6 +487,7 @@ pub fn openai_gpt4o_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Native,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: true,
     }
 }
 
@@ -314,6 +515,7 @@ pub fn openai_o1_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Native,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: true,
     }
 }
 
@@ -341,6 +543,7 @@ pub fn anthropic_claude_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Tool,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: false,
     }
 }
 
@@ -368,6 +571,7 @@ pub fn deepseek_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Prompted,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: true,
     }
 }
 
@@ -395,6 +599,7 @@ pub fn qwen_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Prompted,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: true,
     }
 }
 
@@ -421,6 +626,7 @@ pub fn google_gemini_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Native,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: true,
+        supports_json_object_output: false,
     }
 }
 
@@ -447,6 +653,7 @@ pub fn mistral_profile() -> ModelProfile {
         default_structured_output_mode: OutputMode::Tool,
         prompted_output_template: DEFAULT_PROMPTED_OUTPUT_TEMPLATE.to_string(),
         native_output_requires_schema_in_instructions: false,
+        supports_json_object_output: true,
     }
 }
 
@@ -478,9 +685,46 @@ mod tests {
         assert_eq!(OutputMode::Tool.to_string(), "tool");
         assert_eq!(OutputMode::Native.to_string(), "native");
         assert_eq!(OutputMode::Prompted.to_string(), "prompted");
+        assert_eq!(OutputMode::JsonObject.to_string(), "json_object");
         assert_eq!(OutputMode::Text.to_string(), "text");
     }
 
+    #[test]
+    fn test_auto_output_mode() {
+        assert_eq!(OutputMode::Auto.to_string(), "auto");
+
+        let native = openai_gpt4o_profile();
+        assert_eq!(
+            native.resolve_output_mode(OutputMode::Auto),
+            OutputMode::Native
+        );
+
+        let tools = anthropic_claude_profile();
+        assert_eq!(
+            tools.resolve_output_mode(OutputMode::Auto),
+            OutputMode::Tool
+        );
+
+        let bare = ModelProfile::new()
+            .with_tools(false)
+            .with_default_structured_output_mode(OutputMode::Auto);
+        assert_eq!(bare.structured_output_mode(), OutputMode::Prompted);
+        assert!(bare
+            .format_prompted_output("{}")
+            .starts_with("Output your response as JSON"));
+
+        let json_mode = ModelProfile::new()
+            .with_tools(false)
+            .with_json_object_output(true);
+        assert_eq!(
+            json_mode.resolve_output_mode(OutputMode::Auto),
+            OutputMode::JsonObject
+        );
+
+        // Explicit modes are never overridden
+        assert_eq!(bare.resolve_output_mode(OutputMode::Tool), OutputMode::Tool);
+    }
+
     #[test]
     fn test_openai_profile() {
         let profile = openai_gpt4o_profile();
@@ -589,6 +833,30 @@ mod tests {
         assert_eq!(formatted, r#"Please output JSON: {"type": "object"}"#);
     }
 
+    #[test]
+    fn test_prompted_output_template_examples() {
+        let template = PromptedOutputTemplate::default();
+        let without = template.render("{}", &[]);
+        assert!(!without.contains("{examples}"));
+        assert!(!without.contains("Examples"));
+
+        let with = template.render("{}", &[r#"{"a": 1}"#.to_string()]);
+        assert!(with.contains("Examples of valid output:\n```json\n{\"a\": 1}\n```"));
+    }
+
+    #[test]
+    fn test_localized_prompted_output_template() {
+        let de = PromptedOutputTemplate::localized("de-DE").unwrap();
+        assert!(de
+            .render("{}", &[])
+            .starts_with("Gib deine Antwort als JSON"));
+        assert_eq!(
+            PromptedOutputTemplate::localized("en_US"),
+            Some(PromptedOutputTemplate::default())
+        );
+        assert!(PromptedOutputTemplate::localized("xx").is_none());
+    }
+
     #[test]
     fn test_default_prompted_output_template() {
         let profile = ModelProfile::default();
@@ -0,0 +1,515 @@
+//! Single-flight deduplication of identical concurrent requests.
+//!
+//! [`SingleFlightModel`] wraps a model so that concurrent requests with the
+//! same messages, settings and parameters share one provider call. This is
+//! useful for fan-out UIs and evaluation suites that repeat cases.
+//!
+//! Streaming requests are shared too: the provider stream is driven by a
+//! background task and its events are broadcast to every caller, with late
+//! joiners replaying the events they missed.
+//!
+//! Only in-flight requests are shared; once a request completes, the next
+//! identical request calls the provider again.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use serdes_ai_models::SingleFlightModel;
+//!
+//! let model = SingleFlightModel::new(OpenAIChatModel::from_env("gpt-4o")?);
+//! let (a, b) = tokio::join!(
+//!     model.request(&messages, &settings, &params),
+//!     model.request(&messages, &settings, &params),
+//! );
+//! ```
+
+use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
+use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::profile::ModelProfile;
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use futures::StreamExt;
+use serdes_ai_core::messages::ModelResponseStreamEvent;
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+type SharedError = Arc<ModelError>;
+type SharedResponse = Shared<BoxFuture<'static, Result<ModelResponse, SharedError>>>;
+
+/// Recreate an owned error from a shared one.
+///
+/// Variants that can't be cloned keep their message.
+fn unshare_error(error: &ModelError) -> ModelError {
+    match error {
+        ModelError::Http {
+            status,
+            body,
+            headers,
+        } => ModelError::Http {
+            status: *status,
+            body: body.clone(),
+            headers: headers.clone(),
+        },
+        ModelError::Api { message, code } => ModelError::Api {
+            message: message.clone(),
+            code: code.clone(),
+        },
+        ModelError::Timeout(d) => ModelError::Timeout(*d),
+        ModelError::RateLimited { retry_after } => ModelError::RateLimited {
+            retry_after: *retry_after,
+        },
+        ModelError::Authentication(m) => ModelError::Authentication(m.clone()),
+        ModelError::InvalidResponse(m) => ModelError::InvalidResponse(m.clone()),
+        ModelError::NotFound(m) => ModelError::NotFound(m.clone()),
+        ModelError::NotSupported(m) => ModelError::NotSupported(m.clone()),
+        ModelError::Cancelled => ModelError::Cancelled,
+        ModelError::Connection(m) => ModelError::Connection(m.clone()),
+        ModelError::ContentFiltered(m) => ModelError::ContentFiltered(m.clone()),
+        ModelError::ContextLengthExceeded {
+            max_tokens,
+            requested_tokens,
+        } => ModelError::ContextLengthExceeded {
+            max_tokens: *max_tokens,
+            requested_tokens: *requested_tokens,
+        },
+        ModelError::Configuration(m) => ModelError::Configuration(m.clone()),
+        ModelError::Network(m) => ModelError::Network(m.clone()),
+        ModelError::WithUsage { source, usage } => unshare_error(source).with_usage(usage.clone()),
+        ModelError::Serialization(_) | ModelError::Other(_) => {
+            ModelError::Other(anyhow::anyhow!("{}", error))
+        }
+    }
+}
+
+/// Hash everything that affects a request's result.
+fn request_key(
+    messages: &[ModelRequest],
+    settings: &ModelSettings,
+    params: &ModelRequestParameters,
+    stream: bool,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(messages)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    serde_json::to_string(settings)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    serde_json::to_string(params.tools.as_ref())
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    serde_json::to_string(&params.output_schema)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    params.output_mode.to_string().hash(&mut hasher);
+    params.allow_text_output.hash(&mut hasher);
+    format!("{:?}", params.tool_choice).hash(&mut hasher);
+    params.stream_usage.hash(&mut hasher);
+    stream.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Progress of a shared stream.
+#[derive(Default)]
+struct StreamState {
+    /// Result of opening the stream, once known.
+    opened: Option<Result<(), SharedError>>,
+    /// Events received so far.
+    events: Vec<Result<ModelResponseStreamEvent, SharedError>>,
+    /// Whether the stream has ended.
+    done: bool,
+}
+
+/// A stream shared between callers.
+struct StreamFlight {
+    state: Mutex<StreamState>,
+    changed: watch::Sender<()>,
+}
+
+impl StreamFlight {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(StreamState::default()),
+            changed: watch::channel(()).0,
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut StreamState)) {
+        f(&mut self.state.lock().unwrap());
+        self.changed.send_replace(());
+    }
+}
+
+/// Model wrapper that shares identical concurrent requests.
+pub struct SingleFlightModel<M> {
+    inner: Arc<M>,
+    requests: Arc<Mutex<HashMap<u64, (u64, SharedResponse)>>>,
+    streams: Arc<Mutex<HashMap<u64, Arc<StreamFlight>>>>,
+    next_id: AtomicU64,
+}
+
+impl<M> std::fmt::Debug for SingleFlightModel<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SingleFlightModel")
+            .field("in_flight", &self.in_flight())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Model + 'static> SingleFlightModel<M> {
+    /// Wrap a model.
+    pub fn new(model: M) -> Self {
+        Self {
+            inner: Arc::new(model),
+            requests: Arc::new(Mutex::new(HashMap::new())),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the wrapped model.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+}
+
+impl<M> SingleFlightModel<M> {
+    /// Number of provider calls currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.requests.lock().unwrap().len() + self.streams.lock().unwrap().len()
+    }
+}
+
+impl<M: Model + 'static> SingleFlightModel<M> {
+    /// Join the stream flight for `key`, starting it if needed.
+    fn join_stream(
+        &self,
+        key: u64,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Arc<StreamFlight> {
+        let mut streams = self.streams.lock().unwrap();
+        if let Some(flight) = streams.get(&key) {
+            return flight.clone();
+        }
+
+        let flight = Arc::new(StreamFlight::new());
+        streams.insert(key, flight.clone());
+
+        let inner = self.inner.clone();
+        let streams = self.streams.clone();
+        let task_flight = flight.clone();
+        let (messages, settings, params) = (messages.to_vec(), settings.clone(), params.clone());
+        tokio::spawn(async move {
+            let flight = task_flight;
+            match inner.request_stream(&messages, &settings, &params).await {
+                Ok(mut stream) => {
+                    flight.update(|s| s.opened = Some(Ok(())));
+                    while let Some(item) = stream.next().await {
+                        flight.update(|s| s.events.push(item.map_err(Arc::new)));
+                    }
+                }
+                Err(e) => flight.update(|s| s.opened = Some(Err(Arc::new(e)))),
+            }
+
+            // Stop sharing before marking done so new callers start fresh
+            let mut streams = streams.lock().unwrap();
+            if streams.get(&key).is_some_and(|f| Arc::ptr_eq(f, &flight)) {
+                streams.remove(&key);
+            }
+            drop(streams);
+            flight.update(|s| s.done = true);
+        });
+
+        flight
+    }
+}
+
+#[async_trait]
+impl<M: Model + 'static> Model for SingleFlightModel<M> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn system(&self) -> &str {
+        self.inner.system()
+    }
+
+    fn identifier(&self) -> String {
+        self.inner.identifier()
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
+    async fn request(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<ModelResponse, ModelError> {
+        let key = request_key(messages, settings, params, false);
+
+        let (id, flight) = {
+            let mut requests = self.requests.lock().unwrap();
+            match requests.get(&key) {
+                Some((id, flight)) => (*id, flight.clone()),
+                None => {
+                    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                    let inner = self.inner.clone();
+                    let (messages, settings, params) =
+                        (messages.to_vec(), settings.clone(), params.clone());
+                    let flight = async move {
+                        inner
+                            .request(&messages, &settings, &params)
+                            .await
+                            .map_err(Arc::new)
+                    }
+                    .boxed()
+                    .shared();
+                    requests.insert(key, (id, flight.clone()));
+                    (id, flight)
+                }
+            }
+        };
+
+        let result = flight.await;
+
+        let mut requests = self.requests.lock().unwrap();
+        if requests.get(&key).is_some_and(|(i, _)| *i == id) {
+            requests.remove(&key);
+        }
+
+        result.map_err(|e| unshare_error(&e))
+    }
+
+    async fn request_stream(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<StreamedResponse, ModelError> {
+        let key = request_key(messages, settings, params, true);
+        let flight = self.join_stream(key, messages, settings, params);
+        let mut changed = flight.changed.subscribe();
+
+        // Wait until the stream is open
+        loop {
+            if let Some(opened) = &flight.state.lock().unwrap().opened {
+                if let Err(e) = opened {
+                    return Err(unshare_error(e));
+                }
+                break;
+            }
+            if changed.changed().await.is_err() {
+                return Err(ModelError::Cancelled);
+            }
+        }
+
+        let stream = futures::stream::unfold(
+            (flight, changed, 0usize),
+            |(flight, mut changed, index)| async move {
+                loop {
+                    {
+                        let state = flight.state.lock().unwrap();
+                        if let Some(item) = state.events.get(index) {
+                            let item = item.clone().map_err(|e| unshare_error(&e));
+                            drop(state);
+                            return Some((item, (flight, changed, index + 1)));
+                        }
+                        if state.done {
+                            return None;
+                        }
+                    }
+                    changed.changed().await.ok()?;
+                }
+            },
+        );
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::messages::PartStartEvent;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    /// Slow model that counts provider calls.
+    struct CountingModel {
+        calls: Arc<AtomicUsize>,
+        fail: bool,
+        profile: ModelProfile,
+    }
+
+    impl CountingModel {
+        fn new() -> Self {
+            Self {
+                calls: Arc::new(AtomicUsize::new(0)),
+                fail: false,
+                profile: ModelProfile::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Model for CountingModel {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn system(&self) -> &str {
+            "test"
+        }
+
+        fn profile(&self) -> &ModelProfile {
+            &self.profile
+        }
+
+        async fn request(
+            &self,
+            _messages: &[ModelRequest],
+            _settings: &ModelSettings,
+            _params: &ModelRequestParameters,
+        ) -> Result<ModelResponse, ModelError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if self.fail {
+                return Err(ModelError::rate_limited(Some(Duration::from_secs(1))));
+            }
+            Ok(ModelResponse::text(format!("response {}", n)))
+        }
+
+        async fn request_stream(
+            &self,
+            _messages: &[ModelRequest],
+            _settings: &ModelSettings,
+            _params: &ModelRequestParameters,
+        ) -> Result<StreamedResponse, ModelError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let events = vec![
+                ModelResponseStreamEvent::PartStart(PartStartEvent::text(0, "Hel")),
+                ModelResponseStreamEvent::text_delta(0, "lo"),
+                ModelResponseStreamEvent::part_end(0),
+            ];
+            let stream = futures::stream::iter(events).then(|event| async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(event)
+            });
+            Ok(Box::pin(stream))
+        }
+    }
+
+    fn user(text: &str) -> Vec<ModelRequest> {
+        let mut req = ModelRequest::new();
+        req.add_user_prompt(text);
+        vec![req]
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_requests_share_call() {
+        let inner = CountingModel::new();
+        let calls = inner.calls.clone();
+        let model = SingleFlightModel::new(inner);
+        let (messages, settings, params) = (
+            user("hi"),
+            ModelSettings::new(),
+            ModelRequestParameters::new(),
+        );
+
+        let (a, b) = tokio::join!(
+            model.request(&messages, &settings, &params),
+            model.request(&messages, &settings, &params),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a.unwrap().parts, b.unwrap().parts);
+        assert_eq!(model.in_flight(), 0);
+
+        // Completed requests aren't cached
+        model.request(&messages, &settings, &params).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_different_requests_not_shared() {
+        let inner = CountingModel::new();
+        let calls = inner.calls.clone();
+        let model = SingleFlightModel::new(inner);
+        let (a, b) = (user("a"), user("b"));
+        let settings = ModelSettings::new();
+        let params = ModelRequestParameters::new();
+
+        let (_, _) = tokio::join!(
+            model.request(&a, &settings, &params),
+            model.request(&b, &settings, &params),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shared_error() {
+        let mut inner = CountingModel::new();
+        inner.fail = true;
+        let model = SingleFlightModel::new(inner);
+        let (messages, settings, params) = (
+            user("hi"),
+            ModelSettings::new(),
+            ModelRequestParameters::new(),
+        );
+
+        let (a, b) = tokio::join!(
+            model.request(&messages, &settings, &params),
+            model.request(&messages, &settings, &params),
+        );
+        for result in [a, b] {
+            let err = result.unwrap_err();
+            assert_eq!(err.retry_after(), Some(Duration::from_secs(1)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streams_broadcast_events() {
+        let inner = CountingModel::new();
+        let calls = inner.calls.clone();
+        let model = SingleFlightModel::new(inner);
+        let (messages, settings, params) = (
+            user("hi"),
+            ModelSettings::new(),
+            ModelRequestParameters::new(),
+        );
+
+        let first = model
+            .request_stream(&messages, &settings, &params)
+            .await
+            .unwrap();
+        // Join late, after the first event may have been sent
+        tokio::time::sleep(Duration::from_millis(15)).await;
+        let (a, b) = tokio::join!(first.map(|e| e.unwrap()).collect::<Vec<_>>(), async {
+            let second = model.request_stream(&messages, &settings, &params);
+            second
+                .await
+                .unwrap()
+                .map(|e| e.unwrap())
+                .collect::<Vec<_>>()
+                .await
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a, b);
+    }
+}
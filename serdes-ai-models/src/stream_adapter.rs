@@ -0,0 +1,599 @@
+//! Shared framework for provider stream parsers.
+//!
+//! Most providers stream their responses as server-sent events whose `data`
+//! payloads are JSON chunks. The framing, buffering, error propagation and
+//! part index bookkeeping are the same for all of them; only the mapping from
+//! a provider chunk to [`ModelResponseStreamEvent`]s differs.
+//!
+//! A provider implements [`StreamAdapter`] for that mapping and wraps its
+//! byte stream in an [`AdapterStream`]. [`PartTracker`] handles part index
+//! allocation and closing open parts, and feeds every event it builds through
+//! the streaming crate's parts manager so that parts get stable IDs and the
+//! response can be rebuilt from the stream.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use serdes_ai_models::stream_adapter::{AdapterStream, PartKey, PartTracker, StreamAdapter};
+//!
+//! #[derive(Default)]
+//! struct MyAdapter {
+//!     parts: PartTracker,
+//! }
+//!
+//! impl StreamAdapter for MyAdapter {
+//!     type Chunk = MyChunk;
+//!
+//!     fn map_chunk(&mut self, chunk: MyChunk) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+//!         Ok(vec![self.parts.text(&chunk.text)])
+//!     }
+//! }
+//!
+//! let stream = AdapterStream::<_, MyAdapter>::new(response.bytes_stream());
+//! ```
+
+use crate::error::ModelError;
+use bytes::Bytes;
+use futures::Stream;
+use pin_project_lite::pin_project;
+use serde::de::DeserializeOwned;
+use serdes_ai_core::messages::{
+    ModelResponse, ModelResponsePartDelta, ModelResponseStreamEvent, PartDeltaEvent,
+    PartStartEvent, TextPart, TextPartDelta, ThinkingPart, ThinkingPartDelta,
+};
+use serdes_ai_core::ModelResponsePart;
+use serdes_ai_streaming::ModelResponsePartsManager;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Maps provider stream chunks to model response events.
+pub trait StreamAdapter {
+    /// The JSON chunk type carried in each event's `data` payload.
+    type Chunk: DeserializeOwned;
+
+    /// Map one chunk to zero or more stream events.
+    ///
+    /// Returning an error yields it from the stream; parsing continues with
+    /// the next chunk.
+    fn map_chunk(
+        &mut self,
+        chunk: Self::Chunk,
+    ) -> Result<Vec<ModelResponseStreamEvent>, ModelError>;
+
+    /// Events to emit once the stream is done.
+    fn finish(&mut self) -> Vec<ModelResponseStreamEvent> {
+        Vec::new()
+    }
+
+    /// Whether the provider signalled the end of the response.
+    ///
+    /// Once `true`, no further input is read.
+    fn is_done(&self) -> bool {
+        false
+    }
+}
+
+/// Key identifying a logical part within a provider stream.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PartKey {
+    /// The text part.
+    Text,
+    /// The thinking part.
+    Thinking,
+    /// A tool call, by provider index.
+    ToolCall(u64),
+    /// A content block, by provider index.
+    Block(u64),
+}
+
+/// Allocates part indices, tracks which parts are open and reconstructs the
+/// response.
+///
+/// Every event the tracker returns has been applied to a
+/// [`ModelResponsePartsManager`], which gives each part a stable ID. The ID is
+/// set on the part's start, delta and end events, and
+/// [`to_response`](Self::to_response) builds the response streamed so far.
+#[derive(Debug, Default)]
+pub struct PartTracker {
+    next_index: usize,
+    open: Vec<(PartKey, usize)>,
+    manager: ModelResponsePartsManager,
+}
+
+impl PartTracker {
+    /// Create a new tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the index of the open part for `key`.
+    #[must_use]
+    pub fn get(&self, key: &PartKey) -> Option<usize> {
+        self.open
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, idx)| *idx)
+    }
+
+    /// Get the stable ID of the open part for `key`.
+    #[must_use]
+    pub fn part_id(&self, key: &PartKey) -> Option<&str> {
+        self.get(key)
+            .and_then(|idx| self.manager.event_part_id(idx))
+    }
+
+    /// Start a new part for `key`, returning its start event.
+    ///
+    /// An already open part with the same key is forgotten without an end
+    /// event.
+    pub fn start(&mut self, key: PartKey, part: ModelResponsePart) -> ModelResponseStreamEvent {
+        self.open.retain(|(k, _)| *k != key);
+        let idx = self.next_index;
+        self.next_index += 1;
+        self.open.push((key, idx));
+        self.emit(ModelResponseStreamEvent::PartStart(PartStartEvent::new(
+            idx, part,
+        )))
+    }
+
+    /// Apply a delta to the open part for `key`, returning its delta event.
+    ///
+    /// Returns `None` if no part is open for `key`.
+    pub fn delta(
+        &mut self,
+        key: &PartKey,
+        delta: ModelResponsePartDelta,
+    ) -> Option<ModelResponseStreamEvent> {
+        let idx = self.get(key)?;
+        Some(
+            self.emit(ModelResponseStreamEvent::PartDelta(PartDeltaEvent::new(
+                idx, delta,
+            ))),
+        )
+    }
+
+    /// Check whether any part is open.
+    #[must_use]
+    pub fn has_open(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    /// Close the part for `key`, returning its end event.
+    pub fn close(&mut self, key: &PartKey) -> Option<ModelResponseStreamEvent> {
+        let pos = self.open.iter().position(|(k, _)| k == key)?;
+        let (_, idx) = self.open.remove(pos);
+        Some(self.emit(ModelResponseStreamEvent::part_end(idx)))
+    }
+
+    /// Close all open parts, returning end events in index order.
+    pub fn close_all(&mut self) -> Vec<ModelResponseStreamEvent> {
+        let mut indices: Vec<usize> = self.open.drain(..).map(|(_, idx)| idx).collect();
+        indices.sort_unstable();
+        indices
+            .into_iter()
+            .map(|idx| self.emit(ModelResponseStreamEvent::part_end(idx)))
+            .collect()
+    }
+
+    /// Append text, starting the text part if needed.
+    pub fn text(&mut self, content: &str) -> ModelResponseStreamEvent {
+        match self.delta(
+            &PartKey::Text,
+            ModelResponsePartDelta::Text(TextPartDelta::new(content)),
+        ) {
+            Some(event) => event,
+            None => self.start(
+                PartKey::Text,
+                ModelResponsePart::Text(TextPart::new(content)),
+            ),
+        }
+    }
+
+    /// Append thinking content, starting the thinking part if needed.
+    pub fn thinking(&mut self, content: &str) -> ModelResponseStreamEvent {
+        match self.delta(
+            &PartKey::Thinking,
+            ModelResponsePartDelta::Thinking(ThinkingPartDelta::new(content)),
+        ) {
+            Some(event) => event,
+            None => self.start(
+                PartKey::Thinking,
+                ModelResponsePart::Thinking(ThinkingPart::new(content)),
+            ),
+        }
+    }
+
+    /// Emit a complete response as one started and ended part each.
+    ///
+    /// For providers that fall back to a non-streaming request.
+    pub fn replay(&mut self, parts: Vec<ModelResponsePart>) -> Vec<ModelResponseStreamEvent> {
+        let mut events = Vec::with_capacity(parts.len() * 2);
+        for part in parts {
+            let key = PartKey::Block(self.next_index as u64);
+            events.push(self.start(key.clone(), part));
+            events.extend(self.close(&key));
+        }
+        events
+    }
+
+    /// Build the response from the parts streamed so far.
+    #[must_use]
+    pub fn to_response(&self) -> ModelResponse {
+        self.manager.to_response()
+    }
+
+    /// Get the parts manager the events are applied to.
+    #[must_use]
+    pub fn parts_manager(&self) -> &ModelResponsePartsManager {
+        &self.manager
+    }
+
+    /// Apply an event to the parts manager and set its part ID.
+    fn emit(&mut self, event: ModelResponseStreamEvent) -> ModelResponseStreamEvent {
+        self.manager.handle_event(&event);
+        match self.manager.event_part_id(event.index()) {
+            Some(id) => event.with_part_id(id),
+            None => event,
+        }
+    }
+}
+
+/// Incremental decoder for server-sent events.
+///
+/// Yields the `data` payload of each event. Lines without an SSE field name
+/// are treated as data too, so newline-delimited JSON streams decode the same
+/// way. Comments and `event`, `id` and `retry` fields are skipped.
+///
+/// Bytes are buffered until a line is complete, so multi-byte characters
+/// split across network chunks decode correctly.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    /// Create a new decoder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append raw bytes to the buffer.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Take the next complete data payload from the buffer.
+    pub fn next_data(&mut self) -> Option<String> {
+        while let Some(newline_pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline_pos).collect();
+            if let Some(data) = data_of(&String::from_utf8_lossy(&line)) {
+                return Some(data.to_string());
+            }
+        }
+        None
+    }
+
+    /// Take all remaining payloads, including an unterminated last line.
+    pub fn flush(&mut self) -> Vec<String> {
+        let mut out = Vec::new();
+        while let Some(data) = self.next_data() {
+            out.push(data);
+        }
+        let rest = std::mem::take(&mut self.buffer);
+        if let Some(data) = data_of(&String::from_utf8_lossy(&rest)) {
+            out.push(data.to_string());
+        }
+        out
+    }
+}
+
+/// Extract the data payload of an SSE line.
+fn data_of(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(':') {
+        return None;
+    }
+    if let Some(data) = line.strip_prefix("data:") {
+        let data = data.trim_start();
+        return (!data.is_empty()).then_some(data);
+    }
+    if ["event:", "id:", "retry:"]
+        .iter()
+        .any(|field| line.starts_with(field))
+    {
+        return None;
+    }
+    Some(line)
+}
+
+pin_project! {
+    /// Stream of model response events driven by a [`StreamAdapter`].
+    pub struct AdapterStream<S, A> {
+        #[pin]
+        inner: S,
+        adapter: A,
+        decoder: SseDecoder,
+        pending: VecDeque<Result<ModelResponseStreamEvent, ModelError>>,
+        done: bool,
+    }
+}
+
+impl<S, A> AdapterStream<S, A>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>>,
+    A: StreamAdapter,
+{
+    /// Create a new stream with a default adapter.
+    pub fn new(inner: S) -> Self
+    where
+        A: Default,
+    {
+        Self::with_adapter(inner, A::default())
+    }
+
+    /// Create a new stream with the given adapter.
+    pub fn with_adapter(inner: S, adapter: A) -> Self {
+        Self {
+            inner,
+            adapter,
+            decoder: SseDecoder::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Get the adapter.
+    pub fn adapter(&self) -> &A {
+        &self.adapter
+    }
+}
+
+/// Decode one data payload and queue the resulting events.
+fn handle_data<A: StreamAdapter>(
+    data: &str,
+    adapter: &mut A,
+    pending: &mut VecDeque<Result<ModelResponseStreamEvent, ModelError>>,
+    done: &mut bool,
+) {
+    if data == "[DONE]" {
+        *done = true;
+        return;
+    }
+
+    match serde_json::from_str::<A::Chunk>(data) {
+        Ok(chunk) => match adapter.map_chunk(chunk) {
+            Ok(events) => pending.extend(events.into_iter().map(Ok)),
+            Err(e) => pending.push_back(Err(e)),
+        },
+        Err(e) => {
+            tracing::warn!("Failed to parse stream chunk: {} - data: {}", e, data);
+        }
+    }
+
+    if adapter.is_done() {
+        *done = true;
+    }
+}
+
+impl<S, A> Stream for AdapterStream<S, A>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>>,
+    A: StreamAdapter,
+{
+    type Item = Result<ModelResponseStreamEvent, ModelError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            if *this.done {
+                return Poll::Ready(None);
+            }
+
+            if let Some(data) = this.decoder.next_data() {
+                handle_data(&data, this.adapter, this.pending, this.done);
+                if *this.done {
+                    this.pending
+                        .extend(this.adapter.finish().into_iter().map(Ok));
+                }
+                continue;
+            }
+
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(bytes))) => this.decoder.push(&bytes),
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(Some(Err(ModelError::Other(e.into()))));
+                }
+                Poll::Ready(None) => {
+                    for data in this.decoder.flush() {
+                        if *this.done {
+                            break;
+                        }
+                        handle_data(&data, this.adapter, this.pending, this.done);
+                    }
+                    *this.done = true;
+                    this.pending
+                        .extend(this.adapter.finish().into_iter().map(Ok));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{stream, StreamExt};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Chunk {
+        text: String,
+        #[serde(default)]
+        stop: bool,
+    }
+
+    #[derive(Default)]
+    struct TestAdapter {
+        parts: PartTracker,
+        done: bool,
+    }
+
+    impl StreamAdapter for TestAdapter {
+        type Chunk = Chunk;
+
+        fn map_chunk(&mut self, chunk: Chunk) -> Result<Vec<ModelResponseStreamEvent>, ModelError> {
+            if chunk.text == "fail" {
+                return Err(ModelError::api("boom"));
+            }
+            let mut events = vec![self.parts.text(&chunk.text)];
+            if chunk.stop {
+                self.done = true;
+                events.extend(self.parts.close_all());
+            }
+            Ok(events)
+        }
+
+        fn is_done(&self) -> bool {
+            self.done
+        }
+    }
+
+    async fn collect(
+        bytes: Vec<&'static str>,
+    ) -> Vec<Result<ModelResponseStreamEvent, ModelError>> {
+        let inner = stream::iter(
+            bytes
+                .into_iter()
+                .map(|b| Ok::<_, reqwest::Error>(Bytes::from(b))),
+        );
+        AdapterStream::<_, TestAdapter>::new(inner).collect().await
+    }
+
+    #[test]
+    fn test_sse_decoder() {
+        let mut decoder = SseDecoder::new();
+        decoder.push(b": comment\nevent: message\nid: 1\ndata: {\"a\":1}\n\n{\"b\"");
+        assert_eq!(decoder.next_data().as_deref(), Some("{\"a\":1}"));
+        assert_eq!(decoder.next_data(), None);
+        decoder.push(b":2}");
+        assert_eq!(decoder.flush(), vec!["{\"b\":2}".to_string()]);
+    }
+
+    #[test]
+    fn test_sse_decoder_split_character() {
+        let line = "data: 你好 👋\n".as_bytes();
+        // Split inside the first character and inside the emoji
+        let mut decoder = SseDecoder::new();
+        decoder.push(&line[..7]);
+        assert_eq!(decoder.next_data(), None);
+        decoder.push(&line[7..line.len() - 2]);
+        decoder.push(&line[line.len() - 2..]);
+        assert_eq!(decoder.next_data().as_deref(), Some("你好 👋"));
+    }
+
+    #[test]
+    fn test_part_tracker() {
+        let mut tracker = PartTracker::new();
+        assert!(tracker.text("a").is_start());
+        assert!(tracker.text("b").is_delta());
+        assert!(tracker.thinking("c").is_start());
+        let start = tracker.start(PartKey::ToolCall(0), ModelResponsePart::text("d"));
+        assert_eq!(start.index(), 2);
+        assert_eq!(tracker.get(&PartKey::Thinking), Some(1));
+        assert!(tracker
+            .delta(
+                &PartKey::Block(0),
+                ModelResponsePartDelta::Text(TextPartDelta::new("x"))
+            )
+            .is_none());
+
+        assert_eq!(tracker.close(&PartKey::Text).unwrap().index(), 0);
+        let ends: Vec<usize> = tracker.close_all().iter().map(|e| e.index()).collect();
+        assert_eq!(ends, vec![1, 2]);
+        assert!(!tracker.has_open());
+    }
+
+    #[test]
+    fn test_part_tracker_part_ids_and_response() {
+        use serdes_ai_core::identifier::{use_id_generator, SequentialIds};
+
+        let _ids = use_id_generator(SequentialIds::new());
+        let mut tracker = PartTracker::new();
+        let mut events = vec![tracker.thinking("Let me"), tracker.thinking(" think")];
+        events.extend(tracker.close(&PartKey::Thinking));
+        events.push(tracker.text("Hi"));
+        assert_eq!(tracker.part_id(&PartKey::Text), Some("part_2"));
+        events.push(tracker.text(" there"));
+        events.extend(tracker.close_all());
+
+        let ids: Vec<_> = events.iter().map(|e| e.part_id().unwrap()).collect();
+        assert_eq!(
+            ids,
+            vec!["part_1", "part_1", "part_1", "part_2", "part_2", "part_2"]
+        );
+        assert_eq!(
+            tracker.to_response().parts,
+            vec![
+                ModelResponsePart::Thinking(ThinkingPart::new("Let me think")),
+                ModelResponsePart::text("Hi there"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_part_tracker_replay() {
+        let mut tracker = PartTracker::new();
+        let parts = vec![ModelResponsePart::text("a"), ModelResponsePart::text("b")];
+        let events = tracker.replay(parts.clone());
+        let kinds: Vec<_> = events.iter().map(|e| (e.is_start(), e.index())).collect();
+        assert_eq!(kinds, vec![(true, 0), (false, 0), (true, 1), (false, 1)]);
+        assert_eq!(events[0].part_id(), events[1].part_id());
+        assert_ne!(events[0].part_id(), events[2].part_id());
+        assert_eq!(tracker.to_response().parts, parts);
+        assert!(!tracker.has_open());
+    }
+
+    #[tokio::test]
+    async fn test_adapter_stream_split_chunks() {
+        let events = collect(vec![
+            "data: {\"text\":\"Hel",
+            "lo\"}\n\ndata: {\"text\":\" world\"}\n\n",
+            "data: [DONE]\n\ndata: {\"text\":\"ignored\"}\n\n",
+        ])
+        .await;
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].as_ref().unwrap().is_start());
+        assert!(events[1].as_ref().unwrap().is_delta());
+    }
+
+    #[tokio::test]
+    async fn test_adapter_stream_done_and_errors() {
+        let events = collect(vec![
+            "data: not json\n",
+            "data: {\"text\":\"fail\"}\n",
+            "data: {\"text\":\"hi\",\"stop\":true}\n",
+            "data: {\"text\":\"after\"}\n",
+        ])
+        .await;
+
+        assert_eq!(events.len(), 3);
+        assert!(events[0].is_err());
+        assert!(events[1].as_ref().unwrap().is_start());
+        assert!(events[2].as_ref().unwrap().is_end());
+    }
+
+    #[tokio::test]
+    async fn test_adapter_stream_unterminated_last_line() {
+        let events = collect(vec!["{\"text\":\"a\"}\n{\"text\":\"b\"}"]).await;
+        assert_eq!(events.len(), 2);
+    }
+}
@@ -0,0 +1,542 @@
+//! Splitting of embedded thinking tags.
+//!
+//! Some reasoning models (DeepSeek R1 and its distills, QwQ) return their
+//! chain of thought inline in the text, wrapped in tags such as
+//! `<think>...</think>`. This module turns that text into separate
+//! [`ThinkingPart`]s and [`TextPart`]s, both for complete responses and for
+//! streams where tags may be split across chunks.
+//!
+//! [`ThinkingTagModel`] applies the split to any model, e.g. DeepSeek R1
+//! distills served through Ollama:
+//!
+//! ```rust,ignore
+//! use serdes_ai_models::ThinkingTagModel;
+//!
+//! let model = ThinkingTagModel::new(OllamaModel::new("deepseek-r1:8b"));
+//! ```
+
+use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
+use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::profile::ModelProfile;
+use crate::stream_adapter::{PartKey, PartTracker};
+use async_trait::async_trait;
+use futures::Stream;
+use pin_project_lite::pin_project;
+use serdes_ai_core::messages::{ModelResponseStreamEvent, TextPart, ThinkingPart};
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelResponsePart, ModelSettings};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A piece of text classified as thinking or regular text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Content inside thinking tags.
+    Thinking(String),
+    /// Content outside thinking tags.
+    Text(String),
+}
+
+/// Incremental splitter for text with embedded thinking tags.
+#[derive(Debug, Clone)]
+pub struct ThinkingTagSplitter {
+    open_tag: String,
+    close_tag: String,
+    in_thinking: bool,
+    pending: String,
+}
+
+impl ThinkingTagSplitter {
+    /// Create a splitter for the given tags.
+    pub fn new(open_tag: impl Into<String>, close_tag: impl Into<String>) -> Self {
+        Self {
+            open_tag: open_tag.into(),
+            close_tag: close_tag.into(),
+            in_thinking: false,
+            pending: String::new(),
+        }
+    }
+
+    /// Check whether the splitter is inside a thinking block.
+    pub fn in_thinking(&self) -> bool {
+        self.in_thinking
+    }
+
+    /// Feed a chunk of text, returning the segments that are complete.
+    ///
+    /// A trailing partial tag is held back until the next chunk.
+    pub fn push(&mut self, text: &str) -> Vec<Segment> {
+        self.pending.push_str(text);
+        let mut segments = Vec::new();
+
+        loop {
+            let tag = if self.in_thinking {
+                &self.close_tag
+            } else {
+                &self.open_tag
+            };
+
+            if let Some(pos) = self.pending.find(tag.as_str()) {
+                let before: String = self.pending.drain(..pos).collect();
+                self.pending.drain(..tag.len());
+                self.emit(&mut segments, before);
+                self.in_thinking = !self.in_thinking;
+                continue;
+            }
+
+            // Hold back a suffix that could be the start of the tag
+            let keep = partial_suffix_len(&self.pending, tag);
+            let split = self.pending.len() - keep;
+            let ready: String = self.pending.drain(..split).collect();
+            self.emit(&mut segments, ready);
+            return segments;
+        }
+    }
+
+    /// Flush any held-back text.
+    pub fn flush(&mut self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let rest = std::mem::take(&mut self.pending);
+        self.emit(&mut segments, rest);
+        segments
+    }
+
+    fn emit(&self, segments: &mut Vec<Segment>, content: String) {
+        if content.is_empty() {
+            return;
+        }
+        segments.push(if self.in_thinking {
+            Segment::Thinking(content)
+        } else {
+            Segment::Text(content)
+        });
+    }
+}
+
+/// Length of the longest suffix of `content` that is a proper prefix of `tag`.
+fn partial_suffix_len(content: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&n| {
+            content.len() >= n
+                && content.is_char_boundary(content.len() - n)
+                && tag.starts_with(&content[content.len() - n..])
+        })
+        .unwrap_or(0)
+}
+
+/// Split thinking tags in all text parts of a response.
+///
+/// Whitespace-only text left between blocks is dropped.
+pub fn split_thinking_tags(response: &mut ModelResponse, open_tag: &str, close_tag: &str) {
+    let parts = std::mem::take(&mut response.parts);
+    for part in parts {
+        let ModelResponsePart::Text(text) = part else {
+            response.parts.push(part);
+            continue;
+        };
+        if !text.content.contains(open_tag) {
+            response.parts.push(ModelResponsePart::Text(text));
+            continue;
+        }
+
+        let mut splitter = ThinkingTagSplitter::new(open_tag, close_tag);
+        let mut segments = splitter.push(&text.content);
+        segments.extend(splitter.flush());
+        for segment in segments {
+            match segment {
+                Segment::Thinking(content) => response
+                    .parts
+                    .push(ModelResponsePart::Thinking(ThinkingPart::new(content))),
+                Segment::Text(content) if !content.trim().is_empty() => {
+                    response
+                        .parts
+                        .push(ModelResponsePart::Text(TextPart::new(content.trim_start())));
+                }
+                Segment::Text(_) => {}
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Stream that splits thinking tags out of text deltas.
+    ///
+    /// Text parts are re-emitted as separate thinking and text parts; all
+    /// other parts pass through with re-numbered indices.
+    pub struct ThinkingTagStream<S> {
+        #[pin]
+        inner: S,
+        splitter: ThinkingTagSplitter,
+        parts: PartTracker,
+        // Original indices of text parts
+        text_indices: Vec<usize>,
+        pending: VecDeque<ModelResponseStreamEvent>,
+    }
+}
+
+impl<S> ThinkingTagStream<S>
+where
+    S: Stream<Item = Result<ModelResponseStreamEvent, ModelError>>,
+{
+    /// Wrap a stream, splitting the given tags.
+    pub fn new(inner: S, open_tag: impl Into<String>, close_tag: impl Into<String>) -> Self {
+        Self {
+            inner,
+            splitter: ThinkingTagSplitter::new(open_tag, close_tag),
+            parts: PartTracker::new(),
+            text_indices: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// Queue events for split segments.
+fn push_segments(
+    segments: Vec<Segment>,
+    parts: &mut PartTracker,
+    pending: &mut VecDeque<ModelResponseStreamEvent>,
+) {
+    for segment in segments {
+        match segment {
+            Segment::Thinking(content) => {
+                pending.push_back(parts.thinking(&content));
+            }
+            Segment::Text(content) => {
+                // Don't start a text part for the whitespace after a block
+                if parts.get(&PartKey::Text).is_none() && content.trim().is_empty() {
+                    continue;
+                }
+                // Closing the thinking block when text starts
+                if let Some(end) = parts.close(&PartKey::Thinking) {
+                    pending.push_back(end);
+                }
+                let content = if parts.get(&PartKey::Text).is_none() {
+                    content.trim_start()
+                } else {
+                    content.as_str()
+                };
+                pending.push_back(parts.text(content));
+            }
+        }
+    }
+}
+
+impl<S> Stream for ThinkingTagStream<S>
+where
+    S: Stream<Item = Result<ModelResponseStreamEvent, ModelError>>,
+{
+    type Item = Result<ModelResponseStreamEvent, ModelError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(event) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            let event = match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => event,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => {
+                    let segments = this.splitter.flush();
+                    if segments.is_empty() && !this.parts.has_open() {
+                        return Poll::Ready(None);
+                    }
+                    push_segments(segments, this.parts, this.pending);
+                    this.pending.extend(this.parts.close_all());
+                    continue;
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match event {
+                ModelResponseStreamEvent::PartStart(start) => match start.part {
+                    ModelResponsePart::Text(text) => {
+                        this.text_indices.push(start.index);
+                        let segments = this.splitter.push(&text.content);
+                        push_segments(segments, this.parts, this.pending);
+                    }
+                    part => {
+                        let key = PartKey::Block(start.index as u64);
+                        this.pending.push_back(this.parts.start(key, part));
+                    }
+                },
+                ModelResponseStreamEvent::PartDelta(delta) => {
+                    if this.text_indices.contains(&delta.index) {
+                        if let Some(content) = delta.delta.content_delta() {
+                            let segments = this.splitter.push(content);
+                            push_segments(segments, this.parts, this.pending);
+                        }
+                    } else {
+                        let key = PartKey::Block(delta.index as u64);
+                        this.pending.extend(this.parts.delta(&key, delta.delta));
+                    }
+                }
+                ModelResponseStreamEvent::PartEnd(end) => {
+                    if this.text_indices.contains(&end.index) {
+                        let segments = this.splitter.flush();
+                        push_segments(segments, this.parts, this.pending);
+                        this.pending.extend(this.parts.close(&PartKey::Thinking));
+                        this.pending.extend(this.parts.close(&PartKey::Text));
+                    } else {
+                        this.pending
+                            .extend(this.parts.close(&PartKey::Block(end.index as u64)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Model wrapper that extracts inline thinking blocks from responses.
+///
+/// Both complete and streamed responses are split, so thinking arrives as
+/// [`ThinkingPart`]s no matter how the model was called. The tags default to
+/// the wrapped model's [`ModelProfile::thinking_tags`].
+#[derive(Debug)]
+pub struct ThinkingTagModel<M> {
+    inner: M,
+    open_tag: String,
+    close_tag: String,
+}
+
+impl<M: Model> ThinkingTagModel<M> {
+    /// Wrap a model, using the tags from its profile.
+    pub fn new(model: M) -> Self {
+        let (open_tag, close_tag) = model.profile().thinking_tags.clone();
+        Self {
+            inner: model,
+            open_tag,
+            close_tag,
+        }
+    }
+
+    /// Set the tags that delimit thinking blocks.
+    #[must_use]
+    pub fn with_tags(mut self, open_tag: impl Into<String>, close_tag: impl Into<String>) -> Self {
+        self.open_tag = open_tag.into();
+        self.close_tag = close_tag.into();
+        self
+    }
+
+    /// Get the wrapped model.
+    pub fn inner(&self) -> &M {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<M: Model> Model for ThinkingTagModel<M> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn system(&self) -> &str {
+        self.inner.system()
+    }
+
+    fn identifier(&self) -> String {
+        self.inner.identifier()
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
+    async fn request(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<ModelResponse, ModelError> {
+        let mut response = self.inner.request(messages, settings, params).await?;
+        split_thinking_tags(&mut response, &self.open_tag, &self.close_tag);
+        Ok(response)
+    }
+
+    async fn request_stream(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<StreamedResponse, ModelError> {
+        let stream = self
+            .inner
+            .request_stream(messages, settings, params)
+            .await?;
+        Ok(Box::pin(ThinkingTagStream::new(
+            stream,
+            self.open_tag.clone(),
+            self.close_tag.clone(),
+        )))
+    }
+
+    async fn count_tokens(&self, messages: &[ModelRequest]) -> Result<u64, ModelError> {
+        self.inner.count_tokens(messages).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{stream, StreamExt};
+    use serdes_ai_core::messages::PartStartEvent;
+
+    fn splitter() -> ThinkingTagSplitter {
+        ThinkingTagSplitter::new("<think>", "</think>")
+    }
+
+    #[test]
+    fn test_splitter_whole_text() {
+        let mut s = splitter();
+        let segments = s.push("<think>hmm</think>Answer");
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Thinking("hmm".into()),
+                Segment::Text("Answer".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_splitter_tags_across_chunks() {
+        let mut s = splitter();
+        let mut segments = Vec::new();
+        for chunk in ["<thi", "nk>a", "b</th", "ink>", "c"] {
+            segments.extend(s.push(chunk));
+        }
+        segments.extend(s.flush());
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Thinking("a".into()),
+                Segment::Thinking("b".into()),
+                Segment::Text("c".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_response_parts() {
+        let mut response = ModelResponse::with_parts(vec![ModelResponsePart::text(
+            "<think>\nLet me think.\n</think>\n\nThe answer is 4.",
+        )]);
+        split_thinking_tags(&mut response, "<think>", "</think>");
+
+        assert_eq!(response.parts.len(), 2);
+        match &response.parts[0] {
+            ModelResponsePart::Thinking(t) => assert_eq!(t.content, "\nLet me think.\n"),
+            other => panic!("Expected thinking, got {:?}", other),
+        }
+        match &response.parts[1] {
+            ModelResponsePart::Text(t) => assert_eq!(t.content, "The answer is 4."),
+            other => panic!("Expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_split_response_without_tags_is_unchanged() {
+        let mut response = ModelResponse::text("plain");
+        split_thinking_tags(&mut response, "<think>", "</think>");
+        assert_eq!(response.parts, vec![ModelResponsePart::text("plain")]);
+    }
+
+    #[tokio::test]
+    async fn test_thinking_tag_model() {
+        use crate::mock::FunctionModel;
+
+        let model = ThinkingTagModel::new(FunctionModel::with_both(
+            |_, _| ModelResponse::text("<reasoning>hmm</reasoning>Yes"),
+            |_, _| {
+                let events = vec![
+                    ModelResponseStreamEvent::PartStart(PartStartEvent::text(0, "<reason")),
+                    ModelResponseStreamEvent::text_delta(0, "ing>hmm</reasoning>Yes"),
+                    ModelResponseStreamEvent::part_end(0),
+                ];
+                Box::pin(stream::iter(events.into_iter().map(Ok)))
+            },
+        ))
+        .with_tags("<reasoning>", "</reasoning>");
+
+        let response = model
+            .request(
+                &[],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.parts,
+            vec![
+                ModelResponsePart::Thinking(ThinkingPart::new("hmm")),
+                ModelResponsePart::text("Yes"),
+            ]
+        );
+
+        let events: Vec<_> = model
+            .request_stream(
+                &[],
+                &ModelSettings::default(),
+                &ModelRequestParameters::new(),
+            )
+            .await
+            .unwrap()
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ModelResponseStreamEvent::PartStart(s)
+                if matches!(&s.part, ModelResponsePart::Thinking(t) if t.content == "hmm")
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_stream_splits_thinking() {
+        let events = vec![
+            ModelResponseStreamEvent::PartStart(PartStartEvent::text(0, "<think>why")),
+            ModelResponseStreamEvent::text_delta(0, " not</thi"),
+            ModelResponseStreamEvent::text_delta(0, "nk>\n\nDone"),
+            ModelResponseStreamEvent::part_end(0),
+            ModelResponseStreamEvent::PartStart(PartStartEvent::tool_call(1, "search")),
+            ModelResponseStreamEvent::tool_call_delta(1, "{}"),
+            ModelResponseStreamEvent::part_end(1),
+        ];
+        let inner = stream::iter(events.into_iter().map(Ok));
+        let out: Vec<_> = ThinkingTagStream::new(inner, "<think>", "</think>")
+            .map(|e| e.unwrap())
+            .collect()
+            .await;
+
+        let mut thinking = String::new();
+        let mut text = String::new();
+        for event in &out {
+            match event {
+                ModelResponseStreamEvent::PartStart(s) => match &s.part {
+                    ModelResponsePart::Thinking(t) => thinking.push_str(&t.content),
+                    ModelResponsePart::Text(t) => text.push_str(&t.content),
+                    _ => assert_eq!(s.index, 2),
+                },
+                ModelResponseStreamEvent::PartDelta(d) if d.index == 0 => {
+                    thinking.push_str(d.delta.content_delta().unwrap())
+                }
+                ModelResponseStreamEvent::PartDelta(d) if d.index == 1 => {
+                    text.push_str(d.delta.content_delta().unwrap())
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(thinking, "why not");
+        assert_eq!(text, "Done");
+        assert_eq!(out.iter().filter(|e| e.is_end()).count(), 3);
+    }
+}
@@ -0,0 +1,339 @@
+//! vLLM model implementation.
+//!
+//! [vLLM](https://docs.vllm.ai) serves models behind an OpenAI-compatible
+//! API with extensions for guided decoding, which constrains generation so
+//! the output always matches a JSON schema, regex, grammar or fixed set of
+//! choices.
+//!
+//! Guided decoding is selected per request through the `"vllm"` key of
+//! [`ModelSettings::extra`](serdes_ai_core::ModelSettings), or automatically
+//! from the output schema when the agent uses structured output.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use serdes_ai_models::vllm::{GuidedDecoding, VllmModel};
+//!
+//! let model = VllmModel::new("http://localhost:8000/v1", "Qwen/Qwen2.5-7B-Instruct");
+//!
+//! let settings = GuidedDecoding::Choice(vec!["positive".into(), "negative".into()])
+//!     .apply_to(ModelSettings::new());
+//! ```
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::error::ModelError;
+use crate::http_capture::HttpExchange;
+use crate::model::{Model, ModelRequestParameters, StreamedResponse};
+use crate::openai::OpenAIChatModel;
+use crate::profile::ModelProfile;
+use serdes_ai_core::{ModelRequest, ModelResponse, ModelSettings};
+use serdes_ai_output::OutputMode;
+
+/// Guided decoding constraint.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuidedDecoding {
+    /// Output must match a JSON schema.
+    Json(JsonValue),
+    /// Output must match a regular expression.
+    Regex(String),
+    /// Output must be one of the given choices.
+    Choice(Vec<String>),
+    /// Output must match an EBNF grammar.
+    Grammar(String),
+}
+
+impl GuidedDecoding {
+    /// Request body field for this constraint.
+    pub fn field_name(&self) -> &'static str {
+        match self {
+            Self::Json(_) => "guided_json",
+            Self::Regex(_) => "guided_regex",
+            Self::Choice(_) => "guided_choice",
+            Self::Grammar(_) => "guided_grammar",
+        }
+    }
+
+    /// Request body value for this constraint.
+    pub fn field_value(&self) -> JsonValue {
+        match self {
+            Self::Json(schema) => schema.clone(),
+            Self::Regex(s) | Self::Grammar(s) => JsonValue::String(s.clone()),
+            Self::Choice(choices) => JsonValue::from(choices.clone()),
+        }
+    }
+
+    /// Store this constraint in model settings for a single request.
+    #[must_use]
+    pub fn apply_to(&self, settings: ModelSettings) -> ModelSettings {
+        let extra = serde_json::json!({
+            VllmModel::SETTINGS_KEY: { self.field_name(): self.field_value() },
+        });
+        settings.merge(&ModelSettings::new().extra(extra))
+    }
+}
+
+/// Fields that select a guided decoding backend constraint.
+const GUIDED_FIELDS: &[&str] = &[
+    "guided_json",
+    "guided_regex",
+    "guided_choice",
+    "guided_grammar",
+];
+
+/// vLLM model client.
+///
+/// vLLM uses an OpenAI-compatible API, so this wraps OpenAIChatModel and
+/// adds the vLLM request extensions.
+#[derive(Debug, Clone)]
+pub struct VllmModel {
+    /// Inner OpenAI-compatible model.
+    inner: OpenAIChatModel,
+    /// Whether to derive `guided_json` from the output schema.
+    auto_guided_json: bool,
+}
+
+impl VllmModel {
+    /// Default vLLM server URL.
+    pub const DEFAULT_BASE_URL: &'static str = "http://localhost:8000/v1";
+
+    /// Key under which vLLM extensions are read from [`ModelSettings::extra`].
+    pub const SETTINGS_KEY: &'static str = "vllm";
+
+    /// Create a model for the server at `base_url`.
+    pub fn new(base_url: impl Into<String>, model_name: impl Into<String>) -> Self {
+        let inner = OpenAIChatModel::new(model_name, "")
+            .with_base_url(base_url)
+            .with_timeout(Duration::from_secs(600));
+        Self {
+            inner,
+            auto_guided_json: true,
+        }
+    }
+
+    /// Create from environment variables `VLLM_BASE_URL` (default
+    /// `http://localhost:8000/v1`) and optional `VLLM_API_KEY`.
+    pub fn from_env(model_name: impl Into<String>) -> Result<Self, ModelError> {
+        let base_url =
+            std::env::var("VLLM_BASE_URL").unwrap_or_else(|_| Self::DEFAULT_BASE_URL.to_string());
+        let api_key = std::env::var("VLLM_API_KEY").unwrap_or_default();
+        let inner = OpenAIChatModel::new(model_name, api_key)
+            .with_base_url(base_url)
+            .with_timeout(Duration::from_secs(600));
+        Ok(Self {
+            inner,
+            auto_guided_json: true,
+        })
+    }
+
+    /// Set the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.with_timeout(timeout);
+        self
+    }
+
+    /// Set a custom HTTP client.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.inner = self.inner.with_client(client);
+        self
+    }
+
+    /// Set a custom profile.
+    #[must_use]
+    pub fn with_profile(mut self, profile: ModelProfile) -> Self {
+        self.inner = self.inner.with_profile(profile);
+        self
+    }
+
+    /// Set whether `guided_json` is derived from the output schema.
+    ///
+    /// Enabled by default. When enabled, native and prompted structured
+    /// output are enforced with guided decoding instead of `response_format`.
+    #[must_use]
+    pub fn with_auto_guided_json(mut self, enabled: bool) -> Self {
+        self.auto_guided_json = enabled;
+        self
+    }
+
+    /// Get the base URL.
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    /// Collect the vLLM extension fields for a request.
+    fn extension_fields(
+        &self,
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Map<String, JsonValue> {
+        let mut fields = settings
+            .extra
+            .as_ref()
+            .and_then(|extra| extra.get(Self::SETTINGS_KEY))
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        let explicit_guidance = GUIDED_FIELDS.iter().any(|f| fields.contains_key(*f));
+        if self.auto_guided_json && !explicit_guidance {
+            if let Some(schema) = self.output_schema(params) {
+                fields.insert("guided_json".into(), schema);
+            }
+        }
+
+        fields
+    }
+
+    /// Output schema to enforce with guided decoding, if any.
+    fn output_schema(&self, params: &ModelRequestParameters) -> Option<JsonValue> {
+        match params.output_mode {
+            OutputMode::Native | OutputMode::Prompted => params
+                .output_schema
+                .as_ref()
+                .and_then(|s| serde_json::to_value(s).ok()),
+            _ => None,
+        }
+    }
+
+    /// Build the model and parameters for a single request.
+    fn prepare(
+        &self,
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> (OpenAIChatModel, ModelRequestParameters) {
+        let fields = self.extension_fields(settings, params);
+        let mut params = params.clone();
+        // vLLM rejects `response_format` combined with a guided decoding field
+        if GUIDED_FIELDS.iter().any(|f| fields.contains_key(*f)) {
+            params.output_schema = None;
+        }
+
+        let model = fields
+            .into_iter()
+            .fold(self.inner.clone(), |model, (key, value)| {
+                model.with_extra_body_field(key, value)
+            });
+        (model, params)
+    }
+}
+
+#[async_trait]
+impl Model for VllmModel {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn system(&self) -> &str {
+        "vllm"
+    }
+
+    fn profile(&self) -> &ModelProfile {
+        self.inner.profile()
+    }
+
+    fn last_exchange(&self) -> Option<HttpExchange> {
+        self.inner.last_exchange()
+    }
+
+    async fn request(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<ModelResponse, ModelError> {
+        let (model, params) = self.prepare(settings, params);
+        model.request(messages, settings, &params).await
+    }
+
+    async fn request_stream(
+        &self,
+        messages: &[ModelRequest],
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> Result<StreamedResponse, ModelError> {
+        let (model, params) = self.prepare(settings, params);
+        model.request_stream(messages, settings, &params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_tools::ObjectJsonSchema;
+
+    fn body(
+        model: &VllmModel,
+        settings: &ModelSettings,
+        params: &ModelRequestParameters,
+    ) -> JsonValue {
+        let (inner, params) = model.prepare(settings, params);
+        let req = inner.build_request(&[ModelRequest::new()], settings, &params, false);
+        serde_json::to_value(&req).unwrap()
+    }
+
+    fn schema() -> ObjectJsonSchema {
+        serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "required": ["name"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_vllm_defaults() {
+        let model = VllmModel::new(VllmModel::DEFAULT_BASE_URL, "Qwen/Qwen2.5-7B-Instruct");
+        assert_eq!(model.name(), "Qwen/Qwen2.5-7B-Instruct");
+        assert_eq!(model.system(), "vllm");
+        assert_eq!(model.base_url(), "http://localhost:8000/v1");
+    }
+
+    #[test]
+    fn test_guided_choice_from_settings() {
+        let model = VllmModel::new(VllmModel::DEFAULT_BASE_URL, "m");
+        let settings =
+            GuidedDecoding::Choice(vec!["yes".into(), "no".into()]).apply_to(ModelSettings::new());
+        let json = body(&model, &settings, &ModelRequestParameters::new());
+        assert_eq!(json["guided_choice"], serde_json::json!(["yes", "no"]));
+    }
+
+    #[test]
+    fn test_guided_json_from_output_schema() {
+        let model = VllmModel::new(VllmModel::DEFAULT_BASE_URL, "m");
+        let params = ModelRequestParameters::new()
+            .with_output_schema(schema())
+            .with_output_mode(OutputMode::Native);
+        let json = body(&model, &ModelSettings::new(), &params);
+        assert_eq!(json["guided_json"]["required"], serde_json::json!(["name"]));
+        assert!(json.get("response_format").is_none());
+    }
+
+    #[test]
+    fn test_explicit_guidance_wins_over_schema() {
+        let model = VllmModel::new(VllmModel::DEFAULT_BASE_URL, "m");
+        let params = ModelRequestParameters::new()
+            .with_output_schema(schema())
+            .with_output_mode(OutputMode::Native);
+        let settings = GuidedDecoding::Regex(r"\d+".into()).apply_to(ModelSettings::new());
+        let json = body(&model, &settings, &params);
+        assert_eq!(json["guided_regex"], r"\d+");
+        assert!(json.get("guided_json").is_none());
+    }
+
+    #[test]
+    fn test_auto_guided_json_disabled() {
+        let model = VllmModel::new(VllmModel::DEFAULT_BASE_URL, "m").with_auto_guided_json(false);
+        let params = ModelRequestParameters::new()
+            .with_output_schema(schema())
+            .with_output_mode(OutputMode::Native);
+        let json = body(&model, &ModelSettings::new(), &params);
+        assert!(json.get("guided_json").is_none());
+        assert!(json.get("response_format").is_some());
+    }
+}
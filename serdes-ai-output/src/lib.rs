@@ -89,7 +89,7 @@ pub use schema::{BoxedOutputSchema, OutputSchema, OutputSchemaWrapper};
 pub use spec::{IntoOutputSpec, OutputSpec, OutputSpecBuilder};
 pub use structured::{
     extract_json, AnyJsonSchema, StructuredOutputSchema, DEFAULT_OUTPUT_TOOL_DESCRIPTION,
-    DEFAULT_OUTPUT_TOOL_NAME,
+    DEFAULT_OUTPUT_TOOL_NAME, LIST_ENVELOPE_KEY, VALUE_ENVELOPE_KEY,
 };
 pub use text::{TextOutputSchema, TextOutputSchemaBuilder};
 pub use toolset::{OutputCaptured, OutputToolset};
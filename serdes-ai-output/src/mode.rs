@@ -31,6 +31,15 @@ pub enum OutputMode {
     /// but there's no structural enforcement.
     Prompted,
 
+    /// Use JSON mode (`response_format: json_object` in OpenAI) with the
+    /// schema given in the prompt.
+    ///
+    /// The model is guaranteed to produce a JSON object but not one that
+    /// matches the schema, so the output is still validated on parse.
+    /// Sits between `Native` and `Prompted` for models that support JSON
+    /// mode but not JSON schemas.
+    JsonObject,
+
     /// Use a tool call to return the result.
     ///
     /// This is the most reliable mode for structured output. A special
@@ -46,6 +55,7 @@ impl fmt::Display for OutputMode {
             OutputMode::Text => write!(f, "text"),
             OutputMode::Native => write!(f, "native"),
             OutputMode::Prompted => write!(f, "prompted"),
+            OutputMode::JsonObject => write!(f, "json_object"),
             OutputMode::Tool => write!(f, "tool"),
         }
     }
@@ -77,6 +87,7 @@ impl OutputMode {
             OutputMode::Text,
             OutputMode::Native,
             OutputMode::Prompted,
+            OutputMode::JsonObject,
             OutputMode::Tool,
         ]
     }
@@ -91,6 +102,7 @@ impl std::str::FromStr for OutputMode {
             "text" => Ok(OutputMode::Text),
             "native" => Ok(OutputMode::Native),
             "prompted" | "json" => Ok(OutputMode::Prompted),
+            "json_object" | "json_mode" => Ok(OutputMode::JsonObject),
             "tool" | "function" | "function_call" => Ok(OutputMode::Tool),
             _ => Err(format!("Unknown output mode: {}", s)),
         }
@@ -111,6 +123,7 @@ mod tests {
         assert_eq!(OutputMode::Text.to_string(), "text");
         assert_eq!(OutputMode::Native.to_string(), "native");
         assert_eq!(OutputMode::Prompted.to_string(), "prompted");
+        assert_eq!(OutputMode::JsonObject.to_string(), "json_object");
         assert_eq!(OutputMode::Tool.to_string(), "tool");
     }
 
@@ -123,6 +136,10 @@ mod tests {
             OutputMode::Prompted
         );
         assert_eq!("json".parse::<OutputMode>().unwrap(), OutputMode::Prompted);
+        assert_eq!(
+            "json_object".parse::<OutputMode>().unwrap(),
+            OutputMode::JsonObject
+        );
         assert_eq!("tool".parse::<OutputMode>().unwrap(), OutputMode::Tool);
         assert_eq!("function".parse::<OutputMode>().unwrap(), OutputMode::Tool);
     }
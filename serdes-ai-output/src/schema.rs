@@ -42,7 +42,9 @@ pub trait OutputSchema<T: Send>: Send + Sync {
         match mode {
             OutputMode::Text => true, // Text is always supported
             OutputMode::Tool => !self.tool_definitions().is_empty(),
-            OutputMode::Native | OutputMode::Prompted => self.json_schema().is_some(),
+            OutputMode::Native | OutputMode::Prompted | OutputMode::JsonObject => {
+                self.json_schema().is_some()
+            }
         }
     }
 
@@ -73,7 +75,7 @@ pub trait OutputSchema<T: Send>: Send + Sync {
                 let args = args.ok_or_else(|| OutputParseError::custom("No tool arguments"))?;
                 self.parse_tool_call(name, args)
             }
-            OutputMode::Native | OutputMode::Prompted => {
+            OutputMode::Native | OutputMode::Prompted | OutputMode::JsonObject => {
                 // Try tool call first, then native JSON
                 if let (Some(name), Some(args)) = (tool_name, args) {
                     return self.parse_tool_call(name, args);
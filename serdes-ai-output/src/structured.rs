@@ -2,6 +2,10 @@
 //!
 //! This module provides `StructuredOutputSchema` for handling typed
 //! structured output using serde deserialization.
+//!
+//! Tool parameters and most native structured output APIs require an object
+//! root. Lists and primitives are wrapped in an envelope object
+//! (`{"items": [...]}` or `{"value": ...}`) that is removed again when parsing.
 
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
@@ -19,6 +23,12 @@ pub const DEFAULT_OUTPUT_TOOL_NAME: &str = "final_result";
 /// Default tool description for structured output.
 pub const DEFAULT_OUTPUT_TOOL_DESCRIPTION: &str = "The final response which ends this conversation";
 
+/// Envelope property wrapping list outputs.
+pub const LIST_ENVELOPE_KEY: &str = "items";
+
+/// Envelope property wrapping primitive outputs.
+pub const VALUE_ENVELOPE_KEY: &str = "value";
+
 /// Schema for structured output using serde.
 ///
 /// This schema parses model output into a typed Rust struct using serde.
@@ -55,6 +65,8 @@ pub struct StructuredOutputSchema<T> {
     pub strict: Option<bool>,
     /// Output mode preference.
     mode: OutputMode,
+    /// Envelope property the output is wrapped in, if any.
+    envelope: Option<String>,
     _phantom: PhantomData<T>,
 }
 
@@ -68,10 +80,74 @@ impl<T: DeserializeOwned + Send + Sync> StructuredOutputSchema<T> {
             schema,
             strict: None,
             mode: OutputMode::Tool,
+            envelope: None,
             _phantom: PhantomData,
         }
     }
 
+    /// Create a schema for a list output, e.g. `Vec<Item>`.
+    ///
+    /// The list is wrapped as `{"items": [...]}`.
+    #[must_use]
+    pub fn list(item_schema: JsonValue) -> Self {
+        Self::enveloped(
+            LIST_ENVELOPE_KEY,
+            serde_json::json!({"type": "array", "items": item_schema}),
+        )
+    }
+
+    /// Create a schema for a primitive output, e.g. `u32` or `String`.
+    ///
+    /// The value is wrapped as `{"value": ...}`.
+    #[must_use]
+    pub fn value(schema: JsonValue) -> Self {
+        Self::enveloped(VALUE_ENVELOPE_KEY, schema)
+    }
+
+    /// Create a schema from a JSON schema with any root type.
+    ///
+    /// Object roots are used as is; array roots are wrapped like
+    /// [`list`](Self::list) and other roots like [`value`](Self::value).
+    #[must_use]
+    pub fn from_json_schema(schema: JsonValue) -> Self {
+        match schema.get("type").and_then(JsonValue::as_str) {
+            Some("object") => match serde_json::from_value(schema.clone()) {
+                Ok(object) => Self::new(object),
+                Err(_) => Self::value(schema),
+            },
+            Some("array") => Self::enveloped(LIST_ENVELOPE_KEY, schema),
+            _ => Self::value(schema),
+        }
+    }
+
+    fn enveloped(key: &str, schema: JsonValue) -> Self {
+        let mut output = Self::new(ObjectJsonSchema::new().with_property(key, schema, true));
+        output.envelope = Some(key.to_string());
+        output
+    }
+
+    /// Get the envelope property the output is wrapped in, if any.
+    #[must_use]
+    pub fn envelope(&self) -> Option<&str> {
+        self.envelope.as_deref()
+    }
+
+    /// Remove the envelope from a value.
+    ///
+    /// Values the model returned without the envelope are passed through.
+    fn unwrap_envelope(&self, value: JsonValue) -> JsonValue {
+        match (&self.envelope, value) {
+            (Some(key), JsonValue::Object(mut map)) if map.len() == 1 && map.contains_key(key) => {
+                map.remove(key).unwrap_or(JsonValue::Null)
+            }
+            (_, value) => value,
+        }
+    }
+
+    fn deserialize(&self, value: JsonValue) -> Result<T, OutputParseError> {
+        serde_json::from_value(self.unwrap_envelope(value)).map_err(OutputParseError::JsonParse)
+    }
+
     /// Set the tool name.
     #[must_use]
     pub fn with_tool_name(mut self, name: impl Into<String>) -> Self {
@@ -119,19 +195,25 @@ impl<T: DeserializeOwned + Send + Sync> OutputSchema<T> for StructuredOutputSche
 
     fn parse_text(&self, text: &str) -> Result<T, OutputParseError> {
         // Try to extract JSON from text (may be wrapped in markdown)
-        let json_str = extract_json(text)?;
-        serde_json::from_str(&json_str).map_err(OutputParseError::JsonParse)
+        let json_str = match extract_json(text) {
+            Ok(json) => json,
+            // Bare primitives such as `42` or `true`
+            Err(_) if self.envelope.is_some() => text.trim().to_string(),
+            Err(e) => return Err(e),
+        };
+        let value = serde_json::from_str(&json_str).map_err(OutputParseError::JsonParse)?;
+        self.deserialize(value)
     }
 
     fn parse_tool_call(&self, name: &str, args: &JsonValue) -> Result<T, OutputParseError> {
         if name != self.tool_name {
             return Err(OutputParseError::unexpected_tool(&self.tool_name, name));
         }
-        serde_json::from_value(args.clone()).map_err(OutputParseError::JsonParse)
+        self.deserialize(args.clone())
     }
 
     fn parse_native(&self, value: &JsonValue) -> Result<T, OutputParseError> {
-        serde_json::from_value(value.clone()).map_err(OutputParseError::JsonParse)
+        self.deserialize(value.clone())
     }
 }
 
@@ -349,6 +431,41 @@ Done!"#;
         assert_eq!(result.age, 28);
     }
 
+    #[test]
+    fn test_structured_schema_list() {
+        let schema: StructuredOutputSchema<Vec<Person>> =
+            StructuredOutputSchema::list(serde_json::to_value(person_schema()).unwrap());
+        assert_eq!(schema.envelope(), Some(LIST_ENVELOPE_KEY));
+        assert_eq!(schema.schema.properties["items"]["type"], "array");
+
+        let args = serde_json::json!({"items": [{"name": "Alice", "age": 30}]});
+        let people = schema.parse_tool_call("final_result", &args).unwrap();
+        assert_eq!(people[0].name, "Alice");
+
+        // Lists returned without the envelope are accepted too
+        let people = schema
+            .parse_text(r#"[{"name": "Bob", "age": 25}, {"name": "Eve", "age": 41}]"#)
+            .unwrap();
+        assert_eq!(people.len(), 2);
+    }
+
+    #[test]
+    fn test_structured_schema_primitive() {
+        let schema: StructuredOutputSchema<u32> =
+            StructuredOutputSchema::from_json_schema(serde_json::json!({"type": "integer"}));
+        assert_eq!(schema.envelope(), Some(VALUE_ENVELOPE_KEY));
+        assert_eq!(schema.schema.required, vec!["value"]);
+
+        let value = serde_json::json!({"value": 42});
+        assert_eq!(schema.parse_native(&value).unwrap(), 42);
+        assert_eq!(schema.parse_text("42").unwrap(), 42);
+
+        let object: StructuredOutputSchema<Person> = StructuredOutputSchema::from_json_schema(
+            serde_json::to_value(person_schema()).unwrap(),
+        );
+        assert_eq!(object.envelope(), None);
+    }
+
     #[test]
     fn test_extract_json_code_block() {
         let text = r#"```json
@@ -80,6 +80,23 @@ impl TextOutputSchema {
         self
     }
 
+    /// Check a prefix of streamed text output.
+    ///
+    /// Fails fast once the maximum length is exceeded, since more text can
+    /// never make the output valid. Minimum length and pattern checks are
+    /// deferred until the complete text is parsed.
+    pub fn check_partial(&self, text: &str) -> Result<(), OutputParseError> {
+        let text = if self.trim_whitespace {
+            text.trim()
+        } else {
+            text
+        };
+        match self.max_length {
+            Some(max) if text.len() > max => Err(OutputParseError::too_long(text.len(), max)),
+            _ => Ok(()),
+        }
+    }
+
     /// Validate the text against configured constraints.
     fn validate_text(&self, text: &str) -> Result<String, OutputParseError> {
         let text = if self.trim_whitespace {
@@ -232,6 +249,20 @@ mod tests {
         assert_eq!(result, "hello world");
     }
 
+    #[test]
+    fn test_text_schema_check_partial() {
+        let schema = TextOutputSchema::new()
+            .with_min_length(5)
+            .with_max_length(8)
+            .with_pattern(r"^\d+$")
+            .unwrap();
+
+        // Too short and not matching yet, but could still become valid
+        assert!(schema.check_partial("12").is_ok());
+        assert!(schema.check_partial("abc").is_ok());
+        assert!(schema.check_partial("123456789").is_err());
+    }
+
     #[test]
     fn test_text_schema_min_length() {
         let schema = TextOutputSchema::new().with_min_length(10);
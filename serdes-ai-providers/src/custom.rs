@@ -0,0 +1,383 @@
+//! Custom OpenAI-compatible providers loaded from configuration.
+//!
+//! Internal or self-hosted endpoints can be registered at runtime under their
+//! own prefix (e.g. `mycorp:` → `mycorp:llama-70b`) without code changes.
+//! Providers are described in a JSON file:
+//!
+//! ```json
+//! {
+//!   "providers": [
+//!     {
+//!       "name": "mycorp",
+//!       "base_url": "https://llm.mycorp.internal/v1",
+//!       "api_key_env": "MYCORP_API_KEY",
+//!       "headers": { "x-team": "search" }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! [`ProviderRegistry::load_config_file`] registers the providers of a file,
+//! and [`watch_config_files`] reloads loaded files when they change or the
+//! process receives `SIGHUP`.
+
+use crate::provider::{Provider, ProviderError};
+use crate::registry::ProviderRegistry;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serdes_ai_models::ModelProfile;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Configuration of a custom OpenAI-compatible provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProviderConfig {
+    /// Provider name, used as the model string prefix.
+    pub name: String,
+    /// Base URL of the OpenAI-compatible API.
+    pub base_url: String,
+    /// API key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key, read on every (re)load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// Extra headers sent with every request.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+impl CustomProviderConfig {
+    /// Create a config for an endpoint.
+    pub fn new(name: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_url: base_url.into(),
+            api_key: None,
+            api_key_env: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Set the API key.
+    #[must_use]
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Read the API key from an environment variable.
+    #[must_use]
+    pub fn with_api_key_env(mut self, var: impl Into<String>) -> Self {
+        self.api_key_env = Some(var.into());
+        self
+    }
+
+    /// Add a header sent with every request.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// Contents of a custom provider config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomProvidersFile {
+    /// Providers to register.
+    #[serde(default)]
+    pub providers: Vec<CustomProviderConfig>,
+}
+
+impl CustomProvidersFile {
+    /// Read and parse a config file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProviderError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ProviderError::InvalidConfig(format!("failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            ProviderError::InvalidConfig(format!("failed to parse {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// OpenAI-compatible provider configured at runtime.
+#[derive(Debug)]
+pub struct CustomProvider {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    headers: HeaderMap,
+    client: Client,
+}
+
+impl CustomProvider {
+    /// Create a provider from its config.
+    pub fn from_config(config: &CustomProviderConfig) -> Result<Self, ProviderError> {
+        if config.name.is_empty() || config.name.contains(':') {
+            return Err(ProviderError::InvalidConfig(format!(
+                "invalid provider name '{}'",
+                config.name
+            )));
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes());
+            let header_value = HeaderValue::from_str(value);
+            match (header_name, header_value) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => {
+                    return Err(ProviderError::InvalidConfig(format!(
+                        "invalid header '{}' for provider '{}'",
+                        name, config.name
+                    )))
+                }
+            }
+        }
+
+        let api_key = config.api_key.clone().or_else(|| {
+            config
+                .api_key_env
+                .as_ref()
+                .and_then(|var| std::env::var(var).ok())
+        });
+
+        Ok(Self {
+            name: config.name.clone(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            api_key,
+            headers,
+            client: Client::new(),
+        })
+    }
+}
+
+impl Provider for CustomProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+
+    fn default_headers(&self) -> HeaderMap {
+        let mut headers = self.headers.clone();
+
+        if let Some(key) = &self.api_key {
+            if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", key)) {
+                headers.insert(AUTHORIZATION, value);
+            }
+        }
+
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        headers
+    }
+
+    #[allow(clippy::field_reassign_with_default)]
+    fn model_profile(&self, _model_name: &str) -> Option<ModelProfile> {
+        let mut profile = ModelProfile::default();
+        profile.supports_tools = true;
+        profile.supports_system_messages = true;
+        profile.supports_streaming = true;
+        Some(profile)
+    }
+
+    fn is_configured(&self) -> bool {
+        self.api_key.is_some()
+    }
+}
+
+/// Spawn a task that reloads the registry's config files.
+///
+/// Files are checked for changes every `interval`; on Unix, `SIGHUP` reloads
+/// all of them immediately. A file that fails to load keeps its previous
+/// providers.
+pub fn watch_config_files<R>(registry: R, interval: Duration) -> tokio::task::JoinHandle<()>
+where
+    R: Deref<Target = ProviderRegistry> + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut modified: HashMap<PathBuf, Option<SystemTime>> = registry
+            .config_files()
+            .into_iter()
+            .map(|path| {
+                let mtime = modified_time(&path);
+                (path, mtime)
+            })
+            .collect();
+
+        #[cfg(unix)]
+        let mut hangup =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+
+        loop {
+            #[cfg(unix)]
+            let force = match hangup.as_mut() {
+                Some(signal) => tokio::select! {
+                    _ = tokio::time::sleep(interval) => false,
+                    _ = signal.recv() => true,
+                },
+                None => {
+                    tokio::time::sleep(interval).await;
+                    false
+                }
+            };
+            #[cfg(not(unix))]
+            let force = {
+                tokio::time::sleep(interval).await;
+                false
+            };
+
+            for path in registry.config_files() {
+                let mtime = modified_time(&path);
+                let changed = modified.get(&path) != Some(&mtime);
+                if !force && !changed {
+                    continue;
+                }
+                modified.insert(path.clone(), mtime);
+                match registry.load_config_file(&path) {
+                    Ok(names) => {
+                        tracing::info!(
+                            "Reloaded {} custom providers from {}",
+                            names.len(),
+                            path.display()
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("Keeping previous custom providers: {}", e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn write_config(path: &Path, providers: &[CustomProviderConfig]) {
+        let file = CustomProvidersFile {
+            providers: providers.to_vec(),
+        };
+        std::fs::write(path, serde_json::to_string(&file).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_custom_provider_headers() {
+        let config = CustomProviderConfig::new("mycorp", "https://llm.mycorp.test/v1/")
+            .with_api_key("secret")
+            .with_header("x-team", "search");
+        let provider = CustomProvider::from_config(&config).unwrap();
+
+        assert_eq!(provider.base_url(), "https://llm.mycorp.test/v1");
+        assert!(provider.is_configured());
+        let headers = provider.default_headers();
+        assert_eq!(headers[AUTHORIZATION], "Bearer secret");
+        assert_eq!(headers["x-team"], "search");
+
+        let invalid = CustomProviderConfig::new("my:corp", "https://llm.mycorp.test");
+        assert!(matches!(
+            CustomProvider::from_config(&invalid),
+            Err(ProviderError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_config_file_replaces_previous_providers() {
+        let path = std::env::temp_dir().join(format!(
+            "serdes_ai_custom_providers_{}.json",
+            std::process::id()
+        ));
+        let registry = ProviderRegistry::new();
+
+        write_config(
+            &path,
+            &[
+                CustomProviderConfig::new("mycorp", "https://a.test/v1"),
+                CustomProviderConfig::new("lab", "https://lab.test/v1"),
+            ],
+        );
+        let mut names = registry.load_config_file(&path).unwrap();
+        names.sort();
+        assert_eq!(names, vec!["lab", "mycorp"]);
+
+        let (provider, model) = registry.infer_provider("mycorp:llama-70b").unwrap();
+        assert_eq!(provider.base_url(), "https://a.test/v1");
+        assert_eq!(model, "llama-70b");
+
+        write_config(
+            &path,
+            &[CustomProviderConfig::new("mycorp", "https://b.test/v1")],
+        );
+        registry.load_config_file(&path).unwrap();
+        assert_eq!(
+            registry.get("mycorp").unwrap().base_url(),
+            "https://b.test/v1"
+        );
+        assert!(!registry.contains("lab"));
+
+        // A broken file keeps the previous providers
+        std::fs::write(&path, "{not json").unwrap();
+        assert!(registry.load_config_file(&path).is_err());
+        assert!(registry.contains("mycorp"));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_watch_config_files_reloads_on_change() {
+        let path = std::env::temp_dir().join(format!(
+            "serdes_ai_watched_providers_{}.json",
+            std::process::id()
+        ));
+        write_config(
+            &path,
+            &[CustomProviderConfig::new("mycorp", "https://a.test")],
+        );
+
+        let registry = Arc::new(ProviderRegistry::new());
+        registry.load_config_file(&path).unwrap();
+        let watcher = watch_config_files(Arc::clone(&registry), Duration::from_millis(10));
+
+        // Make sure the modification time changes even on coarse filesystems
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        write_config(
+            &path,
+            &[CustomProviderConfig::new("newcorp", "https://n.test")],
+        );
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        for _ in 0..100 {
+            if registry.contains("newcorp") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(registry.contains("newcorp"));
+        assert!(!registry.contains("mycorp"));
+
+        watcher.abort();
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -41,14 +41,208 @@
 //!     .with_backend("openai")  // Optional: specify backend provider
 //!     .with_api_key("key");
 //! ```
+//!
+//! ## Per-Request Options
+//!
+//! [`GatewayOptions`] describes trace ids, metadata, virtual keys, caching,
+//! fallbacks and session affinity once and maps them to each gateway's
+//! conventions:
+//!
+//! ```rust,ignore
+//! let options = GatewayOptions::new()
+//!     .with_trace_id("run-42")
+//!     .with_metadata("team", "search")
+//!     .with_cache(GatewayCache::ttl(Duration::from_secs(300)))
+//!     .with_fallback_model("gpt-4o-mini");
+//!
+//! // Keep a conversation on one backend, for better prompt cache hits
+//! let options = GatewayOptions::from_settings(&settings).merged(&options);
+//!
+//! let headers = portkey.request_headers(&options);
+//! let body_extras = litellm.request_body_extras(&options);
+//! ```
 
 use crate::provider::{Provider, ProviderError};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
 use reqwest::Client;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use serdes_ai_core::ModelSettings;
 use serdes_ai_models::ModelProfile;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::time::Duration;
 
+/// Gateway caching behavior for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatewayCache {
+    /// Serve from and store in the cache.
+    Enabled {
+        /// How long responses stay cached; gateway default if `None`.
+        ttl: Option<Duration>,
+    },
+    /// Bypass the cache entirely.
+    Skip,
+    /// Don't read from the cache, but store the new response.
+    Refresh,
+}
+
+impl GatewayCache {
+    /// Enable caching with the gateway's default TTL.
+    #[must_use]
+    pub fn enabled() -> Self {
+        Self::Enabled { ttl: None }
+    }
+
+    /// Enable caching with a TTL.
+    #[must_use]
+    pub fn ttl(ttl: Duration) -> Self {
+        Self::Enabled { ttl: Some(ttl) }
+    }
+}
+
+/// Gateway-specific options for a request.
+///
+/// Options a gateway has no convention for are ignored:
+///
+/// | Option | Portkey | LiteLLM (body) | Helicone | Cloudflare |
+/// |---|---|---|---|---|
+/// | trace id | `x-portkey-trace-id` | `metadata.trace_id` | `Helicone-Session-Id` | - |
+/// | metadata | `x-portkey-metadata` | `metadata` | `Helicone-Property-*` | `cf-aig-metadata` |
+/// | virtual key | `x-portkey-virtual-key` | bearer token | - | - |
+/// | cache | `x-portkey-config` | `cache` | `Helicone-Cache-Enabled` | `cf-aig-*-cache*` |
+/// | fallbacks | `x-portkey-config` | `fallbacks` | `Helicone-Fallbacks` | - |
+/// | user | `x-portkey-metadata._user` | `user` | `Helicone-User-Id` | - |
+/// | session affinity | `x-portkey-config` sticky session | `litellm_session_id` | `Helicone-Session-Id` | `cf-aig-metadata` |
+///
+/// Other gateways get `x-request-id` for the trace id and
+/// `x-session-affinity` for the session affinity key.
+///
+/// Helicone sessions are keyed by the session affinity key if one is set;
+/// the trace id is then sent as the `Helicone-Property-Trace-Id` property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GatewayOptions {
+    /// Trace id for correlating requests.
+    pub trace_id: Option<String>,
+    /// Key-value tags for filtering and analytics.
+    pub metadata: BTreeMap<String, String>,
+    /// Virtual key selecting the upstream credentials.
+    pub virtual_key: Option<String>,
+    /// Caching behavior.
+    pub cache: Option<GatewayCache>,
+    /// Models to fall back to, in order, if the request fails.
+    pub fallback_models: Vec<String>,
+    /// End-user id.
+    pub user: Option<String>,
+    /// Key routing requests of one conversation to the same backend.
+    ///
+    /// See [`ModelSettings::session_affinity`].
+    pub session_affinity: Option<String>,
+}
+
+impl GatewayOptions {
+    /// Create empty options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the trace id.
+    #[must_use]
+    pub fn with_trace_id(mut self, id: impl Into<String>) -> Self {
+        self.trace_id = Some(id.into());
+        self
+    }
+
+    /// Add a metadata tag.
+    #[must_use]
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the virtual key.
+    #[must_use]
+    pub fn with_virtual_key(mut self, key: impl Into<String>) -> Self {
+        self.virtual_key = Some(key.into());
+        self
+    }
+
+    /// Set the caching behavior.
+    #[must_use]
+    pub fn with_cache(mut self, cache: GatewayCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Add a fallback model.
+    #[must_use]
+    pub fn with_fallback_model(mut self, model: impl Into<String>) -> Self {
+        self.fallback_models.push(model.into());
+        self
+    }
+
+    /// Set the end-user id.
+    #[must_use]
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Set the session affinity key.
+    #[must_use]
+    pub fn with_session_affinity(mut self, key: impl Into<String>) -> Self {
+        self.session_affinity = Some(key.into());
+        self
+    }
+
+    /// Create options carrying the gateway-relevant parts of model settings.
+    ///
+    /// Currently this is the [`session_affinity`](ModelSettings::session_affinity)
+    /// hint.
+    #[must_use]
+    pub fn from_settings(settings: &ModelSettings) -> Self {
+        Self {
+            session_affinity: settings.session_affinity.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Merge `other` over these options.
+    ///
+    /// Values set in `other` win; metadata is combined.
+    #[must_use]
+    pub fn merged(&self, other: &GatewayOptions) -> Self {
+        let mut metadata = self.metadata.clone();
+        metadata.extend(other.metadata.clone());
+        Self {
+            trace_id: other.trace_id.clone().or_else(|| self.trace_id.clone()),
+            metadata,
+            virtual_key: other
+                .virtual_key
+                .clone()
+                .or_else(|| self.virtual_key.clone()),
+            cache: other.cache.or(self.cache),
+            fallback_models: if other.fallback_models.is_empty() {
+                self.fallback_models.clone()
+            } else {
+                other.fallback_models.clone()
+            },
+            user: other.user.clone().or_else(|| self.user.clone()),
+            session_affinity: other
+                .session_affinity
+                .clone()
+                .or_else(|| self.session_affinity.clone()),
+        }
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) {
+    let header_name: Result<HeaderName, _> = name.try_into();
+    if let (Ok(hn), Ok(hv)) = (header_name, HeaderValue::from_str(value)) {
+        headers.insert(hn, hv);
+    }
+}
+
 /// Configuration for a gateway provider.
 #[derive(Debug, Clone)]
 pub struct GatewayConfig {
@@ -66,6 +260,8 @@ pub struct GatewayConfig {
     pub timeout: Duration,
     /// Provider name override
     pub name: String,
+    /// Options applied to every request
+    pub default_options: GatewayOptions,
 }
 
 impl GatewayConfig {
@@ -79,6 +275,7 @@ impl GatewayConfig {
             custom_headers: HashMap::new(),
             timeout: Duration::from_secs(60),
             name: "gateway".to_string(),
+            default_options: GatewayOptions::default(),
         }
     }
 
@@ -123,6 +320,13 @@ impl GatewayConfig {
         self.name = name.into();
         self
     }
+
+    /// Set options applied to every request.
+    #[must_use]
+    pub fn with_default_options(mut self, options: GatewayOptions) -> Self {
+        self.default_options = options;
+        self
+    }
 }
 
 /// Gateway provider for AI routing services.
@@ -391,30 +595,237 @@ impl GatewayProvider {
         self
     }
 
+    /// Set options applied to every request.
+    #[must_use]
+    pub fn with_default_options(mut self, options: GatewayOptions) -> Self {
+        self.config.default_options = options;
+        self
+    }
+
     /// Get the configured backend, if any.
     pub fn backend(&self) -> Option<&str> {
         self.config.backend.as_deref()
     }
-}
 
-impl Provider for GatewayProvider {
-    fn name(&self) -> &str {
-        &self.config.name
+    /// Get headers for a request with the given options.
+    ///
+    /// `options` are merged over the default options.
+    pub fn request_headers(&self, options: &GatewayOptions) -> HeaderMap {
+        let options = self.config.default_options.merged(options);
+        let mut headers = self.base_headers(options.virtual_key.as_deref());
+
+        match self.config.name.as_str() {
+            "portkey" => self.portkey_headers(&options, &mut headers),
+            "helicone" => Self::helicone_headers(&options, &mut headers),
+            "cloudflare-ai-gateway" => Self::cloudflare_headers(&options, &mut headers),
+            // LiteLLM takes its options in the request body
+            "litellm" => {}
+            _ => {
+                if let Some(trace_id) = &options.trace_id {
+                    insert_header(&mut headers, "x-request-id", trace_id);
+                }
+                if let Some(key) = &options.session_affinity {
+                    insert_header(&mut headers, "x-session-affinity", key);
+                }
+            }
+        }
+
+        headers
     }
 
-    fn base_url(&self) -> &str {
-        &self.config.gateway_url
+    /// Get extra request body fields for the given options.
+    ///
+    /// Only LiteLLM takes options in the body; other gateways return an
+    /// empty map.
+    pub fn request_body_extras(&self, options: &GatewayOptions) -> JsonMap<String, JsonValue> {
+        let options = self.config.default_options.merged(options);
+        let mut extras = JsonMap::new();
+        if self.config.name != "litellm" {
+            return extras;
+        }
+
+        let mut metadata: JsonMap<String, JsonValue> = options
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), json!(v)))
+            .collect();
+        if let Some(trace_id) = &options.trace_id {
+            metadata.insert("trace_id".into(), json!(trace_id));
+        }
+        if !metadata.is_empty() {
+            extras.insert("metadata".into(), JsonValue::Object(metadata));
+        }
+
+        if !options.fallback_models.is_empty() {
+            extras.insert("fallbacks".into(), json!(options.fallback_models));
+        }
+
+        match options.cache {
+            Some(GatewayCache::Enabled { ttl: Some(ttl) }) => {
+                extras.insert("cache".into(), json!({"ttl": ttl.as_secs()}));
+            }
+            Some(GatewayCache::Skip) => {
+                extras.insert("cache".into(), json!({"no-cache": true, "no-store": true}));
+            }
+            Some(GatewayCache::Refresh) => {
+                extras.insert("cache".into(), json!({"no-cache": true}));
+            }
+            Some(GatewayCache::Enabled { ttl: None }) | None => {}
+        }
+
+        if let Some(user) = &options.user {
+            extras.insert("user".into(), json!(user));
+        }
+
+        if let Some(key) = &options.session_affinity {
+            extras.insert("litellm_session_id".into(), json!(key));
+        }
+
+        extras
     }
 
-    fn client(&self) -> &Client {
-        &self.client
+    fn portkey_headers(&self, options: &GatewayOptions, headers: &mut HeaderMap) {
+        if let Some(trace_id) = &options.trace_id {
+            insert_header(headers, "x-portkey-trace-id", trace_id);
+        }
+
+        let mut metadata: JsonMap<String, JsonValue> = options
+            .metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), json!(v)))
+            .collect();
+        if let Some(user) = &options.user {
+            metadata.insert("_user".into(), json!(user));
+        }
+        if let Some(key) = &options.session_affinity {
+            metadata.insert("session_affinity".into(), json!(key));
+        }
+        if !metadata.is_empty() {
+            insert_header(
+                headers,
+                "x-portkey-metadata",
+                &JsonValue::Object(metadata).to_string(),
+            );
+        }
+
+        let mut config = JsonMap::new();
+        if let Some(GatewayCache::Enabled { ttl }) = options.cache {
+            let mut cache = json!({"mode": "simple"});
+            if let Some(ttl) = ttl {
+                cache["max_age"] = json!(ttl.as_secs());
+            }
+            config.insert("cache".into(), cache);
+        }
+        if options.cache == Some(GatewayCache::Refresh) {
+            insert_header(headers, "x-portkey-cache-force-refresh", "true");
+        }
+        if !options.fallback_models.is_empty() {
+            config.insert("strategy".into(), json!({"mode": "fallback"}));
+            let targets: Vec<JsonValue> = std::iter::once(json!({}))
+                .chain(
+                    options
+                        .fallback_models
+                        .iter()
+                        .map(|m| json!({"override_params": {"model": m}})),
+                )
+                .map(|mut target| {
+                    if let Some(vk) = &options.virtual_key {
+                        target["virtual_key"] = json!(vk);
+                    }
+                    target
+                })
+                .collect();
+            config.insert("targets".into(), json!(targets));
+        }
+        if options.session_affinity.is_some() {
+            // Pin load-balanced requests with the same key to one target
+            config.insert(
+                "sticky_session".into(),
+                json!({"hash_fields": ["metadata.session_affinity"]}),
+            );
+        }
+        if !config.is_empty() {
+            insert_header(
+                headers,
+                "x-portkey-config",
+                &JsonValue::Object(config).to_string(),
+            );
+        }
     }
 
-    fn default_headers(&self) -> HeaderMap {
+    fn helicone_headers(options: &GatewayOptions, headers: &mut HeaderMap) {
+        match (&options.session_affinity, &options.trace_id) {
+            (Some(key), trace_id) => {
+                insert_header(headers, "Helicone-Session-Id", key);
+                if let Some(trace_id) = trace_id {
+                    insert_header(headers, "Helicone-Property-Trace-Id", trace_id);
+                }
+            }
+            (None, Some(trace_id)) => insert_header(headers, "Helicone-Session-Id", trace_id),
+            (None, None) => {}
+        }
+        for (key, value) in &options.metadata {
+            insert_header(headers, &format!("Helicone-Property-{}", key), value);
+        }
+        if let Some(user) = &options.user {
+            insert_header(headers, "Helicone-User-Id", user);
+        }
+        match options.cache {
+            Some(GatewayCache::Enabled { ttl }) => {
+                insert_header(headers, "Helicone-Cache-Enabled", "true");
+                if let Some(ttl) = ttl {
+                    insert_header(
+                        headers,
+                        "Cache-Control",
+                        &format!("max-age={}", ttl.as_secs()),
+                    );
+                }
+            }
+            Some(GatewayCache::Skip | GatewayCache::Refresh) => {
+                insert_header(headers, "Helicone-Cache-Enabled", "false");
+            }
+            None => {}
+        }
+        if !options.fallback_models.is_empty() {
+            insert_header(
+                headers,
+                "Helicone-Fallbacks",
+                &json!(options.fallback_models).to_string(),
+            );
+        }
+    }
+
+    fn cloudflare_headers(options: &GatewayOptions, headers: &mut HeaderMap) {
+        // Dynamic routes can match on metadata, so the key goes there
+        let mut metadata = options.metadata.clone();
+        if let Some(key) = &options.session_affinity {
+            metadata.insert("session_affinity".into(), key.clone());
+        }
+        if !metadata.is_empty() {
+            insert_header(headers, "cf-aig-metadata", &json!(metadata).to_string());
+        }
+        match options.cache {
+            Some(GatewayCache::Enabled { ttl: Some(ttl) }) => {
+                insert_header(headers, "cf-aig-cache-ttl", &ttl.as_secs().to_string());
+            }
+            Some(GatewayCache::Skip | GatewayCache::Refresh) => {
+                insert_header(headers, "cf-aig-skip-cache", "true");
+            }
+            Some(GatewayCache::Enabled { ttl: None }) | None => {}
+        }
+    }
+
+    /// Headers that don't depend on request options.
+    fn base_headers(&self, virtual_key: Option<&str>) -> HeaderMap {
         let mut headers = HeaderMap::new();
+        let is_portkey = self.config.name == "portkey";
 
-        // Authorization header
-        if let Some(key) = &self.config.api_key {
+        // Authorization header; LiteLLM virtual keys are bearer tokens
+        let bearer = match virtual_key {
+            Some(vk) if self.config.name == "litellm" => Some(vk),
+            _ => self.config.api_key.as_deref(),
+        };
+        if let Some(key) = bearer {
             let auth_value = format!("Bearer {}", key);
             if let Ok(value) = HeaderValue::from_str(&auth_value) {
                 headers.insert(AUTHORIZATION, value);
@@ -425,7 +836,7 @@ impl Provider for GatewayProvider {
         headers.insert("content-type", HeaderValue::from_static("application/json"));
 
         // Portkey-specific headers
-        if self.config.name == "portkey" {
+        if is_portkey {
             // Portkey uses x-portkey-api-key for its own auth
             if let Some(key) = &self.config.api_key {
                 if let Ok(value) = HeaderValue::from_str(key) {
@@ -434,7 +845,7 @@ impl Provider for GatewayProvider {
             }
 
             // Virtual key for aliased provider keys
-            if let Some(virtual_key) = &self.config.virtual_key {
+            if let Some(virtual_key) = virtual_key.or(self.config.virtual_key.as_deref()) {
                 if let Ok(value) = HeaderValue::from_str(virtual_key) {
                     headers.insert("x-portkey-virtual-key", value);
                 }
@@ -450,15 +861,29 @@ impl Provider for GatewayProvider {
 
         // Custom headers
         for (name, value) in &self.config.custom_headers {
-            let header_name: Result<HeaderName, _> = name.as_str().try_into();
-            let header_value = HeaderValue::from_str(value);
-            if let (Ok(hn), Ok(hv)) = (header_name, header_value) {
-                headers.insert(hn, hv);
-            }
+            insert_header(&mut headers, name, value);
         }
 
         headers
     }
+}
+
+impl Provider for GatewayProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn base_url(&self) -> &str {
+        &self.config.gateway_url
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+
+    fn default_headers(&self) -> HeaderMap {
+        self.request_headers(&GatewayOptions::default())
+    }
 
     #[allow(clippy::field_reassign_with_default)]
     fn model_profile(&self, model_name: &str) -> Option<ModelProfile> {
@@ -636,4 +1061,142 @@ mod tests {
         let litellm = GatewayProvider::litellm("http://localhost:4000");
         assert!(litellm.aliases().contains(&"lite-llm"));
     }
+
+    #[test]
+    fn test_portkey_request_options() {
+        let portkey = GatewayProvider::portkey("pk-test")
+            .with_default_options(GatewayOptions::new().with_metadata("team", "search"));
+        let options = GatewayOptions::new()
+            .with_trace_id("run-42")
+            .with_virtual_key("openai-staging")
+            .with_cache(GatewayCache::ttl(Duration::from_secs(60)))
+            .with_fallback_model("gpt-4o-mini");
+
+        let headers = portkey.request_headers(&options);
+        assert_eq!(headers["x-portkey-trace-id"], "run-42");
+        assert_eq!(headers["x-portkey-virtual-key"], "openai-staging");
+        assert_eq!(headers["x-portkey-metadata"], r#"{"team":"search"}"#);
+
+        let config: JsonValue =
+            serde_json::from_str(headers["x-portkey-config"].to_str().unwrap()).unwrap();
+        assert_eq!(config["cache"], json!({"mode": "simple", "max_age": 60}));
+        assert_eq!(config["strategy"]["mode"], "fallback");
+        assert_eq!(
+            config["targets"][1]["override_params"]["model"],
+            "gpt-4o-mini"
+        );
+        assert!(portkey.request_body_extras(&options).is_empty());
+    }
+
+    #[test]
+    fn test_litellm_request_options() {
+        let litellm = GatewayProvider::litellm("http://localhost:4000").with_api_key("sk-master");
+        let options = GatewayOptions::new()
+            .with_trace_id("run-42")
+            .with_metadata("team", "search")
+            .with_virtual_key("sk-team-key")
+            .with_cache(GatewayCache::Refresh)
+            .with_fallback_model("claude-3-haiku");
+
+        let headers = litellm.request_headers(&options);
+        assert_eq!(headers[AUTHORIZATION], "Bearer sk-team-key");
+        assert!(!headers.contains_key("x-request-id"));
+
+        let extras = litellm.request_body_extras(&options);
+        assert_eq!(
+            extras["metadata"],
+            json!({"team": "search", "trace_id": "run-42"})
+        );
+        assert_eq!(extras["fallbacks"], json!(["claude-3-haiku"]));
+        assert_eq!(extras["cache"], json!({"no-cache": true}));
+
+        // Without a virtual key the configured API key is used
+        let headers = litellm.request_headers(&GatewayOptions::new());
+        assert_eq!(headers[AUTHORIZATION], "Bearer sk-master");
+    }
+
+    #[test]
+    fn test_helicone_and_cloudflare_request_options() {
+        let options = GatewayOptions::new()
+            .with_trace_id("session-1")
+            .with_metadata("team", "search")
+            .with_user("user-7")
+            .with_cache(GatewayCache::Skip);
+
+        let helicone = GatewayProvider::helicone("hc-key", "https://api.openai.com/v1");
+        let headers = helicone.request_headers(&options);
+        assert_eq!(headers["Helicone-Session-Id"], "session-1");
+        assert_eq!(headers["Helicone-Property-team"], "search");
+        assert_eq!(headers["Helicone-User-Id"], "user-7");
+        assert_eq!(headers["Helicone-Cache-Enabled"], "false");
+
+        let cf = GatewayProvider::cloudflare("acc123", "my-gw", "openai");
+        let headers = cf.request_headers(&options);
+        assert_eq!(headers["cf-aig-skip-cache"], "true");
+        assert_eq!(headers["cf-aig-metadata"], r#"{"team":"search"}"#);
+
+        let generic = GatewayProvider::new("https://gateway.com/v1");
+        assert_eq!(
+            generic.request_headers(&options)["x-request-id"],
+            "session-1"
+        );
+    }
+
+    fn affinity_options() -> GatewayOptions {
+        let settings = ModelSettings::new().session_affinity("conv-9");
+        GatewayOptions::from_settings(&settings).with_trace_id("run-42")
+    }
+
+    #[test]
+    fn test_portkey_session_affinity() {
+        let portkey = GatewayProvider::portkey("pk-test");
+        let headers = portkey.request_headers(&affinity_options());
+        let metadata: JsonValue =
+            serde_json::from_str(headers["x-portkey-metadata"].to_str().unwrap()).unwrap();
+        assert_eq!(metadata["session_affinity"], "conv-9");
+        let config: JsonValue =
+            serde_json::from_str(headers["x-portkey-config"].to_str().unwrap()).unwrap();
+        assert_eq!(
+            config["sticky_session"]["hash_fields"],
+            json!(["metadata.session_affinity"])
+        );
+    }
+
+    #[test]
+    fn test_litellm_session_affinity() {
+        let litellm = GatewayProvider::litellm("http://localhost:4000");
+        let extras = litellm.request_body_extras(&affinity_options());
+        assert_eq!(extras["litellm_session_id"], "conv-9");
+        assert_eq!(extras["metadata"]["trace_id"], "run-42");
+    }
+
+    #[test]
+    fn test_helicone_session_affinity() {
+        let helicone = GatewayProvider::helicone("hc-key", "https://api.openai.com/v1");
+        let headers = helicone.request_headers(&affinity_options());
+        assert_eq!(headers["Helicone-Session-Id"], "conv-9");
+        assert_eq!(headers["Helicone-Property-Trace-Id"], "run-42");
+    }
+
+    #[test]
+    fn test_cloudflare_session_affinity() {
+        let cf = GatewayProvider::cloudflare("acc123", "my-gw", "openai");
+        let headers = cf.request_headers(&affinity_options().with_metadata("team", "search"));
+        assert_eq!(
+            headers["cf-aig-metadata"],
+            r#"{"session_affinity":"conv-9","team":"search"}"#
+        );
+    }
+
+    #[test]
+    fn test_generic_session_affinity() {
+        let generic = GatewayProvider::new("https://gateway.com/v1")
+            .with_default_options(GatewayOptions::new().with_session_affinity("default"));
+        let headers = generic.request_headers(&affinity_options());
+        assert_eq!(headers["x-session-affinity"], "conv-9");
+        assert_eq!(
+            generic.request_headers(&GatewayOptions::new())["x-session-affinity"],
+            "default"
+        );
+    }
 }
@@ -79,6 +79,7 @@ impl Provider for GroqProvider {
             supports_parallel_tools: true,
             supports_system_messages: true,
             supports_streaming: true,
+            supports_json_object_output: true,
             ..Default::default()
         };
 
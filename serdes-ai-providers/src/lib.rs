@@ -48,6 +48,7 @@
 //! - `claude-3-5-sonnet-20241022` → Anthropic
 //! - `gemini-2.0-flash` → Google
 
+mod custom;
 mod provider;
 mod registry;
 
@@ -81,6 +82,7 @@ pub use oauth::{
 };
 
 // Re-exports
+pub use custom::{watch_config_files, CustomProvider, CustomProviderConfig, CustomProvidersFile};
 pub use provider::*;
 pub use registry::*;
 
@@ -105,7 +107,7 @@ pub use compatible::{
 };
 
 // Gateway providers
-pub use gateway::{GatewayConfig, GatewayProvider};
+pub use gateway::{GatewayCache, GatewayConfig, GatewayOptions, GatewayProvider};
 
 use std::sync::Arc;
 
@@ -71,6 +71,7 @@ impl Provider for MistralProvider {
             supports_tools: true,
             supports_system_messages: true,
             supports_streaming: true,
+            supports_json_object_output: true,
             ..Default::default()
         };
 
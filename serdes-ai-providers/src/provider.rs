@@ -173,6 +173,10 @@ pub enum ProviderError {
     /// Provider not configured.
     #[error("Provider not configured: {0}")]
     NotConfigured(String),
+
+    /// Invalid provider configuration.
+    #[error("Invalid provider configuration: {0}")]
+    InvalidConfig(String),
 }
 
 #[cfg(test)]
@@ -4,24 +4,27 @@
 //! - Lookup by name
 //! - Model string inference (e.g., "openai:gpt-4o")
 //! - Auto-configuration from environment variables
+//! - Custom OpenAI-compatible providers from config files
 
-use crate::provider::{BoxedProvider, ProviderError};
+use crate::custom::{CustomProvider, CustomProviderConfig, CustomProvidersFile};
+use crate::provider::{BoxedProvider, Provider, ProviderError};
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Registry for looking up providers by name.
 #[derive(Debug, Default)]
 pub struct ProviderRegistry {
     providers: RwLock<HashMap<String, BoxedProvider>>,
+    /// Provider names registered from each config file.
+    config_files: RwLock<HashMap<PathBuf, Vec<String>>>,
 }
 
 impl ProviderRegistry {
     /// Create a new empty registry.
     pub fn new() -> Self {
-        Self {
-            providers: RwLock::new(HashMap::new()),
-        }
+        Self::default()
     }
 
     /// Register a provider.
@@ -38,6 +41,51 @@ impl ProviderRegistry {
         }
     }
 
+    /// Register a custom OpenAI-compatible provider.
+    pub fn register_custom(
+        &self,
+        config: &CustomProviderConfig,
+    ) -> Result<BoxedProvider, ProviderError> {
+        let provider: BoxedProvider = Arc::new(CustomProvider::from_config(config)?);
+        self.register(Arc::clone(&provider));
+        Ok(provider)
+    }
+
+    /// Register the custom providers of a config file.
+    ///
+    /// Loading the same file again replaces the providers it registered
+    /// before, so providers removed from the file are unregistered. If the
+    /// file can't be loaded, the registry is left unchanged.
+    ///
+    /// Returns the names of the registered providers.
+    pub fn load_config_file(&self, path: impl AsRef<Path>) -> Result<Vec<String>, ProviderError> {
+        let path = path.as_ref();
+        let file = CustomProvidersFile::load(path)?;
+        let providers = file
+            .providers
+            .iter()
+            .map(CustomProvider::from_config)
+            .collect::<Result<Vec<_>, _>>()?;
+        let names: Vec<String> = providers.iter().map(|p| p.name().to_string()).collect();
+
+        let mut config_files = self.config_files.write();
+        let mut registered = self.providers.write();
+        for old in config_files.get(path).into_iter().flatten() {
+            registered.remove(old);
+        }
+        for provider in providers {
+            registered.insert(provider.name().to_string(), Arc::new(provider));
+        }
+        config_files.insert(path.to_path_buf(), names.clone());
+
+        Ok(names)
+    }
+
+    /// Get the config files loaded with [`Self::load_config_file`].
+    pub fn config_files(&self) -> Vec<PathBuf> {
+        self.config_files.read().keys().cloned().collect()
+    }
+
     /// Get a provider by name.
     pub fn get(&self, name: &str) -> Option<BoxedProvider> {
         let providers = self.providers.read();
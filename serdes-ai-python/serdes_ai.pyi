@@ -0,0 +1,72 @@
+from typing import Any, Awaitable, Optional
+
+__version__: str
+
+class SerdesAiError(Exception): ...
+
+class RunResult:
+    output: str
+    run_id: str
+    @property
+    def usage(self) -> dict[str, int]: ...
+    def all_messages_json(self) -> str: ...
+
+class ModelResponse:
+    @property
+    def text(self) -> str: ...
+    @property
+    def model_name(self) -> Optional[str]: ...
+    def json(self) -> str: ...
+
+class Agent:
+    def __init__(
+        self,
+        model: str,
+        *,
+        system_prompt: Optional[str] = None,
+        instructions: Optional[str] = None,
+        name: Optional[str] = None,
+        temperature: Optional[float] = None,
+        max_tokens: Optional[int] = None,
+        api_key: Optional[str] = None,
+        base_url: Optional[str] = None,
+    ) -> None: ...
+    @property
+    def name(self) -> Optional[str]: ...
+    @property
+    def model_name(self) -> str: ...
+    def run(
+        self, prompt: str, *, message_history: Optional[str] = None
+    ) -> Awaitable[RunResult]: ...
+    def run_sync(
+        self, prompt: str, *, message_history: Optional[str] = None
+    ) -> RunResult: ...
+
+def model_request(
+    model: str,
+    prompt: str,
+    *,
+    system_prompt: Optional[str] = None,
+    message_history: Optional[str] = None,
+) -> Awaitable[ModelResponse]: ...
+def model_request_sync(
+    model: str,
+    prompt: str,
+    *,
+    system_prompt: Optional[str] = None,
+    message_history: Optional[str] = None,
+) -> ModelResponse: ...
+def evaluate(
+    agent: Agent,
+    cases: list[tuple[str, Optional[str]]],
+    *,
+    scorer: str = "exact",
+    concurrency: int = 4,
+) -> Awaitable[dict[str, Any]]: ...
+def evaluate_sync(
+    agent: Agent,
+    cases: list[tuple[str, Optional[str]]],
+    *,
+    scorer: str = "exact",
+    concurrency: int = 4,
+) -> dict[str, Any]: ...
@@ -0,0 +1,192 @@
+//! The `Agent` and `RunResult` Python classes.
+
+use crate::error::{history_json, parse_history, to_py_err};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serdes_ai::agent::{Agent, AgentBuilder, AgentRunResult, ModelConfig, RunOptions, RunUsage};
+use serdes_ai::core::ModelRequest;
+use std::sync::Arc;
+
+/// An agent with text output, built from a model spec.
+///
+/// ```python
+/// agent = Agent("anthropic:claude-3-5-sonnet-20241022", instructions="Answer in French.")
+/// ```
+#[pyclass(name = "Agent", module = "serdes_ai", frozen)]
+pub struct PyAgent {
+    pub(crate) inner: Arc<Agent<(), String>>,
+}
+
+#[pymethods]
+impl PyAgent {
+    #[new]
+    #[pyo3(signature = (
+        model,
+        *,
+        system_prompt = None,
+        instructions = None,
+        name = None,
+        temperature = None,
+        max_tokens = None,
+        api_key = None,
+        base_url = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        model: String,
+        system_prompt: Option<String>,
+        instructions: Option<String>,
+        name: Option<String>,
+        temperature: Option<f64>,
+        max_tokens: Option<u64>,
+        api_key: Option<String>,
+        base_url: Option<String>,
+    ) -> PyResult<Self> {
+        let mut config = ModelConfig::new(model);
+        if let Some(key) = api_key {
+            config = config.with_api_key(key);
+        }
+        if let Some(url) = base_url {
+            config = config.with_base_url(url);
+        }
+
+        let mut builder = AgentBuilder::from_config(config).map_err(to_py_err)?;
+        if let Some(prompt) = system_prompt {
+            builder = builder.system_prompt(prompt);
+        }
+        if let Some(instructions) = instructions {
+            builder = builder.instructions(instructions);
+        }
+        if let Some(name) = name {
+            builder = builder.name(name);
+        }
+        if let Some(temperature) = temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(tokens) = max_tokens {
+            builder = builder.max_tokens(tokens);
+        }
+
+        Ok(Self {
+            inner: Arc::new(builder.build()),
+        })
+    }
+
+    /// The agent's name, if set.
+    #[getter]
+    fn name(&self) -> Option<String> {
+        self.inner.name().map(str::to_string)
+    }
+
+    /// The model name.
+    #[getter]
+    fn model_name(&self) -> String {
+        self.inner.model().name().to_string()
+    }
+
+    /// Run the agent, returning an awaitable `RunResult`.
+    ///
+    /// `message_history` is pydantic-ai message JSON, e.g. from
+    /// `RunResult.all_messages_json()`.
+    #[pyo3(signature = (prompt, *, message_history = None))]
+    fn run<'py>(
+        &self,
+        py: Python<'py>,
+        prompt: String,
+        message_history: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let agent = Arc::clone(&self.inner);
+        let options = run_options(message_history)?;
+        pyo3_async_runtimes::tokio::future_into_py(py, run_agent(agent, prompt, options))
+    }
+
+    /// Run the agent and block until it finishes, releasing the GIL.
+    #[pyo3(signature = (prompt, *, message_history = None))]
+    fn run_sync(
+        &self,
+        py: Python<'_>,
+        prompt: String,
+        message_history: Option<&str>,
+    ) -> PyResult<PyRunResult> {
+        let agent = Arc::clone(&self.inner);
+        let options = run_options(message_history)?;
+        py.allow_threads(|| block_on(run_agent(agent, prompt, options)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Agent(model={:?})", self.inner.model().name())
+    }
+}
+
+fn run_options(message_history: Option<&str>) -> PyResult<RunOptions> {
+    let mut options = RunOptions::new();
+    if let Some(json) = message_history {
+        options = options.message_history(parse_history(json)?);
+    }
+    Ok(options)
+}
+
+async fn run_agent(
+    agent: Arc<Agent<(), String>>,
+    prompt: String,
+    options: RunOptions,
+) -> PyResult<PyRunResult> {
+    agent
+        .run_with_options(prompt, (), options)
+        .await
+        .map(PyRunResult::from)
+        .map_err(to_py_err)
+}
+
+/// Block on a future with the shared runtime.
+pub(crate) fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    pyo3_async_runtimes::tokio::get_runtime().block_on(future)
+}
+
+/// The result of an agent run.
+#[pyclass(name = "RunResult", module = "serdes_ai", frozen)]
+pub struct PyRunResult {
+    /// The agent's output.
+    #[pyo3(get)]
+    output: String,
+    /// The run ID.
+    #[pyo3(get)]
+    run_id: String,
+    messages: Vec<ModelRequest>,
+    usage: RunUsage,
+}
+
+#[pymethods]
+impl PyRunResult {
+    /// Token usage as a dict.
+    #[getter]
+    fn usage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let usage = PyDict::new(py);
+        usage.set_item("requests", self.usage.request_count)?;
+        usage.set_item("tool_calls", self.usage.tool_call_count)?;
+        usage.set_item("input_tokens", self.usage.request_tokens)?;
+        usage.set_item("output_tokens", self.usage.response_tokens)?;
+        usage.set_item("total_tokens", self.usage.total_tokens)?;
+        Ok(usage)
+    }
+
+    /// The full message history as pydantic-ai message JSON.
+    fn all_messages_json(&self) -> String {
+        history_json(&self.messages)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("RunResult(output={:?})", self.output)
+    }
+}
+
+impl From<AgentRunResult<String>> for PyRunResult {
+    fn from(result: AgentRunResult<String>) -> Self {
+        Self {
+            output: result.output,
+            run_id: result.run_id,
+            messages: result.messages,
+            usage: result.usage,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Direct model requests.
+
+use crate::agent::block_on;
+use crate::error::{history_json, parse_history, to_py_err};
+use pyo3::prelude::*;
+use serdes_ai::core::messages::ModelRequestPart;
+use serdes_ai::core::{ModelRequest, ModelResponse};
+use serdes_ai::direct;
+
+/// A model response.
+#[pyclass(name = "ModelResponse", module = "serdes_ai", frozen)]
+pub struct PyModelResponse {
+    inner: ModelResponse,
+}
+
+#[pymethods]
+impl PyModelResponse {
+    /// The concatenated text of the response.
+    #[getter]
+    fn text(&self) -> String {
+        self.inner.text_content()
+    }
+
+    /// The name of the model that produced the response.
+    #[getter]
+    fn model_name(&self) -> Option<String> {
+        self.inner.model_name.clone()
+    }
+
+    /// The response as a pydantic-ai message JSON list.
+    fn json(&self) -> String {
+        let mut request = ModelRequest::new();
+        request.add_part(ModelRequestPart::ModelResponse(Box::new(
+            self.inner.clone(),
+        )));
+        history_json(&[request])
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ModelResponse(text={:?})", self.inner.text_content())
+    }
+}
+
+/// Make a single request to a model, returning an awaitable `ModelResponse`.
+#[pyfunction]
+#[pyo3(signature = (model, prompt, *, system_prompt = None, message_history = None))]
+pub(crate) fn model_request<'py>(
+    py: Python<'py>,
+    model: String,
+    prompt: String,
+    system_prompt: Option<String>,
+    message_history: Option<&str>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let messages = request_messages(prompt, system_prompt, message_history)?;
+    pyo3_async_runtimes::tokio::future_into_py(py, request(model, messages))
+}
+
+/// Make a single request to a model and block until it answers, releasing
+/// the GIL.
+#[pyfunction]
+#[pyo3(signature = (model, prompt, *, system_prompt = None, message_history = None))]
+pub(crate) fn model_request_sync(
+    py: Python<'_>,
+    model: String,
+    prompt: String,
+    system_prompt: Option<String>,
+    message_history: Option<&str>,
+) -> PyResult<PyModelResponse> {
+    let messages = request_messages(prompt, system_prompt, message_history)?;
+    py.allow_threads(|| block_on(request(model, messages)))
+}
+
+fn request_messages(
+    prompt: String,
+    system_prompt: Option<String>,
+    message_history: Option<&str>,
+) -> PyResult<Vec<ModelRequest>> {
+    let mut messages = match message_history {
+        Some(json) => parse_history(json)?,
+        None => Vec::new(),
+    };
+    let mut request = ModelRequest::new();
+    if let Some(system_prompt) = system_prompt {
+        request.add_system_prompt(system_prompt);
+    }
+    request.add_user_prompt(prompt);
+    messages.push(request);
+    Ok(messages)
+}
+
+async fn request(model: String, messages: Vec<ModelRequest>) -> PyResult<PyModelResponse> {
+    direct::model_request(model, &messages, None, None)
+        .await
+        .map(|inner| PyModelResponse { inner })
+        .map_err(to_py_err)
+}
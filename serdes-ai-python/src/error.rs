@@ -0,0 +1,53 @@
+//! Errors raised to Python.
+
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::PyErr;
+use serdes_ai::core::messages::{from_pydantic_str, to_pydantic_json};
+use serdes_ai::core::ModelRequest;
+use std::fmt::Display;
+
+create_exception!(
+    serdes_ai,
+    SerdesAiError,
+    PyException,
+    "Raised when a model request, agent run or evaluation fails."
+);
+
+/// Convert a Rust error into a [`SerdesAiError`].
+pub(crate) fn to_py_err(err: impl Display) -> PyErr {
+    SerdesAiError::new_err(err.to_string())
+}
+
+/// Parse a pydantic-ai message JSON history.
+pub(crate) fn parse_history(json: &str) -> Result<Vec<ModelRequest>, PyErr> {
+    from_pydantic_str(json)
+        .map_err(|e| PyValueError::new_err(format!("Invalid message history: {e}")))
+}
+
+/// Serialize a history as pydantic-ai message JSON.
+pub(crate) fn history_json(history: &[ModelRequest]) -> String {
+    to_pydantic_json(history).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai::core::messages::ModelRequestPart;
+    use serdes_ai::core::ModelResponse;
+
+    #[test]
+    fn test_history_round_trip() {
+        let mut request = ModelRequest::new();
+        request.add_user_prompt("hi");
+        let mut response = ModelRequest::new();
+        response.add_part(ModelRequestPart::ModelResponse(Box::new(
+            ModelResponse::text("hello"),
+        )));
+
+        let json = history_json(&[request, response]);
+        let history = parse_history(&json).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history_json(&history), json);
+    }
+}
@@ -0,0 +1,126 @@
+//! Evaluating agents from Python.
+
+use crate::agent::{block_on, PyAgent};
+use crate::error::to_py_err;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serdes_ai::agent::Agent;
+use serdes_ai::evals::{
+    EvalOptions, EvalRunner, EvaluationResult, ExactMatchScorer, FunctionScorer,
+};
+use std::sync::Arc;
+
+/// How a case's output is compared to its expected output.
+#[derive(Debug, Clone, Copy)]
+enum Scorer {
+    Exact,
+    Contains,
+}
+
+impl Scorer {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "exact" => Ok(Self::Exact),
+            "contains" => Ok(Self::Contains),
+            other => Err(PyValueError::new_err(format!(
+                "Unknown scorer '{other}', expected 'exact' or 'contains'"
+            ))),
+        }
+    }
+}
+
+/// Run `(prompt, expected)` cases through an agent, returning an awaitable
+/// report dict.
+///
+/// Failed runs are scored on their error message.
+#[pyfunction]
+#[pyo3(signature = (agent, cases, *, scorer = "exact", concurrency = 4))]
+pub(crate) fn evaluate<'py>(
+    py: Python<'py>,
+    agent: &PyAgent,
+    cases: Vec<(String, Option<String>)>,
+    scorer: &str,
+    concurrency: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let scorer = Scorer::parse(scorer)?;
+    let agent = Arc::clone(&agent.inner);
+    pyo3_async_runtimes::tokio::future_into_py(py, run_cases(agent, cases, scorer, concurrency))
+}
+
+/// Run `(prompt, expected)` cases through an agent and block until they
+/// finish, releasing the GIL.
+#[pyfunction]
+#[pyo3(signature = (agent, cases, *, scorer = "exact", concurrency = 4))]
+pub(crate) fn evaluate_sync(
+    py: Python<'_>,
+    agent: &PyAgent,
+    cases: Vec<(String, Option<String>)>,
+    scorer: &str,
+    concurrency: usize,
+) -> PyResult<Report> {
+    let scorer = Scorer::parse(scorer)?;
+    let agent = Arc::clone(&agent.inner);
+    py.allow_threads(|| block_on(run_cases(agent, cases, scorer, concurrency)))
+}
+
+async fn run_cases(
+    agent: Arc<Agent<(), String>>,
+    cases: Vec<(String, Option<String>)>,
+    scorer: Scorer,
+    concurrency: usize,
+) -> PyResult<Report> {
+    let options = EvalOptions::new().concurrency(concurrency);
+    let runner = match scorer {
+        Scorer::Exact => EvalRunner::new().evaluator(ExactMatchScorer::new().trim()),
+        Scorer::Contains => EvalRunner::new().evaluator(FunctionScorer::new(
+            "Contains",
+            |output: &str, expected: Option<&str>| match expected {
+                Some(expected) if !output.contains(expected) => {
+                    EvaluationResult::fail(format!("Output does not contain '{expected}'"))
+                }
+                _ => EvaluationResult::pass(),
+            },
+        )),
+    }
+    .options(options);
+
+    let report = runner
+        .run_simple(&cases, |prompt| {
+            let agent = Arc::clone(&agent);
+            let prompt = prompt.to_string();
+            async move {
+                match agent.run(prompt, ()).await {
+                    Ok(result) => result.output,
+                    Err(e) => e.to_string(),
+                }
+            }
+        })
+        .await
+        .map_err(to_py_err)?;
+    report.to_json().map(Report).map_err(to_py_err)
+}
+
+/// A serialized evaluation report, converted to a dict on the way out.
+pub(crate) struct Report(String);
+
+impl<'py> IntoPyObject<'py> for Report {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> PyResult<Self::Output> {
+        py.import("json")?.call_method1("loads", (self.0,))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scorer_parse() {
+        assert!(matches!(Scorer::parse("exact"), Ok(Scorer::Exact)));
+        assert!(matches!(Scorer::parse("contains"), Ok(Scorer::Contains)));
+        assert!(Scorer::parse("regex").is_err());
+    }
+}
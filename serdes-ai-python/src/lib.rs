@@ -0,0 +1,61 @@
+//! # serdes-ai-python
+//!
+//! Python bindings for serdes-ai, built with [pyo3](https://pyo3.rs).
+//!
+//! The `serdes_ai` Python module exposes a narrow API for reusing Rust
+//! agents from Python services:
+//!
+//! - `Agent`: build an agent from a model spec and run it
+//! - `model_request`: a single direct model request
+//! - `evaluate`: run cases through an agent with the eval runner
+//!
+//! Every blocking call has an awaitable twin. Awaitables run on a shared
+//! tokio runtime and resolve on the caller's asyncio loop; the `*_sync`
+//! variants release the GIL while they wait, so other Python threads keep
+//! running.
+//!
+//! Message histories cross the boundary as pydantic-ai message JSON, so they
+//! can be loaded with `ModelMessagesTypeAdapter.validate_json` on the Python
+//! side.
+//!
+//! ```python
+//! import serdes_ai
+//!
+//! agent = serdes_ai.Agent("openai:gpt-4o", system_prompt="Be concise.")
+//! result = await agent.run("What is the capital of France?")
+//! print(result.output)
+//!
+//! followup = agent.run_sync(
+//!     "And of Germany?",
+//!     message_history=result.all_messages_json(),
+//! )
+//! ```
+//!
+//! Build the wheel with `maturin build --release` from this directory.
+
+use pyo3::prelude::*;
+
+mod agent;
+mod direct;
+mod error;
+mod evals;
+
+pub use agent::{PyAgent, PyRunResult};
+pub use direct::PyModelResponse;
+pub use error::SerdesAiError;
+
+/// The `serdes_ai` Python module.
+#[pymodule]
+#[pyo3(name = "serdes_ai")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("SerdesAiError", m.py().get_type::<SerdesAiError>())?;
+    m.add_class::<PyAgent>()?;
+    m.add_class::<PyRunResult>()?;
+    m.add_class::<PyModelResponse>()?;
+    m.add_function(wrap_pyfunction!(direct::model_request, m)?)?;
+    m.add_function(wrap_pyfunction!(direct::model_request_sync, m)?)?;
+    m.add_function(wrap_pyfunction!(evals::evaluate, m)?)?;
+    m.add_function(wrap_pyfunction!(evals::evaluate_sync, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,224 @@
+//! Per-host circuit breakers.
+//!
+//! When a provider is down, retrying every request against it only adds
+//! load and latency. A circuit breaker counts consecutive failures per host
+//! and, once they reach a threshold, fails requests to that host immediately
+//! for a cooldown period. After the cooldown one trial request is let
+//! through: if it succeeds the circuit closes again, otherwise it reopens.
+//!
+//! State lives in a [`HostCircuits`] registry shared through an [`Arc`], so
+//! every [`RetryClient`](crate::RetryClient) talking to the same provider
+//! sees the same circuit, whichever model instance it belongs to.
+//! [`HostCircuits::global`] is a process-wide registry for that purpose.
+
+use crate::error::RetryableError;
+use chrono::{DateTime, Utc};
+use serdes_ai_core::clock;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tracing::warn;
+
+/// Configuration of the circuit breakers in a [`HostCircuits`] registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit rejects requests before a trial request.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Create the default config: open after 5 failures for 30 seconds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the consecutive failures that open the circuit.
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Set how long an open circuit rejects requests.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+/// State of a host's circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are rejected until the cooldown ends.
+    Open,
+    /// The cooldown ended; one trial request decides the next state.
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct HostCircuit {
+    failures: u32,
+    opened_at: Option<DateTime<Utc>>,
+    trial_in_flight: bool,
+}
+
+/// Registry of circuit breakers, one per host.
+#[derive(Debug, Default)]
+pub struct HostCircuits {
+    config: CircuitBreakerConfig,
+    hosts: Mutex<HashMap<String, HostCircuit>>,
+}
+
+impl HostCircuits {
+    /// Create a registry.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the process-wide registry, with the default config.
+    pub fn global() -> Arc<Self> {
+        static GLOBAL: OnceLock<Arc<HostCircuits>> = OnceLock::new();
+        GLOBAL.get_or_init(Arc::default).clone()
+    }
+
+    /// Get the config.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Get the state of a host's circuit.
+    pub fn state(&self, host: &str) -> CircuitState {
+        let hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        match hosts.get(host).and_then(|c| c.opened_at) {
+            None => CircuitState::Closed,
+            Some(opened_at) if self.remaining(opened_at).is_some() => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Check whether a request to `host` may be sent.
+    ///
+    /// Fails with [`RetryableError::CircuitOpen`] while the circuit is open,
+    /// and while the trial request of a half-open circuit is in flight.
+    pub fn check(&self, host: &str) -> Result<(), RetryableError> {
+        let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(circuit) = hosts.get_mut(host) else {
+            return Ok(());
+        };
+        let Some(opened_at) = circuit.opened_at else {
+            return Ok(());
+        };
+        if let Some(remaining) = self.remaining(opened_at) {
+            return Err(RetryableError::circuit_open(host, Some(remaining)));
+        }
+        if circuit.trial_in_flight {
+            return Err(RetryableError::circuit_open(host, None));
+        }
+        circuit.trial_in_flight = true;
+        Ok(())
+    }
+
+    /// Record a request to `host` that reached a healthy server.
+    pub fn record_success(&self, host: &str) {
+        let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        hosts.remove(host);
+    }
+
+    /// Record a request to `host` that failed because of the server or the
+    /// network.
+    pub fn record_failure(&self, host: &str) {
+        let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        let circuit = hosts.entry(host.to_string()).or_default();
+        circuit.failures += 1;
+        let reopen = circuit.trial_in_flight;
+        circuit.trial_in_flight = false;
+        if reopen || circuit.failures >= self.config.failure_threshold {
+            if circuit.opened_at.is_none() || reopen {
+                warn!(
+                    host,
+                    failures = circuit.failures,
+                    cooldown_ms = self.config.cooldown.as_millis() as u64,
+                    "Circuit opened"
+                );
+            }
+            circuit.opened_at = Some(clock::now());
+        }
+    }
+
+    /// Time left in the cooldown of a circuit opened at `opened_at`.
+    fn remaining(&self, opened_at: DateTime<Utc>) -> Option<Duration> {
+        let elapsed = (clock::now() - opened_at).to_std().unwrap_or_default();
+        self.config
+            .cooldown
+            .checked_sub(elapsed)
+            .filter(|d| !d.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serdes_ai_core::clock::{use_clock, MockClock};
+
+    #[test]
+    fn test_opens_after_threshold_and_recovers() {
+        let clock = MockClock::epoch();
+        let _guard = use_clock(clock.clone());
+        let circuits = HostCircuits::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(2)
+                .cooldown(Duration::from_secs(10)),
+        );
+
+        circuits.record_failure("api.example.com");
+        assert_eq!(circuits.state("api.example.com"), CircuitState::Closed);
+        circuits.record_failure("api.example.com");
+        assert_eq!(circuits.state("api.example.com"), CircuitState::Open);
+
+        let err = circuits.check("api.example.com").unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(10)));
+        // Other hosts are unaffected
+        assert!(circuits.check("other.example.com").is_ok());
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(circuits.state("api.example.com"), CircuitState::HalfOpen);
+        assert!(circuits.check("api.example.com").is_ok());
+        // Only one trial request at a time
+        assert!(circuits.check("api.example.com").is_err());
+
+        circuits.record_success("api.example.com");
+        assert_eq!(circuits.state("api.example.com"), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_failed_trial_reopens() {
+        let clock = MockClock::epoch();
+        let _guard = use_clock(clock.clone());
+        let circuits = HostCircuits::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(1)
+                .cooldown(Duration::from_secs(5)),
+        );
+
+        circuits.record_failure("h");
+        clock.advance(Duration::from_secs(5));
+        assert!(circuits.check("h").is_ok());
+        circuits.record_failure("h");
+        assert_eq!(circuits.state("h"), CircuitState::Open);
+    }
+}
@@ -14,6 +14,9 @@ pub struct RetryConfig {
     pub retry_on: RetryCondition,
     /// Whether to reraise the last error if all retries fail.
     pub reraise: bool,
+    /// Name of the retried operation, e.g. a model name, recorded on retry
+    /// log events.
+    pub operation: Option<String>,
 }
 
 impl Default for RetryConfig {
@@ -27,6 +30,7 @@ impl Default for RetryConfig {
             },
             retry_on: RetryCondition::default(),
             reraise: true,
+            operation: None,
         }
     }
 }
@@ -98,6 +102,12 @@ impl RetryConfig {
         self
     }
 
+    /// Name the retried operation in log events.
+    pub fn operation(mut self, name: impl Into<String>) -> Self {
+        self.operation = Some(name.into());
+        self
+    }
+
     /// Create config for API calls with sensible defaults.
     pub fn for_api() -> Self {
         Self::new()
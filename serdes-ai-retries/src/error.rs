@@ -32,6 +32,15 @@ pub enum RetryableError {
     #[error("Connection error: {0}")]
     Connection(String),
 
+    /// The host's circuit breaker is open.
+    #[error("Circuit open for {host}")]
+    CircuitOpen {
+        /// Host whose circuit is open.
+        host: String,
+        /// Time until the circuit lets a trial request through.
+        retry_after: Option<Duration>,
+    },
+
     /// Other error.
     #[error("{0}")]
     Other(#[from] anyhow::Error),
@@ -57,11 +66,20 @@ impl RetryableError {
         Self::Connection(msg.into())
     }
 
+    /// Create a circuit-open error.
+    pub fn circuit_open(host: impl Into<String>, retry_after: Option<Duration>) -> Self {
+        Self::CircuitOpen {
+            host: host.into(),
+            retry_after,
+        }
+    }
+
     /// Get the suggested retry-after duration.
     pub fn retry_after(&self) -> Option<Duration> {
         match self {
             Self::Http { retry_after, .. } => *retry_after,
             Self::RateLimited { retry_after } => *retry_after,
+            Self::CircuitOpen { retry_after, .. } => *retry_after,
             _ => None,
         }
     }
@@ -73,10 +91,25 @@ impl RetryableError {
             Self::RateLimited { .. } => true,
             Self::Timeout => true,
             Self::Connection(_) => true,
+            // Failing fast is the point of an open circuit
+            Self::CircuitOpen { .. } => false,
             Self::Other(_) => false,
         }
     }
 
+    /// Get a stable snake_case name of the error variant, for logs and
+    /// metrics.
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::Http { .. } => "http",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Timeout => "timeout",
+            Self::Connection(_) => "connection",
+            Self::CircuitOpen { .. } => "circuit_open",
+            Self::Other(_) => "other",
+        }
+    }
+
     /// Get the HTTP status if this is an HTTP error.
     pub fn status(&self) -> Option<u16> {
         match self {
@@ -120,4 +153,11 @@ mod tests {
         let err = RetryableError::RateLimited { retry_after: None };
         assert_eq!(err.status(), Some(429));
     }
+
+    #[test]
+    fn test_class() {
+        assert_eq!(RetryableError::http(503, "unavailable").class(), "http");
+        assert_eq!(RetryableError::rate_limited(None).class(), "rate_limited");
+        assert_eq!(RetryableError::connection("reset").class(), "connection");
+    }
 }
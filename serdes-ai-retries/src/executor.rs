@@ -4,8 +4,7 @@ use crate::config::RetryConfig;
 use crate::error::{RetryResult, RetryableError};
 use std::future::Future;
 use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 /// State of a retry attempt.
 #[derive(Debug, Clone)]
@@ -46,12 +45,18 @@ pub struct AttemptInfo {
 
 /// Execute an operation with retries.
 ///
+/// Every retry emits a `WARN` event with structured fields operators can
+/// alert on: `operation` (see [`RetryConfig::operation`]), `attempt`,
+/// `max_attempts`, `wait_ms`, `error_class` (see [`RetryableError::class`])
+/// and `status`. Exhausting the retries emits another `WARN` event, and
+/// succeeding after a retry an `INFO` event.
+///
 /// # Example
 ///
 /// ```ignore
 /// use serdes_ai_retries::{with_retry, RetryConfig};
 ///
-/// let config = RetryConfig::for_api();
+/// let config = RetryConfig::for_api().operation("openai:gpt-4o");
 /// let result = with_retry(&config, || async {
 ///     // Your async operation here
 ///     Ok("success")
@@ -62,67 +67,19 @@ where
     F: Fn() -> Fut,
     Fut: Future<Output = RetryResult<T>>,
 {
-    let mut state = RetryState::default();
-    let max_attempts = config.max_retries.saturating_add(1);
-
-    loop {
-        state.attempt += 1;
-
-        debug!(
-            attempt = state.attempt,
-            max_attempts,
-            max_retries = config.max_retries,
-            "Executing retry attempt"
-        );
-
-        match operation().await {
-            Ok(result) => {
-                state.history.push(AttemptInfo {
-                    attempt: state.attempt,
-                    success: true,
-                    error: None,
-                    wait_time: Duration::ZERO,
-                });
-                return Ok(result);
-            }
-            Err(error) => {
-                let should_retry =
-                    state.attempt < max_attempts && config.retry_on.should_retry(&error);
-
-                if !should_retry {
-                    warn!(
-                        attempt = state.attempt,
-                        error = %error,
-                        "Retry exhausted or error not retryable"
-                    );
-                    return Err(error);
-                }
-
-                let wait = config.wait.calculate(state.attempt, error.retry_after());
-                state.total_wait_time += wait;
-                state.last_error = Some(format!("{}", error));
-
-                state.history.push(AttemptInfo {
-                    attempt: state.attempt,
-                    success: false,
-                    error: Some(format!("{}", error)),
-                    wait_time: wait,
-                });
-
-                debug!(
-                    attempt = state.attempt,
-                    wait_ms = wait.as_millis(),
-                    error = %error,
-                    "Waiting before retry"
-                );
+    with_retry_state(config, operation).await.0
+}
 
-                sleep(wait).await;
-            }
-        }
+/// Wait before a retry, unless the current clock is simulated.
+pub(crate) async fn wait_for(duration: Duration) {
+    if !serdes_ai_core::clock::clock().skip_wait(duration) {
+        tokio::time::sleep(duration).await;
     }
 }
 
 /// Execute with retries and get state information.
+///
+/// Emits the same log events as [`with_retry`].
 pub async fn with_retry_state<F, Fut, T>(
     config: &RetryConfig,
     operation: F,
@@ -133,12 +90,29 @@ where
 {
     let mut state = RetryState::default();
     let max_attempts = config.max_retries.saturating_add(1);
+    let name = config.operation.as_deref();
 
     loop {
         state.attempt += 1;
 
+        debug!(
+            operation = name,
+            attempt = state.attempt,
+            max_attempts,
+            "Executing retry attempt"
+        );
+
         match operation().await {
             Ok(result) => {
+                if state.attempt > 1 {
+                    info!(
+                        operation = name,
+                        attempt = state.attempt,
+                        max_attempts,
+                        total_wait_ms = state.total_wait_time.as_millis() as u64,
+                        "Succeeded after retrying"
+                    );
+                }
                 state.history.push(AttemptInfo {
                     attempt: state.attempt,
                     success: true,
@@ -148,10 +122,29 @@ where
                 return (Ok(result), state);
             }
             Err(error) => {
-                let should_retry =
-                    state.attempt < max_attempts && config.retry_on.should_retry(&error);
-
-                if !should_retry {
+                let retryable = config.retry_on.should_retry(&error);
+
+                if !retryable || state.attempt >= max_attempts {
+                    if retryable {
+                        warn!(
+                            operation = name,
+                            attempt = state.attempt,
+                            max_attempts,
+                            error_class = error.class(),
+                            status = error.status(),
+                            error = %error,
+                            "Retries exhausted"
+                        );
+                    } else {
+                        debug!(
+                            operation = name,
+                            attempt = state.attempt,
+                            error_class = error.class(),
+                            status = error.status(),
+                            error = %error,
+                            "Error is not retryable"
+                        );
+                    }
                     return (Err(error), state);
                 }
 
@@ -166,7 +159,18 @@ where
                     wait_time: wait,
                 });
 
-                sleep(wait).await;
+                warn!(
+                    operation = name,
+                    attempt = state.attempt,
+                    max_attempts,
+                    wait_ms = wait.as_millis() as u64,
+                    error_class = error.class(),
+                    status = error.status(),
+                    error = %error,
+                    "Retrying after error"
+                );
+
+                wait_for(wait).await;
             }
         }
     }
@@ -253,6 +257,27 @@ mod tests {
         assert_eq!(attempts.load(Ordering::SeqCst), 3);
     }
 
+    #[tokio::test]
+    async fn test_mock_clock_skips_waits() {
+        use serdes_ai_core::clock::{use_clock, Clock, MockClock};
+
+        let clock = MockClock::epoch();
+        let _guard = use_clock(clock.clone());
+        let config = RetryConfig::new()
+            .max_retries(2)
+            .fixed(Duration::from_secs(60));
+
+        let started = std::time::Instant::now();
+        let result = with_retry(&config, || async {
+            Err::<(), _>(RetryableError::http(500, "server error"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(clock.now().timestamp(), 120);
+    }
+
     #[tokio::test]
     async fn test_with_retry_exhausted() {
         let config = RetryConfig::new()
@@ -11,7 +11,10 @@
 //! - **[`WaitStrategy`]**: Define how long to wait between retries
 //! - **[`RetryCondition`]**: Determine which errors are retryable
 //! - **[`with_retry`]**: Execute operations with automatic retries
+//! - **[`with_retry_stream`]**: Retry opening streams, and optionally
+//!   restart or resume them after they fail
 //! - **[`RetryClient`]**: HTTP client with built-in retry support
+//! - **[`HostCircuits`]**: Per-host circuit breakers shared across clients
 //!
 //! ## Wait Strategies
 //!
@@ -50,23 +53,43 @@
 //!
 //! let response = client.get("https://api.example.com/data").await?;
 //! ```
+//!
+//! [`RetryClientBuilder`] tunes the connection pool and protects providers
+//! with per-host concurrency limits and circuit breakers:
+//!
+//! ```ignore
+//! use serdes_ai_retries::{HostCircuits, RetryClient};
+//! use std::time::Duration;
+//!
+//! let client = RetryClient::builder()
+//!     .max_concurrent_per_host(16)
+//!     .pool_max_idle_per_host(32)
+//!     .tcp_keepalive(Duration::from_secs(30))
+//!     .http2_adaptive_window(true)
+//!     .circuit_breaker(HostCircuits::global())
+//!     .build();
+//! ```
 
 #![warn(missing_docs)]
 #![deny(unsafe_code)]
 
 pub mod backoff;
+pub mod circuit;
 pub mod config;
 pub mod error;
 pub mod executor;
 pub mod strategy;
+pub mod stream;
 pub mod transport;
 
 // Re-exports
 pub use backoff::{ExponentialBackoff, ExponentialBackoffBuilder, FixedDelay, LinearBackoff};
+pub use circuit::{CircuitBreakerConfig, CircuitState, HostCircuits};
 pub use config::{RetryCondition, RetryConfig, WaitStrategy};
 pub use error::{RetryResult, RetryableError};
 pub use executor::{with_retry, with_retry_state, AttemptInfo, Retry, RetryState};
 pub use strategy::{NoRetry, RetryStrategy};
+pub use stream::{with_retry_stream, StreamAttempt, StreamRetryPolicy};
 pub use transport::{RetryClient, RetryClientBuilder};
 
 /// Prelude for common imports.
@@ -0,0 +1,303 @@
+//! Retries for streaming operations.
+//!
+//! [`with_retry`](crate::with_retry) retries a request/response future. A
+//! stream can fail in two places: while it is being established, and after
+//! it has already yielded items. [`with_retry_stream`] always retries the
+//! first, and the [`StreamRetryPolicy`] decides what happens on the second,
+//! depending on how much output the consumer has already seen.
+//!
+//! ```ignore
+//! use serdes_ai_retries::{with_retry_stream, RetryConfig, StreamRetryPolicy};
+//!
+//! let stream = with_retry_stream(
+//!     &RetryConfig::for_api(),
+//!     StreamRetryPolicy::Restart { max_yielded: 0 },
+//!     |attempt| open_stream(attempt.yielded),
+//! );
+//! ```
+
+use crate::config::RetryConfig;
+use crate::error::{RetryResult, RetryableError};
+use crate::executor::wait_for;
+use futures::stream::{self, Stream, StreamExt};
+use std::future::Future;
+use std::pin::Pin;
+use tracing::warn;
+
+/// What to do when a stream fails after it was established.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StreamRetryPolicy {
+    /// Only retry establishing the stream; later errors are passed on.
+    #[default]
+    EstablishOnly,
+    /// Open the stream again from the beginning, if at most `max_yielded`
+    /// items were yielded so far.
+    ///
+    /// The new stream is forwarded in full, so the consumer sees the first
+    /// items again. With `max_yielded: 0` a retry is invisible.
+    Restart {
+        /// Largest number of yielded items after which a restart is allowed.
+        max_yielded: usize,
+    },
+    /// Open the stream again after any amount of output.
+    ///
+    /// The operation gets the number of items already yielded in
+    /// [`StreamAttempt::yielded`] and must continue after them.
+    Resume,
+}
+
+impl StreamRetryPolicy {
+    fn allows(self, yielded: usize) -> bool {
+        match self {
+            Self::EstablishOnly => false,
+            Self::Restart { max_yielded } => yielded <= max_yielded,
+            Self::Resume => true,
+        }
+    }
+}
+
+/// An attempt to open a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamAttempt {
+    /// Attempt number (1-indexed).
+    pub attempt: u32,
+    /// Number of items yielded to the consumer before this attempt.
+    pub yielded: usize,
+}
+
+struct RetryStreamState<F, S> {
+    config: RetryConfig,
+    policy: StreamRetryPolicy,
+    operation: F,
+    attempt: u32,
+    yielded: usize,
+    current: Option<Pin<Box<S>>>,
+    done: bool,
+}
+
+impl<F, S> RetryStreamState<F, S> {
+    /// Wait before the next attempt if `error` may be retried.
+    async fn retry_after(&self, error: &RetryableError, phase: &'static str) -> bool {
+        let max_attempts = self.config.max_retries.saturating_add(1);
+        if self.attempt >= max_attempts || !self.config.retry_on.should_retry(error) {
+            return false;
+        }
+
+        let wait = self
+            .config
+            .wait
+            .calculate(self.attempt, error.retry_after());
+        warn!(
+            operation = self.config.operation.as_deref(),
+            phase,
+            attempt = self.attempt,
+            max_attempts,
+            yielded = self.yielded,
+            wait_ms = wait.as_millis() as u64,
+            error_class = error.class(),
+            status = error.status(),
+            error = %error,
+            "Retrying stream after error"
+        );
+        wait_for(wait).await;
+        true
+    }
+}
+
+/// Run a streaming operation with retries.
+///
+/// `operation` opens the stream. Failing to open it is retried like
+/// [`with_retry`](crate::with_retry); an error yielded by an open stream is
+/// retried only if `policy` allows it for the number of items yielded so
+/// far. Both kinds of retry share the `max_retries` budget of `config`.
+///
+/// The returned stream is lazy: nothing is opened until it is polled. It
+/// ends after the first error that is not retried.
+pub fn with_retry_stream<F, Fut, S, T>(
+    config: &RetryConfig,
+    policy: StreamRetryPolicy,
+    operation: F,
+) -> impl Stream<Item = RetryResult<T>>
+where
+    F: FnMut(StreamAttempt) -> Fut,
+    Fut: Future<Output = RetryResult<S>>,
+    S: Stream<Item = RetryResult<T>>,
+{
+    let state = RetryStreamState {
+        config: config.clone(),
+        policy,
+        operation,
+        attempt: 0,
+        yielded: 0,
+        current: None,
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if state.done {
+                return None;
+            }
+
+            let current = match &mut state.current {
+                Some(current) => current,
+                None => {
+                    state.attempt += 1;
+                    let attempt = StreamAttempt {
+                        attempt: state.attempt,
+                        yielded: state.yielded,
+                    };
+                    match (state.operation)(attempt).await {
+                        Ok(opened) => state.current.insert(Box::pin(opened)),
+                        Err(error) => {
+                            if state.retry_after(&error, "establish").await {
+                                continue;
+                            }
+                            state.done = true;
+                            return Some((Err(error), state));
+                        }
+                    }
+                }
+            };
+
+            match current.next().await {
+                Some(Ok(item)) => {
+                    state.yielded += 1;
+                    return Some((Ok(item), state));
+                }
+                Some(Err(error)) => {
+                    state.current = None;
+                    if state.policy.allows(state.yielded)
+                        && state.retry_after(&error, "stream").await
+                    {
+                        continue;
+                    }
+                    state.done = true;
+                    return Some((Err(error), state));
+                }
+                None => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn config() -> RetryConfig {
+        RetryConfig::new()
+            .max_retries(3)
+            .fixed(Duration::from_millis(1))
+    }
+
+    /// A stream of `items` that fails with a server error after `fail_after`
+    /// items, if set.
+    fn numbers(
+        items: std::ops::Range<u32>,
+        fail_after: Option<usize>,
+    ) -> impl Stream<Item = RetryResult<u32>> {
+        let mut results: Vec<RetryResult<u32>> = items.map(Ok).collect();
+        if let Some(n) = fail_after {
+            results.truncate(n);
+            results.push(Err(RetryableError::http(503, "unavailable")));
+        }
+        stream::iter(results)
+    }
+
+    #[tokio::test]
+    async fn test_retries_establishment() {
+        let attempts = Arc::new(Mutex::new(Vec::new()));
+        let seen = attempts.clone();
+        let items: Vec<_> = with_retry_stream(&config(), StreamRetryPolicy::default(), |a| {
+            seen.lock().unwrap().push(a);
+            async move {
+                if a.attempt < 3 {
+                    Err(RetryableError::connection("refused"))
+                } else {
+                    Ok(numbers(0..3, None))
+                }
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(
+            items.into_iter().collect::<RetryResult<Vec<_>>>().unwrap(),
+            [0, 1, 2]
+        );
+        assert_eq!(attempts.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_establish_only_passes_stream_errors() {
+        let items: Vec<_> =
+            with_retry_stream(&config(), StreamRetryPolicy::EstablishOnly, |_| async {
+                Ok(numbers(0..3, Some(1)))
+            })
+            .collect()
+            .await;
+
+        assert_eq!(items.len(), 2);
+        assert!(items[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restart_limited_by_yielded() {
+        // Fails after 2 items every time, more than a restart allows
+        let items: Vec<_> = with_retry_stream(
+            &config(),
+            StreamRetryPolicy::Restart { max_yielded: 1 },
+            |_| async { Ok(numbers(0..3, Some(2))) },
+        )
+        .collect()
+        .await;
+        assert_eq!(items.len(), 3);
+        assert!(items[2].is_err());
+
+        // Fails before any output on the first attempt only
+        let items: Vec<_> = with_retry_stream(
+            &config(),
+            StreamRetryPolicy::Restart { max_yielded: 0 },
+            |a| async move { Ok(numbers(0..3, (a.attempt == 1).then_some(0))) },
+        )
+        .collect()
+        .await;
+        assert_eq!(
+            items.into_iter().collect::<RetryResult<Vec<_>>>().unwrap(),
+            [0, 1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_continues_after_yielded() {
+        let items: Vec<_> = with_retry_stream(&config(), StreamRetryPolicy::Resume, |a| {
+            let start = a.yielded as u32;
+            async move { Ok(numbers(start..5, (a.attempt < 3).then_some(2))) }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(
+            items.into_iter().collect::<RetryResult<Vec<_>>>().unwrap(),
+            [0, 1, 2, 3, 4]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_is_shared() {
+        let items: Vec<_> = with_retry_stream(
+            &config().max_retries(1),
+            StreamRetryPolicy::Resume,
+            |_| async { Ok(numbers(0..3, Some(1))) },
+        )
+        .collect()
+        .await;
+
+        // One item and a retry, then one more item and the final error
+        assert_eq!(items.len(), 3);
+        assert!(items[2].is_err());
+    }
+}
@@ -1,11 +1,15 @@
 //! HTTP transport with automatic retries.
 
+use crate::circuit::HostCircuits;
 use crate::config::RetryConfig;
 use crate::error::{RetryResult, RetryableError};
 use crate::executor::with_retry;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Response, Url};
 use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
+use tokio::sync::Semaphore;
 use tracing::debug;
 
 impl From<reqwest::Error> for RetryableError {
@@ -20,25 +24,64 @@ impl From<reqwest::Error> for RetryableError {
     }
 }
 
+/// Per-host limits on concurrent requests.
+#[derive(Debug)]
+struct HostLimits {
+    max_concurrent: usize,
+    semaphores: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimits {
+    fn new(max_concurrent: usize) -> Self {
+        Self {
+            max_concurrent: max_concurrent.max(1),
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn semaphore(&self, host: &str) -> Arc<Semaphore> {
+        let mut semaphores = self
+            .semaphores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        semaphores
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_concurrent)))
+            .clone()
+    }
+}
+
 /// HTTP client wrapper with automatic retries.
+///
+/// Clones share the underlying connection pool, per-host concurrency limits
+/// and circuit breakers.
 #[derive(Debug, Clone)]
 pub struct RetryClient {
     client: Client,
     config: RetryConfig,
+    host_limits: Option<Arc<HostLimits>>,
+    circuits: Option<Arc<HostCircuits>>,
 }
 
 impl RetryClient {
     /// Create a new retry client with default reqwest client.
     pub fn new(config: RetryConfig) -> Self {
+        Self::with_client(Client::new(), config)
+    }
+
+    /// Create with a custom reqwest client.
+    pub fn with_client(client: Client, config: RetryConfig) -> Self {
         Self {
-            client: Client::new(),
+            client,
             config,
+            host_limits: None,
+            circuits: None,
         }
     }
 
-    /// Create with a custom reqwest client.
-    pub fn with_client(client: Client, config: RetryConfig) -> Self {
-        Self { client, config }
+    /// Create a builder.
+    pub fn builder() -> RetryClientBuilder {
+        RetryClientBuilder::new()
     }
 
     /// Create with default API retry settings.
@@ -56,6 +99,16 @@ impl RetryClient {
         &self.config
     }
 
+    /// Get the circuit breakers, if enabled.
+    pub fn circuits(&self) -> Option<&Arc<HostCircuits>> {
+        self.circuits.as_ref()
+    }
+
+    /// Get the per-host concurrency limit, if any.
+    pub fn max_concurrent_per_host(&self) -> Option<usize> {
+        self.host_limits.as_ref().map(|l| l.max_concurrent)
+    }
+
     /// Execute a GET request with retries.
     pub async fn get(&self, url: &str) -> RetryResult<Response> {
         self.request(Method::GET, url, Option::<()>::None).await
@@ -101,15 +154,40 @@ impl RetryClient {
         body: Option<B>,
     ) -> RetryResult<Response> {
         let url = url.to_string();
+        let host = host_key(&url);
         let client = self.client.clone();
+        let semaphore = self
+            .host_limits
+            .as_ref()
+            .zip(host.as_deref())
+            .map(|(limits, host)| limits.semaphore(host));
 
         with_retry(&self.config, || {
             let url = url.clone();
             let method = method.clone();
             let client = client.clone();
             let body = body.clone();
+            let host = host.clone();
+            let semaphore = semaphore.clone();
+            let circuits = self.circuits.clone();
 
             async move {
+                let circuit = circuits.zip(host);
+                if let Some((circuits, host)) = &circuit {
+                    circuits.check(host)?;
+                }
+                // Held for this attempt only, so waiting out a retry delay
+                // does not block other requests to the host
+                let _permit = match &semaphore {
+                    Some(semaphore) => Some(
+                        semaphore
+                            .acquire()
+                            .await
+                            .map_err(|e| RetryableError::Other(e.into()))?,
+                    ),
+                    None => None,
+                };
+
                 debug!(method = %method, url = %url, "Making HTTP request");
 
                 let mut request = client.request(method, &url);
@@ -117,15 +195,34 @@ impl RetryClient {
                     request = request.json(&b);
                 }
 
-                let response = request.send().await.map_err(RetryableError::from)?;
+                let result = match request.send().await {
+                    Ok(response) => check_response(response).await,
+                    Err(e) => Err(RetryableError::from(e)),
+                };
 
-                check_response(response).await
+                if let Some((circuits, host)) = &circuit {
+                    match &result {
+                        Err(e) if e.is_retryable() => circuits.record_failure(host),
+                        _ => circuits.record_success(host),
+                    }
+                }
+                result
             }
         })
         .await
     }
 }
 
+/// Key identifying the host of a URL, including a non-default port.
+fn host_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    })
+}
+
 /// Check an HTTP response and convert to RetryableError if needed.
 async fn check_response(response: Response) -> RetryResult<Response> {
     let status = response.status().as_u16();
@@ -180,6 +277,12 @@ pub struct RetryClientBuilder {
     initial_delay: Option<Duration>,
     max_delay: Option<Duration>,
     timeout: Option<Duration>,
+    max_concurrent_per_host: Option<usize>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    http2_adaptive_window: bool,
+    circuits: Option<Arc<HostCircuits>>,
 }
 
 impl RetryClientBuilder {
@@ -218,6 +321,56 @@ impl RetryClientBuilder {
         self
     }
 
+    /// Limit the concurrent requests to each host.
+    ///
+    /// Further requests wait for a free slot.
+    pub fn max_concurrent_per_host(mut self, n: usize) -> Self {
+        self.max_concurrent_per_host = Some(n);
+        self
+    }
+
+    /// Set the maximum idle connections kept per host.
+    ///
+    /// Ignored when a custom client is set.
+    pub fn pool_max_idle_per_host(mut self, n: usize) -> Self {
+        self.pool_max_idle_per_host = Some(n);
+        self
+    }
+
+    /// Set how long idle connections are kept in the pool.
+    ///
+    /// Ignored when a custom client is set.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the TCP keep-alive interval.
+    ///
+    /// Ignored when a custom client is set.
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Enable the HTTP/2 adaptive flow-control window, which helps
+    /// long-lived streaming responses on high-latency links.
+    ///
+    /// Ignored when a custom client is set.
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> Self {
+        self.http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Fail fast against hosts whose circuit breaker is open.
+    ///
+    /// Pass the same registry, e.g. [`HostCircuits::global`], to every client
+    /// that should share circuit state.
+    pub fn circuit_breaker(mut self, circuits: Arc<HostCircuits>) -> Self {
+        self.circuits = Some(circuits);
+        self
+    }
+
     /// Build the retry client.
     pub fn build(self) -> RetryClient {
         let client = self.client.unwrap_or_else(|| {
@@ -225,6 +378,18 @@ impl RetryClientBuilder {
             if let Some(timeout) = self.timeout {
                 builder = builder.timeout(timeout);
             }
+            if let Some(n) = self.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(n);
+            }
+            if let Some(timeout) = self.pool_idle_timeout {
+                builder = builder.pool_idle_timeout(timeout);
+            }
+            if let Some(interval) = self.tcp_keepalive {
+                builder = builder.tcp_keepalive(interval);
+            }
+            if self.http2_adaptive_window {
+                builder = builder.http2_adaptive_window(true);
+            }
             builder.build().expect("Failed to build client")
         });
 
@@ -237,7 +402,13 @@ impl RetryClientBuilder {
             config = config.exponential(initial, max);
         }
 
-        RetryClient::with_client(client, config)
+        RetryClient {
+            host_limits: self
+                .max_concurrent_per_host
+                .map(|n| Arc::new(HostLimits::new(n))),
+            circuits: self.circuits,
+            ..RetryClient::with_client(client, config)
+        }
     }
 }
 
@@ -268,6 +439,55 @@ mod tests {
         assert_eq!(client.config().max_retries, 5);
     }
 
+    #[test]
+    fn test_builder_host_options() {
+        let circuits = HostCircuits::global();
+        let client = RetryClient::builder()
+            .max_concurrent_per_host(4)
+            .pool_max_idle_per_host(8)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(30))
+            .http2_adaptive_window(true)
+            .circuit_breaker(circuits.clone())
+            .build();
+
+        assert_eq!(client.max_concurrent_per_host(), Some(4));
+        assert!(Arc::ptr_eq(client.circuits().unwrap(), &circuits));
+    }
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(
+            host_key("https://api.openai.com/v1/chat").as_deref(),
+            Some("api.openai.com")
+        );
+        assert_eq!(
+            host_key("http://localhost:11434/api").as_deref(),
+            Some("localhost:11434")
+        );
+        assert_eq!(host_key("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_fast() {
+        use crate::circuit::CircuitBreakerConfig;
+
+        let circuits = Arc::new(HostCircuits::new(
+            CircuitBreakerConfig::new().failure_threshold(1),
+        ));
+        circuits.record_failure("unreachable.invalid");
+        let client = RetryClient::builder()
+            .max_retries(0)
+            .circuit_breaker(circuits)
+            .build();
+
+        let err = client
+            .get("https://unreachable.invalid/")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RetryableError::CircuitOpen { .. }));
+    }
+
     #[test]
     fn test_parse_retry_after() {
         // This would require mocking the response, so we just test the logic
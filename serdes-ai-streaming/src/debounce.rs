@@ -1,9 +1,10 @@
 //! Temporal grouping for efficient streaming.
 //!
 //! This module provides utilities for debouncing and grouping stream events
-//! to reduce overhead in high-frequency streaming scenarios.
+//! to reduce overhead in high-frequency streaming scenarios, and for pacing
+//! bursty text output into a smooth typewriter effect.
 
-use futures::{Stream, StreamExt};
+use futures::{Future, Stream, StreamExt};
 use pin_project_lite::pin_project;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -271,6 +272,175 @@ where
     }
 }
 
+/// Configuration for [`PacedTextStream`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacingConfig {
+    /// Target output rate in characters per second.
+    pub chars_per_second: f64,
+    /// Maximum number of characters emitted in a single item.
+    pub max_burst: usize,
+    /// Interval between emissions.
+    pub tick: Duration,
+    /// Backlog size (in characters) above which pacing speeds up to avoid
+    /// falling behind the provider. `None` never speeds up.
+    pub catch_up_after: Option<usize>,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            chars_per_second: 120.0,
+            max_burst: 32,
+            tick: Duration::from_millis(16),
+            catch_up_after: Some(2000),
+        }
+    }
+}
+
+impl PacingConfig {
+    /// Create a configuration with the given target rate.
+    pub fn new(chars_per_second: f64) -> Self {
+        Self {
+            chars_per_second,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum characters per emitted item.
+    pub fn with_max_burst(mut self, max_burst: usize) -> Self {
+        self.max_burst = max_burst.max(1);
+        self
+    }
+
+    /// Set the emission interval.
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        self.tick = tick;
+        self
+    }
+
+    /// Set the backlog size that triggers catch-up.
+    pub fn with_catch_up_after(mut self, chars: Option<usize>) -> Self {
+        self.catch_up_after = chars;
+        self
+    }
+
+    /// Characters earned per tick at the target rate.
+    fn chars_per_tick(&self) -> f64 {
+        self.chars_per_second * self.tick.as_secs_f64()
+    }
+}
+
+pin_project! {
+    /// Stream that re-times text deltas to a target characters-per-second.
+    ///
+    /// Providers often emit text in large, irregular bursts. This stream
+    /// buffers incoming text and releases it in small chunks at a steady
+    /// rate, so UIs render a smooth typewriter effect. Each emitted item is
+    /// at most `max_burst` characters. When the backlog grows beyond
+    /// `catch_up_after`, the excess is released as fast as `max_burst`
+    /// allows so output never lags far behind the provider.
+    pub struct PacedTextStream<S> {
+        #[pin]
+        inner: S,
+        #[pin]
+        sleep: tokio::time::Sleep,
+        config: PacingConfig,
+        buffer: String,
+        carry: f64,
+        finished: bool,
+    }
+}
+
+impl<S> PacedTextStream<S>
+where
+    S: Stream<Item = String>,
+{
+    /// Create a new paced text stream.
+    pub fn new(inner: S, config: PacingConfig) -> Self {
+        Self {
+            inner,
+            sleep: tokio::time::sleep(Duration::ZERO),
+            config,
+            buffer: String::new(),
+            carry: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Get the pacing configuration.
+    pub fn config(&self) -> &PacingConfig {
+        &self.config
+    }
+
+    /// Get the number of buffered characters not yet emitted.
+    pub fn backlog(&self) -> usize {
+        self.buffer.chars().count()
+    }
+}
+
+impl<S> Stream for PacedTextStream<S>
+where
+    S: Stream<Item = String> + Unpin,
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        // Drain everything the inner stream has ready
+        while !*this.finished {
+            match this.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(text)) => this.buffer.push_str(&text),
+                Poll::Ready(None) => *this.finished = true,
+                Poll::Pending => break,
+            }
+        }
+
+        loop {
+            if this.buffer.is_empty() {
+                return if *this.finished {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            }
+
+            // Wait for the next tick
+            if this.sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            let max_burst = this.config.max_burst.max(1);
+            *this.carry = (*this.carry + this.config.chars_per_tick()).min(max_burst as f64);
+
+            let backlog = this.buffer.chars().count();
+            let mut n = this.carry.floor() as usize;
+            if let Some(limit) = this.config.catch_up_after {
+                n = n.max(backlog.saturating_sub(limit));
+            }
+            let n = n.min(max_burst).min(backlog);
+
+            let deadline = tokio::time::Instant::now() + this.config.tick;
+            this.sleep.as_mut().reset(deadline);
+
+            if n == 0 {
+                continue;
+            }
+
+            *this.carry = (*this.carry - n as f64).max(0.0);
+            let split = this
+                .buffer
+                .char_indices()
+                .nth(n)
+                .map(|(i, _)| i)
+                .unwrap_or(this.buffer.len());
+            let rest = this.buffer.split_off(split);
+            let chunk = std::mem::replace(this.buffer, rest);
+            return Poll::Ready(Some(chunk));
+        }
+    }
+}
+
 /// Extension trait for adding debouncing capabilities to streams.
 pub trait StreamDebounceExt: Stream {
     /// Debounce the stream, grouping items by time.
@@ -301,6 +471,14 @@ pub trait TextStreamExt: Stream<Item = String> {
     {
         CoalescedTextStream::new(self, min_size, max_size)
     }
+
+    /// Pace text to a steady characters-per-second rate.
+    fn paced(self, config: PacingConfig) -> PacedTextStream<Self>
+    where
+        Self: Sized,
+    {
+        PacedTextStream::new(self, config)
+    }
 }
 
 impl<S: Stream<Item = String>> TextStreamExt for S {}
@@ -369,4 +547,52 @@ mod tests {
         let results: Vec<String> = inner.coalesce(5, 100).collect().await;
         assert!(!results.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_paced_stream_preserves_text_and_caps_bursts() {
+        let items = vec!["Hello, ".to_string(), "wörld! This is a burst.".to_string()];
+        let config = PacingConfig::new(100_000.0)
+            .with_tick(Duration::from_millis(1))
+            .with_max_burst(4);
+
+        let results: Vec<String> = stream::iter(items).paced(config).collect().await;
+
+        assert_eq!(results.concat(), "Hello, wörld! This is a burst.");
+        assert!(results.len() > 2);
+        assert!(results.iter().all(|c| c.chars().count() <= 4));
+    }
+
+    #[tokio::test]
+    async fn test_paced_stream_rate() {
+        let text = "x".repeat(20);
+        let config = PacingConfig::new(1000.0)
+            .with_tick(Duration::from_millis(2))
+            .with_max_burst(8);
+
+        let start = Instant::now();
+        let results: Vec<String> = stream::iter(vec![text]).paced(config).collect().await;
+
+        // 20 chars at 2 chars per tick => at least 9 ticks after the first
+        assert_eq!(results.len(), 10);
+        assert!(start.elapsed() >= Duration::from_millis(18));
+    }
+
+    #[tokio::test]
+    async fn test_paced_stream_catch_up() {
+        let text = "y".repeat(50);
+        let config = PacingConfig::new(1.0)
+            .with_tick(Duration::from_millis(1))
+            .with_max_burst(64)
+            .with_catch_up_after(Some(0));
+
+        let results: Vec<String> = stream::iter(vec![text]).paced(config).collect().await;
+        assert_eq!(results, vec!["y".repeat(50)]);
+    }
+
+    #[tokio::test]
+    async fn test_paced_stream_empty() {
+        let inner = stream::iter(Vec::<String>::new());
+        let results: Vec<String> = inner.paced(PacingConfig::default()).collect().await;
+        assert!(results.is_empty());
+    }
 }
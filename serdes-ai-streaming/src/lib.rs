@@ -56,6 +56,9 @@
 //!
 //! // Or coalesce into larger chunks
 //! let chunked = text_stream.coalesce(100, 1000);
+//!
+//! // Or pace bursty output into a smooth typewriter effect
+//! let paced = text_stream.paced(PacingConfig::new(120.0));
 //! ```
 
 #![warn(missing_docs)]
@@ -65,6 +68,7 @@ pub mod agent_stream;
 pub mod debounce;
 pub mod error;
 pub mod events;
+pub mod partial_json;
 pub mod partial_response;
 pub mod parts_manager;
 pub mod sse;
@@ -79,10 +83,12 @@ pub use agent_stream::{
     TextDelta, TextDeltaStream,
 };
 pub use debounce::{
-    CoalescedTextStream, DebouncedStream, StreamDebounceExt, TextStreamExt, ThrottledStream,
+    CoalescedTextStream, DebouncedStream, PacedTextStream, PacingConfig, StreamDebounceExt,
+    TextStreamExt, ThrottledStream,
 };
 pub use error::{StreamError, StreamResult};
 pub use events::AgentStreamEvent;
+pub use partial_json::{PartialJsonError, PartialJsonValidator};
 pub use partial_response::{PartialResponse, ResponseDelta};
 pub use parts_manager::{ManagedPart, ModelResponsePartsManager, ToolCallAccumulator, VendorId};
 pub use sse::{SseEvent, SseEventExt, SseParser, SseStream};
@@ -0,0 +1,620 @@
+//! Incremental validation of streamed JSON.
+//!
+//! Tool call arguments arrive as JSON fragments. [`PartialJsonValidator`]
+//! checks after every fragment that the text so far is still a plausible
+//! prefix of a valid document, so malformed arguments can be rejected before
+//! the call completes and clients only render arguments that can still parse.
+//!
+//! With a JSON schema, top-level properties are also checked as they arrive:
+//! unknown keys when `additionalProperties` is `false`, the type of each
+//! value from its first character, and `required` keys once the object closes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serdes_ai_streaming::partial_json::PartialJsonValidator;
+//! use serde_json::json;
+//!
+//! let mut validator = PartialJsonValidator::new().with_schema(json!({
+//!     "type": "object",
+//!     "properties": {"city": {"type": "string"}},
+//! }));
+//!
+//! assert!(validator.push(r#"{"city": "Par"#).is_ok());
+//! assert!(validator.push("is\"}").is_ok());
+//! assert!(validator.is_complete());
+//!
+//! let mut validator = PartialJsonValidator::new();
+//! assert!(validator.push(r#"{"city" "Paris"}"#).is_err());
+//! ```
+
+use serde_json::Value;
+use thiserror::Error;
+
+/// Error raised when streamed JSON can no longer become valid.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PartialJsonError {
+    /// The text is not a prefix of any valid JSON document.
+    #[error("Invalid JSON at position {position}: {message}")]
+    Syntax {
+        /// Character offset of the offending input.
+        position: usize,
+        /// What was wrong.
+        message: String,
+    },
+
+    /// The document doesn't match the schema.
+    #[error("Schema mismatch: {0}")]
+    Schema(String),
+}
+
+/// An open container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    Object,
+    Array,
+}
+
+/// What the parser expects next, outside of a token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Expect {
+    #[default]
+    Value,
+    FirstValueOrEnd,
+    FirstKeyOrEnd,
+    Key,
+    Colon,
+    CommaOrEnd,
+    Done,
+}
+
+/// A token being read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum Token {
+    #[default]
+    None,
+    String {
+        key: bool,
+        escape: bool,
+        hex: u8,
+    },
+    Number,
+    Literal {
+        text: &'static str,
+        matched: usize,
+    },
+}
+
+/// Incremental JSON prefix validator with optional schema checks.
+#[derive(Debug, Clone, Default)]
+pub struct PartialJsonValidator {
+    schema: Option<Value>,
+    stack: Vec<Frame>,
+    expect: Expect,
+    token: Token,
+    position: usize,
+    /// Buffer for the top-level key or number being read.
+    buffer: String,
+    /// Most recent top-level key.
+    current_key: Option<String>,
+    /// Top-level keys seen so far.
+    seen_keys: Vec<String>,
+    error: Option<PartialJsonError>,
+}
+
+impl PartialJsonValidator {
+    /// Create a validator that only checks syntax.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also check top-level properties against a JSON schema.
+    #[must_use]
+    pub fn with_schema(mut self, schema: Value) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Get the schema, if any.
+    #[must_use]
+    pub fn schema(&self) -> Option<&Value> {
+        self.schema.as_ref()
+    }
+
+    /// Feed the next fragment.
+    ///
+    /// Once an error is returned, every later call returns the same error.
+    pub fn push(&mut self, fragment: &str) -> Result<(), PartialJsonError> {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+        for c in fragment.chars() {
+            if let Err(e) = self.feed(c) {
+                self.error = Some(e.clone());
+                return Err(e);
+            }
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    /// Signal the end of input.
+    ///
+    /// Fails if the document is incomplete.
+    pub fn finish(&mut self) -> Result<(), PartialJsonError> {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+        let result = if self.token == Token::Number {
+            self.end_number()
+        } else {
+            Ok(())
+        }
+        .and_then(|()| {
+            if self.expect == Expect::Done {
+                Ok(())
+            } else {
+                Err(self.syntax("unexpected end of input"))
+            }
+        });
+        if let Err(e) = &result {
+            self.error = Some(e.clone());
+        }
+        result
+    }
+
+    /// Check whether a complete document has been read.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none() && self.expect == Expect::Done
+    }
+
+    /// Check whether the input so far can still become a valid document.
+    #[must_use]
+    pub fn is_plausible(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Get the error, if validation failed.
+    #[must_use]
+    pub fn error(&self) -> Option<&PartialJsonError> {
+        self.error.as_ref()
+    }
+
+    fn syntax(&self, message: impl Into<String>) -> PartialJsonError {
+        PartialJsonError::Syntax {
+            position: self.position,
+            message: message.into(),
+        }
+    }
+
+    /// Whether the parser is directly inside the top-level object.
+    fn at_top_level_object(&self) -> bool {
+        self.stack == [Frame::Object]
+    }
+
+    fn feed(&mut self, c: char) -> Result<(), PartialJsonError> {
+        match self.token {
+            Token::String { key, escape, hex } => return self.feed_string(c, key, escape, hex),
+            Token::Number => {
+                if c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-') {
+                    self.buffer.push(c);
+                    return Ok(());
+                }
+                self.end_number()?;
+            }
+            Token::Literal { text, matched } => {
+                if text[matched..].starts_with(c) {
+                    if matched + 1 == text.len() {
+                        self.token = Token::None;
+                        self.value_done()?;
+                    } else {
+                        self.token = Token::Literal {
+                            text,
+                            matched: matched + 1,
+                        };
+                    }
+                    return Ok(());
+                }
+                return Err(self.syntax(format!("expected `{}`", text)));
+            }
+            Token::None => {}
+        }
+
+        if c.is_whitespace() {
+            return Ok(());
+        }
+
+        match self.expect {
+            Expect::FirstValueOrEnd if c == ']' => {
+                self.stack.pop();
+                self.value_done()
+            }
+            Expect::Value | Expect::FirstValueOrEnd => self.start_value(c),
+            Expect::FirstKeyOrEnd if c == '}' => {
+                self.stack.pop();
+                self.value_done()
+            }
+            Expect::FirstKeyOrEnd | Expect::Key if c == '"' => {
+                self.token = Token::String {
+                    key: true,
+                    escape: false,
+                    hex: 0,
+                };
+                self.buffer.clear();
+                Ok(())
+            }
+            Expect::FirstKeyOrEnd | Expect::Key => Err(self.syntax("expected a key")),
+            Expect::Colon if c == ':' => {
+                self.expect = Expect::Value;
+                Ok(())
+            }
+            Expect::Colon => Err(self.syntax("expected `:`")),
+            Expect::CommaOrEnd => match (c, self.stack.last()) {
+                (',', Some(Frame::Object)) => {
+                    self.expect = Expect::Key;
+                    Ok(())
+                }
+                (',', Some(Frame::Array)) => {
+                    self.expect = Expect::Value;
+                    Ok(())
+                }
+                ('}', Some(Frame::Object)) | (']', Some(Frame::Array)) => {
+                    self.stack.pop();
+                    self.value_done()
+                }
+                _ => Err(self.syntax("expected `,` or a closing bracket")),
+            },
+            Expect::Done => Err(self.syntax("trailing characters")),
+        }
+    }
+
+    fn feed_string(
+        &mut self,
+        c: char,
+        key: bool,
+        escape: bool,
+        hex: u8,
+    ) -> Result<(), PartialJsonError> {
+        let record = key && self.at_top_level_object();
+        let (escape, hex) = if hex > 0 {
+            if !c.is_ascii_hexdigit() {
+                return Err(self.syntax("invalid unicode escape"));
+            }
+            (false, hex - 1)
+        } else if escape {
+            let unescaped = match c {
+                '"' | '\\' | '/' => c,
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => '\0',
+                _ => return Err(self.syntax("invalid escape")),
+            };
+            if record && c != 'u' {
+                self.buffer.push(unescaped);
+            }
+            (false, if c == 'u' { 4 } else { 0 })
+        } else {
+            match c {
+                '\\' => (true, 0),
+                '"' => {
+                    self.token = Token::None;
+                    return if key {
+                        self.expect = Expect::Colon;
+                        if record {
+                            self.key_done()?;
+                        }
+                        Ok(())
+                    } else {
+                        self.value_done()
+                    };
+                }
+                c if c.is_control() => {
+                    return Err(self.syntax("control character in string"));
+                }
+                c => {
+                    if record {
+                        self.buffer.push(c);
+                    }
+                    (false, 0)
+                }
+            }
+        };
+        self.token = Token::String { key, escape, hex };
+        Ok(())
+    }
+
+    fn start_value(&mut self, c: char) -> Result<(), PartialJsonError> {
+        let kind = match c {
+            '{' => "object",
+            '[' => "array",
+            '"' => "string",
+            't' | 'f' => "boolean",
+            'n' => "null",
+            '-' | '0'..='9' => "number",
+            _ => return Err(self.syntax("expected a value")),
+        };
+        self.check_type(kind)?;
+
+        match c {
+            '{' => {
+                self.stack.push(Frame::Object);
+                self.expect = Expect::FirstKeyOrEnd;
+            }
+            '[' => {
+                self.stack.push(Frame::Array);
+                self.expect = Expect::FirstValueOrEnd;
+            }
+            '"' => {
+                self.token = Token::String {
+                    key: false,
+                    escape: false,
+                    hex: 0,
+                }
+            }
+            't' | 'f' | 'n' => {
+                let text = match c {
+                    't' => "true",
+                    'f' => "false",
+                    _ => "null",
+                };
+                self.token = Token::Literal { text, matched: 1 };
+            }
+            _ => {
+                self.token = Token::Number;
+                self.buffer.clear();
+                self.buffer.push(c);
+            }
+        }
+        Ok(())
+    }
+
+    fn end_number(&mut self) -> Result<(), PartialJsonError> {
+        self.token = Token::None;
+        if serde_json::from_str::<serde_json::Number>(&self.buffer).is_err() {
+            return Err(self.syntax(format!("invalid number `{}`", self.buffer)));
+        }
+        let is_float = self.buffer.contains(['.', 'e', 'E']);
+        if is_float
+            && self
+                .property_schema()
+                .is_some_and(|s| integer_only(&s["type"]))
+        {
+            let key = self.current_key.clone().unwrap_or_default();
+            return Err(PartialJsonError::Schema(format!(
+                "property `{}` must be an integer",
+                key
+            )));
+        }
+        self.value_done()
+    }
+
+    fn value_done(&mut self) -> Result<(), PartialJsonError> {
+        if self.stack.is_empty() {
+            self.expect = Expect::Done;
+            self.check_required()
+        } else {
+            self.expect = Expect::CommaOrEnd;
+            Ok(())
+        }
+    }
+
+    /// Schema of the top-level property whose value is being read.
+    fn property_schema(&self) -> Option<&Value> {
+        if !self.at_top_level_object() {
+            return None;
+        }
+        let key = self.current_key.as_ref()?;
+        self.schema.as_ref()?.get("properties")?.get(key)
+    }
+
+    fn key_done(&mut self) -> Result<(), PartialJsonError> {
+        let key = std::mem::take(&mut self.buffer);
+        if let Some(schema) = &self.schema {
+            let known = schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .is_some_and(|props| props.contains_key(&key));
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            if closed && !known {
+                return Err(PartialJsonError::Schema(format!(
+                    "unknown property `{}`",
+                    key
+                )));
+            }
+        }
+        self.seen_keys.push(key.clone());
+        self.current_key = Some(key);
+        Ok(())
+    }
+
+    fn check_type(&self, kind: &str) -> Result<(), PartialJsonError> {
+        let (schema, what) = if self.stack.is_empty() {
+            (self.schema.as_ref(), "arguments".to_string())
+        } else {
+            let what = format!(
+                "property `{}`",
+                self.current_key.as_deref().unwrap_or_default()
+            );
+            (self.property_schema(), what)
+        };
+        let Some(types) = schema.and_then(|s| s.get("type")) else {
+            return Ok(());
+        };
+        if type_allows(types, kind) {
+            Ok(())
+        } else {
+            Err(PartialJsonError::Schema(format!(
+                "{} must be {}, got {}",
+                what, types, kind
+            )))
+        }
+    }
+
+    fn check_required(&self) -> Result<(), PartialJsonError> {
+        let Some(required) = self
+            .schema
+            .as_ref()
+            .and_then(|s| s.get("required"))
+            .and_then(Value::as_array)
+        else {
+            return Ok(());
+        };
+        match required
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|key| !self.seen_keys.iter().any(|seen| seen == key))
+        {
+            Some(missing) => Err(PartialJsonError::Schema(format!(
+                "missing required property `{}`",
+                missing
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Check whether a schema `type` allows a value kind.
+fn type_allows(types: &Value, kind: &str) -> bool {
+    let allows = |t: &str| t == kind || (t == "integer" && kind == "number");
+    match types {
+        Value::String(t) => allows(t),
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).any(allows),
+        _ => true,
+    }
+}
+
+/// Check whether a schema `type` only allows integers among numbers.
+fn integer_only(types: &Value) -> bool {
+    type_allows(types, "number")
+        && !match types {
+            Value::String(t) => t == "number",
+            Value::Array(ts) => ts.iter().any(|t| t == "number"),
+            _ => true,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn feed_chars(
+        validator: &mut PartialJsonValidator,
+        input: &str,
+    ) -> Result<(), PartialJsonError> {
+        for c in input.chars() {
+            validator.push(&c.to_string())?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_valid_documents_char_by_char() {
+        for input in [
+            r#"{"a": 1, "b": [true, false, null], "c": {"d": "e\"é"}}"#,
+            r#"[1, -2.5e3, "x"]"#,
+            r#"  {}  "#,
+            r#"[]"#,
+        ] {
+            let mut validator = PartialJsonValidator::new();
+            feed_chars(&mut validator, input).unwrap();
+            assert!(validator.is_complete(), "{}", input);
+            validator.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_top_level_number_needs_finish() {
+        let mut validator = PartialJsonValidator::new();
+        validator.push("42").unwrap();
+        assert!(!validator.is_complete());
+        validator.finish().unwrap();
+        assert!(validator.is_complete());
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        for (input, position) in [
+            (r#"{"a" 1}"#, 5),
+            (r#"{a: 1}"#, 1),
+            (r#"{"a": tru"#, usize::MAX),
+            (r#"{"a": trux}"#, 9),
+            (r#"[1,,2]"#, 3),
+            (r#"{"a": 1}}"#, 8),
+        ] {
+            let mut validator = PartialJsonValidator::new();
+            let result = validator.push(input);
+            if position == usize::MAX {
+                assert!(result.is_ok());
+                assert!(validator.finish().is_err());
+            } else {
+                match result {
+                    Err(PartialJsonError::Syntax { position: p, .. }) => {
+                        assert_eq!(p, position, "{}", input)
+                    }
+                    other => panic!("{}: {:?}", input, other),
+                }
+                assert!(!validator.is_plausible());
+                assert!(validator.push("}").is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_rejects_early() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "city": {"type": "string"},
+                "days": {"type": "integer"},
+            },
+            "required": ["city"],
+            "additionalProperties": false,
+        });
+
+        let mut validator = PartialJsonValidator::new().with_schema(schema.clone());
+        assert!(validator.push(r#"{"city": "Paris", "days": "#).is_ok());
+        assert!(matches!(
+            validator.push("\"3"),
+            Err(PartialJsonError::Schema(m)) if m.contains("days")
+        ));
+
+        let mut validator = PartialJsonValidator::new().with_schema(schema.clone());
+        assert!(matches!(
+            validator.push(r#"{"country":"#),
+            Err(PartialJsonError::Schema(m)) if m.contains("country")
+        ));
+
+        let mut validator = PartialJsonValidator::new().with_schema(schema.clone());
+        assert!(validator.push(r#"{"days": 2.5"#).is_ok());
+        assert!(validator.push("}").is_err());
+
+        let mut validator = PartialJsonValidator::new().with_schema(schema.clone());
+        assert!(matches!(
+            validator.push(r#"{"days": 2}"#),
+            Err(PartialJsonError::Schema(m)) if m.contains("required")
+        ));
+
+        let mut validator = PartialJsonValidator::new().with_schema(schema);
+        assert!(validator.push("[").is_err());
+    }
+
+    #[test]
+    fn test_schema_ignores_nested_values() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"filter": {"type": "object"}},
+            "additionalProperties": false,
+        });
+        let mut validator = PartialJsonValidator::new().with_schema(schema);
+        validator
+            .push(r#"{"filter": {"other": [1, "x"]}}"#)
+            .unwrap();
+        assert!(validator.is_complete());
+    }
+}
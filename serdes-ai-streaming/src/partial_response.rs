@@ -257,6 +257,7 @@ impl PartialResponse {
             usage: self.usage,
             vendor_id: self.vendor_id,
             vendor_details: None,
+            alternatives: Vec::new(),
             kind: "response".to_string(),
         }
     }
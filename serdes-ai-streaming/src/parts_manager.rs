@@ -2,13 +2,27 @@
 //!
 //! Handles accumulation of streaming deltas into complete parts,
 //! with support for vendor-specific ID tracking and embedded thinking tags.
+//!
+//! The manager works in two directions:
+//! - Provider parsers feed raw vendor deltas through the `handle_*_delta`
+//!   methods and get back [`ModelResponseStreamEvent`]s to emit.
+//! - Consumers feed [`ModelResponseStreamEvent`]s from any provider stream
+//!   through [`ModelResponsePartsManager::handle_event`] and build the final
+//!   [`ModelResponse`] with [`ModelResponsePartsManager::to_response`].
+//!
+//! Either way, every part gets a stable ID when it is created. Events the
+//! manager emits carry it in their `part_id` field, so consumers can follow a
+//! part across its start, delta and end events.
 
 use serde_json::{Map, Value};
+use serdes_ai_core::identifier::generate_part_id;
 use serdes_ai_core::messages::{
-    BuiltinToolCallPart, FilePart, ModelResponsePart, ModelResponseStreamEvent, TextPart,
-    ThinkingPart, ToolCallArgs, ToolCallPart,
+    BuiltinToolCallPart, FilePart, ModelResponse, ModelResponsePart, ModelResponsePartDelta,
+    ModelResponseStreamEvent, TextPart, ThinkingPart, ToolCallArgs, ToolCallPart,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::partial_json::{PartialJsonError, PartialJsonValidator};
 
 /// Vendor-assigned part identifier.
 ///
@@ -69,6 +83,8 @@ pub struct ToolCallAccumulator {
     pub id: Option<String>,
     /// Provider-specific details/metadata.
     pub provider_details: Option<Map<String, Value>>,
+    /// Incremental validator for arguments added with [`push_args`](Self::push_args).
+    pub validator: PartialJsonValidator,
 }
 
 impl ToolCallAccumulator {
@@ -78,6 +94,28 @@ impl ToolCallAccumulator {
         Self::default()
     }
 
+    /// Validate arguments against the tool's JSON schema as they arrive.
+    #[must_use]
+    pub fn with_schema(mut self, schema: Value) -> Self {
+        self.validator = PartialJsonValidator::new().with_schema(schema);
+        self
+    }
+
+    /// Append an arguments fragment and validate the buffer so far.
+    ///
+    /// The fragment is always appended. An error means the arguments can no
+    /// longer become a valid call, so the caller can reject it early.
+    pub fn push_args(&mut self, delta: &str) -> Result<(), PartialJsonError> {
+        self.args_buffer.push_str(delta);
+        self.validator.push(delta)
+    }
+
+    /// Check whether the arguments so far are a plausible prefix.
+    #[must_use]
+    pub fn is_plausible(&self) -> bool {
+        self.validator.is_plausible()
+    }
+
     /// Convert to a complete ToolCallPart (if tool_name is known).
     #[must_use]
     pub fn to_tool_call_part(&self) -> Option<ToolCallPart> {
@@ -231,6 +269,44 @@ impl ManagedPart {
                 .map(ModelResponsePart::BuiltinToolCall),
         }
     }
+
+    /// Apply a stream delta to this part.
+    ///
+    /// Returns `false` if the delta kind doesn't match the part kind.
+    pub fn apply_delta(&mut self, delta: &ModelResponsePartDelta) -> bool {
+        match (delta, self) {
+            (ModelResponsePartDelta::Text(d), Self::Text(p)) => d.apply(p),
+            (ModelResponsePartDelta::Thinking(d), Self::Thinking(p)) => d.apply(p),
+            (ModelResponsePartDelta::ToolCall(d), Self::ToolCall(p)) => d.apply(p),
+            (ModelResponsePartDelta::ToolCall(d), Self::ToolCallAccumulating(acc)) => {
+                acc.args_buffer.push_str(&d.args_delta);
+                if acc.tool_call_id.is_none() {
+                    acc.tool_call_id = d.tool_call_id.clone();
+                }
+            }
+            (ModelResponsePartDelta::BuiltinToolCall(d), Self::BuiltinToolCall(p)) => d.apply(p),
+            (
+                ModelResponsePartDelta::BuiltinToolCall(d),
+                Self::BuiltinToolCallAccumulating(acc),
+            ) => {
+                acc.args_buffer.push_str(&d.args_delta);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl From<ModelResponsePart> for ManagedPart {
+    fn from(part: ModelResponsePart) -> Self {
+        match part {
+            ModelResponsePart::Text(p) => Self::Text(p),
+            ModelResponsePart::Thinking(p) => Self::Thinking(p),
+            ModelResponsePart::ToolCall(p) => Self::ToolCall(p),
+            ModelResponsePart::File(p) => Self::File(p),
+            ModelResponsePart::BuiltinToolCall(p) => Self::BuiltinToolCall(p),
+        }
+    }
 }
 
 /// State for detecting embedded thinking tags in text content.
@@ -270,12 +346,28 @@ struct ThinkingTagState {
 /// // Get completed parts
 /// let parts = manager.get_parts();
 /// ```
+///
+/// # Reconstructing a response from stream events
+///
+/// ```ignore
+/// let mut manager = ModelResponsePartsManager::new();
+/// while let Some(event) = model_stream.next().await {
+///     manager.handle_event(&event?);
+/// }
+/// let response = manager.to_response();
+/// ```
 #[derive(Debug, Default)]
 pub struct ModelResponsePartsManager {
     /// The managed parts.
     parts: Vec<ManagedPart>,
+    /// Stable ID of each part, by part index.
+    part_ids: Vec<String>,
     /// Map from vendor ID to part index.
     vendor_id_to_index: HashMap<VendorId, usize>,
+    /// Map from stream event index to part index.
+    event_index_to_part: HashMap<usize, usize>,
+    /// Parts for which a `PartEnd` event was received.
+    ended: HashSet<usize>,
     /// State for thinking tag detection.
     thinking_state: ThinkingTagState,
 }
@@ -317,6 +409,105 @@ impl ModelResponsePartsManager {
         &self.parts
     }
 
+    /// Get the stable ID of a part.
+    #[must_use]
+    pub fn part_id(&self, part_index: usize) -> Option<&str> {
+        self.part_ids.get(part_index).map(String::as_str)
+    }
+
+    /// Get the stable ID of the part started by a stream event index.
+    #[must_use]
+    pub fn event_part_id(&self, event_index: usize) -> Option<&str> {
+        self.event_part_index(event_index)
+            .and_then(|idx| self.part_id(idx))
+    }
+
+    /// Get the part index assigned to a vendor ID.
+    #[must_use]
+    pub fn part_index(&self, vendor_id: &VendorId) -> Option<usize> {
+        self.vendor_id_to_index.get(vendor_id).copied()
+    }
+
+    /// Get the part index assigned to a stream event index.
+    ///
+    /// Each event index passed to [`handle_event`](Self::handle_event) maps
+    /// to exactly one part for the lifetime of the manager.
+    #[must_use]
+    pub fn event_part_index(&self, event_index: usize) -> Option<usize> {
+        self.event_index_to_part.get(&event_index).copied()
+    }
+
+    /// Get the part started by a stream event index.
+    #[must_use]
+    pub fn event_part(&self, event_index: usize) -> Option<&ManagedPart> {
+        self.event_part_index(event_index)
+            .map(|idx| &self.parts[idx])
+    }
+
+    /// Check whether a `PartEnd` event was received for a part.
+    #[must_use]
+    pub fn is_part_ended(&self, part_index: usize) -> bool {
+        self.ended.contains(&part_index)
+    }
+
+    /// Apply a stream event, returning the index of the affected part.
+    ///
+    /// Parts are ordered by their first `PartStart`. A repeated `PartStart`
+    /// for the same event index replaces the part in place and keeps its ID.
+    /// Deltas and ends for unknown indices, and deltas of the wrong kind, are
+    /// ignored and return `None`.
+    ///
+    /// A new part takes the `part_id` of its start event, or a generated one
+    /// if the event has none.
+    pub fn handle_event(&mut self, event: &ModelResponseStreamEvent) -> Option<usize> {
+        match event {
+            ModelResponseStreamEvent::PartStart(start) => {
+                let part = ManagedPart::from(start.part.clone());
+                let idx = match self.event_index_to_part.get(&start.index) {
+                    Some(&idx) => {
+                        self.parts[idx] = part;
+                        if let Some(id) = &start.part_id {
+                            self.part_ids[idx] = id.clone();
+                        }
+                        self.ended.remove(&idx);
+                        idx
+                    }
+                    None => {
+                        let idx = self.push_part(part, start.part_id.clone());
+                        self.event_index_to_part.insert(start.index, idx);
+                        idx
+                    }
+                };
+                Some(idx)
+            }
+            ModelResponseStreamEvent::PartDelta(delta) => {
+                let idx = *self.event_index_to_part.get(&delta.index)?;
+                self.parts[idx].apply_delta(&delta.delta).then_some(idx)
+            }
+            ModelResponseStreamEvent::PartEnd(end) => {
+                let idx = *self.event_index_to_part.get(&end.index)?;
+                self.ended.insert(idx);
+                Some(idx)
+            }
+        }
+    }
+
+    /// Build a response from the current parts.
+    ///
+    /// Only the parts are set; callers fill in model name, usage, and
+    /// finish reason from the provider.
+    #[must_use]
+    pub fn to_response(&self) -> ModelResponse {
+        ModelResponse::with_parts(self.get_parts())
+    }
+
+    /// Add a part with the given or a generated ID, returning its index.
+    fn push_part(&mut self, part: ManagedPart, id: Option<String>) -> usize {
+        self.parts.push(part);
+        self.part_ids.push(id.unwrap_or_else(generate_part_id));
+        self.parts.len() - 1
+    }
+
     /// Find the index of the latest part of a given type, or None.
     fn find_latest_part_index<F>(&self, predicate: F) -> Option<usize>
     where
@@ -351,8 +542,7 @@ impl ModelResponsePartsManager {
         }
 
         // Create new part
-        let idx = self.parts.len();
-        self.parts.push(create_part());
+        let idx = self.push_part(create_part(), None);
 
         // Register vendor ID mapping
         if let Some(vid) = vendor_id {
@@ -425,9 +615,12 @@ impl ModelResponsePartsManager {
         if let ManagedPart::Text(ref mut text_part) = self.parts[idx] {
             if is_new {
                 text_part.content = content.to_string();
-                events.push(ModelResponseStreamEvent::part_start(
-                    idx,
-                    ModelResponsePart::Text(text_part.clone()),
+                events.push(with_part_id(
+                    &self.part_ids,
+                    ModelResponseStreamEvent::part_start(
+                        idx,
+                        ModelResponsePart::Text(text_part.clone()),
+                    ),
                 ));
             } else {
                 text_part.content.push_str(content);
@@ -438,7 +631,10 @@ impl ModelResponsePartsManager {
                 if let Some(new_details) = provider_details {
                     text_part.provider_details = Some(new_details);
                 }
-                events.push(ModelResponseStreamEvent::text_delta(idx, content));
+                events.push(with_part_id(
+                    &self.part_ids,
+                    ModelResponseStreamEvent::text_delta(idx, content),
+                ));
             }
         }
 
@@ -632,9 +828,12 @@ impl ModelResponsePartsManager {
 
             if is_new {
                 thinking_part.content = delta_content.to_string();
-                events.push(ModelResponseStreamEvent::part_start(
-                    idx,
-                    ModelResponsePart::Thinking(thinking_part.clone()),
+                events.push(with_part_id(
+                    &self.part_ids,
+                    ModelResponseStreamEvent::part_start(
+                        idx,
+                        ModelResponsePart::Thinking(thinking_part.clone()),
+                    ),
                 ));
             } else {
                 thinking_part.content.push_str(delta_content);
@@ -652,7 +851,10 @@ impl ModelResponsePartsManager {
                     thinking_part.provider_details = Some(new_details);
                 }
                 if !delta_content.is_empty() {
-                    events.push(ModelResponseStreamEvent::thinking_delta(idx, delta_content));
+                    events.push(with_part_id(
+                        &self.part_ids,
+                        ModelResponseStreamEvent::thinking_delta(idx, delta_content),
+                    ));
                 }
             }
         }
@@ -726,9 +928,12 @@ impl ModelResponsePartsManager {
         // If we got a new tool call, update the parts vector and return the event
         if let Some(tool_call_part) = maybe_new_tool_call {
             self.parts[idx] = ManagedPart::ToolCall(tool_call_part.clone());
-            return Some(ModelResponseStreamEvent::part_start(
-                idx,
-                ModelResponsePart::ToolCall(tool_call_part),
+            return Some(with_part_id(
+                &self.part_ids,
+                ModelResponseStreamEvent::part_start(
+                    idx,
+                    ModelResponsePart::ToolCall(tool_call_part),
+                ),
             ));
         }
 
@@ -750,7 +955,10 @@ impl ModelResponsePartsManager {
                         tool_call.provider_details = provider_details;
                     }
 
-                    return Some(ModelResponseStreamEvent::tool_call_delta(idx, delta));
+                    return Some(with_part_id(
+                        &self.part_ids,
+                        ModelResponseStreamEvent::tool_call_delta(idx, delta),
+                    ));
                 }
 
                 // Just updating metadata, no event needed
@@ -785,14 +993,16 @@ impl ModelResponsePartsManager {
         vendor_part_id: Option<VendorId>,
         file_part: FilePart,
     ) -> ModelResponseStreamEvent {
-        let idx = self.parts.len();
-        self.parts.push(ManagedPart::File(file_part.clone()));
+        let idx = self.push_part(ManagedPart::File(file_part.clone()), None);
 
         if let Some(vid) = vendor_part_id {
             self.vendor_id_to_index.insert(vid, idx);
         }
 
-        ModelResponseStreamEvent::file_part(idx, file_part)
+        with_part_id(
+            &self.part_ids,
+            ModelResponseStreamEvent::file_part(idx, file_part),
+        )
     }
 
     /// Handle builtin tool call delta.
@@ -863,9 +1073,9 @@ impl ModelResponsePartsManager {
         // If we got a new builtin tool call, update the parts vector and return the event
         if let Some(builtin_part) = maybe_new_builtin_call {
             self.parts[idx] = ManagedPart::BuiltinToolCall(builtin_part.clone());
-            return Some(ModelResponseStreamEvent::builtin_tool_call_start(
-                idx,
-                builtin_part,
+            return Some(with_part_id(
+                &self.part_ids,
+                ModelResponseStreamEvent::builtin_tool_call_start(idx, builtin_part),
             ));
         }
 
@@ -887,8 +1097,9 @@ impl ModelResponsePartsManager {
                         builtin_call.provider_details = provider_details;
                     }
 
-                    return Some(ModelResponseStreamEvent::builtin_tool_call_delta(
-                        idx, delta,
+                    return Some(with_part_id(
+                        &self.part_ids,
+                        ModelResponseStreamEvent::builtin_tool_call_delta(idx, delta),
                     ));
                 }
 
@@ -909,11 +1120,22 @@ impl ModelResponsePartsManager {
     /// Clear all parts and reset state.
     pub fn clear(&mut self) {
         self.parts.clear();
+        self.part_ids.clear();
         self.vendor_id_to_index.clear();
+        self.event_index_to_part.clear();
+        self.ended.clear();
         self.thinking_state = ThinkingTagState::default();
     }
 }
 
+/// Stamp an event built by the manager with the ID of its part.
+fn with_part_id(part_ids: &[String], event: ModelResponseStreamEvent) -> ModelResponseStreamEvent {
+    match part_ids.get(event.index()) {
+        Some(id) => event.with_part_id(id.clone()),
+        None => event,
+    }
+}
+
 /// Find a partial tag match at the end of content.
 ///
 /// Returns the partial match if the content ends with a prefix of the tag.
@@ -962,6 +1184,19 @@ mod tests {
         assert_eq!(part.tool_call_id, Some("call_123".to_string()));
     }
 
+    #[test]
+    fn test_tool_call_accumulator_validates_args() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {"city": {"type": "string"}},
+        });
+        let mut acc = ToolCallAccumulator::new().with_schema(schema);
+        assert!(acc.push_args(r#"{"city": "#).is_ok());
+        assert!(acc.push_args("42").is_err());
+        assert!(!acc.is_plausible());
+        assert_eq!(acc.args_buffer, r#"{"city": 42"#);
+    }
+
     #[test]
     fn test_managed_part_types() {
         let text = ManagedPart::Text(TextPart::new("hello"));
@@ -1360,4 +1595,142 @@ mod tests {
         // Should still be just one part
         assert_eq!(manager.len(), 1);
     }
+
+    #[test]
+    fn test_handle_event_reconstructs_response() {
+        use serdes_ai_core::messages::PartStartEvent;
+
+        let mut manager = ModelResponsePartsManager::new();
+        let events = vec![
+            ModelResponseStreamEvent::PartStart(PartStartEvent::thinking(0, "Let me")),
+            ModelResponseStreamEvent::thinking_delta(0, " think"),
+            ModelResponseStreamEvent::part_end(0),
+            ModelResponseStreamEvent::PartStart(PartStartEvent::text(1, "Hello")),
+            ModelResponseStreamEvent::PartStart(PartStartEvent::new(
+                5,
+                ModelResponsePart::ToolCall(
+                    ToolCallPart::new("search", serde_json::json!({})).with_tool_call_id("call_1"),
+                ),
+            )),
+            ModelResponseStreamEvent::text_delta(1, ", world"),
+            ModelResponseStreamEvent::tool_call_delta(5, r#"{"q":"#),
+            ModelResponseStreamEvent::tool_call_delta(5, r#""rust"}"#),
+            ModelResponseStreamEvent::part_end(5),
+        ];
+        for event in &events {
+            assert!(manager.handle_event(event).is_some());
+        }
+
+        assert_eq!(manager.event_part_index(5), Some(2));
+        assert!(manager.is_part_ended(0));
+        assert!(!manager.is_part_ended(1));
+
+        let response = manager.to_response();
+        assert_eq!(response.parts.len(), 3);
+        match &response.parts[0] {
+            ModelResponsePart::Thinking(t) => assert_eq!(t.content, "Let me think"),
+            other => panic!("Expected thinking part, got {:?}", other),
+        }
+        match &response.parts[1] {
+            ModelResponsePart::Text(t) => assert_eq!(t.content, "Hello, world"),
+            other => panic!("Expected text part, got {:?}", other),
+        }
+        match &response.parts[2] {
+            ModelResponsePart::ToolCall(tc) => {
+                assert_eq!(tc.tool_call_id.as_deref(), Some("call_1"));
+                assert_eq!(tc.args.to_json()["q"], "rust");
+            }
+            other => panic!("Expected tool call part, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_handle_event_ignores_unknown_and_mismatched() {
+        use serdes_ai_core::messages::PartStartEvent;
+
+        let mut manager = ModelResponsePartsManager::new();
+        assert_eq!(
+            manager.handle_event(&ModelResponseStreamEvent::text_delta(3, "x")),
+            None
+        );
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+            0, "a",
+        )));
+        assert_eq!(
+            manager.handle_event(&ModelResponseStreamEvent::thinking_delta(0, "x")),
+            None
+        );
+        assert_eq!(manager.get_parts(), vec![ModelResponsePart::text("a")]);
+    }
+
+    #[test]
+    fn test_handle_event_assigns_stable_part_ids() {
+        use serdes_ai_core::identifier::{use_id_generator, SequentialIds};
+        use serdes_ai_core::messages::PartStartEvent;
+
+        let _ids = use_id_generator(SequentialIds::new());
+        let mut manager = ModelResponsePartsManager::new();
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+            0, "a",
+        )));
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(
+            PartStartEvent::text(3, "b").with_part_id("item_9"),
+        ));
+        manager.handle_event(&ModelResponseStreamEvent::text_delta(0, "c"));
+        // A restart keeps the ID the part already has
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+            0, "d",
+        )));
+
+        assert_eq!(manager.event_part_id(0), Some("part_1"));
+        assert_eq!(manager.event_part_id(3), Some("item_9"));
+        assert_eq!(manager.part_id(1), Some("item_9"));
+        assert_eq!(manager.event_part_id(7), None);
+    }
+
+    #[test]
+    fn test_emitted_events_carry_part_ids() {
+        use serdes_ai_core::identifier::{use_id_generator, SequentialIds};
+
+        let _ids = use_id_generator(SequentialIds::new());
+        let mut manager = ModelResponsePartsManager::new();
+        let mut events = manager.handle_text_delta(None, "Hello", None, None, None, false);
+        events.extend(manager.handle_text_delta(None, " world", None, None, None, false));
+        events.extend(manager.handle_tool_call_delta(
+            Some(VendorId::Int(0)),
+            Some("search"),
+            Some("{}"),
+            Some("call_1".to_string()),
+            None,
+        ));
+
+        let ids: Vec<_> = events.iter().map(|e| e.part_id()).collect();
+        assert_eq!(ids, vec![Some("part_1"), Some("part_1"), Some("part_2")]);
+    }
+
+    #[test]
+    fn test_handle_event_restart_replaces_in_place() {
+        use serdes_ai_core::messages::PartStartEvent;
+
+        let mut manager = ModelResponsePartsManager::new();
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+            0, "draft",
+        )));
+        manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+            1, "second",
+        )));
+        assert_eq!(
+            manager.handle_event(&ModelResponseStreamEvent::PartStart(PartStartEvent::text(
+                0, "final",
+            ))),
+            Some(0)
+        );
+        assert_eq!(
+            manager.get_parts(),
+            vec![
+                ModelResponsePart::text("final"),
+                ModelResponsePart::text("second")
+            ]
+        );
+    }
 }
@@ -6,7 +6,10 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use serdes_ai_core::artifacts::{data_url, ArtifactRef, ArtifactStore};
+use serdes_ai_core::messages::FilePart;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::{
@@ -201,6 +204,9 @@ pub struct ExecutionResult {
     pub execution_time_ms: u64,
     /// Whether execution timed out.
     pub timed_out: bool,
+    /// Files written by the code, such as plots.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FilePart>,
 }
 
 impl ExecutionResult {
@@ -209,6 +215,11 @@ impl ExecutionResult {
     pub fn is_success(&self) -> bool {
         self.exit_code == 0 && !self.timed_out
     }
+
+    /// Put the output files in an artifact store.
+    pub fn store_files(&self, store: &dyn ArtifactStore) -> std::io::Result<Vec<ArtifactRef>> {
+        self.files.iter().map(|file| store.put_file(file)).collect()
+    }
 }
 
 /// Code execution tool.
@@ -236,21 +247,49 @@ impl ExecutionResult {
 /// ```
 pub struct CodeExecutionTool {
     config: CodeExecutionConfig,
+    artifact_store: Option<Arc<dyn ArtifactStore>>,
 }
 
 impl CodeExecutionTool {
     /// Create a new code execution tool with default config.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            config: CodeExecutionConfig::default(),
-        }
+        Self::with_config(CodeExecutionConfig::default())
     }
 
     /// Create with a specific config.
     #[must_use]
     pub fn with_config(config: CodeExecutionConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            artifact_store: None,
+        }
+    }
+
+    /// Put output files in an artifact store.
+    ///
+    /// The model then sees artifact references instead of inline base64.
+    #[must_use]
+    pub fn with_artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifact_store = Some(store);
+        self
+    }
+
+    /// Describe output files for the model.
+    fn files_output(&self, result: &ExecutionResult) -> Result<JsonValue, ToolError> {
+        match &self.artifact_store {
+            Some(store) => {
+                let artifacts = result.store_files(store.as_ref()).map_err(|e| {
+                    ToolError::execution_failed(format!("Storing output files: {e}"))
+                })?;
+                Ok(serde_json::to_value(artifacts).unwrap_or_default())
+            }
+            None => Ok(result
+                .files
+                .iter()
+                .map(|file| JsonValue::String(data_url(&file.content)))
+                .collect()),
+        }
     }
 
     /// Get the tool schema.
@@ -302,6 +341,7 @@ impl CodeExecutionTool {
             exit_code: 0,
             execution_time_ms: 0,
             timed_out: false,
+            files: Vec::new(),
         }
     }
 }
@@ -370,7 +410,7 @@ impl<Deps: Send + Sync> Tool<Deps> for CodeExecutionTool {
 
         let result = self.execute(language, code, stdin).await;
 
-        let output = serde_json::json!({
+        let mut output = serde_json::json!({
             "success": result.is_success(),
             "stdout": result.stdout,
             "stderr": result.stderr,
@@ -378,6 +418,14 @@ impl<Deps: Send + Sync> Tool<Deps> for CodeExecutionTool {
             "execution_time_ms": result.execution_time_ms,
             "timed_out": result.timed_out
         });
+        if !result.files.is_empty() {
+            let key = if self.artifact_store.is_some() {
+                "artifacts"
+            } else {
+                "files"
+            };
+            output[key] = self.files_output(&result)?;
+        }
 
         Ok(ToolReturn::json(output))
     }
@@ -391,6 +439,7 @@ impl std::fmt::Debug for CodeExecutionTool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CodeExecutionTool")
             .field("config", &self.config)
+            .field("artifact_store", &self.artifact_store.is_some())
             .finish()
     }
 }
@@ -536,6 +585,7 @@ mod tests {
             exit_code: 0,
             execution_time_ms: 100,
             timed_out: false,
+            files: Vec::new(),
         };
         assert!(success.is_success());
 
@@ -545,6 +595,7 @@ mod tests {
             exit_code: 1,
             execution_time_ms: 100,
             timed_out: false,
+            files: Vec::new(),
         };
         assert!(!failure.is_success());
 
@@ -554,7 +605,29 @@ mod tests {
             exit_code: 0,
             execution_time_ms: 30000,
             timed_out: true,
+            files: Vec::new(),
         };
         assert!(!timeout.is_success());
     }
+
+    #[test]
+    fn test_execution_result_store_files() {
+        use serdes_ai_core::artifacts::InMemoryArtifactStore;
+
+        let result = ExecutionResult {
+            stdout: String::new(),
+            stderr: None,
+            exit_code: 0,
+            execution_time_ms: 10,
+            timed_out: false,
+            files: vec![FilePart::from_bytes(vec![1, 2, 3], "image/png")],
+        };
+        let store = InMemoryArtifactStore::new();
+        let artifacts = result.store_files(&store).unwrap();
+
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].media_type, "image/png");
+        assert_eq!(artifacts[0].size, 3);
+        assert!(store.get(&artifacts[0].hash).unwrap().is_some());
+    }
 }
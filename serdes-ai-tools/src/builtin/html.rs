@@ -0,0 +1,1055 @@
+//! HTML to markdown conversion for fetched pages.
+//!
+//! Raw HTML wastes context on markup, scripts and navigation, and confuses
+//! models. [`HtmlConverter`] turns a page into compact markdown:
+//!
+//! - **Readability extraction**: the main content (`<article>`, `<main>` or
+//!   the block with the most paragraph text) is kept; scripts, navigation,
+//!   headers, footers, sidebars and similar boilerplate are dropped.
+//! - **Link preservation**: links become `[text](url)`, resolved against the
+//!   page URL.
+//! - **Pagination**: [`paginate`] splits long output at paragraph boundaries
+//!   and returns a continuation token for the next page.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serdes_ai_tools::builtin::html::{paginate, HtmlConverter};
+//!
+//! let html = r#"<html><head><title>Docs</title></head><body>
+//!     <nav><a href="/">Home</a></nav>
+//!     <article><h1>Install</h1><p>Run <code>cargo add</code>, see <a href="faq">the FAQ</a>.</p></article>
+//! </body></html>"#;
+//!
+//! let page = HtmlConverter::new()
+//!     .base_url("https://example.com/docs/")
+//!     .convert(html);
+//! assert_eq!(page.title.as_deref(), Some("Docs"));
+//! assert_eq!(
+//!     page.markdown,
+//!     "# Install\n\nRun `cargo add`, see [the FAQ](https://example.com/docs/faq)."
+//! );
+//!
+//! let first = paginate(&page.markdown, None, 20).unwrap();
+//! assert!(first.continuation.is_some());
+//! ```
+
+/// Result of converting a page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertedPage {
+    /// Text of the `<title>` element, or of the first `<h1>`.
+    pub title: Option<String>,
+    /// Markdown of the page content.
+    pub markdown: String,
+}
+
+/// Converter from HTML to markdown.
+#[derive(Debug, Clone)]
+pub struct HtmlConverter {
+    base_url: Option<String>,
+    extract_main: bool,
+    links: bool,
+    images: bool,
+}
+
+impl Default for HtmlConverter {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            extract_main: true,
+            links: true,
+            images: false,
+        }
+    }
+}
+
+impl HtmlConverter {
+    /// Create a converter with readability extraction and links enabled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page URL that relative links are resolved against.
+    #[must_use]
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into());
+        self
+    }
+
+    /// Keep only the main content of the page (default `true`).
+    #[must_use]
+    pub fn extract_main(mut self, enabled: bool) -> Self {
+        self.extract_main = enabled;
+        self
+    }
+
+    /// Keep links as `[text](url)` (default `true`); otherwise keep the text.
+    #[must_use]
+    pub fn links(mut self, enabled: bool) -> Self {
+        self.links = enabled;
+        self
+    }
+
+    /// Keep images as `![alt](url)` (default `false`).
+    #[must_use]
+    pub fn images(mut self, enabled: bool) -> Self {
+        self.images = enabled;
+        self
+    }
+
+    /// Convert a page.
+    #[must_use]
+    pub fn convert(&self, html: &str) -> ConvertedPage {
+        let document = parse(html);
+        let title = document
+            .find(&|e| e.name == "title")
+            .or_else(|| document.find(&|e| e.name == "h1"))
+            .map(|e| collapse_whitespace(&e.text()).trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        let root = if self.extract_main {
+            main_content(&document)
+        } else {
+            document.find(&|e| e.name == "body").unwrap_or(&document)
+        };
+        let markdown = self.blocks(&root.children);
+        ConvertedPage { title, markdown }
+    }
+
+    /// Render a sequence of nodes as blocks separated by blank lines.
+    fn blocks(&self, nodes: &[Node]) -> String {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut inline: Vec<&Node> = Vec::new();
+        let flush = |inline: &mut Vec<&Node>, blocks: &mut Vec<String>| {
+            let text = inline
+                .drain(..)
+                .map(|node| self.inline(node))
+                .collect::<String>();
+            let text = text.trim();
+            if !text.is_empty() {
+                blocks.push(text.lines().map(str::trim).collect::<Vec<_>>().join("\n"));
+            }
+        };
+
+        for node in nodes {
+            match node {
+                Node::Element(e) if e.is_block() => {
+                    flush(&mut inline, &mut blocks);
+                    let block = self.block(e);
+                    if !block.trim().is_empty() {
+                        blocks.push(block);
+                    }
+                }
+                Node::Element(e) if is_boilerplate(e) => {}
+                _ => inline.push(node),
+            }
+        }
+        flush(&mut inline, &mut blocks);
+        blocks.join("\n\n")
+    }
+
+    fn block(&self, e: &Element) -> String {
+        if is_boilerplate(e) {
+            return String::new();
+        }
+        match e.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(e.name.as_bytes()[1] - b'0');
+                let text = self.inline_children(e);
+                if text.is_empty() {
+                    String::new()
+                } else {
+                    format!("{} {}", "#".repeat(level), text.replace('\n', " "))
+                }
+            }
+            "hr" => "---".to_string(),
+            "pre" => {
+                let code = e.text();
+                let code = code.trim_matches('\n');
+                let language = e
+                    .find(&|c| c.name == "code")
+                    .and_then(|c| c.attr("class"))
+                    .and_then(|class| {
+                        class
+                            .split_whitespace()
+                            .find_map(|c| c.strip_prefix("language-"))
+                    })
+                    .unwrap_or_default();
+                let fence = if code.contains("```") { "~~~" } else { "```" };
+                format!("{fence}{language}\n{code}\n{fence}")
+            }
+            "blockquote" => self
+                .blocks(&e.children)
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">".to_string()
+                    } else {
+                        format!("> {line}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "ul" | "ol" => self.list(e),
+            "table" => self.table(e),
+            "dt" => format!("**{}**", self.inline_children(e)),
+            _ => self.blocks(&e.children),
+        }
+    }
+
+    fn list(&self, e: &Element) -> String {
+        let ordered = e.name == "ol";
+        let start: usize = e.attr("start").and_then(|s| s.parse().ok()).unwrap_or(1);
+        let mut items = Vec::new();
+        for item in e.elements().filter(|c| c.name == "li") {
+            let marker = if ordered {
+                format!("{}. ", start + items.len())
+            } else {
+                "- ".to_string()
+            };
+            let body = self.blocks(&item.children);
+            let indent = " ".repeat(marker.len());
+            let mut lines = body.lines();
+            let first = lines.next().unwrap_or_default();
+            let mut text = format!("{marker}{first}");
+            for line in lines {
+                text.push('\n');
+                if !line.is_empty() {
+                    text.push_str(&indent);
+                    text.push_str(line);
+                }
+            }
+            items.push(text);
+        }
+        items.join("\n")
+    }
+
+    fn table(&self, e: &Element) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut header = false;
+        e.walk(&mut |el| {
+            if el.name == "tr" {
+                let cells: Vec<String> = el
+                    .elements()
+                    .filter(|c| c.name == "td" || c.name == "th")
+                    .map(|c| {
+                        self.inline_children(c)
+                            .replace('\n', " ")
+                            .replace('|', "\\|")
+                    })
+                    .collect();
+                if rows.is_empty() {
+                    header = el.elements().any(|c| c.name == "th");
+                }
+                if !cells.is_empty() {
+                    rows.push(cells);
+                }
+                return false;
+            }
+            true
+        });
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return String::new();
+        }
+        let line = |cells: &[String]| {
+            let mut cells = cells.to_vec();
+            cells.resize(width, String::new());
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = Vec::new();
+        let body = if header {
+            lines.push(line(&rows[0]));
+            &rows[1..]
+        } else {
+            lines.push(line(&vec![String::new(); width]));
+            &rows[..]
+        };
+        lines.push(format!("|{}", " --- |".repeat(width)));
+        lines.extend(body.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+
+    fn inline_children(&self, e: &Element) -> String {
+        e.children
+            .iter()
+            .map(|node| self.inline(node))
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn inline(&self, node: &Node) -> String {
+        let e = match node {
+            Node::Text(text) => return collapse_whitespace(text),
+            Node::Element(e) => e,
+        };
+        if is_boilerplate(e) {
+            return String::new();
+        }
+        match e.name.as_str() {
+            "br" => "\n".to_string(),
+            "code" | "kbd" | "samp" => {
+                let code = collapse_whitespace(&e.text());
+                if code.trim().is_empty() {
+                    code
+                } else {
+                    let tick = if code.contains('`') { "``" } else { "`" };
+                    format!("{tick}{}{tick}", code.trim())
+                }
+            }
+            "strong" | "b" => wrap(&self.inline_children_raw(e), "**"),
+            "em" | "i" => wrap(&self.inline_children_raw(e), "*"),
+            "del" | "s" => wrap(&self.inline_children_raw(e), "~~"),
+            "a" => {
+                let text = self.inline_children_raw(e);
+                let href = e
+                    .attr("href")
+                    .and_then(|href| resolve_url(self.base_url.as_deref(), href));
+                match href {
+                    Some(href) if self.links && !text.trim().is_empty() => {
+                        let (lead, inner, trail) = split_whitespace_edges(&text);
+                        format!("{lead}[{}]({}){trail}", inner.replace('\n', " "), href)
+                    }
+                    _ => text,
+                }
+            }
+            "img" => {
+                let alt = e.attr("alt").map(collapse_whitespace).unwrap_or_default();
+                let src = e
+                    .attr("src")
+                    .and_then(|src| resolve_url(self.base_url.as_deref(), src));
+                match src {
+                    Some(src) if self.images => format!("![{}]({})", alt.trim(), src),
+                    _ => String::new(),
+                }
+            }
+            _ if e.is_block() => format!("\n{}\n", self.blocks(&e.children)),
+            _ => self.inline_children_raw(e),
+        }
+    }
+
+    /// Inline children without trimming, so spacing around the element is
+    /// kept.
+    fn inline_children_raw(&self, e: &Element) -> String {
+        e.children.iter().map(|node| self.inline(node)).collect()
+    }
+}
+
+/// Convert a page to markdown with the default [`HtmlConverter`].
+#[must_use]
+pub fn html_to_markdown(html: &str, base_url: Option<&str>) -> String {
+    let mut converter = HtmlConverter::new();
+    converter.base_url = base_url.map(str::to_string);
+    converter.convert(html).markdown
+}
+
+/// One page of a long text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextPage {
+    /// The text of this page.
+    pub content: String,
+    /// Token for the next page, if the text continues.
+    pub continuation: Option<String>,
+    /// Length of the whole text in bytes.
+    pub total_len: usize,
+}
+
+/// Split a long text into pages of at most `max_len` bytes.
+///
+/// Pages end at a paragraph, line or word boundary where possible.
+/// `continuation` is the token returned with the previous page, or `None`
+/// for the first page. Returns `None` if the token is invalid.
+#[must_use]
+pub fn paginate(text: &str, continuation: Option<&str>, max_len: usize) -> Option<TextPage> {
+    let start = match continuation {
+        Some(token) => token.trim().parse::<usize>().ok()?,
+        None => 0,
+    };
+    if start > text.len() || !text.is_char_boundary(start) {
+        return None;
+    }
+    let rest = &text[start..];
+    if rest.len() <= max_len {
+        return Some(TextPage {
+            content: rest.to_string(),
+            continuation: None,
+            total_len: text.len(),
+        });
+    }
+
+    let mut limit = max_len.max(1);
+    while !rest.is_char_boundary(limit) {
+        limit -= 1;
+    }
+    if limit == 0 {
+        limit = rest.chars().next().map_or(rest.len(), char::len_utf8);
+    }
+    let window = &rest[..limit];
+    // Prefer breaking at a paragraph, then a line, then a word, but never
+    // in the first half of the page
+    let end = ["\n\n", "\n", " "]
+        .iter()
+        .find_map(|sep| {
+            window
+                .rfind(sep)
+                .filter(|&i| i >= limit / 2)
+                .map(|i| i + sep.len())
+        })
+        .unwrap_or(limit);
+
+    Some(TextPage {
+        content: rest[..end].trim_end().to_string(),
+        continuation: Some((start + end).to_string()),
+        total_len: text.len(),
+    })
+}
+
+/// Resolve a link against the page URL; fragment-only and script links are
+/// dropped.
+fn resolve_url(base: Option<&str>, href: &str) -> Option<String> {
+    let href = href.trim();
+    let lower = href.to_ascii_lowercase();
+    if href.is_empty() || href.starts_with('#') || lower.starts_with("javascript:") {
+        return None;
+    }
+    if lower.starts_with("http://")
+        || lower.starts_with("https://")
+        || lower.starts_with("mailto:")
+        || lower.starts_with("data:")
+    {
+        return Some(href.to_string());
+    }
+    let Some(base) = base else {
+        return Some(href.to_string());
+    };
+    let (scheme, rest) = base.split_once("://")?;
+    let host_end = rest.find('/').unwrap_or(rest.len());
+    let (host, path) = rest.split_at(host_end);
+
+    if let Some(href) = href.strip_prefix("//") {
+        return Some(format!("{scheme}://{href}"));
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    if href.starts_with('?') {
+        return Some(format!("{scheme}://{host}{path}{href}"));
+    }
+    let joined = if href.starts_with('/') {
+        href.to_string()
+    } else {
+        let dir = &path[..path.rfind('/').map_or(0, |i| i + 1)];
+        format!("{}{}", if dir.is_empty() { "/" } else { dir }, href)
+    };
+
+    // Normalize `.` and `..` segments
+    let (joined, suffix) = match joined.find(['?', '#']) {
+        Some(i) => joined.split_at(i),
+        None => (joined.as_str(), ""),
+    };
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/').skip(1) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    if matches!(joined.rsplit('/').next(), Some("." | "..")) {
+        segments.push("");
+    }
+    Some(format!("{scheme}://{host}/{}{suffix}", segments.join("/")))
+}
+
+fn wrap(text: &str, marker: &str) -> String {
+    if text.trim().is_empty() {
+        return text.to_string();
+    }
+    let (lead, inner, trail) = split_whitespace_edges(text);
+    format!("{lead}{marker}{inner}{marker}{trail}")
+}
+
+fn split_whitespace_edges(text: &str) -> (&str, &str, &str) {
+    let inner = text.trim();
+    let lead = &text[..text.len() - text.trim_start().len()];
+    let trail = &text[text.trim_end().len()..];
+    (lead, inner, trail)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() && c != '\u{a0}' {
+            space = true;
+        } else {
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(if c == '\u{a0}' { ' ' } else { c });
+        }
+    }
+    if space {
+        out.push(' ');
+    }
+    out
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Element(Element),
+}
+
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+/// Elements that never have children.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is not markup.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "center",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+/// Elements that never carry page content.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "aside", "button", "footer", "form", "head", "header", "iframe", "input", "link", "meta",
+    "nav", "noscript", "script", "select", "style", "svg", "template", "textarea", "title",
+];
+
+/// Class and id words marking boilerplate blocks.
+const BOILERPLATE_WORDS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "advertisement",
+    "banner",
+    "breadcrumb",
+    "breadcrumbs",
+    "comment",
+    "comments",
+    "cookie",
+    "cookies",
+    "footer",
+    "menu",
+    "nav",
+    "navbar",
+    "newsletter",
+    "popup",
+    "promo",
+    "related",
+    "share",
+    "sidebar",
+    "social",
+    "sponsored",
+    "toc",
+];
+
+impl Element {
+    fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            attrs: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn is_block(&self) -> bool {
+        BLOCK_ELEMENTS.contains(&self.name.as_str())
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Visit descendants depth-first; `f` returns whether to descend.
+    fn walk<'a>(&'a self, f: &mut dyn FnMut(&'a Element) -> bool) {
+        for child in self.elements() {
+            if f(child) {
+                child.walk(f);
+            }
+        }
+    }
+
+    fn find(&self, pred: &dyn Fn(&Element) -> bool) -> Option<&Element> {
+        let mut found = None;
+        self.walk(&mut |e| {
+            if found.is_none() && pred(e) {
+                found = Some(e);
+            }
+            found.is_none()
+        });
+        found
+    }
+
+    fn text(&self) -> String {
+        let mut out = String::new();
+        for node in &self.children {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(e) if e.name == "br" => out.push('\n'),
+                Node::Element(e) => out.push_str(&e.text()),
+            }
+        }
+        out
+    }
+}
+
+fn is_boilerplate(e: &Element) -> bool {
+    if SKIPPED_ELEMENTS.contains(&e.name.as_str())
+        || e.attr("hidden").is_some()
+        || e.attr("aria-hidden") == Some("true")
+        || matches!(
+            e.attr("role"),
+            Some("navigation" | "banner" | "contentinfo" | "complementary")
+        )
+    {
+        return true;
+    }
+    ["class", "id"].iter().any(|attr| {
+        e.attr(attr).is_some_and(|value| {
+            value
+                .to_ascii_lowercase()
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|word| BOILERPLATE_WORDS.contains(&word))
+        })
+    })
+}
+
+/// Pick the element holding the main content of the page.
+fn main_content(document: &Element) -> &Element {
+    let mut articles = Vec::new();
+    document.walk(&mut |e| {
+        if e.name == "article" && !is_boilerplate(e) {
+            articles.push(e);
+        }
+        !is_boilerplate(e)
+    });
+    if let Some(article) = articles
+        .into_iter()
+        .max_by_key(|e| collapse_whitespace(&e.text()).len())
+    {
+        return article;
+    }
+    if let Some(main) = document.find(&|e| e.name == "main" || e.attr("role") == Some("main")) {
+        return main;
+    }
+
+    // Otherwise the block with the most text in direct paragraphs
+    let mut best: Option<(&Element, usize)> = None;
+    document.walk(&mut |e| {
+        if is_boilerplate(e) {
+            return false;
+        }
+        if matches!(e.name.as_str(), "div" | "section" | "td") {
+            let score: usize = e
+                .elements()
+                .filter(|c| matches!(c.name.as_str(), "p" | "pre" | "blockquote"))
+                .map(|c| collapse_whitespace(&c.text()).trim().len())
+                .sum();
+            if best.map_or(true, |(_, s)| score > s) {
+                best = Some((e, score));
+            }
+        }
+        true
+    });
+    match best {
+        Some((e, score)) if score >= 250 => e,
+        _ => document.find(&|e| e.name == "body").unwrap_or(document),
+    }
+}
+
+/// Parse HTML leniently into an element tree.
+fn parse(html: &str) -> Element {
+    let mut stack = vec![Element::new("#document")];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |i| &after[i + 3..]);
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+        } else if let Some(after) = rest
+            .strip_prefix("</")
+            .filter(|a| a.starts_with(|c: char| c.is_ascii_alphabetic()))
+        {
+            let end = after.find('>').unwrap_or(after.len());
+            let name = after[..end]
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            close(&mut stack, &name);
+            rest = after.get(end + 1..).unwrap_or_default();
+        } else if rest.len() > 1
+            && rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let (element, self_closing, after) = parse_tag(&rest[1..]);
+            rest = after;
+            if RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+                let close_tag = format!("</{}", element.name);
+                let end = find_ignore_case(rest, &close_tag).unwrap_or(rest.len());
+                let mut element = element;
+                if !matches!(element.name.as_str(), "script" | "style") {
+                    element
+                        .children
+                        .push(Node::Text(decode_entities(&rest[..end])));
+                }
+                push_node(&mut stack, Node::Element(element));
+                rest = &rest[end..];
+                rest = rest.find('>').map_or("", |i| &rest[i + 1..]);
+            } else if self_closing || VOID_ELEMENTS.contains(&element.name.as_str()) {
+                auto_close(&mut stack, &element.name);
+                push_node(&mut stack, Node::Element(element));
+            } else {
+                auto_close(&mut stack, &element.name);
+                stack.push(element);
+            }
+        } else {
+            let end = rest[1..].find('<').map_or(rest.len(), |i| i + 1);
+            push_node(&mut stack, Node::Text(decode_entities(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+
+    while stack.len() > 1 {
+        pop(&mut stack);
+    }
+    stack.pop().unwrap_or_else(|| Element::new("#document"))
+}
+
+/// Parse a start tag after its `<`, returning the element, whether it is
+/// self-closing, and the rest of the input.
+fn parse_tag(input: &str) -> (Element, bool, &str) {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(input.len());
+    let mut element = Element::new(input[..name_end].to_ascii_lowercase());
+    let mut rest = &input[name_end..];
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return (element, false, rest);
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return (element, false, after);
+        }
+        if let Some(after) = rest.strip_prefix("/>") {
+            return (element, true, after);
+        }
+        if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len())
+            .max(1);
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after[1..];
+                    let end = body.find(quote).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or_default())
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    after.split_at(end)
+                }
+            };
+            value = decode_entities(raw);
+            rest = remaining;
+        }
+        element.attrs.push((name, value));
+    }
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+}
+
+fn push_node(stack: &mut [Element], node: Node) {
+    if let Some(top) = stack.last_mut() {
+        top.children.push(node);
+    }
+}
+
+fn pop(stack: &mut Vec<Element>) {
+    if let Some(element) = stack.pop() {
+        push_node(stack, Node::Element(element));
+    }
+}
+
+/// Close the innermost open element named `name`, if any.
+fn close(stack: &mut Vec<Element>, name: &str) {
+    if let Some(index) = stack.iter().skip(1).rposition(|e| e.name == name) {
+        while stack.len() > index + 1 {
+            pop(stack);
+        }
+    }
+}
+
+/// Close elements that the opening of `name` implicitly ends, like an open
+/// `<p>` before a block or an open `<li>` before the next item.
+fn auto_close(stack: &mut Vec<Element>, name: &str) {
+    let (closes, boundaries): (&[&str], &[&str]) = match name {
+        "li" => (&["li"], &["ul", "ol"]),
+        "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+        "tr" => (&["tr", "td", "th"], &["table", "thead", "tbody", "tfoot"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "option" => (&["option"], &["select"]),
+        _ if BLOCK_ELEMENTS.contains(&name) => (&["p"], &[]),
+        _ => return,
+    };
+    for index in (1..stack.len()).rev() {
+        let open = stack[index].name.as_str();
+        if closes.contains(&open) {
+            while stack.len() > index {
+                pop(stack);
+            }
+            return;
+        }
+        if boundaries.contains(&open) || (boundaries.is_empty() && open != "p") {
+            return;
+        }
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..=end]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "middot" => '·',
+        "bull" => '•',
+        "times" => '×',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readability_extraction() {
+        let html = r#"<!DOCTYPE html>
+            <html><head><title>Release notes &amp; more</title>
+            <script>var x = "<p>not content</p>";</script><style>p { color: red }</style></head>
+            <body>
+              <header><a href="/">Logo</a></header>
+              <div class="sidebar"><p>Popular posts</p></div>
+              <div id="content">
+                <h2>Version 2.0</h2>
+                <p>This release adds <b>streaming</b> and <em>retries</em>.<br>Upgrade soon.
+                <p>Details are in the <a href="../guide/upgrade.html#steps">upgrade guide</a>.</p>
+                <ul><li>First<li>Second <ol><li>Nested</ol></ul>
+                <pre><code class="language-rust">fn main() {
+    println!("hi");
+}</code></pre>
+                <table><tr><th>Name</th><th>Value</th></tr><tr><td>a</td><td>1</td></tr></table>
+              </div>
+              <div class="comments"><p>Great post!</p></div>
+              <footer>Copyright</footer>
+            </body></html>"#;
+
+        let page = HtmlConverter::new()
+            .base_url("https://example.com/blog/posts/v2.html")
+            .convert(html);
+        assert_eq!(page.title.as_deref(), Some("Release notes & more"));
+        assert_eq!(
+            page.markdown,
+            "## Version 2.0\n\n\
+             This release adds **streaming** and *retries*.\nUpgrade soon.\n\n\
+             Details are in the [upgrade guide](https://example.com/blog/guide/upgrade.html#steps).\n\n\
+             - First\n- Second\n\n  1. Nested\n\n\
+             ```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             | Name | Value |\n| --- | --- |\n| a | 1 |"
+        );
+
+        let full = HtmlConverter::new()
+            .extract_main(false)
+            .links(false)
+            .convert(html);
+        assert!(!full.markdown.contains("Popular posts"));
+        assert!(full.markdown.contains("the upgrade guide."));
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        let base = Some("https://example.com/a/b/page.html?x=1");
+        let cases = [
+            ("c.html", "https://example.com/a/b/c.html"),
+            ("../c.html", "https://example.com/a/c.html"),
+            ("/root", "https://example.com/root"),
+            ("//cdn.example.com/x.js", "https://cdn.example.com/x.js"),
+            ("?page=2", "https://example.com/a/b/page.html?page=2"),
+            ("https://other.org/", "https://other.org/"),
+        ];
+        for (href, expected) in cases {
+            assert_eq!(resolve_url(base, href).as_deref(), Some(expected), "{href}");
+        }
+        assert_eq!(resolve_url(base, "#top"), None);
+        assert_eq!(resolve_url(base, "javascript:void(0)"), None);
+    }
+
+    #[test]
+    fn test_paginate() {
+        let text = "First paragraph here.\n\nSecond paragraph here.\n\nThird.";
+        let first = paginate(text, None, 25).unwrap();
+        assert_eq!(first.content, "First paragraph here.");
+        let second = paginate(text, first.continuation.as_deref(), 25).unwrap();
+        assert_eq!(second.content, "Second paragraph here.");
+        let third = paginate(text, second.continuation.as_deref(), 25).unwrap();
+        assert_eq!(third.content, "Third.");
+        assert_eq!(third.continuation, None);
+        assert_eq!(third.total_len, text.len());
+
+        assert!(paginate(text, Some("bogus"), 30).is_none());
+        assert!(paginate(text, Some("1000"), 30).is_none());
+        assert!(paginate("héllo wörld", None, 2).is_some());
+    }
+
+    #[test]
+    fn test_entities_and_malformed_html() {
+        assert_eq!(
+            html_to_markdown("<p>a &lt; b &#8212; c &#x41; &bogus; & d</p>", None),
+            "a < b — c A &bogus; & d"
+        );
+        assert_eq!(
+            html_to_markdown("<div><p>unclosed <i>tags</div><p>next", None),
+            "unclosed *tags*\n\nnext"
+        );
+        assert_eq!(html_to_markdown("plain < text", None), "plain < text");
+    }
+}
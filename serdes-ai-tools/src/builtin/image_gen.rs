@@ -6,6 +6,8 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use serdes_ai_core::artifacts::{ArtifactRef, ArtifactStore};
+use serdes_ai_core::messages::BinaryContent;
 
 use crate::{
     definition::ToolDefinition,
@@ -66,6 +68,18 @@ impl std::fmt::Display for OutputFormat {
     }
 }
 
+impl OutputFormat {
+    /// Get the MIME type of the format.
+    #[must_use]
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+            Self::Jpeg => "image/jpeg",
+        }
+    }
+}
+
 /// Quality level for generated images.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -374,6 +388,7 @@ impl ImageGenerationTool {
             format: self.output_format.unwrap_or(OutputFormat::Png),
             width: 1024,
             height: 1024,
+            artifact: None,
         })
     }
 }
@@ -402,6 +417,34 @@ pub struct ImageGenerationResult {
     pub width: u32,
     /// Image height in pixels.
     pub height: u32,
+    /// Reference to the image in an artifact store (if stored).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<ArtifactRef>,
+}
+
+impl ImageGenerationResult {
+    /// Move inline image data into an artifact store.
+    ///
+    /// The base64 data is replaced by an artifact reference, and the image
+    /// URL is set from the store's URL hook if not already hosted.
+    pub fn store_image(&mut self, store: &dyn ArtifactStore) -> Result<(), ToolError> {
+        use base64::Engine;
+
+        let Some(encoded) = self.image_base64.take() else {
+            return Ok(());
+        };
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&encoded)
+            .map_err(|e| ToolError::execution_failed(format!("Invalid image data: {e}")))?;
+        let artifact = store
+            .put(BinaryContent::new(data, self.format.media_type()))
+            .map_err(|e| ToolError::execution_failed(format!("Storing image: {e}")))?;
+        if self.image_url.is_none() {
+            self.image_url = artifact.url.clone();
+        }
+        self.artifact = Some(artifact);
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -734,6 +777,7 @@ mod tests {
             format: OutputFormat::Png,
             width: 1024,
             height: 1024,
+            artifact: None,
         };
 
         let json = serde_json::to_value(&result).unwrap();
@@ -741,4 +785,33 @@ mod tests {
         assert_eq!(json["width"], 1024);
         assert!(json.get("image_base64").is_none()); // skipped because None
     }
+
+    #[test]
+    fn test_image_generation_result_store_image() {
+        use serdes_ai_core::artifacts::InMemoryArtifactStore;
+
+        let mut result = ImageGenerationResult {
+            prompt: "test prompt".to_string(),
+            image_url: None,
+            image_base64: Some("AQID".to_string()),
+            revised_prompt: None,
+            format: OutputFormat::Webp,
+            width: 1024,
+            height: 1024,
+            artifact: None,
+        };
+        let store = InMemoryArtifactStore::new()
+            .with_url_fn(|a| Some(format!("https://files.example.com/{}", a.hash)));
+
+        result.store_image(&store).unwrap();
+
+        let artifact = result.artifact.clone().unwrap();
+        assert!(result.image_base64.is_none());
+        assert_eq!(artifact.media_type, "image/webp");
+        assert_eq!(result.image_url, artifact.url);
+        assert_eq!(
+            store.get(&artifact.hash).unwrap().unwrap().data,
+            vec![1, 2, 3]
+        );
+    }
 }
@@ -3,7 +3,8 @@
 //! This module provides ready-to-use tools for common agent operations:
 //!
 //! - **Web Search**: Search the web for information
-//! - **Web Fetch**: Fetch content from URLs (Anthropic, Google)
+//! - **Web Fetch**: Fetch content from URLs (Anthropic, Google, or locally)
+//! - **HTML**: Convert fetched pages to cleaned markdown
 //! - **Code Execution**: Execute code in a sandbox
 //! - **File Search**: Vector-based file search
 //! - **Image Generation**: Generate images from text prompts
@@ -15,6 +16,7 @@
 
 pub mod code_execution;
 pub mod file_search;
+pub mod html;
 pub mod image_gen;
 pub mod mcp_server;
 pub mod memory;
@@ -23,12 +25,15 @@ pub mod web_search;
 
 pub use code_execution::{CodeExecutionConfig, CodeExecutionTool, ProgrammingLanguage};
 pub use file_search::{FileSearchConfig, FileSearchTool};
+pub use html::{html_to_markdown, paginate, ConvertedPage, HtmlConverter, TextPage};
 pub use image_gen::{
     ImageAspectRatio, ImageBackground, ImageGenerationTool, ImageQuality, ImageSize, OutputFormat,
 };
 pub use mcp_server::MCPServerTool;
 pub use memory::MemoryTool;
-pub use web_fetch::{WebFetchConfig, WebFetchError, WebFetchTool, WebFetchToolBuilder};
+pub use web_fetch::{
+    FetchedPage, WebFetchConfig, WebFetchError, WebFetchTool, WebFetchToolBuilder,
+};
 pub use web_search::{
     SearchContextSize, SearchDepth, UserLocation, WebSearchConfig, WebSearchError, WebSearchTool,
     WebSearchToolBuilder,
@@ -24,11 +24,43 @@
 //! let anthropic_format = tool.to_anthropic_format();
 //! let google_format = tool.to_google_format();
 //! ```
+//!
+//! # Local Fetching
+//!
+//! With the `common-tools` feature, `WebFetchTool` also implements
+//! [`Tool`](crate::Tool) and fetches pages itself, for providers without
+//! native fetching. Set [`markdown`](WebFetchToolBuilder::markdown) to
+//! convert HTML to cleaned markdown (see [`html`](super::html)) and
+//! [`max_page_length`](WebFetchToolBuilder::max_page_length) to return long
+//! pages in parts, each with a continuation token for the next. Local
+//! fetches respect robots.txt and per-domain rate limits, and pages are
+//! cached (see [`Politeness`](crate::common::Politeness)):
+//!
+//! ```rust
+//! use serdes_ai_tools::builtin::WebFetchTool;
+//!
+//! let tool = WebFetchTool::builder()
+//!     .markdown(true)
+//!     .max_page_length(8_000)
+//!     .build();
+//!
+//! let page = tool
+//!     .process_page(
+//!         "https://example.com/",
+//!         Some("text/html"),
+//!         "<title>Hi</title><p>Hello <a href=\"/a\">there</a></p>",
+//!         None,
+//!     )
+//!     .unwrap();
+//! assert_eq!(page.content, "Hello [there](https://example.com/a)");
+//! ```
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashSet;
 
+use super::html::{paginate, HtmlConverter};
+
 /// Errors that can occur during web fetch configuration.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WebFetchError {
@@ -47,6 +79,8 @@ pub enum WebFetchError {
         /// Maximum allowed uses.
         max: usize,
     },
+    /// The continuation token does not belong to the page.
+    InvalidContinuation(String),
 }
 
 impl std::fmt::Display for WebFetchError {
@@ -67,6 +101,9 @@ impl std::fmt::Display for WebFetchError {
             Self::MaxUsesExceeded { current, max } => {
                 write!(f, "Maximum uses exceeded: {} of {} allowed", current, max)
             }
+            Self::InvalidContinuation(token) => {
+                write!(f, "Invalid continuation token: {}", token)
+            }
         }
     }
 }
@@ -100,6 +137,15 @@ pub struct WebFetchConfig {
     /// If None, provider default is used.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_content_tokens: Option<usize>,
+
+    /// Whether locally fetched HTML is converted to markdown.
+    #[serde(default)]
+    pub markdown: bool,
+
+    /// Maximum length in bytes of a locally fetched page.
+    /// Longer pages are returned in parts with a continuation token.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_page_length: Option<usize>,
 }
 
 impl WebFetchConfig {
@@ -194,6 +240,20 @@ impl WebFetchConfig {
         self
     }
 
+    /// Enable or disable converting locally fetched HTML to markdown.
+    #[must_use]
+    pub fn markdown(mut self, enable: bool) -> Self {
+        self.markdown = enable;
+        self
+    }
+
+    /// Set the maximum length of a locally fetched page.
+    #[must_use]
+    pub fn max_page_length(mut self, max: usize) -> Self {
+        self.max_page_length = Some(max);
+        self
+    }
+
     /// Validate the configuration.
     pub fn validate(&self) -> Result<(), WebFetchError> {
         // Check for conflicting domain filters
@@ -297,6 +357,10 @@ pub struct WebFetchTool {
     use_count: usize,
     /// Tool kind identifier.
     kind: String,
+    /// Robots.txt, rate limit and cache state for local fetching.
+    #[cfg(feature = "common-tools")]
+    #[serde(skip)]
+    politeness: std::sync::Arc<crate::common::Politeness>,
 }
 
 impl WebFetchTool {
@@ -306,11 +370,7 @@ impl WebFetchTool {
     /// Create a new web fetch tool with default configuration.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            config: WebFetchConfig::default(),
-            use_count: 0,
-            kind: Self::KIND.to_string(),
-        }
+        Self::with_config(WebFetchConfig::default())
     }
 
     /// Create a builder for the web fetch tool.
@@ -326,6 +386,8 @@ impl WebFetchTool {
             config,
             use_count: 0,
             kind: Self::KIND.to_string(),
+            #[cfg(feature = "common-tools")]
+            politeness: Default::default(),
         }
     }
 
@@ -384,6 +446,16 @@ impl WebFetchTool {
             }
         }
 
+        self.check_domain(url)?;
+
+        // Record use
+        self.use_count += 1;
+
+        Ok(())
+    }
+
+    /// Check a URL against the domain filters, without recording a use.
+    pub fn check_domain(&self, url: &str) -> Result<(), WebFetchError> {
         // Extract domain from URL
         let domain = extract_domain(url);
 
@@ -402,12 +474,45 @@ impl WebFetchTool {
             }
         }
 
-        // Record use
-        self.use_count += 1;
-
         Ok(())
     }
 
+    /// Turn a locally fetched response body into a page for the model.
+    ///
+    /// HTML is converted to markdown if [`WebFetchConfig::markdown`] is set,
+    /// and the result is cut to [`WebFetchConfig::max_page_length`].
+    /// `continuation` is the token returned with the previous part.
+    pub fn process_page(
+        &self,
+        url: &str,
+        content_type: Option<&str>,
+        body: &str,
+        continuation: Option<&str>,
+    ) -> Result<FetchedPage, WebFetchError> {
+        let is_html = content_type.map_or_else(
+            || body.trim_start().starts_with('<'),
+            |t| t.contains("html"),
+        );
+        let (title, text) = if is_html && self.config.markdown {
+            let page = HtmlConverter::new().base_url(url).convert(body);
+            (page.title, page.markdown)
+        } else {
+            (None, body.to_string())
+        };
+
+        let max = self.config.max_page_length.unwrap_or(usize::MAX);
+        let page = paginate(&text, continuation, max).ok_or_else(|| {
+            WebFetchError::InvalidContinuation(continuation.unwrap_or_default().to_string())
+        })?;
+        Ok(FetchedPage {
+            url: url.to_string(),
+            title,
+            content: page.content,
+            continuation: page.continuation,
+            total_length: page.total_len,
+        })
+    }
+
     /// Reset the use counter.
     pub fn reset_use_count(&mut self) {
         self.use_count = 0;
@@ -515,6 +620,129 @@ impl PartialEq for WebFetchTool {
     }
 }
 
+/// A locally fetched page, or one part of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchedPage {
+    /// The fetched URL.
+    pub url: String,
+    /// Page title, for HTML converted to markdown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Page content, or this part of it.
+    pub content: String,
+    /// Token to fetch the next part, if the page continues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation: Option<String>,
+    /// Length of the whole page in bytes.
+    pub total_length: usize,
+}
+
+#[cfg(feature = "common-tools")]
+mod local {
+    use super::{FetchedPage, WebFetchTool};
+    use crate::common::Politeness;
+    use crate::{
+        RunContext, SchemaBuilder, Tool, ToolDefinition, ToolError, ToolResult, ToolReturn,
+    };
+    use async_trait::async_trait;
+    use serde_json::Value as JsonValue;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+    impl WebFetchTool {
+        /// Share robots.txt rules, rate limits and the page cache with other
+        /// web tools.
+        #[must_use]
+        pub fn with_politeness(mut self, politeness: Arc<Politeness>) -> Self {
+            self.politeness = politeness;
+            self
+        }
+
+        /// Fetch a page over HTTP and process it with
+        /// [`process_page`](Self::process_page).
+        ///
+        /// The fetch respects the site's robots.txt and rate limits, and
+        /// cached pages are reused, so fetching the next part of a page
+        /// doesn't request it again.
+        pub async fn fetch(
+            &self,
+            url: &str,
+            continuation: Option<&str>,
+        ) -> Result<FetchedPage, ToolError> {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(ToolError::model_retry(format!(
+                    "Only http and https URLs can be fetched: {}",
+                    url
+                )));
+            }
+            self.check_domain(url)
+                .map_err(|e| ToolError::model_retry(e.to_string()))?;
+
+            let client = reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .build()
+                .unwrap_or_default();
+            let response = self.politeness.fetch_page(&client, url).await?;
+            if let Some(t) = response.content_type.as_deref() {
+                if !(t.starts_with("text/") || t.contains("json") || t.contains("xml")) {
+                    return Err(ToolError::execution_failed(format!(
+                        "Cannot read content of type {} from {}",
+                        t, url
+                    )));
+                }
+            }
+            self.process_page(
+                &response.url,
+                response.content_type.as_deref(),
+                &response.body,
+                continuation,
+            )
+            .map_err(|e| ToolError::model_retry(e.to_string()))
+        }
+    }
+
+    #[async_trait]
+    impl<Deps: Send + Sync> Tool<Deps> for WebFetchTool {
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition::new(
+                WebFetchTool::KIND,
+                "Fetch the content of a web page. Long pages are returned in parts; pass \
+                 the returned continuation token to get the next part.",
+            )
+            .with_parameters(
+                SchemaBuilder::new()
+                    .string("url", "The http or https URL to fetch", true)
+                    .string(
+                        "continuation",
+                        "Continuation token from the previous part of the page",
+                        false,
+                    )
+                    .build()
+                    .expect("SchemaBuilder JSON serialization failed"),
+            )
+        }
+
+        async fn call(&self, _ctx: &RunContext<Deps>, args: JsonValue) -> ToolResult {
+            let url = args["url"].as_str().ok_or_else(|| {
+                ToolError::validation_error(
+                    WebFetchTool::KIND,
+                    Some("url".to_string()),
+                    "Missing required 'url' parameter",
+                )
+            })?;
+            let page = self
+                .fetch(url.trim(), args["continuation"].as_str())
+                .await?;
+            Ok(ToolReturn::json(
+                serde_json::to_value(page)
+                    .map_err(|e| ToolError::execution_failed(e.to_string()))?,
+            ))
+        }
+    }
+}
+
 /// Builder for `WebFetchTool`.
 #[derive(Debug, Clone, Default)]
 pub struct WebFetchToolBuilder {
@@ -593,6 +821,20 @@ impl WebFetchToolBuilder {
         self
     }
 
+    /// Enable or disable converting locally fetched HTML to markdown.
+    #[must_use]
+    pub fn markdown(mut self, enable: bool) -> Self {
+        self.config.markdown = enable;
+        self
+    }
+
+    /// Set the maximum length of a locally fetched page.
+    #[must_use]
+    pub fn max_page_length(mut self, max: usize) -> Self {
+        self.config.max_page_length = Some(max);
+        self
+    }
+
     /// Build the WebFetchTool.
     ///
     /// # Panics
@@ -1043,4 +1285,41 @@ mod tests {
         assert_eq!(tool1, tool2);
         assert_ne!(tool1, tool3);
     }
+
+    #[test]
+    fn test_web_fetch_process_page() {
+        let html = "<html><head><title>Guide</title></head><body><nav>Menu</nav>\
+                    <main><p>One two three.</p><p>Four five six.</p></main></body></html>";
+
+        let raw = WebFetchTool::new()
+            .process_page("https://example.com/", Some("text/html"), html, None)
+            .unwrap();
+        assert_eq!(raw.content, html);
+        assert_eq!(raw.title, None);
+
+        let tool = WebFetchTool::builder()
+            .markdown(true)
+            .max_page_length(20)
+            .build();
+        let first = tool
+            .process_page("https://example.com/", Some("text/html"), html, None)
+            .unwrap();
+        assert_eq!(first.title.as_deref(), Some("Guide"));
+        assert_eq!(first.content, "One two three.");
+        let second = tool
+            .process_page(
+                "https://example.com/",
+                Some("text/html"),
+                html,
+                first.continuation.as_deref(),
+            )
+            .unwrap();
+        assert_eq!(second.content, "Four five six.");
+        assert_eq!(second.continuation, None);
+
+        let err = tool
+            .process_page("https://example.com/", None, html, Some("999"))
+            .unwrap_err();
+        assert_eq!(err, WebFetchError::InvalidContinuation("999".to_string()));
+    }
 }
@@ -24,7 +24,9 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::sync::Arc;
 
+use super::Politeness;
 use crate::{
     definition::ToolDefinition,
     return_types::{ToolResult, ToolReturn},
@@ -186,11 +188,13 @@ enum DdgRelatedTopic {
 /// DuckDuckGo search tool.
 ///
 /// Uses DuckDuckGo's Instant Answer API to provide search results.
-/// No API key required.
+/// No API key required. Requests go through a [`Politeness`], which rate
+/// limits them and caches responses.
 #[derive(Debug, Clone)]
 pub struct DuckDuckGoTool {
     config: DuckDuckGoConfig,
     client: Client,
+    politeness: Arc<Politeness>,
 }
 
 impl DuckDuckGoTool {
@@ -208,7 +212,11 @@ impl DuckDuckGoTool {
             .build()
             .unwrap_or_default();
 
-        Self { config, client }
+        Self {
+            config,
+            client,
+            politeness: Arc::default(),
+        }
     }
 
     /// Set the maximum number of results.
@@ -218,6 +226,13 @@ impl DuckDuckGoTool {
         self
     }
 
+    /// Share rate limits and the response cache with other web tools.
+    #[must_use]
+    pub fn with_politeness(mut self, politeness: Arc<Politeness>) -> Self {
+        self.politeness = politeness;
+        self
+    }
+
     /// Execute the search.
     async fn search(&self, query: &str) -> Result<Vec<DuckDuckGoResult>, ToolError> {
         let url = format!(
@@ -225,24 +240,9 @@ impl DuckDuckGoTool {
             urlencoding::encode(query)
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .header("User-Agent", "serdes-ai-tools/0.1")
-            .send()
-            .await
-            .map_err(|e| ToolError::execution_failed(format!("HTTP request failed: {e}")))?;
-
-        if !response.status().is_success() {
-            return Err(ToolError::execution_failed(format!(
-                "DuckDuckGo API returned status: {}",
-                response.status()
-            )));
-        }
+        let response = self.politeness.fetch_api(&self.client, &url).await?;
 
-        let ddg_response: DdgResponse = response
-            .json()
-            .await
+        let ddg_response: DdgResponse = serde_json::from_str(&response.body)
             .map_err(|e| ToolError::execution_failed(format!("Failed to parse response: {e}")))?;
 
         let mut results = Vec::new();
@@ -8,6 +8,10 @@
 //! - **[`DuckDuckGoTool`]**: Web search using DuckDuckGo's Instant Answer API
 //! - **[`TavilyTool`]**: AI-optimized web search using Tavily's API
 //!
+//! [`Politeness`] adds robots.txt checks, per-domain rate limits and a
+//! shared response cache to `DuckDuckGoTool` and the local fetching of
+//! [`WebFetchTool`](crate::builtin::WebFetchTool).
+//!
 //! ## Feature Flag
 //!
 //! These tools require the `common-tools` feature to be enabled:
@@ -30,7 +34,9 @@
 //! ```
 
 mod duckduckgo;
+mod politeness;
 mod tavily;
 
 pub use duckduckgo::{DuckDuckGoConfig, DuckDuckGoResult, DuckDuckGoTool};
+pub use politeness::{CachedResponse, Politeness, PolitenessConfig, RobotsRules};
 pub use tavily::{TavilyConfig, TavilyResult, TavilySearchDepth, TavilyTool};
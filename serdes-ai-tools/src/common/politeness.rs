@@ -0,0 +1,571 @@
+//! Politeness controls for web tools.
+//!
+//! Agents can issue many requests in a short time. [`Politeness`] keeps web
+//! tools well-behaved towards the sites they access:
+//!
+//! - **robots.txt**: page fetches are checked against the site's robots.txt
+//!   (can be turned off with [`PolitenessConfig::with_robots_txt`]).
+//! - **Rate limiting**: requests to the same host are spaced at least
+//!   [`PolitenessConfig::min_interval`] apart, or the site's `Crawl-delay`
+//!   if it is longer.
+//! - **Caching**: successful responses are cached for
+//!   [`PolitenessConfig::cache_ttl`], so repeated fetches of the same URL,
+//!   e.g. for the next part of a long page, don't hit the site again.
+//!
+//! Share one instance between tools so the limits apply across them:
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use serdes_ai_tools::builtin::WebFetchTool;
+//! use serdes_ai_tools::common::{DuckDuckGoTool, Politeness, PolitenessConfig};
+//!
+//! let politeness = Arc::new(Politeness::new(
+//!     PolitenessConfig::new().with_min_interval(std::time::Duration::from_secs(2)),
+//! ));
+//! let fetch = WebFetchTool::builder().markdown(true).build().with_politeness(politeness.clone());
+//! let search = DuckDuckGoTool::new().with_politeness(politeness);
+//! ```
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::ToolError;
+
+/// Configuration of [`Politeness`].
+#[derive(Debug, Clone)]
+pub struct PolitenessConfig {
+    /// Whether page fetches are checked against robots.txt.
+    pub respect_robots_txt: bool,
+    /// User agent sent with requests and matched against robots.txt groups.
+    pub user_agent: String,
+    /// Minimum time between requests to the same host.
+    pub min_interval: Duration,
+    /// How long fetched responses are cached. Zero disables caching.
+    pub cache_ttl: Duration,
+    /// Maximum number of cached responses.
+    pub cache_capacity: usize,
+    /// How long a site's robots.txt is cached.
+    pub robots_ttl: Duration,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            respect_robots_txt: true,
+            user_agent: "serdes-ai-tools/0.1".to_string(),
+            min_interval: Duration::from_secs(1),
+            cache_ttl: Duration::from_secs(300),
+            cache_capacity: 100,
+            robots_ttl: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl PolitenessConfig {
+    /// Create a new configuration with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable robots.txt checking.
+    #[must_use]
+    pub fn with_robots_txt(mut self, respect: bool) -> Self {
+        self.respect_robots_txt = respect;
+        self
+    }
+
+    /// Set the user agent.
+    #[must_use]
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Set the minimum time between requests to the same host.
+    #[must_use]
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Set how long responses are cached.
+    #[must_use]
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Set the maximum number of cached responses.
+    #[must_use]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache_capacity = capacity;
+        self
+    }
+}
+
+/// A successful response, possibly from the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    /// URL of the response after redirects.
+    pub url: String,
+    /// Lowercased `Content-Type` header, if any.
+    pub content_type: Option<String>,
+    /// Response body.
+    pub body: String,
+}
+
+/// Rules of a robots.txt that apply to one user agent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    /// `(allow, pattern)` pairs.
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    /// Rules allowing everything.
+    #[must_use]
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Parse a robots.txt, keeping the group for `user_agent`.
+    ///
+    /// The group naming the agent's product token (the part before `/`)
+    /// is used, falling back to the `*` group.
+    #[must_use]
+    pub fn parse(robots_txt: &str, user_agent: &str) -> Self {
+        let token = user_agent
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        let mut specific: Option<Self> = None;
+        let mut wildcard: Option<Self> = None;
+
+        // Agents of the group being read, and whether its rules have started
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        let mut group = Self::default();
+        let mut finish = |agents: &[String], group: Self| {
+            if !token.is_empty() && agents.contains(&token) {
+                specific.get_or_insert(group);
+            } else if agents.iter().any(|a| a == "*") {
+                wildcard.get_or_insert(group);
+            }
+        };
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        finish(&agents, std::mem::take(&mut group));
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_ascii_lowercase());
+                }
+                "allow" | "disallow" if !agents.is_empty() => {
+                    in_rules = true;
+                    // An empty Disallow allows everything
+                    if !value.is_empty() {
+                        let allow = key.trim().eq_ignore_ascii_case("allow");
+                        group.rules.push((allow, value.to_string()));
+                    }
+                }
+                "crawl-delay" if !agents.is_empty() => {
+                    in_rules = true;
+                    group.crawl_delay = value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| d.is_finite() && *d >= 0.0)
+                        .map(Duration::from_secs_f64);
+                }
+                _ => {}
+            }
+        }
+        if !agents.is_empty() {
+            finish(&agents, group);
+        }
+        specific.or(wildcard).unwrap_or_default()
+    }
+
+    /// Check if a path (with query) may be fetched.
+    ///
+    /// The longest matching rule wins; on a tie, `Allow` wins.
+    #[must_use]
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .map_or(true, |(allow, _)| *allow)
+    }
+
+    /// The `Crawl-delay` of the group, if any.
+    #[must_use]
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// Match a robots.txt path pattern with `*` wildcards and a `$` end anchor.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// Split a URL into its origin (`scheme://host[:port]`), host and path.
+fn split_url(url: &str) -> Option<(&str, String, &str)> {
+    let scheme_end = url.find("://")? + 3;
+    let authority_end = url[scheme_end..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |i| scheme_end + i);
+    let origin = &url[..authority_end];
+    let host = url[scheme_end..authority_end]
+        .rsplit('@')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let path = url[authority_end..].split('#').next().unwrap_or_default();
+    Some((origin, host, if path.is_empty() { "/" } else { path }))
+}
+
+struct CacheEntry {
+    response: CachedResponse,
+    fetched_at: Instant,
+}
+
+/// Shared robots.txt, rate limit and cache state for web tools.
+pub struct Politeness {
+    config: PolitenessConfig,
+    robots: Mutex<HashMap<String, (RobotsRules, Instant)>>,
+    next_request: Mutex<HashMap<String, Instant>>,
+    cache: Mutex<IndexMap<String, CacheEntry>>,
+}
+
+impl Politeness {
+    /// Create with a configuration.
+    #[must_use]
+    pub fn new(config: PolitenessConfig) -> Self {
+        Self {
+            config,
+            robots: Mutex::new(HashMap::new()),
+            next_request: Mutex::new(HashMap::new()),
+            cache: Mutex::new(IndexMap::new()),
+        }
+    }
+
+    /// Get the configuration.
+    #[must_use]
+    pub fn config(&self) -> &PolitenessConfig {
+        &self.config
+    }
+
+    /// Fetch a page, checking robots.txt first.
+    pub async fn fetch_page(
+        &self,
+        client: &Client,
+        url: &str,
+    ) -> Result<CachedResponse, ToolError> {
+        self.fetch(client, url, self.config.respect_robots_txt)
+            .await
+    }
+
+    /// Fetch an API endpoint; robots.txt is not checked, since API calls are
+    /// not crawling.
+    pub async fn fetch_api(&self, client: &Client, url: &str) -> Result<CachedResponse, ToolError> {
+        self.fetch(client, url, false).await
+    }
+
+    /// Get a cached response, if it is fresh.
+    #[must_use]
+    pub fn cached(&self, url: &str) -> Option<CachedResponse> {
+        let mut cache = self.cache.lock();
+        let fresh = cache
+            .get(url)
+            .map(|entry| entry.fetched_at.elapsed() < self.config.cache_ttl)?;
+        if fresh {
+            cache.get(url).map(|entry| entry.response.clone())
+        } else {
+            cache.shift_remove(url);
+            None
+        }
+    }
+
+    /// Clear cached responses and robots.txt files.
+    pub fn clear_cache(&self) {
+        self.cache.lock().clear();
+        self.robots.lock().clear();
+    }
+
+    /// Install robots.txt rules for a host, e.g. from a local copy.
+    pub fn set_robots(&self, host: &str, rules: RobotsRules) {
+        self.robots
+            .lock()
+            .insert(host.to_ascii_lowercase(), (rules, Instant::now()));
+    }
+
+    /// Check a URL against the host's robots.txt, fetching it if needed.
+    pub async fn is_allowed(&self, client: &Client, url: &str) -> Result<bool, ToolError> {
+        let (origin, host, path) = split_url(url)
+            .ok_or_else(|| ToolError::model_retry(format!("Invalid URL: {}", url)))?;
+        let rules = self.robots_for(client, origin, &host).await;
+        Ok(rules.is_allowed(path))
+    }
+
+    async fn fetch(
+        &self,
+        client: &Client,
+        url: &str,
+        check_robots: bool,
+    ) -> Result<CachedResponse, ToolError> {
+        if let Some(response) = self.cached(url) {
+            return Ok(response);
+        }
+        let (origin, host, path) = split_url(url)
+            .ok_or_else(|| ToolError::model_retry(format!("Invalid URL: {}", url)))?;
+
+        let mut delay = None;
+        if check_robots {
+            let rules = self.robots_for(client, origin, &host).await;
+            if !rules.is_allowed(path) {
+                return Err(ToolError::model_retry(format!(
+                    "{} disallows fetching {} in its robots.txt",
+                    host, path
+                )));
+            }
+            delay = rules.crawl_delay();
+        }
+
+        self.wait_turn(&host, delay).await;
+        let response = client
+            .get(url)
+            .header("User-Agent", &self.config.user_agent)
+            .send()
+            .await
+            .map_err(|e| ToolError::execution_failed(format!("HTTP request failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(ToolError::execution_failed(format!(
+                "Fetching {} returned status: {}",
+                url,
+                response.status()
+            )));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_ascii_lowercase);
+        let final_url = response.url().to_string();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ToolError::execution_failed(format!("Failed to read body: {e}")))?;
+
+        let response = CachedResponse {
+            url: final_url,
+            content_type,
+            body,
+        };
+        self.store(url, response.clone());
+        Ok(response)
+    }
+
+    fn store(&self, url: &str, response: CachedResponse) {
+        if self.config.cache_ttl.is_zero() || self.config.cache_capacity == 0 {
+            return;
+        }
+        let mut cache = self.cache.lock();
+        cache.shift_remove(url);
+        while cache.len() >= self.config.cache_capacity {
+            cache.shift_remove_index(0);
+        }
+        cache.insert(
+            url.to_string(),
+            CacheEntry {
+                response,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Wait until a request to `host` is allowed, and reserve the slot.
+    async fn wait_turn(&self, host: &str, crawl_delay: Option<Duration>) {
+        let interval = crawl_delay.map_or(self.config.min_interval, |d| {
+            d.max(self.config.min_interval)
+        });
+        let slot = {
+            let mut next = self.next_request.lock();
+            let now = Instant::now();
+            let slot = next.get(host).map_or(now, |at| (*at).max(now));
+            next.insert(host.to_string(), slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Get the robots.txt rules of a host.
+    ///
+    /// A robots.txt that is missing or can't be fetched allows everything.
+    async fn robots_for(&self, client: &Client, origin: &str, host: &str) -> RobotsRules {
+        if let Some((rules, fetched_at)) = self.robots.lock().get(host) {
+            if fetched_at.elapsed() < self.config.robots_ttl {
+                return rules.clone();
+            }
+        }
+
+        self.wait_turn(host, None).await;
+        let response = client
+            .get(format!("{}/robots.txt", origin))
+            .header("User-Agent", &self.config.user_agent)
+            .send()
+            .await;
+        let rules = match response {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(text) => RobotsRules::parse(&text, &self.config.user_agent),
+                Err(_) => RobotsRules::allow_all(),
+            },
+            _ => RobotsRules::allow_all(),
+        };
+        self.set_robots(host, rules.clone());
+        rules
+    }
+}
+
+impl Default for Politeness {
+    fn default() -> Self {
+        Self::new(PolitenessConfig::default())
+    }
+}
+
+impl std::fmt::Debug for Politeness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Politeness")
+            .field("config", &self.config)
+            .field("cached", &self.cache.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/public
+Disallow: /*.pdf$
+
+User-agent: serdes-ai-tools
+User-agent: other-bot
+Disallow: /search
+Crawl-delay: 2.5
+";
+
+    #[test]
+    fn test_robots_rules() {
+        let any = RobotsRules::parse(ROBOTS, "curl/8.0");
+        assert!(any.is_allowed("/"));
+        assert!(!any.is_allowed("/private/data"));
+        assert!(any.is_allowed("/private/public/page"));
+        assert!(!any.is_allowed("/docs/report.pdf"));
+        assert!(any.is_allowed("/docs/report.pdf?download=1"));
+        assert!(any.is_allowed("/search"));
+        assert_eq!(any.crawl_delay(), None);
+
+        let ours = RobotsRules::parse(ROBOTS, "serdes-ai-tools/0.1");
+        assert!(!ours.is_allowed("/search?q=rust"));
+        assert!(ours.is_allowed("/private/data"));
+        assert_eq!(ours.crawl_delay(), Some(Duration::from_millis(2500)));
+
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n", "x").is_allowed("/a"));
+        assert!(!RobotsRules::parse("User-agent: *\nDisallow: /\n", "x").is_allowed("/a"));
+    }
+
+    #[test]
+    fn test_split_url() {
+        assert_eq!(
+            split_url("https://Example.com:8080/a/b?c=1#frag"),
+            Some((
+                "https://Example.com:8080",
+                "example.com:8080".to_string(),
+                "/a/b?c=1"
+            ))
+        );
+        assert_eq!(
+            split_url("https://example.com"),
+            Some(("https://example.com", "example.com".to_string(), "/"))
+        );
+        assert_eq!(split_url("example.com/a"), None);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_and_cache() {
+        let interval = Duration::from_millis(40);
+        let politeness = Politeness::new(
+            PolitenessConfig::new()
+                .with_min_interval(interval)
+                .with_cache_ttl(interval)
+                .with_cache_capacity(1),
+        );
+
+        let start = Instant::now();
+        politeness.wait_turn("example.com", None).await;
+        politeness.wait_turn("other.org", None).await;
+        assert!(start.elapsed() < interval);
+        politeness.wait_turn("example.com", None).await;
+        assert!(start.elapsed() >= interval);
+        politeness
+            .wait_turn("example.com", Some(interval * 2))
+            .await;
+        assert!(start.elapsed() >= interval * 2);
+
+        let response = |body: &str| CachedResponse {
+            url: "https://example.com/".to_string(),
+            content_type: None,
+            body: body.to_string(),
+        };
+        politeness.store("https://example.com/a", response("a"));
+        assert_eq!(
+            politeness.cached("https://example.com/a"),
+            Some(response("a"))
+        );
+        politeness.store("https://example.com/b", response("b"));
+        assert_eq!(politeness.cached("https://example.com/a"), None);
+        tokio::time::sleep(interval).await;
+        assert_eq!(politeness.cached("https://example.com/b"), None);
+    }
+}
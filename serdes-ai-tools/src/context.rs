@@ -4,6 +4,7 @@
 //! to tools during execution, including dependencies, model info, and usage tracking.
 
 use chrono::{DateTime, Utc};
+use serdes_ai_core::now_utc;
 use serdes_ai_core::{identifier::generate_run_id, ModelSettings, RunUsage};
 use std::sync::Arc;
 
@@ -81,7 +82,7 @@ impl<Deps> RunContext<Deps> {
         Self {
             deps: Arc::new(deps),
             run_id: generate_run_id(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             retry_count: 0,
             max_retries: 3,
             tool_name: None,
@@ -100,7 +101,7 @@ impl<Deps> RunContext<Deps> {
         Self {
             deps,
             run_id: generate_run_id(),
-            start_time: Utc::now(),
+            start_time: now_utc(),
             retry_count: 0,
             max_retries: 3,
             tool_name: None,
@@ -174,7 +175,7 @@ impl<Deps> RunContext<Deps> {
     /// Get elapsed time since start.
     #[must_use]
     pub fn elapsed(&self) -> chrono::Duration {
-        Utc::now() - self.start_time
+        now_utc() - self.start_time
     }
 
     /// Get elapsed time in seconds.
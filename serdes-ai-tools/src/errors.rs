@@ -56,6 +56,15 @@ pub enum ToolError {
         args: serde_json::Value,
     },
 
+    /// The current principal lacks scopes the tool requires.
+    #[error("Permission denied for tool '{tool_name}': missing scopes {missing:?}")]
+    PermissionDenied {
+        /// Name of the tool.
+        tool_name: String,
+        /// Required scopes the principal lacks.
+        missing: Vec<String>,
+    },
+
     /// Tool call was deferred for later execution.
     #[error("Tool call '{tool_name}' deferred")]
     CallDeferred {
@@ -106,6 +115,7 @@ impl ToolError {
             Self::ValidationFailed { .. } => false,
             Self::NotFound(_) => false,
             Self::ApprovalRequired { .. } => false,
+            Self::PermissionDenied { .. } => false,
             Self::CallDeferred { .. } => false,
             Self::Cancelled => false,
             Self::ToolReturnedError(_) => false,
@@ -178,6 +188,15 @@ impl ToolError {
         }
     }
 
+    /// Create a permission denied error.
+    #[must_use]
+    pub fn permission_denied(tool_name: impl Into<String>, missing: Vec<String>) -> Self {
+        Self::PermissionDenied {
+            tool_name: tool_name.into(),
+            missing,
+        }
+    }
+
     /// Create a call deferred error.
     #[must_use]
     pub fn call_deferred(tool_name: impl Into<String>, args: serde_json::Value) -> Self {
@@ -205,6 +224,12 @@ impl ToolError {
         matches!(self, Self::ApprovalRequired { .. })
     }
 
+    /// Check if this is a permission denied error.
+    #[must_use]
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, Self::PermissionDenied { .. })
+    }
+
     /// Check if this is a call deferred error.
     #[must_use]
     pub fn is_call_deferred(&self) -> bool {
@@ -216,6 +241,22 @@ impl ToolError {
     pub fn is_model_retry(&self) -> bool {
         matches!(self, Self::ModelRetry(_))
     }
+
+    /// Check if the tool couldn't be reached, as opposed to rejecting the call.
+    ///
+    /// Timeouts and retryable execution failures (such as the transport
+    /// errors of a remote tool server) count as unavailable.
+    #[must_use]
+    pub fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout(_)
+                | Self::ExecutionFailed {
+                    retryable: true,
+                    ..
+                }
+        )
+    }
 }
 
 impl From<String> for ToolError {
@@ -278,6 +319,7 @@ impl From<&ToolError> for ToolErrorInfo {
             ToolError::NotFound(_) => "not_found",
             ToolError::ModelRetry(_) => "model_retry",
             ToolError::ApprovalRequired { .. } => "approval_required",
+            ToolError::PermissionDenied { .. } => "permission_denied",
             ToolError::CallDeferred { .. } => "call_deferred",
             ToolError::Timeout(_) => "timeout",
             ToolError::ValidationFailed { .. } => "validation_failed",
@@ -318,6 +360,14 @@ mod tests {
         assert!(err.is_retryable());
     }
 
+    #[test]
+    fn test_unavailable() {
+        assert!(ToolError::retryable("connection reset").is_unavailable());
+        assert!(ToolError::timeout(Duration::from_secs(5)).is_unavailable());
+        assert!(!ToolError::execution_failed("bad input").is_unavailable());
+        assert!(!ToolError::model_retry("try again").is_unavailable());
+    }
+
     #[test]
     fn test_not_found() {
         let err = ToolError::not_found("unknown_tool");
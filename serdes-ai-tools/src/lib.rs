@@ -12,6 +12,8 @@
 //! - **[`ToolDefinition`]**: JSON Schema-based tool descriptions for LLMs
 //! - **[`RunContext`]**: Execution context with dependencies passed to tools
 //! - **[`ToolReturn`]**: Return values from tool execution
+//! - **[`Permissions`]**: Scopes a tool requires, checked against the
+//!   current principal's [`PermissionPolicy`]
 //!
 //! ## Defining Tools
 //!
@@ -104,9 +106,11 @@ pub mod context;
 pub mod deferred;
 pub mod definition;
 pub mod errors;
+pub mod permissions;
 pub mod registry;
 pub mod return_types;
 pub mod schema;
+pub mod stats;
 pub mod tool;
 
 // Re-export core types
@@ -117,9 +121,13 @@ pub use deferred::{
 };
 pub use definition::{ObjectJsonSchema, ToolDefinition};
 pub use errors::{ToolError, ToolErrorInfo};
+pub use permissions::{
+    HasPermissionPolicy, PermissionDecision, PermissionPolicy, Permissions, Principal,
+};
 pub use registry::{ToolProvider, ToolRegistry};
 pub use return_types::{IntoToolReturn, SerializableToolResult, ToolResult, ToolReturn};
 pub use schema::{PropertySchema, SchemaBuilder};
+pub use stats::{ToolCallRecord, ToolCallStats, ToolStats};
 pub use tool::{BoxedTool, FunctionTool, SyncFunctionTool, Tool};
 
 // Delete old files if they exist
@@ -0,0 +1,269 @@
+//! Declarative tool permissions.
+//!
+//! Tools declare the scopes they need, such as `"fs:read"` or
+//! `"net:fetch"`, as [`Permissions`]. Before a tool runs, the
+//! [`PermissionPolicy`] of the current principal decides whether the call
+//! is allowed, needs approval, or is denied. The policy comes from the run's
+//! dependencies through [`HasPermissionPolicy`], so one agent can serve users
+//! with different rights.
+//!
+//! ```rust
+//! use serdes_ai_tools::permissions::{PermissionDecision, PermissionPolicy, Permissions, Principal};
+//!
+//! let alice = Principal::new("alice", Permissions::new().scope("fs:*"));
+//! let read = Permissions::new().scope("fs:read");
+//! let fetch = Permissions::new().scope("net:fetch");
+//!
+//! assert_eq!(alice.check("read_file", &read), PermissionDecision::Allow);
+//! assert!(matches!(alice.check("fetch_url", &fetch), PermissionDecision::Deny { .. }));
+//! ```
+
+use crate::errors::ToolError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// A set of permission scopes.
+///
+/// Scopes are free-form strings, conventionally `"<area>:<action>"`. When
+/// granting, `"<area>:*"` covers every action in an area and `"*"` covers
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Permissions {
+    scopes: BTreeSet<String>,
+}
+
+impl Permissions {
+    /// Create an empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a scope.
+    #[must_use]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.insert(scope.into());
+        self
+    }
+
+    /// Add all scopes of another set.
+    #[must_use]
+    pub fn union(mut self, other: &Permissions) -> Self {
+        self.scopes.extend(other.scopes.iter().cloned());
+        self
+    }
+
+    /// Check if the set is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scopes.is_empty()
+    }
+
+    /// Iterate over the scopes.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.scopes.iter().map(String::as_str)
+    }
+
+    /// Check whether these granted scopes cover `scope`, wildcards included.
+    #[must_use]
+    pub fn grants(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| {
+            granted == "*"
+                || granted == scope
+                || granted
+                    .strip_suffix('*')
+                    .is_some_and(|prefix| prefix.ends_with(':') && scope.starts_with(prefix))
+        })
+    }
+
+    /// Get the scopes of `required` that these granted scopes don't cover.
+    #[must_use]
+    pub fn missing(&self, required: &Permissions) -> Vec<String> {
+        required
+            .iter()
+            .filter(|scope| !self.grants(scope))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Permissions {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self {
+            scopes: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Outcome of a permission check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionDecision {
+    /// Run the tool.
+    Allow,
+    /// Ask for approval instead of running the tool.
+    RequireApproval,
+    /// Refuse the call.
+    Deny {
+        /// Required scopes the principal lacks.
+        missing: Vec<String>,
+    },
+}
+
+/// Decides whether the current principal may call a tool.
+pub trait PermissionPolicy: Send + Sync {
+    /// Decide on a call to `tool_name`, which requires `required`.
+    fn check(&self, tool_name: &str, required: &Permissions) -> PermissionDecision;
+}
+
+/// A user or service with a fixed set of granted scopes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Principal {
+    /// Identifier of the principal.
+    pub id: String,
+    /// Granted scopes.
+    pub granted: Permissions,
+    /// Whether calls needing missing scopes go to approval rather than being
+    /// denied.
+    #[serde(default)]
+    pub approve_missing: bool,
+}
+
+impl Principal {
+    /// Create a principal with the given granted scopes.
+    #[must_use]
+    pub fn new(id: impl Into<String>, granted: Permissions) -> Self {
+        Self {
+            id: id.into(),
+            granted,
+            approve_missing: false,
+        }
+    }
+
+    /// Route calls needing missing scopes to approval instead of denying
+    /// them.
+    #[must_use]
+    pub fn approve_missing(mut self) -> Self {
+        self.approve_missing = true;
+        self
+    }
+}
+
+impl PermissionPolicy for Principal {
+    fn check(&self, _tool_name: &str, required: &Permissions) -> PermissionDecision {
+        let missing = self.granted.missing(required);
+        if missing.is_empty() {
+            PermissionDecision::Allow
+        } else if self.approve_missing {
+            PermissionDecision::RequireApproval
+        } else {
+            PermissionDecision::Deny { missing }
+        }
+    }
+}
+
+/// Run dependencies that carry the current principal's policy.
+pub trait HasPermissionPolicy {
+    /// Get the policy, or `None` if the run has no principal.
+    ///
+    /// Without a policy only tools that require no scopes may run.
+    fn permission_policy(&self) -> Option<&dyn PermissionPolicy>;
+}
+
+impl HasPermissionPolicy for Principal {
+    fn permission_policy(&self) -> Option<&dyn PermissionPolicy> {
+        Some(self)
+    }
+}
+
+impl<T: HasPermissionPolicy + ?Sized> HasPermissionPolicy for Arc<T> {
+    fn permission_policy(&self) -> Option<&dyn PermissionPolicy> {
+        (**self).permission_policy()
+    }
+}
+
+/// Check a tool call against the principal's policy.
+///
+/// Returns [`ToolError::ApprovalRequired`] or [`ToolError::PermissionDenied`]
+/// unless the call is allowed.
+pub fn authorize(
+    policy: Option<&dyn PermissionPolicy>,
+    tool_name: &str,
+    required: &Permissions,
+    args: &JsonValue,
+) -> Result<(), ToolError> {
+    let decision = match policy {
+        Some(policy) => policy.check(tool_name, required),
+        None if required.is_empty() => PermissionDecision::Allow,
+        None => PermissionDecision::Deny {
+            missing: required.iter().map(str::to_string).collect(),
+        },
+    };
+    match decision {
+        PermissionDecision::Allow => Ok(()),
+        PermissionDecision::RequireApproval => {
+            Err(ToolError::approval_required(tool_name, args.clone()))
+        }
+        PermissionDecision::Deny { missing } => {
+            Err(ToolError::permission_denied(tool_name, missing))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grants_wildcards() {
+        let granted: Permissions = ["fs:*", "net:fetch"].into_iter().collect();
+        assert!(granted.grants("fs:read"));
+        assert!(granted.grants("fs:write"));
+        assert!(granted.grants("net:fetch"));
+        assert!(!granted.grants("net:listen"));
+        assert!(!granted.grants("fsx:read"));
+        assert!(Permissions::new().scope("*").grants("anything"));
+
+        let required: Permissions = ["fs:read", "net:listen", "db:write"].into_iter().collect();
+        assert_eq!(granted.missing(&required), ["db:write", "net:listen"]);
+    }
+
+    #[test]
+    fn test_principal_policy() {
+        let required = Permissions::new().scope("net:fetch");
+        let reader = Principal::new("reader", Permissions::new().scope("fs:read"));
+        assert_eq!(
+            reader.check("fetch", &required),
+            PermissionDecision::Deny {
+                missing: vec!["net:fetch".to_string()]
+            }
+        );
+        assert_eq!(
+            reader.clone().approve_missing().check("fetch", &required),
+            PermissionDecision::RequireApproval
+        );
+        assert_eq!(
+            reader.check("noop", &Permissions::new()),
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_authorize() {
+        let required = Permissions::new().scope("fs:write");
+        let args = serde_json::json!({"path": "a.txt"});
+
+        let err = authorize(None, "write_file", &required, &args).unwrap_err();
+        assert!(err.is_permission_denied());
+        assert!(authorize(None, "noop", &Permissions::new(), &args).is_ok());
+
+        let admin = Principal::new("admin", Permissions::new().scope("*"));
+        assert!(authorize(admin.permission_policy(), "write_file", &required, &args).is_ok());
+
+        let guest = Principal::new("guest", Permissions::new()).approve_missing();
+        let err = authorize(guest.permission_policy(), "write_file", &required, &args).unwrap_err();
+        assert!(err.is_approval_required());
+    }
+}
@@ -7,7 +7,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::{
-    definition::ToolDefinition, errors::ToolError, return_types::ToolReturn, tool::Tool, RunContext,
+    definition::ToolDefinition, errors::ToolError, permissions::Permissions,
+    return_types::ToolReturn, tool::Tool, RunContext,
 };
 
 /// Registry of tools that can be called by an agent.
@@ -207,6 +208,12 @@ impl<Deps> ToolRegistry<Deps> {
     pub fn max_retries(&self, name: &str) -> Option<u32> {
         self.tools.get(name).and_then(|t| t.max_retries())
     }
+
+    /// Get the scopes a tool requires.
+    #[must_use]
+    pub fn permissions(&self, name: &str) -> Option<Permissions> {
+        self.tools.get(name).map(|t| t.permissions())
+    }
 }
 
 impl<Deps> Default for ToolRegistry<Deps> {
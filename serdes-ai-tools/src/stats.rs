@@ -0,0 +1,247 @@
+//! Tool call statistics.
+//!
+//! [`ToolStats`] collects the duration, outcome and payload sizes of tool
+//! calls, per tool. With the `metrics` feature, every call is also exported
+//! through the [`metrics`](https://docs.rs/metrics) facade:
+//!
+//! - `serdes_ai_tool_calls_total` (counter, labels `tool`, `status`)
+//! - `serdes_ai_tool_call_duration_seconds` (histogram, label `tool`)
+//! - `serdes_ai_tool_call_args_bytes` (histogram, label `tool`)
+//! - `serdes_ai_tool_call_return_bytes` (histogram, label `tool`)
+//!
+//! Calls slower than the [slow-call threshold](ToolStats::with_slow_threshold)
+//! are logged as warnings.
+
+use crate::{ToolError, ToolReturn};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Aggregated statistics for one tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallStats {
+    /// Number of calls.
+    pub calls: u64,
+    /// Number of failed calls.
+    pub errors: u64,
+    /// Total time spent in the tool.
+    pub total_duration: Duration,
+    /// Duration of the slowest call.
+    pub max_duration: Duration,
+    /// Total size of the call arguments in bytes.
+    pub args_bytes: u64,
+    /// Total size of the returned content in bytes.
+    pub return_bytes: u64,
+}
+
+impl ToolCallStats {
+    /// Average call duration.
+    #[must_use]
+    pub fn mean_duration(&self) -> Duration {
+        if self.calls == 0 {
+            return Duration::ZERO;
+        }
+        self.total_duration / self.calls as u32
+    }
+
+    /// Fraction of calls that failed.
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        self.errors as f64 / self.calls as f64
+    }
+}
+
+/// A single completed tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallRecord<'a> {
+    /// Tool name.
+    pub tool_name: &'a str,
+    /// Wall-clock duration, including retries.
+    pub duration: Duration,
+    /// Whether the call succeeded.
+    pub success: bool,
+    /// Size of the arguments in bytes.
+    pub args_bytes: usize,
+    /// Size of the returned content in bytes (0 on error).
+    pub return_bytes: usize,
+}
+
+impl<'a> ToolCallRecord<'a> {
+    /// Describe a call from the size of its arguments and its result.
+    ///
+    /// Returns marked as errors count as failed calls.
+    #[must_use]
+    pub fn from_result(
+        tool_name: &'a str,
+        duration: Duration,
+        args_bytes: usize,
+        result: &Result<ToolReturn, ToolError>,
+    ) -> Self {
+        let (success, return_bytes) = match result {
+            Ok(ret) => (!ret.is_error(), ret.content.to_string_content().len()),
+            Err(_) => (false, 0),
+        };
+        Self {
+            tool_name,
+            duration,
+            success,
+            args_bytes,
+            return_bytes,
+        }
+    }
+}
+
+/// Thread-safe collector of per-tool call statistics.
+#[derive(Debug, Default)]
+pub struct ToolStats {
+    stats: RwLock<HashMap<String, ToolCallStats>>,
+    slow_threshold: Option<Duration>,
+}
+
+impl ToolStats {
+    /// Create an empty collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log a warning for calls that take longer than `threshold`.
+    #[must_use]
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// Get the slow-call threshold.
+    #[must_use]
+    pub fn slow_threshold(&self) -> Option<Duration> {
+        self.slow_threshold
+    }
+
+    /// Record a completed tool call.
+    pub fn record(&self, record: ToolCallRecord<'_>) {
+        if let Some(threshold) = self.slow_threshold {
+            if record.duration > threshold {
+                tracing::warn!(
+                    target: "tool_calls",
+                    tool = record.tool_name,
+                    duration_ms = record.duration.as_millis() as u64,
+                    "Slow tool call: '{}' took {:?}",
+                    record.tool_name,
+                    record.duration
+                );
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        export(&record);
+
+        let mut stats = self.stats.write();
+        let entry = stats.entry(record.tool_name.to_string()).or_default();
+        entry.calls += 1;
+        if !record.success {
+            entry.errors += 1;
+        }
+        entry.total_duration += record.duration;
+        entry.max_duration = entry.max_duration.max(record.duration);
+        entry.args_bytes += record.args_bytes as u64;
+        entry.return_bytes += record.return_bytes as u64;
+    }
+
+    /// Get the statistics of one tool.
+    #[must_use]
+    pub fn get(&self, tool_name: &str) -> Option<ToolCallStats> {
+        self.stats.read().get(tool_name).cloned()
+    }
+
+    /// Get the statistics of all tools.
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<String, ToolCallStats> {
+        self.stats.read().clone()
+    }
+
+    /// Get the tools with the highest total duration, slowest first.
+    #[must_use]
+    pub fn slowest(&self, n: usize) -> Vec<(String, ToolCallStats)> {
+        let mut stats: Vec<_> = self.snapshot().into_iter().collect();
+        stats.sort_by_key(|(_, s)| std::cmp::Reverse(s.total_duration));
+        stats.truncate(n);
+        stats
+    }
+
+    /// Clear all statistics.
+    pub fn clear(&self) {
+        self.stats.write().clear();
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn export(record: &ToolCallRecord<'_>) {
+    let tool = record.tool_name.to_string();
+    let status = if record.success { "ok" } else { "error" };
+    metrics::counter!("serdes_ai_tool_calls_total", "tool" => tool.clone(), "status" => status)
+        .increment(1);
+    metrics::histogram!("serdes_ai_tool_call_duration_seconds", "tool" => tool.clone())
+        .record(record.duration.as_secs_f64());
+    metrics::histogram!("serdes_ai_tool_call_args_bytes", "tool" => tool.clone())
+        .record(record.args_bytes as f64);
+    metrics::histogram!("serdes_ai_tool_call_return_bytes", "tool" => tool)
+        .record(record.return_bytes as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool_name: &str, millis: u64, success: bool) -> ToolCallRecord<'_> {
+        ToolCallRecord {
+            tool_name,
+            duration: Duration::from_millis(millis),
+            success,
+            args_bytes: 10,
+            return_bytes: if success { 100 } else { 0 },
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let stats = ToolStats::new();
+        stats.record(call("search", 30, true));
+        stats.record(call("search", 10, false));
+        stats.record(call("fetch", 100, true));
+
+        let search = stats.get("search").unwrap();
+        assert_eq!(search.calls, 2);
+        assert_eq!(search.errors, 1);
+        assert_eq!(search.mean_duration(), Duration::from_millis(20));
+        assert_eq!(search.max_duration, Duration::from_millis(30));
+        assert_eq!(search.args_bytes, 20);
+        assert_eq!(search.return_bytes, 100);
+        assert!((search.error_rate() - 0.5).abs() < f64::EPSILON);
+
+        let slowest = stats.slowest(1);
+        assert_eq!(slowest[0].0, "fetch");
+        assert!(stats.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_record_from_result() {
+        let ok = Ok(ToolReturn::text("found"));
+        let record = ToolCallRecord::from_result("search", Duration::ZERO, 12, &ok);
+        assert!(record.success);
+        assert_eq!(record.args_bytes, 12);
+        assert_eq!(record.return_bytes, 5);
+
+        let failed = Ok(ToolReturn::error("boom"));
+        assert!(!ToolCallRecord::from_result("search", Duration::ZERO, 12, &failed).success);
+
+        let failed = Err(ToolError::execution_failed("boom"));
+        let record = ToolCallRecord::from_result("search", Duration::ZERO, 12, &failed);
+        assert!(!record.success);
+        assert_eq!(record.return_bytes, 0);
+    }
+}
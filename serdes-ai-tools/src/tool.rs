@@ -11,7 +11,8 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::{
-    definition::ToolDefinition, return_types::ToolResult, schema::SchemaBuilder, RunContext,
+    definition::ToolDefinition, permissions::Permissions, return_types::ToolResult,
+    schema::SchemaBuilder, RunContext,
 };
 
 /// Core trait for all tools.
@@ -70,6 +71,14 @@ pub trait Tool<Deps = ()>: Send + Sync {
         None
     }
 
+    /// Scopes the caller needs to run this tool.
+    ///
+    /// Enforced by permission-checking toolsets and agents; see
+    /// [`permissions`](crate::permissions).
+    fn permissions(&self) -> Permissions {
+        Permissions::new()
+    }
+
     /// Prepare the tool definition at runtime.
     ///
     /// This allows modifying the tool definition based on the current context,
@@ -6,7 +6,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use serdes_ai_tools::{RunContext, ToolDefinition, ToolError, ToolReturn};
+use serdes_ai_tools::{Permissions, RunContext, ToolDefinition, ToolError, ToolReturn};
 use std::collections::HashMap;
 
 /// A tool that belongs to a toolset.
@@ -21,6 +21,10 @@ pub struct ToolsetTool {
     pub tool_def: ToolDefinition,
     /// Maximum retries for this tool.
     pub max_retries: u32,
+    /// Behavior hints, e.g. from MCP tool annotations.
+    pub annotations: Option<ToolAnnotations>,
+    /// Scopes the caller needs to run this tool.
+    pub permissions: Permissions,
 }
 
 impl ToolsetTool {
@@ -31,6 +35,8 @@ impl ToolsetTool {
             toolset_id: None,
             tool_def,
             max_retries: 3,
+            annotations: None,
+            permissions: Permissions::new(),
         }
     }
 
@@ -48,6 +54,20 @@ impl ToolsetTool {
         self
     }
 
+    /// Set behavior hints.
+    #[must_use]
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Set the scopes the caller needs.
+    #[must_use]
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
     /// Get the tool name.
     #[must_use]
     pub fn name(&self) -> &str {
@@ -61,6 +81,55 @@ impl ToolsetTool {
     }
 }
 
+/// Hints about a tool's behavior.
+///
+/// Mirrors MCP tool annotations. Hints are not guaranteed to be accurate;
+/// don't rely on them for security decisions about untrusted servers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Human-readable title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The tool does not modify its environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// The tool may perform destructive updates (default `true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Repeated calls with the same arguments have no additional effect
+    /// (default `false`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// The tool interacts with external entities (default `true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// Check if the tool is read-only.
+    #[must_use]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only_hint.unwrap_or(false)
+    }
+
+    /// Check if the tool may perform destructive updates.
+    ///
+    /// Read-only tools are never destructive.
+    #[must_use]
+    pub fn is_destructive(&self) -> bool {
+        !self.is_read_only() && self.destructive_hint.unwrap_or(true)
+    }
+
+    /// Check if repeated calls are safe.
+    ///
+    /// Read-only tools are always idempotent.
+    #[must_use]
+    pub fn is_idempotent(&self) -> bool {
+        self.is_read_only() || self.idempotent_hint.unwrap_or(false)
+    }
+}
+
 /// Information about a toolset for serialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolsetInfo {
@@ -166,6 +235,19 @@ mod tests {
         assert_eq!(tool.max_retries, 5);
     }
 
+    #[test]
+    fn test_tool_annotations_defaults() {
+        // Unspecified hints take the MCP defaults
+        let annotations = ToolAnnotations::default();
+        assert!(annotations.is_destructive());
+        assert!(!annotations.is_idempotent());
+
+        let annotations: ToolAnnotations =
+            serde_json::from_value(serde_json::json!({"readOnlyHint": true})).unwrap();
+        assert!(!annotations.is_destructive());
+        assert!(annotations.is_idempotent());
+    }
+
     #[test]
     fn test_toolset_info_serde() {
         let info = ToolsetInfo {
@@ -2,6 +2,10 @@
 //!
 //! This module provides `ApprovalRequiredToolset`, which requires approval
 //! before executing any tool.
+//!
+//! Tools with [`ToolAnnotations`](crate::ToolAnnotations), such as MCP tools,
+//! can require approval based on their hints: see
+//! [`ApprovalRequiredToolset::from_annotations`].
 
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
@@ -35,10 +39,14 @@ pub type ApprovalChecker<Deps> =
 /// let approved = ApprovalRequiredToolset::with_checker(toolset, |ctx, def, args| {
 ///     def.name.contains("delete") || def.name.contains("modify")
 /// });
+///
+/// // Or based on tool annotations, e.g. for MCP tools
+/// let approved = ApprovalRequiredToolset::from_annotations(mcp_toolset);
 /// ```
 pub struct ApprovalRequiredToolset<T, Deps = ()> {
     inner: T,
     approval_checker: Arc<ApprovalChecker<Deps>>,
+    use_annotations: bool,
     _phantom: PhantomData<fn() -> Deps>,
 }
 
@@ -51,6 +59,7 @@ where
         Self {
             inner,
             approval_checker: Arc::new(|_, _, _| true), // Always require approval
+            use_annotations: false,
             _phantom: PhantomData,
         }
     }
@@ -66,10 +75,26 @@ where
         Self {
             inner,
             approval_checker: Arc::new(checker),
+            use_annotations: false,
             _phantom: PhantomData,
         }
     }
 
+    /// Create a toolset that requires approval based on tool annotations.
+    ///
+    /// Tools annotated as destructive or non-idempotent require approval.
+    /// Read-only tools and tools without annotations do not.
+    pub fn from_annotations(inner: T) -> Self {
+        Self::with_checker(inner, |_, _, _| false).with_annotations()
+    }
+
+    /// Also require approval for tools annotated as destructive or non-idempotent.
+    #[must_use]
+    pub fn with_annotations(mut self) -> Self {
+        self.use_annotations = true;
+        self
+    }
+
     /// Get the inner toolset.
     #[must_use]
     pub fn inner(&self) -> &T {
@@ -110,7 +135,12 @@ where
         tool: &ToolsetTool,
     ) -> Result<ToolReturn, ToolError> {
         // Check if approval is required
-        if (self.approval_checker)(ctx, &tool.tool_def, &args) {
+        let annotated = self.use_annotations
+            && tool
+                .annotations
+                .as_ref()
+                .is_some_and(|a| a.is_destructive() || !a.is_idempotent());
+        if annotated || (self.approval_checker)(ctx, &tool.tool_def, &args) {
             return Err(ToolError::ApprovalRequired {
                 tool_name: name.to_string(),
                 args,
@@ -134,6 +164,7 @@ impl<T: std::fmt::Debug, Deps> std::fmt::Debug for ApprovalRequiredToolset<T, De
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ApprovalRequiredToolset")
             .field("inner", &self.inner)
+            .field("use_annotations", &self.use_annotations)
             .finish()
     }
 }
@@ -294,4 +325,54 @@ mod tests {
 
         assert!(matches!(result, Err(ToolError::ApprovalRequired { .. })));
     }
+
+    #[tokio::test]
+    async fn test_approval_from_annotations() {
+        use crate::ToolAnnotations;
+
+        let approved = ApprovalRequiredToolset::from_annotations(FunctionToolset::new());
+        let ctx = RunContext::minimal("test");
+        let call = |tool: ToolsetTool| {
+            let approved = &approved;
+            let ctx = &ctx;
+            async move {
+                approved
+                    .call_tool("t", serde_json::json!({}), ctx, &tool)
+                    .await
+            }
+        };
+        let tool = || ToolsetTool::new(ToolDefinition::new("t", "Test"));
+
+        let read_only = tool().with_annotations(ToolAnnotations {
+            read_only_hint: Some(true),
+            ..Default::default()
+        });
+        let destructive = tool().with_annotations(ToolAnnotations {
+            destructive_hint: Some(true),
+            idempotent_hint: Some(true),
+            ..Default::default()
+        });
+        let non_idempotent = tool().with_annotations(ToolAnnotations {
+            destructive_hint: Some(false),
+            ..Default::default()
+        });
+
+        // The inner toolset has no tool "t", so skipping approval means a not-found error
+        assert!(!matches!(
+            call(read_only).await,
+            Err(ToolError::ApprovalRequired { .. })
+        ));
+        assert!(!matches!(
+            call(tool()).await,
+            Err(ToolError::ApprovalRequired { .. })
+        ));
+        assert!(matches!(
+            call(destructive).await,
+            Err(ToolError::ApprovalRequired { .. })
+        ));
+        assert!(matches!(
+            call(non_idempotent).await,
+            Err(ToolError::ApprovalRequired { .. })
+        ));
+    }
 }
@@ -2,14 +2,107 @@
 //!
 //! This module provides `CombinedToolset`, which merges multiple toolsets
 //! into a single unified toolset.
+//!
+//! Contained toolsets can be entered lazily on first use, and an
+//! [`UnavailablePolicy`] decides what happens when one of them fails to
+//! start, so an agent can keep working when an optional tool server is down.
 
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use serde_json::Value as JsonValue;
 use serdes_ai_tools::{RunContext, ToolError, ToolReturn};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{AbstractToolset, BoxedToolset, ToolsetTool};
 
+/// What to do when a contained toolset fails to start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnavailablePolicy {
+    /// Return the error from `enter`/`get_tools` (default).
+    #[default]
+    FailFast,
+    /// Leave the toolset out and emit [`ToolsetEvent::Unavailable`].
+    SkipUnavailable,
+    /// Leave the toolset out and keep trying to enter it in the background.
+    ///
+    /// Once a retry succeeds its tools become available again and
+    /// [`ToolsetEvent::Recovered`] is emitted.
+    RetryInBackground {
+        /// Delay between retries.
+        interval: Duration,
+    },
+}
+
+impl UnavailablePolicy {
+    /// Retry in the background every `interval`.
+    #[must_use]
+    pub fn retry_every(interval: Duration) -> Self {
+        Self::RetryInBackground { interval }
+    }
+}
+
+/// Availability change of a toolset inside a [`CombinedToolset`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolsetEvent {
+    /// The toolset failed to start and its tools are left out.
+    Unavailable {
+        /// Label of the toolset.
+        toolset: String,
+        /// Error message.
+        error: String,
+    },
+    /// A background retry succeeded.
+    Recovered {
+        /// Label of the toolset.
+        toolset: String,
+    },
+}
+
+/// Callback for [`ToolsetEvent`]s.
+pub type ToolsetEventHandler = Arc<dyn Fn(&ToolsetEvent) + Send + Sync>;
+
+/// Lifecycle state of a contained toolset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemberState {
+    /// Not entered yet.
+    Pending,
+    /// Entered successfully.
+    Ready,
+    /// Failed to enter; tools are left out.
+    Unavailable,
+}
+
+struct Member<Deps> {
+    toolset: BoxedToolset<Deps>,
+    state: Mutex<MemberState>,
+    /// Serializes concurrent attempts to enter the toolset.
+    entering: tokio::sync::Mutex<()>,
+    /// Bumped on exit so stale background retries stop.
+    epoch: AtomicU64,
+}
+
+impl<Deps> Member<Deps> {
+    fn new(toolset: BoxedToolset<Deps>) -> Arc<Self> {
+        Arc::new(Self {
+            toolset,
+            state: Mutex::new(MemberState::Pending),
+            entering: tokio::sync::Mutex::new(()),
+            epoch: AtomicU64::new(0),
+        })
+    }
+
+    fn state(&self) -> MemberState {
+        *self.state.lock()
+    }
+
+    fn set_state(&self, state: MemberState) {
+        *self.state.lock() = state;
+    }
+}
+
 /// Combines multiple toolsets into one.
 ///
 /// This allows treating multiple toolsets as a single collection.
@@ -18,18 +111,23 @@ use crate::{AbstractToolset, BoxedToolset, ToolsetTool};
 /// # Example
 ///
 /// ```ignore
-/// use serdes_ai_toolsets::{CombinedToolset, FunctionToolset};
+/// use serdes_ai_toolsets::{CombinedToolset, FunctionToolset, UnavailablePolicy};
 ///
 /// let toolset1 = FunctionToolset::with_id("tools1").tool(tool_a);
 /// let toolset2 = FunctionToolset::with_id("tools2").tool(tool_b);
 ///
 /// let combined = CombinedToolset::new()
 ///     .with_toolset(toolset1)
-///     .with_toolset(toolset2);
+///     .with_toolset(toolset2)
+///     .lazy()
+///     .with_unavailable_policy(UnavailablePolicy::SkipUnavailable);
 /// ```
 pub struct CombinedToolset<Deps = ()> {
     id: Option<String>,
-    toolsets: Vec<BoxedToolset<Deps>>,
+    members: Vec<Arc<Member<Deps>>>,
+    lazy: bool,
+    policy: UnavailablePolicy,
+    event_handler: Option<ToolsetEventHandler>,
 }
 
 impl<Deps> CombinedToolset<Deps> {
@@ -38,7 +136,10 @@ impl<Deps> CombinedToolset<Deps> {
     pub fn new() -> Self {
         Self {
             id: None,
-            toolsets: Vec::new(),
+            members: Vec::new(),
+            lazy: false,
+            policy: UnavailablePolicy::default(),
+            event_handler: None,
         }
     }
 
@@ -47,21 +148,21 @@ impl<Deps> CombinedToolset<Deps> {
     pub fn with_id(id: impl Into<String>) -> Self {
         Self {
             id: Some(id.into()),
-            toolsets: Vec::new(),
+            ..Self::new()
         }
     }
 
     /// Add a toolset.
     #[must_use]
     pub fn with_toolset<T: AbstractToolset<Deps> + 'static>(mut self, toolset: T) -> Self {
-        self.toolsets.push(Box::new(toolset));
+        self.members.push(Member::new(Box::new(toolset)));
         self
     }
 
     /// Add a boxed toolset.
     #[must_use]
     pub fn add_boxed(mut self, toolset: BoxedToolset<Deps>) -> Self {
-        self.toolsets.push(toolset);
+        self.members.push(Member::new(toolset));
         self
     }
 
@@ -73,21 +174,61 @@ impl<Deps> CombinedToolset<Deps> {
         T: AbstractToolset<Deps> + 'static,
     {
         for toolset in toolsets {
-            self.toolsets.push(Box::new(toolset));
+            self.members.push(Member::new(Box::new(toolset)));
         }
         self
     }
 
+    /// Enter contained toolsets on first use instead of in `enter`.
+    #[must_use]
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Set the policy for toolsets that fail to start.
+    #[must_use]
+    pub fn with_unavailable_policy(mut self, policy: UnavailablePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Set a callback for availability changes.
+    #[must_use]
+    pub fn with_event_handler<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ToolsetEvent) + Send + Sync + 'static,
+    {
+        self.event_handler = Some(Arc::new(f));
+        self
+    }
+
     /// Get the number of contained toolsets.
     #[must_use]
     pub fn toolset_count(&self) -> usize {
-        self.toolsets.len()
+        self.members.len()
     }
 
     /// Check if empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.toolsets.is_empty()
+        self.members.is_empty()
+    }
+
+    /// Get the policy for toolsets that fail to start.
+    #[must_use]
+    pub fn unavailable_policy(&self) -> UnavailablePolicy {
+        self.policy
+    }
+
+    /// Labels of toolsets that are currently left out.
+    #[must_use]
+    pub fn unavailable(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .filter(|m| m.state() == MemberState::Unavailable)
+            .map(|m| m.toolset.label())
+            .collect()
     }
 }
 
@@ -97,6 +238,122 @@ impl<Deps> Default for CombinedToolset<Deps> {
     }
 }
 
+fn emit(handler: &Option<ToolsetEventHandler>, event: ToolsetEvent) {
+    match &event {
+        ToolsetEvent::Unavailable { toolset, error } => {
+            tracing::warn!("Toolset {} unavailable: {}", toolset, error);
+        }
+        ToolsetEvent::Recovered { toolset } => {
+            tracing::info!("Toolset {} recovered", toolset);
+        }
+    }
+    if let Some(handler) = handler {
+        handler(&event);
+    }
+}
+
+impl<Deps: Send + Sync + 'static> CombinedToolset<Deps> {
+    /// Enter a member, applying the unavailable policy on failure.
+    ///
+    /// Returns `Ok(false)` if the member was left out.
+    async fn enter_member(&self, member: &Arc<Member<Deps>>) -> Result<bool, ToolError> {
+        let _guard = member.entering.lock().await;
+        match member.state() {
+            MemberState::Ready => return Ok(true),
+            MemberState::Unavailable => return Ok(false),
+            MemberState::Pending => {}
+        }
+
+        let error = match member.toolset.enter().await {
+            Ok(()) => {
+                member.set_state(MemberState::Ready);
+                return Ok(true);
+            }
+            Err(e) => e,
+        };
+
+        if self.policy == UnavailablePolicy::FailFast {
+            return Err(error);
+        }
+
+        member.set_state(MemberState::Unavailable);
+        emit(
+            &self.event_handler,
+            ToolsetEvent::Unavailable {
+                toolset: member.toolset.label(),
+                error: error.message().to_string(),
+            },
+        );
+        if let UnavailablePolicy::RetryInBackground { interval } = self.policy {
+            self.spawn_retry(Arc::clone(member), interval);
+        }
+        Ok(false)
+    }
+
+    fn spawn_retry(&self, member: Arc<Member<Deps>>, interval: Duration) {
+        let handler = self.event_handler.clone();
+        let epoch = member.epoch.load(Ordering::SeqCst);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let _guard = member.entering.lock().await;
+                if member.epoch.load(Ordering::SeqCst) != epoch
+                    || member.state() != MemberState::Unavailable
+                {
+                    return;
+                }
+                match member.toolset.enter().await {
+                    Ok(()) => {
+                        member.set_state(MemberState::Ready);
+                        emit(
+                            &handler,
+                            ToolsetEvent::Recovered {
+                                toolset: member.toolset.label(),
+                            },
+                        );
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::debug!(
+                            "Retry of toolset {} failed: {}",
+                            member.toolset.label(),
+                            e
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    /// Get a member's tools, or `None` if it is left out.
+    async fn member_tools(
+        &self,
+        member: &Arc<Member<Deps>>,
+        ctx: &RunContext<Deps>,
+    ) -> Result<Option<HashMap<String, ToolsetTool>>, ToolError> {
+        match member.state() {
+            MemberState::Unavailable => return Ok(None),
+            MemberState::Pending if self.lazy && !self.enter_member(member).await? => {
+                return Ok(None)
+            }
+            _ => {}
+        }
+
+        match member.toolset.get_tools(ctx).await {
+            Ok(tools) => Ok(Some(tools)),
+            Err(e) if self.policy != UnavailablePolicy::FailFast => {
+                tracing::warn!(
+                    "Skipping tools of {}: {}",
+                    member.toolset.label(),
+                    e.message()
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
 /// Track which toolset owns which tool.
 #[derive(Clone)]
 struct ToolOwnership {
@@ -125,14 +382,16 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for CombinedToolset<Deps
         let mut all_tools: HashMap<String, ToolOwnership> = HashMap::new();
         let mut conflicts: Vec<(String, String, String)> = Vec::new();
 
-        for (idx, toolset) in self.toolsets.iter().enumerate() {
-            let tools = toolset.get_tools(ctx).await?;
+        for (idx, member) in self.members.iter().enumerate() {
+            let Some(tools) = self.member_tools(member, ctx).await? else {
+                continue;
+            };
 
             for (name, tool) in tools {
                 if let Some(existing) = all_tools.get(&name) {
                     // Track conflict
-                    let existing_label = self.toolsets[existing.toolset_index].label();
-                    let new_label = toolset.label();
+                    let existing_label = self.members[existing.toolset_index].toolset.label();
+                    let new_label = member.toolset.label();
                     conflicts.push((name.clone(), existing_label, new_label));
                 } else {
                     all_tools.insert(
@@ -174,10 +433,12 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for CombinedToolset<Deps
         tool: &ToolsetTool,
     ) -> Result<ToolReturn, ToolError> {
         // Find which toolset has this tool
-        for toolset in &self.toolsets {
-            let tools = toolset.get_tools(ctx).await?;
+        for member in &self.members {
+            let Some(tools) = self.member_tools(member, ctx).await? else {
+                continue;
+            };
             if tools.contains_key(name) {
-                return toolset.call_tool(name, args, ctx, tool).await;
+                return member.toolset.call_tool(name, args, ctx, tool).await;
             }
         }
 
@@ -189,16 +450,26 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for CombinedToolset<Deps
     }
 
     async fn enter(&self) -> Result<(), ToolError> {
-        for toolset in &self.toolsets {
-            toolset.enter().await?;
+        if self.lazy {
+            return Ok(());
+        }
+        for member in &self.members {
+            self.enter_member(member).await?;
         }
         Ok(())
     }
 
     async fn exit(&self) -> Result<(), ToolError> {
         // Exit in reverse order
-        for toolset in self.toolsets.iter().rev() {
-            toolset.exit().await?;
+        for member in self.members.iter().rev() {
+            let _guard = member.entering.lock().await;
+            member.epoch.fetch_add(1, Ordering::SeqCst);
+            let state = member.state();
+            member.set_state(MemberState::Pending);
+            if state == MemberState::Unavailable || (self.lazy && state == MemberState::Pending) {
+                continue;
+            }
+            member.toolset.exit().await?;
         }
         Ok(())
     }
@@ -208,7 +479,9 @@ impl<Deps> std::fmt::Debug for CombinedToolset<Deps> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("CombinedToolset")
             .field("id", &self.id)
-            .field("toolset_count", &self.toolsets.len())
+            .field("toolset_count", &self.members.len())
+            .field("lazy", &self.lazy)
+            .field("policy", &self.policy)
             .finish()
     }
 }
@@ -399,4 +672,153 @@ mod tests {
         combined.exit().await.unwrap();
         assert_eq!(exit_count.load(Ordering::SeqCst), 2);
     }
+
+    /// Toolset whose `enter` fails a number of times before succeeding.
+    struct FlakyToolset {
+        inner: FunctionToolset<()>,
+        failures: parking_lot::Mutex<u32>,
+        enters: Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    impl FlakyToolset {
+        fn new(failures: u32) -> Self {
+            Self {
+                inner: FunctionToolset::new().with_id("flaky").tool(ToolB),
+                failures: parking_lot::Mutex::new(failures),
+                enters: Arc::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AbstractToolset<()> for FlakyToolset {
+        fn id(&self) -> Option<&str> {
+            Some("flaky")
+        }
+
+        fn type_name(&self) -> &'static str {
+            "FlakyToolset"
+        }
+
+        async fn get_tools(
+            &self,
+            ctx: &RunContext<()>,
+        ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
+            self.inner.get_tools(ctx).await
+        }
+
+        async fn call_tool(
+            &self,
+            name: &str,
+            args: JsonValue,
+            ctx: &RunContext<()>,
+            tool: &ToolsetTool,
+        ) -> Result<ToolReturn, ToolError> {
+            self.inner.call_tool(name, args, ctx, tool).await
+        }
+
+        async fn enter(&self) -> Result<(), ToolError> {
+            self.enters.fetch_add(1, Ordering::SeqCst);
+            let mut failures = self.failures.lock();
+            if *failures > 0 {
+                *failures -= 1;
+                Err(ToolError::execution_failed("server not running"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_combined_toolset_fail_fast() {
+        let combined = CombinedToolset::new()
+            .with_toolset(FunctionToolset::new().tool(ToolA))
+            .with_toolset(FlakyToolset::new(1));
+
+        let err = combined.enter().await.unwrap_err();
+        assert!(err.message().contains("server not running"));
+    }
+
+    #[tokio::test]
+    async fn test_combined_toolset_skip_unavailable() {
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let combined = {
+            let events = Arc::clone(&events);
+            CombinedToolset::new()
+                .with_toolset(FunctionToolset::new().tool(ToolA))
+                .with_toolset(FlakyToolset::new(1))
+                .with_unavailable_policy(UnavailablePolicy::SkipUnavailable)
+                .with_event_handler(move |e| events.lock().push(e.clone()))
+        };
+
+        combined.enter().await.unwrap();
+        let ctx = RunContext::minimal("test");
+        let tools = combined.get_tools(&ctx).await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert!(tools.contains_key("tool_a"));
+        assert_eq!(combined.unavailable(), vec!["FlakyToolset 'flaky'"]);
+        assert_eq!(
+            events.lock().as_slice(),
+            &[ToolsetEvent::Unavailable {
+                toolset: "FlakyToolset 'flaky'".to_string(),
+                error: "Tool execution failed: server not running".to_string(),
+            }]
+        );
+
+        // Re-entering after exit tries the toolset again
+        combined.exit().await.unwrap();
+        combined.enter().await.unwrap();
+        assert!(combined.unavailable().is_empty());
+        assert_eq!(combined.get_tools(&ctx).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_combined_toolset_lazy() {
+        let flaky = FlakyToolset::new(0);
+        let enters = Arc::clone(&flaky.enters);
+        let combined = CombinedToolset::new().with_toolset(flaky).lazy();
+
+        combined.enter().await.unwrap();
+        assert_eq!(enters.load(Ordering::SeqCst), 0);
+
+        let ctx = RunContext::minimal("test");
+        combined.get_tools(&ctx).await.unwrap();
+        combined.get_tools(&ctx).await.unwrap();
+        assert_eq!(enters.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_combined_toolset_retry_in_background() {
+        let events = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let combined = {
+            let events = Arc::clone(&events);
+            CombinedToolset::new()
+                .with_toolset(FlakyToolset::new(2))
+                .with_unavailable_policy(UnavailablePolicy::retry_every(Duration::from_millis(5)))
+                .with_event_handler(move |e| events.lock().push(e.clone()))
+        };
+
+        combined.enter().await.unwrap();
+        let ctx = RunContext::minimal("test");
+        assert!(combined.get_tools(&ctx).await.unwrap().is_empty());
+
+        for _ in 0..100 {
+            if combined.unavailable().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(combined.unavailable().is_empty());
+        assert!(combined
+            .get_tools(&ctx)
+            .await
+            .unwrap()
+            .contains_key("tool_b"));
+        assert_eq!(
+            events.lock().last(),
+            Some(&ToolsetEvent::Recovered {
+                toolset: "FlakyToolset 'flaky'".to_string(),
+            })
+        );
+    }
 }
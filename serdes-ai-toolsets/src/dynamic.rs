@@ -2,16 +2,194 @@
 //!
 //! This module provides `DynamicToolset`, which allows tools to be
 //! added and removed at runtime.
+//!
+//! A [`DynamicToolsetHandle`] can be cloned and moved elsewhere to change the
+//! tools while the toolset is in use. Every change bumps a version counter and
+//! is broadcast as a [`ToolsetChange`], so consumers can refresh their tool
+//! list on the next step instead of only at build time.
 
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use serde_json::Value as JsonValue;
 use serdes_ai_tools::{RunContext, Tool, ToolError, ToolReturn};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::broadcast;
 
 use crate::{AbstractToolset, ToolsetTool};
 
+/// Capacity of the change notification channel.
+const CHANGE_CHANNEL_CAPACITY: usize = 64;
+
+/// A change to the tools of a [`DynamicToolset`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolsetChange {
+    /// A tool was added.
+    Added(String),
+    /// An existing tool was replaced.
+    Replaced(String),
+    /// A tool was removed.
+    Removed(String),
+    /// All tools were removed.
+    Cleared,
+}
+
+struct DynamicTools<Deps> {
+    tools: RwLock<HashMap<String, Arc<dyn Tool<Deps>>>>,
+    version: AtomicU64,
+    changes: broadcast::Sender<ToolsetChange>,
+}
+
+/// Cloneable handle for changing the tools of a [`DynamicToolset`].
+///
+/// All handles share the tools of the toolset they came from.
+pub struct DynamicToolsetHandle<Deps = ()> {
+    shared: Arc<DynamicTools<Deps>>,
+}
+
+impl<Deps> Clone for DynamicToolsetHandle<Deps> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<Deps: Send + Sync + 'static> DynamicToolsetHandle<Deps> {
+    fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            shared: Arc::new(DynamicTools {
+                tools: RwLock::new(HashMap::new()),
+                version: AtomicU64::new(0),
+                changes,
+            }),
+        }
+    }
+
+    fn notify(&self, change: ToolsetChange) {
+        self.shared.version.fetch_add(1, Ordering::SeqCst);
+        // No subscribers is fine
+        let _ = self.shared.changes.send(change);
+    }
+
+    /// Add a tool.
+    ///
+    /// If a tool with the same name exists, it will be replaced.
+    pub fn add_tool<T: Tool<Deps> + 'static>(&self, tool: T) {
+        self.add_boxed(Arc::new(tool));
+    }
+
+    /// Add a boxed tool.
+    ///
+    /// If a tool with the same name exists, it will be replaced.
+    pub fn add_boxed(&self, tool: Arc<dyn Tool<Deps>>) {
+        let name = tool.definition().name.clone();
+        let replaced = self
+            .shared
+            .tools
+            .write()
+            .insert(name.clone(), tool)
+            .is_some();
+        self.notify(if replaced {
+            ToolsetChange::Replaced(name)
+        } else {
+            ToolsetChange::Added(name)
+        });
+    }
+
+    /// Replace an existing tool with the same name.
+    ///
+    /// Returns `false` and leaves the tools unchanged if there is no tool
+    /// with that name.
+    pub fn replace_tool<T: Tool<Deps> + 'static>(&self, tool: T) -> bool {
+        let name = tool.definition().name.clone();
+        {
+            let mut tools = self.shared.tools.write();
+            match tools.get_mut(&name) {
+                Some(existing) => *existing = Arc::new(tool),
+                None => return false,
+            }
+        }
+        self.notify(ToolsetChange::Replaced(name));
+        true
+    }
+
+    /// Remove a tool by name.
+    ///
+    /// Returns `true` if the tool was removed, `false` if it didn't exist.
+    pub fn remove_tool(&self, name: &str) -> bool {
+        let removed = self.shared.tools.write().remove(name).is_some();
+        if removed {
+            self.notify(ToolsetChange::Removed(name.to_string()));
+        }
+        removed
+    }
+
+    /// Clear all tools.
+    pub fn clear(&self) {
+        self.shared.tools.write().clear();
+        self.notify(ToolsetChange::Cleared);
+    }
+
+    /// Get the number of tools.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.shared.tools.read().len()
+    }
+
+    /// Check if empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.shared.tools.read().is_empty()
+    }
+
+    /// Check if a tool exists.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.shared.tools.read().contains_key(name)
+    }
+
+    /// Get tool names.
+    #[must_use]
+    pub fn tool_names(&self) -> Vec<String> {
+        self.shared.tools.read().keys().cloned().collect()
+    }
+
+    /// Get the current version.
+    ///
+    /// The version increases with every change, so comparing it with a
+    /// previously seen value tells whether the tool list needs a refresh.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.shared.version.load(Ordering::SeqCst)
+    }
+
+    /// Subscribe to changes.
+    ///
+    /// Receivers that fall behind by more than 64 changes get
+    /// [`broadcast::error::RecvError::Lagged`] and should refresh the whole
+    /// tool list.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ToolsetChange> {
+        self.shared.changes.subscribe()
+    }
+
+    fn snapshot(&self) -> Vec<(String, Arc<dyn Tool<Deps>>)> {
+        self.shared
+            .tools
+            .read()
+            .iter()
+            .map(|(k, v)| (k.clone(), Arc::clone(v)))
+            .collect()
+    }
+
+    fn get(&self, name: &str) -> Option<Arc<dyn Tool<Deps>>> {
+        self.shared.tools.read().get(name).cloned()
+    }
+}
+
 /// Toolset that can have tools added/removed at runtime.
 ///
 /// This is useful for scenarios where the available tools change
@@ -31,15 +209,17 @@ use crate::{AbstractToolset, ToolsetTool};
 /// // Add tools at runtime
 /// toolset.add_tool(my_tool);
 ///
-/// // Remove tools
-/// toolset.remove_tool("my_tool");
+/// // Change tools from elsewhere while the toolset is in use
+/// let handle = toolset.handle();
+/// let mut changes = handle.subscribe();
+/// handle.remove_tool("my_tool");
 /// ```
 pub struct DynamicToolset<Deps = ()>
 where
     Deps: Send + Sync + 'static,
 {
     id: Option<String>,
-    tools: RwLock<HashMap<String, Arc<dyn Tool<Deps>>>>,
+    handle: DynamicToolsetHandle<Deps>,
     max_retries: u32,
 }
 
@@ -49,7 +229,7 @@ impl<Deps: Send + Sync + 'static> DynamicToolset<Deps> {
     pub fn new() -> Self {
         Self {
             id: None,
-            tools: RwLock::new(HashMap::new()),
+            handle: DynamicToolsetHandle::new(),
             max_retries: 3,
         }
     }
@@ -59,7 +239,7 @@ impl<Deps: Send + Sync + 'static> DynamicToolset<Deps> {
     pub fn with_id(id: impl Into<String>) -> Self {
         Self {
             id: Some(id.into()),
-            tools: RwLock::new(HashMap::new()),
+            handle: DynamicToolsetHandle::new(),
             max_retries: 3,
         }
     }
@@ -71,54 +251,77 @@ impl<Deps: Send + Sync + 'static> DynamicToolset<Deps> {
         self
     }
 
+    /// Get a handle for changing the tools.
+    #[must_use]
+    pub fn handle(&self) -> DynamicToolsetHandle<Deps> {
+        self.handle.clone()
+    }
+
     /// Add a tool.
     ///
     /// If a tool with the same name exists, it will be replaced.
     pub fn add_tool<T: Tool<Deps> + 'static>(&self, tool: T) {
-        let name = tool.definition().name.clone();
-        self.tools.write().insert(name, Arc::new(tool));
+        self.handle.add_tool(tool);
     }
 
     /// Add a boxed tool.
     pub fn add_boxed(&self, tool: Arc<dyn Tool<Deps>>) {
-        let name = tool.definition().name.clone();
-        self.tools.write().insert(name, tool);
+        self.handle.add_boxed(tool);
+    }
+
+    /// Replace an existing tool with the same name.
+    ///
+    /// Returns `false` if there is no tool with that name.
+    pub fn replace_tool<T: Tool<Deps> + 'static>(&self, tool: T) -> bool {
+        self.handle.replace_tool(tool)
     }
 
     /// Remove a tool by name.
     ///
     /// Returns `true` if the tool was removed, `false` if it didn't exist.
     pub fn remove_tool(&self, name: &str) -> bool {
-        self.tools.write().remove(name).is_some()
+        self.handle.remove_tool(name)
     }
 
     /// Clear all tools.
     pub fn clear(&self) {
-        self.tools.write().clear();
+        self.handle.clear();
     }
 
     /// Get the number of tools.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.tools.read().len()
+        self.handle.len()
     }
 
     /// Check if empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.tools.read().is_empty()
+        self.handle.is_empty()
     }
 
     /// Check if a tool exists.
     #[must_use]
     pub fn contains(&self, name: &str) -> bool {
-        self.tools.read().contains_key(name)
+        self.handle.contains(name)
     }
 
     /// Get tool names.
     #[must_use]
     pub fn tool_names(&self) -> Vec<String> {
-        self.tools.read().keys().cloned().collect()
+        self.handle.tool_names()
+    }
+
+    /// Get the current version, see [`DynamicToolsetHandle::version`].
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.handle.version()
+    }
+
+    /// Subscribe to changes.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ToolsetChange> {
+        self.handle.subscribe()
     }
 }
 
@@ -143,13 +346,7 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for DynamicToolset<Deps>
         ctx: &RunContext<Deps>,
     ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
         // Clone the tools under the lock to avoid holding it across await
-        let tools_snapshot: Vec<(String, Arc<dyn Tool<Deps>>)> = {
-            let tools = self.tools.read();
-            tools
-                .iter()
-                .map(|(k, v)| (k.clone(), Arc::clone(v)))
-                .collect()
-        };
+        let tools_snapshot = self.handle.snapshot();
 
         let mut result = HashMap::with_capacity(tools_snapshot.len());
 
@@ -167,6 +364,8 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for DynamicToolset<Deps>
                         toolset_id: self.id.clone(),
                         tool_def: final_def,
                         max_retries,
+                        annotations: None,
+                        permissions: tool.permissions(),
                     },
                 );
             }
@@ -182,13 +381,10 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for DynamicToolset<Deps>
         ctx: &RunContext<Deps>,
         _tool: &ToolsetTool,
     ) -> Result<ToolReturn, ToolError> {
-        let tool = {
-            let tools = self.tools.read();
-            tools
-                .get(name)
-                .cloned()
-                .ok_or_else(|| ToolError::not_found(name))?
-        };
+        let tool = self
+            .handle
+            .get(name)
+            .ok_or_else(|| ToolError::not_found(name))?;
 
         tool.call(ctx, args).await
     }
@@ -200,6 +396,7 @@ impl<Deps: Send + Sync + 'static> std::fmt::Debug for DynamicToolset<Deps> {
             .field("id", &self.id)
             .field("tool_count", &self.len())
             .field("max_retries", &self.max_retries)
+            .field("version", &self.version())
             .finish()
     }
 }
@@ -379,4 +576,58 @@ mod tests {
         // All tools should be there (but with possible overwrites for "echo")
         assert!(!toolset.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_dynamic_toolset_change_notifications() {
+        let toolset = DynamicToolset::<()>::new();
+        let handle = toolset.handle();
+        let mut changes = toolset.subscribe();
+        assert_eq!(toolset.version(), 0);
+
+        handle.add_tool(EchoTool::new("v1: "));
+        handle.add_tool(EchoTool::new("v2: "));
+        assert!(handle.replace_tool(EchoTool::new("v3: ")));
+        assert!(!handle.replace_tool(AddTool));
+        assert!(handle.remove_tool("echo"));
+        assert!(!handle.remove_tool("echo"));
+        handle.clear();
+
+        let mut received = Vec::new();
+        while let Ok(change) = changes.try_recv() {
+            received.push(change);
+        }
+        assert_eq!(
+            received,
+            vec![
+                ToolsetChange::Added("echo".to_string()),
+                ToolsetChange::Replaced("echo".to_string()),
+                ToolsetChange::Replaced("echo".to_string()),
+                ToolsetChange::Removed("echo".to_string()),
+                ToolsetChange::Cleared,
+            ]
+        );
+        assert_eq!(toolset.version(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_toolset_handle_updates_live_toolset() {
+        let toolset = DynamicToolset::<()>::new();
+        let handle = toolset.handle();
+        let ctx = RunContext::minimal("test");
+        assert!(toolset.get_tools(&ctx).await.unwrap().is_empty());
+
+        tokio::spawn(async move {
+            handle.add_tool(AddTool);
+        })
+        .await
+        .unwrap();
+
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        let tool = tools.get("add").unwrap();
+        let result = toolset
+            .call_tool("add", serde_json::json!({"a": 1, "b": 2}), &ctx, tool)
+            .await
+            .unwrap();
+        assert_eq!(result.as_text(), Some("3"));
+    }
 }
@@ -2,20 +2,236 @@
 //!
 //! This module provides `ExternalToolset`, for tools that are executed
 //! externally (not by the agent itself).
+//!
+//! Without a channel, calls are deferred back to the caller. With an
+//! [`ExternalChannel`], calls are sent to a separate service using the wire
+//! types below, which all serialize to JSON:
+//!
+//! - [`ExternalToolManifest`]: the tool definitions a service exposes
+//! - [`ExternalToolCall`]: a call request
+//! - [`ExternalToolResult`]: the result of a call
+//!
+//! [`queue_channel`] connects both sides through an in-process queue; other
+//! transports (HTTP callbacks, message queues) implement [`ExternalChannel`].
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use serdes_ai_tools::{RunContext, ToolDefinition, ToolError, ToolReturn};
+use serdes_ai_core::messages::ToolReturnContent;
+use serdes_ai_tools::{
+    Permissions, RunContext, ToolDefinition, ToolError, ToolErrorInfo, ToolReturn,
+};
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 
 use crate::{AbstractToolset, ToolsetTool};
 
+/// Version of the external tool wire protocol.
+pub const EXTERNAL_PROTOCOL_VERSION: u32 = 1;
+
+/// Tool definitions exposed by an external service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalToolManifest {
+    /// Protocol version.
+    pub version: u32,
+    /// Toolset identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolset_id: Option<String>,
+    /// Tool definitions.
+    pub tools: Vec<ToolDefinition>,
+}
+
+impl ExternalToolManifest {
+    /// Create a manifest for the current protocol version.
+    #[must_use]
+    pub fn new(tools: Vec<ToolDefinition>) -> Self {
+        Self {
+            version: EXTERNAL_PROTOCOL_VERSION,
+            toolset_id: None,
+            tools,
+        }
+    }
+
+    /// Set the toolset ID.
+    #[must_use]
+    pub fn with_toolset_id(mut self, id: impl Into<String>) -> Self {
+        self.toolset_id = Some(id.into());
+        self
+    }
+}
+
+/// Request to execute a tool in an external service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalToolCall {
+    /// Unique ID used to match the result.
+    pub call_id: String,
+    /// Name of the tool.
+    pub tool_name: String,
+    /// Tool arguments.
+    pub args: JsonValue,
+    /// ID of the agent run making the call.
+    pub run_id: String,
+    /// ID of the tool call from the model, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Toolset identifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolset_id: Option<String>,
+}
+
+/// Outcome of an external tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ExternalToolOutcome {
+    /// The tool returned content.
+    Ok {
+        /// Returned content.
+        content: ToolReturnContent,
+    },
+    /// The tool failed.
+    Error {
+        /// Error details.
+        error: ToolErrorInfo,
+    },
+}
+
+/// Result of an external tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalToolResult {
+    /// ID of the call this answers.
+    pub call_id: String,
+    /// Outcome of the call.
+    #[serde(flatten)]
+    pub outcome: ExternalToolOutcome,
+}
+
+impl ExternalToolResult {
+    /// Create a successful result.
+    #[must_use]
+    pub fn ok(call_id: impl Into<String>, content: ToolReturnContent) -> Self {
+        Self {
+            call_id: call_id.into(),
+            outcome: ExternalToolOutcome::Ok { content },
+        }
+    }
+
+    /// Create a failed result.
+    #[must_use]
+    pub fn error(call_id: impl Into<String>, error: ToolErrorInfo) -> Self {
+        Self {
+            call_id: call_id.into(),
+            outcome: ExternalToolOutcome::Error { error },
+        }
+    }
+
+    /// Create a result from a local tool call.
+    ///
+    /// Used on the service side to answer an [`ExternalToolCall`].
+    #[must_use]
+    pub fn from_result(call_id: impl Into<String>, result: &Result<ToolReturn, ToolError>) -> Self {
+        match result {
+            Ok(ret) => Self::ok(call_id, ret.content.clone()),
+            Err(e) => Self::error(call_id, ToolErrorInfo::from(e)),
+        }
+    }
+
+    /// Convert into the result of the local tool call.
+    pub fn into_result(self) -> Result<ToolReturn, ToolError> {
+        match self.outcome {
+            ExternalToolOutcome::Ok { content } => Ok(ToolReturn {
+                content,
+                tool_call_id: None,
+            }),
+            ExternalToolOutcome::Error { error } => Err(match error.error_type.as_str() {
+                "model_retry" => ToolError::ModelRetry(error.message),
+                "not_found" => ToolError::NotFound(error.message),
+                _ => ToolError::ExecutionFailed {
+                    message: error.message,
+                    retryable: error.retryable,
+                },
+            }),
+        }
+    }
+}
+
+/// Channel that delivers external tool calls to the service executing them.
+#[async_trait]
+pub trait ExternalChannel: Send + Sync {
+    /// Send a call and wait for its result.
+    async fn call(&self, call: ExternalToolCall) -> Result<ExternalToolResult, ToolError>;
+}
+
+/// A call waiting in an [`ExternalCallQueue`].
+#[derive(Debug)]
+pub struct PendingExternalCall {
+    /// The call request.
+    pub call: ExternalToolCall,
+    reply: oneshot::Sender<ExternalToolResult>,
+}
+
+impl PendingExternalCall {
+    /// Send the result back to the caller.
+    ///
+    /// Returns `false` if the caller stopped waiting.
+    pub fn respond(self, result: ExternalToolResult) -> bool {
+        self.reply.send(result).is_ok()
+    }
+}
+
+/// Sending side of [`queue_channel`].
+#[derive(Debug, Clone)]
+pub struct QueueChannel {
+    sender: mpsc::Sender<PendingExternalCall>,
+}
+
+/// Receiving side of [`queue_channel`], held by the executing service.
+#[derive(Debug)]
+pub struct ExternalCallQueue {
+    receiver: mpsc::Receiver<PendingExternalCall>,
+}
+
+impl ExternalCallQueue {
+    /// Receive the next call, or `None` once all channels are dropped.
+    pub async fn recv(&mut self) -> Option<PendingExternalCall> {
+        self.receiver.recv().await
+    }
+}
+
+/// Create an in-process queue channel holding up to `buffer` calls.
+#[must_use]
+pub fn queue_channel(buffer: usize) -> (QueueChannel, ExternalCallQueue) {
+    let (sender, receiver) = mpsc::channel(buffer);
+    (QueueChannel { sender }, ExternalCallQueue { receiver })
+}
+
+#[async_trait]
+impl ExternalChannel for QueueChannel {
+    async fn call(&self, call: ExternalToolCall) -> Result<ExternalToolResult, ToolError> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send(PendingExternalCall { call, reply })
+            .await
+            .map_err(|_| ToolError::ExecutionFailed {
+                message: "External tool queue is closed".to_string(),
+                retryable: false,
+            })?;
+        response.await.map_err(|_| ToolError::ExecutionFailed {
+            message: "External tool call was dropped without a result".to_string(),
+            retryable: true,
+        })
+    }
+}
+
 /// Toolset for externally-executed tools.
 ///
 /// This is used when tools need to be exposed to the model but will be
 /// executed by an external system. When any tool is called, it returns
-/// `ToolError::CallDeferred` so the agent knows to defer execution.
+/// `ToolError::CallDeferred` so the agent knows to defer execution,
+/// unless an [`ExternalChannel`] is set to send the call to a service.
 ///
 /// # Example
 ///
@@ -31,6 +247,9 @@ pub struct ExternalToolset<Deps = ()> {
     id: Option<String>,
     definitions: Vec<ToolDefinition>,
     max_retries: u32,
+    channel: Option<Arc<dyn ExternalChannel>>,
+    call_timeout: Option<Duration>,
+    next_call: AtomicU64,
     _phantom: PhantomData<fn() -> Deps>,
 }
 
@@ -42,10 +261,47 @@ impl<Deps> ExternalToolset<Deps> {
             id: None,
             definitions: Vec::new(),
             max_retries: 3,
+            channel: None,
+            call_timeout: None,
+            next_call: AtomicU64::new(0),
             _phantom: PhantomData,
         }
     }
 
+    /// Create a toolset from a service's manifest.
+    #[must_use]
+    pub fn from_manifest(manifest: ExternalToolManifest) -> Self {
+        let toolset = Self::new().definitions(manifest.tools);
+        match manifest.toolset_id {
+            Some(id) => toolset.with_id(id),
+            None => toolset,
+        }
+    }
+
+    /// Get the manifest describing these tools.
+    #[must_use]
+    pub fn manifest(&self) -> ExternalToolManifest {
+        ExternalToolManifest {
+            version: EXTERNAL_PROTOCOL_VERSION,
+            toolset_id: self.id.clone(),
+            tools: self.definitions.clone(),
+        }
+    }
+
+    /// Send calls through a channel instead of deferring them.
+    #[must_use]
+    pub fn with_channel<C: ExternalChannel + 'static>(mut self, channel: C) -> Self {
+        self.channel = Some(Arc::new(channel));
+        self
+    }
+
+    /// Set how long to wait for a result from the channel.
+    #[must_use]
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
+
     /// Set the toolset ID.
     #[must_use]
     pub fn with_id(mut self, id: impl Into<String>) -> Self {
@@ -117,6 +373,8 @@ impl<Deps: Send + Sync> AbstractToolset<Deps> for ExternalToolset<Deps> {
                         toolset_id: self.id.clone(),
                         tool_def: def.clone(),
                         max_retries: self.max_retries,
+                        annotations: None,
+                        permissions: Permissions::new(),
                     },
                 )
             })
@@ -127,14 +385,45 @@ impl<Deps: Send + Sync> AbstractToolset<Deps> for ExternalToolset<Deps> {
         &self,
         name: &str,
         args: JsonValue,
-        _ctx: &RunContext<Deps>,
+        ctx: &RunContext<Deps>,
         _tool: &ToolsetTool,
     ) -> Result<ToolReturn, ToolError> {
-        // Always defer external tool calls
-        Err(ToolError::CallDeferred {
+        let Some(channel) = &self.channel else {
+            // Without a channel, defer external tool calls
+            return Err(ToolError::CallDeferred {
+                tool_name: name.to_string(),
+                args,
+            });
+        };
+
+        let call_id = format!(
+            "{}-{}",
+            ctx.run_id,
+            self.next_call.fetch_add(1, Ordering::Relaxed) + 1
+        );
+        let call = ExternalToolCall {
+            call_id: call_id.clone(),
             tool_name: name.to_string(),
             args,
-        })
+            run_id: ctx.run_id.clone(),
+            tool_call_id: ctx.tool_call_id.clone(),
+            toolset_id: self.id.clone(),
+        };
+
+        let result = match self.call_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, channel.call(call))
+                .await
+                .map_err(|_| ToolError::Timeout(timeout))??,
+            None => channel.call(call).await?,
+        };
+
+        if result.call_id != call_id {
+            return Err(ToolError::execution_failed(format!(
+                "External result for call '{}' does not match call '{}'",
+                result.call_id, call_id
+            )));
+        }
+        result.into_result()
     }
 }
 
@@ -144,6 +433,7 @@ impl<Deps> std::fmt::Debug for ExternalToolset<Deps> {
             .field("id", &self.id)
             .field("definitions", &self.definitions.len())
             .field("max_retries", &self.max_retries)
+            .field("channel", &self.channel.is_some())
             .finish()
     }
 }
@@ -207,4 +497,102 @@ mod tests {
             assert_eq!(args["endpoint"], "/test");
         }
     }
+
+    #[test]
+    fn test_external_manifest_roundtrip() {
+        let toolset = ExternalToolset::<()>::new()
+            .with_id("remote")
+            .definition(ToolDefinition::new("api_call", "Call API"));
+
+        let json = serde_json::to_string(&toolset.manifest()).unwrap();
+        let manifest: ExternalToolManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(manifest.version, EXTERNAL_PROTOCOL_VERSION);
+
+        let restored = ExternalToolset::<()>::from_manifest(manifest);
+        assert_eq!(restored.id(), Some("remote"));
+        assert_eq!(restored.len(), 1);
+    }
+
+    #[test]
+    fn test_external_result_wire_format() {
+        let ok = ExternalToolResult::ok("c1", ToolReturnContent::text("done"));
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            serde_json::json!({
+                "call_id": "c1",
+                "status": "ok",
+                "content": {"type": "text", "content": "done"}
+            })
+        );
+
+        let err: ExternalToolResult = serde_json::from_value(serde_json::json!({
+            "call_id": "c2",
+            "status": "error",
+            "error": {"error_type": "model_retry", "message": "bad id", "retryable": true}
+        }))
+        .unwrap();
+        assert!(matches!(err.into_result(), Err(ToolError::ModelRetry(m)) if m == "bad id"));
+    }
+
+    #[tokio::test]
+    async fn test_external_toolset_queue_channel() {
+        let (channel, mut queue) = queue_channel(8);
+        let toolset = ExternalToolset::<()>::new()
+            .with_id("remote")
+            .definition(ToolDefinition::new("api_call", "Call API"))
+            .with_channel(channel);
+
+        let service = tokio::spawn(async move {
+            while let Some(pending) = queue.recv().await {
+                // Round-trip through JSON like a remote service would
+                let json = serde_json::to_string(&pending.call).unwrap();
+                let call: ExternalToolCall = serde_json::from_str(&json).unwrap();
+                let result = match call.args.get("endpoint") {
+                    Some(endpoint) => Ok(ToolReturn::text(format!("called {}", endpoint))),
+                    None => Err(ToolError::execution_failed("missing endpoint")),
+                };
+                pending.respond(ExternalToolResult::from_result(call.call_id, &result));
+            }
+        });
+
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        let tool = tools.get("api_call").unwrap();
+
+        let result = toolset
+            .call_tool(
+                "api_call",
+                serde_json::json!({"endpoint": "/a"}),
+                &ctx,
+                tool,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.as_text(), Some("called \"/a\""));
+
+        let err = toolset
+            .call_tool("api_call", serde_json::json!({}), &ctx, tool)
+            .await
+            .unwrap_err();
+        assert!(err.message().contains("missing endpoint"));
+
+        drop(toolset);
+        service.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_external_toolset_call_timeout() {
+        let (channel, _queue) = queue_channel(8);
+        let toolset = ExternalToolset::<()>::new()
+            .definition(ToolDefinition::new("api_call", "Call API"))
+            .with_channel(channel)
+            .with_call_timeout(Duration::from_millis(10));
+
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        let result = toolset
+            .call_tool("api_call", serde_json::json!({}), &ctx, &tools["api_call"])
+            .await;
+        assert!(matches!(result, Err(ToolError::Timeout(_))));
+    }
 }
@@ -131,12 +131,15 @@ impl<Deps: Send + Sync + 'static> AbstractToolset<Deps> for FunctionToolset<Deps
             .map(|def| {
                 let name = def.name.clone();
                 let max_retries = self.registry.max_retries(&name).unwrap_or(self.max_retries);
+                let permissions = self.registry.permissions(&name).unwrap_or_default();
                 (
                     name,
                     ToolsetTool {
                         toolset_id: self.id.clone(),
                         tool_def: def,
                         max_retries,
+                        annotations: None,
+                        permissions,
                     },
                 )
             })
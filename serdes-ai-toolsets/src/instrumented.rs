@@ -0,0 +1,195 @@
+//! Instrumented toolset implementation.
+//!
+//! This module provides `InstrumentedToolset`, which records duration,
+//! outcome and payload sizes of every tool call into a [`ToolStats`].
+
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use serdes_ai_tools::{RunContext, ToolCallRecord, ToolError, ToolReturn, ToolStats};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{AbstractToolset, ToolsetTool};
+
+/// Records statistics for every tool call.
+///
+/// Statistics are collected per tool name in a shared [`ToolStats`], which is
+/// also exported through the `metrics` facade when the `metrics` feature is
+/// enabled.
+///
+/// # Example
+///
+/// ```ignore
+/// use serdes_ai_toolsets::{FunctionToolset, InstrumentedToolset};
+/// use std::time::Duration;
+///
+/// let toolset = InstrumentedToolset::new(FunctionToolset::new().tool(search_tool))
+///     .with_slow_threshold(Duration::from_secs(2));
+/// let stats = toolset.stats();
+///
+/// // ... after some runs
+/// for (tool, s) in stats.slowest(3) {
+///     println!("{tool}: {} calls, mean {:?}", s.calls, s.mean_duration());
+/// }
+/// ```
+pub struct InstrumentedToolset<T, Deps = ()> {
+    inner: T,
+    stats: Arc<ToolStats>,
+    _phantom: PhantomData<fn() -> Deps>,
+}
+
+impl<T, Deps> InstrumentedToolset<T, Deps>
+where
+    T: AbstractToolset<Deps>,
+{
+    /// Create an instrumented toolset with its own statistics.
+    pub fn new(inner: T) -> Self {
+        Self::with_stats(inner, Arc::new(ToolStats::new()))
+    }
+
+    /// Create an instrumented toolset that records into shared statistics.
+    pub fn with_stats(inner: T, stats: Arc<ToolStats>) -> Self {
+        Self {
+            inner,
+            stats,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Log a warning for calls slower than `threshold`.
+    ///
+    /// Replaces the statistics with a fresh collector using the threshold.
+    #[must_use]
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.stats = Arc::new(ToolStats::new().with_slow_threshold(threshold));
+        self
+    }
+
+    /// Get the collected statistics.
+    #[must_use]
+    pub fn stats(&self) -> Arc<ToolStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// Get the inner toolset.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T, Deps> AbstractToolset<Deps> for InstrumentedToolset<T, Deps>
+where
+    T: AbstractToolset<Deps>,
+    Deps: Send + Sync,
+{
+    fn id(&self) -> Option<&str> {
+        self.inner.id()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "InstrumentedToolset"
+    }
+
+    fn label(&self) -> String {
+        format!("InstrumentedToolset({})", self.inner.label())
+    }
+
+    async fn get_tools(
+        &self,
+        ctx: &RunContext<Deps>,
+    ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
+        self.inner.get_tools(ctx).await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+        tool: &ToolsetTool,
+    ) -> Result<ToolReturn, ToolError> {
+        let args_bytes = args.to_string().len();
+        let start = Instant::now();
+        let result = self.inner.call_tool(name, args, ctx, tool).await;
+
+        self.stats.record(ToolCallRecord::from_result(
+            name,
+            start.elapsed(),
+            args_bytes,
+            &result,
+        ));
+
+        result
+    }
+
+    async fn enter(&self) -> Result<(), ToolError> {
+        self.inner.enter().await
+    }
+
+    async fn exit(&self) -> Result<(), ToolError> {
+        self.inner.exit().await
+    }
+}
+
+impl<T: std::fmt::Debug, Deps> std::fmt::Debug for InstrumentedToolset<T, Deps> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedToolset")
+            .field("inner", &self.inner)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FunctionToolset;
+    use serdes_ai_tools::{Tool, ToolDefinition};
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool<()> for EchoTool {
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition::new("echo", "Echo the input")
+        }
+
+        async fn call(
+            &self,
+            _ctx: &RunContext<()>,
+            args: JsonValue,
+        ) -> Result<ToolReturn, ToolError> {
+            match args.get("text").and_then(|t| t.as_str()) {
+                Some(text) => Ok(ToolReturn::text(text)),
+                None => Err(ToolError::execution_failed("missing text")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_instrumented_records_calls() {
+        let toolset = InstrumentedToolset::new(FunctionToolset::new().tool(EchoTool));
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        let tool = tools.get("echo").unwrap();
+
+        toolset
+            .call_tool("echo", serde_json::json!({"text": "hi"}), &ctx, tool)
+            .await
+            .unwrap();
+        let result = toolset
+            .call_tool("echo", serde_json::json!({}), &ctx, tool)
+            .await;
+        assert!(result.is_err());
+
+        let stats = toolset.stats().get("echo").unwrap();
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.args_bytes, 13 + 2);
+        assert_eq!(stats.return_bytes, 2);
+    }
+}
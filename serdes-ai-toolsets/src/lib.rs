@@ -19,8 +19,12 @@
 //! - **[`RenamedToolset`]**: Rename specific tools
 //! - **[`PreparedToolset`]**: Runtime tool modification
 //! - **[`ApprovalRequiredToolset`]**: Require approval
+//! - **[`PermissionedToolset`]**: Check calls against the principal's scopes
+//! - **[`InstrumentedToolset`]**: Tool call statistics
 //! - **[`WrapperToolset`]**: Pre/post processing hooks
 //! - **[`ExternalToolset`]**: External tool execution
+//! - **`WasmToolset`**: Sandboxed WASM plugin tools (`wasm` feature)
+//! - **[`WorkspaceToolset`]**: Read, patch and test a sandboxed directory
 //!
 //! ## Example
 //!
@@ -66,31 +70,48 @@ pub mod dynamic;
 pub mod external;
 pub mod filtered;
 pub mod function;
+pub mod instrumented;
+pub mod permissioned;
 pub mod prefixed;
 pub mod prepared;
 pub mod renamed;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod workspace;
 pub mod wrapper;
 
 // Re-exports
 pub use abstract_toolset::{
-    AbstractToolset, BoxedToolset, ToolsetInfo, ToolsetResult, ToolsetTool,
+    AbstractToolset, BoxedToolset, ToolAnnotations, ToolsetInfo, ToolsetResult, ToolsetTool,
 };
 pub use approval::{checkers as approval_checkers, ApprovalRequiredToolset};
-pub use combined::CombinedToolset;
-pub use dynamic::DynamicToolset;
-pub use external::ExternalToolset;
+pub use combined::{CombinedToolset, ToolsetEvent, ToolsetEventHandler, UnavailablePolicy};
+pub use dynamic::{DynamicToolset, DynamicToolsetHandle, ToolsetChange};
+pub use external::{
+    queue_channel, ExternalCallQueue, ExternalChannel, ExternalToolCall, ExternalToolManifest,
+    ExternalToolOutcome, ExternalToolResult, ExternalToolset, PendingExternalCall, QueueChannel,
+    EXTERNAL_PROTOCOL_VERSION,
+};
 pub use filtered::{filters, FilteredToolset};
 pub use function::{AsyncFnTool, FunctionToolset};
+pub use instrumented::InstrumentedToolset;
+pub use permissioned::PermissionedToolset;
 pub use prefixed::PrefixedToolset;
 pub use prepared::{preparers, PreparedToolset};
 pub use renamed::RenamedToolset;
+#[cfg(feature = "wasm")]
+pub use wasm::{WasmLimits, WasmToolset};
+pub use workspace::{
+    WorkspaceToolset, APPLY_PATCH_TOOL, READ_FILE_RANGE_TOOL, ROLLBACK_PATCH_TOOL, RUN_TESTS_TOOL,
+};
 pub use wrapper::{LoggingWrapper, WrapperToolset};
 
 /// Prelude for common imports.
 pub mod prelude {
     pub use crate::{
         AbstractToolset, ApprovalRequiredToolset, BoxedToolset, CombinedToolset, DynamicToolset,
-        ExternalToolset, FilteredToolset, FunctionToolset, PrefixedToolset, PreparedToolset,
-        RenamedToolset, ToolsetInfo, ToolsetResult, ToolsetTool, WrapperToolset,
+        ExternalToolset, FilteredToolset, FunctionToolset, InstrumentedToolset,
+        PermissionedToolset, PrefixedToolset, PreparedToolset, RenamedToolset, ToolsetInfo,
+        ToolsetResult, ToolsetTool, WrapperToolset,
     };
 }
@@ -0,0 +1,207 @@
+//! Permission-checking toolset implementation.
+//!
+//! This module provides `PermissionedToolset`, which checks each tool call
+//! against the current principal's
+//! [`PermissionPolicy`](serdes_ai_tools::PermissionPolicy), taken from the
+//! run's dependencies.
+
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use serdes_ai_tools::permissions::authorize;
+use serdes_ai_tools::{HasPermissionPolicy, Permissions, RunContext, ToolError, ToolReturn};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::{AbstractToolset, ToolsetTool};
+
+/// Checks tool calls against the principal's permissions.
+///
+/// A call needs the scopes declared on the tool (see
+/// [`Tool::permissions`](serdes_ai_tools::Tool::permissions)) plus any
+/// required by the toolset itself. Calls the principal lacks scopes for are
+/// refused with `ToolError::PermissionDenied`, or return
+/// `ToolError::ApprovalRequired` if the policy routes them to approval.
+///
+/// # Example
+///
+/// ```ignore
+/// use serdes_ai_toolsets::{FunctionToolset, PermissionedToolset};
+/// use serdes_ai_tools::{Permissions, Principal};
+///
+/// // Deps implement `HasPermissionPolicy`, e.g. by holding a `Principal`
+/// let toolset = PermissionedToolset::new(FunctionToolset::new().tool(ReadFile))
+///     .require(Permissions::new().scope("fs:read"));
+/// ```
+pub struct PermissionedToolset<T, Deps = ()> {
+    inner: T,
+    required: Permissions,
+    _phantom: PhantomData<fn() -> Deps>,
+}
+
+impl<T, Deps> PermissionedToolset<T, Deps>
+where
+    T: AbstractToolset<Deps>,
+{
+    /// Check calls to the inner toolset's tools against their declared scopes.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            required: Permissions::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Also require these scopes for every tool of the toolset.
+    #[must_use]
+    pub fn require(mut self, permissions: Permissions) -> Self {
+        self.required = self.required.union(&permissions);
+        self
+    }
+
+    /// Get the scopes required for every tool.
+    #[must_use]
+    pub fn required(&self) -> &Permissions {
+        &self.required
+    }
+
+    /// Get the inner toolset.
+    #[must_use]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T, Deps> AbstractToolset<Deps> for PermissionedToolset<T, Deps>
+where
+    T: AbstractToolset<Deps>,
+    Deps: HasPermissionPolicy + Send + Sync,
+{
+    fn id(&self) -> Option<&str> {
+        self.inner.id()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "PermissionedToolset"
+    }
+
+    fn label(&self) -> String {
+        format!("PermissionedToolset({})", self.inner.label())
+    }
+
+    async fn get_tools(
+        &self,
+        ctx: &RunContext<Deps>,
+    ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
+        let mut tools = self.inner.get_tools(ctx).await?;
+        for tool in tools.values_mut() {
+            tool.permissions = tool.permissions.clone().union(&self.required);
+        }
+        Ok(tools)
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: JsonValue,
+        ctx: &RunContext<Deps>,
+        tool: &ToolsetTool,
+    ) -> Result<ToolReturn, ToolError> {
+        let required = tool.permissions.clone().union(&self.required);
+        authorize(ctx.deps.permission_policy(), name, &required, &args)?;
+        self.inner.call_tool(name, args, ctx, tool).await
+    }
+
+    async fn enter(&self) -> Result<(), ToolError> {
+        self.inner.enter().await
+    }
+
+    async fn exit(&self) -> Result<(), ToolError> {
+        self.inner.exit().await
+    }
+}
+
+impl<T: std::fmt::Debug, Deps> std::fmt::Debug for PermissionedToolset<T, Deps> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PermissionedToolset")
+            .field("inner", &self.inner)
+            .field("required", &self.required)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FunctionToolset;
+    use serdes_ai_tools::{Principal, Tool, ToolDefinition};
+
+    struct ReadFile;
+
+    #[async_trait]
+    impl Tool<Principal> for ReadFile {
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition::new("read_file", "Read a file")
+        }
+
+        fn permissions(&self) -> Permissions {
+            Permissions::new().scope("fs:read")
+        }
+
+        async fn call(
+            &self,
+            _ctx: &RunContext<Principal>,
+            _args: JsonValue,
+        ) -> Result<ToolReturn, ToolError> {
+            Ok(ToolReturn::text("contents"))
+        }
+    }
+
+    async fn call(
+        toolset: &PermissionedToolset<FunctionToolset<Principal>, Principal>,
+        principal: Principal,
+    ) -> Result<ToolReturn, ToolError> {
+        let ctx = RunContext::new(principal, "test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        let tool = tools.get("read_file").unwrap();
+        toolset
+            .call_tool("read_file", serde_json::json!({}), &ctx, tool)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_tool_scopes() {
+        let toolset = PermissionedToolset::new(FunctionToolset::new().tool(ReadFile));
+
+        let reader = Principal::new("reader", Permissions::new().scope("fs:read"));
+        assert!(call(&toolset, reader).await.is_ok());
+
+        let nobody = Principal::new("nobody", Permissions::new());
+        let result = call(&toolset, nobody.clone()).await;
+        assert!(matches!(
+            result,
+            Err(ToolError::PermissionDenied { ref missing, .. }) if missing == &["fs:read"]
+        ));
+
+        let result = call(&toolset, nobody.approve_missing()).await;
+        assert!(matches!(result, Err(ToolError::ApprovalRequired { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_toolset_scopes() {
+        let toolset = PermissionedToolset::new(FunctionToolset::new().tool(ReadFile))
+            .require(Permissions::new().scope("workspace:access"));
+
+        let reader = Principal::new("reader", Permissions::new().scope("fs:*"));
+        assert!(call(&toolset, reader)
+            .await
+            .unwrap_err()
+            .is_permission_denied());
+
+        let member = Principal::new(
+            "member",
+            ["fs:read", "workspace:access"].into_iter().collect(),
+        );
+        assert!(call(&toolset, member).await.is_ok());
+    }
+}
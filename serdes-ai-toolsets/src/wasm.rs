@@ -0,0 +1,382 @@
+//! WASM plugin toolset.
+//!
+//! This module provides `WasmToolset`, which loads tools from a WebAssembly
+//! component implementing the `tool-plugin` world in [`WIT`]. Plugins run
+//! sandboxed: they get no imports, a fresh instance per call, and limits on
+//! fuel (CPU) and memory, so third-party tool code can't touch the host.
+//!
+//! Requires the `wasm` feature.
+
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use serdes_ai_tools::{Permissions, RunContext, ToolDefinition, ToolError, ToolReturn};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::Path;
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Config, Engine, Store, StoreLimits, StoreLimitsBuilder, Trap};
+
+use crate::{AbstractToolset, ToolsetTool};
+
+/// WIT definition of the interface plugins implement.
+pub const WIT: &str = include_str!("../wit/tool-plugin.wit");
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit/tool-plugin.wit",
+        world: "tool-plugin",
+    });
+}
+
+use bindings::{ToolPlugin, ToolPluginPre};
+
+/// Resource limits for a single plugin call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// Fuel available per call; roughly one unit per WASM instruction.
+    pub fuel: u64,
+    /// Maximum linear memory per instance in bytes.
+    pub max_memory_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 1_000_000_000,
+            max_memory_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl WasmLimits {
+    /// Set the fuel available per call.
+    #[must_use]
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Set the maximum linear memory per instance.
+    #[must_use]
+    pub fn with_max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = bytes;
+        self
+    }
+}
+
+/// Per-call store state.
+struct PluginState {
+    limits: StoreLimits,
+}
+
+/// Toolset backed by a sandboxed WASM component.
+///
+/// # Example
+///
+/// ```ignore
+/// use serdes_ai_toolsets::{WasmLimits, WasmToolset};
+///
+/// let toolset = WasmToolset::from_file("plugins/weather.wasm")?
+///     .with_id("weather")
+///     .with_limits(WasmLimits::default().with_fuel(50_000_000));
+/// ```
+pub struct WasmToolset<Deps = ()> {
+    id: Option<String>,
+    engine: Engine,
+    pre: ToolPluginPre<PluginState>,
+    definitions: Vec<ToolDefinition>,
+    limits: WasmLimits,
+    max_retries: u32,
+    _phantom: PhantomData<fn() -> Deps>,
+}
+
+impl<Deps> WasmToolset<Deps> {
+    /// Load a plugin from component bytes.
+    ///
+    /// The plugin is instantiated once to read its tool definitions.
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, ToolError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(load_error)?;
+        let component = Component::new(&engine, bytes).map_err(load_error)?;
+        Self::from_component(engine, &component)
+    }
+
+    /// Load a plugin from a component file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ToolError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|e| {
+            ToolError::execution_failed(format!(
+                "Failed to read WASM plugin {}: {}",
+                path.as_ref().display(),
+                e
+            ))
+        })?;
+        Self::from_bytes(bytes)
+    }
+
+    fn from_component(engine: Engine, component: &Component) -> Result<Self, ToolError> {
+        // No imports: plugins can only compute on their inputs
+        let linker = Linker::new(&engine);
+        let pre = linker.instantiate_pre(component).map_err(load_error)?;
+        let pre = ToolPluginPre::new(pre).map_err(load_error)?;
+
+        let limits = WasmLimits::default();
+        let json = run(&engine, &pre, limits, |plugin, store| {
+            plugin.call_definitions(store)
+        })?;
+        let definitions: Vec<ToolDefinition> = serde_json::from_str(&json).map_err(|e| {
+            ToolError::execution_failed(format!("Invalid WASM plugin tool definitions: {}", e))
+        })?;
+
+        Ok(Self {
+            id: None,
+            engine,
+            pre,
+            definitions,
+            limits,
+            max_retries: 3,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Set the toolset ID.
+    #[must_use]
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the resource limits for each call.
+    #[must_use]
+    pub fn with_limits(mut self, limits: WasmLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set max retries.
+    #[must_use]
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Get the tool definitions provided by the plugin.
+    #[must_use]
+    pub fn definitions(&self) -> &[ToolDefinition] {
+        &self.definitions
+    }
+
+    /// Get the resource limits.
+    #[must_use]
+    pub fn limits(&self) -> WasmLimits {
+        self.limits
+    }
+}
+
+fn load_error(e: impl std::fmt::Display) -> ToolError {
+    ToolError::execution_failed(format!("Failed to load WASM plugin: {}", e))
+}
+
+/// Run `f` on a fresh, limited instance of the plugin.
+fn run<T>(
+    engine: &Engine,
+    pre: &ToolPluginPre<PluginState>,
+    limits: WasmLimits,
+    f: impl FnOnce(&ToolPlugin, &mut Store<PluginState>) -> wasmtime::Result<T>,
+) -> Result<T, ToolError> {
+    let mut store = Store::new(
+        engine,
+        PluginState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(limits.max_memory_bytes)
+                .instances(1)
+                .build(),
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store
+        .set_fuel(limits.fuel)
+        .map_err(|e| ToolError::execution_failed(e.to_string()))?;
+
+    let plugin = pre
+        .instantiate(&mut store)
+        .map_err(|e| ToolError::execution_failed(format!("WASM plugin failed to start: {}", e)))?;
+    f(&plugin, &mut store).map_err(|e| {
+        let message = match e.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => "WASM plugin exceeded its fuel limit".to_string(),
+            _ => format!("WASM plugin trapped: {}", e),
+        };
+        ToolError::execution_failed(message)
+    })
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> AbstractToolset<Deps> for WasmToolset<Deps> {
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "WasmToolset"
+    }
+
+    async fn get_tools(
+        &self,
+        _ctx: &RunContext<Deps>,
+    ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
+        Ok(self
+            .definitions
+            .iter()
+            .map(|def| {
+                (
+                    def.name.clone(),
+                    ToolsetTool {
+                        toolset_id: self.id.clone(),
+                        tool_def: def.clone(),
+                        max_retries: self.max_retries,
+                        annotations: None,
+                        permissions: Permissions::new(),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: JsonValue,
+        _ctx: &RunContext<Deps>,
+        _tool: &ToolsetTool,
+    ) -> Result<ToolReturn, ToolError> {
+        if !self.definitions.iter().any(|d| d.name == name) {
+            return Err(ToolError::not_found(name));
+        }
+
+        let engine = self.engine.clone();
+        let pre = self.pre.clone();
+        let limits = self.limits;
+        let name = name.to_string();
+        let args = args.to_string();
+
+        // WASM execution is CPU-bound; keep it off the async workers
+        let output = tokio::task::spawn_blocking(move || {
+            run(&engine, &pre, limits, |plugin, store| {
+                plugin.call_call(store, &name, &args)
+            })
+        })
+        .await
+        .map_err(|e| ToolError::execution_failed(format!("WASM plugin task failed: {}", e)))??;
+
+        output
+            .map(ToolReturn::text)
+            .map_err(ToolError::execution_failed)
+    }
+}
+
+impl<Deps> std::fmt::Debug for WasmToolset<Deps> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmToolset")
+            .field("id", &self.id)
+            .field("definitions", &self.definitions.len())
+            .field("limits", &self.limits)
+            .field("max_retries", &self.max_retries)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITIONS: &str = concat!(
+        r#"[{"name": "echo", "description": "Echo the arguments", "parameters_json_schema": {}},"#,
+        r#"{"name": "fail", "description": "Always fails", "parameters_json_schema": {}},"#,
+        r#"{"name": "spin", "description": "Never returns", "parameters_json_schema": {}}]"#,
+    );
+
+    /// Plugin component in text format; tools are dispatched on the first
+    /// letter of their name.
+    const PLUGIN: &str = r#"
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 8192))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      (local.set $ptr (global.get $heap))
+      (global.set $heap (i32.add (global.get $heap) (local.get 3)))
+      (local.get $ptr))
+    (data (i32.const 1024) "DEFINITIONS")
+    (data (i32.const 512) "boom")
+    (func (export "definitions") (result i32)
+      (i32.store (i32.const 16) (i32.const 1024))
+      (i32.store (i32.const 20) (i32.const DEFINITIONS_LEN))
+      (i32.const 16))
+    (func (export "call") (param $np i32) (param $nl i32) (param $ap i32) (param $al i32) (result i32)
+      (if (i32.eq (i32.load8_u (local.get $np)) (i32.const 115))
+        (then (loop $spin (br $spin))))
+      (if (i32.eq (i32.load8_u (local.get $np)) (i32.const 102))
+        (then
+          (i32.store8 (i32.const 32) (i32.const 1))
+          (i32.store (i32.const 36) (i32.const 512))
+          (i32.store (i32.const 40) (i32.const 4))
+          (return (i32.const 32))))
+      (i32.store8 (i32.const 32) (i32.const 0))
+      (i32.store (i32.const 36) (local.get $ap))
+      (i32.store (i32.const 40) (local.get $al))
+      (i32.const 32)))
+  (core instance $i (instantiate $m))
+  (func (export "definitions") (result string)
+    (canon lift (core func $i "definitions") (memory $i "memory") (realloc (func $i "realloc"))))
+  (func (export "call") (param "name" string) (param "args" string) (result (result string (error string)))
+    (canon lift (core func $i "call") (memory $i "memory") (realloc (func $i "realloc")))))
+"#;
+
+    fn plugin() -> WasmToolset {
+        let wat = PLUGIN
+            .replace("DEFINITIONS_LEN", &DEFINITIONS.len().to_string())
+            .replace("DEFINITIONS", &DEFINITIONS.replace('"', "\\\""));
+        WasmToolset::from_bytes(wat).unwrap().with_id("plugin")
+    }
+
+    #[tokio::test]
+    async fn test_wasm_toolset_calls_plugin() {
+        let toolset = plugin();
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        assert_eq!(tools.len(), 3);
+        assert_eq!(tools["echo"].description(), "Echo the arguments");
+
+        let result = toolset
+            .call_tool("echo", serde_json::json!({"x": 1}), &ctx, &tools["echo"])
+            .await
+            .unwrap();
+        assert_eq!(result.as_text(), Some(r#"{"x":1}"#));
+
+        let err = toolset
+            .call_tool("fail", serde_json::json!({}), &ctx, &tools["fail"])
+            .await
+            .unwrap_err();
+        assert!(err.message().contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_wasm_toolset_fuel_limit() {
+        let toolset = plugin().with_limits(WasmLimits::default().with_fuel(10_000));
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+
+        let err = toolset
+            .call_tool("spin", serde_json::json!({}), &ctx, &tools["spin"])
+            .await
+            .unwrap_err();
+        assert!(err.message().contains("fuel limit"));
+    }
+
+    #[test]
+    fn test_wasm_toolset_rejects_invalid_component() {
+        let err = WasmToolset::<()>::from_bytes(b"not wasm").unwrap_err();
+        assert!(err.message().contains("Failed to load WASM plugin"));
+    }
+}
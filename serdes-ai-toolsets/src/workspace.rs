@@ -0,0 +1,850 @@
+//! Workspace toolset for coding agents.
+//!
+//! This module provides `WorkspaceToolset`, which gives an agent access to a
+//! directory on disk through four tools:
+//!
+//! - `read_file_range`: read numbered lines of a file
+//! - `apply_patch`: apply a unified diff or a list of [`FileEdit`]s
+//! - `rollback_patch`: undo the last applied patch
+//! - `run_tests`: run the configured test command (only if one is set)
+//!
+//! Every path is resolved inside the workspace root; `..`, absolute paths and
+//! symlinks leading outside it are rejected. Patches are checked against the
+//! current files before anything is written, so a patch either applies
+//! completely or not at all, and each applied patch is recorded so it can be
+//! rolled back. In dry-run mode patches are only checked.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use serdes_ai_toolsets::WorkspaceToolset;
+//!
+//! let workspace = WorkspaceToolset::new("./sandbox")?
+//!     .with_test_command(["cargo", "test", "--quiet"])
+//!     .with_test_timeout(std::time::Duration::from_secs(300));
+//! ```
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serdes_ai_core::code_edits::check_path;
+use serdes_ai_core::{CodeEdits, FileEdit, WorkspaceSnapshot};
+use serdes_ai_tools::{
+    Permissions, RunContext, SchemaBuilder, ToolDefinition, ToolError, ToolReturn,
+};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use crate::{AbstractToolset, ToolAnnotations, ToolsetTool};
+
+/// Name of the tool applying patches.
+pub const APPLY_PATCH_TOOL: &str = "apply_patch";
+/// Name of the tool reading line ranges of files.
+pub const READ_FILE_RANGE_TOOL: &str = "read_file_range";
+/// Name of the tool undoing the last patch.
+pub const ROLLBACK_PATCH_TOOL: &str = "rollback_patch";
+/// Name of the tool running the test command.
+pub const RUN_TESTS_TOOL: &str = "run_tests";
+
+/// Contents of the files a patch touched, before it was applied.
+///
+/// `None` means the file did not exist.
+type Checkpoint = Vec<(String, Option<String>)>;
+
+/// Toolset reading, patching and testing a sandboxed directory.
+pub struct WorkspaceToolset<Deps = ()> {
+    id: Option<String>,
+    root: PathBuf,
+    dry_run: bool,
+    test_command: Option<Vec<String>>,
+    test_timeout: Duration,
+    max_read_lines: usize,
+    max_output_chars: usize,
+    max_retries: u32,
+    history: Mutex<Vec<Checkpoint>>,
+    _phantom: PhantomData<fn() -> Deps>,
+}
+
+impl<Deps> WorkspaceToolset<Deps> {
+    /// Create a toolset over an existing directory.
+    pub fn new(root: impl AsRef<Path>) -> Result<Self, ToolError> {
+        let root = root.as_ref();
+        let root = root.canonicalize().map_err(|e| {
+            ToolError::execution_failed(format!("Invalid workspace {}: {}", root.display(), e))
+        })?;
+        if !root.is_dir() {
+            return Err(ToolError::execution_failed(format!(
+                "Workspace {} is not a directory",
+                root.display()
+            )));
+        }
+        Ok(Self {
+            id: None,
+            root,
+            dry_run: false,
+            test_command: None,
+            test_timeout: Duration::from_secs(600),
+            max_read_lines: 400,
+            max_output_chars: 8_000,
+            max_retries: 3,
+            history: Mutex::new(Vec::new()),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Set the toolset ID.
+    #[must_use]
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Only check patches, never write them.
+    #[must_use]
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set the command run by `run_tests`, e.g. `["cargo", "test"]`.
+    ///
+    /// The command runs in the workspace root; a filter given by the model is
+    /// appended as the last argument.
+    #[must_use]
+    pub fn with_test_command<I, S>(mut self, command: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let command: Vec<String> = command.into_iter().map(Into::into).collect();
+        self.test_command = (!command.is_empty()).then_some(command);
+        self
+    }
+
+    /// Set how long the test command may run before it is killed.
+    #[must_use]
+    pub fn with_test_timeout(mut self, timeout: Duration) -> Self {
+        self.test_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum number of lines returned by one `read_file_range` call.
+    #[must_use]
+    pub fn with_max_read_lines(mut self, lines: usize) -> Self {
+        self.max_read_lines = lines.max(1);
+        self
+    }
+
+    /// Set how much of the test output is returned; the tail is kept.
+    #[must_use]
+    pub fn with_max_output_chars(mut self, chars: usize) -> Self {
+        self.max_output_chars = chars;
+        self
+    }
+
+    /// Set max retries.
+    #[must_use]
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Get the workspace root.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check if patches are only checked.
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Number of applied patches that can be rolled back.
+    #[must_use]
+    pub fn checkpoints(&self) -> usize {
+        self.history.lock().len()
+    }
+
+    /// Paths changed by the applied patches, sorted.
+    #[must_use]
+    pub fn changed_files(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .history
+            .lock()
+            .iter()
+            .flatten()
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Read the current contents of files; missing files are left out.
+    pub async fn snapshot<I, S>(&self, paths: I) -> Result<WorkspaceSnapshot, ToolError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut snapshot = WorkspaceSnapshot::new();
+        for path in paths {
+            let path = path.as_ref();
+            if let Some(content) = self.read(path).await? {
+                snapshot.insert(path, content);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Check edits against the current files and apply them.
+    ///
+    /// Returns the status of each touched file. Nothing is written if any
+    /// edit fails to apply, or if `dry_run` is set.
+    pub async fn apply(&self, edits: &CodeEdits, dry_run: bool) -> Result<JsonValue, ToolError> {
+        let mut paths: Vec<&str> = edits.edits.iter().map(FileEdit::path).collect();
+        paths.sort_unstable();
+        paths.dedup();
+        for path in &paths {
+            self.resolve(path)?;
+        }
+
+        let before = self.snapshot(&paths).await?;
+        let errors = edits.check(&before);
+        if !errors.is_empty() {
+            let problems: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            return Err(ToolError::model_retry(format!(
+                "The patch does not apply; nothing was changed.\n{}",
+                problems.join("\n")
+            )));
+        }
+        let after = edits
+            .apply(&before)
+            .map_err(|e| ToolError::model_retry(e.to_string()))?;
+
+        let mut files = Vec::new();
+        let mut checkpoint = Checkpoint::new();
+        for path in paths {
+            let (old, new) = (before.get(path), after.get(path));
+            let status = match (old, new) {
+                (None, Some(_)) => "created",
+                (Some(_), None) => "deleted",
+                (Some(a), Some(b)) if a != b => "modified",
+                _ => continue,
+            };
+            files.push(serde_json::json!({
+                "path": path,
+                "status": status,
+                "lines": new.map_or(0, |c| c.lines().count()),
+            }));
+            checkpoint.push((path.to_string(), old.map(str::to_string)));
+        }
+
+        let dry_run = dry_run || self.dry_run;
+        if !dry_run && !checkpoint.is_empty() {
+            let changes: Vec<(String, Option<String>)> = checkpoint
+                .iter()
+                .map(|(path, _)| (path.clone(), after.get(path).map(str::to_string)))
+                .collect();
+            if let Err(e) = self.write_all(&changes).await {
+                // Restore whatever was written before the failure
+                let _ = self.write_all(&checkpoint).await;
+                return Err(e);
+            }
+            self.history.lock().push(checkpoint);
+        }
+
+        Ok(serde_json::json!({
+            "applied": !dry_run,
+            "dry_run": dry_run,
+            "files": files,
+        }))
+    }
+
+    /// Undo the last applied patch, returning the restored paths.
+    pub async fn rollback_last(&self) -> Result<Vec<String>, ToolError> {
+        let Some(checkpoint) = self.history.lock().pop() else {
+            return Ok(Vec::new());
+        };
+        if let Err(e) = self.write_all(&checkpoint).await {
+            self.history.lock().push(checkpoint);
+            return Err(e);
+        }
+        Ok(checkpoint.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// Undo every applied patch, newest first, returning the restored paths.
+    pub async fn rollback_all(&self) -> Result<Vec<String>, ToolError> {
+        let mut restored = Vec::new();
+        while self.checkpoints() > 0 {
+            restored.extend(self.rollback_last().await?);
+        }
+        restored.sort();
+        restored.dedup();
+        Ok(restored)
+    }
+
+    /// Resolve a relative path to a location inside the root.
+    ///
+    /// Symlinks are followed one component at a time, so links pointing out
+    /// of the workspace are rejected even when their target does not exist.
+    fn resolve(&self, path: &str) -> Result<PathBuf, ToolError> {
+        check_path(path).map_err(|e| ToolError::model_retry(format!("{}: {}", path, e)))?;
+        let full = self.root.join(path);
+        let real = real_path(&full, 0)
+            .map_err(|e| ToolError::execution_failed(format!("{}: {}", path, e)))?;
+        if !real.starts_with(&self.root) {
+            return Err(ToolError::model_retry(format!(
+                "{}: path is outside the workspace",
+                path
+            )));
+        }
+        Ok(full)
+    }
+
+    async fn read(&self, path: &str) -> Result<Option<String>, ToolError> {
+        let full = self.resolve(path)?;
+        if !full.exists() {
+            return Ok(None);
+        }
+        if full.is_dir() {
+            return Err(ToolError::model_retry(format!("{}: is a directory", path)));
+        }
+        let bytes = tokio::fs::read(&full)
+            .await
+            .map_err(|e| ToolError::execution_failed(format!("Failed to read {}: {}", path, e)))?;
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|_| ToolError::model_retry(format!("{}: not a UTF-8 text file", path)))
+    }
+
+    /// Write or delete files; `None` deletes.
+    async fn write_all(&self, changes: &[(String, Option<String>)]) -> Result<(), ToolError> {
+        for (path, content) in changes {
+            let full = self.resolve(path)?;
+            let result = match content {
+                Some(content) => {
+                    if let Some(parent) = full.parent() {
+                        tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                            ToolError::execution_failed(format!(
+                                "Failed to create {}: {}",
+                                parent.display(),
+                                e
+                            ))
+                        })?;
+                    }
+                    tokio::fs::write(&full, content).await
+                }
+                None if full.exists() => tokio::fs::remove_file(&full).await,
+                None => Ok(()),
+            };
+            result.map_err(|e| {
+                ToolError::execution_failed(format!("Failed to write {}: {}", path, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn read_file_range(&self, args: ReadRangeArgs) -> Result<ToolReturn, ToolError> {
+        let content = self
+            .read(&args.path)
+            .await?
+            .ok_or_else(|| ToolError::model_retry(format!("{}: file does not exist", args.path)))?;
+        let lines: Vec<&str> = content.lines().collect();
+        let start = args.start_line.unwrap_or(1).max(1);
+        let last = start.saturating_add(self.max_read_lines - 1);
+        let end = args.end_line.unwrap_or(last).min(last).min(lines.len());
+        if args.end_line.is_some_and(|end_line| end_line < start) {
+            return Err(ToolError::model_retry(format!(
+                "end_line must not be before start_line {}",
+                start
+            )));
+        }
+        if lines.is_empty() {
+            return Ok(ToolReturn::text(format!("{} is empty", args.path)));
+        }
+        if start > lines.len() {
+            return Err(ToolError::model_retry(format!(
+                "{} has only {} lines",
+                args.path,
+                lines.len()
+            )));
+        }
+
+        let width = end.to_string().len();
+        let mut out = format!(
+            "{} (lines {}-{} of {})\n",
+            args.path,
+            start,
+            end,
+            lines.len()
+        );
+        for (number, line) in (start..=end).zip(&lines[start - 1..end]) {
+            out.push_str(&format!("{:>width$} | {}\n", number, line, width = width));
+        }
+        Ok(ToolReturn::text(out))
+    }
+
+    async fn run_tests(&self, args: RunTestsArgs) -> Result<ToolReturn, ToolError> {
+        let command = self
+            .test_command
+            .as_ref()
+            .ok_or_else(|| ToolError::not_found(RUN_TESTS_TOOL))?;
+        let mut cmd = tokio::process::Command::new(&command[0]);
+        cmd.args(&command[1..])
+            .current_dir(&self.root)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        if let Some(filter) = args.filter.filter(|f| !f.trim().is_empty()) {
+            cmd.arg(filter);
+        }
+
+        let output = match tokio::time::timeout(self.test_timeout, cmd.output()).await {
+            Ok(output) => output.map_err(|e| {
+                ToolError::execution_failed(format!("Failed to run {}: {}", command[0], e))
+            })?,
+            Err(_) => {
+                return Ok(ToolReturn::json(serde_json::json!({
+                    "success": false,
+                    "timed_out": true,
+                    "output": format!("Tests did not finish within {:?}", self.test_timeout),
+                })))
+            }
+        };
+
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(ToolReturn::json(serde_json::json!({
+            "success": output.status.success(),
+            "exit_code": output.status.code(),
+            "output": tail(&text, self.max_output_chars),
+        })))
+    }
+
+    fn definitions(&self) -> Vec<(ToolDefinition, bool)> {
+        let schema = |builder: SchemaBuilder| {
+            builder
+                .build()
+                .expect("SchemaBuilder JSON serialization failed")
+        };
+        let mut defs = vec![
+            (
+                ToolDefinition::new(
+                    READ_FILE_RANGE_TOOL,
+                    "Read lines of a file in the workspace, with line numbers",
+                )
+                .with_parameters(schema(
+                    SchemaBuilder::new()
+                        .string("path", "Path relative to the workspace root", true)
+                        .integer("start_line", "First line to read, from 1", false)
+                        .integer("end_line", "Last line to read, inclusive", false),
+                )),
+                true,
+            ),
+            (
+                ToolDefinition::new(
+                    APPLY_PATCH_TOOL,
+                    "Change files in the workspace, either with a unified diff or with \
+                     search/replace edits. The patch applies completely or not at all.",
+                )
+                .with_parameters(schema(
+                    SchemaBuilder::new()
+                        .string(
+                            "patch",
+                            "Unified diff of one or more files, as produced by `git diff`",
+                            false,
+                        )
+                        .raw(
+                            "edits",
+                            CodeEdits::json_schema()["properties"]["edits"].clone(),
+                            false,
+                        )
+                        .boolean(
+                            "dry_run",
+                            "Only check that the patch applies, without writing",
+                            false,
+                        ),
+                )),
+                false,
+            ),
+            (
+                ToolDefinition::new(ROLLBACK_PATCH_TOOL, "Undo the last applied patch")
+                    .with_parameters(schema(SchemaBuilder::new())),
+                false,
+            ),
+        ];
+        if self.test_command.is_some() {
+            defs.push((
+                ToolDefinition::new(
+                    RUN_TESTS_TOOL,
+                    "Run the workspace's tests and return the end of their output",
+                )
+                .with_parameters(schema(SchemaBuilder::new().string(
+                    "filter",
+                    "Only run tests matching this filter",
+                    false,
+                ))),
+                true,
+            ));
+        }
+        defs
+    }
+}
+
+#[derive(Deserialize)]
+struct ReadRangeArgs {
+    path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ApplyPatchArgs {
+    patch: Option<String>,
+    edits: Option<Vec<FileEdit>>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+struct RunTestsArgs {
+    filter: Option<String>,
+}
+
+/// Keep the last `max` characters of `text`.
+fn tail(text: &str, max: usize) -> String {
+    let count = text.chars().count();
+    if count <= max {
+        return text.to_string();
+    }
+    let skipped: String = text.chars().skip(count - max).collect();
+    format!("[... {} characters omitted]\n{}", count - max, skipped)
+}
+
+fn parse_args<T: serde::de::DeserializeOwned>(name: &str, args: JsonValue) -> Result<T, ToolError> {
+    serde_json::from_value(args).map_err(|e| ToolError::invalid_arguments(name, e.to_string()))
+}
+
+#[async_trait]
+impl<Deps: Send + Sync> AbstractToolset<Deps> for WorkspaceToolset<Deps> {
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn type_name(&self) -> &'static str {
+        "WorkspaceToolset"
+    }
+
+    async fn get_tools(
+        &self,
+        _ctx: &RunContext<Deps>,
+    ) -> Result<HashMap<String, ToolsetTool>, ToolError> {
+        Ok(self
+            .definitions()
+            .into_iter()
+            .map(|(def, read_only)| {
+                let annotations = ToolAnnotations {
+                    read_only_hint: Some(read_only),
+                    open_world_hint: Some(false),
+                    ..Default::default()
+                };
+                (
+                    def.name.clone(),
+                    ToolsetTool {
+                        toolset_id: self.id.clone(),
+                        tool_def: def,
+                        max_retries: self.max_retries,
+                        annotations: Some(annotations),
+                        permissions: Permissions::new(),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        args: JsonValue,
+        _ctx: &RunContext<Deps>,
+        _tool: &ToolsetTool,
+    ) -> Result<ToolReturn, ToolError> {
+        match name {
+            READ_FILE_RANGE_TOOL => self.read_file_range(parse_args(name, args)?).await,
+            APPLY_PATCH_TOOL => {
+                let args: ApplyPatchArgs = parse_args(name, args)?;
+                let edits = match (args.patch, args.edits) {
+                    (Some(patch), None) => CodeEdits::from_patch(&patch)
+                        .map_err(|e| ToolError::model_retry(e.to_string()))?,
+                    (None, Some(edits)) => CodeEdits::new(edits),
+                    _ => {
+                        return Err(ToolError::invalid_arguments(
+                            name,
+                            "give exactly one of `patch` or `edits`",
+                        ))
+                    }
+                };
+                self.apply(&edits, args.dry_run).await.map(ToolReturn::json)
+            }
+            ROLLBACK_PATCH_TOOL => {
+                let restored = self.rollback_last().await?;
+                if restored.is_empty() {
+                    return Ok(ToolReturn::text("No patch to roll back"));
+                }
+                Ok(ToolReturn::json(
+                    serde_json::json!({ "restored": restored }),
+                ))
+            }
+            RUN_TESTS_TOOL => self.run_tests(parse_args(name, args)?).await,
+            _ => Err(ToolError::not_found(name)),
+        }
+    }
+}
+
+impl<Deps> std::fmt::Debug for WorkspaceToolset<Deps> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkspaceToolset")
+            .field("id", &self.id)
+            .field("root", &self.root)
+            .field("dry_run", &self.dry_run)
+            .field("test_command", &self.test_command)
+            .field("checkpoints", &self.checkpoints())
+            .finish()
+    }
+}
+
+/// Symlinks followed before giving up, like `ELOOP`.
+const MAX_SYMLINKS: usize = 40;
+
+/// Resolve every symlink of an absolute path, including dangling ones.
+///
+/// Components that do not exist are kept as they are.
+fn real_path(path: &Path, links: usize) -> std::io::Result<PathBuf> {
+    let mut real = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => {
+                let next = real.join(component);
+                match std::fs::symlink_metadata(&next) {
+                    Ok(meta) if meta.file_type().is_symlink() => {
+                        if links >= MAX_SYMLINKS {
+                            return Err(std::io::Error::other("too many levels of symlinks"));
+                        }
+                        let target = std::fs::read_link(&next)?;
+                        real = real_path(&real.join(target), links + 1)?;
+                    }
+                    _ => real = next,
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                real.pop();
+            }
+        }
+    }
+    Ok(real)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "serdes-ai-workspace-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        dir
+    }
+
+    async fn call(
+        toolset: &WorkspaceToolset,
+        name: &str,
+        args: JsonValue,
+    ) -> Result<ToolReturn, ToolError> {
+        let ctx = RunContext::minimal("test");
+        let tools = toolset.get_tools(&ctx).await.unwrap();
+        toolset.call_tool(name, args, &ctx, &tools[name]).await
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_and_rollback() {
+        let dir = temp_workspace("apply");
+        let toolset = WorkspaceToolset::new(&dir).unwrap();
+
+        let patch = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n";
+        let result = call(
+            &toolset,
+            APPLY_PATCH_TOOL,
+            serde_json::json!({"patch": patch}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.as_json().unwrap()["files"][0]["status"], "modified");
+
+        let edits = serde_json::json!([{"path": "src/new.rs", "search": "", "replace": "x\n"}]);
+        call(
+            &toolset,
+            APPLY_PATCH_TOOL,
+            serde_json::json!({"edits": edits}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "fn a() {}\nfn c() {}\n"
+        );
+        assert_eq!(toolset.changed_files(), vec!["src/lib.rs", "src/new.rs"]);
+
+        // A failing edit leaves every file untouched
+        let edits = serde_json::json!([
+            {"path": "src/lib.rs", "search": "fn a() {}", "replace": "fn z() {}"},
+            {"path": "src/lib.rs", "search": "missing", "replace": ""}
+        ]);
+        let err = call(
+            &toolset,
+            APPLY_PATCH_TOOL,
+            serde_json::json!({"edits": edits}),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is_model_retry());
+        assert!(err.to_string().contains("edit 1"));
+
+        call(&toolset, ROLLBACK_PATCH_TOOL, serde_json::json!({}))
+            .await
+            .unwrap();
+        assert!(!dir.join("src/new.rs").exists());
+        toolset.rollback_all().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "fn a() {}\nfn b() {}\n"
+        );
+        assert_eq!(toolset.checkpoints(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_and_sandbox() {
+        let dir = temp_workspace("dry-run");
+        let toolset = WorkspaceToolset::new(&dir).unwrap().with_dry_run(true);
+
+        let edits =
+            serde_json::json!([{"path": "src/lib.rs", "search": "fn b() {}", "replace": ""}]);
+        let result = call(
+            &toolset,
+            APPLY_PATCH_TOOL,
+            serde_json::json!({"edits": edits}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.as_json().unwrap()["applied"], false);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "fn a() {}\nfn b() {}\n"
+        );
+        assert_eq!(toolset.checkpoints(), 0);
+
+        for path in ["../outside.rs", "/etc/passwd"] {
+            let err = call(
+                &toolset,
+                READ_FILE_RANGE_TOOL,
+                serde_json::json!({"path": path}),
+            )
+            .await
+            .unwrap_err();
+            assert!(err.is_model_retry(), "{path}");
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlinks_outside_are_rejected() {
+        let dir = temp_workspace("symlinks");
+        let outside = dir.with_extension("outside");
+        let _ = std::fs::remove_dir_all(&outside);
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(outside.join("missing.rs"), dir.join("dangling.rs")).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("out")).unwrap();
+        std::os::unix::fs::symlink("lib.rs", dir.join("src/alias.rs")).unwrap();
+        let toolset = WorkspaceToolset::new(&dir).unwrap();
+
+        for path in ["dangling.rs", "out/new.rs"] {
+            let edits = serde_json::json!([{"path": path, "search": "", "replace": "x\n"}]);
+            let err = call(
+                &toolset,
+                APPLY_PATCH_TOOL,
+                serde_json::json!({"edits": edits}),
+            )
+            .await
+            .unwrap_err();
+            assert!(err.is_model_retry(), "{path}");
+        }
+        assert!(!outside.join("missing.rs").exists());
+        assert!(!outside.join("new.rs").exists());
+
+        // Links that stay inside the workspace still work
+        let result = call(
+            &toolset,
+            READ_FILE_RANGE_TOOL,
+            serde_json::json!({"path": "src/alias.rs", "end_line": 1}),
+        )
+        .await
+        .unwrap();
+        assert!(result.as_text().unwrap().contains("fn a() {}"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_file_range_and_run_tests() {
+        let dir = temp_workspace("read");
+        let toolset = WorkspaceToolset::new(&dir).unwrap();
+        let ctx = RunContext::minimal("test");
+        assert!(!toolset
+            .get_tools(&ctx)
+            .await
+            .unwrap()
+            .contains_key(RUN_TESTS_TOOL));
+
+        let result = call(
+            &toolset,
+            READ_FILE_RANGE_TOOL,
+            serde_json::json!({"path": "src/lib.rs", "start_line": 2}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            result.as_text().unwrap(),
+            "src/lib.rs (lines 2-2 of 2)\n2 | fn b() {}\n"
+        );
+
+        let err = call(
+            &toolset,
+            READ_FILE_RANGE_TOOL,
+            serde_json::json!({"path": "src/lib.rs", "start_line": 2, "end_line": 1}),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is_model_retry());
+
+        let toolset = toolset.with_test_command(["sh", "-c", "echo ok; exit 3"]);
+        let result = call(&toolset, RUN_TESTS_TOOL, serde_json::json!({}))
+            .await
+            .unwrap();
+        let result = result.as_json().unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["exit_code"], 3);
+        assert_eq!(result["output"], "ok\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+//! Bridge for frontend-executed tools.
+//!
+//! Some tools can only run in the browser (reading the user's location,
+//! asking for confirmation, manipulating the UI). With AG-UI the agent streams
+//! the tool call to the client, the run pauses, and the client posts a new
+//! [`RunAgentInput`] containing the tool result. [`FrontendToolBridge`] tracks
+//! those pending calls and turns the incoming tool messages back into
+//! [`DeferredToolResults`] and a [`ModelRequest`] the agent can resume from.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use serdes_ai_ui::ag_ui::{FrontendToolBridge, RunAgentInput};
+//!
+//! // First request: stream the run and remember frontend tool calls.
+//! let mut bridge = FrontendToolBridge::from_input(&input);
+//! while let Some(event) = agent_stream.next().await {
+//!     bridge.observe(&event);
+//!     // ... forward to AgUiEventStream
+//! }
+//!
+//! // Follow-up request from the client carrying the tool results.
+//! let input = RunAgentInput::from_json(&body)?;
+//! let results = bridge.ingest(&input);
+//! let mut history = previous_history;
+//! history.push(bridge.to_model_request(&results));
+//! ```
+
+use super::input::{apply_state_delta, Message, RunAgentInput, StateDeltaError, ToolMessage};
+use super::types::StateDeltaEvent;
+use serde_json::Value;
+use serdes_ai_core::messages::ToolReturnPart;
+use serdes_ai_core::{ModelRequest, ModelRequestPart};
+use serdes_ai_streaming::AgentStreamEvent;
+use serdes_ai_tools::{
+    DeferredToolCall, DeferredToolRequests, DeferredToolResult, DeferredToolResults, ToolReturn,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Tracks frontend tool calls and ingests their results from the client.
+#[derive(Debug, Default)]
+pub struct FrontendToolBridge {
+    /// Names of tools executed by the frontend.
+    frontend_tools: HashSet<String>,
+    /// Tool call IDs by stream index, for calls still streaming.
+    streaming_ids: HashMap<usize, Option<String>>,
+    /// Calls waiting for a result from the frontend.
+    pending: DeferredToolRequests,
+    /// Tool names by call ID for results that were ingested.
+    resolved_names: HashMap<String, String>,
+    /// Client-side state.
+    state: Value,
+}
+
+impl FrontendToolBridge {
+    /// Create a bridge for the given frontend tool names.
+    pub fn new<I, S>(frontend_tools: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            frontend_tools: frontend_tools.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Create a bridge from a client input.
+    ///
+    /// Frontend tools and state are taken from the input. Tool calls in the
+    /// last assistant message that target frontend tools become pending, so a
+    /// stateless server can rebuild the bridge on every request.
+    pub fn from_input(input: &RunAgentInput) -> Self {
+        let mut bridge = Self::new(input.tools.iter().map(|t| t.name.clone()));
+        bridge.state = input.state.clone();
+
+        let last_assistant = input.messages.iter().rev().find_map(|m| match m {
+            Message::Assistant(a) => Some(a),
+            _ => None,
+        });
+        if let Some(assistant) = last_assistant {
+            for call in &assistant.tool_calls {
+                if bridge.is_frontend_tool(&call.function.name) {
+                    let args = serde_json::from_str(&call.function.arguments)
+                        .unwrap_or(Value::Object(Default::default()));
+                    bridge.pending.add(
+                        DeferredToolCall::new(&call.function.name, args)
+                            .with_tool_call_id(&call.id),
+                    );
+                }
+            }
+        }
+
+        bridge
+    }
+
+    /// Check whether a tool is executed by the frontend.
+    pub fn is_frontend_tool(&self, name: &str) -> bool {
+        self.frontend_tools.contains(name)
+    }
+
+    /// Observe an agent stream event.
+    ///
+    /// Returns `true` if the event completed a frontend tool call, which is
+    /// now pending until the client posts its result.
+    pub fn observe<O>(&mut self, event: &AgentStreamEvent<O>) -> bool {
+        match event {
+            AgentStreamEvent::ToolCallStart {
+                name,
+                tool_call_id,
+                index,
+            } if self.is_frontend_tool(name) => {
+                let id = tool_call_id
+                    .clone()
+                    .or_else(|| Some(format!("call-{}", index)));
+                self.streaming_ids.insert(*index, id);
+                false
+            }
+            AgentStreamEvent::ToolCallComplete { name, args, index }
+                if self.is_frontend_tool(name) =>
+            {
+                let mut call = DeferredToolCall::new(name, args.clone());
+                if let Some(Some(id)) = self.streaming_ids.remove(index) {
+                    call = call.with_tool_call_id(id);
+                }
+                self.pending.add(call);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get the calls waiting for a frontend result.
+    pub fn pending(&self) -> &DeferredToolRequests {
+        &self.pending
+    }
+
+    /// Check whether the run is paused on frontend tools.
+    pub fn is_paused(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Get the client-side state.
+    pub fn state(&self) -> &Value {
+        &self.state
+    }
+
+    /// Apply a `STATE_DELTA` event sent by the client.
+    pub fn apply_state_delta(&mut self, event: &StateDeltaEvent) -> Result<(), StateDeltaError> {
+        apply_state_delta(&mut self.state, &event.delta)
+    }
+
+    /// Ingest a client input, returning results for pending frontend calls.
+    ///
+    /// Tool messages answering a pending call are converted to
+    /// [`DeferredToolResult`]s and the call is no longer pending. Messages for
+    /// unknown call IDs are ignored. The bridge state is replaced by the
+    /// input state.
+    pub fn ingest(&mut self, input: &RunAgentInput) -> DeferredToolResults {
+        if !input.state.is_null() {
+            self.state = input.state.clone();
+        }
+
+        let mut results = DeferredToolResults::new();
+        for message in input.tool_messages() {
+            let Some(pos) = self
+                .pending
+                .calls
+                .iter()
+                .position(|c| c.tool_call_id.as_deref() == Some(message.tool_call_id.as_str()))
+            else {
+                continue;
+            };
+
+            let call = self.pending.calls.remove(pos);
+            self.resolved_names
+                .insert(message.tool_call_id.clone(), call.tool_name);
+            results.add(
+                DeferredToolResult::new(tool_return_from_message(message))
+                    .with_tool_call_id(&message.tool_call_id),
+            );
+        }
+
+        results
+    }
+
+    /// Build the request that feeds ingested results back into the agent.
+    ///
+    /// Append the returned request to the message history of the paused run.
+    pub fn to_model_request(&self, results: &DeferredToolResults) -> ModelRequest {
+        let mut req = ModelRequest::new();
+        for result in &results.results {
+            let tool_name = result
+                .tool_call_id
+                .as_ref()
+                .and_then(|id| self.resolved_names.get(id))
+                .map(String::as_str)
+                .unwrap_or("unknown");
+            let mut part = ToolReturnPart::new(tool_name, result.result.content.clone());
+            if let Some(ref id) = result.tool_call_id {
+                part = part.with_tool_call_id(id);
+            }
+            req.add_part(ModelRequestPart::ToolReturn(part));
+        }
+        req
+    }
+}
+
+/// Convert an AG-UI tool message into a [`ToolReturn`].
+fn tool_return_from_message(message: &ToolMessage) -> ToolReturn {
+    if let Some(ref error) = message.error {
+        return ToolReturn::error(error);
+    }
+    match message.content_value() {
+        Value::String(text) => ToolReturn::text(text),
+        value => ToolReturn::json(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn input_with_result(call_id: &str, content: &str) -> RunAgentInput {
+        RunAgentInput::from_json(
+            &json!({
+                "threadId": "t",
+                "runId": "r2",
+                "state": {"step": 2},
+                "messages": [
+                    {"role": "tool", "id": "m1", "toolCallId": call_id, "content": content}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_observe_frontend_tool_call() {
+        let mut bridge = FrontendToolBridge::new(["confirm"]);
+
+        let start: AgentStreamEvent<()> = AgentStreamEvent::ToolCallStart {
+            name: "confirm".into(),
+            tool_call_id: Some("call-1".into()),
+            index: 0,
+        };
+        assert!(!bridge.observe(&start));
+
+        let complete: AgentStreamEvent<()> = AgentStreamEvent::ToolCallComplete {
+            name: "confirm".into(),
+            args: json!({"question": "Proceed?"}),
+            index: 0,
+        };
+        assert!(bridge.observe(&complete));
+        assert!(bridge.is_paused());
+        assert_eq!(
+            bridge.pending().get(0).unwrap().tool_call_id.as_deref(),
+            Some("call-1")
+        );
+    }
+
+    #[test]
+    fn test_observe_ignores_backend_tools() {
+        let mut bridge = FrontendToolBridge::new(["confirm"]);
+        let complete: AgentStreamEvent<()> = AgentStreamEvent::ToolCallComplete {
+            name: "search".into(),
+            args: json!({}),
+            index: 0,
+        };
+        assert!(!bridge.observe(&complete));
+        assert!(!bridge.is_paused());
+    }
+
+    #[test]
+    fn test_ingest_and_resume() {
+        let mut bridge = FrontendToolBridge::new(["confirm"]);
+        bridge.observe(&AgentStreamEvent::<()>::ToolCallStart {
+            name: "confirm".into(),
+            tool_call_id: Some("call-1".into()),
+            index: 0,
+        });
+        bridge.observe(&AgentStreamEvent::<()>::ToolCallComplete {
+            name: "confirm".into(),
+            args: json!({}),
+            index: 0,
+        });
+
+        let results = bridge.ingest(&input_with_result("call-1", r#"{"confirmed":true}"#));
+        assert_eq!(results.len(), 1);
+        assert!(!bridge.is_paused());
+        assert_eq!(bridge.state(), &json!({"step": 2}));
+
+        let req = bridge.to_model_request(&results);
+        let returns: Vec<_> = req.tool_returns().collect();
+        assert_eq!(returns.len(), 1);
+        assert_eq!(returns[0].tool_name, "confirm");
+        assert_eq!(returns[0].tool_call_id.as_deref(), Some("call-1"));
+    }
+
+    #[test]
+    fn test_ingest_ignores_unknown_calls() {
+        let mut bridge = FrontendToolBridge::new(["confirm"]);
+        let results = bridge.ingest(&input_with_result("call-9", "ok"));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_from_input_rebuilds_pending() {
+        let input = RunAgentInput::from_json(
+            &json!({
+                "threadId": "t",
+                "runId": "r",
+                "messages": [
+                    {"role": "user", "id": "m1", "content": "hi"},
+                    {"role": "assistant", "id": "m2", "toolCalls": [
+                        {"id": "call-1", "type": "function",
+                         "function": {"name": "confirm", "arguments": "{\"q\":1}"}},
+                        {"id": "call-2", "type": "function",
+                         "function": {"name": "search", "arguments": "{}"}}
+                    ]},
+                    {"role": "tool", "id": "m3", "toolCallId": "call-1", "content": "", "error": "User declined"}
+                ],
+                "tools": [{"name": "confirm"}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut bridge = FrontendToolBridge::from_input(&input);
+        assert_eq!(bridge.pending().len(), 1);
+
+        let results = bridge.ingest(&input);
+        assert_eq!(results.len(), 1);
+        assert!(results.results[0].result.is_error());
+    }
+
+    #[test]
+    fn test_apply_state_delta_event() {
+        let mut bridge = FrontendToolBridge::new(Vec::<String>::new());
+        bridge.state = json!({"count": 1});
+        let event = StateDeltaEvent::new(json!([
+            {"op": "replace", "path": "/count", "value": 3}
+        ]));
+        bridge.apply_state_delta(&event).unwrap();
+        assert_eq!(bridge.state(), &json!({"count": 3}));
+    }
+}
@@ -0,0 +1,475 @@
+//! AG-UI incoming message types.
+//!
+//! The AG-UI protocol is bidirectional: besides the events emitted by the
+//! agent, the client posts a `RunAgentInput` payload to start or resume a run.
+//! That payload carries the conversation messages (including results of tools
+//! executed in the frontend), the client-side state and the tools the frontend
+//! is able to execute.
+//!
+//! Clients may also send `STATE_DELTA` events whose `delta` is an
+//! [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) JSON Patch.
+//! [`apply_state_delta`] applies such a patch to a state document.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Input posted by an AG-UI client to start or resume a run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunAgentInput {
+    /// Thread identifier.
+    pub thread_id: String,
+    /// Run identifier.
+    pub run_id: String,
+    /// Client-side state.
+    #[serde(default)]
+    pub state: Value,
+    /// Conversation messages.
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Tools the frontend can execute.
+    #[serde(default)]
+    pub tools: Vec<Tool>,
+    /// Additional context provided by the client.
+    #[serde(default)]
+    pub context: Vec<Context>,
+    /// Arbitrary properties forwarded by the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forwarded_props: Option<Value>,
+}
+
+impl RunAgentInput {
+    /// Parse a `RunAgentInput` from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Iterate over the tool result messages.
+    pub fn tool_messages(&self) -> impl Iterator<Item = &ToolMessage> {
+        self.messages.iter().filter_map(|m| match m {
+            Message::Tool(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// Iterate over all tool calls made by assistant messages.
+    pub fn tool_calls(&self) -> impl Iterator<Item = &ToolCall> {
+        self.messages.iter().flat_map(|m| match m {
+            Message::Assistant(a) => a.tool_calls.iter(),
+            _ => [].iter(),
+        })
+    }
+
+    /// Find the tool name for a tool call ID from the assistant messages.
+    pub fn tool_name_for(&self, tool_call_id: &str) -> Option<&str> {
+        self.tool_calls()
+            .find(|c| c.id == tool_call_id)
+            .map(|c| c.function.name.as_str())
+    }
+
+    /// Get the names of all frontend tools.
+    pub fn frontend_tool_names(&self) -> Vec<&str> {
+        self.tools.iter().map(|t| t.name.as_str()).collect()
+    }
+}
+
+/// A message in an AG-UI conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum Message {
+    /// Developer message.
+    Developer(TextMessage),
+    /// System message.
+    System(TextMessage),
+    /// User message.
+    User(TextMessage),
+    /// Assistant message, optionally with tool calls.
+    Assistant(AssistantMessage),
+    /// Result of a tool call.
+    Tool(ToolMessage),
+}
+
+impl Message {
+    /// Get the message ID.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Developer(m) | Self::System(m) | Self::User(m) => &m.id,
+            Self::Assistant(m) => &m.id,
+            Self::Tool(m) => &m.id,
+        }
+    }
+}
+
+/// A plain text message (developer, system or user).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextMessage {
+    /// Message identifier.
+    pub id: String,
+    /// Text content.
+    pub content: String,
+    /// Optional sender name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// An assistant message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssistantMessage {
+    /// Message identifier.
+    pub id: String,
+    /// Text content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Tool calls requested by the assistant.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Optional sender name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A tool result message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolMessage {
+    /// Message identifier.
+    pub id: String,
+    /// Tool output.
+    pub content: String,
+    /// ID of the tool call this result answers.
+    pub tool_call_id: String,
+    /// Error message if the tool failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ToolMessage {
+    /// Content parsed as JSON, falling back to a JSON string.
+    pub fn content_value(&self) -> Value {
+        serde_json::from_str(&self.content).unwrap_or_else(|_| Value::String(self.content.clone()))
+    }
+}
+
+/// A tool call inside an assistant message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCall {
+    /// Tool call identifier.
+    pub id: String,
+    /// Call type (always `function`).
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    pub call_type: String,
+    /// Function being called.
+    pub function: FunctionCall,
+}
+
+fn default_tool_call_type() -> String {
+    "function".to_string()
+}
+
+/// Function name and arguments of a tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// Function name.
+    pub name: String,
+    /// Arguments as a JSON string.
+    pub arguments: String,
+}
+
+/// A tool the frontend can execute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tool {
+    /// Tool name.
+    pub name: String,
+    /// Tool description.
+    #[serde(default)]
+    pub description: String,
+    /// JSON schema of the parameters.
+    #[serde(default)]
+    pub parameters: Value,
+}
+
+/// Context entry provided by the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Context {
+    /// Description of the context.
+    pub description: String,
+    /// Context value.
+    pub value: String,
+}
+
+// ============================================================================
+// State Deltas
+// ============================================================================
+
+/// Error applying a state delta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateDeltaError {
+    /// The delta is not a valid JSON Patch document.
+    InvalidPatch(String),
+    /// A path did not resolve in the target document.
+    PathNotFound(String),
+    /// A `test` operation failed.
+    TestFailed(String),
+}
+
+impl std::fmt::Display for StateDeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPatch(msg) => write!(f, "Invalid JSON patch: {}", msg),
+            Self::PathNotFound(path) => write!(f, "Path not found: {}", path),
+            Self::TestFailed(path) => write!(f, "Test operation failed at: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for StateDeltaError {}
+
+/// A single JSON Patch operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Add a value.
+    Add {
+        /// Target path.
+        path: String,
+        /// Value to add.
+        value: Value,
+    },
+    /// Remove a value.
+    Remove {
+        /// Target path.
+        path: String,
+    },
+    /// Replace a value.
+    Replace {
+        /// Target path.
+        path: String,
+        /// New value.
+        value: Value,
+    },
+    /// Move a value.
+    Move {
+        /// Source path.
+        from: String,
+        /// Target path.
+        path: String,
+    },
+    /// Copy a value.
+    Copy {
+        /// Source path.
+        from: String,
+        /// Target path.
+        path: String,
+    },
+    /// Test that a value matches.
+    Test {
+        /// Target path.
+        path: String,
+        /// Expected value.
+        value: Value,
+    },
+}
+
+/// Apply an AG-UI state delta (JSON Patch) to a state document.
+///
+/// The patch is applied to a copy, so `state` is left untouched on error.
+pub fn apply_state_delta(state: &mut Value, delta: &Value) -> Result<(), StateDeltaError> {
+    let ops: Vec<PatchOperation> = serde_json::from_value(delta.clone())
+        .map_err(|e| StateDeltaError::InvalidPatch(e.to_string()))?;
+
+    let mut doc = state.clone();
+    for op in ops {
+        apply_operation(&mut doc, op)?;
+    }
+    *state = doc;
+    Ok(())
+}
+
+fn apply_operation(doc: &mut Value, op: PatchOperation) -> Result<(), StateDeltaError> {
+    match op {
+        PatchOperation::Add { path, value } => add_value(doc, &path, value),
+        PatchOperation::Remove { path } => remove_value(doc, &path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            let target = doc
+                .pointer_mut(&path)
+                .ok_or(StateDeltaError::PathNotFound(path))?;
+            *target = value;
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            let value = remove_value(doc, &from)?;
+            add_value(doc, &path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = doc
+                .pointer(&from)
+                .cloned()
+                .ok_or(StateDeltaError::PathNotFound(from))?;
+            add_value(doc, &path, value)
+        }
+        PatchOperation::Test { path, value } => match doc.pointer(&path) {
+            Some(current) if *current == value => Ok(()),
+            _ => Err(StateDeltaError::TestFailed(path)),
+        },
+    }
+}
+
+/// Split a JSON pointer into its parent pointer and unescaped last token.
+fn split_pointer(path: &str) -> Result<(&str, String), StateDeltaError> {
+    let idx = path
+        .rfind('/')
+        .ok_or_else(|| StateDeltaError::InvalidPatch(format!("invalid path '{}'", path)))?;
+    let token = path[idx + 1..].replace("~1", "/").replace("~0", "~");
+    Ok((&path[..idx], token))
+}
+
+fn add_value(doc: &mut Value, path: &str, value: Value) -> Result<(), StateDeltaError> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+
+    let (parent_path, token) = split_pointer(path)?;
+    let parent = doc
+        .pointer_mut(parent_path)
+        .ok_or_else(|| StateDeltaError::PathNotFound(path.to_string()))?;
+
+    match parent {
+        Value::Object(map) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Value::Array(arr) => {
+            if token == "-" {
+                arr.push(value);
+                return Ok(());
+            }
+            match token.parse::<usize>() {
+                Ok(i) if i <= arr.len() => {
+                    arr.insert(i, value);
+                    Ok(())
+                }
+                _ => Err(StateDeltaError::PathNotFound(path.to_string())),
+            }
+        }
+        _ => Err(StateDeltaError::PathNotFound(path.to_string())),
+    }
+}
+
+fn remove_value(doc: &mut Value, path: &str) -> Result<Value, StateDeltaError> {
+    let (parent_path, token) = split_pointer(path)?;
+    let parent = doc
+        .pointer_mut(parent_path)
+        .ok_or_else(|| StateDeltaError::PathNotFound(path.to_string()))?;
+
+    match parent {
+        Value::Object(map) => map
+            .remove(&token)
+            .ok_or_else(|| StateDeltaError::PathNotFound(path.to_string())),
+        Value::Array(arr) => match token.parse::<usize>() {
+            Ok(i) if i < arr.len() => Ok(arr.remove(i)),
+            _ => Err(StateDeltaError::PathNotFound(path.to_string())),
+        },
+        _ => Err(StateDeltaError::PathNotFound(path.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_run_agent_input() {
+        let input = RunAgentInput::from_json(
+            r#"{
+                "threadId": "thread-1",
+                "runId": "run-2",
+                "state": {"count": 1},
+                "messages": [
+                    {"role": "user", "id": "m1", "content": "What's the weather?"},
+                    {"role": "assistant", "id": "m2", "toolCalls": [
+                        {"id": "call-1", "type": "function",
+                         "function": {"name": "get_location", "arguments": "{}"}}
+                    ]},
+                    {"role": "tool", "id": "m3", "toolCallId": "call-1", "content": "{\"city\":\"Paris\"}"}
+                ],
+                "tools": [{"name": "get_location", "description": "Browser location", "parameters": {}}],
+                "context": []
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(input.thread_id, "thread-1");
+        assert_eq!(input.messages.len(), 3);
+        assert_eq!(input.frontend_tool_names(), vec!["get_location"]);
+        assert_eq!(input.tool_name_for("call-1"), Some("get_location"));
+
+        let results: Vec<_> = input.tool_messages().collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content_value(), json!({"city": "Paris"}));
+    }
+
+    #[test]
+    fn test_tool_message_plain_text_content() {
+        let msg = ToolMessage {
+            id: "m".into(),
+            content: "sunny".into(),
+            tool_call_id: "c".into(),
+            error: None,
+        };
+        assert_eq!(msg.content_value(), json!("sunny"));
+    }
+
+    #[test]
+    fn test_apply_state_delta() {
+        let mut state = json!({"count": 1, "items": ["a"]});
+        let delta = json!([
+            {"op": "replace", "path": "/count", "value": 2},
+            {"op": "add", "path": "/items/-", "value": "b"},
+            {"op": "add", "path": "/flag", "value": true},
+            {"op": "test", "path": "/flag", "value": true}
+        ]);
+
+        apply_state_delta(&mut state, &delta).unwrap();
+        assert_eq!(
+            state,
+            json!({"count": 2, "items": ["a", "b"], "flag": true})
+        );
+    }
+
+    #[test]
+    fn test_apply_state_delta_move_and_remove() {
+        let mut state = json!({"a": {"b": 1}, "c": [1, 2, 3]});
+        let delta = json!([
+            {"op": "move", "from": "/a/b", "path": "/d"},
+            {"op": "remove", "path": "/c/0"}
+        ]);
+
+        apply_state_delta(&mut state, &delta).unwrap();
+        assert_eq!(state, json!({"a": {}, "c": [2, 3], "d": 1}));
+    }
+
+    #[test]
+    fn test_apply_state_delta_is_atomic() {
+        let mut state = json!({"count": 1});
+        let delta = json!([
+            {"op": "replace", "path": "/count", "value": 5},
+            {"op": "remove", "path": "/missing"}
+        ]);
+
+        let err = apply_state_delta(&mut state, &delta).unwrap_err();
+        assert_eq!(err, StateDeltaError::PathNotFound("/missing".into()));
+        assert_eq!(state, json!({"count": 1}));
+    }
+
+    #[test]
+    fn test_apply_state_delta_invalid() {
+        let mut state = json!({});
+        let err = apply_state_delta(&mut state, &json!({"op": "add"})).unwrap_err();
+        assert!(matches!(err, StateDeltaError::InvalidPatch(_)));
+    }
+}
@@ -13,6 +13,7 @@
 //! - **Thinking**: Nested thinking blocks with their own text messages
 //! - **Tool calls**: Start/args/end/result lifecycle
 //! - **State management**: Snapshots and deltas
+//! - **Frontend tools**: Ingesting tool results posted back by the client
 //!
 //! # Example
 //!
@@ -49,9 +50,16 @@
 //! - **SSE**: Server-Sent Events (`data: {...}\n\n`)
 //! - **NDJSON**: Newline-delimited JSON (`{...}\n`)
 
+mod bridge;
+mod input;
 mod stream;
 mod types;
 
+pub use bridge::FrontendToolBridge;
+pub use input::{
+    apply_state_delta, AssistantMessage, Context, FunctionCall, Message, PatchOperation,
+    RunAgentInput, StateDeltaError, TextMessage, Tool, ToolCall, ToolMessage,
+};
 pub use stream::{events_to_output, AgUiEventStream, OutputFormat};
 pub use types::{
     // Core trait and helper
@@ -0,0 +1,42 @@
+//! OpenAI Assistants streaming adapter for serdesAI.
+//!
+//! This module translates agent stream events into the event format of the
+//! OpenAI Assistants/Threads streaming API, so frontends built against that
+//! protocol can switch their backend to serdesAI without client changes.
+//!
+//! # Overview
+//!
+//! Events are sent as SSE frames with a named event and a JSON object:
+//!
+//! - **Run lifecycle**: `thread.run.created`, `thread.run.in_progress`,
+//!   `thread.run.requires_action`, `thread.run.completed`, `thread.run.failed`
+//! - **Run steps**: `thread.run.step.created`, `thread.run.step.in_progress`,
+//!   `thread.run.step.delta`, `thread.run.step.completed`
+//! - **Messages**: `thread.message.created`, `thread.message.in_progress`,
+//!   `thread.message.delta`, `thread.message.completed`
+//! - **Termination**: `error`, `done`
+//!
+//! # Example
+//!
+//! ```ignore
+//! use serdes_ai_ui::assistants::{AssistantsEventStream, events_to_sse};
+//!
+//! let mut transformer = AssistantsEventStream::new("thread_abc", "run_123");
+//!
+//! send(events_to_sse(&transformer.before_stream()));
+//! while let Some(event) = agent_stream.next().await {
+//!     send(events_to_sse(&transformer.transform_event(event)));
+//! }
+//! send(events_to_sse(&transformer.after_stream()));
+//! ```
+
+mod stream;
+mod types;
+
+pub use stream::{events_to_sse, AssistantsEventStream};
+pub use types::{
+    ErrorData, FunctionCall, FunctionCallDelta, LastError, MessageContent, MessageContentDelta,
+    MessageCreation, MessageDelta, MessageDeltaBody, MessageStatus, RequiredAction, Run, RunStatus,
+    RunStep, RunStepDelta, RunStepDeltaBody, StepDetails, StepStatus, SubmitToolOutputs, Text,
+    TextDelta, ThreadEvent, ThreadMessage, ToolCall, ToolCallDelta, ToolCallsDelta, Usage,
+};
@@ -0,0 +1,654 @@
+//! Assistants event stream adapter.
+//!
+//! This module provides the [`AssistantsEventStream`] transformer that converts
+//! serdesAI agent stream events to the OpenAI Assistants streaming format.
+
+use super::types::*;
+use serdes_ai_streaming::AgentStreamEvent;
+use std::collections::HashMap;
+
+/// Message currently being streamed.
+#[derive(Debug)]
+struct OpenMessage {
+    /// Step that creates the message.
+    step: RunStep,
+    /// The message being built.
+    message: ThreadMessage,
+    /// Part index of the current content block.
+    part_index: Option<usize>,
+}
+
+/// Tool calls step currently open.
+#[derive(Debug)]
+struct OpenToolStep {
+    /// The step being built.
+    step: RunStep,
+    /// Tool calls of the step.
+    calls: Vec<ToolCall>,
+    /// Position in `calls` by agent stream index.
+    positions: HashMap<usize, usize>,
+}
+
+/// OpenAI Assistants event stream transformer.
+///
+/// Converts [`AgentStreamEvent`]s into [`ThreadEvent`]s so frontends written
+/// against the Assistants/Threads streaming API can consume serdesAI runs.
+///
+/// Text is emitted as a `message_creation` run step with message deltas; tool
+/// calls are emitted as a `tool_calls` run step with step deltas. Tool calls
+/// that never receive a result (for example tools executed by the client)
+/// end the run with `thread.run.requires_action`.
+///
+/// # Example
+///
+/// ```ignore
+/// use serdes_ai_ui::assistants::AssistantsEventStream;
+///
+/// let mut transformer = AssistantsEventStream::new("thread_abc", "run_123")
+///     .with_assistant_id("asst_1");
+///
+/// for event in transformer.before_stream() {
+///     send(event.to_sse());
+/// }
+/// while let Some(event) = agent_stream.next().await {
+///     for ev in transformer.transform_event(event) {
+///         send(ev.to_sse());
+///     }
+/// }
+/// for event in transformer.after_stream() {
+///     send(event.to_sse());
+/// }
+/// ```
+pub struct AssistantsEventStream {
+    /// The run being streamed.
+    run: Run,
+    /// Counter for generating step IDs.
+    step_counter: u32,
+    /// Counter for generating message IDs.
+    message_counter: u32,
+    /// Open message, if text is streaming.
+    message: Option<OpenMessage>,
+    /// Open tool calls step.
+    tool_step: Option<OpenToolStep>,
+    /// Accumulated usage.
+    usage: Usage,
+    /// Whether we had an error.
+    had_error: bool,
+}
+
+impl AssistantsEventStream {
+    /// Create a new transformer for a run on a thread.
+    pub fn new(thread_id: impl Into<String>, run_id: impl Into<String>) -> Self {
+        Self {
+            run: Run::new(run_id, thread_id, "asst_serdes_ai"),
+            step_counter: 0,
+            message_counter: 0,
+            message: None,
+            tool_step: None,
+            usage: Usage::default(),
+            had_error: false,
+        }
+    }
+
+    /// Set the assistant ID reported on all objects.
+    pub fn with_assistant_id(mut self, assistant_id: impl Into<String>) -> Self {
+        self.run.assistant_id = assistant_id.into();
+        self
+    }
+
+    /// Set the model name reported on the run.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.run.model = Some(model.into());
+        self
+    }
+
+    /// Get the run being streamed.
+    pub fn run(&self) -> &Run {
+        &self.run
+    }
+
+    fn new_step(&mut self, step_type: &str, details: StepDetails) -> RunStep {
+        self.step_counter += 1;
+        RunStep {
+            id: format!("step_{}", self.step_counter),
+            object: "thread.run.step".to_string(),
+            created_at: now(),
+            run_id: self.run.id.clone(),
+            thread_id: self.run.thread_id.clone(),
+            assistant_id: self.run.assistant_id.clone(),
+            step_type: step_type.to_string(),
+            status: StepStatus::InProgress,
+            step_details: details,
+        }
+    }
+
+    /// Generate events to emit before the stream starts.
+    ///
+    /// Emits `thread.run.created` and `thread.run.in_progress`.
+    pub fn before_stream(&mut self) -> Vec<ThreadEvent> {
+        let created = self.run.with_status(RunStatus::Queued);
+        self.run.status = RunStatus::InProgress;
+        vec![
+            ThreadEvent::RunCreated(created),
+            ThreadEvent::RunInProgress(self.run.clone()),
+        ]
+    }
+
+    /// Generate events to emit after the stream ends.
+    ///
+    /// Closes any open message or step and emits the terminal run event
+    /// followed by `done`.
+    pub fn after_stream(&mut self) -> Vec<ThreadEvent> {
+        let mut events = self.close_message();
+
+        if self.had_error {
+            events.push(ThreadEvent::Done);
+            return events;
+        }
+
+        let pending: Vec<ToolCall> = self
+            .tool_step
+            .as_ref()
+            .map(|s| {
+                s.calls
+                    .iter()
+                    .filter(|c| c.function.output.is_none())
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if pending.is_empty() {
+            events.extend(self.close_tool_step());
+            self.run.status = RunStatus::Completed;
+            self.run.usage = Some(self.usage);
+            events.push(ThreadEvent::RunCompleted(self.run.clone()));
+        } else {
+            self.run.status = RunStatus::RequiresAction;
+            self.run.required_action = Some(RequiredAction {
+                action_type: "submit_tool_outputs".to_string(),
+                submit_tool_outputs: SubmitToolOutputs {
+                    tool_calls: pending,
+                },
+            });
+            events.push(ThreadEvent::RunRequiresAction(self.run.clone()));
+        }
+
+        events.push(ThreadEvent::Done);
+        events
+    }
+
+    /// Handle an error and generate error events.
+    pub fn on_error(&mut self, error: &str) -> Vec<ThreadEvent> {
+        self.had_error = true;
+
+        let mut events = self.close_message();
+        if let Some(mut open) = self.tool_step.take() {
+            open.step.status = StepStatus::Failed;
+            events.push(ThreadEvent::RunStepCompleted(open.step));
+        }
+
+        self.run.status = RunStatus::Failed;
+        self.run.last_error = Some(LastError {
+            code: "server_error".to_string(),
+            message: error.to_string(),
+        });
+        events.push(ThreadEvent::RunFailed(self.run.clone()));
+        events.push(ThreadEvent::Error(ErrorData {
+            message: error.to_string(),
+            code: Some("server_error".to_string()),
+        }));
+        events
+    }
+
+    /// Transform an agent stream event into Assistants events.
+    pub fn transform_event<O>(&mut self, event: AgentStreamEvent<O>) -> Vec<ThreadEvent> {
+        match event {
+            AgentStreamEvent::RunStart { .. } | AgentStreamEvent::RunComplete { .. } => {
+                // Handled by before_stream() / after_stream()
+                vec![]
+            }
+
+            AgentStreamEvent::RequestStart { .. } => {
+                let mut events = self.close_message();
+                events.extend(self.close_tool_step());
+                events
+            }
+
+            AgentStreamEvent::TextDelta {
+                content,
+                part_index,
+            } => self.handle_text_delta(content, part_index),
+
+            AgentStreamEvent::ThinkingDelta { .. } => {
+                // The Assistants format has no reasoning events
+                vec![]
+            }
+
+            AgentStreamEvent::ToolCallStart {
+                name,
+                tool_call_id,
+                index,
+            } => self.handle_tool_call_start(name, tool_call_id, index),
+
+            AgentStreamEvent::ToolCallDelta { args_delta, index } => {
+                self.handle_tool_call_delta(args_delta, index)
+            }
+
+            AgentStreamEvent::ToolCallComplete { name, args, index } => {
+                self.handle_tool_call_complete(name, args, index)
+            }
+
+            AgentStreamEvent::ToolResult { result, index, .. } => {
+                self.handle_tool_result(result, index)
+            }
+
+            AgentStreamEvent::UsageUpdate { usage } => {
+                self.usage.prompt_tokens += usage.request_tokens.unwrap_or(0);
+                self.usage.completion_tokens += usage.response_tokens.unwrap_or(0);
+                self.usage.total_tokens += usage.total_tokens.unwrap_or(0);
+                vec![]
+            }
+
+            AgentStreamEvent::ResponseComplete { .. }
+            | AgentStreamEvent::PartialOutput { .. }
+            | AgentStreamEvent::FinalOutput { .. } => vec![],
+
+            AgentStreamEvent::Error { message, .. } => self.on_error(&message),
+        }
+    }
+
+    /// Handle text delta event.
+    fn handle_text_delta(&mut self, content: String, part_index: usize) -> Vec<ThreadEvent> {
+        let mut events = Vec::new();
+
+        if self.message.is_none() {
+            events.extend(self.close_tool_step());
+
+            self.message_counter += 1;
+            let message_id = format!("msg_{}", self.message_counter);
+            let step = self.new_step(
+                "message_creation",
+                StepDetails::MessageCreation {
+                    message_creation: MessageCreation {
+                        message_id: message_id.clone(),
+                    },
+                },
+            );
+            let message = ThreadMessage {
+                id: message_id,
+                object: "thread.message".to_string(),
+                created_at: now(),
+                thread_id: self.run.thread_id.clone(),
+                run_id: self.run.id.clone(),
+                assistant_id: self.run.assistant_id.clone(),
+                role: "assistant".to_string(),
+                status: MessageStatus::InProgress,
+                content: Vec::new(),
+            };
+
+            events.push(ThreadEvent::RunStepCreated(step.clone()));
+            events.push(ThreadEvent::RunStepInProgress(step.clone()));
+            events.push(ThreadEvent::MessageCreated(message.clone()));
+            events.push(ThreadEvent::MessageInProgress(message.clone()));
+
+            self.message = Some(OpenMessage {
+                step,
+                message,
+                part_index: None,
+            });
+        }
+
+        let Some(open) = self.message.as_mut() else {
+            return events;
+        };
+
+        if open.part_index != Some(part_index) {
+            open.part_index = Some(part_index);
+            open.message.content.push(MessageContent::Text {
+                text: Text::default(),
+            });
+        }
+
+        let content_index = open.message.content.len() - 1;
+        if let Some(MessageContent::Text { text }) = open.message.content.last_mut() {
+            text.value.push_str(&content);
+        }
+
+        events.push(ThreadEvent::MessageDelta(MessageDelta::text(
+            &open.message.id,
+            content_index,
+            content,
+        )));
+        events
+    }
+
+    /// Handle tool call start.
+    fn handle_tool_call_start(
+        &mut self,
+        name: String,
+        tool_call_id: Option<String>,
+        index: usize,
+    ) -> Vec<ThreadEvent> {
+        let mut events = self.close_message();
+        events.extend(self.ensure_tool_step());
+
+        let call_id = tool_call_id.unwrap_or_else(|| format!("call_{}", index));
+        let Some(open) = self.tool_step.as_mut() else {
+            return events;
+        };
+
+        let position = open.calls.len();
+        open.calls.push(ToolCall::new(&call_id, &name, ""));
+        open.positions.insert(index, position);
+
+        events.push(ThreadEvent::RunStepDelta(RunStepDelta::tool_call(
+            &open.step.id,
+            ToolCallDelta {
+                index: position,
+                id: Some(call_id),
+                call_type: Some("function".to_string()),
+                function: FunctionCallDelta {
+                    name: Some(name),
+                    arguments: Some(String::new()),
+                    output: None,
+                },
+            },
+        )));
+        events
+    }
+
+    /// Handle tool call arguments delta.
+    fn handle_tool_call_delta(&mut self, args_delta: String, index: usize) -> Vec<ThreadEvent> {
+        let Some(open) = self.tool_step.as_mut() else {
+            return vec![];
+        };
+        let Some(&position) = open.positions.get(&index) else {
+            return vec![];
+        };
+
+        open.calls[position]
+            .function
+            .arguments
+            .push_str(&args_delta);
+        vec![ThreadEvent::RunStepDelta(RunStepDelta::tool_call(
+            &open.step.id,
+            ToolCallDelta {
+                index: position,
+                id: None,
+                call_type: None,
+                function: FunctionCallDelta {
+                    arguments: Some(args_delta),
+                    ..Default::default()
+                },
+            },
+        ))]
+    }
+
+    /// Handle tool call complete.
+    fn handle_tool_call_complete(
+        &mut self,
+        name: String,
+        args: serde_json::Value,
+        index: usize,
+    ) -> Vec<ThreadEvent> {
+        let args = args.to_string();
+
+        let known = self
+            .tool_step
+            .as_ref()
+            .is_some_and(|s| s.positions.contains_key(&index));
+        if !known {
+            // Tool call completed without streaming: emit it in one delta
+            let mut events = self.handle_tool_call_start(name, None, index);
+            events.extend(self.handle_tool_call_delta(args, index));
+            return events;
+        }
+
+        // Arguments may not have been streamed; keep the canonical value
+        if let Some(open) = self.tool_step.as_mut() {
+            if let Some(&position) = open.positions.get(&index) {
+                open.calls[position].function.arguments = args;
+            }
+        }
+        vec![]
+    }
+
+    /// Handle tool result.
+    fn handle_tool_result(&mut self, result: serde_json::Value, index: usize) -> Vec<ThreadEvent> {
+        let Some(open) = self.tool_step.as_mut() else {
+            return vec![];
+        };
+        let Some(&position) = open.positions.get(&index) else {
+            return vec![];
+        };
+
+        let output = match result {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        open.calls[position].function.output = Some(output.clone());
+
+        vec![ThreadEvent::RunStepDelta(RunStepDelta::tool_call(
+            &open.step.id,
+            ToolCallDelta {
+                index: position,
+                id: None,
+                call_type: None,
+                function: FunctionCallDelta {
+                    output: Some(output),
+                    ..Default::default()
+                },
+            },
+        ))]
+    }
+
+    /// Open a tool calls step if none is open.
+    fn ensure_tool_step(&mut self) -> Vec<ThreadEvent> {
+        if self.tool_step.is_some() {
+            return vec![];
+        }
+
+        let step = self.new_step(
+            "tool_calls",
+            StepDetails::ToolCalls {
+                tool_calls: Vec::new(),
+            },
+        );
+        let events = vec![
+            ThreadEvent::RunStepCreated(step.clone()),
+            ThreadEvent::RunStepInProgress(step.clone()),
+        ];
+        self.tool_step = Some(OpenToolStep {
+            step,
+            calls: Vec::new(),
+            positions: HashMap::new(),
+        });
+        events
+    }
+
+    /// Complete the open message and its step.
+    fn close_message(&mut self) -> Vec<ThreadEvent> {
+        let Some(mut open) = self.message.take() else {
+            return vec![];
+        };
+
+        open.message.status = MessageStatus::Completed;
+        open.step.status = StepStatus::Completed;
+        vec![
+            ThreadEvent::MessageCompleted(open.message),
+            ThreadEvent::RunStepCompleted(open.step),
+        ]
+    }
+
+    /// Complete the open tool calls step.
+    fn close_tool_step(&mut self) -> Vec<ThreadEvent> {
+        let Some(mut open) = self.tool_step.take() else {
+            return vec![];
+        };
+
+        open.step.status = StepStatus::Completed;
+        open.step.step_details = StepDetails::ToolCalls {
+            tool_calls: open.calls,
+        };
+        vec![ThreadEvent::RunStepCompleted(open.step)]
+    }
+}
+
+/// Helper to create an SSE response body from events.
+pub fn events_to_sse(events: &[ThreadEvent]) -> String {
+    events.iter().map(ThreadEvent::to_sse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(events: &[ThreadEvent]) -> Vec<&'static str> {
+        events.iter().map(|e| e.event_name()).collect()
+    }
+
+    #[test]
+    fn test_before_stream() {
+        let mut stream = AssistantsEventStream::new("thread_1", "run_1");
+        let events = stream.before_stream();
+        assert_eq!(
+            names(&events),
+            vec!["thread.run.created", "thread.run.in_progress"]
+        );
+        assert_eq!(stream.run().status, RunStatus::InProgress);
+    }
+
+    #[test]
+    fn test_text_flow() {
+        let mut stream = AssistantsEventStream::new("thread_1", "run_1");
+        stream.before_stream();
+
+        let events = stream.transform_event(AgentStreamEvent::<()>::text_delta("Hello", 0));
+        assert_eq!(
+            names(&events),
+            vec![
+                "thread.run.step.created",
+                "thread.run.step.in_progress",
+                "thread.message.created",
+                "thread.message.in_progress",
+                "thread.message.delta",
+            ]
+        );
+
+        let events = stream.transform_event(AgentStreamEvent::<()>::text_delta(" world", 0));
+        assert_eq!(names(&events), vec!["thread.message.delta"]);
+
+        let events = stream.after_stream();
+        assert_eq!(
+            names(&events),
+            vec![
+                "thread.message.completed",
+                "thread.run.step.completed",
+                "thread.run.completed",
+                "done",
+            ]
+        );
+
+        match &events[0] {
+            ThreadEvent::MessageCompleted(m) => match &m.content[0] {
+                MessageContent::Text { text } => assert_eq!(text.value, "Hello world"),
+            },
+            _ => panic!("expected message completed"),
+        }
+    }
+
+    #[test]
+    fn test_tool_call_flow() {
+        let mut stream = AssistantsEventStream::new("thread_1", "run_1");
+        stream.before_stream();
+
+        let events = stream.transform_event(AgentStreamEvent::<()>::ToolCallStart {
+            name: "get_weather".into(),
+            tool_call_id: Some("call_abc".into()),
+            index: 0,
+        });
+        assert_eq!(
+            names(&events),
+            vec![
+                "thread.run.step.created",
+                "thread.run.step.in_progress",
+                "thread.run.step.delta",
+            ]
+        );
+
+        stream.transform_event(AgentStreamEvent::<()>::ToolCallDelta {
+            args_delta: r#"{"city":"Paris"}"#.into(),
+            index: 0,
+        });
+        stream.transform_event(AgentStreamEvent::<()>::ToolCallComplete {
+            name: "get_weather".into(),
+            args: serde_json::json!({"city": "Paris"}),
+            index: 0,
+        });
+        let events = stream.transform_event(AgentStreamEvent::<()>::ToolResult {
+            name: "get_weather".into(),
+            result: serde_json::json!("sunny"),
+            success: true,
+            index: 0,
+        });
+        let data: serde_json::Value = serde_json::from_str(&events[0].data()).unwrap();
+        assert_eq!(
+            data["delta"]["step_details"]["tool_calls"][0]["function"]["output"],
+            "sunny"
+        );
+
+        let events = stream.transform_event(AgentStreamEvent::<()>::RequestStart { step: 2 });
+        assert_eq!(names(&events), vec!["thread.run.step.completed"]);
+    }
+
+    #[test]
+    fn test_unanswered_tool_call_requires_action() {
+        let mut stream = AssistantsEventStream::new("thread_1", "run_1");
+        stream.before_stream();
+        stream.transform_event(AgentStreamEvent::<()>::ToolCallComplete {
+            name: "confirm".into(),
+            args: serde_json::json!({}),
+            index: 0,
+        });
+
+        let events = stream.after_stream();
+        assert_eq!(names(&events), vec!["thread.run.requires_action", "done"]);
+        match &events[0] {
+            ThreadEvent::RunRequiresAction(run) => {
+                let action = run.required_action.as_ref().unwrap();
+                assert_eq!(action.submit_tool_outputs.tool_calls.len(), 1);
+                assert_eq!(
+                    action.submit_tool_outputs.tool_calls[0].function.name,
+                    "confirm"
+                );
+            }
+            _ => panic!("expected requires_action"),
+        }
+    }
+
+    #[test]
+    fn test_on_error() {
+        let mut stream = AssistantsEventStream::new("thread_1", "run_1");
+        stream.before_stream();
+        stream.transform_event(AgentStreamEvent::<()>::text_delta("Hi", 0));
+
+        let events = stream.on_error("boom");
+        assert_eq!(
+            names(&events),
+            vec![
+                "thread.message.completed",
+                "thread.run.step.completed",
+                "thread.run.failed",
+                "error",
+            ]
+        );
+        assert_eq!(names(&stream.after_stream()), vec!["done"]);
+    }
+
+    #[test]
+    fn test_events_to_sse() {
+        let sse = events_to_sse(&[ThreadEvent::Done, ThreadEvent::Done]);
+        assert_eq!(sse.matches("event: done").count(), 2);
+    }
+}
@@ -0,0 +1,569 @@
+//! OpenAI Assistants streaming event types.
+//!
+//! The Assistants API streams Server-Sent Events whose `event:` line names
+//! the event (`thread.run.created`, `thread.message.delta`, ...) and whose
+//! `data:` line carries the affected object as JSON. The stream ends with
+//! `event: done` / `data: [DONE]`.
+//!
+//! Only the fields used by typical frontends are modelled here.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Current Unix timestamp in seconds.
+pub(crate) fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+// ============================================================================
+// Runs
+// ============================================================================
+
+/// Status of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Run is queued.
+    Queued,
+    /// Run is in progress.
+    InProgress,
+    /// Run is waiting for tool outputs from the client.
+    RequiresAction,
+    /// Run completed successfully.
+    Completed,
+    /// Run failed.
+    Failed,
+    /// Run was cancelled.
+    Cancelled,
+}
+
+/// Token usage of a run or run step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Prompt tokens.
+    pub prompt_tokens: u64,
+    /// Completion tokens.
+    pub completion_tokens: u64,
+    /// Total tokens.
+    pub total_tokens: u64,
+}
+
+/// Error attached to a failed run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastError {
+    /// Error code.
+    pub code: String,
+    /// Error message.
+    pub message: String,
+}
+
+/// Action required from the client to continue a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequiredAction {
+    /// Action type (always `submit_tool_outputs`).
+    #[serde(rename = "type")]
+    pub action_type: String,
+    /// Tool calls the client must answer.
+    pub submit_tool_outputs: SubmitToolOutputs,
+}
+
+/// Tool calls awaiting client outputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmitToolOutputs {
+    /// The pending tool calls.
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// A run on a thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    /// Run identifier.
+    pub id: String,
+    /// Object type (`thread.run`).
+    pub object: String,
+    /// Creation time (Unix seconds).
+    pub created_at: i64,
+    /// Thread identifier.
+    pub thread_id: String,
+    /// Assistant identifier.
+    pub assistant_id: String,
+    /// Run status.
+    pub status: RunStatus,
+    /// Action required to continue, if any.
+    pub required_action: Option<RequiredAction>,
+    /// Last error, if the run failed.
+    pub last_error: Option<LastError>,
+    /// Model used for the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Token usage, available once the run is terminal.
+    pub usage: Option<Usage>,
+}
+
+impl Run {
+    /// Create a new queued run.
+    pub fn new(
+        id: impl Into<String>,
+        thread_id: impl Into<String>,
+        assistant_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            object: "thread.run".to_string(),
+            created_at: now(),
+            thread_id: thread_id.into(),
+            assistant_id: assistant_id.into(),
+            status: RunStatus::Queued,
+            required_action: None,
+            last_error: None,
+            model: None,
+            usage: None,
+        }
+    }
+
+    /// Return a copy with the given status.
+    pub fn with_status(&self, status: RunStatus) -> Self {
+        Self {
+            status,
+            ..self.clone()
+        }
+    }
+}
+
+// ============================================================================
+// Run Steps
+// ============================================================================
+
+/// Status of a run step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// Step is in progress.
+    InProgress,
+    /// Step completed.
+    Completed,
+    /// Step failed.
+    Failed,
+    /// Step was cancelled.
+    Cancelled,
+}
+
+/// Details of a run step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepDetails {
+    /// The step created a message.
+    MessageCreation {
+        /// The created message.
+        message_creation: MessageCreation,
+    },
+    /// The step made tool calls.
+    ToolCalls {
+        /// The tool calls.
+        tool_calls: Vec<ToolCall>,
+    },
+}
+
+/// Reference to the message created by a step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageCreation {
+    /// Message identifier.
+    pub message_id: String,
+}
+
+/// A function tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Tool call identifier.
+    pub id: String,
+    /// Tool call type (always `function`).
+    #[serde(rename = "type")]
+    pub call_type: String,
+    /// Function details.
+    pub function: FunctionCall,
+}
+
+impl ToolCall {
+    /// Create a new function tool call.
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: name.into(),
+                arguments: arguments.into(),
+                output: None,
+            },
+        }
+    }
+}
+
+/// Function name, arguments and output of a tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// Function name.
+    pub name: String,
+    /// Arguments as a JSON string.
+    pub arguments: String,
+    /// Output, once the tool has run.
+    pub output: Option<String>,
+}
+
+/// A step of a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStep {
+    /// Step identifier.
+    pub id: String,
+    /// Object type (`thread.run.step`).
+    pub object: String,
+    /// Creation time (Unix seconds).
+    pub created_at: i64,
+    /// Run identifier.
+    pub run_id: String,
+    /// Thread identifier.
+    pub thread_id: String,
+    /// Assistant identifier.
+    pub assistant_id: String,
+    /// Step type (`message_creation` or `tool_calls`).
+    #[serde(rename = "type")]
+    pub step_type: String,
+    /// Step status.
+    pub status: StepStatus,
+    /// Step details.
+    pub step_details: StepDetails,
+}
+
+/// Incremental update to a run step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStepDelta {
+    /// Step identifier.
+    pub id: String,
+    /// Object type (`thread.run.step.delta`).
+    pub object: String,
+    /// The delta.
+    pub delta: RunStepDeltaBody,
+}
+
+/// Body of a run step delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStepDeltaBody {
+    /// Step details delta.
+    pub step_details: ToolCallsDelta,
+}
+
+/// Tool calls delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallsDelta {
+    /// Delta type (always `tool_calls`).
+    #[serde(rename = "type")]
+    pub delta_type: String,
+    /// Tool call fragments.
+    pub tool_calls: Vec<ToolCallDelta>,
+}
+
+/// Fragment of a tool call inside a step delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    /// Index of the tool call within the step.
+    pub index: usize,
+    /// Tool call identifier (first fragment only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Tool call type (first fragment only).
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub call_type: Option<String>,
+    /// Function fragment.
+    pub function: FunctionCallDelta,
+}
+
+/// Fragment of a function call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    /// Function name (first fragment only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Arguments fragment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+    /// Output, once available.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl RunStepDelta {
+    /// Create a delta for a single tool call fragment.
+    pub fn tool_call(step_id: impl Into<String>, fragment: ToolCallDelta) -> Self {
+        Self {
+            id: step_id.into(),
+            object: "thread.run.step.delta".to_string(),
+            delta: RunStepDeltaBody {
+                step_details: ToolCallsDelta {
+                    delta_type: "tool_calls".to_string(),
+                    tool_calls: vec![fragment],
+                },
+            },
+        }
+    }
+}
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// Status of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    /// Message is being generated.
+    InProgress,
+    /// Message is complete.
+    Completed,
+    /// Message generation ended early.
+    Incomplete,
+}
+
+/// Text content of a message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Text {
+    /// Text value.
+    pub value: String,
+    /// Annotations (citations, file paths).
+    #[serde(default)]
+    pub annotations: Vec<Value>,
+}
+
+/// A content block of a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageContent {
+    /// Text content.
+    Text {
+        /// The text.
+        text: Text,
+    },
+}
+
+/// A message on a thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadMessage {
+    /// Message identifier.
+    pub id: String,
+    /// Object type (`thread.message`).
+    pub object: String,
+    /// Creation time (Unix seconds).
+    pub created_at: i64,
+    /// Thread identifier.
+    pub thread_id: String,
+    /// Run identifier.
+    pub run_id: String,
+    /// Assistant identifier.
+    pub assistant_id: String,
+    /// Message role (always `assistant` here).
+    pub role: String,
+    /// Message status.
+    pub status: MessageStatus,
+    /// Content blocks.
+    pub content: Vec<MessageContent>,
+}
+
+/// Incremental update to a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDelta {
+    /// Message identifier.
+    pub id: String,
+    /// Object type (`thread.message.delta`).
+    pub object: String,
+    /// The delta.
+    pub delta: MessageDeltaBody,
+}
+
+/// Body of a message delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDeltaBody {
+    /// Content fragments.
+    pub content: Vec<MessageContentDelta>,
+}
+
+/// Fragment of a content block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageContentDelta {
+    /// Index of the content block.
+    pub index: usize,
+    /// Content type (always `text`).
+    #[serde(rename = "type")]
+    pub content_type: String,
+    /// Text fragment.
+    pub text: TextDelta,
+}
+
+/// Text fragment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextDelta {
+    /// Text value fragment.
+    pub value: String,
+}
+
+impl MessageDelta {
+    /// Create a text delta for a content block.
+    pub fn text(message_id: impl Into<String>, index: usize, value: impl Into<String>) -> Self {
+        Self {
+            id: message_id.into(),
+            object: "thread.message.delta".to_string(),
+            delta: MessageDeltaBody {
+                content: vec![MessageContentDelta {
+                    index,
+                    content_type: "text".to_string(),
+                    text: TextDelta {
+                        value: value.into(),
+                    },
+                }],
+            },
+        }
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+/// Error payload of an `error` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorData {
+    /// Error message.
+    pub message: String,
+    /// Error code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+/// An Assistants streaming event.
+#[derive(Debug, Clone)]
+pub enum ThreadEvent {
+    /// `thread.run.created`
+    RunCreated(Run),
+    /// `thread.run.in_progress`
+    RunInProgress(Run),
+    /// `thread.run.requires_action`
+    RunRequiresAction(Run),
+    /// `thread.run.completed`
+    RunCompleted(Run),
+    /// `thread.run.failed`
+    RunFailed(Run),
+    /// `thread.run.step.created`
+    RunStepCreated(RunStep),
+    /// `thread.run.step.in_progress`
+    RunStepInProgress(RunStep),
+    /// `thread.run.step.delta`
+    RunStepDelta(RunStepDelta),
+    /// `thread.run.step.completed`
+    RunStepCompleted(RunStep),
+    /// `thread.message.created`
+    MessageCreated(ThreadMessage),
+    /// `thread.message.in_progress`
+    MessageInProgress(ThreadMessage),
+    /// `thread.message.delta`
+    MessageDelta(MessageDelta),
+    /// `thread.message.completed`
+    MessageCompleted(ThreadMessage),
+    /// `error`
+    Error(ErrorData),
+    /// `done`
+    Done,
+}
+
+impl ThreadEvent {
+    /// Get the SSE event name.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::RunCreated(_) => "thread.run.created",
+            Self::RunInProgress(_) => "thread.run.in_progress",
+            Self::RunRequiresAction(_) => "thread.run.requires_action",
+            Self::RunCompleted(_) => "thread.run.completed",
+            Self::RunFailed(_) => "thread.run.failed",
+            Self::RunStepCreated(_) => "thread.run.step.created",
+            Self::RunStepInProgress(_) => "thread.run.step.in_progress",
+            Self::RunStepDelta(_) => "thread.run.step.delta",
+            Self::RunStepCompleted(_) => "thread.run.step.completed",
+            Self::MessageCreated(_) => "thread.message.created",
+            Self::MessageInProgress(_) => "thread.message.in_progress",
+            Self::MessageDelta(_) => "thread.message.delta",
+            Self::MessageCompleted(_) => "thread.message.completed",
+            Self::Error(_) => "error",
+            Self::Done => "done",
+        }
+    }
+
+    /// Encode the event payload as JSON (`[DONE]` for the done event).
+    pub fn data(&self) -> String {
+        let result = match self {
+            Self::RunCreated(r)
+            | Self::RunInProgress(r)
+            | Self::RunRequiresAction(r)
+            | Self::RunCompleted(r)
+            | Self::RunFailed(r) => serde_json::to_string(r),
+            Self::RunStepCreated(s) | Self::RunStepInProgress(s) | Self::RunStepCompleted(s) => {
+                serde_json::to_string(s)
+            }
+            Self::RunStepDelta(d) => serde_json::to_string(d),
+            Self::MessageCreated(m) | Self::MessageInProgress(m) | Self::MessageCompleted(m) => {
+                serde_json::to_string(m)
+            }
+            Self::MessageDelta(d) => serde_json::to_string(d),
+            Self::Error(e) => serde_json::to_string(e),
+            Self::Done => return "[DONE]".to_string(),
+        };
+        result.unwrap_or_default()
+    }
+
+    /// Encode the event as an SSE frame.
+    pub fn to_sse(&self) -> String {
+        format!("event: {}\ndata: {}\n\n", self.event_name(), self.data())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_serialization() {
+        let run = Run::new("run_1", "thread_1", "asst_1").with_status(RunStatus::InProgress);
+        let json = serde_json::to_string(&run).unwrap();
+        assert!(json.contains(r#""object":"thread.run""#));
+        assert!(json.contains(r#""status":"in_progress""#));
+        assert!(json.contains(r#""thread_id":"thread_1""#));
+    }
+
+    #[test]
+    fn test_message_delta_serialization() {
+        let delta = MessageDelta::text("msg_1", 0, "Hello");
+        let json = serde_json::to_value(&delta).unwrap();
+        assert_eq!(json["object"], "thread.message.delta");
+        assert_eq!(json["delta"]["content"][0]["type"], "text");
+        assert_eq!(json["delta"]["content"][0]["text"]["value"], "Hello");
+    }
+
+    #[test]
+    fn test_step_details_tagging() {
+        let details = StepDetails::ToolCalls {
+            tool_calls: vec![ToolCall::new("call_1", "search", "{}")],
+        };
+        let json = serde_json::to_value(&details).unwrap();
+        assert_eq!(json["type"], "tool_calls");
+        assert_eq!(json["tool_calls"][0]["function"]["name"], "search");
+    }
+
+    #[test]
+    fn test_event_sse_encoding() {
+        let event = ThreadEvent::MessageDelta(MessageDelta::text("msg_1", 0, "Hi"));
+        let sse = event.to_sse();
+        assert!(sse.starts_with("event: thread.message.delta\ndata: {"));
+        assert!(sse.ends_with("\n\n"));
+
+        assert_eq!(ThreadEvent::Done.to_sse(), "event: done\ndata: [DONE]\n\n");
+    }
+}
@@ -5,11 +5,13 @@
 //!
 //! - **[`vercel_ai`]**: Vercel AI SDK Data Stream Protocol (SSE)
 //! - **[`ag_ui`]**: AG-UI protocol for rich agent interactions
+//! - **[`assistants`]**: OpenAI Assistants/Threads streaming events
 //!
 //! # Feature Flags
 //!
 //! - `vercel` (default): Enable Vercel AI SDK adapter
 //! - `ag-ui`: Enable AG-UI protocol adapter
+//! - `assistants`: Enable OpenAI Assistants adapter
 //! - `full`: Enable all adapters
 //!
 //! # Example: Vercel AI SDK
@@ -63,6 +65,9 @@ pub mod vercel_ai;
 #[cfg(feature = "ag-ui")]
 pub mod ag_ui;
 
+#[cfg(feature = "assistants")]
+pub mod assistants;
+
 // Re-export commonly used types when features are enabled
 #[cfg(feature = "vercel")]
 pub use vercel_ai::{Chunk, FinishReason, VercelAIEventStream, VERCEL_AI_DSP_HEADERS};
@@ -0,0 +1,197 @@
+//! Hooks for injecting custom chunks into a Vercel AI stream.
+//!
+//! The chunks produced by [`VercelAIEventStream`](super::VercelAIEventStream)
+//! are derived from agent events only. Applications often need to attach
+//! their own data (intermediate state, progress, message metadata such as
+//! model name or timings). A [`StreamHook`] is called at fixed points of the
+//! stream lifecycle and may return extra chunks to emit at that point.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use serdes_ai_ui::vercel_ai::{HookPoint, VercelAIEventStream};
+//! use serde_json::json;
+//!
+//! let transformer = VercelAIEventStream::new()
+//!     .with_data_hook(HookPoint::StepFinish, "progress", |ctx| {
+//!         Some(json!({ "step": ctx.step }))
+//!     })
+//!     .with_metadata_hook(HookPoint::Finish, |ctx| {
+//!         vec![("model".to_string(), json!("gpt-4o"))]
+//!     });
+//! ```
+
+use super::types::{Chunk, DataChunk, FinishReason, MessageMetadataChunk, UsageInfo};
+use serde_json::Value;
+
+/// Point in the stream lifecycle at which hooks are invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookPoint {
+    /// After the `start` chunk.
+    Start,
+    /// After each `start-step` chunk.
+    StepStart,
+    /// Before each `finish-step` chunk.
+    StepFinish,
+    /// Before the `finish` chunk.
+    Finish,
+}
+
+/// Stream state passed to hooks.
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    /// Current message ID.
+    pub message_id: String,
+    /// Current step number.
+    pub step: u32,
+    /// Finish reason, if known.
+    pub finish_reason: Option<FinishReason>,
+    /// Latest usage information, if any.
+    pub usage: Option<UsageInfo>,
+}
+
+/// A hook that emits extra chunks at lifecycle points.
+pub trait StreamHook: Send + Sync {
+    /// Return the chunks to emit at `point`.
+    fn chunks(&self, point: HookPoint, ctx: &HookContext) -> Vec<Box<dyn Chunk>>;
+}
+
+impl<F> StreamHook for F
+where
+    F: Fn(HookPoint, &HookContext) -> Vec<Box<dyn Chunk>> + Send + Sync,
+{
+    fn chunks(&self, point: HookPoint, ctx: &HookContext) -> Vec<Box<dyn Chunk>> {
+        self(point, ctx)
+    }
+}
+
+/// Hook emitting a [`DataChunk`] with a fixed key at one lifecycle point.
+pub struct DataHook<F> {
+    point: HookPoint,
+    key: String,
+    f: F,
+}
+
+impl<F> DataHook<F>
+where
+    F: Fn(&HookContext) -> Option<Value> + Send + Sync,
+{
+    /// Create a new data hook.
+    ///
+    /// Returning `None` from `f` skips the chunk.
+    pub fn new(point: HookPoint, key: impl Into<String>, f: F) -> Self {
+        Self {
+            point,
+            key: key.into(),
+            f,
+        }
+    }
+}
+
+impl<F> StreamHook for DataHook<F>
+where
+    F: Fn(&HookContext) -> Option<Value> + Send + Sync,
+{
+    fn chunks(&self, point: HookPoint, ctx: &HookContext) -> Vec<Box<dyn Chunk>> {
+        if point != self.point {
+            return vec![];
+        }
+        match (self.f)(ctx) {
+            Some(value) => vec![Box::new(DataChunk::new(&self.key, value))],
+            None => vec![],
+        }
+    }
+}
+
+/// Hook emitting a [`MessageMetadataChunk`] at one lifecycle point.
+pub struct MetadataHook<F> {
+    point: HookPoint,
+    f: F,
+}
+
+impl<F> MetadataHook<F>
+where
+    F: Fn(&HookContext) -> Vec<(String, Value)> + Send + Sync,
+{
+    /// Create a new metadata hook.
+    ///
+    /// Returning no entries from `f` skips the chunk.
+    pub fn new(point: HookPoint, f: F) -> Self {
+        Self { point, f }
+    }
+}
+
+impl<F> StreamHook for MetadataHook<F>
+where
+    F: Fn(&HookContext) -> Vec<(String, Value)> + Send + Sync,
+{
+    fn chunks(&self, point: HookPoint, ctx: &HookContext) -> Vec<Box<dyn Chunk>> {
+        if point != self.point {
+            return vec![];
+        }
+        let entries = (self.f)(ctx);
+        if entries.is_empty() {
+            return vec![];
+        }
+        let chunk = entries
+            .into_iter()
+            .fold(MessageMetadataChunk::new(&ctx.message_id), |c, (k, v)| {
+                c.with_metadata(k, v)
+            });
+        vec![Box::new(chunk)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vercel_ai::encode_chunk;
+    use serde_json::json;
+
+    fn ctx() -> HookContext {
+        HookContext {
+            message_id: "msg-1".into(),
+            step: 2,
+            finish_reason: None,
+            usage: None,
+        }
+    }
+
+    #[test]
+    fn test_data_hook_only_fires_at_its_point() {
+        let hook = DataHook::new(HookPoint::StepFinish, "progress", |c: &HookContext| {
+            Some(json!({"step": c.step}))
+        });
+
+        assert!(hook.chunks(HookPoint::Start, &ctx()).is_empty());
+        let chunks = hook.chunks(HookPoint::StepFinish, &ctx());
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].chunk_type(), "data");
+        assert!(encode_chunk(&*chunks[0]).contains(r#""key":"progress""#));
+    }
+
+    #[test]
+    fn test_metadata_hook() {
+        let hook = MetadataHook::new(HookPoint::Finish, |_: &HookContext| {
+            vec![("model".to_string(), json!("gpt-4o"))]
+        });
+        let chunks = hook.chunks(HookPoint::Finish, &ctx());
+        assert_eq!(chunks.len(), 1);
+        let json = encode_chunk(&*chunks[0]);
+        assert!(json.contains(r#""messageId":"msg-1""#));
+        assert!(json.contains(r#""model":"gpt-4o""#));
+    }
+
+    #[test]
+    fn test_closure_hook() {
+        let hook = |point: HookPoint, _: &HookContext| -> Vec<Box<dyn Chunk>> {
+            if point == HookPoint::Start {
+                vec![Box::new(DataChunk::new("state", json!({})))]
+            } else {
+                vec![]
+            }
+        };
+        assert_eq!(hook.chunks(HookPoint::Start, &ctx()).len(), 1);
+        assert!(hook.chunks(HookPoint::Finish, &ctx()).is_empty());
+    }
+}
@@ -59,10 +59,15 @@
 //! - **Tool calls**: `tool-input-start`, `tool-input-delta`, `tool-input-available`
 //! - **Tool results**: `tool-output-available`, `tool-output-error`
 //! - **Errors**: `error`
+//!
+//! Custom `data` and `message-metadata` chunks can be injected at lifecycle
+//! points with [`StreamHook`]s (see [`VercelAIEventStream::with_hook`]).
 
+mod hooks;
 mod stream;
 mod types;
 
+pub use hooks::{DataHook, HookContext, HookPoint, MetadataHook, StreamHook};
 pub use stream::{chunks_to_sse, VercelAIEventStream, VERCEL_AI_DSP_HEADERS};
 pub use types::{
     // Core trait and helper
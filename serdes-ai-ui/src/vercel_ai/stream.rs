@@ -3,10 +3,14 @@
 //! This module provides the [`VercelAIEventStream`] transformer that converts
 //! serdesAI agent stream events to the Vercel AI Data Stream Protocol format.
 
+use super::hooks::{DataHook, HookContext, HookPoint, MetadataHook, StreamHook};
 use super::types::{self, *};
 use serde_json::Value;
-use serdes_ai_streaming::AgentStreamEvent;
+use serdes_ai_core::artifacts::ArtifactStore;
+use serdes_ai_core::messages::FilePart;
+use serdes_ai_streaming::{AgentStreamEvent, ToolCallAccumulator};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// HTTP headers for Vercel AI Data Stream Protocol responses.
 pub const VERCEL_AI_DSP_HEADERS: &[(&str, &str)] = &[
@@ -42,8 +46,8 @@ struct ToolCallState {
     /// Tool name (kept for potential future use in multi-step).
     #[allow(dead_code)]
     tool_name: String,
-    /// Accumulated arguments buffer.
-    args_buffer: String,
+    /// Accumulated and validated arguments.
+    args: ToolCallAccumulator,
     /// Whether we've emitted ToolInputStartChunk (for tracking).
     #[allow(dead_code)]
     started: bool,
@@ -98,6 +102,12 @@ pub struct VercelAIEventStream {
     has_pending_tool_calls: bool,
     /// Usage information.
     usage: Option<UsageInfo>,
+    /// Hooks emitting custom chunks at lifecycle points.
+    hooks: Vec<Box<dyn StreamHook>>,
+    /// Argument schemas by tool name, for incremental validation.
+    tool_schemas: HashMap<String, Value>,
+    /// Store serving large files as links, with the inline size limit.
+    artifact_store: Option<(Arc<dyn ArtifactStore>, usize)>,
 }
 
 impl Default for VercelAIEventStream {
@@ -120,9 +130,92 @@ impl VercelAIEventStream {
             tool_calls: HashMap::new(),
             has_pending_tool_calls: false,
             usage: None,
+            hooks: Vec::new(),
+            tool_schemas: HashMap::new(),
+            artifact_store: None,
         }
     }
 
+    /// Validate streamed arguments of a tool against its JSON schema.
+    ///
+    /// Argument deltas are always checked for JSON syntax. Once a call's
+    /// arguments can no longer be valid, a [`ToolInputErrorChunk`] is emitted
+    /// and further deltas for that call are dropped.
+    pub fn with_tool_schema(mut self, tool_name: impl Into<String>, schema: Value) -> Self {
+        self.tool_schemas.insert(tool_name.into(), schema);
+        self
+    }
+
+    /// Link files larger than `inline_limit` bytes instead of inlining them.
+    ///
+    /// Files are put in `store` and the chunk carries the URL from the
+    /// store's URL hook. Files the store has no URL for stay inline.
+    pub fn with_artifact_store(
+        mut self,
+        store: Arc<dyn ArtifactStore>,
+        inline_limit: usize,
+    ) -> Self {
+        self.artifact_store = Some((store, inline_limit));
+        self
+    }
+
+    /// Create a [`FileChunk`] for a file part.
+    ///
+    /// With an artifact store, large files are linked; if storing fails, the
+    /// file is inlined.
+    pub fn file_chunk(&self, file: &FilePart, name: impl Into<String>) -> FileChunk {
+        let mut chunk = FileChunk::from_file(file, name);
+        if let Some((store, inline_limit)) = &self.artifact_store {
+            if let Ok(link) = store.link(file, *inline_limit) {
+                chunk.data = link;
+            }
+        }
+        chunk
+    }
+
+    /// Add a hook that emits custom chunks at lifecycle points.
+    pub fn with_hook(mut self, hook: impl StreamHook + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Emit a [`DataChunk`] with the given key at a lifecycle point.
+    ///
+    /// Returning `None` from `f` skips the chunk.
+    pub fn with_data_hook<F>(self, point: HookPoint, key: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(&HookContext) -> Option<Value> + Send + Sync + 'static,
+    {
+        self.with_hook(DataHook::new(point, key, f))
+    }
+
+    /// Emit a [`MessageMetadataChunk`] at a lifecycle point.
+    ///
+    /// Returning no entries from `f` skips the chunk.
+    pub fn with_metadata_hook<F>(self, point: HookPoint, f: F) -> Self
+    where
+        F: Fn(&HookContext) -> Vec<(String, Value)> + Send + Sync + 'static,
+    {
+        self.with_hook(MetadataHook::new(point, f))
+    }
+
+    /// Collect the chunks returned by all hooks for a lifecycle point.
+    fn run_hooks(&mut self, point: HookPoint) -> Vec<Box<dyn Chunk>> {
+        if self.hooks.is_empty() {
+            return Vec::new();
+        }
+        let ctx = HookContext {
+            message_id: self.current_message_id(),
+            step: self.current_step,
+            finish_reason: self.finish_reason,
+            usage: self.usage.clone(),
+        };
+        self.hooks
+            .iter()
+            .flat_map(|h| h.chunks(point, &ctx))
+            .collect()
+    }
+
     /// Generate a new unique message ID.
     pub fn new_message_id(&mut self) -> String {
         self.message_id_counter += 1;
@@ -156,12 +249,14 @@ impl VercelAIEventStream {
         let mut chunks: Vec<Box<dyn Chunk>> = Vec::new();
 
         let message_id = self.current_message_id();
-        chunks.push(Box::new(StartChunk::new(&message_id)));
-        chunks.push(Box::new(StartStepChunk::new(&message_id).with_step(0)));
-
         self.step_started = true;
         self.current_step = 0;
 
+        chunks.push(Box::new(StartChunk::new(&message_id)));
+        chunks.extend(self.run_hooks(HookPoint::Start));
+        chunks.push(Box::new(StartStepChunk::new(&message_id).with_step(0)));
+        chunks.extend(self.run_hooks(HookPoint::StepStart));
+
         chunks
     }
 
@@ -193,6 +288,7 @@ impl VercelAIEventStream {
 
         // Finish the current step
         if self.step_started {
+            chunks.extend(self.run_hooks(HookPoint::StepFinish));
             let mut finish_step = FinishStepChunk::new(&message_id, finish_reason);
             if let Some(ref usage) = self.usage {
                 finish_step = finish_step.with_usage(usage.clone());
@@ -205,6 +301,7 @@ impl VercelAIEventStream {
         }
 
         // Finish the message
+        chunks.extend(self.run_hooks(HookPoint::Finish));
         let mut finish = FinishChunk::new(&message_id, finish_reason);
         if let Some(ref usage) = self.usage {
             finish = finish.with_usage(usage.clone());
@@ -300,6 +397,7 @@ impl VercelAIEventStream {
             }
 
             // Finish the previous step
+            chunks.extend(self.run_hooks(HookPoint::StepFinish));
             let finish_step =
                 FinishStepChunk::new(&message_id, FinishReason::ToolCalls).with_continued(true);
             chunks.push(Box::new(finish_step));
@@ -307,6 +405,7 @@ impl VercelAIEventStream {
             // Start the new step
             chunks.push(Box::new(StartStepChunk::new(&message_id).with_step(step)));
             self.current_step = step;
+            chunks.extend(self.run_hooks(HookPoint::StepStart));
         }
 
         chunks
@@ -390,13 +489,18 @@ impl VercelAIEventStream {
         // Generate tool call ID if not provided
         let call_id = tool_call_id.unwrap_or_else(|| format!("call-{}", index));
 
+        let mut args = ToolCallAccumulator::new();
+        if let Some(schema) = self.tool_schemas.get(&name) {
+            args = args.with_schema(schema.clone());
+        }
+
         // Store state
         self.tool_calls.insert(
             index,
             ToolCallState {
                 tool_call_id: call_id.clone(),
                 tool_name: name.clone(),
-                args_buffer: String::new(),
+                args,
                 started: true,
             },
         );
@@ -412,14 +516,22 @@ impl VercelAIEventStream {
         let mut chunks: Vec<Box<dyn Chunk>> = Vec::new();
 
         if let Some(state) = self.tool_calls.get_mut(&index) {
-            // Accumulate args
-            state.args_buffer.push_str(&args_delta);
+            // Already rejected
+            if !state.args.is_plausible() {
+                return chunks;
+            }
 
-            // Emit delta chunk
-            chunks.push(Box::new(ToolInputDeltaChunk::new(
-                &state.tool_call_id,
-                args_delta,
-            )));
+            // Accumulate and validate args
+            match state.args.push_args(&args_delta) {
+                Ok(()) => chunks.push(Box::new(ToolInputDeltaChunk::new(
+                    &state.tool_call_id,
+                    args_delta,
+                ))),
+                Err(e) => chunks.push(Box::new(ToolInputErrorChunk::new(
+                    &state.tool_call_id,
+                    e.to_string(),
+                ))),
+            }
         }
 
         chunks
@@ -436,6 +548,11 @@ impl VercelAIEventStream {
 
         // Get or create tool call state
         let tool_call_id = if let Some(state) = self.tool_calls.get(&index) {
+            if !state.args.is_plausible() {
+                // Input was already rejected with an error chunk
+                self.has_pending_tool_calls = true;
+                return chunks;
+            }
             state.tool_call_id.clone()
         } else {
             // Tool call started without ToolCallStart event
@@ -445,7 +562,7 @@ impl VercelAIEventStream {
                 ToolCallState {
                     tool_call_id: call_id.clone(),
                     tool_name: name.clone(),
-                    args_buffer: String::new(),
+                    args: ToolCallAccumulator::new(),
                     started: false,
                 },
             );
@@ -544,6 +661,27 @@ mod tests {
         assert!(!stream.step_started);
     }
 
+    #[test]
+    fn test_file_chunk_links_large_files() {
+        use serdes_ai_core::artifacts::InMemoryArtifactStore;
+
+        let store = InMemoryArtifactStore::new()
+            .with_url_fn(|a| Some(format!("https://files.example.com/{}", a.hash)));
+        let stream = VercelAIEventStream::new().with_artifact_store(Arc::new(store), 16);
+
+        let small = FilePart::from_bytes(vec![1, 2, 3], "image/png");
+        let chunk = stream.file_chunk(&small, "small.png");
+        assert_eq!(chunk.data, "data:image/png;base64,AQID");
+        assert_eq!(chunk.mime_type, "image/png");
+
+        let large = FilePart::from_bytes(vec![0; 1024], "image/png");
+        let chunk = stream.file_chunk(&large, "large.png");
+        assert_eq!(
+            chunk.data,
+            format!("https://files.example.com/{}", chunk.file_id)
+        );
+    }
+
     #[test]
     fn test_message_id_generation() {
         let mut stream = VercelAIEventStream::new();
@@ -658,6 +796,62 @@ mod tests {
         assert_eq!(complete_chunks[0].chunk_type(), "tool-input-available");
     }
 
+    #[test]
+    fn test_tool_call_deltas_validated_against_schema() {
+        let mut stream = VercelAIEventStream::new().with_tool_schema(
+            "get_weather",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"city": {"type": "string"}},
+                "additionalProperties": false,
+            }),
+        );
+        stream.before_stream();
+
+        let delta = |args: &str| -> AgentStreamEvent<()> {
+            AgentStreamEvent::ToolCallDelta {
+                args_delta: args.to_string(),
+                index: 0,
+            }
+        };
+        stream.transform_event::<()>(AgentStreamEvent::ToolCallStart {
+            name: "get_weather".to_string(),
+            tool_call_id: Some("call-1".to_string()),
+            index: 0,
+        });
+
+        let chunks = stream.transform_event(delta("{\"city\": \"Lon"));
+        assert_eq!(chunks[0].chunk_type(), "tool-input-delta");
+
+        let chunks = stream.transform_event(delta("don\", \"units\":"));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].chunk_type(), "tool-input-error");
+
+        // Later deltas and the completed input are dropped
+        assert!(stream.transform_event(delta(" \"metric\"}")).is_empty());
+        let chunks = stream.transform_event::<()>(AgentStreamEvent::ToolCallComplete {
+            name: "get_weather".to_string(),
+            args: serde_json::json!({"city": "London", "units": "metric"}),
+            index: 0,
+        });
+        assert!(chunks.is_empty());
+    }
+
+    #[test]
+    fn test_tool_call_deltas_reject_invalid_json() {
+        let mut stream = VercelAIEventStream::new();
+        stream.transform_event::<()>(AgentStreamEvent::ToolCallStart {
+            name: "search".to_string(),
+            tool_call_id: None,
+            index: 0,
+        });
+        let chunks = stream.transform_event::<()>(AgentStreamEvent::ToolCallDelta {
+            args_delta: "{query:".to_string(),
+            index: 0,
+        });
+        assert_eq!(chunks[0].chunk_type(), "tool-input-error");
+    }
+
     #[test]
     fn test_tool_result_success() {
         let mut stream = VercelAIEventStream::new();
@@ -766,4 +960,33 @@ mod tests {
         let after = stream.after_stream();
         assert!(after.iter().any(|c| c.chunk_type() == "done"));
     }
+
+    #[test]
+    fn test_hooks_emit_at_lifecycle_points() {
+        let mut stream = VercelAIEventStream::new()
+            .with_data_hook(HookPoint::StepFinish, "progress", |ctx| {
+                Some(serde_json::json!({ "step": ctx.step }))
+            })
+            .with_metadata_hook(HookPoint::Finish, |ctx| {
+                vec![(
+                    "finishReason".to_string(),
+                    serde_json::json!(format!("{:?}", ctx.finish_reason)),
+                )]
+            });
+
+        let before = stream.before_stream();
+        assert_eq!(before.len(), 2);
+
+        let step: AgentStreamEvent<()> = AgentStreamEvent::RequestStart { step: 1 };
+        let chunks = stream.transform_event(step);
+        let types: Vec<_> = chunks.iter().map(|c| c.chunk_type()).collect();
+        assert_eq!(types, vec!["data", "finish-step", "start-step"]);
+
+        let after = stream.after_stream();
+        let types: Vec<_> = after.iter().map(|c| c.chunk_type()).collect();
+        assert_eq!(
+            types,
+            vec!["data", "finish-step", "message-metadata", "finish", "done"]
+        );
+    }
 }
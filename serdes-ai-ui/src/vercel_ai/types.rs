@@ -7,6 +7,8 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use serdes_ai_core::artifacts::{content_hash, data_url};
+use serdes_ai_core::messages::FilePart;
 use std::collections::HashMap;
 
 /// Provider metadata type.
@@ -863,6 +865,20 @@ impl FileChunk {
             data: data.into(),
         }
     }
+
+    /// Create a file chunk inlining a file part as a `data:` URL.
+    ///
+    /// The file ID is the part's ID, or the hash of its content.
+    pub fn from_file(file: &FilePart, name: impl Into<String>) -> Self {
+        Self::new(
+            file.id
+                .clone()
+                .unwrap_or_else(|| content_hash(&file.content.data)),
+            name,
+            file.content.media_type.clone(),
+            data_url(&file.content),
+        )
+    }
 }
 
 impl Chunk for FileChunk {
@@ -264,6 +264,63 @@ pub fn model_request_sync(
     rt.block_on(async move { model_request(model_spec, &messages_owned, settings, params).await })
 }
 
+/// Sample `n` completions for the same messages.
+///
+/// Asks for all of them in one request with [`ModelSettings::n`]. Providers
+/// that return fewer choices are topped up with concurrent single requests,
+/// so this works with any model.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use serdes_ai::direct::sample_n;
+/// use serdes_ai_core::{ModelRequest, ModelSettings};
+///
+/// let candidates = sample_n(
+///     "openai:gpt-4o",
+///     &[ModelRequest::user("Suggest a name for a cat")],
+///     5,
+///     Some(ModelSettings::new().temperature(1.0)),
+///     None,
+/// ).await?;
+///
+/// for candidate in &candidates {
+///     println!("{}", candidate.text_content());
+/// }
+/// ```
+pub async fn sample_n(
+    model: impl Into<ModelSpec>,
+    messages: &[ModelRequest],
+    n: u32,
+    model_settings: Option<ModelSettings>,
+    model_request_parameters: Option<ModelRequestParameters>,
+) -> Result<Vec<ModelResponse>, DirectError> {
+    let n = n as usize;
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+    let model = model.into().resolve()?;
+    let mut settings = model_settings.unwrap_or_default();
+    let params = model_request_parameters.unwrap_or_default();
+
+    settings.n = Some(n as u32);
+    let mut choices = model
+        .request(messages, &settings, &params)
+        .await?
+        .into_choices();
+    choices.truncate(n);
+
+    if choices.len() < n {
+        settings.n = None;
+        let rest = futures::future::try_join_all(
+            (choices.len()..n).map(|_| model.request(messages, &settings, &params)),
+        )
+        .await?;
+        choices.extend(rest);
+    }
+    Ok(choices)
+}
+
 // ============================================================================
 // Streaming Requests
 // ============================================================================
@@ -441,6 +498,9 @@ pub fn model_request_stream_sync(
 /// - `openrouter` / `or`: OpenRouter multi-provider
 /// - `huggingface` / `hf`: HuggingFace Inference API
 /// - `cohere` / `co`: Cohere models
+/// - `lmstudio` / `lms`: Local LM Studio server
+/// - `llamacpp` / `llama.cpp`: Local llama.cpp server
+/// - `vllm`: vLLM server
 fn parse_model_name(name: &str) -> Result<BoxedModel, DirectError> {
     // Use the infer_model function from serdes-ai-models
     #[cfg(feature = "openai")]
@@ -530,6 +590,49 @@ mod tests {
         assert!(err.to_string().contains("something went wrong"));
     }
 
+    #[tokio::test]
+    async fn test_sample_n() {
+        use serdes_ai_models::FunctionModel;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut request = ModelRequest::new();
+        request.add_user_prompt("Hi");
+        let messages = [request];
+
+        // A model that ignores `n` is asked once per sample
+        let calls = Arc::new(AtomicUsize::new(0));
+        let model = {
+            let calls = calls.clone();
+            FunctionModel::new(move |_, _| {
+                let i = calls.fetch_add(1, Ordering::SeqCst);
+                ModelResponse::text(format!("sample {i}"))
+            })
+        };
+        let samples = sample_n(ModelSpec::from_model(model), &messages, 3, None, None)
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // A model that honors `n` answers in one request
+        let calls = Arc::new(AtomicUsize::new(0));
+        let model = {
+            let calls = calls.clone();
+            FunctionModel::new(move |_, settings| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let n = settings.n.unwrap_or(1);
+                ModelResponse::text("0")
+                    .with_alternatives((1..n).map(|i| ModelResponse::text(i.to_string())).collect())
+            })
+        };
+        let samples = sample_n(ModelSpec::from_model(model), &messages, 3, None, None)
+            .await
+            .unwrap();
+        let texts: Vec<_> = samples.iter().map(|r| r.text_content()).collect();
+        assert_eq!(texts, ["0", "1", "2"]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_sync_runtime_detection() {
         // In a normal sync context, this should not error due to runtime detection
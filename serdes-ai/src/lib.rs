@@ -47,6 +47,9 @@
 //! | `mistral` | Mistral AI models | ❌ |
 //! | `ollama` | Local Ollama models | ❌ |
 //! | `bedrock` | AWS Bedrock | ❌ |
+//! | `lmstudio` | Local LM Studio server | ❌ |
+//! | `llamacpp` | Local llama.cpp server | ❌ |
+//! | `vllm` | vLLM server with guided decoding | ❌ |
 //! | `mcp` | MCP protocol support | ❌ |
 //! | `embeddings` | Embedding models | ❌ |
 //! | `graph` | Graph execution engine | ❌ |
@@ -291,7 +294,7 @@ pub use serdes_ai_core::{
 };
 
 // Settings
-pub use serdes_ai_core::ModelSettings;
+pub use serdes_ai_core::{ApiKey, ModelSettings};
 
 // Usage
 pub use serdes_ai_core::{RequestUsage, RunUsage, UsageLimits};
@@ -339,6 +342,18 @@ pub use serdes_ai_models::ollama::OllamaModel;
 #[cfg_attr(docsrs, doc(cfg(feature = "bedrock")))]
 pub use serdes_ai_models::bedrock::BedrockModel;
 
+#[cfg(feature = "lmstudio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lmstudio")))]
+pub use serdes_ai_models::lmstudio::LmStudioModel;
+
+#[cfg(feature = "llamacpp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "llamacpp")))]
+pub use serdes_ai_models::llamacpp::LlamaCppModel;
+
+#[cfg(feature = "vllm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vllm")))]
+pub use serdes_ai_models::vllm::VllmModel;
+
 // Tools
 pub use serdes_ai_tools::{
     ObjectJsonSchema, SchemaBuilder, Tool, ToolDefinition, ToolRegistry, ToolResult,
@@ -347,10 +362,15 @@ pub use serdes_ai_tools::{
 // Toolsets
 pub use serdes_ai_toolsets::{
     AbstractToolset, ApprovalRequiredToolset, BoxedToolset, CombinedToolset, DynamicToolset,
-    ExternalToolset, FilteredToolset, FunctionToolset, PrefixedToolset, PreparedToolset,
-    RenamedToolset, ToolsetInfo, ToolsetTool, WrapperToolset,
+    DynamicToolsetHandle, ExternalToolset, FilteredToolset, FunctionToolset, PrefixedToolset,
+    PreparedToolset, RenamedToolset, ToolsetChange, ToolsetEvent, ToolsetInfo, ToolsetTool,
+    UnavailablePolicy, WrapperToolset,
 };
 
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub use serdes_ai_toolsets::{WasmLimits, WasmToolset};
+
 // Output
 pub use serdes_ai_output::{
     OutputSchema, StructuredOutputSchema, TextOutputSchema, ValidationResult,
@@ -366,7 +386,7 @@ pub use serdes_ai_retries::{
 
 // Direct model access
 pub use direct::{
-    model_request, model_request_stream, model_request_stream_sync, model_request_sync,
+    model_request, model_request_stream, model_request_stream_sync, model_request_sync, sample_n,
     DirectError, ModelSpec, StreamedResponseSync,
 };
 